@@ -0,0 +1,219 @@
+//! Throughput of parsing and generation on large synthetic designs.
+//!
+//! Run with `cargo bench`; `cargo bench -- generate` runs only the generator benches.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use rtl_transpiler::ir::{
+    Architecture, Entity, Port, PortDirection, Process, Signal, SystemVerilogGenerator, VHDLType,
+    VectorRange, VerilogGenerator,
+};
+use rtl_transpiler::parser::tree_sitter_vhdl::TreeSitterVHDLParser;
+use rtl_transpiler::parser::{ASTVHDLParser, InputEdit};
+use rtl_transpiler::tools::{Tool, TranspileTool};
+
+/// Processes in the synthetic architecture; each adds a signal and 21 lines of VHDL
+const PROCESSES: usize = 100;
+
+/// Processes in the large file, giving about 5000 lines
+const LARGE_FILE_PROCESSES: usize = 235;
+
+/// Entities in the large file edited by the incremental benches
+const MULTI_ENTITY_COUNT: usize = 22;
+
+fn byte() -> VectorRange {
+    VectorRange { left: 7.into(), right: 0.into(), downto: true }
+}
+
+/// Body of clocked process `i`: a synchronous reset, an enable and a case on `op`
+fn process_body(i: usize) -> String {
+    format!(
+        "if rst = '1' then
+            acc_{i} <= (others => '0');
+        elsif rising_edge(clk) then
+            if en = '1' then
+                case op is
+                    when \"00\" =>
+                        acc_{i} <= std_logic_vector(unsigned(acc_{i}) + unsigned(din));
+                    when \"01\" =>
+                        acc_{i} <= std_logic_vector(unsigned(acc_{i}) - unsigned(din));
+                    when \"10\" =>
+                        acc_{i} <= acc_{i} and din; -- mask
+                    when others =>
+                        acc_{i} <= acc_{i} xor x\"{i:02X}\";
+                end case;
+            end if;
+        end if;",
+        i = i % 256
+    )
+}
+
+/// Entity `bench_top` with `processes` accumulator processes and the concurrent
+/// statements reducing their outputs
+fn synthetic_entity(processes: usize) -> Entity {
+    let mut entity = Entity::new("bench_top".to_string());
+    for (name, direction, port_type) in [
+        ("clk", PortDirection::In, VHDLType::StdLogic),
+        ("rst", PortDirection::In, VHDLType::StdLogic),
+        ("en", PortDirection::In, VHDLType::StdLogic),
+        ("op", PortDirection::In, VHDLType::StdLogicVector(VectorRange { left: 1.into(), right: 0.into(), downto: true })),
+        ("din", PortDirection::In, VHDLType::StdLogicVector(byte())),
+        ("dout", PortDirection::Out, VHDLType::StdLogicVector(byte())),
+    ] {
+        entity.add_port(Port::new(name.to_string(), direction, port_type));
+    }
+
+    let mut arch = Architecture {
+        name: "rtl".to_string(),
+        signals: Vec::new(),
+        processes: Vec::new(),
+        concurrent_statements: Vec::new(),
+        instantiations: Vec::new(),
+        generate_blocks: Vec::new(),
+        concurrent_statement_lines: Vec::new(),
+        types: Default::default(),
+    };
+    for i in 0..processes {
+        arch.signals.push(Signal {
+            name: format!("acc_{}", i),
+            signal_type: VHDLType::StdLogicVector(byte()),
+            source_line: None,
+        });
+        arch.processes.push(Process {
+            label: None,
+            sensitivity_list: vec!["clk".to_string()],
+            body: process_body(i),
+            source_line: None,
+            testbench_style: false,
+        });
+    }
+    let reduction: Vec<String> = (0..processes).map(|i| format!("acc_{}", i)).collect();
+    arch.concurrent_statements.push(format!("dout <= {};", reduction.join(" xor ")));
+    entity.add_architecture(arch);
+    entity
+}
+
+/// Entity and architecture of `synthetic_entity(processes)`, named `name`
+fn entity_vhdl(name: &str, processes: usize) -> String {
+    let mut vhdl = format!(
+        "entity {name} is
+    port(
+        clk  : in  std_logic;
+        rst  : in  std_logic;
+        en   : in  std_logic;
+        op   : in  std_logic_vector(1 downto 0);
+        din  : in  std_logic_vector(7 downto 0);
+        dout : out std_logic_vector(7 downto 0)
+    );
+end entity {name};
+
+architecture rtl of {name} is
+"
+    );
+    for i in 0..processes {
+        vhdl.push_str(&format!("    signal acc_{} : std_logic_vector(7 downto 0);\n", i));
+    }
+    vhdl.push_str("begin\n");
+    for i in 0..processes {
+        vhdl.push_str(&format!("    process(clk)\n    begin\n        {}\n    end process;\n\n", process_body(i)));
+    }
+    let reduction: Vec<String> = (0..processes).map(|i| format!("acc_{}", i)).collect();
+    vhdl.push_str(&format!("    dout <= {};\nend architecture rtl;\n\n", reduction.join(" xor ")));
+    vhdl
+}
+
+const LIBRARY_CLAUSES: &str = "library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+";
+
+/// VHDL source of `synthetic_entity(processes)`
+fn synthetic_vhdl(processes: usize) -> String {
+    format!("{}{}", LIBRARY_CLAUSES, entity_vhdl("bench_top", processes))
+}
+
+/// About 5000 lines in `MULTI_ENTITY_COUNT` entities of 10 processes each
+fn multi_entity_vhdl() -> String {
+    let entities: String = (0..MULTI_ENTITY_COUNT).map(|i| entity_vhdl(&format!("bench_{}", i), 10)).collect();
+    format!("{}{}", LIBRARY_CLAUSES, entities)
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let vhdl = synthetic_vhdl(LARGE_FILE_PROCESSES);
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Bytes(vhdl.len() as u64));
+
+    group.bench_function(format!("tree_sitter_{}_lines", vhdl.lines().count()), |b| {
+        let mut parser = TreeSitterVHDLParser::new().unwrap();
+        b.iter(|| parser.parse(black_box(&vhdl)).unwrap())
+    });
+    group.bench_function(format!("extract_entities_{}_lines", vhdl.lines().count()), |b| {
+        b.iter(|| {
+            let mut parser = ASTVHDLParser::new(black_box(vhdl.clone())).unwrap();
+            parser.parse_entities().unwrap()
+        })
+    });
+    group.finish();
+}
+
+/// A one-token edit in one entity of a large file: a full parse of the new source
+/// against `ASTVHDLParser::update`, which reuses the old tree and the other entities
+fn bench_incremental(c: &mut Criterion) {
+    let vhdl = multi_entity_vhdl();
+    let entity_start = vhdl.find(&format!("entity bench_{} is", MULTI_ENTITY_COUNT / 2)).unwrap();
+    let start = entity_start + vhdl[entity_start..].find("en = '1'").unwrap() + "en = '".len();
+    let edited = format!("{}0{}", &vhdl[..start], &vhdl[start + 1..]);
+    let edit = InputEdit { start_byte: start, old_end_byte: start + 1, new_end_byte: start + 1 };
+
+    let mut group = c.benchmark_group("incremental");
+    let lines = vhdl.lines().count();
+    group.bench_function(format!("full_parse_{}_lines", lines), |b| {
+        b.iter(|| ASTVHDLParser::new(black_box(edited.clone())).unwrap().parse_entities().unwrap())
+    });
+    group.bench_function(format!("update_{}_lines", lines), |b| {
+        b.iter_batched(
+            || {
+                let mut parser = ASTVHDLParser::new(vhdl.clone()).unwrap();
+                parser.parse_entities().unwrap();
+                parser
+            },
+            |mut parser| parser.update(edit, black_box(edited.clone())).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn bench_generate(c: &mut Criterion) {
+    let entity = synthetic_entity(PROCESSES);
+    let mut group = c.benchmark_group("generate");
+    group.throughput(Throughput::Elements(PROCESSES as u64));
+
+    group.bench_function(format!("verilog_{}_processes", PROCESSES), |b| {
+        let generator = VerilogGenerator::new();
+        b.iter(|| generator.generate(black_box(&entity)).unwrap())
+    });
+    group.bench_function(format!("systemverilog_{}_processes", PROCESSES), |b| {
+        let generator = SystemVerilogGenerator::new();
+        b.iter(|| generator.generate(black_box(&entity)).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_transpile_tool(c: &mut Criterion) {
+    let folder = tempfile::TempDir::new().unwrap();
+    let vhdl_file = folder.path().join("bench_top.vhd");
+    std::fs::write(&vhdl_file, synthetic_vhdl(PROCESSES)).unwrap();
+    let arguments = serde_json::json!({
+        "vhdl_file": vhdl_file.to_str().unwrap(),
+        "output_file": folder.path().join("bench_top.sv").to_str().unwrap(),
+    });
+    let tool = TranspileTool::new(vec![folder.path().to_str().unwrap().to_string()]);
+
+    c.bench_function(&format!("transpile_tool_{}_processes", PROCESSES), |b| {
+        b.iter(|| tool.execute(black_box(&arguments)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_parse, bench_incremental, bench_generate, bench_transpile_tool);
+criterion_main!(benches);
@@ -14,4 +14,22 @@ fn main() {
     }
 
     build.compile("tree-sitter-vhdl");
+
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+/// Write the C header for the `ffi` feature's extern functions
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(cbindgen::Config::from_file("cbindgen.toml").unwrap())
+        .generate()
+        .expect("Unable to generate C header")
+        .write_to_file(PathBuf::from(&crate_dir).join("include").join("rtl_transpiler.h"));
 }
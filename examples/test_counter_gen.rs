@@ -8,7 +8,7 @@ fn main() -> anyhow::Result<()> {
 
     if let Some(entity) = entities.first() {
         let generator = VerilogGenerator::new();
-        let verilog = generator.generate(entity)?;
+        let (verilog, _) = generator.generate(entity)?;
 
         println!("Generated Verilog:\n");
         println!("{}", verilog);
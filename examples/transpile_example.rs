@@ -43,7 +43,7 @@ fn main() -> anyhow::Result<()> {
 
         for entity in &entities {
             println!("  Converting entity: {}", entity.name);
-            let verilog = generator.generate(entity)?;
+            let (verilog, _) = generator.generate(entity)?;
             verilog_output.push_str(&verilog);
             verilog_output.push('\n');
         }
@@ -50,7 +50,7 @@ fn main() -> anyhow::Result<()> {
                 println!("   ℹ No architecture found");
             }
 
-            let verilog = generator.generate(entity)?;
+            let (verilog, _) = generator.generate(entity)?;
             verilog_output.push_str(&verilog);
             verilog_output.push('\n');
         }
@@ -0,0 +1,39 @@
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+entity adder is
+    port(
+        a   : in  std_logic_vector(7 downto 0);
+        b   : in  std_logic_vector(7 downto 0);
+        sum : out std_logic_vector(7 downto 0)
+    );
+end entity adder;
+
+architecture rtl of adder is
+begin
+    sum <= std_logic_vector(unsigned(a) + unsigned(b));
+end architecture rtl;
+
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity adder_array is
+    port(
+        a   : in  std_logic_vector(31 downto 0);
+        b   : in  std_logic_vector(31 downto 0);
+        sum : out std_logic_vector(31 downto 0)
+    );
+end entity adder_array;
+
+architecture rtl of adder_array is
+begin
+    gen_add : for i in 0 to 3 generate
+        u_add : entity work.adder
+            port map (
+                a   => a(8 * i + 7 downto 8 * i),
+                b   => b(8 * i + 7 downto 8 * i),
+                sum => sum(8 * i + 7 downto 8 * i)
+            );
+    end generate gen_add;
+end architecture rtl;
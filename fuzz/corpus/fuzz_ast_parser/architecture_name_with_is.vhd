@@ -0,0 +1,3 @@
+architecture is_first of e is
+begin
+end architecture is_first;
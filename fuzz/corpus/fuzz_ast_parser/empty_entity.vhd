@@ -0,0 +1,2 @@
+entity empty is
+end entity empty;
@@ -0,0 +1,10 @@
+entity nested_generics is
+    generic(
+        WIDTH : integer := ((((8))));
+        DEPTH : integer := WIDTH * (WIDTH - (1 + (2 * (3 - 1))));
+        INIT  : std_logic_vector(((WIDTH - 1)) downto 0) := (others => '0')
+    );
+    port(
+        data : in std_logic_vector(WIDTH-1 downto 0)
+    );
+end entity nested_generics;
@@ -0,0 +1,6 @@
+entity no_semi is
+    port(
+        a : in std_logic
+        b : out std_logic
+    );
+end entity no_semi;
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rtl_transpiler::parser::ASTVHDLParser;
+
+// Any input may be rejected with an Err, but the parser must never panic
+fuzz_target!(|data: &[u8]| {
+    let Ok(source) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(mut parser) = ASTVHDLParser::new(source.to_string()) {
+        let _ = parser.parse_entities();
+    }
+});
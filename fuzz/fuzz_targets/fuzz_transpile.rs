@@ -0,0 +1,26 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rtl_transpiler::ir::{SystemVerilogGenerator, VerilogGenerator, VerilogStyle};
+use rtl_transpiler::parser::ASTVHDLParser;
+
+// Whatever parses goes through both generators; errors are fine, panics are not
+fuzz_target!(|data: &[u8]| {
+    let Ok(source) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(mut parser) = ASTVHDLParser::new(source.to_string()) else {
+        return;
+    };
+    let Ok((entities, _)) = parser.parse_entities_partial() else {
+        return;
+    };
+
+    for entity in &entities {
+        let _ = SystemVerilogGenerator::new().generate(entity);
+        let _ = SystemVerilogGenerator::new().generate_stub(entity);
+        for style in [VerilogStyle::Verilog2001Ansi, VerilogStyle::Verilog2001NonAnsi, VerilogStyle::Verilog95] {
+            let _ = VerilogGenerator::new().with_style(style).generate(entity);
+        }
+    }
+});
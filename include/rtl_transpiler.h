@@ -0,0 +1,67 @@
+#ifndef RTL_TRANSPILER_H
+#define RTL_TRANSPILER_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define RTLT_OK 0
+
+/**
+ * A required pointer was null or not valid UTF-8, or the target is unknown
+ */
+#define RTLT_ERR_INVALID_ARGUMENT 1
+
+/**
+ * Parsing, generation or file I/O failed
+ */
+#define RTLT_ERR_TRANSPILE 2
+
+/**
+ * The library panicked; the panic was caught at the boundary
+ */
+#define RTLT_ERR_PANIC 3
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Transpile every entity in `in_path` to `target` ("verilog", "sv" or "spinalhdl") and write
+ * the result to `out_path`. Returns `RTLT_OK` or an `RTLT_ERR_*` code.
+ *
+ * # Safety
+ * `in_path`, `out_path` and `target` must be null or NUL-terminated strings. `error_out`
+ * must be null or point to writable storage; it receives null on success and otherwise an
+ * error message the caller frees with `rtlt_free_string`.
+ */
+int rtlt_transpile_file(const char *in_path,
+                        const char *out_path,
+                        const char *target,
+                        char **error_out);
+
+/**
+ * Parse every entity in `in_path` and return them as a JSON array, or null on failure.
+ *
+ * # Safety
+ * `in_path` must be null or a NUL-terminated string. `error_out` follows the same rules as
+ * in `rtlt_transpile_file`. The returned string must be freed with `rtlt_free_string`.
+ */
+char *rtlt_analyze_file(const char *in_path, char **error_out);
+
+/**
+ * Release a string returned by this library; null is ignored.
+ *
+ * # Safety
+ * `s` must be null or a pointer returned by this library that has not been freed yet.
+ */
+void rtlt_free_string(char *s);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* RTL_TRANSPILER_H */
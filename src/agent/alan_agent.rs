@@ -4,9 +4,12 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::Mutex;
+use std::time::Duration;
 
 use crate::agent::base::{BaseAgent, BaseAgentImpl};
-use crate::agent::basics::{AgentError, AgentExecution, AgentState};
+use crate::agent::mcp_connection::MCPConnection;
+use crate::agent::observer::AgentObserver;
+use crate::agent::basics::{validate_task_args, AgentError, AgentExecution, AgentState};
 use crate::config::{AgentConfig, MCPServerConfig};
 use crate::llm::{LLMClient, LLMMessage, LLMResponse, create_llm_client};
 use crate::tools::{Tool, ToolExecutor, ToolResult};
@@ -130,7 +133,7 @@ Phase 3 - Logic Verification:
 - Validate bit vector ordering
 
 Phase 4 - Testbench Creation:
-- Generate a SystemVerilog testbench
+- Generate a SystemVerilog testbench (start from the skeleton emitted with generate_testbench=true)
 - Create test cases covering key functionality
 - Compare with VHDL simulation if possible
 
@@ -201,6 +204,22 @@ Task: {task}
 "#).to_string();
 }
 
+/// Connects to a configured MCP server and returns the tools it exposes
+pub trait MCPConnector: Send + Sync {
+    fn connect(&self, server_name: &str, config: &MCPServerConfig) -> Result<Vec<Arc<dyn Tool>>>;
+
+    /// Check a connected server answers within `timeout`, e.g. with an empty `list_tools`
+    /// call. Connectors that cannot ping report every server as healthy, so tool errors
+    /// are returned without reconnecting
+    fn ping(&self, _server_name: &str, _timeout: Duration) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Reconnect attempts after an MCP server stops answering, and the delay before the first
+const MCP_RECONNECT_ATTEMPTS: u32 = 3;
+const MCP_RECONNECT_BASE_DELAY_MS: u64 = 500;
+
 pub struct AlanAgent {
     base: BaseAgentImpl,
     project_path: String,
@@ -210,6 +229,8 @@ pub struct AlanAgent {
     mcp_servers_config: Option<HashMap<String, MCPServerConfig>>,
     pub allow_mcp_servers: Vec<String>,
     mcp_tools: Vec<Arc<dyn Tool>>,
+    mcp_connector: Option<Arc<dyn MCPConnector>>,
+    mcp_connections: Vec<Arc<MCPConnection>>,
 }
 
 impl AlanAgent {
@@ -219,7 +240,7 @@ impl AlanAgent {
         cli_console: Option<Arc<dyn CLIConsole>>,
     ) -> Result<Self> {
         // Create LLM client
-        let model_config = config.model_config.as_ref()
+        let model_config = config.default_model_config()
             .ok_or_else(|| anyhow::anyhow!("Model configuration required for AlanAgent"))?;
 
         let llm_client = create_llm_client(model_config)?;
@@ -245,17 +266,54 @@ impl AlanAgent {
             mcp_servers_config: config.mcp_servers_config.clone(),
             allow_mcp_servers,
             mcp_tools: Vec::new(),
+            mcp_connector: None,
+            mcp_connections: Vec::new(),
         })
     }
 
+    pub fn set_mcp_connector(&mut self, connector: Arc<dyn MCPConnector>) {
+        self.mcp_connector = Some(connector);
+    }
+
     pub async fn initialize_mcp(&mut self) -> Result<()> {
-        // MCP initialization disabled
-        // MCP initialization disabled
+        let connector = match &self.mcp_connector {
+            Some(connector) => connector.clone(),
+            None => {
+                tracing::warn!("No MCP connector configured, skipping MCP initialization");
+                return Ok(());
+            }
+        };
+
+        for server_name in self.allow_mcp_servers.clone() {
+            let server_config = match self.mcp_servers_config.as_ref().and_then(|c| c.get(&server_name)) {
+                Some(config) => config.clone(),
+                None => {
+                    tracing::warn!("MCP server '{}' is allowed but not configured", server_name);
+                    continue;
+                }
+            };
+
+            let connection = MCPConnection::connect(&server_name, &server_config, connector.clone())?
+                .with_auto_reconnect(MCP_RECONNECT_ATTEMPTS, MCP_RECONNECT_BASE_DELAY_MS);
+            let connection = Arc::new(connection);
+            let tools = connection.tools();
+            self.mcp_connections.push(connection);
+            tracing::info!("Registered {} tool(s) from MCP server '{}'", tools.len(), server_name);
+
+            // Register directly on the base agent so the executor used by `run` sees them
+            self.mcp_tools.extend(tools.iter().cloned());
+            self.base.add_tools(tools)?;
+        }
 
         Ok(())
     }
 
-
+    /// Name and health of each connected MCP server, in connection order
+    pub fn health_check_mcp_clients(&self) -> Vec<(String, bool)> {
+        self.mcp_connections.iter()
+            .map(|connection| (connection.server_name().to_string(), connection.is_healthy()))
+            .collect()
+    }
 }
 
 
@@ -288,6 +346,14 @@ impl BaseAgent for AlanAgent {
         self.base.get_cli_console()
     }
 
+    fn set_observer(&mut self, observer: Arc<dyn AgentObserver>) {
+        self.base.set_observer(observer)
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
     fn initialize(&mut self) -> Result<()> {
         self.base.initialize()?;
         // MCP initialization needs to be done asynchronously after this
@@ -327,6 +393,7 @@ impl BaseAgent for AlanAgent {
         task_args: serde_json::Value,
         cancel_flag: Arc<AtomicBool>,
     ) -> Result<String> {
+        validate_task_args(&task_args)?;
         let mut execution = AgentExecution::new(task.clone());
         execution.start();
 
@@ -355,10 +422,15 @@ impl BaseAgent for AlanAgent {
             if done {
                 break;
             }
+
+            // The observer chose to resume in run_step's on_pause, which stops the run otherwise
+            execution.resume();
         }
 
         // Check if we exceeded max steps
-        if execution.step_count() >= max_steps as usize && execution.state != AgentState::Finished {
+        if execution.step_count() >= max_steps as usize
+            && !matches!(execution.state, AgentState::Finished | AgentState::Stopped)
+        {
             execution.finish_with_error(format!("Maximum steps ({}) exceeded", max_steps));
             return Err(AgentError::MaxStepsExceeded(max_steps).into());
         }
@@ -403,6 +475,10 @@ impl Clone for AlanAgent {
                 tool_executor: self.base.tool_executor.clone(),
                 trajectory_recorder: self.base.trajectory_recorder.clone(),
                 cli_console: self.base.cli_console.clone(),
+                observer: self.base.observer.clone(),
+                thought_store: self.base.thought_store.clone(),
+                profile_clients: self.base.profile_clients.clone(),
+                estimated_cost_usd: self.base.estimated_cost_usd.clone(),
             },
             project_path: self.project_path.clone(),
             base_commit: self.base_commit.clone(),
@@ -411,6 +487,8 @@ impl Clone for AlanAgent {
             mcp_servers_config: self.mcp_servers_config.clone(),
             allow_mcp_servers: self.allow_mcp_servers.clone(),
             mcp_tools: self.mcp_tools.clone(),
+            mcp_connector: self.mcp_connector.clone(),
+            mcp_connections: self.mcp_connections.clone(),
         }
     }
 }
\ No newline at end of file
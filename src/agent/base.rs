@@ -1,12 +1,15 @@
 use anyhow::Result;
+use std::any::Any;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::Mutex;
 
-use crate::agent::basics::{AgentError, AgentExecution, AgentState};
+use crate::agent::basics::{validate_task_args, AgentError, AgentExecution, AgentState};
+use crate::agent::observer::{AgentObserver, Approval, PauseDecision};
 use crate::config::AgentConfig;
-use crate::llm::{LLMClient, LLMMessage, LLMResponse};
-use crate::tools::{Tool, ToolExecutor, ToolResult};
+use crate::llm::{create_llm_client, truncate_conversation, LLMClient, LLMConversationSummarizer, LLMMessage, LLMResponse, DEFAULT_PRESERVED_MESSAGES};
+use crate::tools::{ThoughtStore, Tool, ToolCall, ToolExecutor, ToolResult};
 use crate::utils::{CLIConsole, TrajectoryRecorder};
 
 pub trait BaseAgent: Send + Sync {
@@ -17,6 +20,10 @@ pub trait BaseAgent: Send + Sync {
     fn get_llm_client(&self) -> Arc<dyn LLMClient>;
     fn get_trajectory_recorder(&self) -> Option<Arc<Mutex<TrajectoryRecorder>>>;
     fn get_cli_console(&self) -> Option<Arc<dyn CLIConsole>>;
+    fn set_observer(&mut self, observer: Arc<dyn AgentObserver>);
+
+    /// Downcast hook so wrappers can reach agent-specific APIs (e.g. MCP initialization)
+    fn as_any_mut(&mut self) -> &mut dyn Any;
 
     fn initialize(&mut self) -> Result<()>;
     fn shutdown(&mut self) -> Result<()>;
@@ -43,6 +50,7 @@ pub trait BaseAgent: Send + Sync {
         task_args: serde_json::Value,
         cancel_flag: Arc<AtomicBool>,
     ) -> Result<String> {
+        validate_task_args(&task_args)?;
         let mut execution = AgentExecution::new(task.clone());
         execution.start();
 
@@ -70,10 +78,15 @@ pub trait BaseAgent: Send + Sync {
             if done {
                 break;
             }
+
+            // The observer chose to resume in run_step's on_pause, which stops the run otherwise
+            execution.resume();
         }
 
         // Check if we exceeded max steps
-        if execution.step_count() >= max_steps as usize && execution.state != AgentState::Finished {
+        if execution.step_count() >= max_steps as usize
+            && !matches!(execution.state, AgentState::Finished | AgentState::Stopped)
+        {
             execution.finish_with_error(format!("Maximum steps ({}) exceeded", max_steps));
             return Err(AgentError::MaxStepsExceeded(max_steps).into());
         }
@@ -96,6 +109,12 @@ pub struct BaseAgentImpl {
     pub tool_executor: Arc<ToolExecutor>,
     pub trajectory_recorder: Option<Arc<Mutex<TrajectoryRecorder>>>,
     pub cli_console: Option<Arc<dyn CLIConsole>>,
+    pub observer: Option<Arc<dyn AgentObserver>>,
+    pub thought_store: Arc<Mutex<ThoughtStore>>,
+    /// LLM clients for non-default model profiles, created on first use
+    pub profile_clients: Arc<Mutex<HashMap<String, Arc<dyn LLMClient>>>>,
+    /// Estimated prompt cost of the LLM calls made so far, checked against `max_cost_usd`
+    pub estimated_cost_usd: Arc<Mutex<f64>>,
 }
 
 impl BaseAgentImpl {
@@ -108,17 +127,29 @@ impl BaseAgentImpl {
     ) -> Result<Self> {
         let mut tools: Vec<Arc<dyn Tool>> = Vec::new();
 
+        // Shared across all tool instances so thought chains survive between steps
+        let thought_store = Arc::new(Mutex::new(ThoughtStore::new()));
+
         // Initialize tools based on config
         for tool_name in &config.tools {
-            let tool = crate::tools::create_tool(
-                tool_name,
-                config.allowed_folders.clone(),
-                config.model_config.as_ref().and_then(|m| m.model_provider.as_ref()),
-            )?;
+            let tool: Arc<dyn Tool> = match (tool_name.as_str(), &cli_console) {
+                // Batch progress is shown on the console while the folder is transpiled
+                ("transpile_vhdl_folder", Some(console)) => Arc::new(
+                    crate::tools::TranspileFolderTool::new(config.allowed_folders.clone())
+                        .with_progress_callback(Box::new(console.clone())),
+                ),
+                _ => crate::tools::create_tool(
+                    tool_name,
+                    config.allowed_folders.clone(),
+                    config.model_config.as_ref().and_then(|m| m.model_provider.as_ref()),
+                    thought_store.clone(),
+                    &config.bash_policy,
+                )?,
+            };
             tools.push(tool);
         }
 
-        let tool_executor = Arc::new(ToolExecutor::new(tools.clone()));
+        let tool_executor = Arc::new(ToolExecutor::new(tools.clone()).with_dry_run(config.dry_run));
 
         Ok(Self {
             name,
@@ -128,9 +159,71 @@ impl BaseAgentImpl {
             tool_executor,
             trajectory_recorder,
             cli_console,
+            observer: None,
+            thought_store,
+            profile_clients: Arc::new(Mutex::new(HashMap::new())),
+            estimated_cost_usd: Arc::new(Mutex::new(0.0)),
         })
     }
 
+    /// Model profile that should serve the next step of `execution` according to the step policy
+    pub fn active_profile(&self, execution: &AgentExecution) -> String {
+        if let Some(policy) = &self.config.step_policy {
+            if execution.failed_tool_results >= policy.after_failed_tool_results {
+                return policy.switch_to.clone();
+            }
+        }
+        self.config.default_profile_name().to_string()
+    }
+
+    /// LLM client for a profile. The default profile uses `llm_client`, others are created lazily.
+    pub fn client_for_profile(&self, profile: &str) -> Result<Arc<dyn LLMClient>> {
+        if profile == self.config.default_profile_name() {
+            return Ok(self.llm_client.clone());
+        }
+
+        let mut clients = self.profile_clients.lock().unwrap();
+        if let Some(client) = clients.get(profile) {
+            return Ok(client.clone());
+        }
+
+        let model_config = self.config.model_profile(profile)
+            .ok_or_else(|| anyhow::anyhow!("Model profile '{}' is not configured", profile))?;
+        let client = create_llm_client(model_config)?;
+        clients.insert(profile.to_string(), client.clone());
+        Ok(client)
+    }
+
+    /// Register additional tools (e.g. discovered on MCP servers) with this agent
+    pub fn add_tools(&mut self, tools: Vec<Arc<dyn Tool>>) -> Result<()> {
+        for tool in &tools {
+            tool.initialize()?;
+        }
+        self.tools.extend(tools);
+        self.tool_executor = Arc::new(
+            ToolExecutor::new(self.tools.clone()).with_dry_run(self.config.dry_run)
+        );
+        Ok(())
+    }
+
+    /// Summarize (with `use_summarization`) or drop the old messages of a conversation
+    /// over `budget` estimated tokens; a failed summary falls back to dropping them
+    fn compress_conversation(&self, messages: &mut Vec<LLMMessage>, budget: usize, llm_client: &Arc<dyn LLMClient>) {
+        if crate::llm::conversation_tokens(messages) <= budget {
+            return;
+        }
+        if self.config.use_summarization {
+            match LLMConversationSummarizer::new(llm_client.clone()).summarize(messages, budget) {
+                Ok(summarized) => {
+                    *messages = summarized;
+                    return;
+                }
+                Err(e) => tracing::warn!("Conversation summary failed, dropping old messages instead: {}", e),
+            }
+        }
+        *messages = truncate_conversation(messages, DEFAULT_PRESERVED_MESSAGES);
+    }
+
     pub fn close_tools(&mut self) -> Result<()> {
         for tool in &self.tools {
             tool.cleanup()?;
@@ -168,6 +261,14 @@ impl BaseAgent for BaseAgentImpl {
         self.cli_console.clone()
     }
 
+    fn set_observer(&mut self, observer: Arc<dyn AgentObserver>) {
+        self.observer = Some(observer);
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
     fn initialize(&mut self) -> Result<()> {
         // Initialize tools
         for tool in &self.tools {
@@ -198,8 +299,20 @@ impl BaseAgent for BaseAgentImpl {
         let mut results = Vec::new();
 
         if let Some(tool_calls) = &response.tool_calls {
+            if self.config.parallel_tool_execution && tool_calls.len() > 1 {
+                return self.process_parallel(tool_calls);
+            }
+
             for tool_call in tool_calls {
-                let result = self.tool_executor.execute(tool_call)?;
+                let result = match self.denial(tool_call) {
+                    Some(denied) => denied,
+                    None => self.tool_executor.execute(tool_call)
+                        .map_err(|e| tool_failure(tool_call, e))?,
+                };
+
+                if let Some(observer) = &self.observer {
+                    observer.on_tool_result(tool_call, &result);
+                }
                 results.push(result);
             }
         }
@@ -214,6 +327,14 @@ impl BaseAgent for BaseAgentImpl {
         cancel_flag: Arc<AtomicBool>,
         step_num: u32,
     ) -> Result<bool> {
+        if execution.state == AgentState::Paused {
+            return Err(AgentError::Other("Execution is paused; call resume() before the next step".to_string()).into());
+        }
+
+        if let Some(observer) = &self.observer {
+            observer.on_step_start(step_num, self.get_max_steps());
+        }
+
         // Print step header
         if let Some(console) = &self.cli_console {
             console.print_step(step_num, self.get_max_steps());
@@ -223,7 +344,49 @@ impl BaseAgent for BaseAgentImpl {
         // Debug: Print the complete prompt being sent to LLM
         self.print_prompt_box(messages);
 
-        let response = self.llm_client.complete(messages, Some(self.tools.clone()))?;
+        let profile = self.active_profile(execution);
+        let llm_client = self.client_for_profile(&profile)?;
+        tracing::debug!("Step {} served by model profile '{}' ({})", step_num, profile, llm_client.get_model_name());
+
+        if let Some(recorder) = &self.trajectory_recorder {
+            recorder.lock().unwrap().record_profile(step_num, &profile).ok();
+        }
+
+        // Keep the conversation within the context budget
+        if let Some(budget) = self.config.max_context_tokens {
+            self.compress_conversation(messages, budget, &llm_client);
+        }
+
+        // Stop before a call that would push the estimated cost over the limit
+        let step_cost = crate::llm::estimate_cost_usd(
+            crate::llm::conversation_tokens(messages),
+            llm_client.get_model_name(),
+        );
+        {
+            let mut spent = self.estimated_cost_usd.lock().unwrap();
+            if let Some(limit) = self.config.max_cost_usd {
+                if *spent + step_cost > limit {
+                    let message = format!(
+                        "Estimated cost ${:.4} would exceed max_cost_usd ${:.4}",
+                        *spent + step_cost,
+                        limit
+                    );
+                    if let Some(console) = &self.cli_console {
+                        console.print_error(&message);
+                    }
+                    execution.finish_with_error(message);
+                    return Ok(true);
+                }
+            }
+            *spent += step_cost;
+        }
+
+        let response = llm_client.complete(messages, Some(self.tools.clone()))
+            .map_err(AgentError::from_llm_error)?;
+
+        if let (Some(observer), Some(usage)) = (&self.observer, &response.usage) {
+            observer.on_llm_usage(usage);
+        }
 
         // Record LLM response to trajectory
         if let Some(recorder) = &self.trajectory_recorder {
@@ -269,6 +432,8 @@ impl BaseAgent for BaseAgentImpl {
         // Process tool calls
         let tool_results = self.process_response(&response, execution)?;
 
+        execution.failed_tool_results += tool_results.iter().filter(|r| !r.success).count() as u32;
+
         // Record and print tool usage
         if let Some(tool_calls) = &response.tool_calls {
             for tool_call in tool_calls {
@@ -317,11 +482,59 @@ impl BaseAgent for BaseAgentImpl {
             return Ok(true);
         }
 
+        if self.config.pause_after_step == Some(step_num) {
+            execution.pause();
+            if let Some(observer) = &self.observer {
+                if observer.on_pause(&execution.inspect(messages)) == PauseDecision::Stop {
+                    execution.stop();
+                    return Ok(true);
+                }
+            }
+        }
+
         Ok(false)
     }
 }
 
 impl BaseAgentImpl {
+    /// Give the observer a chance to veto a call before it runs
+    fn denial(&self, tool_call: &ToolCall) -> Option<ToolResult> {
+        match self.observer.as_ref().map(|o| o.on_tool_call(tool_call)) {
+            Some(Approval::Deny(reason)) => Some(ToolResult::error(
+                tool_call.id.clone(),
+                format!("Tool call '{}' was denied: {}", tool_call.name, reason),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Approve calls in order, run the approved ones concurrently, then report results in order
+    fn process_parallel(&self, tool_calls: &[ToolCall]) -> Result<Vec<ToolResult>> {
+        let denials: Vec<Option<ToolResult>> = tool_calls.iter().map(|call| self.denial(call)).collect();
+        let approved: Vec<ToolCall> = tool_calls.iter()
+            .zip(&denials)
+            .filter(|(_, denied)| denied.is_none())
+            .map(|(call, _)| call.clone())
+            .collect();
+        let mut executed = self.tool_executor.execute_parallel(&approved).into_iter();
+
+        let mut results = Vec::new();
+        for (tool_call, denied) in tool_calls.iter().zip(denials) {
+            let result = match denied {
+                Some(denied) => denied,
+                None => executed.next()
+                    .expect("one result per approved call")
+                    .map_err(|e| tool_failure(tool_call, e))?,
+            };
+
+            if let Some(observer) = &self.observer {
+                observer.on_tool_result(tool_call, &result);
+            }
+            results.push(result);
+        }
+        Ok(results)
+    }
+
     /// Log prompt messages in a structured format
     fn print_prompt_box(&self, messages: &[crate::llm::LLMMessage]) {
         tracing::debug!("=== CONVERSATION PROMPT ({} messages) ===", messages.len());
@@ -362,4 +575,447 @@ impl BaseAgentImpl {
         }
     }
 
-}
\ No newline at end of file
+}
+
+fn tool_failure(tool_call: &ToolCall, error: anyhow::Error) -> AgentError {
+    AgentError::ToolExecutionFailed { tool_name: tool_call.name.clone(), error: format!("{:#}", error) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{LLMUsage, ReplayLLMClient};
+    use crate::test_support::{EchoTool, ObserverEvent, RecordingObserver};
+    use crate::tools::ToolCall;
+    use std::sync::atomic::Ordering;
+
+    fn create_agent(responses: Vec<LLMResponse>, echo: Arc<EchoTool>) -> BaseAgentImpl {
+        let config = AgentConfig {
+            tools: vec![],
+            ..Default::default()
+        };
+        let client = Arc::new(ReplayLLMClient::new(responses));
+        let mut agent = BaseAgentImpl::new("test".to_string(), config, client, None, None).unwrap();
+
+        let tools: Vec<Arc<dyn Tool>> = vec![echo];
+        agent.tools = tools.clone();
+        agent.tool_executor = Arc::new(ToolExecutor::new(tools));
+        agent
+    }
+
+    fn echo_call(id: &str) -> ToolCall {
+        ToolCall::with_id(id.to_string(), "echo".to_string(), serde_json::json!({"text": "hi"}))
+    }
+
+    #[test]
+    fn test_observer_call_ordering() {
+        let responses = vec![
+            LLMResponse::new("Echoing".to_string())
+                .with_tool_calls(vec![echo_call("call_1")])
+                .with_usage(LLMUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    ..Default::default()
+                }),
+            LLMResponse::new("Done".to_string()).with_tool_calls(vec![ToolCall::with_id(
+                "call_2".to_string(),
+                "task_done".to_string(),
+                serde_json::json!({}),
+            )]),
+        ];
+
+        let echo = Arc::new(EchoTool::new());
+        let mut agent = create_agent(responses, echo.clone());
+        let observer = Arc::new(RecordingObserver::new());
+        agent.set_observer(observer.clone());
+
+        let result = agent.run(
+            "test task".to_string(),
+            serde_json::json!({}),
+            Arc::new(AtomicBool::new(false)),
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(echo.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            observer.events(),
+            vec![
+                ObserverEvent::StepStart(1),
+                ObserverEvent::LLMUsage(10, 5),
+                ObserverEvent::ToolCall("echo".to_string()),
+                ObserverEvent::ToolResult("echo".to_string(), true),
+                ObserverEvent::StepStart(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_observer_can_deny_tool_call() {
+        let responses = vec![
+            LLMResponse::new("Echoing".to_string()).with_tool_calls(vec![echo_call("call_1")]),
+        ];
+
+        let echo = Arc::new(EchoTool::new());
+        let mut agent = create_agent(responses, echo.clone());
+        let observer = Arc::new(RecordingObserver::with_denied_tools(vec!["echo".to_string()]));
+        agent.set_observer(observer.clone());
+
+        let mut messages = vec![LLMMessage::user("test task".to_string())];
+        let mut execution = AgentExecution::new("test task".to_string());
+        let done = agent
+            .run_step(&mut messages, &mut execution, Arc::new(AtomicBool::new(false)), 1)
+            .unwrap();
+
+        assert!(!done);
+        assert_eq!(echo.calls.load(Ordering::SeqCst), 0);
+
+        // The denial is fed back to the LLM as an error tool result
+        match messages.last().unwrap() {
+            LLMMessage::Tool { tool_call_id, content } => {
+                assert_eq!(tool_call_id, "call_1");
+                assert!(content.contains("denied"));
+            }
+            other => panic!("Expected tool message, got {:?}", other),
+        }
+
+        assert_eq!(
+            observer.events(),
+            vec![
+                ObserverEvent::StepStart(1),
+                ObserverEvent::ToolCall("echo".to_string()),
+                ObserverEvent::ToolResult("echo".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parallel_tool_execution_keeps_result_order() {
+        let echo = Arc::new(EchoTool::new());
+        let mut agent = create_agent(vec![], echo.clone());
+        agent.config.parallel_tool_execution = true;
+        let observer = Arc::new(RecordingObserver::new());
+        agent.set_observer(observer.clone());
+
+        let response = LLMResponse::new("Echoing".to_string())
+            .with_tool_calls(vec![echo_call("call_1"), echo_call("call_2"), echo_call("call_3")]);
+        let mut execution = AgentExecution::new("test task".to_string());
+        let results = agent.process_response(&response, &mut execution).unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|r| r.tool_call_id.as_str()).collect();
+        assert_eq!(ids, vec!["call_1", "call_2", "call_3"]);
+        assert_eq!(echo.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(observer.events().len(), 6);
+    }
+
+    #[test]
+    fn test_step_policy_switches_profile_after_failures() {
+        let fixer = crate::config::ModelConfig {
+            model_provider: Some(crate::config::ModelProvider {
+                provider: "mock".to_string(),
+                api_key: None,
+                base_url: None,
+                azure_resource_name: None,
+                azure_api_version: None,
+            }),
+            model_name: "fixer-model".to_string(),
+            model: "fixer-model".to_string(),
+            temperature: 0.0,
+            max_tokens: Some(512),
+            top_p: None,
+            stop_sequences: None,
+            max_retries: 1,
+        };
+        let config = AgentConfig {
+            tools: vec![],
+            models: std::collections::HashMap::from([("fixer".to_string(), fixer)]),
+            step_policy: Some(crate::config::StepPolicy {
+                after_failed_tool_results: 2,
+                switch_to: "fixer".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        // Both calls target a tool that does not exist, so both results fail
+        let missing_call = |id: &str| {
+            ToolCall::with_id(id.to_string(), "missing".to_string(), serde_json::json!({}))
+        };
+        let client = Arc::new(ReplayLLMClient::new(vec![
+            LLMResponse::new("Trying".to_string())
+                .with_tool_calls(vec![missing_call("call_1"), missing_call("call_2")]),
+            LLMResponse::new("Never served".to_string()),
+        ]));
+        let recorder = Arc::new(Mutex::new(TrajectoryRecorder::new(None).unwrap()));
+        let agent = BaseAgentImpl::new(
+            "test".to_string(),
+            config,
+            client.clone(),
+            Some(recorder.clone()),
+            None,
+        ).unwrap();
+
+        let mut messages = vec![LLMMessage::user("test task".to_string())];
+        let mut execution = AgentExecution::new("test task".to_string());
+        for step in 1..=3 {
+            agent
+                .run_step(&mut messages, &mut execution, Arc::new(AtomicBool::new(false)), step)
+                .unwrap();
+        }
+
+        assert_eq!(execution.failed_tool_results, 2);
+        assert_eq!(
+            recorder.lock().unwrap().step_profiles(),
+            &[
+                (1, "default".to_string()),
+                (2, "fixer".to_string()),
+                (3, "fixer".to_string()),
+            ]
+        );
+
+        // The scripted default client was only asked once; the mock fixer served the rest
+        assert_eq!(client.remaining(), 1);
+        assert_eq!(agent.profile_clients.lock().unwrap()["fixer"].get_model_name(), "fixer-model");
+        assert_eq!(messages.last().unwrap().content(), Some("Mock LLM response"));
+
+        // The next run starts on the default profile again
+        let mut execution = AgentExecution::new("next task".to_string());
+        agent.run_step(&mut messages, &mut execution, Arc::new(AtomicBool::new(false)), 1).unwrap();
+        assert_eq!(recorder.lock().unwrap().step_profiles().last(), Some(&(1, "default".to_string())));
+        client.assert_all_responses_consumed();
+    }
+
+    /// Observer keeping every snapshot taken at a pause, then resuming or stopping the run
+    #[derive(Default)]
+    struct PauseObserver {
+        snapshots: Mutex<Vec<crate::agent::basics::AgentSnapshot>>,
+        stop: bool,
+    }
+
+    impl AgentObserver for PauseObserver {
+        fn on_pause(&self, snapshot: &crate::agent::basics::AgentSnapshot) -> PauseDecision {
+            self.snapshots.lock().unwrap().push(snapshot.clone());
+            if self.stop {
+                PauseDecision::Stop
+            } else {
+                PauseDecision::Resume
+            }
+        }
+    }
+
+    #[test]
+    fn test_pause_and_resume_across_steps() {
+        let config = AgentConfig {
+            tools: vec![],
+            pause_after_step: Some(2),
+            ..Default::default()
+        };
+        let client = Arc::new(ReplayLLMClient::new(vec![
+            LLMResponse::new("step one".to_string()),
+            LLMResponse::new("step two".to_string()),
+            LLMResponse::new("step three".to_string()),
+        ]));
+        let observer = Arc::new(PauseObserver::default());
+        let mut agent = BaseAgentImpl::new("test".to_string(), config, client, None, None).unwrap();
+        agent.set_observer(observer.clone());
+
+        let mut messages = vec![LLMMessage::user("test task".to_string())];
+        let mut execution = AgentExecution::new("test task".to_string());
+        execution.start();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        assert!(!agent.run_step(&mut messages, &mut execution, cancel.clone(), 1).unwrap());
+        assert_eq!(execution.state, AgentState::Running);
+
+        assert!(!agent.run_step(&mut messages, &mut execution, cancel.clone(), 2).unwrap());
+        assert_eq!(execution.state, AgentState::Paused);
+
+        let snapshot = execution.inspect(&messages);
+        assert_eq!(snapshot.state, AgentState::Paused);
+        assert_eq!(snapshot.messages.len(), 3);
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["messages"][2]["content"], "step two");
+
+        let observed = observer.snapshots.lock().unwrap().clone();
+        assert_eq!(observed.len(), 1);
+        assert_eq!(observed[0].messages.len(), 3);
+
+        // Steps are refused until the caller resumes
+        assert!(agent.run_step(&mut messages, &mut execution, cancel.clone(), 3).is_err());
+        execution.resume();
+        assert!(!agent.run_step(&mut messages, &mut execution, cancel, 3).unwrap());
+        assert_eq!(execution.state, AgentState::Running);
+        assert_eq!(messages.last().unwrap().content(), Some("step three"));
+    }
+
+    #[test]
+    fn test_run_pauses_until_observer_decides() {
+        let run = |stop: bool| {
+            let config = AgentConfig {
+                tools: vec![],
+                pause_after_step: Some(1),
+                ..Default::default()
+            };
+            let client = Arc::new(ReplayLLMClient::new(vec![
+                LLMResponse::new("step one".to_string()),
+                LLMResponse::new("Done".to_string()).with_tool_calls(vec![ToolCall::with_id(
+                    "call_1".to_string(),
+                    "task_done".to_string(),
+                    serde_json::json!({}),
+                )]),
+            ]));
+            let observer = Arc::new(PauseObserver { stop, ..Default::default() });
+            let mut agent = BaseAgentImpl::new("test".to_string(), config, client.clone(), None, None).unwrap();
+            agent.set_observer(observer.clone());
+
+            let result = agent.run("test task".to_string(), serde_json::json!({}), Arc::new(AtomicBool::new(false)));
+
+            let observed = observer.snapshots.lock().unwrap().clone();
+            assert_eq!(observed.len(), 1);
+            assert_eq!(observed[0].state, AgentState::Paused);
+            assert_eq!(observed[0].messages.last().unwrap().content(), Some("step one"));
+            let remaining = client.remaining();
+            (result, remaining)
+        };
+
+        let (result, remaining) = run(false);
+        assert!(result.is_ok());
+        assert_eq!(remaining, 0);
+
+        let (result, remaining) = run(true);
+        assert!(matches!(result.unwrap_err().downcast_ref::<AgentError>(), Some(AgentError::Cancelled)));
+        assert_eq!(remaining, 1);
+    }
+
+    #[test]
+    fn test_max_cost_stops_before_llm_call() {
+        let config = AgentConfig {
+            tools: vec![],
+            max_cost_usd: Some(0.0001),
+            ..Default::default()
+        };
+        let client = Arc::new(ReplayLLMClient::new(vec![
+            LLMResponse::new("First".to_string()),
+            LLMResponse::new("Never served".to_string()),
+        ]));
+        let agent = BaseAgentImpl::new("test".to_string(), config, client.clone(), None, None).unwrap();
+
+        // 100 tokens at the default $10/M is $0.001, well over the limit
+        let mut messages = vec![LLMMessage::user("x".repeat(400))];
+        let mut execution = AgentExecution::new("test task".to_string());
+        let done = agent
+            .run_step(&mut messages, &mut execution, Arc::new(AtomicBool::new(false)), 1)
+            .unwrap();
+
+        assert!(done);
+        assert_eq!(execution.state, AgentState::Error);
+        assert!(execution.error.unwrap().contains("max_cost_usd"));
+        assert_eq!(client.remaining(), 2);
+    }
+
+    #[test]
+    fn test_context_budget_summarizes_or_drops_old_messages() {
+        let run = |use_summarization: bool| {
+            let config = AgentConfig {
+                tools: vec![],
+                max_context_tokens: Some(1000),
+                use_summarization,
+                ..Default::default()
+            };
+            let client = Arc::new(ReplayLLMClient::new(vec![
+                LLMResponse::new("Earlier steps summarized".to_string()),
+                LLMResponse::new("Next".to_string()),
+            ]));
+            let agent = BaseAgentImpl::new("test".to_string(), config, client, None, None).unwrap();
+
+            // 18 messages of 100 tokens after the system message and task
+            let mut messages = vec![
+                LLMMessage::system("system".to_string()),
+                LLMMessage::user("task".to_string()),
+            ];
+            for i in 0..18 {
+                messages.push(LLMMessage::assistant(format!("{} {}", i, "x".repeat(400)), None));
+            }
+            let mut execution = AgentExecution::new("test task".to_string());
+            agent.run_step(&mut messages, &mut execution, Arc::new(AtomicBool::new(false)), 1).unwrap();
+            messages
+        };
+
+        // System message, task, summary, the last 8 messages and the new response
+        let summarized = run(true);
+        assert_eq!(summarized.len(), 12);
+        assert_eq!(summarized[1].content(), Some("task"));
+        assert_eq!(summarized[2].content(), Some("Summary of 10 earlier message(s):\nEarlier steps summarized"));
+        assert_eq!(summarized[11].content(), Some("Next"));
+
+        // Without summarization the old messages are dropped and no summary is requested
+        let truncated = run(false);
+        assert_eq!(truncated.len(), 11);
+        assert!(truncated[2].content().unwrap().starts_with("10 "));
+        assert_eq!(truncated[10].content(), Some("Earlier steps summarized"));
+    }
+
+    #[test]
+    fn test_dry_run_does_not_touch_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let new_dir = temp_dir.path().join("rtl");
+        let new_file = temp_dir.path().join("alu.v");
+        let existing = temp_dir.path().join("top.v");
+        std::fs::write(&existing, "module top; endmodule\n").unwrap();
+
+        let config = AgentConfig {
+            tools: vec!["file_ops".to_string(), "str_replace_edit".to_string()],
+            allowed_folders: vec![temp_dir.path().to_str().unwrap().to_string()],
+            dry_run: true,
+            ..Default::default()
+        };
+        let client = Arc::new(ReplayLLMClient::new(vec![
+            LLMResponse::new("Writing files".to_string()).with_tool_calls(vec![
+                ToolCall::with_id("call_1".to_string(), "file_ops".to_string(), serde_json::json!({
+                    "command": "mkdir",
+                    "path": new_dir.to_str().unwrap()
+                })),
+                ToolCall::with_id("call_2".to_string(), "str_replace_based_edit_tool".to_string(), serde_json::json!({
+                    "command": "create",
+                    "path": new_file.to_str().unwrap(),
+                    "file_text": "module alu; endmodule\n"
+                })),
+                ToolCall::with_id("call_3".to_string(), "file_ops".to_string(), serde_json::json!({
+                    "command": "delete",
+                    "path": existing.to_str().unwrap()
+                })),
+            ]),
+        ]));
+        let recorder = Arc::new(Mutex::new(TrajectoryRecorder::new(None).unwrap()));
+        recorder.lock().unwrap().set_dry_run(true);
+        let agent = BaseAgentImpl::new(
+            "test".to_string(),
+            config,
+            client,
+            Some(recorder.clone()),
+            None,
+        ).unwrap();
+
+        let mut messages = vec![LLMMessage::user("test task".to_string())];
+        let mut execution = AgentExecution::new("test task".to_string());
+        agent
+            .run_step(&mut messages, &mut execution, Arc::new(AtomicBool::new(false)), 1)
+            .unwrap();
+
+        assert!(!new_dir.exists());
+        assert!(!new_file.exists());
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "module top; endmodule\n");
+
+        match messages.last().unwrap() {
+            LLMMessage::Tool { content, .. } => {
+                assert!(content.starts_with("[DRY-RUN] Would call tool 'file_ops' with args:"));
+            }
+            other => panic!("Expected tool message, got {:?}", other),
+        }
+
+        let recorder = recorder.lock().unwrap();
+        assert_eq!(recorder.actions().len(), 3);
+        assert!(recorder.actions().iter().all(|a| a.dry_run));
+        let summary = recorder.dry_run_summary().unwrap();
+        assert!(summary.starts_with("[DRY-RUN] Would have executed 3 tool calls: file_ops({"));
+        assert!(summary.contains("str_replace_based_edit_tool({"));
+    }
+}
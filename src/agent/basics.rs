@@ -1,5 +1,16 @@
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+use crate::llm::LLMMessage;
+
+lazy_static! {
+    static ref CONTEXT_OVERFLOW_RE: Regex =
+        Regex::new(r"(?is)maximum context length is (\d+) tokens.*?(?:resulted in|requested) (\d+) tokens").unwrap();
+    static ref RATE_LIMIT_RE: Regex = Regex::new(r"(?i)rate[ _-]?limit|\b429\b|too many requests").unwrap();
+    static ref RETRY_AFTER_RE: Regex = Regex::new(r"(?i)(?:retry[ _-]after|try again in)\D{0,3}(\d+(?:\.\d+)?)").unwrap();
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AgentState {
     Init,
@@ -7,6 +18,7 @@ pub enum AgentState {
     Finished,
     Error,
     Stopped,
+    Paused,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -77,6 +89,9 @@ pub struct AgentExecution {
     pub error: Option<String>,
     pub started_at: chrono::DateTime<chrono::Utc>,
     pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Failed tool results seen in this run, consulted by the step policy
+    #[serde(default)]
+    pub failed_tool_results: u32,
 }
 
 impl AgentExecution {
@@ -90,6 +105,7 @@ impl AgentExecution {
             error: None,
             started_at: chrono::Utc::now(),
             finished_at: None,
+            failed_tool_results: 0,
         }
     }
 
@@ -118,9 +134,45 @@ impl AgentExecution {
         self.finished_at = Some(chrono::Utc::now());
     }
 
+    /// Suspend a running execution; `run_step` refuses to proceed until `resume`
+    pub fn pause(&mut self) {
+        if self.state == AgentState::Running {
+            self.state = AgentState::Paused;
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.state == AgentState::Paused {
+            self.state = AgentState::Running;
+        }
+    }
+
     pub fn step_count(&self) -> usize {
         self.steps.len()
     }
+
+    /// Capture the execution state together with the conversation so far
+    pub fn inspect(&self, messages: &[LLMMessage]) -> AgentSnapshot {
+        AgentSnapshot {
+            execution_id: self.id.clone(),
+            task: self.task.clone(),
+            state: self.state.clone(),
+            step_count: self.step_count(),
+            messages: messages.to_vec(),
+            taken_at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// Serializable view of a (typically paused) execution for external tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentSnapshot {
+    pub execution_id: String,
+    pub task: String,
+    pub state: AgentState,
+    pub step_count: usize,
+    pub messages: Vec<LLMMessage>,
+    pub taken_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -142,10 +194,136 @@ pub enum AgentError {
 
     #[error("Agent error: {0}")]
     Other(String),
+
+    #[error("LLM rate limit reached; {}", match retry_after_secs {
+        Some(secs) => format!("retry after {} seconds", secs),
+        None => "wait before retrying or lower the request rate".to_string(),
+    })]
+    LLMRateLimit { retry_after_secs: Option<u64> },
+
+    #[error("Conversation needs {actual_tokens} tokens but the model accepts {max_tokens}; shorten the task, view files with view_range, or use a model with a larger context")]
+    LLMContextOverflow { max_tokens: usize, actual_tokens: usize },
+
+    #[error("Tool '{tool_name}' failed: {error}")]
+    ToolExecutionFailed { tool_name: String, error: String },
+
+    #[error("Invalid task argument '{field}': {reason}")]
+    InvalidTaskArgs { field: String, reason: String },
+
+    #[error("{file}:{line}: parse error: {message}")]
+    ParseError { file: String, line: usize, message: String },
+}
+
+impl AgentError {
+    /// Whether the same request may succeed if retried later
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, AgentError::LLMRateLimit { .. })
+    }
+
+    /// Classify an error from `LLMClient::complete` by the provider's error message
+    pub fn from_llm_error(err: anyhow::Error) -> Self {
+        let message = format!("{:#}", err);
+        if let Some(caps) = CONTEXT_OVERFLOW_RE.captures(&message) {
+            return AgentError::LLMContextOverflow {
+                max_tokens: caps[1].parse().unwrap_or(0),
+                actual_tokens: caps[2].parse().unwrap_or(0),
+            };
+        }
+        if RATE_LIMIT_RE.is_match(&message) {
+            let retry_after_secs = RETRY_AFTER_RE.captures(&message)
+                .and_then(|caps| caps[1].parse::<f64>().ok())
+                .map(|secs| secs.ceil() as u64);
+            return AgentError::LLMRateLimit { retry_after_secs };
+        }
+        AgentError::LLM(message)
+    }
+}
+
+/// `task_args` must be a JSON object (or null); known fields must have the right type
+pub fn validate_task_args(task_args: &serde_json::Value) -> Result<(), AgentError> {
+    let args = match task_args {
+        serde_json::Value::Null => return Ok(()),
+        serde_json::Value::Object(args) => args,
+        _ => return Err(AgentError::InvalidTaskArgs {
+            field: "task_args".to_string(),
+            reason: "expected a JSON object".to_string(),
+        }),
+    };
+
+    match args.get("project_path") {
+        None | Some(serde_json::Value::String(_)) => Ok(()),
+        Some(_) => Err(AgentError::InvalidTaskArgs {
+            field: "project_path".to_string(),
+            reason: "expected a string path".to_string(),
+        }),
+    }
 }
 
 impl From<anyhow::Error> for AgentError {
     fn from(err: anyhow::Error) -> Self {
-        AgentError::Other(err.to_string())
+        // Keep structured errors that were passed through anyhow
+        match err.downcast::<AgentError>() {
+            Ok(agent_error) => agent_error,
+            Err(err) => AgentError::Other(err.to_string()),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_llm_errors_are_classified() {
+        let err = AgentError::from_llm_error(anyhow::anyhow!(
+            r#"OpenAI API error: {{"code":"rate_limit_exceeded","message":"Rate limit reached. Please try again in 7.5s."}}"#
+        ));
+        assert!(matches!(err, AgentError::LLMRateLimit { retry_after_secs: Some(8) }));
+        assert!(err.is_retryable());
+        assert_eq!(err.to_string(), "LLM rate limit reached; retry after 8 seconds");
+
+        let err = AgentError::from_llm_error(anyhow::anyhow!("HTTP 429 Too Many Requests"));
+        assert!(matches!(err, AgentError::LLMRateLimit { retry_after_secs: None }));
+
+        let err = AgentError::from_llm_error(anyhow::anyhow!(
+            "OpenAI API error: This model's maximum context length is 8192 tokens. However, your messages resulted in 9500 tokens."
+        ));
+        assert!(matches!(err, AgentError::LLMContextOverflow { max_tokens: 8192, actual_tokens: 9500 }));
+        assert!(!err.is_retryable());
+        assert!(err.to_string().starts_with("Conversation needs 9500 tokens but the model accepts 8192;"));
+
+        let err = AgentError::from_llm_error(anyhow::anyhow!("connection reset").context("Failed to send request"));
+        assert_eq!(err.to_string(), "LLM error: Failed to send request: connection reset");
+    }
+
+    #[test]
+    fn test_tool_and_parse_error_messages() {
+        let err = AgentError::ToolExecutionFailed { tool_name: "bash".to_string(), error: "timed out".to_string() };
+        assert_eq!(err.to_string(), "Tool 'bash' failed: timed out");
+        assert!(err.source().is_none());
+
+        let err = AgentError::ParseError { file: "top.vhd".to_string(), line: 12, message: "unexpected 'end'".to_string() };
+        assert_eq!(err.to_string(), "top.vhd:12: parse error: unexpected 'end'");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_task_args_validation() {
+        assert!(validate_task_args(&serde_json::Value::Null).is_ok());
+        assert!(validate_task_args(&serde_json::json!({"project_path": "/work"})).is_ok());
+
+        let err = validate_task_args(&serde_json::json!(["not", "an", "object"])).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid task argument 'task_args': expected a JSON object");
+        let err = validate_task_args(&serde_json::json!({"project_path": 3})).unwrap_err();
+        assert!(matches!(err, AgentError::InvalidTaskArgs { ref field, .. } if field == "project_path"));
+    }
+
+    #[test]
+    fn test_structured_errors_survive_anyhow() {
+        let wrapped: anyhow::Error = AgentError::LLMRateLimit { retry_after_secs: Some(2) }.into();
+        assert!(matches!(
+            wrapped.downcast_ref::<AgentError>(),
+            Some(AgentError::LLMRateLimit { retry_after_secs: Some(2) })
+        ));
+        assert!(matches!(AgentError::from(wrapped), AgentError::LLMRateLimit { .. }));
+    }
+}
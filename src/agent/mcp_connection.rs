@@ -0,0 +1,222 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::agent::alan_agent::MCPConnector;
+use crate::config::MCPServerConfig;
+use crate::tools::{Tool, ToolSchema};
+
+/// How long `is_healthy` waits for a server to answer its ping
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A connected MCP server whose tool calls can reconnect to it after a crash
+pub struct MCPConnection {
+    server_name: String,
+    config: MCPServerConfig,
+    connector: Arc<dyn MCPConnector>,
+    tools: Mutex<HashMap<String, Arc<dyn Tool>>>,
+    max_attempts: u32,
+    base_delay_ms: u64,
+}
+
+impl MCPConnection {
+    /// Connect to `server_name` through `connector`
+    pub fn connect(server_name: &str, config: &MCPServerConfig, connector: Arc<dyn MCPConnector>) -> Result<Self> {
+        let connection = Self {
+            server_name: server_name.to_string(),
+            config: config.clone(),
+            connector,
+            tools: Mutex::new(HashMap::new()),
+            max_attempts: 0,
+            base_delay_ms: 0,
+        };
+        connection.reconnect()?;
+        Ok(connection)
+    }
+
+    /// When a tool call fails and the server no longer answers, reconnect up to
+    /// `max_attempts` times, waiting `base_delay_ms * 2^attempt` before each attempt
+    pub fn with_auto_reconnect(mut self, max_attempts: u32, base_delay_ms: u64) -> Self {
+        self.max_attempts = max_attempts;
+        self.base_delay_ms = base_delay_ms;
+        self
+    }
+
+    pub fn server_name(&self) -> &str {
+        &self.server_name
+    }
+
+    /// Call `tool_name` on the server, reconnecting first if the call failed because
+    /// the server went away
+    pub fn call_tool(&self, tool_name: &str, arguments: &serde_json::Value) -> Result<String> {
+        let error = match self.tool(tool_name)?.execute(arguments) {
+            Ok(result) => return Ok(result),
+            Err(error) => error,
+        };
+        // A server that still answers reported a tool error, not a lost connection
+        if self.max_attempts == 0 || self.is_healthy() {
+            return Err(error);
+        }
+
+        let mut last_error = error;
+        for attempt in 0..self.max_attempts {
+            std::thread::sleep(Duration::from_millis(self.base_delay_ms.saturating_mul(1 << attempt.min(16))));
+            tracing::warn!(
+                "Reconnecting to MCP server '{}' (attempt {}/{})",
+                self.server_name,
+                attempt + 1,
+                self.max_attempts
+            );
+            match self.reconnect() {
+                Ok(()) => return self.tool(tool_name)?.execute(arguments),
+                Err(error) => last_error = error,
+            }
+        }
+        Err(last_error.context(format!(
+            "MCP server '{}' did not come back after {} reconnect attempt(s)",
+            self.server_name, self.max_attempts
+        )))
+    }
+
+    /// Whether the server answers a ping within two seconds; the connector enforces
+    /// the timeout, so a hung server does not hold up the caller
+    pub fn is_healthy(&self) -> bool {
+        self.connector.ping(&self.server_name, PING_TIMEOUT).is_ok()
+    }
+
+    /// The server's tools, with calls routed through this connection
+    pub fn tools(self: &Arc<Self>) -> Vec<Arc<dyn Tool>> {
+        let tools = self.tools.lock().unwrap();
+        let mut names: Vec<&String> = tools.keys().collect();
+        names.sort();
+        names.into_iter()
+            .map(|name| {
+                let tool = &tools[name];
+                Arc::new(ConnectionTool {
+                    connection: self.clone(),
+                    name: name.clone(),
+                    description: tool.description().to_string(),
+                    schema: tool.schema(),
+                }) as Arc<dyn Tool>
+            })
+            .collect()
+    }
+
+    fn tool(&self, tool_name: &str) -> Result<Arc<dyn Tool>> {
+        self.tools.lock().unwrap().get(tool_name).cloned().ok_or_else(|| {
+            anyhow::anyhow!("MCP server '{}' has no tool '{}'", self.server_name, tool_name)
+        })
+    }
+
+    fn reconnect(&self) -> Result<()> {
+        let tools = self.connector.connect(&self.server_name, &self.config)?;
+        *self.tools.lock().unwrap() = tools.into_iter()
+            .map(|tool| (tool.name().to_string(), tool))
+            .collect();
+        Ok(())
+    }
+}
+
+/// A tool of an `MCPConnection`, executed through `MCPConnection::call_tool`
+struct ConnectionTool {
+    connection: Arc<MCPConnection>,
+    name: String,
+    description: String,
+    schema: ToolSchema,
+}
+
+impl Tool for ConnectionTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.schema.clone()
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        self.connection.call_tool(&self.name, arguments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::EchoTool;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    /// A server that can crash and refuses `refusals` connections before it recovers
+    #[derive(Default)]
+    struct FlakyServer {
+        alive: Arc<AtomicBool>,
+        refusals: AtomicU32,
+        connects: AtomicU32,
+    }
+
+    struct FlakyConnector(Arc<FlakyServer>);
+
+    impl MCPConnector for FlakyConnector {
+        fn connect(&self, _server_name: &str, _config: &MCPServerConfig) -> Result<Vec<Arc<dyn Tool>>> {
+            self.0.connects.fetch_add(1, Ordering::SeqCst);
+            if self.0.refusals.load(Ordering::SeqCst) > 0 {
+                self.0.refusals.fetch_sub(1, Ordering::SeqCst);
+                anyhow::bail!("connection refused");
+            }
+            self.0.alive.store(true, Ordering::SeqCst);
+            Ok(vec![Arc::new(EchoTool::new().with_connection(self.0.alive.clone()))])
+        }
+
+        fn ping(&self, _server_name: &str, _timeout: Duration) -> Result<()> {
+            if self.0.alive.load(Ordering::SeqCst) {
+                Ok(())
+            } else {
+                anyhow::bail!("broken pipe")
+            }
+        }
+    }
+
+    fn connect(server: &Arc<FlakyServer>) -> MCPConnection {
+        let config = MCPServerConfig { command: "flaky-server".to_string(), args: vec![], env: None };
+        MCPConnection::connect("flaky", &config, Arc::new(FlakyConnector(server.clone()))).unwrap()
+    }
+
+    #[test]
+    fn test_reconnects_after_crash() {
+        let server = Arc::new(FlakyServer::default());
+        let connection = Arc::new(connect(&server).with_auto_reconnect(3, 1));
+        let echo = connection.tools().pop().unwrap();
+        assert_eq!(echo.execute(&serde_json::json!({"text": "hi"})).unwrap(), "hi");
+        assert!(connection.is_healthy());
+
+        // The server crashes and refuses two connections before it recovers
+        server.alive.store(false, Ordering::SeqCst);
+        server.refusals.store(2, Ordering::SeqCst);
+        assert!(!connection.is_healthy());
+        assert_eq!(echo.execute(&serde_json::json!({"text": "again"})).unwrap(), "again");
+        assert_eq!(server.connects.load(Ordering::SeqCst), 4);
+        assert!(connection.is_healthy());
+
+        // A server that stays down fails the call once the attempts run out
+        server.alive.store(false, Ordering::SeqCst);
+        server.refusals.store(10, Ordering::SeqCst);
+        let err = connection.call_tool("echo", &serde_json::json!({})).unwrap_err();
+        assert!(format!("{:#}", err).contains("did not come back after 3 reconnect attempt(s)"));
+    }
+
+    #[test]
+    fn test_no_reconnect_by_default() {
+        let server = Arc::new(FlakyServer::default());
+        let connection = connect(&server);
+        server.alive.store(false, Ordering::SeqCst);
+
+        let err = connection.call_tool("echo", &serde_json::json!({})).unwrap_err();
+        assert_eq!(err.to_string(), "broken pipe");
+        assert_eq!(server.connects.load(Ordering::SeqCst), 1);
+        assert!(connection.call_tool("missing", &serde_json::json!({})).is_err());
+    }
+}
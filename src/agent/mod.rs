@@ -1,9 +1,12 @@
 pub mod alan_agent;
 pub mod base;
 pub mod basics;
+pub mod mcp_connection;
+pub mod observer;
 pub mod transpiler_agent;
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
@@ -12,10 +15,14 @@ use std::sync::Mutex;
 use crate::config::AgentConfig;
 use crate::utils::{CLIConsole, TrajectoryRecorder};
 
+pub use alan_agent::MCPConnector;
 pub use base::{BaseAgent, BaseAgentImpl};
-pub use basics::{AgentError, AgentExecution, AgentState, AgentStep, AgentStepState};
+pub use basics::{validate_task_args, AgentError, AgentExecution, AgentSnapshot, AgentState, AgentStep, AgentStepState};
+pub use mcp_connection::MCPConnection;
+pub use observer::{AgentObserver, Approval, PauseDecision};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AgentType {
     AlanAgent,
     TranspilerAgent,
@@ -34,7 +41,6 @@ impl AgentType {
 pub struct Agent {
     agent_type: AgentType,
     inner: Box<dyn BaseAgent>,
-    alan_agent: Option<alan_agent::AlanAgent>,  // Keep a separate reference for async MCP operations
     trajectory_recorder: Option<Arc<Mutex<TrajectoryRecorder>>>,
 }
 
@@ -45,64 +51,94 @@ impl Agent {
         trajectory_file: Option<PathBuf>,
         cli_console: Box<dyn CLIConsole>,
     ) -> Result<Self> {
+        config.validate(agent_type)?;
+
         let trajectory_recorder = if let Some(path) = trajectory_file {
             Some(Arc::new(Mutex::new(TrajectoryRecorder::new(Some(path))?)))
         } else {
             Some(Arc::new(Mutex::new(TrajectoryRecorder::new(None)?)))
         };
 
+        if let Some(recorder) = &trajectory_recorder {
+            recorder.lock().unwrap().set_dry_run(config.dry_run);
+        }
+
         let cli_console: Arc<dyn CLIConsole> = Arc::from(cli_console);
 
-        let (inner, alan_agent): (Box<dyn BaseAgent>, Option<alan_agent::AlanAgent>) = match agent_type {
-            AgentType::AlanAgent => {
-                let agent = alan_agent::AlanAgent::new(
-                    config,
-                    trajectory_recorder.clone(),
-                    Some(cli_console),
-                )?;
-                let agent_clone = agent.clone();
-                (Box::new(agent) as Box<dyn BaseAgent>, Some(agent_clone))
-            }
-            AgentType::TranspilerAgent => {
-                let agent = transpiler_agent::TranspilerAgent::new(
-                    config,
-                    trajectory_recorder.clone(),
-                    Some(cli_console),
-                )?;
-                (Box::new(agent) as Box<dyn BaseAgent>, None)
-            }
+        let inner: Box<dyn BaseAgent> = match agent_type {
+            AgentType::AlanAgent => Box::new(alan_agent::AlanAgent::new(
+                config,
+                trajectory_recorder.clone(),
+                Some(cli_console),
+            )?),
+            AgentType::TranspilerAgent => Box::new(transpiler_agent::TranspilerAgent::new(
+                config,
+                trajectory_recorder.clone(),
+                Some(cli_console),
+            )?),
         };
 
         Ok(Self {
             agent_type,
             inner,
-            alan_agent,
             trajectory_recorder,
         })
     }
 
+    /// Create an agent using the console selected by `config.console`
+    pub fn from_config(
+        agent_type: AgentType,
+        config: AgentConfig,
+        trajectory_file: Option<PathBuf>,
+    ) -> Result<Self> {
+        let cli_console = crate::utils::create_console(config.console);
+        Self::new(agent_type, config, trajectory_file, cli_console)
+    }
+
+    /// Access the inner AlanAgent, if this agent is one
+    fn alan_agent_mut(&mut self) -> Option<&mut alan_agent::AlanAgent> {
+        self.inner.as_any_mut().downcast_mut::<alan_agent::AlanAgent>()
+    }
+
+    /// Set the connector used to reach MCP servers during `initialize_mcp`
+    pub fn set_mcp_connector(&mut self, connector: Arc<dyn MCPConnector>) -> Result<()> {
+        let alan_agent = self
+            .alan_agent_mut()
+            .ok_or_else(|| anyhow::anyhow!("MCP servers are only supported by AlanAgent"))?;
+        alan_agent.set_mcp_connector(connector);
+        Ok(())
+    }
+
+    /// Name and health of each MCP server connected by `initialize_mcp`; empty for
+    /// agents other than AlanAgent
+    pub fn health_check_mcp_clients(&mut self) -> Vec<(String, bool)> {
+        self.alan_agent_mut()
+            .map(|alan_agent| alan_agent.health_check_mcp_clients())
+            .unwrap_or_default()
+    }
+
     pub fn initialize_mcp(&mut self) -> Result<()> {
         // First initialize the base agent
         self.inner.initialize()?;
 
-        // Then initialize MCP if it's an AlanAgent
-        if let Some(ref mut alan_agent) = self.alan_agent {
-            // Check if MCP servers are configured
-            let has_mcp = alan_agent.allow_mcp_servers.len() > 0;
-            if has_mcp {
+        // Then initialize MCP in place if it's an AlanAgent, so the instance used
+        // by `run` is the one that received the MCP tools
+        if let Some(alan_agent) = self.alan_agent_mut() {
+            if !alan_agent.allow_mcp_servers.is_empty() {
                 // Create a runtime for async MCP initialization
                 let runtime = tokio::runtime::Runtime::new()?;
                 runtime.block_on(alan_agent.initialize_mcp())?;
-
-                // CRITICAL FIX: Replace the inner instance with the MCP-initialized one
-                // This ensures that the agent used for execution has all the MCP tools
-                self.inner = Box::new(alan_agent.clone());
             }
         }
 
         Ok(())
     }
 
+    /// Register an observer that receives step, tool and usage events
+    pub fn set_observer(&mut self, observer: Arc<dyn AgentObserver>) {
+        self.inner.set_observer(observer);
+    }
+
     pub fn close_tools(&mut self) -> Result<()> {
         self.inner.shutdown()
     }
@@ -132,6 +168,149 @@ impl Agent {
         task_args: serde_json::Value,
         cancel_flag: Arc<AtomicBool>,
     ) -> Result<String> {
-        self.inner.run(task, task_args, cancel_flag)
+        let result = self.inner.run(task, task_args, cancel_flag)?;
+
+        let summary = self.trajectory_recorder
+            .as_ref()
+            .and_then(|recorder| recorder.lock().unwrap().dry_run_summary());
+
+        match summary {
+            Some(summary) => Ok(format!("{}\n\n{}", result, summary)),
+            None => Ok(result),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::config::{MCPServerConfig, ModelConfig, ModelProvider};
+    use crate::tools::{BaseToolImpl, Tool, ToolSchema};
+    use crate::utils::SimpleConsole;
+
+    struct StubMCPTool {
+        base: BaseToolImpl,
+    }
+
+    impl Tool for StubMCPTool {
+        fn name(&self) -> &str {
+            &self.base.name
+        }
+
+        fn description(&self) -> &str {
+            &self.base.description
+        }
+
+        fn schema(&self) -> ToolSchema {
+            self.base.schema.clone()
+        }
+
+        fn execute(&self, _arguments: &serde_json::Value) -> Result<String> {
+            Ok("stub".to_string())
+        }
     }
-}
\ No newline at end of file
+
+    struct StubConnector;
+
+    impl MCPConnector for StubConnector {
+        fn connect(&self, server_name: &str, _config: &MCPServerConfig) -> Result<Vec<Arc<dyn Tool>>> {
+            Ok(vec![Arc::new(StubMCPTool {
+                base: BaseToolImpl::new(
+                    format!("{}_lookup", server_name),
+                    "Stub MCP tool".to_string(),
+                    vec![],
+                ),
+            })])
+        }
+    }
+
+    fn mcp_config() -> AgentConfig {
+        let mut servers = HashMap::new();
+        servers.insert(
+            "stub".to_string(),
+            MCPServerConfig {
+                command: "stub-server".to_string(),
+                args: vec![],
+                env: None,
+            },
+        );
+
+        AgentConfig {
+            tools: vec!["task_done".to_string()],
+            model_config: Some(ModelConfig {
+                model_provider: Some(ModelProvider {
+                    provider: "mock".to_string(),
+                    api_key: None,
+                    base_url: None,
+                    azure_resource_name: None,
+                    azure_api_version: None,
+                }),
+                model_name: "mock".to_string(),
+                model: "mock".to_string(),
+                temperature: 0.0,
+                max_tokens: None,
+                top_p: None,
+                stop_sequences: None,
+                max_retries: 1,
+            }),
+            allow_mcp_servers: vec!["stub".to_string()],
+            mcp_servers_config: Some(servers),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_initialize_mcp_registers_tools_on_running_agent() {
+        let mut agent = Agent::new(
+            AgentType::AlanAgent,
+            mcp_config(),
+            None,
+            Box::new(SimpleConsole),
+        )
+        .unwrap();
+
+        agent.set_mcp_connector(Arc::new(StubConnector)).unwrap();
+        agent.initialize_mcp().unwrap();
+
+        let tool_names = agent.get_tool_names();
+        assert!(tool_names.contains(&"task_done".to_string()));
+        assert!(tool_names.contains(&"stub_lookup".to_string()));
+        assert!(agent.inner.get_tool_executor().get_tool("stub_lookup").is_some());
+        assert_eq!(agent.health_check_mcp_clients(), vec![("stub".to_string(), true)]);
+    }
+
+    #[test]
+    fn test_mcp_connector_requires_alan_agent() {
+        let mut agent = Agent::new(
+            AgentType::TranspilerAgent,
+            mcp_config(),
+            None,
+            Box::new(SimpleConsole),
+        )
+        .unwrap();
+
+        assert!(agent.set_mcp_connector(Arc::new(StubConnector)).is_err());
+    }
+
+    #[test]
+    fn test_profiles_create_initialized_agents() {
+        for name in ["transpile", "debug", "analyze"] {
+            let config = AgentConfig::merge(
+                AgentConfig::from_profile(name).unwrap(),
+                AgentConfig {
+                    model_config: mcp_config().model_config,
+                    ..Default::default()
+                },
+            );
+            let agent_type = config.agent_type.unwrap();
+            let tools = config.tools.clone();
+
+            let mut agent = Agent::new(agent_type, config, None, Box::new(SimpleConsole)).unwrap();
+            agent.initialize_mcp().unwrap();
+
+            assert_eq!(agent.get_tool_names().len(), tools.len(), "profile {}", name);
+            agent.close_tools().unwrap();
+        }
+    }
+}
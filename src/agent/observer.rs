@@ -0,0 +1,45 @@
+use crate::agent::basics::AgentSnapshot;
+use crate::llm::LLMUsage;
+use crate::tools::{ToolCall, ToolResult};
+
+/// Decision returned by an observer before a tool call is executed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Approval {
+    Approve,
+    Deny(String),
+}
+
+/// Decision returned by an observer when the execution pauses
+#[derive(Debug, Clone, PartialEq)]
+pub enum PauseDecision {
+    Resume,
+    Stop,
+}
+
+/// Hooks for applications embedding an agent (GUIs, approval workflows, ...)
+///
+/// Every method has an empty default implementation, so an observer only
+/// overrides the events it cares about.
+pub trait AgentObserver: Send + Sync {
+    /// Called at the beginning of every agent step
+    fn on_step_start(&self, _step: u32, _max_steps: u32) {}
+
+    /// Called before a tool is executed. Returning `Approval::Deny` skips the
+    /// execution and reports the denial back to the LLM as an error result.
+    fn on_tool_call(&self, _tool_call: &ToolCall) -> Approval {
+        Approval::Approve
+    }
+
+    /// Called once a tool call has produced a result (including denied calls)
+    fn on_tool_result(&self, _tool_call: &ToolCall, _result: &ToolResult) {}
+
+    /// Called with the token usage reported for each LLM completion
+    fn on_llm_usage(&self, _usage: &LLMUsage) {}
+
+    /// Called when the execution pauses after the step configured in `pause_after_step`.
+    /// The observer may block here while the snapshot is inspected; returning
+    /// `PauseDecision::Stop` ends the run instead of resuming it.
+    fn on_pause(&self, _snapshot: &AgentSnapshot) -> PauseDecision {
+        PauseDecision::Resume
+    }
+}
@@ -4,6 +4,7 @@ use std::sync::atomic::AtomicBool;
 use std::sync::Mutex;
 
 use crate::agent::base::{BaseAgent, BaseAgentImpl};
+use crate::agent::observer::AgentObserver;
 use crate::agent::basics::AgentExecution;
 use crate::config::AgentConfig;
 use crate::llm::{create_llm_client, LLMMessage};
@@ -103,7 +104,7 @@ impl TranspilerAgent {
         cli_console: Option<Arc<dyn CLIConsole>>,
     ) -> Result<Self> {
         // Create LLM client
-        let model_config = config.model_config.as_ref()
+        let model_config = config.default_model_config()
             .ok_or_else(|| anyhow::anyhow!("Model configuration required for TranspilerAgent"))?;
 
         let llm_client = create_llm_client(model_config)?;
@@ -153,6 +154,14 @@ impl BaseAgent for TranspilerAgent {
         self.base.get_cli_console()
     }
 
+    fn set_observer(&mut self, observer: Arc<dyn AgentObserver>) {
+        self.base.set_observer(observer)
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
     fn initialize(&mut self) -> Result<()> {
         self.base.initialize()
     }
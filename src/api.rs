@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::ir::{Entity, SpinalHDLGenerator, SystemVerilogGenerator, VerilogGenerator};
+use crate::parser::ASTVHDLParser;
+
+/// Output language of the library transpile functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Verilog,
+    SystemVerilog,
+    SpinalHDL,
+}
+
+impl std::str::FromStr for Target {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "v" | "verilog" => Ok(Target::Verilog),
+            "sv" | "systemverilog" => Ok(Target::SystemVerilog),
+            "spinal" | "spinalhdl" => Ok(Target::SpinalHDL),
+            other => Err(anyhow::anyhow!(
+                "Unknown target '{}': expected \"verilog\", \"sv\" or \"spinalhdl\"",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Target::Verilog => "Verilog",
+            Target::SystemVerilog => "SystemVerilog",
+            Target::SpinalHDL => "SpinalHDL",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Generated code for every entity in a VHDL source, with non-fatal diagnostics
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranspileOutput {
+    pub code: String,
+    /// Renamed identifiers and untranslated constructs, prefixed with the entity name
+    pub diagnostics: Vec<String>,
+}
+
+/// Parse every entity in a VHDL source
+pub fn parse_entities(vhdl: &str) -> Result<Vec<Entity>> {
+    let mut parser = ASTVHDLParser::new(vhdl.to_string())?;
+    parser.parse_entities().context("Failed to extract entities from VHDL")
+}
+
+/// Parse every entity in a VHDL file
+pub fn parse_entities_file(path: impl AsRef<Path>) -> Result<Vec<Entity>> {
+    let path = path.as_ref();
+    let vhdl = std::fs::read_to_string(path)
+        .context(format!("Failed to read VHDL file: {:?}", path))?;
+    parse_entities(&vhdl)
+}
+
+/// Transpile every entity in a VHDL source to the target language
+pub fn transpile_vhdl_str(vhdl: &str, target: Target) -> Result<TranspileOutput> {
+    let mut parser = ASTVHDLParser::new(vhdl.to_string())?;
+    let entities = parser.parse_entities().context("Failed to extract entities from VHDL")?;
+    if entities.is_empty() {
+        return Err(anyhow::anyhow!("No entities found in VHDL source"));
+    }
+
+    let mut diagnostics: Vec<String> = parser.case_normalizations().iter()
+        .map(|(entity, rename)| format!("{}: {}", entity, rename))
+        .collect();
+    let mut code = String::new();
+
+    for entity in &entities {
+        let (generated, notes): (String, Vec<String>) = match target {
+            Target::Verilog => VerilogGenerator::new().generate(entity)
+                .map(|(v, report)| (v, report.renames.iter().map(ToString::to_string).collect())),
+            Target::SystemVerilog => SystemVerilogGenerator::new().generate(entity)
+                .map(|(sv, report)| (sv, report.renames.iter().map(ToString::to_string).collect())),
+            Target::SpinalHDL => SpinalHDLGenerator::new().generate(entity)
+                .map(|(scala, report)| (scala, report.renames.iter().map(ToString::to_string)
+                    .chain(report.warnings.iter().map(ToString::to_string))
+                    .collect())),
+        }
+        .with_context(|| format!("Failed to generate {} for entity: {}", target, entity.name))?;
+
+        diagnostics.extend(notes.into_iter().map(|note| format!("{}: {}", entity.name, note)));
+        code.push_str(&generated);
+        code.push('\n');
+    }
+
+    Ok(TranspileOutput { code, diagnostics })
+}
+
+/// Transpile every entity in a VHDL file to the target language
+pub fn transpile_vhdl_file(path: impl AsRef<Path>, target: Target) -> Result<TranspileOutput> {
+    let path = path.as_ref();
+    let vhdl = std::fs::read_to_string(path)
+        .context(format!("Failed to read VHDL file: {:?}", path))?;
+    transpile_vhdl_str(&vhdl, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_names() {
+        assert_eq!("sv".parse::<Target>().unwrap(), Target::SystemVerilog);
+        assert_eq!("Verilog".parse::<Target>().unwrap(), Target::Verilog);
+        assert_eq!("spinalhdl".parse::<Target>().unwrap(), Target::SpinalHDL);
+        assert!("vhdl".parse::<Target>().unwrap_err().to_string().contains("Unknown target 'vhdl'"));
+    }
+
+    #[test]
+    fn test_transpile_str_collects_diagnostics() {
+        let vhdl = r#"
+        entity buf is
+            port(
+                A : in  std_logic;
+                y : out std_logic
+            );
+        end entity buf;
+
+        architecture rtl of buf is
+        begin
+            y <= a;
+        end architecture rtl;
+        "#;
+
+        let output = transpile_vhdl_str(vhdl, Target::SystemVerilog).unwrap();
+        assert!(output.code.contains("assign y = A;"));
+        assert_eq!(output.diagnostics, vec!["buf: a -> A (case normalized)"]);
+    }
+}
@@ -6,8 +6,6 @@
 use anyhow::Result;
 use clap::Parser;
 use rtl_transpiler::mcp::RTLTranspilerMCPServer;
-use tracing_subscriber;
-use rmcp::ServiceExt;
 
 #[derive(Parser)]
 #[command(name = "rtl-transpiler-mcp")]
@@ -21,6 +19,21 @@ struct Args {
     /// Enable verbose logging
     #[arg(short, long)]
     verbose: bool,
+
+    /// Expose the VHDL files below this folder as vhdl://<path>/entities and
+    /// vhdl://<path>/report resources
+    #[arg(long)]
+    root: Option<std::path::PathBuf>,
+
+    /// Folder the tools may read and write (repeatable); also taken from the
+    /// RTL_TRANSPILER_ALLOWED_FOLDERS path list. Without any, every path is allowed
+    #[arg(long = "allowed-folder", value_name = "DIR")]
+    allowed_folders: Vec<String>,
+
+    /// Listen for MCP clients on this TCP address (e.g. 127.0.0.1:7300) instead of
+    /// serving a single client on stdio
+    #[arg(long, value_name = "ADDR")]
+    tcp: Option<String>,
 }
 
 #[tokio::main]
@@ -41,8 +54,10 @@ async fn main() -> Result<()> {
         tracing::Level::WARN
     };
     
+    // Logs go to stderr: on stdio, stdout carries nothing but MCP messages
     tracing_subscriber::fmt()
         .with_max_level(log_level)
+        .with_writer(std::io::stderr)
         .with_target(false)
         .with_thread_ids(false)
         .with_file(false)
@@ -52,18 +67,42 @@ async fn main() -> Result<()> {
     tracing::info!("Starting RTL Transpiler MCP Server (rmcp)");
     
     // Create and run the MCP server - following the example_server.rs pattern
-    let server = RTLTranspilerMCPServer::new();
-    let service = server.serve(rmcp::transport::io::stdio()).await?;
+    let mut allowed_folders = args.allowed_folders.clone();
+    if let Some(paths) = std::env::var_os("RTL_TRANSPILER_ALLOWED_FOLDERS") {
+        allowed_folders.extend(std::env::split_paths(&paths).map(|path| path.display().to_string()));
+    }
+    if allowed_folders.is_empty() {
+        tracing::warn!("No --allowed-folder given: MCP clients can read and write any path");
+    }
+
+    let mut server = RTLTranspilerMCPServer::with_allowed_folders(allowed_folders);
+    if let Some(root) = &args.root {
+        server = server.with_resource_root(root);
+    }
+    let handle = match &args.tcp {
+        Some(addr) => server.serve_tcp(addr.as_str()).await?.1,
+        None => server.serve_stdio(),
+    };
     
     tracing::info!("MCP Server initialized with tools:");
     tracing::info!("  - transpile_vhdl_to_verilog: Convert VHDL entities to Verilog modules");
     tracing::info!("  - analyze_vhdl: Analyze VHDL files for entities, ports, signals, and processes");
+    tracing::info!("  - analyze_hierarchy: Trace the instantiation hierarchy below a top entity");
+    tracing::info!("  - lint_vhdl: Flag VHDL constructs that cannot be transpiled faithfully");
     tracing::info!("  - edit_file: Edit text files with search/replace functionality");
+    tracing::info!("  - find: Search directories for files by pattern, extension, depth and size");
     
-    tracing::info!("Server ready, listening on stdio...");
-    
-    // Run the server (this will block until the server shuts down)
-    service.waiting().await?;
+    if let Some(root) = &args.root {
+        tracing::info!("Serving VHDL resources below {}", root.display());
+    }
+
+    match &args.tcp {
+        Some(addr) => tracing::info!("Server ready, listening on {}...", addr),
+        None => tracing::info!("Server ready, listening on stdio..."),
+    }
+
+    // Run the server until the client disconnects or SIGINT/SIGTERM arrives
+    handle.await??;
     
     tracing::info!("MCP Server shutting down");
     Ok(())
@@ -1,5 +1,59 @@
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
+
+use crate::agent::AgentType;
+
+lazy_static! {
+    static ref ENV_VAR_RE: Regex =
+        Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(?::-([^}]*))?\}").unwrap();
+}
+
+/// Largest accepted `max_steps`
+pub const MAX_STEPS_LIMIT: u32 = 1000;
+
+/// Built-in presets selectable with `AgentConfig::from_profile`
+const PROFILES: &[(&str, &str)] = &[
+    ("transpile", include_str!("profiles/transpile.toml")),
+    ("debug", include_str!("profiles/debug.toml")),
+    ("analyze", include_str!("profiles/analyze.toml")),
+];
+
+/// All problems found while validating a configuration, each prefixed with its field path
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid configuration:\n{}", .problems.iter().map(|p| format!("  - {}", p)).collect::<Vec<_>>().join("\n"))]
+pub struct ConfigValidationError {
+    pub problems: Vec<String>,
+}
+
+/// Expand `${VAR}` and `${VAR:-default}` references using the process environment
+pub fn interpolate_env_vars(value: &str) -> Result<String> {
+    let mut missing = Vec::new();
+
+    let expanded = ENV_VAR_RE.replace_all(value, |caps: &regex::Captures| {
+        let name = &caps[1];
+        match (std::env::var(name), caps.get(2)) {
+            (Ok(val), _) => val,
+            (Err(_), Some(default)) => default.as_str().to_string(),
+            (Err(_), None) => {
+                missing.push(name.to_string());
+                String::new()
+            }
+        }
+    });
+
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Environment variable(s) not set: {}",
+            missing.join(", ")
+        ));
+    }
+
+    Ok(expanded.into_owned())
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentConfig {
@@ -9,8 +63,87 @@ pub struct AgentConfig {
     pub model_config: Option<ModelConfig>,
     pub allow_mcp_servers: Vec<String>,
     pub mcp_servers_config: Option<HashMap<String, MCPServerConfig>>,
+    /// Named model profiles (e.g. "planner", "fixer") selectable per step
+    #[serde(default)]
+    pub models: HashMap<String, ModelConfig>,
+    /// Profile from `models` used by default instead of `model_config`
+    #[serde(default)]
+    pub default_model: Option<String>,
+    #[serde(default)]
+    pub step_policy: Option<StepPolicy>,
+    /// How agent progress is reported: "interactive", "quiet" or "jsonl"
+    #[serde(default)]
+    pub console: ConsoleMode,
+    /// Describe tool calls instead of executing them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Stop the run once the estimated prompt cost would exceed this amount
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+    /// Estimated conversation size above which the oldest messages after the task
+    /// are dropped, or summarized with `use_summarization`
+    #[serde(default)]
+    pub max_context_tokens: Option<usize>,
+    /// Summarize old messages with the model instead of dropping them
+    #[serde(default)]
+    pub use_summarization: bool,
+    /// Pause the execution after this step so it can be inspected before resuming
+    #[serde(default)]
+    pub pause_after_step: Option<u32>,
+    /// Agent a profile is tuned for; callers may still pick another
+    #[serde(default)]
+    pub agent_type: Option<AgentType>,
+    /// Log at debug level, including every prompt sent to the model
+    #[serde(default)]
+    pub verbose: bool,
+    /// Run the tool calls of one response concurrently; calls sharing a `path` still run in order
+    #[serde(default)]
+    pub parallel_tool_execution: bool,
+    /// Which commands the `bash` tool may run, and with what environment
+    #[serde(default)]
+    pub bash_policy: BashPolicy,
+}
+
+/// Restrictions on commands run by the `bash` tool; the default allows everything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BashPolicy {
+    /// Command prefixes (e.g. "ghdl", "git status") every command in a pipeline or list
+    /// must start with; empty allows any command
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+    /// Text that must not appear anywhere in the command (e.g. "rm -rf", "sudo")
+    #[serde(default)]
+    pub denied_patterns: Vec<String>,
+    /// Start commands with an empty environment plus `env_allowlist`
+    #[serde(default)]
+    pub clear_env: bool,
+    /// Variables passed through when `clear_env` is set, e.g. "PATH" and "HOME"
+    #[serde(default)]
+    pub env_allowlist: Vec<String>,
+}
+
+/// Console implementation used when constructing an agent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleMode {
+    #[default]
+    Interactive,
+    Quiet,
+    Jsonl,
+}
+
+/// Rule for switching model profile during a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepPolicy {
+    /// Number of failed tool results after which `switch_to` serves the remaining steps
+    pub after_failed_tool_results: u32,
+    /// Profile name from `models`
+    pub switch_to: String,
 }
 
+/// Profile name reported for steps served by the plain `model_config`
+pub const DEFAULT_PROFILE_NAME: &str = "default";
+
 impl Default for AgentConfig {
     fn default() -> Self {
         Self {
@@ -23,6 +156,313 @@ impl Default for AgentConfig {
             model_config: None,
             allow_mcp_servers: vec![],
             mcp_servers_config: None,
+            models: HashMap::new(),
+            default_model: None,
+            step_policy: None,
+            console: ConsoleMode::default(),
+            dry_run: false,
+            max_cost_usd: None,
+            max_context_tokens: None,
+            use_summarization: false,
+            pause_after_step: None,
+            agent_type: None,
+            verbose: false,
+            parallel_tool_execution: false,
+            bash_policy: BashPolicy::default(),
+        }
+    }
+}
+
+impl AgentConfig {
+    /// Load a JSON configuration file, expanding environment variable references
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read config file: {}", path.display()))?;
+        Self::from_json_str(&content)
+            .context(format!("Failed to load config file: {}", path.display()))
+    }
+
+    /// Parse a JSON configuration, expanding environment variable references
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        let mut config: AgentConfig = serde_json::from_str(content)
+            .context("Failed to parse agent configuration")?;
+        config.interpolate_env()?;
+        config.check_max_steps()?;
+        Ok(config)
+    }
+
+    /// Load a TOML configuration file, expanding environment variable references
+    pub fn from_toml_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read config file: {}", path.display()))?;
+        Self::from_toml_str(&content)
+            .context(format!("Failed to load config file: {}", path.display()))
+    }
+
+    /// Parse a TOML configuration with the same field names as the JSON one
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let mut config: AgentConfig = toml::from_str(content)
+            .context("Failed to parse agent configuration")?;
+        config.interpolate_env()?;
+        config.check_max_steps()?;
+        Ok(config)
+    }
+
+    /// Override fields from `RTL_TRANSPILER_MAX_STEPS`, `RTL_TRANSPILER_MODEL` and
+    /// `RTL_TRANSPILER_API_KEY`; the model variables apply to the default model profile
+    pub fn from_env_overrides(&mut self) -> Result<()> {
+        if let Ok(max_steps) = std::env::var("RTL_TRANSPILER_MAX_STEPS") {
+            self.max_steps = max_steps.trim().parse()
+                .context(format!("RTL_TRANSPILER_MAX_STEPS: '{}' is not a number", max_steps))?;
+            self.check_max_steps().context("RTL_TRANSPILER_MAX_STEPS")?;
+        }
+
+        let model = std::env::var("RTL_TRANSPILER_MODEL").ok();
+        let api_key = std::env::var("RTL_TRANSPILER_API_KEY").ok();
+        if model.is_none() && api_key.is_none() {
+            return Ok(());
+        }
+
+        let profile = self.default_profile_name().to_string();
+        let model_config = match self.models.get_mut(&profile) {
+            Some(model_config) => model_config,
+            None => self.model_config.as_mut().context(
+                "RTL_TRANSPILER_MODEL/RTL_TRANSPILER_API_KEY: no model_config to override"
+            )?,
+        };
+
+        if let Some(model) = model {
+            model_config.model_name = model.clone();
+            model_config.model = model;
+        }
+        if let Some(api_key) = api_key {
+            model_config.model_provider
+                .as_mut()
+                .context("RTL_TRANSPILER_API_KEY: model_config has no model_provider")?
+                .api_key = Some(api_key);
+        }
+
+        Ok(())
+    }
+
+    fn check_max_steps(&self) -> Result<()> {
+        if self.max_steps == 0 || self.max_steps > MAX_STEPS_LIMIT {
+            return Err(anyhow::anyhow!(
+                "max_steps: must be between 1 and {}, got {}",
+                MAX_STEPS_LIMIT,
+                self.max_steps
+            ));
+        }
+        Ok(())
+    }
+
+    /// Built-in preset: "transpile", "debug" or "analyze"; layer a model config on top with `merge`
+    pub fn from_profile(name: &str) -> Result<Self> {
+        let (_, content) = PROFILES.iter()
+            .find(|(profile, _)| *profile == name)
+            .ok_or_else(|| anyhow::anyhow!(
+                "Unknown profile '{}': expected one of {}",
+                name,
+                PROFILES.iter().map(|(profile, _)| *profile).collect::<Vec<_>>().join(", ")
+            ))?;
+        toml::from_str(content).context(format!("Failed to parse built-in profile: {}", name))
+    }
+
+    /// Layer `overrides` on `base`; fields left at their default value in `overrides` keep the base value
+    pub fn merge(base: AgentConfig, overrides: AgentConfig) -> AgentConfig {
+        let defaults = AgentConfig::default();
+        let mut models = base.models;
+        models.extend(overrides.models);
+        let mcp_servers_config = match (base.mcp_servers_config, overrides.mcp_servers_config) {
+            (Some(mut servers), Some(extra)) => {
+                servers.extend(extra);
+                Some(servers)
+            }
+            (servers, extra) => extra.or(servers),
+        };
+
+        AgentConfig {
+            max_steps: if overrides.max_steps != defaults.max_steps { overrides.max_steps } else { base.max_steps },
+            tools: if overrides.tools != defaults.tools { overrides.tools } else { base.tools },
+            allowed_folders: if overrides.allowed_folders.is_empty() { base.allowed_folders } else { overrides.allowed_folders },
+            model_config: overrides.model_config.or(base.model_config),
+            allow_mcp_servers: if overrides.allow_mcp_servers.is_empty() { base.allow_mcp_servers } else { overrides.allow_mcp_servers },
+            mcp_servers_config,
+            models,
+            default_model: overrides.default_model.or(base.default_model),
+            step_policy: overrides.step_policy.or(base.step_policy),
+            console: if overrides.console != defaults.console { overrides.console } else { base.console },
+            dry_run: overrides.dry_run || base.dry_run,
+            max_cost_usd: overrides.max_cost_usd.or(base.max_cost_usd),
+            max_context_tokens: overrides.max_context_tokens.or(base.max_context_tokens),
+            use_summarization: overrides.use_summarization || base.use_summarization,
+            pause_after_step: overrides.pause_after_step.or(base.pause_after_step),
+            agent_type: overrides.agent_type.or(base.agent_type),
+            verbose: overrides.verbose || base.verbose,
+            parallel_tool_execution: overrides.parallel_tool_execution || base.parallel_tool_execution,
+            bash_policy: if overrides.bash_policy != defaults.bash_policy { overrides.bash_policy } else { base.bash_policy },
+        }
+    }
+
+    /// Expand `${VAR}` / `${VAR:-default}` in api_key, base_url, MCP env values and allowed_folders
+    pub fn interpolate_env(&mut self) -> Result<()> {
+        for (i, folder) in self.allowed_folders.iter_mut().enumerate() {
+            *folder = interpolate_env_vars(folder)
+                .context(format!("allowed_folders[{}]", i))?;
+        }
+
+        if let Some(model_config) = self.model_config.as_mut() {
+            Self::interpolate_model_config("model_config", model_config)?;
+        }
+
+        for (name, model_config) in self.models.iter_mut() {
+            Self::interpolate_model_config(&format!("models.{}", name), model_config)?;
+        }
+
+        if let Some(servers) = self.mcp_servers_config.as_mut() {
+            for (server_name, server) in servers.iter_mut() {
+                if let Some(env) = server.env.as_mut() {
+                    for (key, value) in env.iter_mut() {
+                        *value = interpolate_env_vars(value)
+                            .context(format!("mcp_servers_config.{}.env.{}", server_name, key))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn interpolate_model_config(path: &str, model_config: &mut ModelConfig) -> Result<()> {
+        if let Some(provider) = model_config.model_provider.as_mut() {
+            if let Some(api_key) = provider.api_key.as_mut() {
+                *api_key = interpolate_env_vars(api_key)
+                    .context(format!("{}.model_provider.api_key", path))?;
+            }
+            if let Some(base_url) = provider.base_url.as_mut() {
+                *base_url = interpolate_env_vars(base_url)
+                    .context(format!("{}.model_provider.base_url", path))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Name of the profile serving steps when no step policy applies
+    pub fn default_profile_name(&self) -> &str {
+        self.default_model.as_deref().unwrap_or(DEFAULT_PROFILE_NAME)
+    }
+
+    /// Model configuration for a profile; the default profile falls back to `model_config`
+    pub fn model_profile(&self, name: &str) -> Option<&ModelConfig> {
+        match self.models.get(name) {
+            Some(model_config) => Some(model_config),
+            None if name == self.default_profile_name() => self.model_config.as_ref(),
+            None => None,
+        }
+    }
+
+    /// Model configuration of the default profile
+    pub fn default_model_config(&self) -> Option<&ModelConfig> {
+        self.model_profile(self.default_profile_name())
+    }
+
+    /// Check the configuration for the given agent type, reporting every problem at once
+    pub fn validate(&self, agent_type: AgentType) -> std::result::Result<(), ConfigValidationError> {
+        let mut problems = Vec::new();
+
+        if let Err(err) = self.check_max_steps() {
+            problems.push(err.to_string());
+        }
+
+        match (&self.default_model, &self.model_config) {
+            (Some(name), _) if !self.models.contains_key(name) => {
+                problems.push(format!("default_model: profile '{}' is not defined in models", name))
+            }
+            (None, None) => problems.push(format!("model_config: required for {:?}", agent_type)),
+            _ => {}
+        }
+
+        if let Some(model_config) = &self.model_config {
+            Self::validate_model_config("model_config", model_config, &mut problems);
+        }
+
+        let mut profile_names: Vec<&String> = self.models.keys().collect();
+        profile_names.sort();
+        for name in profile_names {
+            Self::validate_model_config(&format!("models.{}", name), &self.models[name], &mut problems);
+        }
+
+        if let Some(policy) = &self.step_policy {
+            if self.model_profile(&policy.switch_to).is_none() {
+                problems.push(format!(
+                    "step_policy.switch_to: profile '{}' is not defined in models",
+                    policy.switch_to
+                ));
+            }
+            if policy.after_failed_tool_results == 0 {
+                problems.push("step_policy.after_failed_tool_results: must be greater than 0".to_string());
+            }
+        }
+
+        if let Some(limit) = self.max_cost_usd {
+            if limit <= 0.0 {
+                problems.push("max_cost_usd: must be greater than 0".to_string());
+            }
+        }
+
+        if self.max_context_tokens == Some(0) {
+            problems.push("max_context_tokens: must be greater than 0".to_string());
+        }
+
+        if self.tools.is_empty() {
+            problems.push("tools: at least one tool must be configured".to_string());
+        }
+
+        for (i, folder) in self.allowed_folders.iter().enumerate() {
+            if !Path::new(folder).is_dir() {
+                problems.push(format!("allowed_folders[{}]: '{}' does not exist", i, folder));
+            }
+        }
+
+        if agent_type == AgentType::AlanAgent {
+            for (i, server_name) in self.allow_mcp_servers.iter().enumerate() {
+                let configured = self
+                    .mcp_servers_config
+                    .as_ref()
+                    .map(|servers| servers.contains_key(server_name))
+                    .unwrap_or(false);
+                if !configured {
+                    problems.push(format!(
+                        "allow_mcp_servers[{}]: server '{}' has no entry in mcp_servers_config",
+                        i, server_name
+                    ));
+                }
+            }
+        }
+
+        if let Some(servers) = &self.mcp_servers_config {
+            let mut names: Vec<&String> = servers.keys().collect();
+            names.sort();
+            for name in names {
+                if servers[name].command.trim().is_empty() {
+                    problems.push(format!("mcp_servers_config.{}.command: must not be empty", name));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigValidationError { problems })
+        }
+    }
+
+    fn validate_model_config(path: &str, model_config: &ModelConfig, problems: &mut Vec<String>) {
+        if model_config.model_provider.is_none() {
+            problems.push(format!("{}.model_provider: required", path));
+        }
+        if model_config.model_name.trim().is_empty() {
+            problems.push(format!("{}.model_name: must not be empty", path));
         }
     }
 }
@@ -54,6 +494,12 @@ pub struct ModelProvider {
     pub provider: String,
     pub api_key: Option<String>,
     pub base_url: Option<String>,
+    /// Azure OpenAI resource, i.e. `<resource>` in `https://<resource>.openai.azure.com`
+    #[serde(default)]
+    pub azure_resource_name: Option<String>,
+    /// Azure OpenAI `api-version` query parameter (defaults to 2024-02-01)
+    #[serde(default)]
+    pub azure_api_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,4 +507,276 @@ pub struct MCPServerConfig {
     pub command: String,
     pub args: Vec<String>,
     pub env: Option<HashMap<String, String>>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model_config() -> ModelConfig {
+        ModelConfig {
+            model_provider: Some(ModelProvider {
+                provider: "mock".to_string(),
+                api_key: None,
+                base_url: None,
+                azure_resource_name: None,
+                azure_api_version: None,
+            }),
+            model_name: "mock".to_string(),
+            model: "mock".to_string(),
+            temperature: 0.0,
+            max_tokens: None,
+            top_p: None,
+            stop_sequences: None,
+            max_retries: 1,
+        }
+    }
+
+    #[test]
+    fn test_interpolation_with_variable_set() {
+        std::env::set_var("RTL_TRANSPILER_TEST_API_KEY", "secret-key");
+
+        let config = AgentConfig::from_json_str(r#"{
+            "max_steps": 10,
+            "tools": ["task_done"],
+            "allowed_folders": [],
+            "model_config": {
+                "model_provider": {
+                    "provider": "openai",
+                    "api_key": "${RTL_TRANSPILER_TEST_API_KEY}",
+                    "base_url": "https://${RTL_TRANSPILER_TEST_UNSET_HOST:-api.example.com}/v1"
+                },
+                "model_name": "gpt",
+                "model": "gpt",
+                "temperature": 0.0,
+                "max_tokens": null,
+                "top_p": null,
+                "stop_sequences": null,
+                "max_retries": 1
+            },
+            "allow_mcp_servers": [],
+            "mcp_servers_config": null
+        }"#).unwrap();
+
+        let provider = config.model_config.unwrap().model_provider.unwrap();
+        assert_eq!(provider.api_key.as_deref(), Some("secret-key"));
+        assert_eq!(provider.base_url.as_deref(), Some("https://api.example.com/v1"));
+    }
+
+    #[test]
+    fn test_interpolation_without_variable_set() {
+        assert_eq!(
+            interpolate_env_vars("${RTL_TRANSPILER_TEST_MISSING:-/tmp/default}").unwrap(),
+            "/tmp/default"
+        );
+
+        let err = interpolate_env_vars("${RTL_TRANSPILER_TEST_MISSING}/rtl").unwrap_err();
+        assert!(err.to_string().contains("RTL_TRANSPILER_TEST_MISSING"));
+
+        let mut env = HashMap::new();
+        env.insert("TOKEN".to_string(), "${RTL_TRANSPILER_TEST_MISSING}".to_string());
+        let mut servers = HashMap::new();
+        servers.insert("kb".to_string(), MCPServerConfig {
+            command: "kb-server".to_string(),
+            args: vec![],
+            env: Some(env),
+        });
+        let mut config = AgentConfig {
+            mcp_servers_config: Some(servers),
+            ..Default::default()
+        };
+
+        let err = config.interpolate_env().unwrap_err();
+        assert!(format!("{:#}", err).contains("mcp_servers_config.kb.env.TOKEN"));
+    }
+
+    #[test]
+    fn test_toml_parsing() {
+        let config = AgentConfig::from_toml_str(r#"
+            max_steps = 30
+            tools = ["transpile_vhdl_to_verilog", "task_done"]
+            allowed_folders = []
+            allow_mcp_servers = []
+            console = "quiet"
+
+            [model_config]
+            model_name = "gpt"
+            model = "gpt"
+            temperature = 0.2
+            max_retries = 3
+
+            [model_config.model_provider]
+            provider = "openai"
+            api_key = "${RTL_TRANSPILER_TEST_TOML_KEY:-toml-key}"
+
+            [mcp_servers_config.kb]
+            command = "kb-server"
+            args = ["--stdio"]
+        "#).unwrap();
+
+        assert_eq!(config.max_steps, 30);
+        assert_eq!(config.console, ConsoleMode::Quiet);
+        let model_config = config.model_config.as_ref().unwrap();
+        assert_eq!(model_config.max_retries, 3);
+        assert_eq!(model_config.model_provider.as_ref().unwrap().api_key.as_deref(), Some("toml-key"));
+        assert_eq!(config.mcp_servers_config.unwrap()["kb"].args, vec!["--stdio"]);
+
+        let err = AgentConfig::from_toml_str(r#"
+            max_steps = 5000
+            tools = ["task_done"]
+            allowed_folders = []
+            allow_mcp_servers = []
+        "#).unwrap_err();
+        assert_eq!(err.to_string(), "max_steps: must be between 1 and 1000, got 5000");
+    }
+
+    #[test]
+    fn test_bash_policy_parsing_and_merge() {
+        let config = AgentConfig::from_toml_str(r#"
+            max_steps = 30
+            tools = ["bash", "task_done"]
+            allowed_folders = []
+            allow_mcp_servers = []
+
+            [bash_policy]
+            allowed_commands = ["ghdl", "iverilog", "make"]
+            denied_patterns = ["rm -rf", "sudo"]
+            clear_env = true
+            env_allowlist = ["PATH"]
+        "#).unwrap();
+
+        assert_eq!(config.bash_policy.allowed_commands, vec!["ghdl", "iverilog", "make"]);
+        assert_eq!(config.bash_policy.denied_patterns, vec!["rm -rf", "sudo"]);
+        assert!(config.bash_policy.clear_env);
+        assert_eq!(config.bash_policy.env_allowlist, vec!["PATH"]);
+
+        // A profile without a policy keeps the base one
+        let merged = AgentConfig::merge(config.clone(), AgentConfig::default());
+        assert_eq!(merged.bash_policy, config.bash_policy);
+        assert_eq!(AgentConfig::default().bash_policy, BashPolicy::default());
+    }
+
+    #[test]
+    fn test_env_overrides_take_precedence() {
+        let mut config = AgentConfig::from_toml_str(r#"
+            max_steps = 30
+            tools = ["task_done"]
+            allowed_folders = []
+            allow_mcp_servers = []
+
+            [model_config]
+            model_name = "gpt"
+            model = "gpt"
+            temperature = 0.0
+            max_retries = 1
+
+            [model_config.model_provider]
+            provider = "openai"
+            api_key = "file-key"
+        "#).unwrap();
+
+        std::env::set_var("RTL_TRANSPILER_MAX_STEPS", "12");
+        std::env::set_var("RTL_TRANSPILER_MODEL", "gpt-env");
+        std::env::set_var("RTL_TRANSPILER_API_KEY", "env-key");
+        let result = config.from_env_overrides();
+        std::env::set_var("RTL_TRANSPILER_MAX_STEPS", "0");
+        let out_of_range = config.clone().from_env_overrides();
+        std::env::remove_var("RTL_TRANSPILER_MAX_STEPS");
+        std::env::remove_var("RTL_TRANSPILER_MODEL");
+        std::env::remove_var("RTL_TRANSPILER_API_KEY");
+
+        result.unwrap();
+        assert_eq!(config.max_steps, 12);
+        let model_config = config.model_config.unwrap();
+        assert_eq!(model_config.model_name, "gpt-env");
+        assert_eq!(model_config.model_provider.unwrap().api_key.as_deref(), Some("env-key"));
+        assert!(format!("{:#}", out_of_range.unwrap_err()).contains("max_steps: must be between 1 and 1000"));
+    }
+
+    #[test]
+    fn test_validate_reports_all_problems() {
+        let config = AgentConfig {
+            max_steps: 0,
+            tools: vec![],
+            allowed_folders: vec!["/definitely/not/a/real/folder".to_string()],
+            model_config: None,
+            allow_mcp_servers: vec!["kb".to_string()],
+            mcp_servers_config: None,
+            ..Default::default()
+        };
+
+        let err = config.validate(AgentType::AlanAgent).unwrap_err();
+        assert_eq!(err.problems.len(), 5);
+
+        let message = err.to_string();
+        assert!(message.contains("max_steps:"));
+        assert!(message.contains("model_config: required"));
+        assert!(message.contains("tools:"));
+        assert!(message.contains("allowed_folders[0]:"));
+        assert!(message.contains("allow_mcp_servers[0]:"));
+    }
+
+    #[test]
+    fn test_validate_accepts_complete_config() {
+        let config = AgentConfig {
+            model_config: Some(model_config()),
+            ..Default::default()
+        };
+
+        assert!(config.validate(AgentType::TranspilerAgent).is_ok());
+    }
+
+    #[test]
+    fn test_from_profile_and_merge() {
+        let transpile = AgentConfig::from_profile("transpile").unwrap();
+        assert_eq!(transpile.agent_type, Some(AgentType::TranspilerAgent));
+        assert_eq!(transpile.max_steps, 20);
+        assert!(!transpile.verbose);
+        assert!(AgentConfig::from_profile("debug").unwrap().verbose);
+        assert_eq!(
+            AgentConfig::from_profile("analyze").unwrap().tools,
+            vec!["analyze_vhdl", "sequential_thinking", "task_done"]
+        );
+        assert!(AgentConfig::from_profile("fast").unwrap_err().to_string()
+            .contains("Unknown profile 'fast': expected one of transpile, debug, analyze"));
+
+        let merged = AgentConfig::merge(transpile, AgentConfig {
+            max_steps: 5,
+            model_config: Some(model_config()),
+            ..Default::default()
+        });
+        assert_eq!(merged.max_steps, 5);
+        assert_eq!(merged.tools, vec!["transpile_vhdl_to_verilog", "str_replace_edit", "bash", "task_done"]);
+        assert_eq!(merged.agent_type, Some(AgentType::TranspilerAgent));
+        assert!(merged.validate(AgentType::TranspilerAgent).is_ok());
+    }
+
+    #[test]
+    fn test_model_profiles_resolution_and_validation() {
+        let mut models = HashMap::new();
+        models.insert("planner".to_string(), ModelConfig {
+            temperature: 0.9,
+            ..model_config()
+        });
+        let mut config = AgentConfig {
+            models,
+            default_model: Some("planner".to_string()),
+            step_policy: Some(StepPolicy {
+                after_failed_tool_results: 2,
+                switch_to: "fixer".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(config.default_model_config().unwrap().temperature, 0.9);
+        let err = config.validate(AgentType::TranspilerAgent).unwrap_err();
+        assert_eq!(err.problems, vec!["step_policy.switch_to: profile 'fixer' is not defined in models"]);
+
+        config.models.insert("fixer".to_string(), model_config());
+        assert!(config.validate(AgentType::TranspilerAgent).is_ok());
+
+        config.default_model = Some("missing".to_string());
+        let err = config.validate(AgentType::TranspilerAgent).unwrap_err();
+        assert!(err.problems[0].starts_with("default_model:"));
+    }
+}
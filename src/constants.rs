@@ -4,6 +4,15 @@ pub const TOOL_STR_REPLACE_EDIT: &str = "str_replace_edit";
 pub const TOOL_SEQUENTIAL_THINKING: &str = "sequential_thinking";
 pub const TOOL_TASK_DONE: &str = "task_done";
 pub const TOOL_TRANSPILE: &str = "transpile_vhdl_to_verilog";
+pub const TOOL_FIND: &str = "find";
+pub const TOOL_FILE_OPS: &str = "file_ops";
+pub const TOOL_ANALYZE: &str = "analyze_vhdl";
+pub const TOOL_COCOTB: &str = "generate_cocotb_test";
+pub const TOOL_LINT: &str = "lint_vhdl";
+pub const TOOL_CONSTRAINTS: &str = "generate_constraints";
+pub const TOOL_COMPARE_INTERFACES: &str = "compare_interfaces";
+pub const TOOL_TRACE_SIGNAL: &str = "trace_signal";
+pub const TOOL_GIT: &str = "git";
 
 // File size limits
 pub const MAX_FILE_SIZE_BYTES: usize = 10 * 1024 * 1024; // 10 MB
\ No newline at end of file
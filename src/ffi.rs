@@ -0,0 +1,194 @@
+//! C ABI for EDA tool plugins, enabled with the `ffi` feature. The header is generated by
+//! cbindgen into `include/rtl_transpiler.h`.
+//!
+//! Strings returned by this module, including `error_out` messages, are owned by the caller
+//! and must be released with `rtlt_free_string`.
+
+use anyhow::{Context, Result};
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::api::{self, Target};
+
+pub const RTLT_OK: c_int = 0;
+/// A required pointer was null or not valid UTF-8, or the target is unknown
+pub const RTLT_ERR_INVALID_ARGUMENT: c_int = 1;
+/// Parsing, generation or file I/O failed
+pub const RTLT_ERR_TRANSPILE: c_int = 2;
+/// The library panicked; the panic was caught at the boundary
+pub const RTLT_ERR_PANIC: c_int = 3;
+
+/// Failure carrying the error code reported across the boundary
+struct FfiError {
+    code: c_int,
+    message: String,
+}
+
+impl FfiError {
+    fn invalid_argument(err: anyhow::Error) -> Self {
+        Self { code: RTLT_ERR_INVALID_ARGUMENT, message: format!("{:#}", err) }
+    }
+
+    fn transpile(err: anyhow::Error) -> Self {
+        Self { code: RTLT_ERR_TRANSPILE, message: format!("{:#}", err) }
+    }
+}
+
+/// Run `f`, converting errors and panics into an error code and an optional `error_out` message
+fn ffi_boundary<T>(error_out: *mut *mut c_char, f: impl FnOnce() -> Result<T, FfiError>) -> Result<T, c_int> {
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(FfiError { code: RTLT_ERR_PANIC, message: format!("Panic in rtl_transpiler: {}", message) })
+    });
+
+    let message = result.as_ref().err().map(|err| err.message.as_str());
+    if !error_out.is_null() {
+        // SAFETY: a non-null error_out must point to writable storage for one pointer
+        unsafe { *error_out = message.map_or(ptr::null_mut(), into_c_string) };
+    }
+    result.map_err(|err| err.code)
+}
+
+fn into_c_string(s: &str) -> *mut c_char {
+    // Interior NULs would truncate the string on the C side; replace them instead
+    CString::new(s.replace('\0', " ")).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Borrow a required C string argument
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::invalid_argument(anyhow::anyhow!("'{}' must not be null", name)));
+    }
+    CStr::from_ptr(ptr).to_str()
+        .context(format!("'{}' is not valid UTF-8", name))
+        .map_err(FfiError::invalid_argument)
+}
+
+/// Transpile every entity in `in_path` to `target` ("verilog", "sv" or "spinalhdl") and write
+/// the result to `out_path`. Returns `RTLT_OK` or an `RTLT_ERR_*` code.
+///
+/// # Safety
+/// `in_path`, `out_path` and `target` must be null or NUL-terminated strings. `error_out`
+/// must be null or point to writable storage; it receives null on success and otherwise an
+/// error message the caller frees with `rtlt_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn rtlt_transpile_file(
+    in_path: *const c_char,
+    out_path: *const c_char,
+    target: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    let result = ffi_boundary(error_out, || {
+        let in_path = str_arg(in_path, "in_path")?;
+        let out_path = str_arg(out_path, "out_path")?;
+        let target: Target = str_arg(target, "target")?.parse().map_err(FfiError::invalid_argument)?;
+
+        let output = api::transpile_vhdl_file(in_path, target).map_err(FfiError::transpile)?;
+        std::fs::write(out_path, output.code)
+            .context(format!("Failed to write {} to: {}", target, out_path))
+            .map_err(FfiError::transpile)
+    });
+
+    match result {
+        Ok(()) => RTLT_OK,
+        Err(code) => code,
+    }
+}
+
+/// Parse every entity in `in_path` and return them as a JSON array, or null on failure.
+///
+/// # Safety
+/// `in_path` must be null or a NUL-terminated string. `error_out` follows the same rules as
+/// in `rtlt_transpile_file`. The returned string must be freed with `rtlt_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn rtlt_analyze_file(in_path: *const c_char, error_out: *mut *mut c_char) -> *mut c_char {
+    let result = ffi_boundary(error_out, || {
+        let in_path = str_arg(in_path, "in_path")?;
+        let entities = api::parse_entities_file(in_path).map_err(FfiError::transpile)?;
+        serde_json::to_string(&entities)
+            .context("Failed to serialize entities")
+            .map_err(FfiError::transpile)
+    });
+
+    result.map_or(ptr::null_mut(), |json| into_c_string(&json))
+}
+
+/// Release a string returned by this library; null is ignored.
+///
+/// # Safety
+/// `s` must be null or a pointer returned by this library that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rtlt_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Take ownership of a returned string through the public free function
+    unsafe fn take_string(s: *mut c_char) -> String {
+        let owned = CStr::from_ptr(s).to_string_lossy().into_owned();
+        rtlt_free_string(s);
+        owned
+    }
+
+    #[test]
+    fn test_transpile_file_error_paths() {
+        let in_path = CString::new("/definitely/not/a/real/file.vhd").unwrap();
+        let out_path = CString::new("/tmp/out.sv").unwrap();
+        let target = CString::new("sv").unwrap();
+        let bad_target = CString::new("vhdl").unwrap();
+        let mut error: *mut c_char = ptr::null_mut();
+
+        unsafe {
+            let code = rtlt_transpile_file(ptr::null(), out_path.as_ptr(), target.as_ptr(), &mut error);
+            assert_eq!(code, RTLT_ERR_INVALID_ARGUMENT);
+            assert_eq!(take_string(error), "'in_path' must not be null");
+
+            let code = rtlt_transpile_file(in_path.as_ptr(), out_path.as_ptr(), bad_target.as_ptr(), &mut error);
+            assert_eq!(code, RTLT_ERR_INVALID_ARGUMENT);
+            assert!(take_string(error).starts_with("Unknown target 'vhdl'"));
+
+            let code = rtlt_transpile_file(in_path.as_ptr(), out_path.as_ptr(), target.as_ptr(), &mut error);
+            assert_eq!(code, RTLT_ERR_TRANSPILE);
+            assert!(take_string(error).starts_with("Failed to read VHDL file"));
+
+            // error_out is optional
+            let code = rtlt_transpile_file(in_path.as_ptr(), out_path.as_ptr(), target.as_ptr(), ptr::null_mut());
+            assert_eq!(code, RTLT_ERR_TRANSPILE);
+        }
+    }
+
+    #[test]
+    fn test_analyze_file_returns_null_with_error() {
+        let in_path = CString::new("/definitely/not/a/real/file.vhd").unwrap();
+        let mut error: *mut c_char = ptr::null_mut();
+
+        unsafe {
+            let json = rtlt_analyze_file(in_path.as_ptr(), &mut error);
+            assert!(json.is_null());
+            assert!(take_string(error).contains("file.vhd"));
+
+            rtlt_free_string(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_panics_are_caught_at_boundary() {
+        let mut error: *mut c_char = ptr::null_mut();
+        let result: Result<(), c_int> = ffi_boundary(&mut error, || panic!("index out of range"));
+
+        assert_eq!(result, Err(RTLT_ERR_PANIC));
+        assert_eq!(unsafe { take_string(error) }, "Panic in rtl_transpiler: index out of range");
+
+        let result = ffi_boundary(&mut error, || Ok(7));
+        assert_eq!(result, Ok(7));
+        assert!(error.is_null());
+    }
+}
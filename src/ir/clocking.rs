@@ -0,0 +1,341 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use crate::ir::{Entity, Process};
+
+lazy_static! {
+    static ref EDGE_RE: Regex = Regex::new(r"(?i)\b(rising_edge|falling_edge)\s*\(\s*(\w+)\s*\)").unwrap();
+    static ref EVENT_RE: Regex = Regex::new(r"(?i)\b(\w+)\s*'\s*event\s+and\s+(\w+)\s*=\s*'([01])'").unwrap();
+    static ref RESET_COND_RE: Regex =
+        Regex::new(r"(?i)^(?:if|elsif)\s*\(?\s*(\w+)\s*=\s*'([01])'\s*\)?\s*then$").unwrap();
+    static ref RESET_NAME_RE: Regex = Regex::new(r"(?i)(rst|reset|clr|clear)").unwrap();
+    static ref ASSIGN_TARGET_RE: Regex = Regex::new(r"^(\w+)\s*(?:\([^;]*?\))?\s*<=").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClockEdge {
+    Rising,
+    Falling,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResetStyle {
+    Async,
+    Sync,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResetPolarity {
+    ActiveHigh,
+    ActiveLow,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResetInfo {
+    pub signal: String,
+    pub polarity: ResetPolarity,
+    pub style: ResetStyle,
+}
+
+/// Clock and reset inferred for one process; `clock` is `None` for combinational processes
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProcessClocking {
+    pub process: String,
+    pub clock: Option<String>,
+    pub edge: Option<ClockEdge>,
+    pub reset: Option<ResetInfo>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntityClocking {
+    pub entity: String,
+    pub processes: Vec<ProcessClocking>,
+}
+
+impl ProcessClocking {
+    pub fn analyze(process: &Process, label: &str) -> Self {
+        let lines: Vec<&str> = process.body.lines()
+            .map(|line| line.split("--").next().unwrap_or("").trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let edges: Vec<(ClockEdge, String)> = lines.iter().flat_map(|line| line_edges(line)).collect();
+        let mut warnings = Vec::new();
+        if process.sensitivity_list.is_empty() {
+            warnings.push("no sensitivity list".to_string());
+        }
+
+        let Some((edge, clock)) = edges.first().cloned() else {
+            return Self { process: label.to_string(), clock: None, edge: None, reset: None, warnings };
+        };
+
+        let mut other_clocks: Vec<&str> = edges.iter()
+            .map(|(_, signal)| signal.as_str())
+            .filter(|signal| !signal.eq_ignore_ascii_case(&clock))
+            .collect();
+        other_clocks.dedup();
+        if !other_clocks.is_empty() {
+            warnings.push(format!("multiple clocks: {}, {}", clock, other_clocks.join(", ")));
+        }
+        if edges.iter().any(|(other, signal)| *other != edge && signal.eq_ignore_ascii_case(&clock)) {
+            warnings.push(format!("mixed rising and falling edges on {}", clock));
+        }
+
+        let reset = async_reset(&lines).or_else(|| sync_reset(&lines));
+        if let Some(reset) = &reset {
+            let listed = process.sensitivity_list.iter().any(|s| s.eq_ignore_ascii_case(&reset.signal));
+            if reset.style == ResetStyle::Async && !listed && !process.sensitivity_list.is_empty() && !process.senses_all() {
+                warnings.push(format!("async reset {} missing from sensitivity list", reset.signal));
+            }
+        }
+
+        Self { process: label.to_string(), clock: Some(clock), edge: Some(edge), reset, warnings }
+    }
+
+    /// One-line description, e.g. "clock clk (rising), async reset rst_n (active low)"
+    pub fn summary(&self) -> String {
+        let (Some(clock), Some(edge)) = (&self.clock, self.edge) else {
+            return "combinational".to_string();
+        };
+        let edge = match edge {
+            ClockEdge::Rising => "rising",
+            ClockEdge::Falling => "falling",
+        };
+        let reset = match &self.reset {
+            Some(reset) => format!(
+                "{} reset {} ({})",
+                match reset.style {
+                    ResetStyle::Async => "async",
+                    ResetStyle::Sync => "sync",
+                },
+                reset.signal,
+                match reset.polarity {
+                    ResetPolarity::ActiveHigh => "active high",
+                    ResetPolarity::ActiveLow => "active low",
+                }
+            ),
+            None => "no reset".to_string(),
+        };
+        format!("clock {} ({}), {}", clock, edge, reset)
+    }
+}
+
+/// Clocking of every process in the entity's architecture, unlabeled ones named `process_<i>`
+pub fn analyze_clocking(entity: &Entity) -> EntityClocking {
+    let processes = entity.architecture.iter()
+        .flat_map(|arch| arch.processes.iter().enumerate())
+        .map(|(i, process)| {
+            let label = process.label.clone().unwrap_or_else(|| format!("process_{}", i));
+            ProcessClocking::analyze(process, &label)
+        })
+        .collect();
+    EntityClocking { entity: entity.name.clone(), processes }
+}
+
+/// Signals a combinational process leaves unassigned on some path through its
+/// `if`/`case` statements, which synthesis turns into latches. A signal given a default
+/// at the top of the enclosing branch is complete; clocked processes have none
+pub fn inferred_latches(process: &Process) -> Vec<String> {
+    #[derive(Default)]
+    struct Branch {
+        assigned: BTreeSet<String>,
+        incomplete: BTreeSet<String>,
+    }
+    struct Block {
+        branches: Vec<Branch>,
+        exhaustive: bool,
+    }
+
+    let lines: Vec<&str> = process.body.lines()
+        .map(|line| line.split("--").next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.iter().any(|line| is_edge_line(line)) {
+        return Vec::new();
+    }
+
+    let mut top = Branch::default();
+    let mut blocks: Vec<Block> = Vec::new();
+    for line in lines {
+        let lower = line.to_lowercase();
+        let mut statement = line;
+        if lower.starts_with("end if") || lower.starts_with("end case") {
+            let Some(block) = blocks.pop() else { continue };
+            let parent = blocks.last_mut().and_then(|b| b.branches.last_mut()).unwrap_or(&mut top);
+            let assigned_everywhere = block.branches.iter()
+                .map(|branch| &branch.assigned)
+                .fold(None, |all: Option<BTreeSet<String>>, assigned| Some(match all {
+                    Some(all) => all.intersection(assigned).cloned().collect(),
+                    None => assigned.clone(),
+                }))
+                .filter(|_| block.exhaustive)
+                .unwrap_or_default();
+            for branch in block.branches {
+                parent.incomplete.extend(branch.incomplete.difference(&branch.assigned).cloned());
+                parent.incomplete.extend(branch.assigned.difference(&assigned_everywhere).cloned());
+            }
+            parent.assigned.extend(assigned_everywhere);
+            continue;
+        } else if lower.starts_with("if ") || lower.starts_with("if(") {
+            blocks.push(Block { branches: vec![Branch::default()], exhaustive: false });
+            continue;
+        } else if lower.starts_with("case ") || lower.starts_with("case(") {
+            blocks.push(Block { branches: Vec::new(), exhaustive: false });
+            continue;
+        } else if lower.starts_with("elsif") || lower == "else" {
+            if let Some(block) = blocks.last_mut() {
+                block.branches.push(Branch::default());
+                block.exhaustive |= lower == "else";
+            }
+            continue;
+        } else if lower.starts_with("when ") {
+            if let Some(block) = blocks.last_mut() {
+                block.branches.push(Branch::default());
+                block.exhaustive |= lower.starts_with("when others");
+            }
+            match line.find("=>") {
+                Some(arrow) => statement = line[arrow + 2..].trim(),
+                None => continue,
+            }
+        }
+
+        let branch = blocks.last_mut().and_then(|b| b.branches.last_mut()).unwrap_or(&mut top);
+        for statement in statement.split(';') {
+            if let Some(caps) = ASSIGN_TARGET_RE.captures(statement.trim()) {
+                branch.assigned.insert(caps[1].to_lowercase());
+            }
+        }
+    }
+
+    top.incomplete.difference(&top.assigned).cloned().collect()
+}
+
+fn line_edges(line: &str) -> Vec<(ClockEdge, String)> {
+    let mut edges: Vec<(ClockEdge, String)> = EDGE_RE.captures_iter(line)
+        .map(|caps| {
+            let edge = if caps[1].eq_ignore_ascii_case("rising_edge") { ClockEdge::Rising } else { ClockEdge::Falling };
+            (edge, caps[2].to_string())
+        })
+        .collect();
+    edges.extend(EVENT_RE.captures_iter(line)
+        .filter(|caps| caps[1].eq_ignore_ascii_case(&caps[2]))
+        .map(|caps| {
+            let edge = if &caps[3] == "1" { ClockEdge::Rising } else { ClockEdge::Falling };
+            (edge, caps[1].to_string())
+        }));
+    edges
+}
+
+fn is_edge_line(line: &str) -> bool {
+    !line_edges(line).is_empty()
+}
+
+fn reset_condition(line: &str, style: ResetStyle) -> Option<ResetInfo> {
+    let caps = RESET_COND_RE.captures(line)?;
+    let polarity = if &caps[2] == "0" { ResetPolarity::ActiveLow } else { ResetPolarity::ActiveHigh };
+    Some(ResetInfo { signal: caps[1].to_string(), polarity, style })
+}
+
+/// `if rst = '1' then ... elsif rising_edge(clk) then` at the top of the body
+fn async_reset(lines: &[&str]) -> Option<ResetInfo> {
+    let first = lines.first()?;
+    if !first.to_lowercase().starts_with("if") {
+        return None;
+    }
+    let reset = reset_condition(first, ResetStyle::Async)?;
+    let edge_branch = lines.iter().skip(1)
+        .any(|line| line.to_lowercase().starts_with("elsif") && is_edge_line(line));
+    edge_branch.then_some(reset)
+}
+
+/// `if rising_edge(clk) then` directly followed by `if rst = '1' then`
+fn sync_reset(lines: &[&str]) -> Option<ResetInfo> {
+    let edge_line = lines.iter().position(|line| is_edge_line(line))?;
+    let next = lines.get(edge_line + 1)?;
+    if !next.to_lowercase().starts_with("if") {
+        return None;
+    }
+    reset_condition(next, ResetStyle::Sync).filter(|reset| RESET_NAME_RE.is_match(&reset.signal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(label: &str, sensitivity: &[&str], body: &str) -> Process {
+        Process {
+            label: Some(label.to_string()),
+            sensitivity_list: sensitivity.iter().map(|s| s.to_string()).collect(),
+            body: body.to_string(),
+            source_line: None,
+            testbench_style: false,
+        }
+    }
+
+    #[test]
+    fn test_async_sync_and_combinational_processes() {
+        let async_reset = process("p_async", &["clk", "rst_n"],
+            "if rst_n = '0' then\n    q <= '0';\nelsif rising_edge(clk) then\n    q <= d;\nend if;");
+        let clocking = ProcessClocking::analyze(&async_reset, "p_async");
+        assert_eq!(clocking.clock.as_deref(), Some("clk"));
+        assert_eq!(clocking.edge, Some(ClockEdge::Rising));
+        assert_eq!(clocking.reset, Some(ResetInfo {
+            signal: "rst_n".to_string(),
+            polarity: ResetPolarity::ActiveLow,
+            style: ResetStyle::Async,
+        }));
+        assert!(clocking.warnings.is_empty());
+        assert_eq!(clocking.summary(), "clock clk (rising), async reset rst_n (active low)");
+
+        let sync_reset = process("p_sync", &["clk"],
+            "if clk'event and clk = '0' then -- falling edge\n    if srst = '1' then\n        q <= '0';\n    else\n        q <= d;\n    end if;\nend if;");
+        let clocking = ProcessClocking::analyze(&sync_reset, "p_sync");
+        assert_eq!(clocking.summary(), "clock clk (falling), sync reset srst (active high)");
+
+        let comb = process("p_comb", &[], "if sel = '1' then\n    y <= a;\nelse\n    y <= b;\nend if;");
+        let clocking = ProcessClocking::analyze(&comb, "p_comb");
+        assert_eq!(clocking.summary(), "combinational");
+        assert_eq!(clocking.warnings, vec!["no sensitivity list"]);
+        assert_eq!(serde_json::to_value(&clocking).unwrap(), serde_json::json!({
+            "process": "p_comb",
+            "clock": null,
+            "edge": null,
+            "reset": null,
+            "warnings": ["no sensitivity list"]
+        }));
+    }
+
+    #[test]
+    fn test_flags_mixed_edges_and_missing_reset() {
+        let mixed = process("p_ddr", &["clk"],
+            "if rst = '1' then\n    q <= '0';\nelsif rising_edge(clk) then\n    q <= d0;\nend if;\nif falling_edge(clk) then\n    r <= d1;\nend if;");
+        let clocking = ProcessClocking::analyze(&mixed, "p_ddr");
+        assert_eq!(clocking.warnings, vec![
+            "mixed rising and falling edges on clk",
+            "async reset rst missing from sensitivity list",
+        ]);
+    }
+
+    #[test]
+    fn test_inferred_latches() {
+        let latches = |body: &str| inferred_latches(&process("p", &["en", "d"], body));
+
+        assert_eq!(latches("if en = '1' then\n    q <= d;\nend if;"), vec!["q"]);
+        assert!(latches("if en = '1' then\n    q <= d;\nelse\n    q <= '0';\nend if;").is_empty());
+        assert!(latches("q <= '0'; -- default\nif en = '1' then\n    q <= d;\nend if;").is_empty());
+        assert!(latches("if rising_edge(clk) then\n    q <= d;\nend if;").is_empty());
+
+        let case = "case sel is\n    when \"00\" => y <= a; z <= a;\n    when \"01\" =>\n        y <= b;\nend case;";
+        assert_eq!(latches(case), vec!["y", "z"]);
+        assert_eq!(latches(&case.replace("end case;", "    when others => y <= '0';\nend case;")), vec!["z"]);
+
+        let nested = "if a = '1' then\n    if b = '1' then\n        y <= '1';\n    end if;\nelse\n    y <= '0';\nend if;";
+        assert_eq!(latches(nested), vec!["y"]);
+    }
+}
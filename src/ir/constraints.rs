@@ -0,0 +1,251 @@
+//! Timing constraints (SDC, Xilinx XDC) for the module generated from an entity
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::ir::hdl_body::{Dialect, SystemVerilogDialect};
+use crate::ir::sanitize::sanitize_entity;
+use crate::ir::{analyze_clocking, Entity, Port, PortDirection, SystemVerilogGenerator, VHDLType};
+
+/// Clock period used when no `ClockConstraint` names the clock
+pub const DEFAULT_CLOCK_PERIOD_NS: f64 = 10.0;
+
+/// Timing of one clock port
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockConstraint {
+    pub period_ns: f64,
+}
+
+impl ClockConstraint {
+    pub fn new(period_ns: f64) -> Self {
+        Self { period_ns }
+    }
+}
+
+impl Default for ClockConstraint {
+    fn default() -> Self {
+        Self::new(DEFAULT_CLOCK_PERIOD_NS)
+    }
+}
+
+/// Clocks, resets and remaining data ports of a module, by generated port name
+struct PortRoles<'a> {
+    clocks: Vec<&'a Port>,
+    resets: Vec<&'a Port>,
+    data: Vec<&'a Port>,
+}
+
+impl<'a> PortRoles<'a> {
+    /// Clocks and resets are named like one (`Port::is_clock`/`is_reset`) or used as one
+    /// by a clocked process
+    fn classify(entity: &'a Entity) -> Self {
+        let clocking = analyze_clocking(entity);
+        let used_as = |port: &Port, names: &[&str]| {
+            port.direction == PortDirection::In && names.iter().any(|name| name.eq_ignore_ascii_case(&port.name))
+        };
+        let clock_names: Vec<&str> = clocking.processes.iter().filter_map(|p| p.clock.as_deref()).collect();
+        let reset_names: Vec<&str> = clocking.processes.iter()
+            .filter_map(|p| p.reset.as_ref().map(|reset| reset.signal.as_str()))
+            .collect();
+
+        let mut roles = PortRoles { clocks: Vec::new(), resets: Vec::new(), data: Vec::new() };
+        for port in &entity.ports {
+            if port.is_clock() || used_as(port, &clock_names) {
+                roles.clocks.push(port);
+            } else if port.is_reset() || used_as(port, &reset_names) {
+                roles.resets.push(port);
+            } else {
+                roles.data.push(port);
+            }
+        }
+        roles
+    }
+}
+
+impl SystemVerilogGenerator {
+    /// SDC constraints: a `create_clock` per clock port (period from `clocks`, else
+    /// 10 ns), `set_false_path` from each reset, and zero I/O delay placeholders for the
+    /// data ports relative to the first clock
+    pub fn generate_sdc(&self, entity: &Entity, clocks: &HashMap<String, ClockConstraint>) -> Result<String> {
+        let (entity, _) = sanitize_entity(entity, |name| SystemVerilogDialect.is_reserved(name));
+        let roles = PortRoles::classify(&entity);
+
+        let mut output = format!("# SDC timing constraints for module {}\n", entity.name);
+        output.push_str(&clock_and_reset_constraints(&roles, clocks));
+        output.push_str(&io_delay_placeholders(&roles));
+        Ok(output)
+    }
+
+    /// Xilinx XDC constraints: the SDC timing commands plus commented-out
+    /// `PACKAGE_PIN`/`IOSTANDARD` stubs for every port
+    pub fn generate_xdc(&self, entity: &Entity, clocks: &HashMap<String, ClockConstraint>) -> Result<String> {
+        let (entity, _) = sanitize_entity(entity, |name| SystemVerilogDialect.is_reserved(name));
+        let roles = PortRoles::classify(&entity);
+
+        let mut output = format!("# XDC constraints for module {}\n", entity.name);
+        output.push_str(&clock_and_reset_constraints(&roles, clocks));
+        output.push_str(&io_delay_placeholders(&roles));
+
+        output.push_str("\n# Pin assignments: fill in the board's package pins and I/O standards\n");
+        for port in &entity.ports {
+            let target = get_ports(port);
+            output.push_str(&format!("# set_property PACKAGE_PIN <pin> {}\n", target));
+            output.push_str(&format!("# set_property IOSTANDARD LVCMOS33 {}\n", target));
+        }
+        Ok(output)
+    }
+}
+
+fn clock_and_reset_constraints(roles: &PortRoles, clocks: &HashMap<String, ClockConstraint>) -> String {
+    let mut output = String::new();
+    if roles.clocks.is_empty() {
+        output.push_str("\n# No clock port detected\n");
+    } else {
+        output.push('\n');
+    }
+    for clock in &roles.clocks {
+        let period = clocks.get(&clock.name)
+            .or_else(|| clocks.iter().find(|(name, _)| name.eq_ignore_ascii_case(&clock.name)).map(|(_, c)| c))
+            .copied()
+            .unwrap_or_default()
+            .period_ns;
+        output.push_str(&format!(
+            "create_clock -name {} -period {} {}\n",
+            clock.name,
+            format_ns(period),
+            get_ports(clock)
+        ));
+    }
+
+    if !roles.resets.is_empty() {
+        output.push('\n');
+    }
+    for reset in &roles.resets {
+        output.push_str(&format!("set_false_path -from {}\n", get_ports(reset)));
+    }
+    output
+}
+
+fn io_delay_placeholders(roles: &PortRoles) -> String {
+    let Some(clock) = roles.clocks.first() else {
+        return if roles.data.is_empty() {
+            String::new()
+        } else {
+            "\n# I/O delays need a reference clock; none was detected\n".to_string()
+        };
+    };
+
+    let mut output = "\n# I/O delay placeholders: replace 0.0 with board-level timing\n".to_string();
+    for port in &roles.data {
+        let command = match port.direction {
+            PortDirection::In => "set_input_delay",
+            PortDirection::Out | PortDirection::Buffer => "set_output_delay",
+            PortDirection::InOut => {
+                output.push_str(&format!("set_input_delay -clock {} 0.0 {}\n", clock.name, get_ports(port)));
+                "set_output_delay"
+            }
+        };
+        output.push_str(&format!("{} -clock {} 0.0 {}\n", command, clock.name, get_ports(port)));
+    }
+    output
+}
+
+/// `[get_ports name]`, or `[get_ports {name[*]}]` for vector ports
+fn get_ports(port: &Port) -> String {
+    match port.port_type {
+        VHDLType::StdLogic | VHDLType::Bit | VHDLType::Boolean => format!("[get_ports {}]", port.name),
+        _ => format!("[get_ports {{{}[*]}}]", port.name),
+    }
+}
+
+/// Nanoseconds with at least one decimal: 10.0, 6.4, 3.333
+fn format_ns(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.1}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Architecture, Process, VectorRange};
+
+    fn byte() -> VHDLType {
+        VHDLType::StdLogicVector(VectorRange { left: 7.into(), right: 0.into(), downto: true })
+    }
+
+    fn counter() -> Entity {
+        let mut entity = Entity::new("counter".to_string());
+        entity.add_port(Port::new("sys_clk".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("clk_en".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("rst_n".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("d".to_string(), PortDirection::In, byte()));
+        entity.add_port(Port::new("q".to_string(), PortDirection::Out, byte()));
+        entity
+    }
+
+    #[test]
+    fn test_clock_and_reset_detection() {
+        let entity = counter();
+        assert!(entity.ports[0].is_clock());
+        assert!(!entity.ports[1].is_clock());
+        assert!(entity.ports[2].is_reset());
+        assert!(!Port::new("clk".to_string(), PortDirection::Out, VHDLType::StdLogic).is_clock());
+
+        // A port driving rising_edge is a clock whatever its name
+        let mut sampled = Entity::new("sampler".to_string());
+        sampled.add_port(Port::new("strobe".to_string(), PortDirection::In, VHDLType::StdLogic));
+        sampled.add_port(Port::new("a".to_string(), PortDirection::In, VHDLType::StdLogic));
+        sampled.add_architecture(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![Process {
+                label: Some("p".to_string()),
+                sensitivity_list: vec!["strobe".to_string()],
+                body: "if rising_edge(strobe) then\n    y <= a;\nend if;".to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        let roles = PortRoles::classify(&sampled);
+        assert_eq!(roles.clocks.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["strobe"]);
+    }
+
+    #[test]
+    fn test_sdc_syntax() {
+        let generator = SystemVerilogGenerator::new();
+        let sdc = generator.generate_sdc(&counter(), &HashMap::new()).unwrap();
+        assert_eq!(sdc, "\
+# SDC timing constraints for module counter
+
+create_clock -name sys_clk -period 10.0 [get_ports sys_clk]
+
+set_false_path -from [get_ports rst_n]
+
+# I/O delay placeholders: replace 0.0 with board-level timing
+set_input_delay -clock sys_clk 0.0 [get_ports clk_en]
+set_input_delay -clock sys_clk 0.0 [get_ports {d[*]}]
+set_output_delay -clock sys_clk 0.0 [get_ports {q[*]}]
+");
+
+        let clocks = HashMap::from([("SYS_CLK".to_string(), ClockConstraint::new(6.4))]);
+        let sdc = generator.generate_sdc(&counter(), &clocks).unwrap();
+        assert!(sdc.contains("create_clock -name sys_clk -period 6.4 [get_ports sys_clk]"));
+    }
+
+    #[test]
+    fn test_xdc_stubs_pins() {
+        let xdc = SystemVerilogGenerator::new().generate_xdc(&counter(), &HashMap::new()).unwrap();
+        assert!(xdc.starts_with("# XDC constraints for module counter\n"));
+        assert!(xdc.contains("create_clock -name sys_clk -period 10.0 [get_ports sys_clk]"));
+        assert!(xdc.contains("# set_property PACKAGE_PIN <pin> [get_ports {q[*]}]"));
+        assert!(xdc.lines().filter(|line| line.starts_with("# set_property IOSTANDARD")).count() == 5);
+    }
+}
@@ -0,0 +1,124 @@
+use anyhow::Result;
+
+/// Kind of procedural block opened while emitting a process body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    /// `begin ... end` (if/else branches)
+    Begin,
+    /// `case (...) ... endcase`
+    Case,
+    /// `label: begin ... end` inside a case
+    CaseItem,
+    /// VHDL block with no HDL counterpart (e.g. `if rising_edge(clk)`, folded into the sensitivity list)
+    Elided,
+}
+
+/// Line emitter for procedural code that keeps indentation and begin/end nesting in sync
+pub struct BlockEmitter {
+    indent: String,
+    base_depth: usize,
+    stack: Vec<BlockKind>,
+    output: String,
+}
+
+impl BlockEmitter {
+    pub fn new(indent: &str, base_depth: usize) -> Self {
+        Self {
+            indent: indent.to_string(),
+            base_depth,
+            stack: Vec::new(),
+            output: String::new(),
+        }
+    }
+
+    /// Innermost open block, if any
+    pub fn top(&self) -> Option<BlockKind> {
+        self.stack.last().copied()
+    }
+
+    /// Emit a line at the current nesting depth
+    pub fn emit_line(&mut self, line: &str) {
+        let depth = self.base_depth
+            + self.stack.iter().filter(|kind| **kind != BlockKind::Elided).count();
+        self.output.push_str(&self.indent.repeat(depth));
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
+    /// Number of bytes emitted so far, to tell whether a statement produced any output
+    pub fn emitted_len(&self) -> usize {
+        self.output.len()
+    }
+
+    /// Append text to the end of the last emitted line (e.g. a trailing comment)
+    pub fn append_to_last_line(&mut self, text: &str) {
+        if self.output.ends_with('\n') {
+            self.output.pop();
+            self.output.push(' ');
+            self.output.push_str(text);
+            self.output.push('\n');
+        }
+    }
+
+    /// Open a block; lines emitted afterwards are indented one level deeper
+    pub fn push_block(&mut self, kind: BlockKind) {
+        self.stack.push(kind);
+    }
+
+    /// Close the innermost block, failing if no block is open
+    pub fn pop_block(&mut self) -> Result<BlockKind> {
+        self.stack
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Unbalanced block: 'end' without a matching 'begin'"))
+    }
+
+    /// Return the emitted text, failing if blocks are still open
+    pub fn finish(self) -> Result<String> {
+        if !self.stack.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Unbalanced block: {} block(s) still open at end of process ({:?})",
+                self.stack.len(),
+                self.stack
+            ));
+        }
+        Ok(self.output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indentation_at_arbitrary_depth() {
+        let mut emitter = BlockEmitter::new("  ", 1);
+        for depth in 0..5 {
+            emitter.emit_line(&format!("if (l{}) begin", depth));
+            emitter.push_block(BlockKind::Begin);
+        }
+        emitter.push_block(BlockKind::Elided);
+        emitter.emit_line("x <= 1'b1;");
+        emitter.pop_block().unwrap();
+        for _ in 0..5 {
+            emitter.pop_block().unwrap();
+            emitter.emit_line("end");
+        }
+
+        let output = emitter.finish().unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "  if (l0) begin");
+        assert_eq!(lines[4], "          if (l4) begin");
+        assert_eq!(lines[5], "            x <= 1'b1;");
+        assert_eq!(lines[6], "          end");
+        assert_eq!(lines[10], "  end");
+    }
+
+    #[test]
+    fn test_unbalanced_blocks_are_errors() {
+        let mut emitter = BlockEmitter::new("    ", 0);
+        assert!(emitter.pop_block().is_err());
+
+        emitter.push_block(BlockKind::Begin);
+        assert!(emitter.finish().is_err());
+    }
+}
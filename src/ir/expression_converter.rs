@@ -0,0 +1,198 @@
+use anyhow::Result;
+
+/// VHDL aggregate: positional `(a, b, c)` or named `(3 => x, others => '0')`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Aggregate {
+    Positional(Vec<String>),
+    Named {
+        /// Choice and value pairs in source order; a choice may be `7 downto 4` or `1 | 3`
+        choices: Vec<(String, String)>,
+        others: Option<String>,
+    },
+}
+
+/// Parse a whole expression as an aggregate; `None` for anything else, including
+/// plain parenthesized expressions and function calls
+pub fn parse_aggregate(expr: &str) -> Option<Aggregate> {
+    let inner = expr.trim().strip_prefix('(')?.strip_suffix(')')?;
+    if !balanced(inner) {
+        return None;
+    }
+
+    let elements = split_top_level(inner, ',');
+    let named: Vec<Option<(&str, &str)>> = elements.iter().map(|element| split_choice(element)).collect();
+
+    if named.iter().all(Option::is_none) {
+        return (elements.len() > 1).then(|| Aggregate::Positional(elements.iter().map(|e| e.trim().to_string()).collect()));
+    }
+
+    let mut choices = Vec::new();
+    let mut others = None;
+    for choice in named {
+        let (choice, value) = choice?;
+        if choice.eq_ignore_ascii_case("others") {
+            others = Some(value.to_string());
+        } else {
+            choices.push((choice.to_string(), value.to_string()));
+        }
+    }
+    Some(Aggregate::Named { choices, others })
+}
+
+/// "(byte3, byte2, byte1, byte0)" -> "{byte3, byte2, byte1, byte0}"; nested positional
+/// aggregates become nested concatenations. Named aggregates need a target and go
+/// through `convert_aggregate_assignment`
+pub fn convert_aggregate_expression(expr: &str) -> Result<String> {
+    match parse_aggregate(expr) {
+        Some(Aggregate::Positional(elements)) => {
+            let elements = elements.iter()
+                .map(|element| match parse_aggregate(element) {
+                    Some(_) => convert_aggregate_expression(element),
+                    None => Ok(element.clone()),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(format!("{{{}}}", elements.join(", ")))
+        }
+        Some(Aggregate::Named { .. }) => Err(anyhow::anyhow!(
+            "Named aggregate '{}' has no expression form; assign it element by element",
+            expr.trim()
+        )),
+        None => Err(anyhow::anyhow!("'{}' is not an aggregate", expr.trim())),
+    }
+}
+
+/// Assignments that give `target` the value of the aggregate `expr`, using `op`
+/// (`=` or `<=`): a concatenation for positional aggregates, otherwise an `others`
+/// fill with `{N{value}}` followed by one assignment per named choice
+pub fn convert_aggregate_assignment(target: &str, expr: &str, width: Option<u32>, op: &str) -> Result<Vec<String>> {
+    let aggregate = parse_aggregate(expr)
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not an aggregate", expr.trim()))?;
+
+    let (choices, others) = match aggregate {
+        Aggregate::Positional(_) => {
+            return Ok(vec![format!("{} {} {};", target, op, convert_aggregate_expression(expr)?)]);
+        }
+        Aggregate::Named { choices, others } => (choices, others),
+    };
+
+    let mut lines = Vec::new();
+    if let Some(value) = others {
+        let width = width.ok_or_else(|| anyhow::anyhow!(
+            "Cannot size (others => {}) for '{}': target width is unknown",
+            value,
+            target
+        ))?;
+        lines.push(format!("{} {} {{{}{{{}}}}};", target, op, width, value));
+    }
+
+    for (choice, value) in choices {
+        for alternative in split_top_level(&choice, '|') {
+            let alternative = alternative.trim();
+            let index = if let Some((left, right)) = split_range(alternative, " downto ") {
+                format!("[{}:{}]", left, right)
+            } else if let Some((left, right)) = split_range(alternative, " to ") {
+                format!("[{}:{}]", right, left)
+            } else {
+                format!("[{}]", alternative)
+            };
+            lines.push(format!("{}{} {} {};", target, index, op, value));
+        }
+    }
+    Ok(lines)
+}
+
+/// "3 => x" -> ("3", "x"), skipping `=>` nested inside parentheses
+fn split_choice(element: &str) -> Option<(&str, &str)> {
+    let mut depth = 0i32;
+    let bytes = element.as_bytes();
+    for i in 0..bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            b'=' if depth == 0 && bytes.get(i + 1) == Some(&b'>') => {
+                return Some((element[..i].trim(), element[i + 2..].trim()));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn split_range<'a>(choice: &'a str, keyword: &str) -> Option<(&'a str, &'a str)> {
+    let lower = choice.to_lowercase();
+    let pos = lower.find(keyword)?;
+    Some((choice[..pos].trim(), choice[pos + keyword.len()..].trim()))
+}
+
+/// Split on `separator` outside parentheses, braces and string literals
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut start = 0;
+    for (i, ch) in text.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            '(' | '{' if !in_string => depth += 1,
+            ')' | '}' if !in_string => depth -= 1,
+            c if c == separator && depth == 0 && !in_string => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Parentheses in `text` never close more than they open, so the outer pair matches
+fn balanced(text: &str) -> bool {
+    let mut depth = 0i32;
+    for ch in text.chars() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth < 0 {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    depth == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positional_aggregate() {
+        assert_eq!(convert_aggregate_expression("(byte3, byte2, byte1, byte0)").unwrap(), "{byte3, byte2, byte1, byte0}");
+        assert_eq!(convert_aggregate_expression("((a, b), f(c, d))").unwrap(), "{{a, b}, f(c, d)}");
+        assert_eq!(parse_aggregate("(a & b)"), None);
+        assert_eq!(parse_aggregate("(a) & (b)"), None);
+        assert!(convert_aggregate_expression("(3 => x)").is_err());
+    }
+
+    #[test]
+    fn test_named_aggregate() {
+        assert_eq!(
+            convert_aggregate_assignment("flags", "(3 => high_bit, 1 | 0 => 1'b1, 7 downto 5 => mode)", Some(8), "=").unwrap(),
+            vec!["flags[3] = high_bit;", "flags[1] = 1'b1;", "flags[0] = 1'b1;", "flags[7:5] = mode;"]
+        );
+    }
+
+    #[test]
+    fn test_others_aggregate() {
+        assert_eq!(
+            convert_aggregate_assignment("data", "(3 => high_bit, others => 1'b0)", Some(8), "<=").unwrap(),
+            vec!["data <= {8{1'b0}};", "data[3] <= high_bit;"]
+        );
+        assert_eq!(convert_aggregate_assignment("data", "(others => en)", Some(4), "=").unwrap(), vec!["data = {4{en}};"]);
+        let err = convert_aggregate_assignment("data", "(others => en)", None, "=").unwrap_err();
+        assert_eq!(err.to_string(), "Cannot size (others => en) for 'data': target width is unknown");
+    }
+}
@@ -0,0 +1,185 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref OPENS_BLOCK_RE: Regex = Regex::new(r"\bbegin(\s*:\s*\w+)?$").unwrap();
+    static ref CASE_RE: Regex = Regex::new(r"^((unique|unique0|priority)\s+)?case[zx]?\b").unwrap();
+    static ref PORT_RE: Regex =
+        Regex::new(r"^(input|output|inout)\s+(.*?)\s*\b([A-Za-z_]\w*)\s*(,?)\s*(/\*.*?\*/(?:\s*//.*)?|//.*)?$").unwrap();
+}
+
+/// Deterministic layout pass over generated Verilog/SystemVerilog: re-indents by
+/// module/begin/end/case/paren structure, collapses blank lines, aligns port
+/// declarations in columns and ends the text with exactly one newline
+pub struct HdlFormatter {
+    indent: String,
+}
+
+impl HdlFormatter {
+    pub fn new(indent: &str) -> Self {
+        Self { indent: indent.to_string() }
+    }
+
+    pub fn format(&self, text: &str) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        let mut port_lines = Vec::new();
+        let mut level = 0usize;
+        let mut in_header = false;
+
+        for raw in text.lines() {
+            let line = raw.trim();
+            if line.is_empty() {
+                if lines.last().is_some_and(|last| !last.is_empty()) {
+                    lines.push(String::new());
+                }
+                continue;
+            }
+
+            let code = strip_line_comment(line).trim_end();
+            let first_word = code.split(|c: char| !c.is_ascii_alphanumeric() && c != '_').next().unwrap_or("");
+
+            // Dedent before the line
+            if code.starts_with(')')
+                || matches!(first_word, "end" | "endcase" | "endgenerate" | "endfunction" | "endtask")
+            {
+                level = level.saturating_sub(1);
+            } else if matches!(first_word, "module" | "endmodule") {
+                level = 0;
+            }
+
+            if in_header && level == 1 && PORT_RE.is_match(line) {
+                port_lines.push(lines.len());
+            }
+            lines.push(format!("{}{}", self.indent.repeat(level), line));
+
+            // Indent after the line
+            if first_word == "module" {
+                in_header = !code.ends_with(';');
+                if !in_header {
+                    level = 1;
+                }
+            }
+            if code.ends_with('(') {
+                level += 1;
+            }
+            if OPENS_BLOCK_RE.is_match(code)
+                || CASE_RE.is_match(code)
+                || matches!(first_word, "generate" | "function" | "task")
+            {
+                level += 1;
+            }
+            if in_header && level == 0 && code.starts_with(')') && code.ends_with(';') {
+                in_header = false;
+                level = 1;
+            }
+        }
+
+        while lines.last().is_some_and(|last| last.is_empty()) {
+            lines.pop();
+        }
+        self.align_ports(&mut lines, &port_lines);
+
+        let mut output = lines.join("\n");
+        output.push('\n');
+        output
+    }
+
+    /// Pad direction and type so port names line up in a column
+    fn align_ports(&self, lines: &mut [String], port_lines: &[usize]) {
+        let ports: Vec<(usize, regex::Captures)> = port_lines.iter()
+            .filter_map(|&i| Some((i, PORT_RE.captures(lines[i].trim())?)))
+            .collect();
+        let direction_width = ports.iter().map(|(_, caps)| caps[1].len()).max().unwrap_or(0);
+        let type_width = ports.iter().map(|(_, caps)| caps[2].len()).max().unwrap_or(0);
+
+        let aligned: Vec<(usize, String)> = ports.iter()
+            .map(|(i, caps)| {
+                let mut line = format!("{}{:<dw$} ", self.indent, &caps[1], dw = direction_width);
+                if type_width > 0 {
+                    line.push_str(&format!("{:<tw$} ", &caps[2], tw = type_width));
+                }
+                line.push_str(&caps[3]);
+                line.push_str(&caps[4]);
+                if let Some(comment) = caps.get(5) {
+                    line.push(' ');
+                    line.push_str(comment.as_str());
+                }
+                (*i, line)
+            })
+            .collect();
+
+        for (i, line) in aligned {
+            lines[i] = line;
+        }
+    }
+}
+
+/// Code part of a line, before any `//` or `/*` comment outside string literals
+fn strip_line_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let bytes = line.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'"' => in_string = !in_string,
+            b'/' if !in_string && matches!(bytes.get(i + 1), Some(b'/' | b'*')) => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reindents_and_aligns() {
+        let messy = "\n\nmodule m (\n  input logic clk,   \ninput logic [7:0] d, // data\n        output logic q\n);\n\n\n\nlogic t;\n always_ff @(posedge clk) begin\nif (d[0]) begin\n  q <= 1'b1;\n     end else begin\nunique case (d)\n8'h00: begin\nq <= 1'b0;\nend\nendcase\nend\n  end\n\n\nendmodule";
+
+        assert_eq!(HdlFormatter::new("    ").format(messy), "\
+module m (
+    input  logic       clk,
+    input  logic [7:0] d, // data
+    output logic       q
+);
+
+    logic t;
+    always_ff @(posedge clk) begin
+        if (d[0]) begin
+            q <= 1'b1;
+        end else begin
+            unique case (d)
+                8'h00: begin
+                    q <= 1'b0;
+                end
+            endcase
+        end
+    end
+
+endmodule
+");
+    }
+
+    #[test]
+    fn test_parameters_and_instances() {
+        let text = "module m #(\nparameter W = 8\n) (\ninput wire a\n);\ngenerate\nfor (i = 0; i < W; i = i + 1) begin : gen_u\nsub #(\n.W(W)\n) u (\n.a(a)\n);\nend\nendgenerate\nendmodule\n";
+
+        assert_eq!(HdlFormatter::new("  ").format(text), "\
+module m #(
+  parameter W = 8
+) (
+  input wire a
+);
+  generate
+    for (i = 0; i < W; i = i + 1) begin : gen_u
+      sub #(
+        .W(W)
+      ) u (
+        .a(a)
+      );
+    end
+  endgenerate
+endmodule
+");
+    }
+}
@@ -0,0 +1,16 @@
+//! Expected generator output checked in under `tests/fixtures/golden`
+
+use std::path::PathBuf;
+
+/// Compare `actual` with the golden file `name`, or rewrite the file when `UPDATE_GOLDEN`
+/// is set, as `tests/golden_test.rs` does for the golden designs
+pub(crate) fn assert_golden(actual: &str, name: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden").join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("cannot read {} ({}), run with UPDATE_GOLDEN=1", path.display(), e));
+    assert_eq!(actual, expected, "{} differs from the generator output (UPDATE_GOLDEN=1 regenerates it)", name);
+}
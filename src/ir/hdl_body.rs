@@ -0,0 +1,1936 @@
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::ir::emitter::{BlockEmitter, BlockKind};
+use crate::ir::expression_converter::{convert_aggregate_assignment, parse_aggregate};
+use crate::ir::sanitize::{SYSTEMVERILOG_KEYWORDS, VERILOG_KEYWORDS};
+use crate::ir::{inferred_latches, Actual, Association, GenerateBlock, GenerateScheme, Instantiation, Process, VHDLType};
+use crate::ir::{ClockEdge, ProcessClocking, ResetPolarity, ResetStyle, TranspilationWarning, VectorRange};
+
+lazy_static! {
+    static ref HEX_LITERAL_RE: Regex = Regex::new(r#"x"([0-9A-Fa-f]+)""#).unwrap();
+    static ref OTHERS_RE: Regex = Regex::new(r"\(others\s*=>\s*1'b([01])\)").unwrap();
+    static ref TRANSLATE_PRAGMA_RE: Regex =
+        Regex::new(r"(?i)^(?:synthesis|synopsys|pragma|rtl_synthesis)\s+translate_(off|on)$").unwrap();
+    static ref KEEP_PRAGMA_RE: Regex = Regex::new(r"(?i)^(?:synthesis|syn)\s+keep$").unwrap();
+    static ref WAIT_FOR_RE: Regex = Regex::new(r"(?i)^wait\s+for\s+(.+?)\s*;?$").unwrap();
+    static ref WAIT_UNTIL_RE: Regex = Regex::new(r"(?i)^wait\s+until\s+(.+?)\s*;?$").unwrap();
+    static ref WAIT_ON_RE: Regex = Regex::new(r"(?i)^wait\s+on\s+(.+?)\s*;?$").unwrap();
+    static ref WAIT_FOREVER_RE: Regex = Regex::new(r"(?i)^wait\s*;?$").unwrap();
+    static ref WAIT_EDGE_RE: Regex = Regex::new(r"(?i)^(rising|falling)_edge\s*\(\s*(\w+)\s*\)$").unwrap();
+    static ref TIME_LITERAL_RE: Regex = Regex::new(r"(?i)^(\d+(?:\.\d+)?)\s*(fs|ps|ns|us|ms|sec)$").unwrap();
+    static ref EDGE_CALL_RE: Regex = Regex::new(r"(?i)\b(?:rising|falling)_edge\s*\(\s*\w+\s*\)").unwrap();
+    /// A clock edge and-ed with an enable: the edge goes to the sensitivity list, the enable stays
+    static ref EDGE_AND_RE: Regex = Regex::new(
+        r"(?i)^(?:(?:rising|falling)_edge\s*\(\s*\w+\s*\)\s+and\s+(.+)|(.+?)\s+and\s+(?:rising|falling)_edge\s*\(\s*\w+\s*\))$"
+    ).unwrap();
+    /// VHDL-2008 unary reduction operator at the start of an operand
+    static ref REDUCTION_RE: Regex = Regex::new(r"(^|[(=>,]\s*)(nand|nor|xnor|and|or|xor)\s+([\w(])").unwrap();
+    static ref SELECTED_ASSIGNMENT_RE: Regex =
+        Regex::new(r"(?is)^with\s+(.+?)\s+select\??\s+(.+?)\s*(<=|:=)\s*(.+?)\s*;$").unwrap();
+}
+
+/// numeric_std conversion functions, rewritten in this order
+const TYPE_CONVERSIONS: &[&str] = &["std_logic_vector", "to_unsigned", "to_signed", "unsigned", "signed"];
+
+/// Largest integer range expanded into a list of case labels
+const MAX_EXPANDED_CASE_RANGE: i64 = 256;
+
+/// How a case statement has to be emitted, decided from all of its choices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaseStyle {
+    Plain,
+    /// Don't-care bits without `case inside`: Verilog `casez`
+    Casez,
+    /// SystemVerilog `case (...) inside`
+    Inside,
+}
+
+/// Qualifier of a SystemVerilog case statement, decided from its `when` choices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseType {
+    /// No two choices match the same value: `unique case`
+    Unique,
+    /// Choices can overlap, so the first match wins: `priority case`
+    Priority,
+}
+
+/// Target-language details that differ between the Verilog and SystemVerilog generators
+pub trait Dialect {
+    /// Language name used in generated comments
+    fn name(&self) -> &str;
+
+    /// Process keyword: clocked (`always` / `always_ff`, followed by the edge list)
+    /// or combinational (`always @(*)` / `always_comb`)
+    fn always_keyword(&self, is_sequential: bool) -> &str;
+
+    /// Process keyword for a combinational process that infers latches
+    fn latch_keyword(&self) -> &str;
+
+    /// Replacement for `(others => '0')` / `(others => '1')`, given the target width if known
+    fn others_aggregate(&self, bit: char, width: Option<u32>) -> String;
+
+    /// Keyword opening a case statement
+    fn case_keyword(&self, case_type: CaseType) -> &str;
+
+    /// Whether `case (...) inside` with `[lo:hi]` ranges and `?` wildcards is available
+    fn supports_case_inside(&self) -> bool;
+
+    /// Cast replacing VHDL `to_integer(...)`; `None` drops the conversion
+    fn integer_cast(&self) -> Option<&str>;
+
+    /// Whether `name` is a reserved word and has to be renamed
+    fn is_reserved(&self, name: &str) -> bool;
+}
+
+/// Verilog-2001 dialect
+pub struct VerilogDialect;
+
+impl Dialect for VerilogDialect {
+    fn name(&self) -> &str {
+        "Verilog"
+    }
+
+    fn always_keyword(&self, is_sequential: bool) -> &str {
+        if is_sequential { "always" } else { "always @(*)" }
+    }
+
+    fn latch_keyword(&self) -> &str {
+        "always @(*)"
+    }
+
+    fn others_aggregate(&self, bit: char, width: Option<u32>) -> String {
+        match width {
+            Some(width) => format!("{{{}{{1'b{}}}}}", width, bit),
+            // Unsized literals extend to the target width
+            None if bit == '0' => "0".to_string(),
+            None => "~0".to_string(),
+        }
+    }
+
+    fn case_keyword(&self, _case_type: CaseType) -> &str {
+        "case"
+    }
+
+    fn supports_case_inside(&self) -> bool {
+        false
+    }
+
+    fn integer_cast(&self) -> Option<&str> {
+        None
+    }
+
+    fn is_reserved(&self, name: &str) -> bool {
+        VERILOG_KEYWORDS.contains(&name)
+    }
+}
+
+/// SystemVerilog 2012 dialect
+pub struct SystemVerilogDialect;
+
+impl Dialect for SystemVerilogDialect {
+    fn name(&self) -> &str {
+        "SystemVerilog"
+    }
+
+    fn always_keyword(&self, is_sequential: bool) -> &str {
+        if is_sequential { "always_ff" } else { "always_comb" }
+    }
+
+    fn latch_keyword(&self) -> &str {
+        "always_latch"
+    }
+
+    fn others_aggregate(&self, bit: char, _width: Option<u32>) -> String {
+        format!("'{}", bit)
+    }
+
+    fn case_keyword(&self, case_type: CaseType) -> &str {
+        match case_type {
+            CaseType::Unique => "unique case",
+            CaseType::Priority => "priority case",
+        }
+    }
+
+    fn supports_case_inside(&self) -> bool {
+        true
+    }
+
+    fn integer_cast(&self) -> Option<&str> {
+        Some("int'")
+    }
+
+    fn is_reserved(&self, name: &str) -> bool {
+        VERILOG_KEYWORDS.contains(&name) || SYSTEMVERILOG_KEYWORDS.contains(&name)
+    }
+}
+
+/// Converts VHDL process bodies and concurrent statements into HDL for a given dialect
+pub struct HdlBodyConverter<D: Dialect> {
+    dialect: D,
+    indent: String,
+    symbols: HashMap<String, VHDLType>,
+    keep_comments: bool,
+    fix_latches: bool,
+    blocking_assignments: bool,
+}
+
+impl<D: Dialect> HdlBodyConverter<D> {
+    pub fn new(dialect: D, indent: String) -> Self {
+        Self {
+            dialect,
+            indent,
+            symbols: HashMap::new(),
+            keep_comments: true,
+            fix_latches: false,
+            blocking_assignments: true,
+        }
+    }
+
+    /// Carry `--` comments in process bodies over as `//` comments (default: true)
+    pub fn with_comments(mut self, keep_comments: bool) -> Self {
+        self.keep_comments = keep_comments;
+        self
+    }
+
+    /// Port and signal types, used to size `others` aggregates
+    pub fn with_symbols(mut self, symbols: HashMap<String, VHDLType>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Assign latched signals a default at the top of their process, so it converts
+    /// to a combinational block instead of a latch (default: false)
+    pub fn with_fix_latches(mut self, fix_latches: bool) -> Self {
+        self.fix_latches = fix_latches;
+        self
+    }
+
+    /// Emit signal assignments in combinational blocks as blocking `=` (default: true);
+    /// false keeps VHDL's `<=` as written. Variable assignments are always blocking
+    pub fn with_blocking_assignments(mut self, blocking_assignments: bool) -> Self {
+        self.blocking_assignments = blocking_assignments;
+        self
+    }
+
+    /// Convert a VHDL process into an always block
+    pub fn convert_process(&self, process: &Process) -> Result<String> {
+        let process = &*self.with_latch_defaults(process);
+        let mut output = String::new();
+        output.push_str(&self.indent);
+        let mut blocking = false;
+
+        if is_sequential(process) {
+            // Clock edges first, followed by asynchronous resets
+            let mut edge_signals = Vec::new();
+            let mut reset_edges = Vec::new();
+
+            if process.senses_all() {
+                // No names to go by: take the clock and an asynchronous reset from the body
+                let clocking = ProcessClocking::analyze(process, "");
+                if let (Some(clock), Some(edge)) = (&clocking.clock, clocking.edge) {
+                    let keyword = if edge == ClockEdge::Rising { "posedge" } else { "negedge" };
+                    edge_signals.push(format!("{} {}", keyword, clock));
+                }
+                if let Some(reset) = clocking.reset.filter(|reset| reset.style == ResetStyle::Async) {
+                    let keyword = if reset.polarity == ResetPolarity::ActiveHigh { "posedge" } else { "negedge" };
+                    reset_edges.push(format!("{} {}", keyword, reset.signal));
+                }
+            }
+            for sig in process.sensitivity_list.iter().filter(|_| !process.senses_all()) {
+                let lower = sig.to_lowercase();
+                if lower.contains("clk") || lower.contains("clock") {
+                    edge_signals.push(format!("posedge {}", sig));
+                } else if lower.contains("reset") || lower.contains("rst") {
+                    // Check if active high or low reset
+                    if process.body.contains(&format!("{} = '1'", sig)) || process.body.contains(&format!("{} = \"1\"", sig)) {
+                        reset_edges.push(format!("posedge {}", sig));
+                    } else {
+                        reset_edges.push(format!("negedge {}", sig));
+                    }
+                }
+            }
+
+            if edge_signals.is_empty() {
+                edge_signals.push("posedge clk".to_string());
+            }
+            edge_signals.extend(reset_edges);
+
+            output.push_str(&format!(
+                "{} @({}) begin\n",
+                self.dialect.always_keyword(true),
+                edge_signals.join(" or ")
+            ));
+        } else if !self.latched_signals(process).is_empty() {
+            output.push_str(&format!("{} begin\n", self.dialect.latch_keyword()));
+        } else {
+            output.push_str(&format!("{} begin\n", self.dialect.always_keyword(false)));
+            blocking = self.blocking_assignments;
+        }
+
+        let body = self.convert_body(&process.body, blocking).with_context(|| {
+            format!("Failed to convert process {}", process.label.as_deref().unwrap_or("<unlabeled>"))
+        })?;
+        output.push_str(&body);
+
+        output.push_str(&self.indent);
+        output.push_str("end\n");
+
+        Ok(output)
+    }
+
+    /// Signals `process` infers latches for; empty for clocked and testbench processes
+    pub fn latched_signals(&self, process: &Process) -> Vec<String> {
+        if process.testbench_style || is_sequential(process) {
+            return Vec::new();
+        }
+        inferred_latches(process)
+    }
+
+    /// Latched signals of `process` that `with_fix_latches` gives a default; empty
+    /// when the option is off. Signals of custom types have no known default
+    pub fn defaulted_latches(&self, process: &Process) -> Vec<String> {
+        if !self.fix_latches {
+            return Vec::new();
+        }
+        self.latched_signals(process)
+            .into_iter()
+            .filter(|signal| self.latch_default(signal).is_some())
+            .collect()
+    }
+
+    /// Diagnostics for the latches of `process`: defaulted ones and those left as latches
+    pub fn latch_warnings(&self, process: &Process) -> Vec<TranspilationWarning> {
+        let label = process.label.as_deref().unwrap_or("<unlabeled>");
+        let defaulted = self.defaulted_latches(process);
+        let latches: Vec<String> = self.latched_signals(process)
+            .into_iter()
+            .filter(|signal| !defaulted.contains(signal))
+            .collect();
+        let mut warnings = Vec::new();
+        if !defaulted.is_empty() {
+            warnings.push(TranspilationWarning::defaulted_latch(label, &defaulted));
+        }
+        if !latches.is_empty() {
+            warnings.push(TranspilationWarning::inferred_latch(label, &latches));
+        }
+        warnings
+    }
+
+    /// VHDL value a latched signal is reset to at the top of its process
+    fn latch_default(&self, signal: &str) -> Option<&'static str> {
+        match self.symbols.get(signal)? {
+            VHDLType::StdLogic | VHDLType::Bit | VHDLType::Boolean => Some("'0'"),
+            VHDLType::StdLogicVector(_) | VHDLType::BitVector(_) | VHDLType::Signed(_) | VHDLType::Unsigned(_) => {
+                Some("(others => '0')")
+            }
+            VHDLType::Integer | VHDLType::Natural | VHDLType::Positive | VHDLType::ConstrainedInteger(_) => Some("0"),
+            VHDLType::Custom(_) => None,
+        }
+    }
+
+    /// `process` with default assignments for its defaulted latches prepended
+    fn with_latch_defaults<'a>(&self, process: &'a Process) -> Cow<'a, Process> {
+        let defaulted = self.defaulted_latches(process);
+        if defaulted.is_empty() {
+            return Cow::Borrowed(process);
+        }
+        let mut body: String = defaulted.iter()
+            .filter_map(|signal| Some(format!("{} <= {};\n", signal, self.latch_default(signal)?)))
+            .collect();
+        body.push_str(&process.body);
+        Cow::Owned(Process { body, ..process.clone() })
+    }
+
+    /// Convert a testbench-style process: one that ends in a bare `wait;` runs once
+    /// as an `initial` block, any other repeats as a plain `always` block. Delays
+    /// are emitted in nanoseconds and assume a 1ns timescale
+    pub fn convert_timed_process(&self, process: &Process) -> Result<String> {
+        let mut lines: Vec<&str> = process.body.lines().collect();
+        let last_statement = lines.iter()
+            .rposition(|line| !split_comment(line.trim()).0.is_empty());
+        let runs_once = match last_statement {
+            Some(index) if WAIT_FOREVER_RE.is_match(split_comment(lines[index].trim()).0) => {
+                lines.remove(index);
+                true
+            }
+            _ => false,
+        };
+
+        let body = self.convert_process_body(&lines.join("\n")).with_context(|| {
+            format!("Failed to convert process {}", process.label.as_deref().unwrap_or("<unlabeled>"))
+        })?;
+        let keyword = if runs_once { "initial" } else { "always" };
+        Ok(format!("{}{} begin\n{}{}end\n", self.indent, keyword, body, self.indent))
+    }
+
+    /// Convert the sequential statements of a process body
+    pub fn convert_process_body(&self, vhdl_body: &str) -> Result<String> {
+        self.convert_body(vhdl_body, false)
+    }
+
+    /// Convert the sequential statements of a process body, with `<=` signal
+    /// assignments emitted as `=` when `blocking` is set
+    fn convert_body(&self, vhdl_body: &str, blocking: bool) -> Result<String> {
+        let mut emitter = BlockEmitter::new(&self.indent, 2);
+        let mut case_styles = Vec::new();
+        let (statements, comments) = sequential_statements(vhdl_body);
+        let lines: Vec<&str> = statements.iter().map(String::as_str).collect();
+
+        for (index, &trimmed) in lines.iter().enumerate() {
+            let comment = comments[index].filter(|_| self.keep_comments).map(convert_comment);
+
+            if trimmed.is_empty() {
+                if let Some(comment) = comment {
+                    emitter.emit_line(&comment);
+                }
+                continue;
+            }
+            let emitted = emitter.emitted_len();
+
+            // Classify the statement before rewriting it, so conditions and
+            // assignments get their own treatment of `=` and `<=`
+            if trimmed.starts_with("if") && (trimmed.contains("rising_edge") || trimmed.contains("falling_edge")) {
+                // The edge is handled in the sensitivity list; an enable and-ed with it is kept
+                match Self::edge_enable(Self::strip_then(&trimmed[2..])) {
+                    Some(enable) => {
+                        emitter.emit_line(&format!("if {} begin", self.convert_condition(enable)));
+                        emitter.push_block(BlockKind::Begin);
+                    }
+                    None => emitter.push_block(BlockKind::Elided),
+                }
+            } else if trimmed.starts_with("case ") && trimmed.contains(" is") {
+                // "case state is" -> "case (state)"
+                let selector = trimmed["case ".len()..].replace(" is", "");
+                let selector = self.convert_expression(selector.trim());
+                let (style, case_type) = self.case_style(&lines[index + 1..]);
+                let header = match style {
+                    CaseStyle::Plain => format!("{} ({})", self.dialect.case_keyword(case_type), selector),
+                    CaseStyle::Casez => format!("casez ({})", selector),
+                    CaseStyle::Inside => format!("{} ({}) inside", self.dialect.case_keyword(case_type), selector),
+                };
+                emitter.emit_line(&header);
+                emitter.push_block(BlockKind::Case);
+                case_styles.push(style);
+            } else if trimmed.starts_with("when ") {
+                self.close_case_item(&mut emitter)?;
+                if emitter.top() != Some(BlockKind::Case) {
+                    return Err(anyhow::anyhow!("Unbalanced block: 'when' outside of a case statement"));
+                }
+
+                let style = case_styles.last().copied().unwrap_or(CaseStyle::Plain);
+                emitter.emit_line(&self.convert_case_label(trimmed, style)?);
+                emitter.push_block(BlockKind::CaseItem);
+            } else if trimmed == "end case" || trimmed == "end case;" {
+                self.close_case_item(&mut emitter)?;
+                if emitter.pop_block()? != BlockKind::Case {
+                    return Err(anyhow::anyhow!("Unbalanced block: 'end case' does not close a case statement"));
+                }
+                case_styles.pop();
+                emitter.emit_line("endcase");
+            } else if trimmed.starts_with("if ") || trimmed.starts_with("if(") {
+                // "if (reset = '1') then" -> "if (reset == 1'b1) begin"
+                let condition = Self::strip_then(&trimmed[2..]);
+                emitter.emit_line(&format!("if {} begin", self.convert_condition(condition)));
+                emitter.push_block(BlockKind::Begin);
+            } else if trimmed.starts_with("elsif ") || trimmed.starts_with("elsif(") || trimmed == "else" {
+                // "elsif rising_edge(clk) then" -> "end else begin"
+                // (rising_edge is already handled in sensitivity list)
+                let condition = Self::strip_then(trimmed.get(5..).unwrap_or(""));
+                let branch = if trimmed == "else" {
+                    "end else begin".to_string()
+                } else if trimmed.contains("rising_edge") || trimmed.contains("falling_edge") {
+                    match Self::edge_enable(condition) {
+                        Some(enable) => format!("end else if {} begin", self.convert_condition(enable)),
+                        None => "end else begin".to_string(),
+                    }
+                } else {
+                    format!("end else if {} begin", self.convert_condition(condition))
+                };
+                self.reopen_block(&mut emitter, &branch)?;
+            } else if trimmed == "end if" || trimmed == "end if;" {
+                match emitter.pop_block()? {
+                    BlockKind::Begin => emitter.emit_line("end"),
+                    BlockKind::Elided => {}
+                    kind => {
+                        return Err(anyhow::anyhow!("Unbalanced block: 'end if' closes a {:?} block", kind));
+                    }
+                }
+            } else if let Some(wait) = self.convert_wait(trimmed) {
+                emitter.emit_line(&wait);
+            } else if let Some(assignment) = self.convert_conditional_assignment(trimmed, blocking) {
+                emitter.emit_line(&assignment);
+            } else {
+                // Converted once, then emitted either as aggregate assignments or as is
+                let hdl_line = self.convert_expression(trimmed);
+                if let Some(assignments) = self.convert_aggregate_statement(&hdl_line, blocking)? {
+                    for assignment in &assignments {
+                        emitter.emit_line(assignment);
+                    }
+                } else {
+                    let mut hdl_line = self.convert_statement(hdl_line, blocking);
+
+                    // Don't add semicolons to control flow keywords
+                    let is_control_flow = hdl_line.starts_with("end") || hdl_line.ends_with(':');
+                    if !is_control_flow && !hdl_line.ends_with(';') {
+                        hdl_line.push(';');
+                    }
+                    emitter.emit_line(&hdl_line);
+                }
+            }
+
+            // Trailing comments follow their statement, or stand alone if it emitted nothing
+            if let Some(comment) = comment {
+                if emitter.emitted_len() > emitted {
+                    emitter.append_to_last_line(&comment);
+                } else {
+                    emitter.emit_line(&comment);
+                }
+            }
+        }
+
+        emitter.finish()
+    }
+
+    /// Convert a VHDL concurrent statement to a continuous assignment
+    pub fn convert_concurrent_statement(&self, stmt: &str) -> Result<String> {
+        // Handle with...select statements
+        if stmt.contains("with ") && stmt.contains(" select") {
+            return Ok(format!("// TODO: Convert VHDL 'with...select' to {} case:\n    // {}",
+                self.dialect.name(),
+                stmt.replace("\n", "\n    // ")));
+        }
+
+        // The first "<=" is the assignment; any later ones are comparisons
+        let Some(assign_pos) = stmt.find(" <= ") else {
+            return Ok(self.convert_expression(stmt));
+        };
+        let target = self.convert_expression(stmt[..assign_pos].trim());
+        let rest = stmt[assign_pos + 4..].trim().trim_end_matches(';');
+
+        // Aggregates: one continuous assignment, or a combinational block of element assignments
+        if let Some(assignments) = self.aggregate_assignments(&target, &self.convert_expression(rest), "=")? {
+            if let [assignment] = assignments.as_slice() {
+                return Ok(format!("assign {}", assignment));
+            }
+            let body: Vec<String> = assignments.iter()
+                .map(|assignment| format!("{}{}{}", self.indent, self.indent, assignment))
+                .collect();
+            return Ok(format!(
+                "{} begin\n{}\n{}end",
+                self.dialect.always_keyword(false),
+                body.join("\n"),
+                self.indent
+            ));
+        }
+
+        // Handle conditional assignments: "target <= value1 when condition else value2"
+        if let Some(value) = self.conditional_value(rest) {
+            return Ok(format!("assign {} = {};", target, value));
+        }
+
+        Ok(format!("assign {} = {};", target, self.convert_expression(rest)))
+    }
+
+    /// Convert an instantiation into a module instance at the given indentation depth
+    pub fn convert_instantiation(&self, inst: &Instantiation, depth: usize) -> String {
+        let pad = self.indent.repeat(depth);
+        let mut output = String::new();
+        if let Some(architecture) = &inst.architecture {
+            output.push_str(&format!("{}// {}({})", pad, inst.component, architecture));
+            if let Some(configuration) = &inst.configuration {
+                output.push_str(&format!(" via configuration {}", configuration));
+            }
+            output.push('\n');
+        }
+        output.push_str(&format!("{}{}", pad, inst.component));
+
+        if !inst.generic_map.is_empty() {
+            let parameters: Vec<String> = inst.generic_map.iter()
+                .map(|assoc| self.convert_association(assoc))
+                .collect();
+            output.push_str(&format!(" #({})", parameters.join(", ")));
+        }
+
+        output.push_str(&format!(" {} (\n", inst.label));
+        for (i, assoc) in inst.port_map.iter().enumerate() {
+            output.push_str(&pad);
+            output.push_str(&self.indent);
+            output.push_str(&self.convert_association(assoc));
+            if i < inst.port_map.len() - 1 {
+                output.push(',');
+            }
+            output.push('\n');
+        }
+        output.push_str(&pad);
+        output.push_str(");\n");
+
+        output
+    }
+
+    /// Convert top-level generate blocks into a `generate` region with their genvars
+    pub fn convert_generate_blocks(&self, blocks: &[GenerateBlock]) -> Result<String> {
+        let mut genvars = Vec::new();
+        Self::collect_genvars(blocks, &mut genvars);
+
+        let mut output = String::new();
+        for var in &genvars {
+            output.push_str(&format!("{}genvar {};\n", self.indent, var));
+        }
+
+        output.push_str(&self.indent);
+        output.push_str("generate\n");
+        for block in blocks {
+            self.convert_generate_block(block, 2, &mut output)?;
+        }
+        output.push_str(&self.indent);
+        output.push_str("endgenerate\n");
+
+        Ok(output)
+    }
+
+    fn convert_generate_block(&self, block: &GenerateBlock, depth: usize, output: &mut String) -> Result<()> {
+        let pad = self.indent.repeat(depth);
+
+        let header = match &block.scheme {
+            GenerateScheme::For { var, left, right, downto } => {
+                let (left, right) = (self.convert_expression(left), self.convert_expression(right));
+                if *downto {
+                    format!("for ({v} = {}; {v} >= {}; {v} = {v} - 1)", left, right, v = var)
+                } else {
+                    format!("for ({v} = {}; {v} <= {}; {v} = {v} + 1)", left, right, v = var)
+                }
+            }
+            GenerateScheme::If { condition } => format!("if {}", self.convert_condition(condition)),
+        };
+        output.push_str(&format!("{}{} begin : {}\n", pad, header, block.label));
+
+        for stmt in &block.concurrent_statements {
+            output.push_str(&pad);
+            output.push_str(&self.indent);
+            output.push_str(&self.convert_concurrent_statement(stmt)?);
+            output.push('\n');
+        }
+        for inst in &block.instantiations {
+            output.push_str(&self.convert_instantiation(inst, depth + 1));
+        }
+        for nested in &block.generate_blocks {
+            self.convert_generate_block(nested, depth + 1, output)?;
+        }
+
+        output.push_str(&pad);
+        output.push_str("end\n");
+        Ok(())
+    }
+
+    fn collect_genvars(blocks: &[GenerateBlock], genvars: &mut Vec<String>) {
+        for block in blocks {
+            if let GenerateScheme::For { var, .. } = &block.scheme {
+                if !genvars.contains(var) {
+                    genvars.push(var.clone());
+                }
+            }
+            Self::collect_genvars(&block.generate_blocks, genvars);
+        }
+    }
+
+    /// "a => x(i)" -> ".a(x[i])"; `open` becomes an unconnected ".a()"
+    fn convert_association(&self, assoc: &Association) -> String {
+        let actual = match &assoc.actual {
+            Actual::Open => String::new(),
+            Actual::Expression(expr) => self.convert_expression(expr),
+        };
+
+        match &assoc.formal {
+            Some(formal) => format!(".{}({})", formal, actual),
+            None => actual,
+        }
+    }
+
+    /// Ternary for `value1 when condition else value2`, nested for further `when ... else`
+    /// alternatives; `None` if `rest` is not a conditional value
+    fn conditional_value(&self, rest: &str) -> Option<String> {
+        let when_pos = rest.find(" when ")?;
+        // The `else` must follow the condition, not share its leading space with `when`
+        let condition_start = when_pos + " when ".len();
+        let else_pos = rest[condition_start..].find(" else ").map(|pos| condition_start + pos)?;
+        let value1 = rest[..when_pos].trim();
+        let condition = rest[condition_start..else_pos].trim();
+        let value2 = rest[else_pos + " else ".len()..].trim();
+
+        let value2 = self.conditional_value(value2).unwrap_or_else(|| self.convert_expression(value2));
+        Some(format!("{} ? {} : {}", self.convert_condition(condition), self.convert_expression(value1), value2))
+    }
+
+    /// VHDL-2008 conditional assignment in a process: "y <= a when s = '1' else b;"
+    /// -> "y <= (s == 1'b1) ? a : b;"
+    fn convert_conditional_assignment(&self, line: &str, blocking: bool) -> Option<String> {
+        let (assign_pos, op) = assignment_operator(line)?;
+        let value = self.conditional_value(line[assign_pos + 4..].trim().trim_end_matches(';'))?;
+        Some(format!(
+            "{} {} {};",
+            self.convert_expression(line[..assign_pos].trim()),
+            hdl_assignment_operator(op, blocking),
+            value
+        ))
+    }
+
+    /// The enable of `rising_edge(clk) and enable`, `None` for a bare clock edge
+    fn edge_enable(condition: &str) -> Option<&str> {
+        let caps = EDGE_AND_RE.captures(condition)?;
+        caps.get(1).or(caps.get(2)).map(|enable| enable.as_str())
+    }
+
+    /// Finish a sequential statement already passed through `convert_expression`, where
+    /// `<=` is the non-blocking assignment, or the blocking one when `blocking` is set
+    fn convert_statement(&self, mut hdl_line: String, blocking: bool) -> String {
+
+        // Convert others => value, sized from the assignment target when known
+        if OTHERS_RE.is_match(&hdl_line) {
+            let target = hdl_line.find(" <=").map(|pos| hdl_line[..pos].trim()).unwrap_or("");
+            let width = self.target_width(target);
+            if width.is_none() {
+                tracing::warn!(
+                    "Cannot resolve width of '{}' for (others => ...) aggregate; emitting an unsized {} literal",
+                    target,
+                    self.dialect.name()
+                );
+            }
+            hdl_line = OTHERS_RE.replace_all(&hdl_line, |caps: &regex::Captures| {
+                self.dialect.others_aggregate(caps[1].chars().next().unwrap(), width)
+            }).to_string();
+        }
+
+        if let Some((assign_pos, op)) = assignment_operator(&hdl_line) {
+            hdl_line.replace_range(assign_pos + 1..assign_pos + 3, hdl_assignment_operator(op, blocking));
+        }
+        hdl_line
+    }
+
+    /// Assignments for a sequential `target <= aggregate;` statement already passed
+    /// through `convert_expression`, `None` if the value is not an aggregate
+    fn convert_aggregate_statement(&self, hdl_line: &str, blocking: bool) -> Result<Option<Vec<String>>> {
+        let Some((assign_pos, op)) = assignment_operator(hdl_line) else {
+            return Ok(None);
+        };
+        let target = hdl_line[..assign_pos].trim();
+        let value = hdl_line[assign_pos + 4..].trim().trim_end_matches(';').trim_end();
+        self.aggregate_assignments(target, value, hdl_assignment_operator(op, blocking))
+    }
+
+    /// A lone `(others => bit)` keeps the dialect's fill literal; other aggregates go
+    /// through `convert_aggregate_assignment`, sized from the target
+    fn aggregate_assignments(&self, target: &str, value: &str, op: &str) -> Result<Option<Vec<String>>> {
+        if parse_aggregate(value).is_none() {
+            return Ok(None);
+        }
+
+        let width = self.target_width(target);
+        if let Some(caps) = OTHERS_RE.captures(value).filter(|caps| &caps[0] == value) {
+            if width.is_none() {
+                tracing::warn!(
+                    "Cannot resolve width of '{}' for (others => ...) aggregate; emitting an unsized {} literal",
+                    target,
+                    self.dialect.name()
+                );
+            }
+            let fill = self.dialect.others_aggregate(caps[1].chars().next().unwrap(), width);
+            return Ok(Some(vec![format!("{} {} {};", target, op, fill)]));
+        }
+
+        convert_aggregate_assignment(target, value, width, op).map(Some)
+    }
+
+    /// "wait for 10 ns;" -> "#10;", "wait until done = '1';" -> "wait (done == 1'b1);",
+    /// "wait until rising_edge(clk);" -> "@(posedge clk);", "wait on a, b;" -> "@(a or b);"
+    /// and a bare "wait;" blocks forever
+    fn convert_wait(&self, line: &str) -> Option<String> {
+        // Cheap test first: this runs on every statement
+        if !line.get(..4).is_some_and(|word| word.eq_ignore_ascii_case("wait")) {
+            return None;
+        }
+        if WAIT_FOREVER_RE.is_match(line) {
+            return Some("wait (0);".to_string());
+        }
+        if let Some(caps) = WAIT_FOR_RE.captures(line) {
+            let delay = match TIME_LITERAL_RE.captures(&caps[1]) {
+                Some(time) => {
+                    let scale = match time[2].to_lowercase().as_str() {
+                        "fs" => 1e-6,
+                        "ps" => 1e-3,
+                        "ns" => 1.0,
+                        "us" => 1e3,
+                        "ms" => 1e6,
+                        _ => 1e9,
+                    };
+                    let value: f64 = time[1].parse().ok()?;
+                    format!("{}", value * scale)
+                }
+                None => format!("({})", self.convert_expression(&caps[1])),
+            };
+            return Some(format!("#{};", delay));
+        }
+        if let Some(caps) = WAIT_UNTIL_RE.captures(line) {
+            if let Some(edge) = WAIT_EDGE_RE.captures(&caps[1]) {
+                let keyword = if edge[1].eq_ignore_ascii_case("rising") { "posedge" } else { "negedge" };
+                return Some(format!("@({} {});", keyword, &edge[2]));
+            }
+            return Some(format!("wait {};", self.convert_condition(&caps[1])));
+        }
+        WAIT_ON_RE.captures(line).map(|caps| {
+            let signals: Vec<&str> = caps[1].split(',').map(str::trim).collect();
+            format!("@({});", signals.join(" or "))
+        })
+    }
+
+    /// Convert an if/elsif/when condition, where `=`, `/=`, `<=` and `>=` are comparisons
+    fn convert_condition(&self, condition: &str) -> String {
+        let condition = Self::convert_binary_strings(&Self::convert_relational(condition));
+        Self::wrap_condition(&self.convert_expression(&condition))
+    }
+
+    /// Literal, logical operator and type conversion rewrites shared by conditions and statements
+    fn convert_expression(&self, line: &str) -> String {
+        // Convert hex literals first: x"0" -> 4'h0, x"FF" -> 8'hFF
+        let mut hdl_line = HEX_LITERAL_RE.replace_all(line, |caps: &regex::Captures| {
+            let hex_value = &caps[1];
+            format!("{}'h{}", hex_value.len() * 4, hex_value)
+        }).to_string();
+
+        hdl_line = Self::convert_binary_strings(&hdl_line);
+
+        // Convert bit literals
+        hdl_line = hdl_line.replace("'1'", "1'b1");
+        hdl_line = hdl_line.replace("'0'", "1'b0");
+
+        // Reduction operators before their binary forms: "or data" -> "|data"
+        hdl_line = REDUCTION_RE.replace_all(&hdl_line, |caps: &regex::Captures| {
+            let op = match &caps[2] {
+                "and" => "&",
+                "or" => "|",
+                "xor" => "^",
+                "nand" => "~&",
+                "nor" => "~|",
+                _ => "~^",
+            };
+            format!("{}{}{}", &caps[1], op, &caps[3])
+        }).to_string();
+
+        // Convert logical operators
+        hdl_line = hdl_line.replace(" and ", " & ");
+        hdl_line = hdl_line.replace(" or ", " | ");
+        hdl_line = hdl_line.replace(" xor ", " ^ ");
+        hdl_line = hdl_line.replace(" not ", " ~");
+
+        hdl_line = self.convert_type_conversions(&hdl_line);
+        self.convert_indexing(&hdl_line)
+    }
+
+    /// Rewrite `sig(i)`, `sig(a downto b)` and `sig(a to b)` on declared signals and ports
+    /// to bracket indexing; calls to anything else (functions, casts) are left untouched
+    fn convert_indexing(&self, expr: &str) -> String {
+        let mut result = String::with_capacity(expr.len());
+        let mut rest = expr;
+
+        while let Some(start) = rest.find(|c: char| c.is_alphabetic() || c == '_') {
+            // Identifiers glued to a preceding word, digit or tick (e.g. 8'hFF) are not names
+            let preceding = rest[..start].chars().next_back().or_else(|| result.chars().next_back());
+            let after_word = preceding.map(|c| c.is_alphanumeric() || c == '_' || c == '\'').unwrap_or(false);
+
+            let ident_len = rest[start..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len() - start);
+            let ident = &rest[start..start + ident_len];
+            result.push_str(&rest[..start + ident_len]);
+            rest = &rest[start + ident_len..];
+
+            if after_word || !self.symbols.contains_key(ident) {
+                continue;
+            }
+
+            // Chained indexing such as mem(i)(j)
+            while rest.starts_with('(') {
+                let Some(close) = Self::matching_paren(rest, 1) else {
+                    break;
+                };
+                let inner = self.convert_indexing(&rest[1..close]);
+
+                if let Some((left, right)) = inner.split_once(" downto ") {
+                    result.push_str(&format!("[{}:{}]", left.trim(), right.trim()));
+                } else if let Some((left, right)) = inner.split_once(" to ") {
+                    // Ascending ranges are declared as [high:low], so swap the bounds
+                    result.push_str(&format!("[{}:{}]", right.trim(), left.trim()));
+                } else {
+                    result.push_str(&format!("[{}]", inner.trim()));
+                }
+                rest = &rest[close + 1..];
+            }
+        }
+
+        result.push_str(rest);
+        result
+    }
+
+    /// Rewrite `=` to `==` and `/=` to `!=`, leaving `<=` and `>=` as comparisons
+    fn convert_relational(condition: &str) -> String {
+        let chars: Vec<char> = condition.chars().collect();
+        let mut result = String::with_capacity(condition.len() + 4);
+
+        let mut i = 0;
+        while i < chars.len() {
+            let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+            let next = chars.get(i + 1).copied();
+
+            match chars[i] {
+                '/' if next == Some('=') => {
+                    result.push_str("!=");
+                    i += 1;
+                }
+                '=' if !matches!(prev, Some('<' | '>' | '=' | '!' | ':'))
+                    && !matches!(next, Some('=' | '>')) => {
+                    result.push_str("==");
+                }
+                ch => result.push(ch),
+            }
+            i += 1;
+        }
+
+        result
+    }
+
+    /// Width of an assignment target, if it is a known signal or port
+    fn target_width(&self, target: &str) -> Option<u32> {
+        if let Some(width) = self.symbols.get(target).and_then(VHDLType::bit_width) {
+            return Some(width);
+        }
+
+        // Constant slices such as data[7:4]
+        let (name, range) = target.strip_suffix(']')?.split_once('[')?;
+        let (high, low) = range.split_once(':')?;
+        let range = VectorRange::downto(high.trim().parse().ok()?, low.trim().parse().ok()?);
+        self.symbols.contains_key(name.trim()).then(|| range.width()).flatten()
+    }
+
+    fn close_case_item(&self, emitter: &mut BlockEmitter) -> Result<()> {
+        if emitter.top() == Some(BlockKind::CaseItem) {
+            emitter.pop_block()?;
+            emitter.emit_line("end");
+        }
+        Ok(())
+    }
+
+    /// Close the current if branch and open the next one
+    fn reopen_block(&self, emitter: &mut BlockEmitter, branch: &str) -> Result<()> {
+        match emitter.pop_block()? {
+            BlockKind::Begin => {
+                emitter.emit_line(branch);
+                emitter.push_block(BlockKind::Begin);
+            }
+            BlockKind::Elided => emitter.push_block(BlockKind::Elided),
+            kind => {
+                return Err(anyhow::anyhow!("Unbalanced block: 'else' inside a {:?} block", kind));
+            }
+        }
+        Ok(())
+    }
+
+    /// Choices of a `when` line: `when "00" | "11" =>` -> `["\"00\"", "\"11\""]`
+    fn case_choices(line: &str) -> Vec<&str> {
+        let value = match line.find("=>") {
+            Some(value_end) => &line["when ".len()..value_end],
+            None => &line["when ".len()..],
+        };
+        value.split('|').map(str::trim).filter(|choice| !choice.is_empty()).collect()
+    }
+
+    /// Integer bounds of a `2 to 5` / `5 downto 2` choice, low first
+    fn choice_range(choice: &str) -> Option<(i64, i64)> {
+        let (left, right) = choice
+            .split_once(" to ")
+            .or_else(|| choice.split_once(" downto "))?;
+        let (left, right): (i64, i64) = (left.trim().parse().ok()?, right.trim().parse().ok()?);
+        Some((left.min(right), left.max(right)))
+    }
+
+    fn is_dont_care(choice: &str) -> bool {
+        choice.starts_with('"') && choice.contains('-')
+    }
+
+    /// Whether two choices can match the same selector value: overlapping integer ranges,
+    /// bit patterns agreeing on every position that is not a don't-care, or duplicates
+    fn choices_overlap(a: &str, b: &str) -> bool {
+        let interval = |choice: &str| Self::choice_range(choice).or_else(|| choice.parse::<i64>().ok().map(|v| (v, v)));
+        if let (Some((a_low, a_high)), Some((b_low, b_high))) = (interval(a), interval(b)) {
+            return a_low <= b_high && b_low <= a_high;
+        }
+
+        let bits = |choice: &str| {
+            let inner = choice.strip_prefix('"')?.strip_suffix('"')?;
+            inner.chars().all(|c| matches!(c, '0' | '1' | '-')).then_some(inner.to_string())
+        };
+        match (bits(a), bits(b)) {
+            (Some(a), Some(b)) => {
+                a.len() == b.len() && a.chars().zip(b.chars()).all(|(x, y)| x == y || x == '-' || y == '-')
+            }
+            _ => a.eq_ignore_ascii_case(b),
+        }
+    }
+
+    /// `priority case` when any two choices overlap, else `unique case`; `others` never
+    /// overlaps, it only covers the values no other choice matches
+    fn case_type(choices: &[&str]) -> CaseType {
+        let choices: Vec<&str> = choices.iter().copied().filter(|choice| *choice != "others").collect();
+        let overlap = choices.iter().enumerate()
+            .any(|(i, a)| choices[i + 1..].iter().any(|b| Self::choices_overlap(a, b)));
+        if overlap { CaseType::Priority } else { CaseType::Unique }
+    }
+
+    /// Pick the case style and qualifier from the `when` choices in `rest`, the lines
+    /// following the case header
+    fn case_style(&self, rest: &[&str]) -> (CaseStyle, CaseType) {
+        let (mut has_range, mut has_dont_care) = (false, false);
+        let mut choices = Vec::new();
+        let mut depth = 0;
+
+        for line in rest {
+            if line.starts_with("case ") && line.contains(" is") {
+                depth += 1;
+            } else if *line == "end case" || *line == "end case;" {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            } else if depth == 0 && line.starts_with("when ") {
+                for choice in Self::case_choices(line) {
+                    has_range |= Self::choice_range(choice).is_some();
+                    has_dont_care |= Self::is_dont_care(choice);
+                    choices.push(choice);
+                }
+            }
+        }
+
+        let style = if self.dialect.supports_case_inside() && (has_range || has_dont_care) {
+            CaseStyle::Inside
+        } else if has_dont_care {
+            CaseStyle::Casez
+        } else {
+            CaseStyle::Plain
+        };
+        (style, Self::case_type(&choices))
+    }
+
+    /// "when "00" | "11" =>" -> "2'b00, 2'b11: begin", "when IDLE =>" -> "IDLE: begin"
+    fn convert_case_label(&self, line: &str, style: CaseStyle) -> Result<String> {
+        let mut labels = Vec::new();
+
+        for choice in Self::case_choices(line) {
+            if choice == "others" {
+                return Ok("default: begin".to_string());
+            }
+
+            if choice.len() >= 2 && choice.starts_with('"') && choice.ends_with('"') {
+                // Don't-care bits ('-') become '?' wildcards
+                let binary = choice.trim_matches('"').replace('-', "?");
+                labels.push(format!("{}'b{}", binary.len(), binary));
+            } else if let Some((low, high)) = Self::choice_range(choice) {
+                if style == CaseStyle::Inside {
+                    labels.push(format!("[{}:{}]", low, high));
+                } else if high - low < MAX_EXPANDED_CASE_RANGE {
+                    labels.extend((low..=high).map(|value| value.to_string()));
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "Case range '{}' is too large to expand for {}",
+                        choice,
+                        self.dialect.name()
+                    ));
+                }
+            } else {
+                labels.push(self.convert_expression(choice));
+            }
+        }
+
+        Ok(format!("{}: begin", labels.join(", ")))
+    }
+
+    fn strip_then(condition: &str) -> &str {
+        let condition = condition.trim();
+        condition.strip_suffix("then").unwrap_or(condition).trim()
+    }
+
+    /// Parenthesize a condition unless it already is
+    fn wrap_condition(condition: &str) -> String {
+        let condition = condition.trim();
+
+        if Self::is_parenthesized(condition) {
+            condition.to_string()
+        } else {
+            format!("({})", condition)
+        }
+    }
+
+    /// True if the whole expression is enclosed by one pair of parentheses
+    fn is_parenthesized(expr: &str) -> bool {
+        if !expr.starts_with('(') {
+            return false;
+        }
+
+        let mut depth = 0;
+        for (i, ch) in expr.char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i == expr.len() - 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Rewrite VHDL type conversions: `std_logic_vector(...)` is dropped, `signed(...)` and
+    /// `unsigned(...)` become `$signed(...)`/`$unsigned(...)` unless the operand already has
+    /// that signedness, and `to_signed`/`to_unsigned` become sized literals or casts
+    fn convert_type_conversions(&self, line: &str) -> String {
+        let mut result = line.to_string();
+
+        for name in TYPE_CONVERSIONS.iter().copied().chain(std::iter::once("to_integer")) {
+            let pattern = format!("{}(", name);
+            let mut search_from = 0;
+
+            while let Some(offset) = result[search_from..].find(&pattern) {
+                let start = search_from + offset;
+
+                // Only match whole identifiers (e.g. not "signed(" inside "unsigned(" or "$signed(")
+                let preceded_by_ident = result[..start]
+                    .chars()
+                    .next_back()
+                    .map(|c| c.is_alphanumeric() || c == '_' || c == '$')
+                    .unwrap_or(false);
+                if preceded_by_ident {
+                    search_from = start + pattern.len();
+                    continue;
+                }
+
+                let args_start = start + pattern.len();
+                let Some(close) = Self::matching_paren(&result, args_start) else {
+                    break;
+                };
+
+                // Sized conversions take their width as a second argument
+                let args = &result[args_start..close];
+                let (value, width) = match Self::top_level_comma(args) {
+                    Some(comma) => (args[..comma].trim(), Some(args[comma + 1..].trim())),
+                    None => (args.trim(), None),
+                };
+
+                let replacement = match name {
+                    "to_integer" => match self.dialect.integer_cast() {
+                        Some(cast) => format!("{}({})", cast, value),
+                        None => value.to_string(),
+                    },
+                    "to_unsigned" | "to_signed" => {
+                        Self::convert_sized_conversion(value, width.unwrap_or_default(), name == "to_signed")
+                    }
+                    "signed" | "unsigned" => {
+                        let to_signed = name == "signed";
+                        if self.operand_signedness(value) == Some(to_signed) {
+                            value.to_string()
+                        } else {
+                            format!("${}({})", name, value)
+                        }
+                    }
+                    _ => value.to_string(),
+                };
+
+                result.replace_range(start..=close, &replacement);
+                search_from = start;
+            }
+        }
+
+        result
+    }
+
+    /// "to_unsigned(5, 8)" -> "8'd5", "to_signed(-3, 4)" -> "-4'sd3", "to_unsigned(x, 8)" -> "$unsigned(x) /* 8 bits */"
+    fn convert_sized_conversion(value: &str, width: &str, signed: bool) -> String {
+        match value.parse::<i64>() {
+            Ok(literal) if !width.is_empty() => {
+                let sign = if literal < 0 { "-" } else { "" };
+                let base = if signed { "sd" } else { "d" };
+                format!("{}{}'{}{}", sign, width, base, literal.unsigned_abs())
+            }
+            _ => {
+                let cast = if signed { "$signed" } else { "$unsigned" };
+                format!("{}({}) /* {} bits */", cast, value, width)
+            }
+        }
+    }
+
+    /// Signedness of a declared signal or port operand; part-selects are always unsigned
+    fn operand_signedness(&self, operand: &str) -> Option<bool> {
+        let name_end = operand
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(operand.len());
+        let declared = self.symbols.get(&operand[..name_end])?;
+        let rest = &operand[name_end..];
+
+        if rest.is_empty() {
+            Some(match declared {
+                VHDLType::Signed(_) | VHDLType::Integer => true,
+                VHDLType::ConstrainedInteger(range) => range.is_signed(),
+                _ => false,
+            })
+        } else if rest.starts_with('(') && Self::matching_paren(rest, 1) == Some(rest.len() - 1) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    fn matching_paren(text: &str, args_start: usize) -> Option<usize> {
+        let mut depth = 1;
+        for (i, ch) in text[args_start..].char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(args_start + i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn top_level_comma(args: &str) -> Option<usize> {
+        let mut depth = 0;
+        for (i, ch) in args.char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => return Some(i),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Convert string literals in conditions like "00000000" to 8'b00000000
+    fn convert_binary_strings(condition: &str) -> String {
+        if !condition.contains('"') {
+            return condition.to_string();
+        }
+
+        let mut result = String::new();
+        let mut chars = condition.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            if ch == '"' {
+                let mut binary = String::new();
+                while let Some(&next_ch) = chars.peek() {
+                    if next_ch == '"' {
+                        chars.next(); // consume the closing quote
+                        break;
+                    }
+                    if next_ch == '0' || next_ch == '1' {
+                        binary.push(chars.next().unwrap());
+                    } else {
+                        chars.next();
+                    }
+                }
+
+                if !binary.is_empty() {
+                    result.push_str(&format!("{}'b{}", binary.len(), binary));
+                } else {
+                    result.push_str("\"\"");
+                }
+            } else {
+                result.push(ch);
+            }
+        }
+
+        result
+    }
+}
+
+/// Clocked process, judged by a clock name or edge in its sensitivity list, or for
+/// `process(all)` by a clock edge in its body
+pub(crate) fn is_sequential(process: &Process) -> bool {
+    if process.senses_all() {
+        return EDGE_CALL_RE.is_match(&process.body);
+    }
+    process.sensitivity_list.iter()
+        .map(|s| s.to_lowercase())
+        .any(|s| s.contains("clk") || s.contains("clock") || s.contains("rising_edge") || s.contains("falling_edge"))
+}
+
+/// Statements of a process body, one per line, with their trailing comments. Assignments
+/// spanning several lines are joined, their later comments following on lines of their
+/// own, and selected assignments (`with sel select y <= ...`) become case statements
+fn sequential_statements(vhdl_body: &str) -> (Vec<String>, Vec<Option<&str>>) {
+    let mut statements = Vec::new();
+    let mut comments = Vec::new();
+    let mut pending: Option<(String, Vec<&str>)> = None;
+
+    for line in vhdl_body.lines() {
+        let (code, comment) = split_comment(line.trim());
+        let (statement, statement_comments) = match pending.take() {
+            Some((mut statement, mut statement_comments)) => {
+                if !code.is_empty() {
+                    statement.push(' ');
+                    statement.push_str(code);
+                }
+                statement_comments.extend(comment);
+                (statement, statement_comments)
+            }
+            None if is_unterminated_assignment(code) => (code.to_string(), comment.into_iter().collect()),
+            None => {
+                statements.push(code.to_string());
+                comments.push(comment);
+                continue;
+            }
+        };
+        if !statement.ends_with(';') {
+            pending = Some((statement, statement_comments));
+            continue;
+        }
+
+        let expanded = selected_assignment_case(&statement).unwrap_or_else(|| vec![statement]);
+        comments.resize(comments.len() + expanded.len(), None);
+        statements.extend(expanded);
+        for comment in statement_comments {
+            statements.push(String::new());
+            comments.push(Some(comment));
+        }
+    }
+    if let Some((statement, statement_comments)) = pending {
+        statements.push(statement);
+        comments.push(statement_comments.first().copied());
+    }
+
+    (statements, comments)
+}
+
+/// Position and operator of the first ` <= ` or ` := ` in a sequential statement,
+/// its assignment; later `<=` are comparisons
+fn assignment_operator(statement: &str) -> Option<(usize, &'static str)> {
+    [" <= ", " := "].iter()
+        .filter_map(|op| statement.find(op).map(|pos| (pos, op.trim())))
+        .min()
+}
+
+/// Verilog operator for a VHDL assignment operator: variables are always assigned
+/// with blocking `=`, signals with `<=` unless `blocking` is set
+fn hdl_assignment_operator(op: &str, blocking: bool) -> &'static str {
+    if op == ":=" || blocking {
+        "="
+    } else {
+        "<="
+    }
+}
+
+/// The first line of an assignment or selected assignment continued on the next line
+fn is_unterminated_assignment(code: &str) -> bool {
+    if code.is_empty() || code.ends_with(';') {
+        return false;
+    }
+    let first_word = code.split(|c: char| !(c.is_alphanumeric() || c == '_')).next().unwrap_or("").to_lowercase();
+    match first_word.as_str() {
+        "with" => true,
+        "if" | "elsif" | "else" | "when" | "case" | "for" | "while" | "loop" | "end" => false,
+        _ => code.contains("<=") || code.contains(":="),
+    }
+}
+
+/// "with sel select y <= a when "0", b when others;" as the lines of the equivalent
+/// case statement, `None` for any other statement
+fn selected_assignment_case(statement: &str) -> Option<Vec<String>> {
+    let caps = SELECTED_ASSIGNMENT_RE.captures(statement)?;
+    let mut lines = vec![format!("case {} is", &caps[1])];
+    for alternative in split_top_level(&caps[4], ',') {
+        let (value, choices) = alternative.rsplit_once(" when ")?;
+        lines.push(format!("when {} =>", choices.trim()));
+        lines.push(format!("{} {} {};", &caps[2], &caps[3], value.trim()));
+    }
+    lines.push("end case;".to_string());
+    Some(lines)
+}
+
+/// Split `text` at each `separator` outside parentheses and string literals
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut in_string, mut start) = (0i32, false, 0);
+    for (i, ch) in text.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => depth -= 1,
+            c if c == separator && depth == 0 && !in_string => {
+                parts.push(text[start..i].trim());
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+    parts
+}
+
+/// Split a VHDL line into code and the text of a trailing `--` comment,
+/// ignoring dashes inside string and character literals
+pub(crate) fn split_comment(line: &str) -> (&str, Option<&str>) {
+    let bytes = line.as_bytes();
+    let mut in_string = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => in_string = !in_string,
+            // Character literal such as '-'
+            b'\'' if !in_string && bytes.get(i + 2) == Some(&b'\'') => i += 2,
+            b'-' if !in_string && bytes.get(i + 1) == Some(&b'-') => {
+                return (line[..i].trim_end(), Some(line[i + 2..].trim()));
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    (line, None)
+}
+
+/// Turn the text of a VHDL comment into a `//` comment, mapping synthesis pragmas
+pub(crate) fn convert_comment(text: &str) -> String {
+    let text = text.trim();
+
+    if let Some(caps) = TRANSLATE_PRAGMA_RE.captures(text) {
+        format!("// synthesis translate_{}", caps[1].to_lowercase())
+    } else if KEEP_PRAGMA_RE.is_match(text) {
+        "/* synthesis keep */".to_string()
+    } else if text.is_empty() {
+        "//".to_string()
+    } else {
+        format!("// {}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARITY_BODY: &str = r#"
+        if (enable = '1' and mode /= '0') then
+            data_out <= std_logic_vector(unsigned(data_in) + to_unsigned(1, 8));
+            index <= to_integer(unsigned(addr));
+        elsif load = '1' then
+            data_out <= (others => '0');
+        else
+            data_out <= (others => '1');
+        end if;
+    "#;
+
+    fn vector(width: i32) -> VHDLType {
+        VHDLType::StdLogicVector(crate::ir::VectorRange { left: (width - 1).into(), right: 0.into(), downto: true })
+    }
+
+    fn converters() -> (HdlBodyConverter<VerilogDialect>, HdlBodyConverter<SystemVerilogDialect>) {
+        let symbols = HashMap::from([
+            ("data_out".to_string(), vector(8)),
+            ("data_in".to_string(), vector(8)),
+            ("addr".to_string(), vector(4)),
+        ]);
+        (
+            HdlBodyConverter::new(VerilogDialect, "    ".to_string()).with_symbols(symbols.clone()),
+            HdlBodyConverter::new(SystemVerilogDialect, "    ".to_string()).with_symbols(symbols),
+        )
+    }
+
+    #[test]
+    fn test_dialects_share_body_structure() {
+        let (verilog, sv) = converters();
+        let verilog_body = verilog.convert_process_body(PARITY_BODY).unwrap();
+        let sv_body = sv.convert_process_body(PARITY_BODY).unwrap();
+
+        assert_eq!(
+            verilog_body,
+            [
+                "        if (enable == 1'b1 & mode != 1'b0) begin",
+                "            data_out <= data_in + 8'd1;",
+                "            index <= addr;",
+                "        end else if (load == 1'b1) begin",
+                "            data_out <= {8{1'b0}};",
+                "        end else begin",
+                "            data_out <= {8{1'b1}};",
+                "        end",
+                "",
+            ].join("\n")
+        );
+
+        // Only the dialect hooks differ between the two outputs
+        let expected_sv = verilog_body
+            .replace("index <= addr;", "index <= int'(addr);")
+            .replace("{8{1'b0}}", "'0")
+            .replace("{8{1'b1}}", "'1");
+        assert_eq!(sv_body, expected_sv);
+    }
+
+    #[test]
+    fn test_process_header_per_dialect() {
+        let (verilog, sv) = converters();
+        let process = Process {
+            label: None,
+            sensitivity_list: vec!["rst".to_string(), "clk".to_string()],
+            body: "if rst = '1' then\nq <= '0';\nelsif rising_edge(clk) then\nq <= d;\nend if;".to_string(),
+            source_line: None,
+            testbench_style: false,
+        };
+
+        let verilog_process = verilog.convert_process(&process).unwrap();
+        let sv_process = sv.convert_process(&process).unwrap();
+
+        assert!(verilog_process.starts_with("    always @(posedge clk or posedge rst) begin\n"));
+        assert!(sv_process.starts_with("    always_ff @(posedge clk or posedge rst) begin\n"));
+        assert_eq!(
+            verilog_process.lines().skip(1).collect::<Vec<_>>(),
+            sv_process.lines().skip(1).collect::<Vec<_>>()
+        );
+
+        let comb = Process {
+            label: None,
+            sensitivity_list: vec!["a".to_string()],
+            body: "case a is\nwhen \"0\" =>\ny <= '1';\nwhen others =>\ny <= '0';\nend case;".to_string(),
+            source_line: None,
+            testbench_style: false,
+        };
+        assert!(verilog.convert_process(&comb).unwrap().contains("always @(*) begin\n        case (a)\n"));
+        assert!(sv.convert_process(&comb).unwrap().contains("always_comb begin\n        unique case (a)\n"));
+    }
+
+    #[test]
+    fn test_process_all_sensitivity() {
+        let (verilog, sv) = converters();
+        let process = |body: &str| Process {
+            label: None,
+            sensitivity_list: vec![crate::ir::SENSITIVITY_ALL.to_string()],
+            body: body.to_string(),
+            source_line: None,
+            testbench_style: false,
+        };
+
+        // Combinational even though no signal names are listed
+        let comb = process("if sel = '1' then\ny <= a;\nelse\ny <= b;\nend if;");
+        assert!(verilog.convert_process(&comb).unwrap().starts_with("    always @(*) begin\n        if (sel == 1'b1) begin\n"));
+        assert!(sv.convert_process(&comb).unwrap().starts_with("    always_comb begin\n"));
+
+        // Clocked: clock and asynchronous reset come from the body
+        let clocked = process("if rst_n = '0' then\nq <= '0';\nelsif rising_edge(clk) then\nq <= d;\nend if;");
+        assert!(verilog.convert_process(&clocked).unwrap().starts_with("    always @(posedge clk or negedge rst_n) begin\n"));
+        assert!(sv.convert_process(&clocked).unwrap().starts_with("    always_ff @(posedge clk or negedge rst_n) begin\n"));
+
+        // `if rising_edge(clk)` without an elsif: no reset, and an and-ed enable is kept
+        let enabled = process("if rising_edge(clk) and en = '1' then\nq <= d;\nend if;");
+        assert_eq!(
+            sv.convert_process(&enabled).unwrap(),
+            "    always_ff @(posedge clk) begin\n        if (en == 1'b1) begin\n            q <= d;\n        end\n    end\n"
+        );
+        assert_eq!(
+            verilog.convert_process(&process("if rising_edge(clk) then\nq <= d;\nend if;")).unwrap(),
+            "    always @(posedge clk) begin\n        q <= d;\n    end\n"
+        );
+    }
+
+    #[test]
+    fn test_blocking_assignments_in_combinational_blocks() {
+        let (verilog, sv) = converters();
+        let process = |sensitivity: &[&str], body: &str| Process {
+            label: None,
+            sensitivity_list: sensitivity.iter().map(|s| s.to_string()).collect(),
+            body: body.to_string(),
+            source_line: None,
+            testbench_style: false,
+        };
+
+        // A mux: `=` in nested ifs and case branches, and `:=` is always blocking
+        let mux = process(
+            &["sel", "a", "b"],
+            "tmp := a;\ncase sel is\nwhen \"00\" =>\nif en = '1' then\ny <= tmp;\nelse\ny <= b;\nend if;\nwhen others =>\ny <= a when b <= a else b;\nend case;",
+        );
+        let body = [
+            "        tmp = a;",
+            "        case (sel)",
+            "            2'b00: begin",
+            "                if (en == 1'b1) begin",
+            "                    y = tmp;",
+            "                end else begin",
+            "                    y = b;",
+            "                end",
+            "            end",
+            "            default: begin",
+            "                y = (b <= a) ? a : b;",
+            "            end",
+            "        endcase",
+            "    end",
+            "",
+        ].join("\n");
+        assert_eq!(verilog.convert_process(&mux).unwrap(), format!("    always @(*) begin\n{}", body));
+        assert_eq!(
+            sv.convert_process(&mux).unwrap(),
+            format!("    always_comb begin\n{}", body.replace("        case (sel)", "        unique case (sel)"))
+        );
+
+        // A register keeps `<=`
+        let register = process(&["clk"], "if rising_edge(clk) then\ncount := count + 1;\nq <= d;\nend if;");
+        let body = "        count = count + 1;\n        q <= d;\n    end\n";
+        assert_eq!(verilog.convert_process(&register).unwrap(), format!("    always @(posedge clk) begin\n{}", body));
+        assert_eq!(sv.convert_process(&register).unwrap(), format!("    always_ff @(posedge clk) begin\n{}", body));
+
+        // Literal translation keeps `<=` in combinational blocks too
+        let literal = HdlBodyConverter::new(SystemVerilogDialect, "    ".to_string()).with_blocking_assignments(false);
+        assert!(literal.convert_process(&mux).unwrap().contains("                    y <= tmp;\n"));
+        assert!(literal.convert_process(&mux).unwrap().contains("        tmp = a;\n"));
+    }
+
+    #[test]
+    fn test_conditional_and_selected_assignments_in_process() {
+        let (verilog, sv) = converters();
+        let body = "y <= a when sel = '1' else b when en = '1' else '0';\n\
+                    with addr select\n    \
+                        z <= a when \"0000\",\n         \
+                             b when \"0001\" | \"0010\", -- pair\n         \
+                             '0' when others;";
+        let expected = [
+            "        y <= (sel == 1'b1) ? a : (en == 1'b1) ? b : 1'b0;",
+            "        CASE (addr)",
+            "            4'b0000: begin",
+            "                z <= a;",
+            "            end",
+            "            4'b0001, 4'b0010: begin",
+            "                z <= b;",
+            "            end",
+            "            default: begin",
+            "                z <= 1'b0;",
+            "            end",
+            "        endcase",
+            "        // pair",
+            "",
+        ].join("\n");
+
+        assert_eq!(verilog.convert_process_body(body).unwrap(), expected.replace("CASE", "case"));
+        assert_eq!(sv.convert_process_body(body).unwrap(), expected.replace("CASE", "unique case"));
+        assert_eq!(
+            sv.convert_concurrent_statement("y <= a when sel = '1' else b when en = '1' else '0';").unwrap(),
+            "assign y = (sel == 1'b1) ? a : (en == 1'b1) ? b : 1'b0;"
+        );
+    }
+
+    #[test]
+    fn test_reduction_operators() {
+        let (verilog, sv) = converters();
+        let body = "if or data_in then\nparity <= xor data_in;\nelsif (and addr) = '1' then\nall_set <= nor data_in(3 downto 0);\nend if;";
+        let expected = [
+            "        if (|data_in) begin",
+            "            parity <= ^data_in;",
+            "        end else if ((&addr) == 1'b1) begin",
+            "            all_set <= ~|data_in[3:0];",
+            "        end",
+            "",
+        ].join("\n");
+        assert_eq!(verilog.convert_process_body(body).unwrap(), expected);
+        assert_eq!(sv.convert_process_body(body).unwrap(), expected);
+        assert_eq!(verilog.convert_concurrent_statement("any <= or data_in;").unwrap(), "assign any = |data_in;");
+    }
+
+    #[test]
+    fn test_others_aggregate_uses_target_width() {
+        let symbols = HashMap::from([
+            ("nibble".to_string(), vector(4)),
+            ("byte".to_string(), vector(8)),
+            ("word".to_string(), vector(16)),
+            ("state".to_string(), VHDLType::Custom("state_t".to_string())),
+        ]);
+        let body = "nibble <= (others => '0');\nbyte <= (others => '1');\nword <= (others => '0');\nstate <= (others => '1');";
+
+        let verilog = HdlBodyConverter::new(VerilogDialect, "    ".to_string())
+            .with_symbols(symbols.clone())
+            .convert_process_body(body)
+            .unwrap();
+        let lines: Vec<&str> = verilog.lines().map(str::trim).collect();
+        assert_eq!(lines, [
+            "nibble <= {4{1'b0}};",
+            "byte <= {8{1'b1}};",
+            "word <= {16{1'b0}};",
+            "state <= ~0;",
+        ]);
+
+        let sv = HdlBodyConverter::new(SystemVerilogDialect, "    ".to_string())
+            .with_symbols(symbols)
+            .convert_process_body(body)
+            .unwrap();
+        let lines: Vec<&str> = sv.lines().map(str::trim).collect();
+        assert_eq!(lines, ["nibble <= '0;", "byte <= '1;", "word <= '0;", "state <= '1;"]);
+    }
+
+    #[test]
+    fn test_aggregates_in_processes_and_concurrent_statements() {
+        let symbols = HashMap::from([
+            ("data".to_string(), vector(32)),
+            ("flags".to_string(), vector(8)),
+        ]);
+        let sv = HdlBodyConverter::new(SystemVerilogDialect, "    ".to_string()).with_symbols(symbols);
+
+        let body = sv.convert_process_body("data <= (byte3, byte2, byte1, byte0);\nflags <= (7 => high_bit, others => '0'); -- sticky").unwrap();
+        let lines: Vec<&str> = body.lines().map(str::trim).collect();
+        assert_eq!(lines, [
+            "data <= {byte3, byte2, byte1, byte0};",
+            "flags <= {8{1'b0}};",
+            "flags[7] <= high_bit; // sticky",
+        ]);
+
+        assert_eq!(
+            sv.convert_concurrent_statement("data <= (byte3, byte2, byte1, byte0);").unwrap(),
+            "assign data = {byte3, byte2, byte1, byte0};"
+        );
+        assert_eq!(
+            sv.convert_concurrent_statement("flags <= (0 => '1', others => en);").unwrap(),
+            "always_comb begin\n        flags = {8{en}};\n        flags[0] = 1'b1;\n    end"
+        );
+        assert!(sv.convert_process_body("unknown <= (others => en);").is_err());
+    }
+
+    #[test]
+    fn test_relational_operators_in_conditions() {
+        let (verilog, _) = converters();
+        let body = "if a /= b then\nx <= '1';\nelsif count <= 10 then\nx <= '0';\nelsif count >= 20 then\nx <= y;\nend if;";
+
+        let output = verilog.convert_process_body(body).unwrap();
+        let lines: Vec<&str> = output.lines().map(str::trim).collect();
+        assert_eq!(lines, [
+            "if (a != b) begin",
+            "x <= 1'b1;",
+            "end else if (count <= 10) begin",
+            "x <= 1'b0;",
+            "end else if (count >= 20) begin",
+            "x <= y;",
+            "end",
+        ]);
+    }
+
+    #[test]
+    fn test_comparison_inside_conditional_assignment() {
+        let (verilog, sv) = converters();
+        let stmt = "full <= '1' when count <= 10 and mode = '0' else '0';";
+
+        let expected = "assign full = (count <= 10 & mode == 1'b0) ? 1'b1 : 1'b0;";
+        assert_eq!(verilog.convert_concurrent_statement(stmt).unwrap(), expected);
+        assert_eq!(sv.convert_concurrent_statement(stmt).unwrap(), expected);
+
+        assert_eq!(
+            verilog.convert_concurrent_statement("y <= a and not_b;").unwrap(),
+            "assign y = a & not_b;"
+        );
+    }
+
+    #[test]
+    fn test_slices_and_indexing() {
+        let symbols = HashMap::from([
+            ("data_out".to_string(), vector(8)),
+            ("vec".to_string(), vector(4)),
+            ("sel".to_string(), vector(2)),
+            ("asc".to_string(), VHDLType::StdLogicVector(crate::ir::VectorRange { left: 0.into(), right: 7.into(), downto: false })),
+        ]);
+        let converter = HdlBodyConverter::new(VerilogDialect, "    ".to_string()).with_symbols(symbols);
+        let body = [
+            "data_out(7 downto 4) <= vec;",
+            "data_out(0) <= vec(3);",
+            "data_out(3 downto 0) <= (others => '0');",
+            "asc(0 to 3) <= vec(to_integer(unsigned(sel(1 downto 0))) );",
+            "if rising_edge(clk) then",
+            "data_out(1) <= some_func(vec(2));",
+            "end if;",
+        ].join("\n");
+
+        let output = converter.convert_process_body(&body).unwrap();
+        let lines: Vec<&str> = output.lines().map(str::trim).collect();
+        assert_eq!(lines, [
+            "data_out[7:4] <= vec;",
+            "data_out[0] <= vec[3];",
+            "data_out[3:0] <= {4{1'b0}};",
+            "asc[3:0] <= vec[sel[1:0]];",
+            "data_out[1] <= some_func(vec[2]);",
+        ]);
+
+        assert_eq!(
+            converter.convert_concurrent_statement("vec(1) <= data_out(7) when sel(0) = '1' else '0';").unwrap(),
+            "assign vec[1] = (sel[0] == 1'b1) ? data_out[7] : 1'b0;"
+        );
+        // No condition between `when` and `else`: not a conditional assignment, and no panic
+        assert!(converter.convert_concurrent_statement("y <= a when else b;").is_ok());
+    }
+
+    #[test]
+    fn test_numeric_std_casts_keep_signedness() {
+        let symbols = HashMap::from([
+            ("count_reg".to_string(), vector(8)),
+            ("a".to_string(), vector(8)),
+            ("b".to_string(), vector(8)),
+            ("sa".to_string(), VHDLType::Signed(crate::ir::VectorRange { left: 7.into(), right: 0.into(), downto: true })),
+            ("sb".to_string(), VHDLType::Signed(crate::ir::VectorRange { left: 7.into(), right: 0.into(), downto: true })),
+            ("product".to_string(), vector(16)),
+        ]);
+        let converter = HdlBodyConverter::new(VerilogDialect, "    ".to_string()).with_symbols(symbols);
+        let body = [
+            "count_reg <= std_logic_vector(unsigned(count_reg) + 1);",
+            "product <= std_logic_vector(signed(a) * signed(b));",
+            "product <= std_logic_vector(signed(sa) * signed(sb));",
+            "if unsigned(sa) > unsigned(sb) then",
+            "count_reg <= std_logic_vector(to_signed(-3, 8));",
+            "elsif unsigned(a) > unsigned(b(3 downto 0)) then",
+            "count_reg <= std_logic_vector(to_unsigned(idx, 8));",
+            "end if;",
+        ].join("\n");
+
+        let output = converter.convert_process_body(&body).unwrap();
+        let lines: Vec<&str> = output.lines().map(str::trim).collect();
+        assert_eq!(lines, [
+            "count_reg <= count_reg + 1;",
+            "product <= $signed(a) * $signed(b);",
+            "product <= sa * sb;",
+            "if ($unsigned(sa) > $unsigned(sb)) begin",
+            "count_reg <= -8'sd3;",
+            "end else if (a > b[3:0]) begin",
+            "count_reg <= $unsigned(idx) /* 8 bits */;",
+            "end",
+        ]);
+    }
+
+    #[test]
+    fn test_case_choice_forms() {
+        let (verilog, sv) = converters();
+        let body = [
+            "case sel is",
+            "when \"00\" | \"11\" =>",
+            "y <= '1';",
+            "when others =>",
+            "y <= '0';",
+            "end case;",
+            "case count is",
+            "when 2 to 5 =>",
+            "y <= '1';",
+            "when 7 =>",
+            "y <= '0';",
+            "end case;",
+            "case opcode is",
+            "when \"1--\" =>",
+            "y <= '1';",
+            "when others =>",
+            "y <= '0';",
+            "end case;",
+        ].join("\n");
+
+        let verilog_body = verilog.convert_process_body(&body).unwrap();
+        let verilog_lines: Vec<&str> = verilog_body.lines().map(str::trim).collect();
+        assert!(verilog_lines.contains(&"case (sel)"));
+        assert!(verilog_lines.contains(&"2'b00, 2'b11: begin"));
+        assert!(verilog_lines.contains(&"case (count)"));
+        assert!(verilog_lines.contains(&"2, 3, 4, 5: begin"));
+        assert!(verilog_lines.contains(&"casez (opcode)"));
+        assert!(verilog_lines.contains(&"3'b1??: begin"));
+
+        let sv_body = sv.convert_process_body(&body).unwrap();
+        let sv_lines: Vec<&str> = sv_body.lines().map(str::trim).collect();
+        assert!(sv_lines.contains(&"unique case (sel)"));
+        assert!(sv_lines.contains(&"2'b00, 2'b11: begin"));
+        assert!(sv_lines.contains(&"unique case (count) inside"));
+        assert!(sv_lines.contains(&"[2:5]: begin"));
+        assert!(sv_lines.contains(&"unique case (opcode) inside"));
+        assert!(sv_lines.contains(&"3'b1??: begin"));
+
+        let huge = "case count is\nwhen 0 to 1000 =>\ny <= '1';\nend case;";
+        assert!(verilog.convert_process_body(huge).is_err());
+        assert!(sv.convert_process_body(huge).is_ok());
+    }
+
+    #[test]
+    fn test_unique_and_priority_case() {
+        let (verilog, sv) = converters();
+        let header = |body: &str| sv.convert_process_body(body).unwrap().lines().next().unwrap().trim().to_string();
+
+        // 3-bit mux: distinct literals, no others
+        let mux = (0..8)
+            .map(|i| format!("when \"{:03b}\" => y <= d({});", i, i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mux = format!("case sel is\n{}\nend case;", mux);
+        assert_eq!(header(&mux), "unique case (sel)");
+
+        // Priority encoder whose patterns overlap: the first match wins
+        let encoder = [
+            "case req is",
+            "when \"1---\" => grant <= \"11\";",
+            "when \"-1--\" => grant <= \"10\";",
+            "when \"--1-\" => grant <= \"01\";",
+            "when others => grant <= \"00\";",
+            "end case;",
+        ].join("\n");
+        assert_eq!(header(&encoder), "priority case (req) inside");
+        assert!(verilog.convert_process_body(&encoder).unwrap().trim_start().starts_with("casez (req)\n"));
+
+        let ranges = "case count is\nwhen 0 to 5 =>\ny <= '1';\nwhen 4 =>\ny <= '0';\nend case;";
+        assert_eq!(header(ranges), "priority case (count) inside");
+
+        // A lone default covers every value
+        assert_eq!(header("case sel is\nwhen others =>\ny <= a;\nend case;"), "unique case (sel)");
+    }
+
+    #[test]
+    fn test_port_map_associations() {
+        let (_, sv) = converters();
+        let inst = Instantiation {
+            label: "u_ctrl".to_string(),
+            component: "ctrl".to_string(),
+            generic_map: vec![Association::named("DEPTH", "16")],
+            port_map: vec![
+                Association::named("clk", "clk"),
+                Association::named("irq", "open"),
+                Association::named("mode", "\"01\""),
+                Association::named("dout", "data_out(7 downto 4)"),
+                Association::named("din", "std_logic_vector(data_in)"),
+            ],
+            architecture: None,
+            configuration: None,
+        };
+
+        assert_eq!(
+            sv.convert_instantiation(&inst, 1),
+            [
+                "    ctrl #(.DEPTH(16)) u_ctrl (",
+                "        .clk(clk),",
+                "        .irq(),",
+                "        .mode(2'b01),",
+                "        .dout(data_out[7:4]),",
+                "        .din(data_in)",
+                "    );",
+                "",
+            ].join("\n")
+        );
+    }
+
+    #[test]
+    fn test_comment_splitting_and_pragmas() {
+        assert_eq!(split_comment("q <= '0'; -- clear"), ("q <= '0';", Some("clear")));
+        assert_eq!(split_comment("s <= \"--\" & '-';"), ("s <= \"--\" & '-';", None));
+        assert_eq!(split_comment("--"), ("", Some("")));
+
+        assert_eq!(convert_comment(" pragma translate_off"), "// synthesis translate_off");
+        assert_eq!(convert_comment("synthesis TRANSLATE_ON"), "// synthesis translate_on");
+        assert_eq!(convert_comment("synthesis keep"), "/* synthesis keep */");
+        assert_eq!(convert_comment("plain note"), "// plain note");
+    }
+}
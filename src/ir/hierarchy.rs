@@ -0,0 +1,212 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::ir::{Entity, GenerateBlock, Instantiation};
+
+/// Entity in a design hierarchy with the entities it instantiates
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HierarchyNode {
+    pub name: String,
+    /// Instance label in the parent; `None` for the top entity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Port count, or `None` when the component has no entity among the parsed sources
+    pub ports: Option<usize>,
+    pub children: Vec<HierarchyNode>,
+}
+
+impl HierarchyNode {
+    /// Indented text tree, one instance per line
+    pub fn summary(&self) -> String {
+        let mut output = String::new();
+        self.write_summary(0, &mut output);
+        output
+    }
+
+    /// Names of the entities found below and including this one, each after the entities
+    /// it instantiates, without repeats
+    pub fn dependency_order(&self) -> Vec<String> {
+        let mut order = Vec::new();
+        self.collect_dependencies(&mut order);
+        order
+    }
+
+    fn collect_dependencies(&self, order: &mut Vec<String>) {
+        for child in &self.children {
+            child.collect_dependencies(order);
+        }
+        if self.ports.is_some() && !order.iter().any(|name| name.eq_ignore_ascii_case(&self.name)) {
+            order.push(self.name.clone());
+        }
+    }
+
+    /// Instances whose component has no entity, as `parent/instance: component`
+    pub fn unresolved_instances(&self) -> Vec<String> {
+        let mut unresolved = Vec::new();
+        for child in &self.children {
+            if child.ports.is_none() {
+                unresolved.push(format!("{}/{}: {}", self.name, child.instance.as_deref().unwrap_or_default(), child.name));
+            }
+            unresolved.extend(child.unresolved_instances());
+        }
+        unresolved
+    }
+
+    fn write_summary(&self, depth: usize, output: &mut String) {
+        output.push_str(&"  ".repeat(depth));
+        if let Some(instance) = &self.instance {
+            output.push_str(&format!("{}: ", instance));
+        }
+        match self.ports {
+            Some(ports) => output.push_str(&format!("{} ({} ports)\n", self.name, ports)),
+            None => output.push_str(&format!("{} (not found)\n", self.name)),
+        }
+        for child in &self.children {
+            child.write_summary(depth + 1, output);
+        }
+    }
+}
+
+/// Trace component instantiations from `top` through `entities`, matching names
+/// case-insensitively; fails on unknown top entities and instantiation cycles
+pub fn build_hierarchy(entities: &[Entity], top: &str) -> Result<HierarchyNode> {
+    let entity = find_entity(entities, top)
+        .ok_or_else(|| anyhow::anyhow!("Top entity '{}' not found", top))?;
+    let mut stack = Vec::new();
+    build_node(entities, entity, None, &mut stack)
+}
+
+fn find_entity<'a>(entities: &'a [Entity], name: &str) -> Option<&'a Entity> {
+    entities.iter().find(|e| e.name.eq_ignore_ascii_case(name))
+}
+
+fn build_node(entities: &[Entity], entity: &Entity, instance: Option<String>, stack: &mut Vec<String>) -> Result<HierarchyNode> {
+    if let Some(pos) = stack.iter().position(|name| name.eq_ignore_ascii_case(&entity.name)) {
+        let mut cycle = stack[pos..].to_vec();
+        cycle.push(entity.name.clone());
+        return Err(anyhow::anyhow!("Instantiation cycle: {}", cycle.join(" -> ")));
+    }
+    stack.push(entity.name.clone());
+
+    let mut instantiations = Vec::new();
+    if let Some(arch) = &entity.architecture {
+        instantiations.extend(&arch.instantiations);
+        collect_generate_instantiations(&arch.generate_blocks, &mut instantiations);
+    }
+
+    let mut children = Vec::new();
+    for inst in instantiations {
+        let child = match find_entity(entities, &inst.component) {
+            // A bound architecture decides which instances the child has in turn
+            Some(child) if inst.architecture.is_some() => {
+                let mut bound = child.clone();
+                bound.select_architecture(inst.architecture.as_deref().unwrap_or_default());
+                build_node(entities, &bound, Some(inst.label.clone()), stack)?
+            }
+            Some(child) => build_node(entities, child, Some(inst.label.clone()), stack)?,
+            None => HierarchyNode {
+                name: inst.component.clone(),
+                instance: Some(inst.label.clone()),
+                ports: None,
+                children: vec![],
+            },
+        };
+        children.push(child);
+    }
+
+    stack.pop();
+    Ok(HierarchyNode {
+        name: entity.name.clone(),
+        instance,
+        ports: Some(entity.ports.len()),
+        children,
+    })
+}
+
+fn collect_generate_instantiations<'a>(blocks: &'a [GenerateBlock], out: &mut Vec<&'a Instantiation>) {
+    for block in blocks {
+        out.extend(&block.instantiations);
+        collect_generate_instantiations(&block.generate_blocks, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Architecture, Port, PortDirection, VHDLType};
+
+    fn entity(name: &str, ports: usize, components: &[(&str, &str)]) -> Entity {
+        let mut entity = Entity::new(name.to_string());
+        for i in 0..ports {
+            entity.add_port(Port::new(format!("p{}", i), PortDirection::In, VHDLType::StdLogic));
+        }
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![],
+            concurrent_statements: vec![],
+            instantiations: components.iter()
+                .map(|(label, component)| Instantiation {
+                    label: label.to_string(),
+                    component: component.to_string(),
+                    generic_map: vec![],
+                    port_map: vec![],
+                    architecture: None,
+                    configuration: None,
+                })
+                .collect(),
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    #[test]
+    fn test_three_level_hierarchy() {
+        let entities = vec![
+            entity("full_adder", 5, &[("ha0", "half_adder"), ("ha1", "HALF_ADDER")]),
+            entity("half_adder", 4, &[]),
+            entity("top", 3, &[("u_add", "full_adder"), ("u_pll", "vendor_pll")]),
+        ];
+
+        let tree = build_hierarchy(&entities, "top").unwrap();
+        assert_eq!(serde_json::to_value(&tree).unwrap(), serde_json::json!({
+            "name": "top",
+            "ports": 3,
+            "children": [
+                {
+                    "name": "full_adder",
+                    "instance": "u_add",
+                    "ports": 5,
+                    "children": [
+                        {"name": "half_adder", "instance": "ha0", "ports": 4, "children": []},
+                        {"name": "half_adder", "instance": "ha1", "ports": 4, "children": []}
+                    ]
+                },
+                {"name": "vendor_pll", "instance": "u_pll", "ports": null, "children": []}
+            ]
+        }));
+        assert_eq!(
+            tree.summary(),
+            "top (3 ports)\n  u_add: full_adder (5 ports)\n    ha0: half_adder (4 ports)\n    ha1: half_adder (4 ports)\n  u_pll: vendor_pll (not found)\n"
+        );
+        assert_eq!(tree.dependency_order(), vec!["half_adder", "full_adder", "top"]);
+        assert_eq!(tree.unresolved_instances(), vec!["top/u_pll: vendor_pll"]);
+    }
+
+    #[test]
+    fn test_cycles_and_missing_top_are_errors() {
+        let entities = vec![
+            entity("a", 1, &[("u_b", "b")]),
+            entity("b", 1, &[("u_c", "c")]),
+            entity("c", 1, &[("u_a", "a")]),
+        ];
+
+        let err = build_hierarchy(&entities, "a").unwrap_err();
+        assert_eq!(err.to_string(), "Instantiation cycle: a -> b -> c -> a");
+
+        let err = build_hierarchy(&entities, "soc").unwrap_err();
+        assert_eq!(err.to_string(), "Top entity 'soc' not found");
+    }
+}
@@ -1,8 +1,36 @@
+pub mod clocking;
+pub mod constraints;
+pub mod emitter;
+pub mod expression_converter;
+pub mod formatter;
+#[cfg(test)]
+mod golden;
+pub mod hdl_body;
+pub mod hierarchy;
 pub mod model;
+pub mod quality;
+pub mod report;
+pub mod sanitize;
+pub mod source_map;
 pub mod verilog_gen;  // Keep for backward compatibility
 pub mod systemverilog_gen;
+pub mod spinalhdl_gen;
+pub mod testbench;
+pub mod type_resolution;
 
-pub use model::{Entity, Port, PortDirection, VHDLType, VectorRange, Generic, Architecture, Signal, Process};
+pub use model::{Entity, Port, PortDirection, VHDLType, VectorRange, RangeExpr, Generic, Architecture, Signal, Process, Instantiation, Association, Actual, GenerateBlock, GenerateScheme, IntegerRange, SENSITIVITY_ALL};
+pub use clocking::{analyze_clocking, inferred_latches, ClockEdge, EntityClocking, ProcessClocking, ResetInfo, ResetPolarity, ResetStyle};
+pub use constraints::{ClockConstraint, DEFAULT_CLOCK_PERIOD_NS};
+pub use expression_converter::{convert_aggregate_assignment, convert_aggregate_expression, parse_aggregate, Aggregate};
+pub use formatter::HdlFormatter;
+pub use hierarchy::{build_hierarchy, HierarchyNode};
+pub use quality::{CheckOutcome, QualityCheck, QualityReport, TranspileQualityChecker};
+pub use report::{ConversionReport, PortMapping, ProcessClassification, ProcessKind, TranspilationWarning, TypeMapping};
+pub use sanitize::{Rename, RenameReason};
+pub use source_map::{SourceMap, SourceMapEntry, SourceMapOutput};
 pub use systemverilog_gen::SystemVerilogGenerator;
+pub use spinalhdl_gen::SpinalHDLGenerator;
+pub use testbench::TestbenchGenerator;
+pub use type_resolution::TypeResolutionContext;
 // VerilogGenerator still available if needed for legacy code
-pub use verilog_gen::VerilogGenerator;
\ No newline at end of file
+pub use verilog_gen::{VerilogGenerator, VerilogStyle};
\ No newline at end of file
@@ -1,4 +1,8 @@
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::ir::type_resolution::TypeResolutionContext;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PortDirection {
@@ -27,16 +31,104 @@ impl PortDirection {
             PortDirection::Buffer => "output", // Buffer maps to output in Verilog
         }
     }
+
+    pub fn to_vhdl(&self) -> &str {
+        match self {
+            PortDirection::In => "in",
+            PortDirection::Out => "out",
+            PortDirection::InOut => "inout",
+            PortDirection::Buffer => "buffer",
+        }
+    }
+}
+
+/// Bound of a vector range: a literal or a generic plus a constant offset (`WIDTH-1`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RangeExpr {
+    Literal(i32),
+    Parametric { base: String, offset: i32 },
+}
+
+impl RangeExpr {
+    /// Parse `8`, `WIDTH`, `WIDTH-1` or `WIDTH + 2`; anything more complex is rejected
+    pub fn from_vhdl(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Ok(value) = s.parse::<i32>() {
+            return Some(RangeExpr::Literal(value));
+        }
+
+        let (base, offset) = match s.find(['+', '-']) {
+            Some(pos) => {
+                let offset: i32 = s[pos + 1..].trim().parse().ok()?;
+                let sign = if s[pos..].starts_with('-') { -1 } else { 1 };
+                (s[..pos].trim(), sign * offset)
+            }
+            None => (s, 0),
+        };
+
+        let is_identifier = base.starts_with(|c: char| c.is_ascii_alphabetic())
+            && base.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        is_identifier.then(|| RangeExpr::Parametric { base: base.to_string(), offset })
+    }
+
+    pub fn as_literal(&self) -> Option<i32> {
+        match self {
+            RangeExpr::Literal(value) => Some(*value),
+            RangeExpr::Parametric { .. } => None,
+        }
+    }
+}
+
+impl From<i32> for RangeExpr {
+    fn from(value: i32) -> Self {
+        RangeExpr::Literal(value)
+    }
+}
+
+impl std::fmt::Display for RangeExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RangeExpr::Literal(value) => write!(f, "{}", value),
+            RangeExpr::Parametric { base, offset } if *offset < 0 => write!(f, "{}-{}", base, -offset),
+            RangeExpr::Parametric { base, offset } if *offset > 0 => write!(f, "{}+{}", base, offset),
+            RangeExpr::Parametric { base, .. } => write!(f, "{}", base),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VectorRange {
-    pub left: i32,
-    pub right: i32,
+    pub left: RangeExpr,
+    pub right: RangeExpr,
     pub downto: bool, // true for "downto", false for "to"
 }
 
 impl VectorRange {
+    /// `msb downto lsb`
+    pub fn downto(msb: i32, lsb: i32) -> Self {
+        VectorRange { left: msb.into(), right: lsb.into(), downto: true }
+    }
+
+    /// `lsb to msb`
+    pub fn upto(lsb: i32, msb: i32) -> Self {
+        VectorRange { left: lsb.into(), right: msb.into(), downto: false }
+    }
+
+    /// Parse `15 downto 0` or `0 to WIDTH-1`
+    pub fn from_vhdl(text: &str) -> Option<Self> {
+        let lower = text.to_lowercase();
+        let (pos, keyword, downto) = match lower.find(" downto ") {
+            Some(pos) => (pos, " downto ", true),
+            None => (lower.find(" to ")?, " to ", false),
+        };
+        Some(VectorRange {
+            left: RangeExpr::from_vhdl(&text[..pos])?,
+            right: RangeExpr::from_vhdl(&text[pos + keyword.len()..])?,
+            downto,
+        })
+    }
+
     pub fn to_verilog(&self) -> String {
         // Verilog uses [msb:lsb] format
         if self.downto {
@@ -45,6 +137,128 @@ impl VectorRange {
             format!("[{}:{}]", self.right, self.left)
         }
     }
+
+    /// `(7 downto 0)` or `(0 to 7)`
+    pub fn to_vhdl(&self) -> String {
+        let direction = if self.downto { "downto" } else { "to" };
+        format!("({} {} {})", self.left, direction, self.right)
+    }
+
+    /// Number of bits covered by the range, or `None` when a bound depends on a generic
+    pub fn width(&self) -> Option<u32> {
+        match (&self.left, &self.right) {
+            (RangeExpr::Literal(left), RangeExpr::Literal(right)) => Some(left.abs_diff(*right) + 1),
+            (RangeExpr::Parametric { base: l, offset: lo }, RangeExpr::Parametric { base: r, offset: ro }) if l == r => {
+                Some(lo.abs_diff(*ro) + 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the bounds run in the declared direction (`7 downto 0`, not `0 downto 7`);
+    /// bounds on different generics can't be compared and count as valid
+    pub fn is_valid(&self) -> bool {
+        let (left, right) = match (&self.left, &self.right) {
+            (RangeExpr::Literal(left), RangeExpr::Literal(right)) => (*left, *right),
+            (RangeExpr::Parametric { base: l, offset: lo }, RangeExpr::Parametric { base: r, offset: ro }) if l == r => {
+                (*lo, *ro)
+            }
+            _ => return true,
+        };
+        if self.downto { left >= right } else { left <= right }
+    }
+
+    /// Verilog slice notation `[msb:lsb]`
+    pub fn to_verilog_part_select(msb: u32, lsb: u32) -> String {
+        format!("[{}:{}]", msb, lsb)
+    }
+}
+
+/// Range constraint of an integer subtype: `range 0 to 255`, `range -8 to 7`, `range 0 to 2**AW-1`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntegerRange {
+    /// Bounds as written in VHDL, lowest first whatever the direction
+    pub low: String,
+    pub high: String,
+}
+
+impl IntegerRange {
+    /// Parse `0 to 255`, `255 downto 0` or a subtype indication such as `natural range 0 to N-1`
+    pub fn from_vhdl(text: &str) -> Option<Self> {
+        let lower = text.to_lowercase();
+        let start = lower.find("range ").map_or(0, |pos| pos + "range ".len());
+        let (text, lower) = (&text[start..], &lower[start..]);
+        let (low, high) = match lower.find(" downto ") {
+            Some(pos) => (&text[pos + " downto ".len()..], &text[..pos]),
+            None => {
+                let pos = lower.find(" to ")?;
+                (&text[..pos], &text[pos + " to ".len()..])
+            }
+        };
+        let (low, high) = (low.trim(), high.trim());
+        (!low.is_empty() && !high.is_empty()).then(|| IntegerRange { low: low.to_string(), high: high.to_string() })
+    }
+
+    /// Whether the range can hold negative values; bounds that aren't literals count as
+    /// negative, like an unconstrained integer
+    pub fn is_signed(&self) -> bool {
+        !self.low.parse::<i64>().is_ok_and(|low| low >= 0)
+    }
+
+    /// Bits needed for every value of the range, or `None` when the width follows a generic.
+    /// Ranges whose width can't be derived keep the 32 bits of an unconstrained integer
+    pub fn bit_width(&self) -> Option<u32> {
+        if let (Ok(low), Ok(high)) = (self.low.parse::<i64>(), self.high.parse::<i64>()) {
+            let signed = low < 0;
+            return Some(value_bits(low, signed).max(value_bits(high, signed)));
+        }
+        self.parametric_msb().is_none().then_some(32)
+    }
+
+    /// MSB of a range from a non-negative literal to a generic bound: `2**AW-1` needs `AW`
+    /// bits, `N-1` needs `$clog2(N)`
+    fn parametric_msb(&self) -> Option<String> {
+        if self.is_signed() {
+            return None;
+        }
+        let high: String = self.high.chars().filter(|c| !c.is_whitespace()).collect();
+        let is_generic = |name: &str| matches!(RangeExpr::from_vhdl(name), Some(RangeExpr::Parametric { offset: 0, .. }));
+        if let Some(exponent) = high.strip_prefix("2**") {
+            return match exponent.strip_suffix("-1") {
+                Some(base) if is_generic(base) => Some(format!("{}-1", base)),
+                // 2**N itself needs N+1 bits
+                None if is_generic(exponent) => Some(exponent.to_string()),
+                _ => None,
+            };
+        }
+        match RangeExpr::from_vhdl(&high)? {
+            RangeExpr::Parametric { base, offset } => {
+                Some(format!("$clog2({})-1", RangeExpr::Parametric { base, offset: offset + 1 }))
+            }
+            RangeExpr::Literal(_) => None,
+        }
+    }
+
+    /// Verilog vector holding the range: `[7:0]`, `signed [3:0]`, `[AW-1:0]`
+    pub fn to_verilog(&self) -> String {
+        let msb = self.bit_width()
+            .map(|width| (width - 1).to_string())
+            .or_else(|| self.parametric_msb())
+            .unwrap_or_default();
+        format!("{}[{}:0]", if self.is_signed() { "signed " } else { "" }, msb)
+    }
+
+    /// `range 0 to 255`
+    pub fn to_vhdl(&self) -> String {
+        format!("range {} to {}", self.low, self.high)
+    }
+}
+
+/// Bits for `value` as an unsigned or two's complement number
+fn value_bits(value: i64, signed: bool) -> u32 {
+    let magnitude = if value < 0 { !value } else { value };
+    let bits = 64 - magnitude.leading_zeros();
+    if signed { bits + 1 } else { bits.max(1) }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -54,6 +268,8 @@ pub enum VHDLType {
     Integer,
     Natural,
     Positive,
+    /// `integer range 0 to 255` and ranged `natural`/`positive`, sized from the range
+    ConstrainedInteger(IntegerRange),
     Boolean,
     Bit,
     BitVector(VectorRange),
@@ -62,7 +278,60 @@ pub enum VHDLType {
     Custom(String), // For user-defined types
 }
 
+/// The type a library type name denotes with or without a range constraint
+type StandardType = fn(Option<VectorRange>) -> Option<VHDLType>;
+
+/// Well-known library types: name, declaring package, and the type it is given a range
+/// or not. `std_logic_arith` declares `signed`/`unsigned` like `numeric_std`
+const STANDARD_TYPES: &[(&str, &str, StandardType)] = &[
+    ("std_logic", "ieee.std_logic_1164", |range| range.is_none().then_some(VHDLType::StdLogic)),
+    ("std_ulogic", "ieee.std_logic_1164", |range| range.is_none().then_some(VHDLType::StdLogic)),
+    ("std_logic_vector", "ieee.std_logic_1164", |range| range.map(VHDLType::StdLogicVector)),
+    ("std_ulogic_vector", "ieee.std_logic_1164", |range| range.map(VHDLType::StdLogicVector)),
+    ("signed", "ieee.numeric_std", |range| range.map(VHDLType::Signed)),
+    ("unsigned", "ieee.numeric_std", |range| range.map(VHDLType::Unsigned)),
+    ("bit", "std.standard", |range| range.is_none().then_some(VHDLType::Bit)),
+    ("bit_vector", "std.standard", |range| range.map(VHDLType::BitVector)),
+    ("boolean", "std.standard", |range| range.is_none().then_some(VHDLType::Boolean)),
+    ("integer", "std.standard", |range| range.is_none().then_some(VHDLType::Integer)),
+    ("natural", "std.standard", |range| range.is_none().then_some(VHDLType::Natural)),
+    ("positive", "std.standard", |range| range.is_none().then_some(VHDLType::Positive)),
+];
+
 impl VHDLType {
+    /// The library type a name such as `unsigned(7 downto 0)` or
+    /// `ieee.numeric_std.signed(WIDTH-1 downto 0)` denotes; unconstrained vectors and
+    /// user-defined types have none
+    pub fn from_standard_name(name: &str) -> Option<VHDLType> {
+        let (base, range) = match name.split_once('(') {
+            Some((base, range)) => (base, Some(VectorRange::from_vhdl(range.trim().strip_suffix(')')?)?)),
+            None => (name, None),
+        };
+        let (prefix, base) = match base.trim().rsplit_once('.') {
+            Some((prefix, base)) => (Some(prefix), base),
+            None => (None, base.trim()),
+        };
+        let (_, package, standard_type) = STANDARD_TYPES.iter().find(|(name, _, _)| name.eq_ignore_ascii_case(base))?;
+        // A selected name must come from the declaring package
+        let declared_in = |prefix: &str| {
+            prefix.eq_ignore_ascii_case(package)
+                || (*package == "ieee.numeric_std" && prefix.eq_ignore_ascii_case("ieee.std_logic_arith"))
+        };
+        if prefix.is_some_and(|prefix| !declared_in(prefix)) {
+            return None;
+        }
+        standard_type(range)
+    }
+
+    /// Replace a `Custom` type naming a library type with the type itself
+    pub fn resolve_standard(&mut self) {
+        if let VHDLType::Custom(name) = self {
+            if let Some(resolved) = VHDLType::from_standard_name(name) {
+                *self = resolved;
+            }
+        }
+    }
+
     pub fn to_verilog(&self) -> String {
         match self {
             VHDLType::StdLogic => "wire".to_string(),
@@ -70,6 +339,7 @@ impl VHDLType {
             VHDLType::Integer => "wire signed [31:0]".to_string(),
             VHDLType::Natural => "wire [31:0]".to_string(),
             VHDLType::Positive => "wire [31:0]".to_string(),
+            VHDLType::ConstrainedInteger(range) => format!("wire {}", range.to_verilog()),
             VHDLType::Boolean => "wire".to_string(),
             VHDLType::Bit => "wire".to_string(),
             VHDLType::BitVector(range) => format!("wire {}", range.to_verilog()),
@@ -78,6 +348,38 @@ impl VHDLType {
             VHDLType::Custom(name) => format!("wire /* {} */", name),
         }
     }
+
+    /// The type as written in VHDL, e.g. `std_logic_vector(7 downto 0)`
+    pub fn to_vhdl(&self) -> String {
+        match self {
+            VHDLType::StdLogic => "std_logic".to_string(),
+            VHDLType::StdLogicVector(range) => format!("std_logic_vector{}", range.to_vhdl()),
+            VHDLType::Integer => "integer".to_string(),
+            VHDLType::Natural => "natural".to_string(),
+            VHDLType::Positive => "positive".to_string(),
+            VHDLType::ConstrainedInteger(range) => format!("integer {}", range.to_vhdl()),
+            VHDLType::Boolean => "boolean".to_string(),
+            VHDLType::Bit => "bit".to_string(),
+            VHDLType::BitVector(range) => format!("bit_vector{}", range.to_vhdl()),
+            VHDLType::Signed(range) => format!("signed{}", range.to_vhdl()),
+            VHDLType::Unsigned(range) => format!("unsigned{}", range.to_vhdl()),
+            VHDLType::Custom(name) => name.clone(),
+        }
+    }
+
+    /// Bit width of the type, or `None` for user-defined types and generic-sized vectors
+    pub fn bit_width(&self) -> Option<u32> {
+        match self {
+            VHDLType::StdLogic | VHDLType::Boolean | VHDLType::Bit => Some(1),
+            VHDLType::Integer | VHDLType::Natural | VHDLType::Positive => Some(32),
+            VHDLType::ConstrainedInteger(range) => range.bit_width(),
+            VHDLType::StdLogicVector(range)
+            | VHDLType::BitVector(range)
+            | VHDLType::Signed(range)
+            | VHDLType::Unsigned(range) => range.width(),
+            VHDLType::Custom(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,6 +387,12 @@ pub struct Port {
     pub name: String,
     pub direction: PortDirection,
     pub port_type: VHDLType,
+    /// Trailing `--` comment on the port declaration, without the dashes
+    #[serde(default)]
+    pub comment: Option<String>,
+    /// 1-based line of the declaration in the VHDL source
+    #[serde(default)]
+    pub source_line: Option<usize>,
 }
 
 impl Port {
@@ -93,9 +401,42 @@ impl Port {
             name,
             direction,
             port_type,
+            comment: None,
+            source_line: None,
         }
     }
 
+    pub fn with_comment(mut self, comment: Option<String>) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    pub fn with_source_line(mut self, source_line: Option<usize>) -> Self {
+        self.source_line = source_line;
+        self
+    }
+
+    /// Single-bit input named like a clock (`clk`, `clock`, `sys_clk`, `clk_100`), excluding
+    /// clock enables such as `clk_en`
+    pub fn is_clock(&self) -> bool {
+        let name = self.name.to_lowercase();
+        let clock_like = name == "clk" || name == "clock"
+            || name.ends_with("_clk") || name.ends_with("_clock")
+            || name.starts_with("clk_") || name.starts_with("clock_");
+        let enable_like = name.ends_with("_en") || name.ends_with("_ena") || name.ends_with("_enable") || name.contains("_en_");
+        self.is_scalar_input() && clock_like && !enable_like
+    }
+
+    /// Single-bit input named like a reset (`rst`, `reset`, `rst_n`, `areset`)
+    pub fn is_reset(&self) -> bool {
+        let name = self.name.to_lowercase();
+        self.is_scalar_input() && (name.contains("rst") || name.contains("reset"))
+    }
+
+    fn is_scalar_input(&self) -> bool {
+        self.direction == PortDirection::In && matches!(self.port_type, VHDLType::StdLogic | VHDLType::Bit)
+    }
+
     pub fn to_verilog(&self) -> String {
         let direction = self.direction.to_verilog();
         let verilog_type = self.port_type.to_verilog();
@@ -108,9 +449,15 @@ impl Port {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entity {
     pub name: String,
+    /// `--` comment lines directly above the entity declaration, without the dashes
+    #[serde(default)]
+    pub header_comments: Vec<String>,
     pub ports: Vec<Port>,
     pub generics: Vec<Generic>,
     pub architecture: Option<Architecture>,
+    /// Further architectures of the same entity, in source order
+    #[serde(default)]
+    pub alternate_architectures: Vec<Architecture>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -119,12 +466,50 @@ pub struct Architecture {
     pub signals: Vec<Signal>,
     pub processes: Vec<Process>,
     pub concurrent_statements: Vec<String>,
+    #[serde(default)]
+    pub instantiations: Vec<Instantiation>,
+    #[serde(default)]
+    pub generate_blocks: Vec<GenerateBlock>,
+    /// 1-based source line of each entry in `concurrent_statements`; empty when unknown
+    #[serde(default)]
+    pub concurrent_statement_lines: Vec<usize>,
+    /// Types declared in the architecture, e.g. the state enumeration of an FSM
+    #[serde(skip)]
+    pub types: TypeResolutionContext,
+}
+
+impl Architecture {
+    /// Promote signal types naming library types, see `VHDLType::from_standard_name`
+    pub fn resolve_standard_types(&mut self) {
+        for signal in &mut self.signals {
+            signal.signal_type.resolve_standard();
+        }
+    }
+
+    /// Behavioral model: a process suspends on `wait` or a concurrent assignment is
+    /// delayed with `after`, timing that synthesizable Verilog cannot keep
+    pub fn is_behavioral_only(&self) -> bool {
+        let has_after = |statement: &String| {
+            statement.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .any(|word| word.eq_ignore_ascii_case("after"))
+        };
+        self.processes.iter().any(|process| process.testbench_style)
+            || self.concurrent_statements.iter().any(has_after)
+    }
+
+    /// Only clocked/combinational processes and untimed concurrent statements
+    pub fn is_rtl(&self) -> bool {
+        !self.is_behavioral_only()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Signal {
     pub name: String,
     pub signal_type: VHDLType,
+    /// 1-based line of the declaration in the VHDL source
+    #[serde(default)]
+    pub source_line: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +517,102 @@ pub struct Process {
     pub label: Option<String>,
     pub sensitivity_list: Vec<String>,
     pub body: String, // Store as raw text for now
+    /// 1-based line of the process statement in the VHDL source
+    #[serde(default)]
+    pub source_line: Option<usize>,
+    /// Body uses `wait` statements (testbench style); converted to an `initial` block
+    #[serde(default)]
+    pub testbench_style: bool,
+}
+
+/// Sensitivity list entry of a VHDL-2008 `process(all)`
+pub const SENSITIVITY_ALL: &str = "all";
+
+impl Process {
+    /// VHDL-2008 `process(all)`: sensitive to every signal the body reads
+    pub fn senses_all(&self) -> bool {
+        matches!(self.sensitivity_list.as_slice(), [name] if name.eq_ignore_ascii_case(SENSITIVITY_ALL))
+    }
+}
+
+/// Component or entity instantiation: `u0 : entity work.adder generic map (...) port map (...)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Instantiation {
+    pub label: String,
+    pub component: String,
+    pub generic_map: Vec<Association>,
+    pub port_map: Vec<Association>,
+    /// Architecture of the bound entity, from `entity work.foo(rtl)` or a binding
+    #[serde(default)]
+    pub architecture: Option<String>,
+    /// Configuration the instance is bound through, if any
+    #[serde(default)]
+    pub configuration: Option<String>,
+}
+
+/// One element of a generic or port map; `formal` is `None` for positional associations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Association {
+    pub formal: Option<String>,
+    pub actual: Actual,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Actual {
+    /// `open`: the port is left unconnected
+    Open,
+    /// A VHDL expression, kept unconverted until emission
+    Expression(String),
+}
+
+impl Association {
+    pub fn named(formal: &str, actual: &str) -> Self {
+        Self {
+            formal: Some(formal.to_string()),
+            actual: Actual::from_vhdl(actual),
+        }
+    }
+
+    pub fn positional(actual: &str) -> Self {
+        Self {
+            formal: None,
+            actual: Actual::from_vhdl(actual),
+        }
+    }
+}
+
+impl Actual {
+    pub fn from_vhdl(s: &str) -> Self {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("open") {
+            Actual::Open
+        } else {
+            Actual::Expression(s.to_string())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GenerateScheme {
+    /// `for var in left to/downto right generate`
+    For {
+        var: String,
+        left: String,
+        right: String,
+        downto: bool,
+    },
+    /// `if condition generate`
+    If { condition: String },
+}
+
+/// `label : for ... generate` / `label : if ... generate` block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerateBlock {
+    pub label: String,
+    pub scheme: GenerateScheme,
+    pub concurrent_statements: Vec<String>,
+    pub instantiations: Vec<Instantiation>,
+    pub generate_blocks: Vec<GenerateBlock>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,13 +622,25 @@ pub struct Generic {
     pub default_value: Option<String>,
 }
 
+impl Generic {
+    /// Format: "parameter WIDTH = 8"
+    pub fn to_verilog(&self) -> String {
+        match &self.default_value {
+            Some(value) => format!("parameter {} = {}", self.name, value),
+            None => format!("parameter {}", self.name),
+        }
+    }
+}
+
 impl Entity {
     pub fn new(name: String) -> Self {
         Self {
             name,
+            header_comments: Vec::new(),
             ports: Vec::new(),
             generics: Vec::new(),
             architecture: None,
+            alternate_architectures: Vec::new(),
         }
     }
 
@@ -155,9 +648,167 @@ impl Entity {
         self.ports.push(port);
     }
 
+    /// Module declaration line, with a parameter port list when the entity has generics
+    pub fn module_declaration(&self, indent: &str) -> String {
+        if self.generics.is_empty() {
+            return format!("module {} (\n", self.name);
+        }
+
+        let parameters: Vec<String> = self.generics.iter()
+            .map(|g| format!("{}{}", indent, g.to_verilog()))
+            .collect();
+        format!("module {} #(\n{}\n) (\n", self.name, parameters.join(",\n"))
+    }
+
     pub fn add_generic(&mut self, generic: Generic) {
         self.generics.push(generic);
     }
+
+    /// Attach an architecture; the first becomes primary, later ones alternates
+    pub fn add_architecture(&mut self, arch: Architecture) {
+        if self.architecture.is_none() {
+            self.architecture = Some(arch);
+        } else {
+            self.alternate_architectures.push(arch);
+        }
+    }
+
+    /// Make the named architecture primary, returning false if the entity has no such architecture
+    pub fn select_architecture(&mut self, arch_name: &str) -> bool {
+        let matches = |arch: &Architecture| arch.name.eq_ignore_ascii_case(arch_name);
+
+        if self.architecture.as_ref().is_some_and(matches) {
+            return true;
+        }
+
+        let Some(pos) = self.alternate_architectures.iter().position(matches) else {
+            return false;
+        };
+
+        let selected = self.alternate_architectures.remove(pos);
+        if let Some(previous) = self.architecture.replace(selected) {
+            self.alternate_architectures.insert(pos, previous);
+        }
+        true
+    }
+
+    /// Promote port, signal and generic types that name `ieee.std_logic_1164`,
+    /// `ieee.numeric_std` or `std.standard` types but were parsed as `Custom`
+    pub fn resolve_standard_types(&mut self) {
+        for port in &mut self.ports {
+            port.port_type.resolve_standard();
+        }
+        for generic in &mut self.generics {
+            if let Some(resolved) = VHDLType::from_standard_name(&generic.generic_type) {
+                generic.generic_type = resolved.to_vhdl();
+            }
+        }
+        for arch in self.architecture.iter_mut().chain(&mut self.alternate_architectures) {
+            arch.resolve_standard_types();
+        }
+    }
+
+    /// Combine two partial parses of one entity, e.g. its declaration and an architecture
+    /// read from different files: ports and generics are joined by name and the
+    /// architecture comes from whichever has one. Fails on different entity names,
+    /// ports or generics declared differently, and two differently named architectures
+    pub fn merge(base: &Entity, extension: &Entity) -> Result<Entity> {
+        if !base.name.eq_ignore_ascii_case(&extension.name) {
+            return Err(anyhow::anyhow!("Cannot merge entity {} with entity {}", base.name, extension.name));
+        }
+
+        let mut merged = base.clone();
+        for port in &extension.ports {
+            match merged.ports.iter().find(|p| p.name.eq_ignore_ascii_case(&port.name)) {
+                Some(existing) if existing.direction != port.direction || existing.port_type != port.port_type => {
+                    return Err(anyhow::anyhow!(
+                        "Conflicting declarations of port {} in entity {}: {} {} vs {} {}",
+                        port.name,
+                        base.name,
+                        existing.direction.to_vhdl(),
+                        existing.port_type.to_vhdl(),
+                        port.direction.to_vhdl(),
+                        port.port_type.to_vhdl()
+                    ));
+                }
+                Some(_) => {}
+                None => merged.ports.push(port.clone()),
+            }
+        }
+
+        for generic in &extension.generics {
+            match merged.generics.iter_mut().find(|g| g.name.eq_ignore_ascii_case(&generic.name)) {
+                Some(existing) if !existing.generic_type.eq_ignore_ascii_case(&generic.generic_type) => {
+                    return Err(anyhow::anyhow!(
+                        "Conflicting declarations of generic {} in entity {}: {} vs {}",
+                        generic.name, base.name, existing.generic_type, generic.generic_type
+                    ));
+                }
+                Some(existing) => {
+                    if existing.default_value.is_none() {
+                        existing.default_value = generic.default_value.clone();
+                    }
+                }
+                None => merged.generics.push(generic.clone()),
+            }
+        }
+
+        match (&merged.architecture, &extension.architecture) {
+            (Some(ours), Some(theirs)) if !ours.name.eq_ignore_ascii_case(&theirs.name) => {
+                return Err(anyhow::anyhow!(
+                    "Ambiguous architecture for entity {}: both {} and {}",
+                    base.name, ours.name, theirs.name
+                ));
+            }
+            (None, Some(theirs)) => merged.architecture = Some(theirs.clone()),
+            _ => {}
+        }
+        for arch in &extension.alternate_architectures {
+            let known = merged.architecture.iter().chain(&merged.alternate_architectures)
+                .any(|a| a.name.eq_ignore_ascii_case(&arch.name));
+            if !known {
+                merged.alternate_architectures.push(arch.clone());
+            }
+        }
+
+        if merged.header_comments.is_empty() {
+            merged.header_comments = extension.header_comments.clone();
+        }
+        Ok(merged)
+    }
+
+    /// Whether `self` can be merged into `other` as more of the same entity
+    pub fn extends(&self, other: &Entity) -> bool {
+        Entity::merge(other, self).is_ok()
+    }
+
+    /// Move every recorded source line after `after_line` by `delta`, e.g. once lines
+    /// were inserted or removed above part of the entity
+    pub fn shift_source_lines(&mut self, after_line: usize, delta: isize) {
+        let shift = |line: &mut usize| {
+            if *line > after_line {
+                *line = line.saturating_add_signed(delta);
+            }
+        };
+
+        self.ports.iter_mut().filter_map(|p| p.source_line.as_mut()).for_each(shift);
+        for arch in self.architecture.iter_mut().chain(self.alternate_architectures.iter_mut()) {
+            arch.signals.iter_mut().filter_map(|s| s.source_line.as_mut()).for_each(shift);
+            arch.processes.iter_mut().filter_map(|p| p.source_line.as_mut()).for_each(shift);
+            arch.concurrent_statement_lines.iter_mut().for_each(shift);
+        }
+    }
+
+    /// Types of ports and architecture signals, keyed by name
+    pub fn symbol_table(&self) -> HashMap<String, VHDLType> {
+        let ports = self.ports.iter().map(|p| (p.name.clone(), p.port_type.clone()));
+        let signals = self
+            .architecture
+            .iter()
+            .flat_map(|arch| arch.signals.iter().map(|s| (s.name.clone(), s.signal_type.clone())));
+
+        ports.chain(signals).collect()
+    }
 }
 
 #[cfg(test)]
@@ -173,21 +824,100 @@ mod tests {
     #[test]
     fn test_vector_range_conversion() {
         let range = VectorRange {
-            left: 7,
-            right: 0,
+            left: 7.into(),
+            right: 0.into(),
             downto: true,
         };
         assert_eq!(range.to_verilog(), "[7:0]");
     }
 
+    #[test]
+    fn test_vector_range_width_and_validity() {
+        let bit = VectorRange::downto(0, 0);
+        assert_eq!(bit.width(), Some(1));
+        assert!(bit.is_valid());
+        assert_eq!(bit.to_verilog(), "[0:0]");
+
+        assert_eq!(VectorRange::upto(0, 7), VectorRange { left: 0.into(), right: 7.into(), downto: false });
+        assert_eq!(VectorRange::upto(0, 7).to_verilog(), "[7:0]");
+        assert!(VectorRange::upto(0, 7).is_valid());
+        assert_eq!(VectorRange::downto(-4, -11).width(), Some(8));
+
+        // Inverted (null) ranges still report the span of their bounds
+        let inverted = VectorRange::downto(0, 7);
+        assert!(!inverted.is_valid());
+        assert!(!VectorRange::upto(7, 0).is_valid());
+        assert_eq!(inverted.width(), Some(8));
+
+        let parametric = VectorRange::from_vhdl("WIDTH-1 downto WIDTH-8").unwrap();
+        assert_eq!(parametric.width(), Some(8));
+        assert!(parametric.is_valid());
+        assert!(!VectorRange::from_vhdl("WIDTH-8 downto WIDTH-1").unwrap().is_valid());
+        // Bounds on different generics can't be compared
+        assert!(VectorRange::from_vhdl("0 to DEPTH-1").unwrap().is_valid());
+
+        assert_eq!(VectorRange::to_verilog_part_select(15, 8), "[15:8]");
+    }
+
+    #[test]
+    fn test_integer_range_sizing() {
+        let byte = IntegerRange::from_vhdl("integer range 0 to 255").unwrap();
+        assert_eq!(byte, IntegerRange { low: "0".to_string(), high: "255".to_string() });
+        assert_eq!(VHDLType::ConstrainedInteger(byte.clone()).to_verilog(), "wire [7:0]");
+        assert_eq!(VHDLType::ConstrainedInteger(byte).to_vhdl(), "integer range 0 to 255");
+        assert_eq!(IntegerRange::from_vhdl("range 256 downto 0").unwrap().to_verilog(), "[8:0]");
+        assert_eq!(IntegerRange::from_vhdl("0 to 0").unwrap().bit_width(), Some(1));
+
+        // Negative bounds need a sign bit
+        let nibble = IntegerRange::from_vhdl("range -8 to 7").unwrap();
+        assert!(nibble.is_signed());
+        assert_eq!(nibble.to_verilog(), "signed [3:0]");
+        assert_eq!(IntegerRange::from_vhdl("-1 to 1").unwrap().bit_width(), Some(2));
+        assert_eq!(IntegerRange::from_vhdl("-129 to 0").unwrap().bit_width(), Some(9));
+
+        // Bounds on generics follow the generic
+        let address = IntegerRange::from_vhdl("natural range 0 to 2**AW-1").unwrap();
+        assert_eq!(address.bit_width(), None);
+        assert_eq!(address.to_verilog(), "[AW-1:0]");
+        assert_eq!(IntegerRange::from_vhdl("0 to 2 ** AW").unwrap().to_verilog(), "[AW:0]");
+        assert_eq!(IntegerRange::from_vhdl("0 to DEPTH-1").unwrap().to_verilog(), "[$clog2(DEPTH)-1:0]");
+
+        // Anything else keeps the 32 bits of an unconstrained integer
+        let unknown = IntegerRange::from_vhdl("range MIN_VAL to MAX_VAL").unwrap();
+        assert_eq!(unknown.bit_width(), Some(32));
+        assert_eq!(unknown.to_verilog(), "signed [31:0]");
+        assert_eq!(IntegerRange::from_vhdl("integer"), None);
+    }
+
+    #[test]
+    fn test_parametric_range() {
+        assert_eq!(RangeExpr::from_vhdl(" 7 "), Some(RangeExpr::Literal(7)));
+        assert_eq!(
+            RangeExpr::from_vhdl("WIDTH - 1"),
+            Some(RangeExpr::Parametric { base: "WIDTH".to_string(), offset: -1 })
+        );
+        assert_eq!(RangeExpr::from_vhdl("DEPTH+2").unwrap().to_string(), "DEPTH+2");
+        assert_eq!(RangeExpr::from_vhdl("WIDTH/2"), None);
+        assert_eq!(RangeExpr::from_vhdl("2*N-1"), None);
+
+        let range = VectorRange {
+            left: RangeExpr::from_vhdl("WIDTH-1").unwrap(),
+            right: 0.into(),
+            downto: true,
+        };
+        assert_eq!(range.to_verilog(), "[WIDTH-1:0]");
+        assert_eq!(range.width(), None);
+        assert_eq!(VHDLType::StdLogicVector(range).to_systemverilog(), "logic [WIDTH-1:0]");
+    }
+
     #[test]
     fn test_vhdl_type_conversion() {
         let std_logic = VHDLType::StdLogic;
         assert_eq!(std_logic.to_verilog(), "wire");
 
         let vector = VHDLType::StdLogicVector(VectorRange {
-            left: 7,
-            right: 0,
+            left: 7.into(),
+            right: 0.into(),
             downto: true,
         });
         assert_eq!(vector.to_verilog(), "wire [7:0]");
@@ -206,11 +936,161 @@ mod tests {
             "data".to_string(),
             PortDirection::Out,
             VHDLType::StdLogicVector(VectorRange {
-                left: 7,
-                right: 0,
+                left: 7.into(),
+                right: 0.into(),
                 downto: true,
             }),
         );
         assert_eq!(port_vector.to_verilog(), "output wire [7:0] data");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_select_architecture() {
+        let arch = |name: &str| Architecture {
+            name: name.to_string(),
+            signals: vec![],
+            processes: vec![],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        };
+
+        let mut entity = Entity::new("alu".to_string());
+        entity.add_architecture(arch("rtl"));
+        entity.add_architecture(arch("behavioral"));
+        assert_eq!(entity.architecture.as_ref().unwrap().name, "rtl");
+
+        assert!(entity.select_architecture("BEHAVIORAL"));
+        assert_eq!(entity.architecture.as_ref().unwrap().name, "behavioral");
+        assert_eq!(entity.alternate_architectures[0].name, "rtl");
+
+        assert!(!entity.select_architecture("gate"));
+        assert_eq!(entity.architecture.as_ref().unwrap().name, "behavioral");
+    }
+
+    #[test]
+    fn test_behavioral_and_rtl_architectures() {
+        let process = |body: &str, testbench_style: bool| Process {
+            label: None,
+            sensitivity_list: vec![],
+            body: body.to_string(),
+            source_line: None,
+            testbench_style,
+        };
+        let mut arch = Architecture {
+            name: "behavioral".to_string(),
+            signals: vec![],
+            processes: vec![process("if rising_edge(clk) then\n    q <= d;\nend if;", false)],
+            concurrent_statements: vec!["y <= a and b_after;".to_string()],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        };
+        // The name says behavioral, the content is RTL
+        assert!(arch.is_rtl());
+
+        arch.concurrent_statements.push("ack <= req AFTER 5 ns;".to_string());
+        assert!(arch.is_behavioral_only());
+
+        arch.concurrent_statements.pop();
+        arch.processes.push(process("clk <= '0';\nwait for 5 ns;", true));
+        assert!(arch.is_behavioral_only());
+        assert!(!arch.is_rtl());
+    }
+
+    #[test]
+    fn test_merge_partial_entities() {
+        let byte = || VHDLType::StdLogicVector(VectorRange { left: 7.into(), right: 0.into(), downto: true });
+        let mut declaration = Entity::new("uart_tx".to_string());
+        declaration.add_port(Port::new("clk".to_string(), PortDirection::In, VHDLType::StdLogic));
+        declaration.add_port(Port::new("data".to_string(), PortDirection::In, byte()));
+
+        let mut extension = Entity::new("UART_TX".to_string());
+        extension.add_port(Port::new("data".to_string(), PortDirection::In, byte()));
+        extension.add_port(Port::new("tx".to_string(), PortDirection::Out, VHDLType::StdLogic));
+        extension.add_architecture(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+
+        let merged = Entity::merge(&declaration, &extension).unwrap();
+        assert_eq!(merged.name, "uart_tx");
+        let ports: Vec<&str> = merged.ports.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(ports, vec!["clk", "data", "tx"]);
+        assert_eq!(merged.architecture.unwrap().name, "rtl");
+        assert!(extension.extends(&declaration));
+
+        // Same port name, different type
+        let mut conflicting = Entity::new("uart_tx".to_string());
+        conflicting.add_port(Port::new("data".to_string(), PortDirection::In, VHDLType::Integer));
+        let err = Entity::merge(&declaration, &conflicting).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Conflicting declarations of port data in entity uart_tx: in std_logic_vector(7 downto 0) vs in integer"
+        );
+        assert!(!conflicting.extends(&declaration));
+
+        // Same port name, different direction
+        let mut reversed = Entity::new("uart_tx".to_string());
+        reversed.add_port(Port::new("clk".to_string(), PortDirection::Out, VHDLType::StdLogic));
+        assert!(!reversed.extends(&declaration));
+
+        let mut other_arch = extension.clone();
+        other_arch.architecture.as_mut().unwrap().name = "behavioral".to_string();
+        let err = Entity::merge(&extension, &other_arch).unwrap_err();
+        assert!(err.to_string().starts_with("Ambiguous architecture for entity UART_TX"));
+    }
+
+    #[test]
+    fn test_resolve_standard_types() {
+        let byte = VectorRange { left: 7.into(), right: 0.into(), downto: true };
+        let mut entity = Entity::new("acc".to_string());
+        entity.add_port(Port::new("a".to_string(), PortDirection::In, VHDLType::Custom("unsigned(7 downto 0)".to_string())));
+        entity.add_port(Port::new("b".to_string(), PortDirection::In, VHDLType::Custom("IEEE.NUMERIC_STD.SIGNED(7 downto 0)".to_string())));
+        entity.add_port(Port::new("en".to_string(), PortDirection::In, VHDLType::Custom("std_ulogic".to_string())));
+        // Unconstrained, user-defined and foreign types stay custom
+        entity.add_port(Port::new("c".to_string(), PortDirection::In, VHDLType::Custom("unsigned".to_string())));
+        entity.add_port(Port::new("s".to_string(), PortDirection::Out, VHDLType::Custom("state_t".to_string())));
+        entity.add_port(Port::new("d".to_string(), PortDirection::Out, VHDLType::Custom("work.my_pkg.unsigned(3 downto 0)".to_string())));
+        entity.add_generic(Generic { name: "DEPTH".to_string(), generic_type: "NATURAL".to_string(), default_value: None });
+        entity.add_architecture(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![Signal {
+                name: "sum".to_string(),
+                signal_type: VHDLType::Custom("ieee.std_logic_arith.unsigned(WIDTH-1 downto 0)".to_string()),
+                source_line: None,
+            }],
+            processes: vec![],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+
+        entity.resolve_standard_types();
+        let types: Vec<VHDLType> = entity.ports.iter().map(|p| p.port_type.clone()).collect();
+        assert_eq!(types, vec![
+            VHDLType::Unsigned(byte.clone()),
+            VHDLType::Signed(byte),
+            VHDLType::StdLogic,
+            VHDLType::Custom("unsigned".to_string()),
+            VHDLType::Custom("state_t".to_string()),
+            VHDLType::Custom("work.my_pkg.unsigned(3 downto 0)".to_string()),
+        ]);
+        assert_eq!(entity.generics[0].generic_type, "natural");
+        assert_eq!(
+            entity.architecture.unwrap().signals[0].signal_type,
+            VHDLType::Unsigned(VectorRange::from_vhdl("WIDTH-1 downto 0").unwrap())
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+
+use crate::ir::{Entity, VHDLType};
+use crate::parser::VerilogModuleParser;
+
+lazy_static! {
+    static ref PORT_DECL_RE: Regex = Regex::new(r"(?m)^\s*(input|output|inout)\b").unwrap();
+    static ref PARAMETER_RE: Regex = Regex::new(r"\bparameter\b").unwrap();
+    static ref ALWAYS_RE: Regex = Regex::new(r"(?m)^\s*(always_ff|always_comb|always_latch|always)\b(.*)$").unwrap();
+    static ref BLOCK_WORD_RE: Regex = Regex::new(r"\b(begin|end)\b").unwrap();
+    static ref VHDL_KEYWORD_RE: Regex = Regex::new(
+        r"(?i)\b(process|signal|architecture|elsif|end\s+(?:if|process|case|loop|generate|architecture|entity))\b"
+    ).unwrap();
+}
+
+/// Outcome of a single quality check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum CheckOutcome {
+    Passed,
+    Failed(String),
+    Warning(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QualityCheck {
+    pub name: String,
+    pub outcome: CheckOutcome,
+}
+
+/// Result of checking generated SystemVerilog; `score` is in 0.0..=1.0
+#[derive(Debug, Clone, Serialize)]
+pub struct QualityReport {
+    pub score: f32,
+    pub checks: Vec<QualityCheck>,
+}
+
+impl QualityReport {
+    pub fn check(&self, name: &str) -> Option<&CheckOutcome> {
+        self.checks.iter().find(|c| c.name == name).map(|c| &c.outcome)
+    }
+}
+
+impl fmt::Display for QualityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Quality score: {:.0}%", self.score * 100.0)?;
+        for check in &self.checks {
+            match &check.outcome {
+                CheckOutcome::Passed => writeln!(f, "  [PASS] {}", check.name)?,
+                CheckOutcome::Warning(details) => writeln!(f, "  [WARN] {}: {}", check.name, details)?,
+                CheckOutcome::Failed(details) => writeln!(f, "  [FAIL] {}: {}", check.name, details)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Scores generated SystemVerilog against the entity it was generated from
+pub struct TranspileQualityChecker;
+
+impl TranspileQualityChecker {
+    pub fn check(entity: &Entity, sv: &str) -> QualityReport {
+        let code = strip_comments(sv);
+        let module = VerilogModuleParser::new(code.clone()).parse_modules().ok()
+            .and_then(|modules| {
+                let position = modules.iter().position(|m| m.name.eq_ignore_ascii_case(&entity.name)).unwrap_or(0);
+                modules.into_iter().nth(position)
+            });
+
+        let checks = vec![
+            QualityCheck { name: "port_count".to_string(), outcome: Self::check_port_count(entity, &code) },
+            QualityCheck { name: "port_names".to_string(), outcome: Self::check_port_names(entity, &code, module.as_ref()) },
+            QualityCheck { name: "port_widths".to_string(), outcome: Self::check_port_widths(entity, module.as_ref()) },
+            QualityCheck { name: "no_todos".to_string(), outcome: Self::check_todos(sv) },
+            QualityCheck { name: "balanced_blocks".to_string(), outcome: Self::check_balanced_blocks(&code) },
+            QualityCheck { name: "no_vhdl_keywords".to_string(), outcome: Self::check_vhdl_keywords(&code) },
+            QualityCheck { name: "generic_count".to_string(), outcome: Self::check_generic_count(entity, &code) },
+        ];
+
+        // Warnings count for half a passed check
+        let points: f32 = checks.iter()
+            .map(|c| match c.outcome {
+                CheckOutcome::Passed => 1.0,
+                CheckOutcome::Warning(_) => 0.5,
+                CheckOutcome::Failed(_) => 0.0,
+            })
+            .sum();
+
+        QualityReport {
+            score: points / checks.len() as f32,
+            checks,
+        }
+    }
+
+    fn check_port_count(entity: &Entity, code: &str) -> CheckOutcome {
+        let found = PORT_DECL_RE.find_iter(code).count();
+        if found == entity.ports.len() {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::Failed(format!("expected {} ports, found {}", entity.ports.len(), found))
+        }
+    }
+
+    /// Against the parsed module header, or anywhere in the code when it cannot be parsed
+    fn check_port_names(entity: &Entity, code: &str, module: Option<&Entity>) -> CheckOutcome {
+        let declared = |name: &str| match module {
+            Some(module) => module.ports.iter().any(|p| p.name == name),
+            None => contains_word(code, name),
+        };
+        let missing: Vec<&str> = entity.ports.iter()
+            .map(|p| p.name.as_str())
+            .filter(|name| !declared(name))
+            .collect();
+
+        if missing.is_empty() {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::Failed(format!("missing ports: {}", missing.join(", ")))
+        }
+    }
+
+    /// Ports whose width is known on both sides must agree; user-defined types are skipped
+    fn check_port_widths(entity: &Entity, module: Option<&Entity>) -> CheckOutcome {
+        let Some(module) = module else {
+            return CheckOutcome::Warning("module header could not be parsed".to_string());
+        };
+
+        let mismatches: Vec<String> = entity.ports.iter()
+            .filter_map(|port| {
+                let generated = module.ports.iter().find(|p| p.name == port.name)?;
+                let (expected, found) = (width_text(&port.port_type)?, width_text(&generated.port_type)?);
+                (expected != found).then(|| format!("{} ({} vs {})", port.name, expected, found))
+            })
+            .collect();
+
+        if mismatches.is_empty() {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::Failed(format!("width mismatches: {}", mismatches.join(", ")))
+        }
+    }
+
+    /// TODOs mark constructs the converter left for manual work
+    fn check_todos(sv: &str) -> CheckOutcome {
+        let todos = sv.lines().filter(|line| line.contains("TODO")).count();
+        if todos == 0 {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::Warning(format!("{} TODO comment(s) remain", todos))
+        }
+    }
+
+    fn check_balanced_blocks(code: &str) -> CheckOutcome {
+        for caps in ALWAYS_RE.captures_iter(code) {
+            let rest = caps[2].trim_end();
+            if !rest.ends_with("begin") {
+                return CheckOutcome::Failed(format!("'{}' block does not open with begin", &caps[1]));
+            }
+        }
+
+        let mut depth: i32 = 0;
+        for word in BLOCK_WORD_RE.find_iter(code) {
+            depth += if word.as_str() == "begin" { 1 } else { -1 };
+            if depth < 0 {
+                return CheckOutcome::Failed("'end' without matching 'begin'".to_string());
+            }
+        }
+
+        if depth == 0 {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::Failed(format!("{} 'begin' without matching 'end'", depth))
+        }
+    }
+
+    fn check_vhdl_keywords(code: &str) -> CheckOutcome {
+        let mut found: Vec<String> = VHDL_KEYWORD_RE.find_iter(code)
+            .map(|m| m.as_str().to_lowercase())
+            .collect();
+        found.dedup();
+
+        if found.is_empty() {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::Failed(format!("VHDL keywords remain: {}", found.join(", ")))
+        }
+    }
+
+    fn check_generic_count(entity: &Entity, code: &str) -> CheckOutcome {
+        let found = PARAMETER_RE.find_iter(code).count();
+        if found == entity.generics.len() {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::Failed(format!("expected {} parameters, found {}", entity.generics.len(), found))
+        }
+    }
+}
+
+/// `8 bits`, or the `[msb:lsb]` range when it depends on a parameter
+fn width_text(port_type: &VHDLType) -> Option<String> {
+    if let Some(bits) = port_type.bit_width() {
+        return Some(format!("{} bit{}", bits, if bits == 1 { "" } else { "s" }));
+    }
+    match port_type {
+        VHDLType::StdLogicVector(range)
+        | VHDLType::BitVector(range)
+        | VHDLType::Signed(range)
+        | VHDLType::Unsigned(range) => Some(range.to_verilog()),
+        _ => None,
+    }
+}
+
+fn contains_word(code: &str, word: &str) -> bool {
+    code.match_indices(word).any(|(i, _)| {
+        let before = code[..i].chars().next_back();
+        let after = code[i + word.len()..].chars().next();
+        let is_ident = |c: Option<char>| c.map(|c| c.is_alphanumeric() || c == '_').unwrap_or(false);
+        !is_ident(before) && !is_ident(after)
+    })
+}
+
+fn strip_comments(sv: &str) -> String {
+    sv.lines()
+        .map(|line| line.split("//").next().unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Generic, Port, PortDirection, VectorRange};
+
+    fn inverter() -> Entity {
+        let mut entity = Entity::new("inv".to_string());
+        entity.add_port(Port::new("a".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("y".to_string(), PortDirection::Out, VHDLType::StdLogic));
+        entity
+    }
+
+    const GOOD_SV: &str = "module inv (\n    input logic a,\n    output logic y\n);\n\n    always_comb begin\n        y = ~a;\n    end\nendmodule\n";
+
+    #[test]
+    fn test_clean_output_scores_full_marks() {
+        let report = TranspileQualityChecker::check(&inverter(), GOOD_SV);
+        assert!(report.checks.iter().all(|c| c.outcome == CheckOutcome::Passed), "{}", report);
+        assert_eq!(report.score, 1.0);
+    }
+
+    #[test]
+    fn test_port_checks() {
+        let missing_port = GOOD_SV.replace("    output logic y\n", "").replace("a,\n", "a\n");
+        let report = TranspileQualityChecker::check(&inverter(), &missing_port);
+        assert!(matches!(report.check("port_count"), Some(CheckOutcome::Failed(_))));
+
+        let renamed = GOOD_SV.replace('y', "q");
+        let report = TranspileQualityChecker::check(&inverter(), &renamed);
+        assert_eq!(report.check("port_count"), Some(&CheckOutcome::Passed));
+        assert_eq!(report.check("port_names"), Some(&CheckOutcome::Failed("missing ports: y".to_string())));
+    }
+
+    #[test]
+    fn test_port_widths_from_parsed_header() {
+        let mut entity = inverter();
+        entity.add_port(Port::new("count".to_string(), PortDirection::Out, VHDLType::StdLogicVector(VectorRange::from_vhdl("7 downto 0").unwrap())));
+        let sv = GOOD_SV.replace("    output logic y\n", "    output logic y,\n    output logic [3:0] count\n");
+        let report = TranspileQualityChecker::check(&entity, &sv);
+        assert_eq!(report.check("port_names"), Some(&CheckOutcome::Passed));
+        assert_eq!(
+            report.check("port_widths"),
+            Some(&CheckOutcome::Failed("width mismatches: count (8 bits vs 4 bits)".to_string()))
+        );
+
+        let report = TranspileQualityChecker::check(&entity, &sv.replace("[3:0]", "[7:0]"));
+        assert_eq!(report.check("port_widths"), Some(&CheckOutcome::Passed));
+    }
+
+    #[test]
+    fn test_todos_are_warnings() {
+        let sv = GOOD_SV.replace("y = ~a;", "// TODO: Convert VHDL 'with...select'");
+        let report = TranspileQualityChecker::check(&inverter(), &sv);
+        assert!(matches!(report.check("no_todos"), Some(CheckOutcome::Warning(_))));
+        assert!(report.score < 1.0 && report.score > 0.9);
+    }
+
+    #[test]
+    fn test_unbalanced_blocks() {
+        let sv = GOOD_SV.replace("    end\n", "");
+        let report = TranspileQualityChecker::check(&inverter(), &sv);
+        assert!(matches!(report.check("balanced_blocks"), Some(CheckOutcome::Failed(_))));
+
+        let sv = GOOD_SV.replace("always_comb begin", "always_comb");
+        let report = TranspileQualityChecker::check(&inverter(), &sv);
+        assert!(matches!(report.check("balanced_blocks"), Some(CheckOutcome::Failed(_))));
+    }
+
+    #[test]
+    fn test_vhdl_keywords_are_reported() {
+        let sv = GOOD_SV.replace("y = ~a;", "if a then y = 1; end if;\n        // process comments are fine");
+        let report = TranspileQualityChecker::check(&inverter(), &sv);
+        assert_eq!(
+            report.check("no_vhdl_keywords"),
+            Some(&CheckOutcome::Failed("VHDL keywords remain: end if".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_generic_count() {
+        let mut entity = inverter();
+        entity.add_generic(Generic {
+            name: "WIDTH".to_string(),
+            generic_type: "integer".to_string(),
+            default_value: Some("8".to_string()),
+        });
+
+        let report = TranspileQualityChecker::check(&entity, GOOD_SV);
+        assert!(matches!(report.check("generic_count"), Some(CheckOutcome::Failed(_))));
+
+        let sv = GOOD_SV.replace("module inv (", "module inv #(parameter WIDTH = 8) (");
+        let report = TranspileQualityChecker::check(&entity, &sv);
+        assert_eq!(report.check("generic_count"), Some(&CheckOutcome::Passed));
+    }
+}
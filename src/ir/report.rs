@@ -0,0 +1,262 @@
+use serde::Serialize;
+
+use crate::ir::clocking::{inferred_latches, ProcessClocking};
+use crate::ir::hdl_body::is_sequential;
+use crate::ir::sanitize::Rename;
+use crate::ir::{Entity, Port, VHDLType};
+
+/// A VHDL construct with no equivalent in the target language, left for manual rework
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TranspilationWarning {
+    pub construct: String,
+    pub message: String,
+}
+
+impl TranspilationWarning {
+    /// A user-defined type with no known definition, emitted as a placeholder
+    pub fn unresolvable_type(name: &str) -> Self {
+        Self {
+            construct: format!("type {}", name),
+            message: "no definition found in the loaded packages; emitted as an unsized placeholder".to_string(),
+        }
+    }
+
+    /// An architecture timed with `wait`/`after`, whose simulation behavior the
+    /// generated module does not keep
+    pub fn behavioral_only(architecture: &str) -> Self {
+        Self {
+            construct: format!("architecture {}", architecture),
+            message: "behavioral model (wait statements or after delays); simulation timing is not preserved".to_string(),
+        }
+    }
+
+    /// A combinational process leaving `signals` unassigned on some path
+    pub fn inferred_latch(process: &str, signals: &[String]) -> Self {
+        Self {
+            construct: format!("process {}", process),
+            message: format!("inferred latch for {} (not assigned on every path)", signals.join(", ")),
+        }
+    }
+
+    /// A combinational process whose latched `signals` were given a default assignment
+    pub fn defaulted_latch(process: &str, signals: &[String]) -> Self {
+        Self {
+            construct: format!("process {}", process),
+            message: format!(
+                "inferred latch for {} (not assigned on every path); default assignment inserted",
+                signals.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for TranspilationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.construct, self.message)
+    }
+}
+
+/// How one VHDL port appears in the generated module
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PortMapping {
+    pub vhdl_name: String,
+    /// Differs from `vhdl_name` when the port was renamed, e.g. to avoid a reserved word
+    pub hdl_name: String,
+    pub vhdl_direction: String,
+    pub hdl_direction: String,
+    pub vhdl_type: String,
+    pub hdl_type: String,
+}
+
+/// A VHDL type and the declaration it was emitted as, once per distinct pair
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TypeMapping {
+    pub vhdl_type: String,
+    pub hdl_type: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessKind {
+    Sequential,
+    Combinational,
+    /// Combinational with signals left unassigned on some path
+    Latch,
+    /// Timed with `wait` statements
+    Testbench,
+}
+
+impl ProcessKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ProcessKind::Sequential => "sequential",
+            ProcessKind::Combinational => "combinational",
+            ProcessKind::Latch => "latch",
+            ProcessKind::Testbench => "testbench",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProcessClassification {
+    /// Process label, `process_<i>` for unlabeled ones
+    pub process: String,
+    pub kind: ProcessKind,
+    /// Clock and reset summary for sequential processes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clocking: Option<String>,
+}
+
+/// Mapping decisions made while generating one module
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversionReport {
+    pub entity_name: String,
+    pub port_mappings: Vec<PortMapping>,
+    pub type_mappings: Vec<TypeMapping>,
+    pub process_classifications: Vec<ProcessClassification>,
+    /// Identifiers renamed to avoid reserved words and case-only spelling differences
+    pub renames: Vec<Rename>,
+    /// Constructs emitted as placeholders or left out of the module
+    pub unsupported_constructs: Vec<String>,
+    pub warnings: Vec<TranspilationWarning>,
+}
+
+impl ConversionReport {
+    /// Report for `generated`, the resolved and sanitized copy of `original` the module
+    /// was emitted from, with the port and signal declarations of the target language
+    pub(crate) fn new(
+        original: &Entity,
+        generated: &Entity,
+        port_type: impl Fn(&Port) -> String,
+        signal_type: impl Fn(&VHDLType) -> String,
+    ) -> Self {
+        let mut type_mappings: Vec<TypeMapping> = Vec::new();
+        let mut add_type = |vhdl_type: String, hdl_type: String| {
+            if !type_mappings.iter().any(|m| m.vhdl_type == vhdl_type && m.hdl_type == hdl_type) {
+                type_mappings.push(TypeMapping { vhdl_type, hdl_type });
+            }
+        };
+
+        // Resolution and sanitizing keep the port order
+        let port_mappings: Vec<PortMapping> = original.ports.iter().zip(&generated.ports)
+            .map(|(vhdl, hdl)| {
+                let mapping = PortMapping {
+                    vhdl_name: vhdl.name.clone(),
+                    hdl_name: hdl.name.clone(),
+                    vhdl_direction: vhdl.direction.to_vhdl().to_string(),
+                    hdl_direction: hdl.direction.to_verilog().to_string(),
+                    vhdl_type: vhdl.port_type.to_vhdl(),
+                    hdl_type: port_type(hdl),
+                };
+                add_type(mapping.vhdl_type.clone(), mapping.hdl_type.clone());
+                mapping
+            })
+            .collect();
+
+        let mut process_classifications = Vec::new();
+        if let (Some(vhdl), Some(hdl)) = (&original.architecture, &generated.architecture) {
+            for (signal, declared) in vhdl.signals.iter().zip(&hdl.signals) {
+                add_type(signal.signal_type.to_vhdl(), signal_type(&declared.signal_type));
+            }
+            for (i, process) in hdl.processes.iter().enumerate() {
+                let label = process.label.clone().unwrap_or_else(|| format!("process_{}", i));
+                let clocking = ProcessClocking::analyze(process, &label);
+                let kind = if process.testbench_style {
+                    ProcessKind::Testbench
+                } else if is_sequential(process) {
+                    ProcessKind::Sequential
+                } else if !inferred_latches(process).is_empty() {
+                    ProcessKind::Latch
+                } else {
+                    ProcessKind::Combinational
+                };
+                process_classifications.push(ProcessClassification {
+                    process: label,
+                    kind,
+                    clocking: (kind == ProcessKind::Sequential).then(|| clocking.summary()),
+                });
+            }
+        }
+
+        Self {
+            entity_name: original.name.clone(),
+            port_mappings,
+            type_mappings,
+            process_classifications,
+            renames: Vec::new(),
+            unsupported_constructs: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_renames(mut self, renames: Vec<Rename>) -> Self {
+        self.renames = renames;
+        self
+    }
+
+    pub(crate) fn with_unsupported_constructs(mut self, constructs: Vec<String>) -> Self {
+        self.unsupported_constructs = constructs;
+        self
+    }
+
+    pub(crate) fn with_warnings(mut self, warnings: Vec<TranspilationWarning>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Human-readable report with one table per kind of decision
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("## Conversion report: {}\n", self.entity_name);
+
+        output.push_str("\n### Ports\n\n");
+        output.push_str("| VHDL | Direction | VHDL type | Generated | Direction | Type |\n");
+        output.push_str("|------|-----------|-----------|-----------|-----------|------|\n");
+        for port in &self.port_mappings {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                port.vhdl_name, port.vhdl_direction, port.vhdl_type, port.hdl_name, port.hdl_direction, port.hdl_type
+            ));
+        }
+
+        if !self.type_mappings.is_empty() {
+            output.push_str("\n### Types\n\n| VHDL type | Generated type |\n|-----------|----------------|\n");
+            for mapping in &self.type_mappings {
+                output.push_str(&format!("| {} | {} |\n", mapping.vhdl_type, mapping.hdl_type));
+            }
+        }
+
+        if !self.process_classifications.is_empty() {
+            output.push_str("\n### Processes\n\n| Process | Kind | Clocking |\n|---------|------|----------|\n");
+            for process in &self.process_classifications {
+                output.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    process.process,
+                    process.kind.as_str(),
+                    process.clocking.as_deref().unwrap_or("-")
+                ));
+            }
+        }
+
+        if !self.renames.is_empty() {
+            output.push_str("\n### Renames\n\n");
+            for rename in &self.renames {
+                output.push_str(&format!("- {}\n", rename));
+            }
+        }
+
+        if !self.unsupported_constructs.is_empty() {
+            output.push_str("\n### Unsupported constructs\n\n");
+            for construct in &self.unsupported_constructs {
+                output.push_str(&format!("- {}\n", construct));
+            }
+        }
+
+        if !self.warnings.is_empty() {
+            output.push_str("\n### Warnings\n\n");
+            for warning in &self.warnings {
+                output.push_str(&format!("- {}\n", warning));
+            }
+        }
+        output
+    }
+}
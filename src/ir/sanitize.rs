@@ -0,0 +1,417 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::ir::{Actual, Entity, GenerateBlock, GenerateScheme, Instantiation};
+
+/// IEEE 1364-2005 reserved words
+pub const VERILOG_KEYWORDS: &[&str] = &[
+    "always", "and", "assign", "automatic", "begin", "buf", "bufif0", "bufif1", "case", "casex",
+    "casez", "cell", "cmos", "config", "deassign", "default", "defparam", "design", "disable",
+    "edge", "else", "end", "endcase", "endconfig", "endfunction", "endgenerate", "endmodule",
+    "endprimitive", "endspecify", "endtable", "endtask", "event", "for", "force", "forever",
+    "fork", "function", "generate", "genvar", "highz0", "highz1", "if", "ifnone", "incdir",
+    "include", "initial", "inout", "input", "instance", "integer", "join", "large", "liblist",
+    "library", "localparam", "macromodule", "medium", "module", "nand", "negedge", "nmos", "nor",
+    "noshowcancelled", "not", "notif0", "notif1", "or", "output", "parameter", "pmos", "posedge",
+    "primitive", "pull0", "pull1", "pulldown", "pullup", "pulsestyle_ondetect",
+    "pulsestyle_onevent", "rcmos", "real", "realtime", "reg", "release", "repeat", "rnmos",
+    "rpmos", "rtran", "rtranif0", "rtranif1", "scalared", "showcancelled", "signed", "small",
+    "specify", "specparam", "strong0", "strong1", "supply0", "supply1", "table", "task", "time",
+    "tran", "tranif0", "tranif1", "tri", "tri0", "tri1", "triand", "trior", "trireg",
+    "unsigned", "use", "uwire", "vectored", "wait", "wand", "weak0", "weak1", "while", "wire",
+    "wor", "xnor", "xor",
+];
+
+/// Reserved words IEEE 1800-2012 adds on top of Verilog
+pub const SYSTEMVERILOG_KEYWORDS: &[&str] = &[
+    "accept_on", "alias", "always_comb", "always_ff", "always_latch", "assert", "assume",
+    "before", "bind", "bins", "binsof", "bit", "break", "byte", "chandle", "checker", "class",
+    "clocking", "const", "constraint", "context", "continue", "cover", "covergroup",
+    "coverpoint", "cross", "dist", "do", "endchecker", "endclass", "endclocking", "endgroup",
+    "endinterface", "endpackage", "endprogram", "endproperty", "endsequence", "enum",
+    "eventually", "expect", "export", "extends", "extern", "final", "first_match", "foreach",
+    "forkjoin", "global", "iff", "ignore_bins", "illegal_bins", "implements", "implies",
+    "import", "inside", "int", "interconnect", "interface", "intersect", "join_any",
+    "join_none", "let", "local", "logic", "longint", "matches", "modport", "nettype", "new",
+    "nexttime", "null", "package", "packed", "priority", "program", "property", "protected",
+    "pure", "rand", "randc", "randcase", "randsequence", "ref", "reject_on", "restrict",
+    "return", "s_always", "s_eventually", "s_nexttime", "s_until", "s_until_with", "sequence",
+    "shortint", "shortreal", "soft", "solve", "static", "string", "strong", "struct", "super",
+    "sync_accept_on", "sync_reject_on", "tagged", "this", "throughout", "timeprecision",
+    "timeunit", "type", "typedef", "union", "unique", "unique0", "until", "until_with",
+    "untyped", "var", "virtual", "void", "wait_order", "weak", "wildcard", "with", "within",
+];
+
+/// Scala reserved words, for the SpinalHDL generator
+pub const SCALA_KEYWORDS: &[&str] = &[
+    "abstract", "case", "catch", "class", "def", "do", "else", "extends", "false", "final",
+    "finally", "for", "forSome", "if", "implicit", "import", "lazy", "match", "new", "null",
+    "object", "override", "package", "private", "protected", "return", "sealed", "super",
+    "this", "throw", "trait", "true", "try", "type", "val", "var", "while", "with", "yield",
+];
+
+/// Why an identifier was renamed during generation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenameReason {
+    /// The VHDL name is a reserved word in the target language
+    ReservedWord,
+    /// A different-case spelling of a declared name (VHDL is case-insensitive)
+    CaseNormalized,
+}
+
+/// One identifier rename applied while generating a module
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Rename {
+    pub original: String,
+    pub renamed: String,
+    pub reason: RenameReason,
+}
+
+impl std::fmt::Display for Rename {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self.reason {
+            RenameReason::ReservedWord => "reserved word",
+            RenameReason::CaseNormalized => "case normalized",
+        };
+        write!(f, "{} -> {} ({})", self.original, self.renamed, reason)
+    }
+}
+
+/// Deterministic replacement for a reserved identifier: `reg` -> `reg_r`
+pub fn reserved_rename(name: &str, is_reserved: impl Fn(&str) -> bool) -> Option<String> {
+    is_reserved(name).then(|| format!("{}_r", name))
+}
+
+/// Rename reserved identifiers and normalize spellings to their declaration, across
+/// ports, generics, signals, processes, concurrent statements and instantiations
+pub fn sanitize_entity(entity: &Entity, is_reserved: impl Fn(&str) -> bool) -> (Entity, Vec<Rename>) {
+    let mut declared: Vec<String> = entity.generics.iter().map(|g| g.name.clone())
+        .chain(entity.ports.iter().map(|p| p.name.clone()))
+        .collect();
+    if let Some(arch) = &entity.architecture {
+        declared.extend(arch.signals.iter().map(|s| s.name.clone()));
+    }
+
+    let taken: HashSet<String> = declared.iter().map(|n| n.to_lowercase()).collect();
+    let mut renames = Vec::new();
+
+    // Lowercased declared name -> spelling used in the generated code
+    let mut spellings: HashMap<String, String> = HashMap::new();
+    for name in &declared {
+        let mut target = name.clone();
+        if let Some(mut renamed) = reserved_rename(name, &is_reserved) {
+            while taken.contains(&renamed.to_lowercase()) {
+                renamed.push_str("_r");
+            }
+            renames.push(Rename { original: name.clone(), renamed: renamed.clone(), reason: RenameReason::ReservedWord });
+            target = renamed;
+        }
+        spellings.entry(name.to_lowercase()).or_insert(target);
+    }
+
+    let mut sanitizer = Sanitizer {
+        declared: declared.into_iter().collect(),
+        spellings,
+        is_reserved: &is_reserved,
+        seen_variants: HashSet::new(),
+        renames,
+    };
+    let mut entity = entity.clone();
+
+    // Instances of the entity get the same deterministic rename of their module name
+    if let Some(renamed) = reserved_rename(&entity.name, &is_reserved) {
+        sanitizer.renames.push(Rename { original: entity.name.clone(), renamed: renamed.clone(), reason: RenameReason::ReservedWord });
+        entity.name = renamed;
+    }
+
+    for generic in &mut entity.generics {
+        generic.name = sanitizer.rename(&generic.name);
+    }
+    for port in &mut entity.ports {
+        port.name = sanitizer.rename(&port.name);
+    }
+    if let Some(arch) = entity.architecture.as_mut() {
+        for signal in &mut arch.signals {
+            signal.name = sanitizer.rename(&signal.name);
+        }
+        for process in &mut arch.processes {
+            for name in &mut process.sensitivity_list {
+                *name = sanitizer.rename(name);
+            }
+            process.body = sanitizer.rewrite(&process.body);
+        }
+        for stmt in &mut arch.concurrent_statements {
+            *stmt = sanitizer.rewrite(stmt);
+        }
+        for inst in &mut arch.instantiations {
+            sanitizer.rewrite_instantiation(inst);
+        }
+        for block in &mut arch.generate_blocks {
+            sanitizer.rewrite_generate_block(block);
+        }
+    }
+
+    (entity, sanitizer.renames)
+}
+
+/// Rewrite every reference to the spelling of its declaration (VHDL is case-insensitive),
+/// in the selected architecture and in each alternate one
+pub fn normalize_case(entity: &Entity) -> (Entity, Vec<Rename>) {
+    let (mut normalized, mut renames) = sanitize_entity(entity, |_| false);
+
+    for (index, arch) in entity.alternate_architectures.iter().enumerate() {
+        let mut view = entity.clone();
+        view.architecture = Some(arch.clone());
+        let (view, view_renames) = sanitize_entity(&view, |_| false);
+        if let Some(arch) = view.architecture {
+            normalized.alternate_architectures[index] = arch;
+        }
+        for rename in view_renames {
+            if !renames.contains(&rename) {
+                renames.push(rename);
+            }
+        }
+    }
+
+    (normalized, renames)
+}
+
+struct Sanitizer<'a, F: Fn(&str) -> bool> {
+    declared: HashSet<String>,
+    spellings: HashMap<String, String>,
+    is_reserved: &'a F,
+    seen_variants: HashSet<String>,
+    renames: Vec<Rename>,
+}
+
+impl<F: Fn(&str) -> bool> Sanitizer<'_, F> {
+    fn rename(&mut self, name: &str) -> String {
+        let Some(target) = self.spellings.get(&name.to_lowercase()) else {
+            return name.to_string();
+        };
+
+        // Declarations were recorded up front; only other spellings are new
+        if !self.declared.contains(name) && self.seen_variants.insert(name.to_string()) {
+            self.renames.push(Rename {
+                original: name.to_string(),
+                renamed: target.clone(),
+                reason: RenameReason::CaseNormalized,
+            });
+        }
+        target.clone()
+    }
+
+    /// Rewrite declared identifiers in VHDL text, leaving literals and comments alone
+    fn rewrite(&mut self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            if c == '"' {
+                // String literal, copied verbatim
+                result.push(c);
+                for (_, c) in chars.by_ref() {
+                    result.push(c);
+                    if c == '"' {
+                        break;
+                    }
+                }
+            } else if c == '-' && text[i + 1..].starts_with('-') {
+                // Comment up to the end of the line
+                let end = text[i..].find('\n').map(|n| i + n).unwrap_or(text.len());
+                result.push_str(&text[i..end]);
+                while chars.peek().map(|(j, _)| *j < end).unwrap_or(false) {
+                    chars.next();
+                }
+            } else if c.is_ascii_alphabetic() {
+                let mut end = i + c.len_utf8();
+                while let Some(&(j, next)) = chars.peek() {
+                    if !(next.is_ascii_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    end = j + next.len_utf8();
+                    chars.next();
+                }
+                // Attribute names (clk'event) and based literals are not identifiers we own
+                let after_tick = result.ends_with('\'');
+                let word = &text[i..end];
+                if after_tick {
+                    result.push_str(word);
+                } else {
+                    result.push_str(&self.rename(word));
+                }
+            } else {
+                result.push(c);
+            }
+        }
+
+        result
+    }
+
+    fn rewrite_instantiation(&mut self, inst: &mut Instantiation) {
+        if let Some(renamed) = reserved_rename(&inst.component, self.is_reserved) {
+            inst.component = renamed;
+        }
+        // Formals name ports of the instantiated module, which gets the same deterministic rename
+        for assoc in inst.generic_map.iter_mut().chain(inst.port_map.iter_mut()) {
+            if let Some(formal) = assoc.formal.as_mut() {
+                if let Some(renamed) = reserved_rename(formal, self.is_reserved) {
+                    *formal = renamed;
+                }
+            }
+            if let Actual::Expression(expr) = &mut assoc.actual {
+                *expr = self.rewrite(expr);
+            }
+        }
+    }
+
+    fn rewrite_generate_block(&mut self, block: &mut GenerateBlock) {
+        match &mut block.scheme {
+            GenerateScheme::For { left, right, .. } => {
+                *left = self.rewrite(left);
+                *right = self.rewrite(right);
+            }
+            GenerateScheme::If { condition } => *condition = self.rewrite(condition),
+        }
+        for stmt in &mut block.concurrent_statements {
+            *stmt = self.rewrite(stmt);
+        }
+        for inst in &mut block.instantiations {
+            self.rewrite_instantiation(inst);
+        }
+        for nested in &mut block.generate_blocks {
+            self.rewrite_generate_block(nested);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Architecture, Port, PortDirection, Process, Signal, VHDLType};
+
+    fn is_sv_reserved(name: &str) -> bool {
+        VERILOG_KEYWORDS.contains(&name) || SYSTEMVERILOG_KEYWORDS.contains(&name)
+    }
+
+    #[test]
+    fn test_reserved_port_and_case_variants() {
+        let mut entity = Entity::new("filter".to_string());
+        entity.add_port(Port::new("clk".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("output".to_string(), PortDirection::Out, VHDLType::StdLogic));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![
+                Signal { name: "Data".to_string(), signal_type: VHDLType::StdLogic, source_line: None },
+                Signal { name: "output_r".to_string(), signal_type: VHDLType::StdLogic, source_line: None },
+            ],
+            processes: vec![Process {
+                label: None,
+                sensitivity_list: vec!["CLK".to_string()],
+                body: "if rising_edge(Clk) then\n    Output <= DATA; -- keep output\n    data <= \"output\";\nend if;".to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec!["OUTPUT_R <= not data".to_string()],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+
+        let (sanitized, renames) = sanitize_entity(&entity, is_sv_reserved);
+
+        assert_eq!(sanitized.ports[1].name, "output_r_r");
+        let arch = sanitized.architecture.unwrap();
+        assert_eq!(arch.processes[0].sensitivity_list, vec!["clk"]);
+        assert_eq!(
+            arch.processes[0].body,
+            "if rising_edge(clk) then\n    output_r_r <= Data; -- keep output\n    Data <= \"output\";\nend if;"
+        );
+        assert_eq!(arch.concurrent_statements[0], "output_r <= not Data");
+
+        assert_eq!(renames[0], Rename {
+            original: "output".to_string(),
+            renamed: "output_r_r".to_string(),
+            reason: RenameReason::ReservedWord,
+        });
+        let normalized: Vec<&str> = renames.iter()
+            .filter(|r| r.reason == RenameReason::CaseNormalized)
+            .map(|r| r.original.as_str())
+            .collect();
+        assert_eq!(normalized, vec!["CLK", "Clk", "Output", "DATA", "data", "OUTPUT_R"]);
+    }
+
+    #[test]
+    fn test_normalize_case_covers_alternate_architectures() {
+        let arch = |name: &str, body: &str| Architecture {
+            name: name.to_string(),
+            signals: vec![Signal { name: "Count".to_string(), signal_type: VHDLType::StdLogic, source_line: None }],
+            processes: vec![Process {
+                label: None,
+                sensitivity_list: vec!["clk".to_string()],
+                body: body.to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        };
+        let mut entity = Entity::new("counter".to_string());
+        entity.add_port(Port::new("clk".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_architecture(arch("rtl", "count <= not COUNT;"));
+        entity.add_architecture(arch("alt", "COUNT <= '0';"));
+
+        let (normalized, renames) = normalize_case(&entity);
+
+        assert_eq!(normalized.architecture.unwrap().processes[0].body, "Count <= not Count;");
+        assert_eq!(normalized.alternate_architectures[0].processes[0].body, "Count <= '0';");
+        let originals: Vec<&str> = renames.iter().map(|r| r.original.as_str()).collect();
+        assert_eq!(originals, vec!["count", "COUNT"]);
+        assert!(renames.iter().all(|r| r.reason == RenameReason::CaseNormalized && r.renamed == "Count"));
+    }
+
+    #[test]
+    fn test_dialect_specific_reserved_words() {
+        let mut entity = Entity::new("m".to_string());
+        entity.add_port(Port::new("logic".to_string(), PortDirection::In, VHDLType::StdLogic));
+
+        let (verilog, renames) = sanitize_entity(&entity, |n| VERILOG_KEYWORDS.contains(&n));
+        assert_eq!(verilog.ports[0].name, "logic");
+        assert!(renames.is_empty());
+
+        let (sv, _) = sanitize_entity(&entity, is_sv_reserved);
+        assert_eq!(sv.ports[0].name, "logic_r");
+    }
+
+    #[test]
+    fn test_reserved_module_and_component_names() {
+        let mut entity = Entity::new("module".to_string());
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![],
+            concurrent_statements: vec![],
+            instantiations: vec![crate::ir::Instantiation {
+                label: "u0".to_string(),
+                component: "wire".to_string(),
+                generic_map: vec![],
+                port_map: vec![],
+                architecture: None,
+                configuration: None,
+            }],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+
+        let (sanitized, renames) = sanitize_entity(&entity, is_sv_reserved);
+        assert_eq!(sanitized.name, "module_r");
+        assert_eq!(sanitized.architecture.unwrap().instantiations[0].component, "wire_r");
+        assert_eq!(renames.iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["module -> module_r (reserved word)"]);
+    }
+}
@@ -0,0 +1,198 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+lazy_static! {
+    static ref SOURCE_REF_RE: Regex = Regex::new(r"// vhdl:.*:(\d+)$").unwrap();
+}
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Byte-to-line/column index of a VHDL source. Each line records the column of the
+/// leftmost syntax node starting on it; lines without nodes fall back to their
+/// first non-blank character
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    line_starts: Vec<usize>,
+    indents: Vec<u32>,
+    columns: BTreeMap<u32, u32>,
+}
+
+impl SourceMap {
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        let indents = source.split('\n')
+            .map(|line| (line.len() - line.trim_start().len()) as u32)
+            .collect();
+        Self { line_starts, indents, columns: BTreeMap::new() }
+    }
+
+    /// Record a syntax node starting at byte offset `start`
+    pub fn add_node(&mut self, start: usize) {
+        let (line, col) = self.position(start);
+        self.columns.entry(line)
+            .and_modify(|existing| *existing = (*existing).min(col))
+            .or_insert(col);
+    }
+
+    /// 1-based line and 0-based column of a byte offset
+    pub fn position(&self, offset: usize) -> (u32, u32) {
+        let index = self.line_starts.partition_point(|&start| start <= offset).saturating_sub(1);
+        ((index + 1) as u32, (offset - self.line_starts[index]) as u32)
+    }
+
+    /// 0-based column where the code on the 1-based `line` starts
+    pub fn column(&self, line: u32) -> u32 {
+        self.columns.get(&line).copied()
+            .or_else(|| self.indents.get((line as usize).checked_sub(1)?).copied())
+            .unwrap_or(0)
+    }
+}
+
+/// ` // vhdl:<source>:<line>` comment tracing a generated line to its VHDL source line
+pub fn source_ref(source: &str, line: usize) -> String {
+    format!(" // vhdl:{}:{}", source, line)
+}
+
+/// Map of 1-based generated line to VHDL source line, read from `// vhdl:` source refs
+pub fn source_ref_lines(generated: &str) -> BTreeMap<usize, usize> {
+    generated.lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let caps = SOURCE_REF_RE.captures(line)?;
+            Some((i + 1, caps[1].parse().ok()?))
+        })
+        .collect()
+}
+
+/// One generated Verilog line traced back to the VHDL source
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SourceMapEntry {
+    pub verilog_line: u32,
+    pub vhdl_line: u32,
+    pub vhdl_col: u32,
+}
+
+/// Line mapping of a generated module, read from its `// vhdl:` source refs
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct SourceMapOutput {
+    pub entries: Vec<SourceMapEntry>,
+}
+
+impl SourceMapOutput {
+    pub fn from_generated(generated: &str, source_map: &SourceMap) -> Self {
+        let entries = source_ref_lines(generated).into_iter()
+            .map(|(verilog_line, vhdl_line)| {
+                let vhdl_line = vhdl_line as u32;
+                SourceMapEntry {
+                    verilog_line: verilog_line as u32,
+                    vhdl_line,
+                    vhdl_col: source_map.column(vhdl_line),
+                }
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// JSON array of `{ verilog_line, vhdl_line, vhdl_col }` entries
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// Source Map v3 document mapping the start of each traced line of `file` to `source`
+    pub fn to_v3(&self, file: &str, source: &str) -> serde_json::Value {
+        let mut mappings = String::new();
+        let mut generated_line = 1;
+        let (mut prev_line, mut prev_col) = (0i64, 0i64);
+        for entry in &self.entries {
+            while generated_line < entry.verilog_line {
+                mappings.push(';');
+                generated_line += 1;
+            }
+            if mappings.ends_with(|c| c != ';') {
+                continue;
+            }
+            let (line, col) = (entry.vhdl_line as i64 - 1, entry.vhdl_col as i64);
+            for value in [0, 0, line - prev_line, col - prev_col] {
+                encode_vlq(value, &mut mappings);
+            }
+            (prev_line, prev_col) = (line, col);
+        }
+
+        serde_json::json!({
+            "version": 3,
+            "file": file,
+            "sources": [source],
+            "names": [],
+            "mappings": mappings,
+        })
+    }
+}
+
+/// Base64 VLQ digits of `value`, sign in the lowest bit
+fn encode_vlq(value: i64, out: &mut String) {
+    let mut vlq = if value < 0 { ((-value) << 1) | 1 } else { value << 1 };
+    loop {
+        let mut digit = (vlq & 31) as usize;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 32;
+        }
+        out.push(BASE64[digit] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions_and_columns() {
+        let source = "entity e is\n  port (\n    clk : in std_logic\n  );\nend entity;\n";
+        let mut map = SourceMap::new(source);
+        assert_eq!(map.position(0), (1, 0));
+        assert_eq!(map.position(source.find("clk").unwrap()), (3, 4));
+        assert_eq!(map.column(3), 4);
+
+        map.add_node(source.find("std_logic").unwrap());
+        map.add_node(source.find("in std").unwrap());
+        assert_eq!(map.column(3), 10);
+        assert_eq!(map.column(99), 0);
+    }
+
+    #[test]
+    fn test_v3_mappings() {
+        let source = "a\n  b\nc\n";
+        let generated = "x // vhdl:in.vhd:2\n\ny // vhdl:in.vhd:1\n";
+        let output = SourceMapOutput::from_generated(generated, &SourceMap::new(source));
+        assert_eq!(output.entries, vec![
+            SourceMapEntry { verilog_line: 1, vhdl_line: 2, vhdl_col: 2 },
+            SourceMapEntry { verilog_line: 3, vhdl_line: 1, vhdl_col: 0 },
+        ]);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&output.to_json()).unwrap(),
+            serde_json::json!([
+                { "verilog_line": 1, "vhdl_line": 2, "vhdl_col": 2 },
+                { "verilog_line": 3, "vhdl_line": 1, "vhdl_col": 0 },
+            ])
+        );
+        assert_eq!(output.to_v3("out.v", "in.vhd")["mappings"], "AACE;;AADF");
+
+        let mut digits = String::new();
+        encode_vlq(-17, &mut digits);
+        encode_vlq(1000, &mut digits);
+        assert_eq!(digits, "jBw+B");
+    }
+
+    #[test]
+    fn test_source_names_with_spaces() {
+        let generated = format!("x;{}\ny;{}\n", source_ref("my design.vhd", 7), source_ref("C:/rtl/top level.vhd", 12));
+        assert_eq!(source_ref_lines(&generated), BTreeMap::from([(1, 7), (2, 12)]));
+    }
+}
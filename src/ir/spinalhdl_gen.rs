@@ -0,0 +1,1039 @@
+use anyhow::Result;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+use crate::ir::emitter::{BlockEmitter, BlockKind};
+use crate::ir::hdl_body::{convert_comment, split_comment};
+use crate::ir::report::{ConversionReport, TranspilationWarning};
+use crate::ir::sanitize::{sanitize_entity, SCALA_KEYWORDS};
+use crate::ir::testbench::TestbenchGenerator;
+use crate::ir::{Actual, Architecture, Entity, GenerateBlock, GenerateScheme, Instantiation, Port, PortDirection, Process, RangeExpr, VHDLType, VectorRange};
+
+lazy_static! {
+    static ref EDGE_RE: Regex = Regex::new(r"(?i)\b(rising_edge|falling_edge)\s*\(\s*(\w+)\s*\)").unwrap();
+    static ref OTHERS_RE: Regex = Regex::new(r"^\(\s*others\s*=>\s*'([01])'\s*\)$").unwrap();
+    static ref FOR_LOOP_RE: Regex = Regex::new(r"(?i)^for\s+(\w+)\s+in\s+(.+?)\s+(to|downto)\s+(.+?)\s+loop$").unwrap();
+}
+
+/// Generate a SpinalHDL (Scala) component from Entity IR
+pub struct SpinalHDLGenerator {
+    indent: String,
+}
+
+impl SpinalHDLGenerator {
+    pub fn new() -> Self {
+        Self { indent: "  ".to_string() }
+    }
+
+    pub fn with_indent(indent: String) -> Self {
+        Self { indent }
+    }
+
+    /// Generate a `BlackBox` with the entity's ports and generics and no body
+    pub fn generate_stub(&self, entity: &Entity) -> Result<String> {
+        let (entity, _) = sanitize_entity(entity, |name| SCALA_KEYWORDS.contains(&name));
+        let mut writer = ScalaWriter::new(&entity, &self.indent);
+        let mut output = String::from("import spinal.core._\n\n");
+
+        output.push_str(&writer.class_header(&entity, "BlackBox"));
+        output.push_str(&format!("{}setDefinitionName(\"{}\")\n", self.indent, entity.name));
+        for generic in &entity.generics {
+            output.push_str(&format!("{}addGeneric(\"{}\", {})\n", self.indent, generic.name, generic.name));
+        }
+        output.push('\n');
+        output.push_str(&writer.io_bundle(&entity.ports));
+        output.push_str(&format!("\n{}noIoPrefix()\n", self.indent));
+        output.push_str("}\n");
+
+        Ok(output)
+    }
+
+    /// Generate the complete component and a report of how the entity was converted,
+    /// including the constructs that have no SpinalHDL equivalent
+    pub fn generate(&self, entity: &Entity) -> Result<(String, ConversionReport)> {
+        let original = entity;
+        let (entity, renames) = sanitize_entity(entity, |name| SCALA_KEYWORDS.contains(&name));
+        for rename in &renames {
+            tracing::warn!("Renamed identifier in {}: {}", entity.name, rename);
+        }
+
+        let mut writer = ScalaWriter::new(&entity, &self.indent);
+        let mut output = String::from("import spinal.core._\n\n");
+
+        output.push_str(&writer.class_header(&entity, "Component"));
+        output.push_str(&writer.io_bundle(&entity.ports));
+        if let Some(arch) = &entity.architecture {
+            output.push_str(&writer.architecture(&entity, arch)?);
+        }
+        output.push_str("}\n");
+
+        for warning in &writer.warnings {
+            tracing::warn!("SpinalHDL output for {}: {}", entity.name, warning);
+        }
+        let unsupported = writer.warnings.iter().map(|w| w.construct.clone()).collect();
+        let report = ConversionReport::new(original, &entity, |port| spinal_type_name(&port.port_type), spinal_type_name)
+            .with_renames(renames)
+            .with_unsupported_constructs(unsupported)
+            .with_warnings(writer.warnings);
+        Ok((output, report))
+    }
+}
+
+impl Default for SpinalHDLGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SpinalHDL hardware type declared for a VHDL type
+fn spinal_type_name(vhdl_type: &VHDLType) -> String {
+    match vhdl_type {
+        VHDLType::StdLogic | VHDLType::Bit | VHDLType::Boolean => "Bool()".to_string(),
+        VHDLType::StdLogicVector(range) | VHDLType::BitVector(range) => format!("Bits({} bits)", width_expr(range)),
+        VHDLType::Signed(range) => format!("SInt({} bits)", width_expr(range)),
+        VHDLType::Unsigned(range) => format!("UInt({} bits)", width_expr(range)),
+        VHDLType::Integer => "SInt(32 bits)".to_string(),
+        VHDLType::Natural | VHDLType::Positive => "UInt(32 bits)".to_string(),
+        VHDLType::ConstrainedInteger(range) => {
+            let kind = if range.is_signed() { "SInt" } else { "UInt" };
+            format!("{}({} bits)", kind, range.bit_width().unwrap_or(32))
+        }
+        VHDLType::Custom(name) => format!("{}()", name),
+    }
+}
+
+/// Scala class name for a VHDL entity: `fifo_sync` -> `FifoSync`
+pub fn scala_class_name(entity_name: &str) -> String {
+    entity_name
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Lexical token of a VHDL expression
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    /// `'0'`, `'1'`, `'Z'`
+    Char(char),
+    /// `"0101"`, `x"FF"`; `base` is the lowercase prefix letter
+    BitString { base: Option<char>, digits: String },
+    Symbol(String),
+    Space,
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        if c.is_whitespace() {
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            tokens.push(Token::Space);
+        } else if c.is_ascii_alphabetic() {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            if i - start == 1 && chars.get(i) == Some(&'"') && "xXbBoO".contains(c) {
+                let (digits, end) = string_body(&chars, i);
+                tokens.push(Token::BitString { base: Some(c.to_ascii_lowercase()), digits });
+                i = end;
+            } else {
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || matches!(chars[i], '_' | '.' | '#')) {
+                i += 1;
+            }
+            tokens.push(Token::Number(chars[start..i].iter().collect()));
+        } else if c == '"' {
+            let (digits, end) = string_body(&chars, i);
+            tokens.push(Token::BitString { base: None, digits });
+            i = end;
+        } else if c == '\'' && chars.get(i + 2) == Some(&'\'') {
+            tokens.push(Token::Char(chars[i + 1]));
+            i += 3;
+        } else {
+            let pair: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            if ["/=", "<=", ">=", "=>", "**", ":="].contains(&pair.as_str()) {
+                tokens.push(Token::Symbol(pair));
+                i += 2;
+            } else {
+                tokens.push(Token::Symbol(c.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Contents of the string literal whose opening quote is at `open`, and the index after it
+fn string_body(chars: &[char], open: usize) -> (String, usize) {
+    let close = chars[open + 1..].iter().position(|&c| c == '"').map(|p| open + 1 + p);
+    match close {
+        Some(close) => (chars[open + 1..close].iter().collect(), close + 1),
+        None => (chars[open + 1..].iter().collect(), chars.len()),
+    }
+}
+
+fn is_symbol(token: Option<&Token>, symbol: &str) -> bool {
+    matches!(token, Some(Token::Symbol(s)) if s == symbol)
+}
+
+/// Index of the `)` closing the `(` at `open`
+fn matching_paren(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if is_symbol(Some(token), "(") {
+            depth += 1;
+        } else if is_symbol(Some(token), ")") {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// Remove one pair of parentheses enclosing the whole expression
+fn strip_outer_parens(expr: &str) -> &str {
+    let expr = expr.trim();
+    if expr.starts_with('(') && expr.ends_with(')') {
+        let tokens = tokenize(expr);
+        if matching_paren(&tokens, 0) == Some(tokens.len() - 1) {
+            return expr[1..expr.len() - 1].trim();
+        }
+    }
+    expr
+}
+
+/// Parenthesize an expression unless it is a single operand
+fn wrap_operand(expr: &str) -> String {
+    if expr.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+        expr.to_string()
+    } else {
+        format!("({})", expr)
+    }
+}
+
+fn strip_then(condition: &str) -> &str {
+    let condition = condition.trim();
+    let condition = condition.strip_suffix("then").unwrap_or(condition);
+    strip_outer_parens(condition)
+}
+
+/// Number of bits of a range as a Scala expression
+fn width_expr(range: &VectorRange) -> String {
+    if let Some(width) = range.width() {
+        return width.to_string();
+    }
+    let (high, low) = if range.downto { (&range.left, &range.right) } else { (&range.right, &range.left) };
+    match (high, low) {
+        (RangeExpr::Parametric { base, offset }, RangeExpr::Literal(low)) => {
+            RangeExpr::Parametric { base: base.clone(), offset: offset - low + 1 }.to_string()
+        }
+        _ => format!("{} - {} + 1", high, low),
+    }
+}
+
+/// Emits the Scala text of one component, collecting warnings along the way
+struct ScalaWriter {
+    indent: String,
+    ports: HashSet<String>,
+    symbols: HashMap<String, VHDLType>,
+    reset: Option<(String, bool)>,
+    warnings: Vec<TranspilationWarning>,
+}
+
+impl ScalaWriter {
+    fn new(entity: &Entity, indent: &str) -> Self {
+        Self {
+            indent: indent.to_string(),
+            ports: entity.ports.iter().map(|p| p.name.clone()).collect(),
+            symbols: entity.symbol_table(),
+            reset: TestbenchGenerator::detect_reset(entity),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn warn(&mut self, construct: &str, message: &str) {
+        let warning = TranspilationWarning { construct: construct.to_string(), message: message.to_string() };
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    fn class_header(&mut self, entity: &Entity, base_class: &str) -> String {
+        let mut output = String::new();
+        for comment in &entity.header_comments {
+            output.push_str(&convert_comment(comment));
+            output.push('\n');
+        }
+
+        let parameters: Vec<String> = entity.generics.iter()
+            .map(|generic| {
+                let scala_type = match generic.generic_type.to_lowercase().as_str() {
+                    "integer" | "natural" | "positive" => "Int",
+                    "boolean" => "Boolean",
+                    "string" => "String",
+                    "real" => "Double",
+                    other => {
+                        self.warn(&format!("generic {} : {}", generic.name, other), "unsupported generic type, mapped to Int");
+                        "Int"
+                    }
+                };
+                match &generic.default_value {
+                    Some(value) if scala_type == "Boolean" => format!("{}: {} = {}", generic.name, scala_type, value.to_lowercase()),
+                    Some(value) => format!("{}: {} = {}", generic.name, scala_type, value),
+                    None => format!("{}: {}", generic.name, scala_type),
+                }
+            })
+            .collect();
+
+        output.push_str(&format!(
+            "case class {}({}) extends {} {{\n",
+            scala_class_name(&entity.name),
+            parameters.join(", "),
+            base_class
+        ));
+        output
+    }
+
+    fn spinal_type(&mut self, vhdl_type: &VHDLType) -> String {
+        match vhdl_type {
+            VHDLType::ConstrainedInteger(range) if range.bit_width().is_none() => {
+                self.warn(&vhdl_type.to_vhdl(), "integer range sized by a generic; emitted as 32 bits");
+            }
+            VHDLType::Custom(name) => {
+                self.warn(name, "user-defined type; declare it as a SpinalEnum or Bundle");
+            }
+            _ => {}
+        }
+        spinal_type_name(vhdl_type)
+    }
+
+    fn io_bundle(&mut self, ports: &[Port]) -> String {
+        let indent = self.indent.clone();
+        let mut output = format!("{}val io = new Bundle {{\n", indent);
+
+        for port in ports {
+            let spinal_type = self.spinal_type(&port.port_type);
+            let declaration = match port.direction {
+                PortDirection::In => format!("in {}", spinal_type),
+                PortDirection::Out | PortDirection::Buffer => format!("out {}", spinal_type),
+                PortDirection::InOut => {
+                    self.warn(&format!("inout port {}", port.name), "mapped to an Analog signal; tri-state drivers need manual rework");
+                    format!("inout(Analog({}))", spinal_type)
+                }
+            };
+            output.push_str(&format!("{}{}val {} = {}", indent, indent, port.name, declaration));
+            if let Some(comment) = &port.comment {
+                output.push(' ');
+                output.push_str(&convert_comment(comment));
+            }
+            output.push('\n');
+        }
+
+        output.push_str(&format!("{}}}\n", indent));
+        output
+    }
+
+    fn architecture(&mut self, entity: &Entity, arch: &Architecture) -> Result<String> {
+        let clocked: Vec<&Process> = arch.processes.iter().filter(|p| EDGE_RE.is_match(&p.body)).collect();
+        let mut output = String::new();
+
+        // Clocked processes share one clock domain; everything lives in its area so
+        // registers and combinational logic can refer to each other by name
+        let depth = if clocked.is_empty() { 1 } else { 2 };
+        if !clocked.is_empty() {
+            output.push('\n');
+            output.push_str(&self.clock_domain(entity, &clocked));
+            output.push_str(&format!("{}val core = new ClockingArea(clockDomain) {{\n", self.indent));
+        }
+
+        // Registers take their init value from an asynchronous reset branch
+        let mut registers = HashSet::new();
+        let mut inits = HashMap::new();
+        for process in &clocked {
+            let lines: Vec<&str> = process.body.lines().map(|line| split_comment(line.trim()).0).collect();
+            registers.extend(assigned_targets(&lines));
+            if let Some((start, end)) = self.async_reset_branch(process, &lines) {
+                for line in &lines[start + 1..end] {
+                    if let Some((target, value)) = line.trim_end_matches(';').split_once(" <= ") {
+                        let target = target.trim();
+                        let value = self.value(target, value.trim());
+                        inits.insert(target.to_string(), value);
+                    }
+                }
+            }
+        }
+
+        let pad = self.indent.repeat(depth);
+        if !arch.signals.is_empty() {
+            separate(&mut output);
+        }
+        for signal in &arch.signals {
+            let spinal_type = self.spinal_type(&signal.signal_type);
+            let declaration = if registers.contains(&signal.name) {
+                format!("Reg({})", spinal_type)
+            } else {
+                spinal_type
+            };
+            output.push_str(&format!("{}val {} = {}", pad, signal.name, declaration));
+            if let Some(init) = inits.get(&signal.name) {
+                output.push_str(&format!(" init({})", init));
+            }
+            output.push('\n');
+        }
+
+        let register_ports: Vec<&Port> = entity.ports.iter()
+            .filter(|p| matches!(p.direction, PortDirection::Out | PortDirection::Buffer) && registers.contains(&p.name))
+            .collect();
+        if !register_ports.is_empty() {
+            separate(&mut output);
+        }
+        for port in register_ports {
+            output.push_str(&format!("{}io.{}.setAsReg()", pad, port.name));
+            if let Some(init) = inits.get(&port.name) {
+                output.push_str(&format!(" init({})", init));
+            }
+            output.push('\n');
+        }
+
+        for process in &arch.processes {
+            separate(&mut output);
+            if let Some(label) = &process.label {
+                output.push_str(&format!("{}// {}\n", pad, label));
+            }
+            output.push_str(&self.process_body(process, depth)?);
+        }
+
+        for stmt in &arch.concurrent_statements {
+            separate(&mut output);
+            output.push_str(&self.concurrent_statement(stmt, depth));
+        }
+
+        for inst in &arch.instantiations {
+            separate(&mut output);
+            output.push_str(&self.instantiation(inst, depth));
+        }
+
+        for block in &arch.generate_blocks {
+            separate(&mut output);
+            output.push_str(&self.generate_block(block, depth));
+        }
+
+        if !clocked.is_empty() {
+            output.push_str(&format!("{}}}\n", self.indent));
+        }
+        Ok(output)
+    }
+
+    fn clock_domain(&mut self, entity: &Entity, clocked: &[&Process]) -> String {
+        let edges: Vec<(String, String)> = clocked.iter()
+            .flat_map(|p| EDGE_RE.captures_iter(&p.body).map(|caps| (caps[1].to_lowercase(), caps[2].to_string())))
+            .collect();
+        let clock = TestbenchGenerator::detect_clock(entity)
+            .or_else(|| edges.first().map(|(_, signal)| signal.clone()))
+            .unwrap_or_else(|| "clk".to_string());
+        if edges.iter().any(|(_, signal)| !signal.eq_ignore_ascii_case(&clock)) {
+            self.warn("multiple clocks", "all clocked processes share the clock domain of the first clock");
+        }
+
+        let edge = if edges.iter().all(|(edge, _)| edge == "falling_edge") { "FALLING" } else { "RISING" };
+        let async_reset = self.reset.clone().filter(|(name, _)| {
+            clocked.iter().any(|p| p.sensitivity_list.iter().any(|s| s.eq_ignore_ascii_case(name)))
+        });
+
+        let domain = match async_reset {
+            Some((reset, active_low)) => format!(
+                "ClockDomain({}, {}, config = ClockDomainConfig(clockEdge = {}, resetKind = ASYNC, resetActiveLevel = {}))",
+                self.operand(&clock),
+                self.operand(&reset),
+                edge,
+                if active_low { "LOW" } else { "HIGH" }
+            ),
+            None => format!("ClockDomain({}, config = ClockDomainConfig(clockEdge = {}))", self.operand(&clock), edge),
+        };
+        format!("{}val clockDomain = {}\n", self.indent, domain)
+    }
+
+    /// Line range `[if, elsif edge]` of a top-level `if reset ... elsif rising_edge(clk)` branch
+    fn async_reset_branch(&self, process: &Process, lines: &[&str]) -> Option<(usize, usize)> {
+        let (reset, _) = self.reset.as_ref()?;
+        if !process.sensitivity_list.iter().any(|s| s.eq_ignore_ascii_case(reset)) {
+            return None;
+        }
+
+        let start = lines.iter().position(|line| !line.is_empty())?;
+        let first = lines[start];
+        let mentions_reset = tokenize(first).iter().any(|t| matches!(t, Token::Ident(name) if name.eq_ignore_ascii_case(reset)));
+        if !first.starts_with("if") || EDGE_RE.is_match(first) || !mentions_reset {
+            return None;
+        }
+
+        let mut depth = 0;
+        for (i, line) in lines.iter().enumerate().skip(start + 1) {
+            if line.starts_with("if ") || line.starts_with("if(") {
+                depth += 1;
+            } else if *line == "end if" || *line == "end if;" {
+                if depth == 0 {
+                    return None;
+                }
+                depth -= 1;
+            } else if depth == 0 && line.starts_with("elsif") && EDGE_RE.is_match(line) {
+                return Some((start, i));
+            }
+        }
+        None
+    }
+
+    fn process_body(&mut self, process: &Process, depth: usize) -> Result<String> {
+        let mut emitter = BlockEmitter::new(&self.indent, depth);
+        let (lines, comments): (Vec<&str>, Vec<Option<&str>>) = process.body.lines()
+            .map(|line| split_comment(line.trim()))
+            .unzip();
+        let reset_branch = self.async_reset_branch(process, &lines);
+
+        for (index, &line) in lines.iter().enumerate() {
+            let comment = comments[index].map(convert_comment);
+
+            // The reset branch became init values, the edge test the clock domain
+            if let Some((start, end)) = reset_branch {
+                if (start..end).contains(&index) {
+                    continue;
+                }
+                if index == end {
+                    emitter.push_block(BlockKind::Elided);
+                    continue;
+                }
+            }
+
+            if line.is_empty() {
+                if let Some(comment) = comment {
+                    emitter.emit_line(&comment);
+                }
+                continue;
+            }
+            let emitted = emitter.emitted_len();
+
+            if line.starts_with("if") && EDGE_RE.is_match(line) {
+                emitter.push_block(BlockKind::Elided);
+            } else if line.starts_with("if ") || line.starts_with("if(") {
+                let condition = self.expression(strip_then(&line[2..]));
+                emitter.emit_line(&format!("when({}) {{", condition));
+                emitter.push_block(BlockKind::Begin);
+            } else if line.starts_with("elsif ") || line.starts_with("elsif(") || line == "else" {
+                if emitter.pop_block()? != BlockKind::Begin {
+                    return Err(anyhow::anyhow!("Unbalanced block: '{}' outside of an if statement", line));
+                }
+                if line == "else" {
+                    emitter.emit_line("}.otherwise {");
+                } else {
+                    let condition = self.expression(strip_then(&line[5..]));
+                    emitter.emit_line(&format!("}}.elsewhen({}) {{", condition));
+                }
+                emitter.push_block(BlockKind::Begin);
+            } else if line == "end if" || line == "end if;" || line == "end loop" || line == "end loop;" {
+                if emitter.pop_block()? == BlockKind::Begin {
+                    emitter.emit_line("}");
+                }
+            } else if line.starts_with("case ") && line.ends_with(" is") {
+                let selector = self.expression(&line["case ".len()..line.len() - " is".len()]);
+                emitter.emit_line(&format!("switch({}) {{", selector));
+                emitter.push_block(BlockKind::Case);
+            } else if let Some(item) = line.strip_prefix("when ") {
+                if emitter.top() == Some(BlockKind::CaseItem) {
+                    emitter.pop_block()?;
+                    emitter.emit_line("}");
+                }
+                if emitter.top() != Some(BlockKind::Case) {
+                    return Err(anyhow::anyhow!("Unbalanced block: 'when' outside of a case statement"));
+                }
+                let (choices, statement) = item.split_once("=>").unwrap_or((item, ""));
+                emitter.emit_line(&self.case_label(choices.trim()));
+                emitter.push_block(BlockKind::CaseItem);
+                if !statement.trim().is_empty() {
+                    let statement = self.sequential_statement(statement.trim());
+                    emitter.emit_line(&statement);
+                }
+            } else if line == "end case" || line == "end case;" {
+                if emitter.top() == Some(BlockKind::CaseItem) {
+                    emitter.pop_block()?;
+                    emitter.emit_line("}");
+                }
+                if emitter.pop_block()? != BlockKind::Case {
+                    return Err(anyhow::anyhow!("Unbalanced block: 'end case' does not close a case statement"));
+                }
+                emitter.emit_line("}");
+            } else if let Some(caps) = FOR_LOOP_RE.captures(line) {
+                // Loops unroll at elaboration time, like in synthesis
+                let (from, to) = if &caps[3] == "downto" { (&caps[4], &caps[2]) } else { (&caps[2], &caps[4]) };
+                let header = format!("for ({} <- {} to {}) {{", &caps[1], self.expression(from), self.expression(to));
+                emitter.emit_line(&header);
+                emitter.push_block(BlockKind::Begin);
+            } else if line == "null;" {
+                emitter.emit_line("// null");
+            } else {
+                let statement = self.sequential_statement(line);
+                emitter.emit_line(&statement);
+            }
+
+            if let Some(comment) = comment {
+                if emitter.emitted_len() > emitted {
+                    emitter.append_to_last_line(&comment);
+                } else {
+                    emitter.emit_line(&comment);
+                }
+            }
+        }
+
+        emitter.finish()
+    }
+
+    fn case_label(&mut self, choices: &str) -> String {
+        if choices.eq_ignore_ascii_case("others") {
+            return "default {".to_string();
+        }
+        let values: Vec<String> = choices.split('|')
+            .map(|choice| {
+                let choice = choice.trim();
+                if choice.contains(" to ") || choice.contains(" downto ") {
+                    self.warn(&format!("when {}", choice), "range choices have no SpinalHDL switch equivalent");
+                }
+                self.expression(choice)
+            })
+            .collect();
+        format!("is({}) {{", values.join(", "))
+    }
+
+    fn sequential_statement(&mut self, line: &str) -> String {
+        let statement = line.trim_end_matches(';').trim();
+        let lower = statement.to_lowercase();
+
+        if let Some((target, value)) = statement.split_once(" <= ") {
+            let target = target.trim();
+            return format!("{} := {}", self.expression(target), self.value(target, value.trim()));
+        }
+        if let Some((target, value)) = statement.split_once(" := ") {
+            self.warn(&format!("variable {}", target.trim()), "process variables are translated as signals; check read-after-write ordering");
+            let target = target.trim();
+            return format!("{} := {}", self.expression(target), self.value(target, value.trim()));
+        }
+
+        let construct = if lower.starts_with("wait") {
+            "wait statement"
+        } else if lower.starts_with("assert") || lower.starts_with("report") {
+            "assertion"
+        } else {
+            "statement"
+        };
+        self.warn(statement, &format!("{} has no SpinalHDL equivalent", construct));
+        format!("// TODO: {}", statement)
+    }
+
+    /// Right-hand side of an assignment to `target`, sizing `others` aggregates from its type
+    fn value(&mut self, target: &str, value: &str) -> String {
+        let Some(caps) = OTHERS_RE.captures(value) else {
+            return self.expression(value);
+        };
+        let fill = if &caps[1] == "1" { "True" } else { "False" };
+        let base = target.split('(').next().unwrap_or(target).trim();
+
+        let literal = match self.symbols.get(base) {
+            Some(VHDLType::StdLogicVector(range)) | Some(VHDLType::BitVector(range)) => Some(("B", width_expr(range))),
+            Some(VHDLType::Unsigned(range)) => Some(("U", width_expr(range))),
+            Some(VHDLType::Signed(range)) => Some(("S", width_expr(range))),
+            _ => None,
+        };
+        match literal {
+            Some((kind, width)) => format!("{}({} bits, default -> {})", kind, width, fill),
+            None => {
+                self.warn(&format!("{} <= {}", target, value), "aggregate target width is unknown");
+                format!("{}.{}", self.expression(target), if fill == "True" { "getAllTrue" } else { "getZero" })
+            }
+        }
+    }
+
+    /// A bare identifier, prefixed with `io.` when it is a port
+    fn operand(&self, name: &str) -> String {
+        if self.ports.contains(name) {
+            format!("io.{}", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn expression(&mut self, expr: &str) -> String {
+        let tokens = tokenize(expr.trim());
+        self.convert_tokens(&tokens)
+    }
+
+    fn convert_tokens(&mut self, tokens: &[Token]) -> String {
+        let mut output = String::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            match &tokens[i] {
+                Token::Ident(name) => {
+                    let lower = name.to_lowercase();
+                    let call = if is_symbol(tokens.get(i + 1), "(") { matching_paren(tokens, i + 1) } else { None };
+                    if let (Some(close), true) = (call, is_conversion(&lower)) {
+                        let args = self.call_arguments(&tokens[i + 2..close]);
+                        output.push_str(&self.conversion(name, &lower, &args));
+                        i = close + 1;
+                        continue;
+                    }
+
+                    match lower.as_str() {
+                        "and" => output.push('&'),
+                        "or" => output.push('|'),
+                        "xor" => output.push('^'),
+                        "mod" | "rem" => output.push('%'),
+                        "not" => {
+                            output.push('~');
+                            if tokens.get(i + 1) == Some(&Token::Space) {
+                                i += 1;
+                            }
+                        }
+                        "nand" | "nor" | "xnor" | "sll" | "srl" | "sla" | "sra" | "rol" | "ror" => {
+                            self.warn(&lower, "operator has no direct SpinalHDL equivalent");
+                            output.push_str(name);
+                        }
+                        _ => output.push_str(&self.operand(name)),
+                    }
+                }
+                Token::Number(number) => output.push_str(number),
+                Token::Char(c) => match c {
+                    '0' => output.push_str("False"),
+                    '1' => output.push_str("True"),
+                    other => {
+                        self.warn(&format!("'{}'", other), "std_logic value has no SpinalHDL equivalent");
+                        output.push_str("False");
+                    }
+                },
+                Token::BitString { base, digits } => match base {
+                    Some('x') => output.push_str(&format!("B\"x{}\"", digits)),
+                    Some('o') => output.push_str(&format!("B\"o{}\"", digits)),
+                    _ => output.push_str(&format!("B\"{}\"", digits)),
+                },
+                Token::Symbol(symbol) => match symbol.as_str() {
+                    "=" => output.push_str("==="),
+                    "/=" => output.push_str("=/="),
+                    "&" => output.push_str("##"),
+                    "'" => {
+                        // Attribute: a'length, a'high
+                        match tokens.get(i + 1) {
+                            Some(Token::Ident(attr)) if attr.eq_ignore_ascii_case("length") => output.push_str(".getWidth"),
+                            Some(Token::Ident(attr)) if attr.eq_ignore_ascii_case("high") => output.push_str(".high"),
+                            Some(Token::Ident(attr)) => {
+                                self.warn(&format!("'{}", attr), "attribute has no SpinalHDL equivalent");
+                                output.push_str(&format!("/* '{} */", attr));
+                            }
+                            _ => output.push('\''),
+                        }
+                        i += 1;
+                    }
+                    other => output.push_str(other),
+                },
+                Token::Space => output.push(' '),
+            }
+            i += 1;
+        }
+
+        output
+    }
+
+    fn call_arguments(&mut self, tokens: &[Token]) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut depth = 0;
+        let mut start = 0;
+        for (i, token) in tokens.iter().enumerate() {
+            if is_symbol(Some(token), "(") {
+                depth += 1;
+            } else if is_symbol(Some(token), ")") {
+                depth -= 1;
+            } else if depth == 0 && is_symbol(Some(token), ",") {
+                args.push(self.convert_tokens(&tokens[start..i]).trim().to_string());
+                start = i + 1;
+            }
+        }
+        args.push(self.convert_tokens(&tokens[start..]).trim().to_string());
+        args
+    }
+
+    fn conversion(&mut self, name: &str, lower: &str, args: &[String]) -> String {
+        let arg = |index: usize| args.get(index).cloned().unwrap_or_default();
+        match lower {
+            "unsigned" => format!("{}.asUInt", wrap_operand(&arg(0))),
+            "signed" => format!("{}.asSInt", wrap_operand(&arg(0))),
+            "std_logic_vector" => format!("{}.asBits", wrap_operand(&arg(0))),
+            // Spinal indexes and compares with UInt directly
+            "to_integer" => arg(0),
+            "to_unsigned" => format!("U({}, {} bits)", arg(0), arg(1)),
+            "to_signed" => format!("S({}, {} bits)", arg(0), arg(1)),
+            "resize" => format!("{}.resize({})", wrap_operand(&arg(0)), arg(1)),
+            _ => {
+                self.warn(&format!("{}(...)", name), "edge detection outside of a clocked process");
+                format!("{}({})", name, args.join(", "))
+            }
+        }
+    }
+
+    fn concurrent_statement(&mut self, stmt: &str, depth: usize) -> String {
+        let pad = self.indent.repeat(depth);
+        let stmt = stmt.trim().trim_end_matches(';');
+
+        if stmt.starts_with("with ") && stmt.contains(" select") {
+            self.warn("with ... select", "selected signal assignments are not translated; use switch or a Mux chain");
+            return stmt.lines().map(|line| format!("{}// TODO: {}\n", pad, line.trim())).collect();
+        }
+
+        let Some((target, rest)) = stmt.split_once(" <= ") else {
+            return format!("{}{}\n", pad, self.sequential_statement(stmt));
+        };
+        let target = target.trim();
+
+        // "a when c1 else b when c2 else d" -> Mux(c1, a, Mux(c2, b, d))
+        let branches: Vec<&str> = rest.split(" else ").collect();
+        let mut value = self.value(target, branches[branches.len() - 1].trim());
+        for branch in branches[..branches.len() - 1].iter().rev() {
+            match branch.split_once(" when ") {
+                Some((choice, condition)) => {
+                    let choice = self.value(target, choice.trim());
+                    value = format!("Mux({}, {}, {})", self.expression(strip_outer_parens(condition)), choice, value);
+                }
+                None => self.warn(stmt, "unrecognized conditional assignment"),
+            }
+        }
+
+        format!("{}{} := {}\n", pad, self.expression(target), value)
+    }
+
+    fn instantiation(&mut self, inst: &Instantiation, depth: usize) -> String {
+        let pad = self.indent.repeat(depth);
+        let generics: Vec<String> = inst.generic_map.iter()
+            .filter_map(|assoc| {
+                let Actual::Expression(value) = &assoc.actual else { return None };
+                let value = self.expression(value);
+                Some(match &assoc.formal {
+                    Some(formal) => format!("{} = {}", formal, value),
+                    None => value,
+                })
+            })
+            .collect();
+
+        let mut output = format!("{}val {} = {}({})\n", pad, inst.label, scala_class_name(&inst.component), generics.join(", "));
+        for assoc in &inst.port_map {
+            match (&assoc.formal, &assoc.actual) {
+                (Some(formal), Actual::Expression(actual)) => {
+                    let actual = self.expression(actual);
+                    output.push_str(&format!("{}{}.io.{} <> {}\n", pad, inst.label, formal, actual));
+                }
+                (Some(formal), Actual::Open) => {
+                    output.push_str(&format!("{}// {}.io.{} left open\n", pad, inst.label, formal));
+                }
+                (None, actual) => {
+                    self.warn(&format!("{} port map", inst.label), "positional associations need the component's port order");
+                    let actual = match actual {
+                        Actual::Expression(actual) => actual.as_str(),
+                        Actual::Open => "open",
+                    };
+                    output.push_str(&format!("{}// TODO: connect {} to {}\n", pad, actual, inst.label));
+                }
+            }
+        }
+        output
+    }
+
+    fn generate_block(&mut self, block: &GenerateBlock, depth: usize) -> String {
+        let pad = self.indent.repeat(depth);
+        let mut output = format!("{}// {}\n", pad, block.label);
+
+        // Generate statements run at elaboration time, like Scala control flow
+        let header = match &block.scheme {
+            GenerateScheme::For { var, left, right, downto } => {
+                let (from, to) = if *downto { (right, left) } else { (left, right) };
+                format!("for ({} <- {} to {}) {{", var, self.expression(from), self.expression(to))
+            }
+            GenerateScheme::If { condition } => {
+                let condition = self.expression(strip_outer_parens(condition)).replace("===", "==").replace("=/=", "!=");
+                format!("if ({}) {{", condition)
+            }
+        };
+        output.push_str(&format!("{}{}\n", pad, header));
+
+        for stmt in &block.concurrent_statements {
+            output.push_str(&self.concurrent_statement(stmt, depth + 1));
+        }
+        for inst in &block.instantiations {
+            output.push_str(&self.instantiation(inst, depth + 1));
+        }
+        for nested in &block.generate_blocks {
+            output.push_str(&self.generate_block(nested, depth + 1));
+        }
+
+        output.push_str(&format!("{}}}\n", pad));
+        output
+    }
+}
+
+/// Blank line between groups, except right after an opening brace
+fn separate(output: &mut String) {
+    if !output.ends_with("{\n") {
+        output.push('\n');
+    }
+}
+
+fn is_conversion(name: &str) -> bool {
+    matches!(
+        name,
+        "unsigned" | "signed" | "std_logic_vector" | "to_integer" | "to_unsigned" | "to_signed" | "resize" | "rising_edge" | "falling_edge"
+    )
+}
+
+/// Base names of the signals a process body assigns with `<=`
+fn assigned_targets(lines: &[&str]) -> Vec<String> {
+    lines.iter()
+        .filter_map(|line| {
+            // A case item may carry its statement: "when IDLE => state <= RUN;"
+            let statement = match line.strip_prefix("when ") {
+                Some(item) => item.split_once("=>").map_or("", |(_, rest)| rest).trim(),
+                None => line,
+            };
+            let (target, _) = statement.split_once(" <= ")?;
+            let base = target.split('(').next().unwrap_or(target).trim();
+            (!base.is_empty() && !base.contains(' ')).then(|| base.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Generic, Signal};
+
+    fn vector(left: RangeExpr) -> VectorRange {
+        VectorRange { left, right: 0.into(), downto: true }
+    }
+
+    fn counter() -> Entity {
+        let mut entity = Entity::new("counter".to_string());
+        entity.add_port(Port::new("clk".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("rst_n".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("enable".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("count".to_string(), PortDirection::Out, VHDLType::Unsigned(vector(7.into()))));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![Process {
+                label: Some("tick".to_string()),
+                sensitivity_list: vec!["clk".to_string(), "rst_n".to_string()],
+                body: "if rst_n = '0' then\ncount <= (others => '0');\nelsif rising_edge(clk) then\nif enable = '1' then -- count up\ncount <= count + 1;\nend if;\nend if;".to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    fn fifo() -> Entity {
+        let width = RangeExpr::Parametric { base: "WIDTH".to_string(), offset: -1 };
+        let mut entity = Entity::new("fifo_sync".to_string());
+        for (name, default) in [("DEPTH", "16"), ("WIDTH", "8")] {
+            entity.add_generic(Generic {
+                name: name.to_string(),
+                generic_type: "natural".to_string(),
+                default_value: Some(default.to_string()),
+            });
+        }
+        entity.add_port(Port::new("clk".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("wr_en".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("rd_en".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("din".to_string(), PortDirection::In, VHDLType::StdLogicVector(vector(width.clone()))));
+        entity.add_port(Port::new("dout".to_string(), PortDirection::Out, VHDLType::StdLogicVector(vector(width))));
+        entity.add_port(Port::new("empty".to_string(), PortDirection::Out, VHDLType::StdLogic));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![
+                Signal { name: "mem".to_string(), signal_type: VHDLType::Custom("mem_t".to_string()), source_line: None },
+                Signal { name: "wr_ptr".to_string(), signal_type: VHDLType::Unsigned(vector(3.into())), source_line: None },
+                Signal { name: "rd_ptr".to_string(), signal_type: VHDLType::Unsigned(vector(3.into())), source_line: None },
+            ],
+            processes: vec![Process {
+                label: None,
+                sensitivity_list: vec!["clk".to_string()],
+                body: "if rising_edge(clk) then\nif wr_en = '1' then\nmem(to_integer(wr_ptr)) <= din;\nwr_ptr <= wr_ptr + 1;\nend if;\nif rd_en = '1' then\ndout <= mem(to_integer(rd_ptr));\nrd_ptr <= rd_ptr + 1;\nend if;\nend if;".to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec!["empty <= '1' when wr_ptr = rd_ptr else '0';".to_string()],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    #[test]
+    fn test_counter_matches_golden() {
+        let (scala, report) = SpinalHDLGenerator::new().generate(&counter()).unwrap();
+
+        crate::ir::golden::assert_golden(&scala, "counter.scala");
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    #[test]
+    fn test_fifo_registers_and_warnings() {
+        let (scala, report) = SpinalHDLGenerator::new().generate(&fifo()).unwrap();
+        let warnings = &report.warnings;
+
+        assert!(scala.starts_with("import spinal.core._\n\ncase class FifoSync(DEPTH: Int = 16, WIDTH: Int = 8) extends Component {\n"));
+        assert!(scala.contains("val din = in Bits(WIDTH bits)"));
+        assert!(scala.contains("val clockDomain = ClockDomain(io.clk, config = ClockDomainConfig(clockEdge = RISING))"));
+        assert!(scala.contains("val wr_ptr = Reg(UInt(4 bits))\n"));
+        assert!(scala.contains("io.dout.setAsReg()\n"));
+        assert!(scala.contains("when(io.wr_en === True) {\n      mem(wr_ptr) := io.din\n"));
+        assert!(scala.contains("io.empty := Mux(wr_ptr === rd_ptr, True, False)"));
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].construct, "mem_t");
+        assert_eq!(warnings[0].to_string(), "mem_t: user-defined type; declare it as a SpinalEnum or Bundle");
+        assert_eq!(report.unsupported_constructs, vec!["mem_t"]);
+        let din = report.port_mappings.iter().find(|m| m.vhdl_name == "din").unwrap();
+        assert_eq!(din.hdl_type, "Bits(WIDTH bits)");
+    }
+
+    #[test]
+    fn test_stub_is_black_box() {
+        let stub = SpinalHDLGenerator::new().generate_stub(&fifo()).unwrap();
+
+        assert!(stub.contains("case class FifoSync(DEPTH: Int = 16, WIDTH: Int = 8) extends BlackBox {"));
+        assert!(stub.contains("setDefinitionName(\"fifo_sync\")\n  addGeneric(\"DEPTH\", DEPTH)"));
+        assert!(stub.contains("noIoPrefix()"));
+        assert!(!stub.contains("ClockingArea"));
+    }
+}
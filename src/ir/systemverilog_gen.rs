@@ -1,44 +1,206 @@
 use crate::ir::{Entity, Architecture, Port, PortDirection, VHDLType};
+use crate::ir::hdl_body::{convert_comment, Dialect, HdlBodyConverter, SystemVerilogDialect};
+use crate::ir::formatter::HdlFormatter;
+use crate::ir::report::ConversionReport;
+use crate::ir::sanitize::{sanitize_entity, Rename};
+use crate::ir::source_map::source_ref;
+use crate::ir::report::TranspilationWarning;
+use crate::ir::type_resolution::TypeResolutionContext;
 use anyhow::Result;
 
 /// Generate SystemVerilog 2012 module from Entity IR
 /// This generator produces synthesizable SystemVerilog code following IEEE 1800-2012
 pub struct SystemVerilogGenerator {
     indent: String,
+    black_box: bool,
+    strip_comments: bool,
+    format: bool,
+    synthesizable: bool,
+    fix_latches: bool,
+    blocking_assignments: bool,
+    source_refs: Option<String>,
+    types: Option<TypeResolutionContext>,
 }
 
 impl SystemVerilogGenerator {
     pub fn new() -> Self {
         Self {
             indent: "    ".to_string(),
+            black_box: false,
+            strip_comments: false,
+            format: true,
+            synthesizable: false,
+            fix_latches: false,
+            blocking_assignments: true,
+            source_refs: None,
+            types: None,
         }
     }
 
     pub fn with_indent(indent: String) -> Self {
-        Self { indent }
+        Self { indent, ..Self::new() }
     }
 
-    /// Generate complete SystemVerilog module from entity
-    pub fn generate(&self, entity: &Entity) -> Result<String> {
+    /// Annotate stubs with `(* black_box *)` and `// pragma: black_box`
+    pub fn with_black_box(mut self, black_box: bool) -> Self {
+        self.black_box = black_box;
+        self
+    }
+
+    /// Drop comments carried over from the VHDL source
+    pub fn with_strip_comments(mut self, strip_comments: bool) -> Self {
+        self.strip_comments = strip_comments;
+        self
+    }
+
+    /// Append `// vhdl:<source_name>:<line>` to declarations, always blocks and assigns
+    /// whose VHDL line is known; `None` disables the comments
+    pub fn with_source_refs(mut self, source_name: Option<String>) -> Self {
+        self.source_refs = source_name;
+        self
+    }
+
+    /// Run the `HdlFormatter` layout pass over the output (default: true)
+    pub fn with_formatting(mut self, format: bool) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Skip testbench-style processes (those using `wait`) with a warning instead of
+    /// converting them to `initial`/`always` blocks
+    pub fn with_synthesizable(mut self, synthesizable: bool) -> Self {
+        self.synthesizable = synthesizable;
+        self
+    }
+
+    /// Give signals that would infer a latch a default assignment at the top of their
+    /// process, converting it to a combinational block; each fix is reported as a warning
+    pub fn with_fix_latches(mut self, fix_latches: bool) -> Self {
+        self.fix_latches = fix_latches;
+        self
+    }
+
+    /// Emit signal assignments in combinational blocks as blocking `=` (default: true);
+    /// false keeps VHDL's `<=` as written. Sequential blocks keep `<=` either way
+    pub fn with_blocking_assignments(mut self, blocking_assignments: bool) -> Self {
+        self.blocking_assignments = blocking_assignments;
+        self
+    }
+
+    /// Substitute custom types with their definitions from `types`, e.g. package
+    /// subtypes and enumerations; unresolvable ones are reported as warnings
+    pub fn with_type_context(mut self, types: Option<TypeResolutionContext>) -> Self {
+        self.types = types;
+        self
+    }
+
+    /// Generate a module stub with the entity's ports and no body
+    pub fn generate_stub(&self, entity: &Entity) -> Result<String> {
+        let (resolved, _) = self.type_context(entity).resolve_entity(entity);
+        let (sanitized, _) = self.sanitize(&resolved);
+        let entity = &sanitized;
+        let mut output = String::new();
+
+        if self.black_box {
+            output.push_str("// pragma: black_box\n");
+            output.push_str("(* black_box *)\n");
+        }
+
+        output.push_str(&self.generate_module_header(entity)?);
+        output.push_str("endmodule\n");
+
+        Ok(self.finish(output))
+    }
+
+    /// Generate complete SystemVerilog module from entity, with the mapping decisions made,
+    /// renamed identifiers and warnings
+    pub fn generate(&self, entity: &Entity) -> Result<(String, ConversionReport)> {
+        let original = entity;
+        let types = self.type_context(entity);
+        let enumerations = types.enumerations(entity);
+        let (resolved, mut warnings) = types.resolve_entity(entity);
+        let mut unsupported: Vec<String> = warnings.iter().map(|w| w.construct.clone()).collect();
+        let (sanitized, renames) = self.sanitize(&resolved);
+        let entity = &sanitized;
         let mut output = String::new();
 
         // Module header with ports in SystemVerilog ANSI-style
         output.push_str(&self.generate_module_header(entity)?);
 
         // Module body
-        output.push_str(&self.generate_module_body(entity)?);
+        output.push_str(&self.generate_enum_parameters(&enumerations));
+        output.push_str(&self.generate_module_body(entity, &mut warnings)?);
 
         // Module footer
         output.push_str("endmodule\n");
 
-        Ok(output)
+        for warning in &warnings {
+            tracing::warn!("SystemVerilog output for {}: {}", entity.name, warning);
+        }
+        if self.synthesizable {
+            let skipped = entity.architecture.iter()
+                .flat_map(|arch| &arch.processes)
+                .filter(|process| process.testbench_style)
+                .map(|process| format!("process {}", process.label.as_deref().unwrap_or("<unlabeled>")));
+            unsupported.extend(skipped);
+        }
+        let report = ConversionReport::new(original, entity, |port| port.port_type.to_systemverilog(), VHDLType::to_systemverilog)
+            .with_renames(renames)
+            .with_unsupported_constructs(unsupported)
+            .with_warnings(warnings);
+        Ok((self.finish(output), report))
+    }
+
+    fn finish(&self, output: String) -> String {
+        if self.format {
+            HdlFormatter::new(&self.indent).format(&output)
+        } else {
+            output
+        }
+    }
+
+    /// The generator's types with the architecture's local types on top
+    fn type_context(&self, entity: &Entity) -> std::borrow::Cow<'_, TypeResolutionContext> {
+        let local = entity.architecture.as_ref().map(|arch| &arch.types).filter(|types| !types.is_empty());
+        match (&self.types, local) {
+            (Some(types), None) => std::borrow::Cow::Borrowed(types),
+            (None, None) => std::borrow::Cow::Owned(TypeResolutionContext::new()),
+            (types, Some(local)) => std::borrow::Cow::Owned(types.clone().unwrap_or_default().with_context(local)),
+        }
+    }
+
+    /// One `localparam` per enumeration literal, numbered in declaration order
+    fn generate_enum_parameters(&self, enumerations: &[(u32, Vec<String>)]) -> String {
+        let mut output = String::new();
+        for (width, literals) in enumerations {
+            output.push('\n');
+            for (value, literal) in literals.iter().enumerate() {
+                output.push_str(&format!("{}localparam logic {} {} = {}'d{};\n", self.indent, VectorRange::to_verilog_part_select(width - 1, 0), literal, width, value));
+            }
+        }
+        output
+    }
+
+    fn sanitize(&self, entity: &Entity) -> (Entity, Vec<Rename>) {
+        let (entity, renames) = sanitize_entity(entity, |name| SystemVerilogDialect.is_reserved(name));
+        for rename in &renames {
+            tracing::warn!("Renamed identifier in {}: {}", entity.name, rename);
+        }
+        (entity, renames)
     }
 
     fn generate_module_header(&self, entity: &Entity) -> Result<String> {
         let mut output = String::new();
 
+        if !self.strip_comments {
+            for comment in &entity.header_comments {
+                output.push_str(&convert_comment(comment));
+                output.push('\n');
+            }
+        }
+
         // Start module declaration
-        output.push_str(&format!("module {} (\n", entity.name));
+        output.push_str(&entity.module_declaration(&self.indent));
 
         // Generate port list in ANSI style (SystemVerilog)
         if !entity.ports.is_empty() {
@@ -55,6 +217,11 @@ impl SystemVerilogGenerator {
                 if i < entity.ports.len() - 1 {
                     output.push(',');
                 }
+                if let Some(comment) = port.comment.as_deref().filter(|_| !self.strip_comments) {
+                    output.push(' ');
+                    output.push_str(&convert_comment(comment));
+                }
+                output.push_str(&self.source_ref(port.source_line));
                 output.push('\n');
             }
         }
@@ -64,18 +231,28 @@ impl SystemVerilogGenerator {
         Ok(output)
     }
 
-    fn generate_module_body(&self, entity: &Entity) -> Result<String> {
+    fn generate_module_body(&self, entity: &Entity, warnings: &mut Vec<TranspilationWarning>) -> Result<String> {
         let mut output = String::new();
 
         // If there's an architecture, generate the implementation
         if let Some(arch) = &entity.architecture {
-            output.push_str(&self.generate_architecture_body(arch)?);
+            let converter = HdlBodyConverter::new(SystemVerilogDialect, self.indent.clone())
+                .with_symbols(entity.symbol_table())
+                .with_comments(!self.strip_comments)
+                .with_fix_latches(self.fix_latches)
+                .with_blocking_assignments(self.blocking_assignments);
+            output.push_str(&self.generate_architecture_body(arch, &converter, warnings)?);
         }
 
         Ok(output)
     }
 
-    fn generate_architecture_body(&self, arch: &Architecture) -> Result<String> {
+    fn generate_architecture_body(
+        &self,
+        arch: &Architecture,
+        converter: &HdlBodyConverter<SystemVerilogDialect>,
+        warnings: &mut Vec<TranspilationWarning>,
+    ) -> Result<String> {
         let mut output = String::new();
 
         // Generate signal declarations using 'logic' type
@@ -84,372 +261,70 @@ impl SystemVerilogGenerator {
             for signal in &arch.signals {
                 output.push_str(&self.indent);
                 let sv_type = signal.signal_type.to_systemverilog();
-                output.push_str(&format!("{} {};\n", sv_type, signal.name));
+                output.push_str(&format!("{} {};{}\n", sv_type, signal.name, self.source_ref(signal.source_line)));
             }
         }
 
-        // Generate processes as always_comb or always_ff blocks
+        // Generate processes as always_comb or always_ff blocks, wait-based ones as initial/always
         for process in &arch.processes {
+            if process.testbench_style && self.synthesizable {
+                warnings.push(TranspilationWarning {
+                    construct: format!("process {}", process.label.as_deref().unwrap_or("<unlabeled>")),
+                    message: "wait statements are not synthesizable; process skipped".to_string(),
+                });
+                continue;
+            }
+            warnings.extend(converter.latch_warnings(process));
             output.push('\n');
-            output.push_str(&self.generate_process(process)?);
+            let block = if process.testbench_style {
+                converter.convert_timed_process(process)?
+            } else {
+                converter.convert_process(process)?
+            };
+            output.push_str(&self.append_to_first_line(block, process.source_line));
         }
 
         // Generate concurrent statements as continuous assignments
-        for stmt in &arch.concurrent_statements {
+        for (i, stmt) in arch.concurrent_statements.iter().enumerate() {
             output.push('\n');
             output.push_str(&self.indent);
-            output.push_str(&self.convert_concurrent_statement(stmt)?);
+            let assign = converter.convert_concurrent_statement(stmt)?;
+            output.push_str(&self.append_to_first_line(assign, arch.concurrent_statement_lines.get(i).copied()));
             output.push('\n');
         }
 
-        Ok(output)
-    }
-
-    fn generate_process(&self, process: &crate::ir::Process) -> Result<String> {
-        let mut output = String::new();
-
-        // Determine if it's sequential or combinational based on sensitivity list
-        let is_sequential = process.sensitivity_list.iter()
-            .any(|s| s.contains("clk") || s.contains("clock") || s.contains("rising_edge") || s.contains("falling_edge"));
-
-        output.push_str(&self.indent);
-
-        if is_sequential {
-            // Sequential logic - always_ff @(posedge clk)
-            let mut edge_signals = Vec::new();
-            let mut has_async_reset = false;
-            let mut async_reset_edge = String::new();
-            
-            for sig in &process.sensitivity_list {
-                if sig.contains("clk") || sig.contains("clock") {
-                    edge_signals.push(format!("posedge {}", sig));
-                } else if sig.contains("reset") || sig.contains("rst") {
-                    // Check if active high or low reset
-                    if process.body.contains(&format!("{} = '1'", sig)) || process.body.contains(&format!("{} = \"1\"", sig)) {
-                        async_reset_edge = format!("posedge {}", sig);
-                    } else {
-                        async_reset_edge = format!("negedge {}", sig);
-                    }
-                    has_async_reset = true;
-                }
-            }
-
-            if edge_signals.is_empty() {
-                edge_signals.push("posedge clk".to_string());
-            }
-
-            if has_async_reset {
-                edge_signals.push(async_reset_edge);
-                output.push_str(&format!("always_ff @({}) begin\n", edge_signals.join(" or ")));
-            } else {
-                output.push_str(&format!("always_ff @({}) begin\n", edge_signals.join(" or ")));
-            }
-        } else {
-            // Combinational logic - always_comb
-            output.push_str("always_comb begin\n");
+        // Generate component/entity instantiations as module instances
+        for inst in &arch.instantiations {
+            output.push('\n');
+            output.push_str(&converter.convert_instantiation(inst, 1));
         }
 
-        // Convert VHDL process body to SystemVerilog
-        let sv_body = self.convert_process_body(&process.body)?;
-        output.push_str(&sv_body);
-
-        output.push_str(&self.indent);
-        output.push_str("end\n");
-
-        Ok(output)
-    }
-
-    fn convert_process_body(&self, vhdl_body: &str) -> Result<String> {
-        let mut output = String::new();
-        let double_indent = format!("{}{}", self.indent, self.indent);
-        let triple_indent = format!("{}{}{}", self.indent, self.indent, self.indent);
-        let mut in_case = false;
-        let mut case_branch_has_stmt = false;
-        let mut indent_level = 0;
-
-        for line in vhdl_body.lines() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() || trimmed.starts_with("--") {
-                continue;
-            }
-
-            let mut sv_line = trimmed.to_string();
-
-            // Skip lines with rising_edge/falling_edge as they're handled in sensitivity list
-            if sv_line.starts_with("if") && (sv_line.contains("rising_edge") || sv_line.contains("falling_edge")) {
-                continue;
-            }
-
-            // Convert hex literals: x"0" -> 4'h0, x"FF" -> 8'hFF
-            let hex_re = regex::Regex::new(r#"x"([0-9A-Fa-f]+)""#).unwrap();
-            sv_line = hex_re.replace_all(&sv_line, |caps: &regex::Captures| {
-                let hex_value = &caps[1];
-                let bit_width = hex_value.len() * 4;
-                format!("{}'h{}", bit_width, hex_value)
-            }).to_string();
-
-            // Convert bit literals and comparison operators
-            sv_line = sv_line.replace("='1'", " == 1'b1");
-            sv_line = sv_line.replace("='0'", " == 1'b0");
-            sv_line = sv_line.replace(" = '1'", " == 1'b1");
-            sv_line = sv_line.replace(" = '0'", " == 1'b0");
-            sv_line = sv_line.replace("'1'", "1'b1");
-            sv_line = sv_line.replace("'0'", "1'b0");
-
-            // Convert others => value to '0 (SystemVerilog replication)
-            if sv_line.contains("(others =>") {
-                // Extract the replicated value
-                if sv_line.contains("1'b0") {
-                    sv_line = sv_line.replace("(others => 1'b0)", "'0");
-                } else if sv_line.contains("1'b1") {
-                    sv_line = sv_line.replace("(others => 1'b1)", "'1");
-                }
-            }
-
-            // Convert case statements to unique case (for synthesis)
-            if sv_line.starts_with("case ") && sv_line.contains(" is") {
-                sv_line = sv_line.replace(" is", "");
-                sv_line = sv_line.replacen("case ", "unique case (", 1);
-                if !sv_line.ends_with(")") {
-                    sv_line.push(')');
-                }
-                in_case = true;
-                case_branch_has_stmt = false;
-            } else if sv_line.starts_with("when ") {
-                // Close previous case branch if it had statements
-                if in_case && case_branch_has_stmt {
-                    output.push_str(&format!("{}end\n", &double_indent));
-                    case_branch_has_stmt = false;
-                }
-
-                if let Some(value_end) = sv_line.find(" =>") {
-                    let value_part = &sv_line[5..value_end];
-                    let value = value_part.trim();
-                    if value == "others" {
-                        sv_line = "default: begin".to_string();
-                    } else if value.starts_with('"') && value.ends_with('"') {
-                        let binary = value.trim_matches('"');
-                        let width = binary.len();
-                        sv_line = format!("{}'b{}: begin", width, binary);
-                    } else {
-                        sv_line = format!("{}: begin", value);
-                    }
-                }
-            } else if sv_line == "end case" || sv_line == "end case;" {
-                if in_case && case_branch_has_stmt {
-                    output.push_str(&format!("{}end\n", &double_indent));
-                }
-                sv_line = "endcase".to_string();
-                in_case = false;
-                case_branch_has_stmt = false;
-            }
-
-            // Handle if/elsif/else/then keywords
-            let is_if = sv_line.starts_with("if ") || sv_line.starts_with("if(");
-            let is_elsif = sv_line.starts_with("elsif ") || sv_line.starts_with("elsif(");
-            let is_else = sv_line.trim() == "else";
-            let is_endif = sv_line == "end if" || sv_line == "end if;";
-
-            if is_if {
-                if sv_line.starts_with("if(") {
-                    sv_line = sv_line.replacen("if(", "if (", 1);
-                }
-                if sv_line.contains(" then") {
-                    sv_line = sv_line.replace(" then", " begin");
-                    if !sv_line.contains(")") && sv_line.matches('(').count() > 0 {
-                        let begin_pos = sv_line.find(" begin").unwrap();
-                        sv_line.insert(begin_pos, ')');
-                    }
-                } else if sv_line.contains(" begin") {
-                    if !sv_line.contains(')') && sv_line.contains('(') {
-                        sv_line = sv_line.replace(" begin", ") begin");
-                    }
-                } else {
-                    if sv_line.contains('(') && !sv_line.contains(')') {
-                        sv_line.push_str(") begin");
-                    } else if !sv_line.ends_with("begin") {
-                        sv_line.push_str(" begin");
-                    }
-                }
-            } else if is_elsif {
-                if sv_line.contains("rising_edge") || sv_line.contains("falling_edge") {
-                    sv_line = "end else begin".to_string();
-                } else {
-                    sv_line = sv_line.replacen("elsif ", "end else if (", 1);
-                    sv_line = sv_line.replace(" then", ") begin");
-                    if !sv_line.contains(") begin") {
-                        sv_line.push_str(" begin");
-                    }
-                }
-            } else if is_else {
-                sv_line = "end else begin".to_string();
-            } else if is_endif {
-                sv_line = "end".to_string();
-            }
-
-            // Convert logical operators
-            sv_line = sv_line.replace(" and ", " & ");
-            sv_line = sv_line.replace(" or ", " | ");
-            sv_line = sv_line.replace(" xor ", " ^ ");
-            sv_line = sv_line.replace(" not ", " ~");
-
-            // Convert type conversions - SystemVerilog doesn't need most of these
-            sv_line = sv_line.replace("std_logic_vector(unsigned(", "");
-            sv_line = sv_line.replace("std_logic_vector(signed(", "");
-            sv_line = sv_line.replace("std_logic_vector(", "");
-            sv_line = sv_line.replace("unsigned(", "");
-            sv_line = sv_line.replace("signed(", "");
-            sv_line = sv_line.replace("to_unsigned(", "");
-            sv_line = sv_line.replace("to_signed(", "");
-            sv_line = sv_line.replace("to_integer(", "int'(");
-
-            // Remove extra closing parens
-            let mut paren_diff = sv_line.matches(')').count() as i32 - sv_line.matches('(').count() as i32;
-            while paren_diff > 0 {
-                if let Some(pos) = sv_line.rfind(')') {
-                    sv_line.remove(pos);
-                    paren_diff -= 1;
-                } else {
-                    break;
-                }
-            }
-
-            // Don't add semicolons to control flow keywords
-            let is_control_flow = sv_line.contains("begin") ||
-                                   (sv_line.starts_with("end") && !sv_line.starts_with("endcase")) ||
-                                   sv_line == "else" ||
-                                   sv_line.ends_with(":") ||
-                                   sv_line.starts_with("unique case") ||
-                                   sv_line.starts_with("case") ||
-                                   sv_line == "endcase";
-
-            // Adjust indent level
-            if sv_line.starts_with("end") {
-                if indent_level > 0 {
-                    indent_level -= 1;
-                }
-            }
-
-            let current_indent = match indent_level {
-                0 => double_indent.clone(),
-                1 => triple_indent.clone(),
-                _ => format!("{}{}", triple_indent, self.indent.repeat(indent_level - 1)),
-            };
-
-            output.push_str(&current_indent);
-            output.push_str(&sv_line);
-
-            if !is_control_flow && !sv_line.ends_with(';') {
-                output.push(';');
-            }
-
+        // Generate for/if generate statements inside a generate region
+        if !arch.generate_blocks.is_empty() {
             output.push('\n');
-
-            if sv_line.contains("begin") {
-                indent_level += 1;
-            }
-
-            if in_case && !is_control_flow && sv_line.contains(" <= ") {
-                case_branch_has_stmt = true;
-            }
+            output.push_str(&converter.convert_generate_blocks(&arch.generate_blocks)?);
         }
 
         Ok(output)
     }
 
-    fn convert_concurrent_statement(&self, stmt: &str) -> Result<String> {
-        let mut sv = stmt.to_string();
-
-        // Remove type conversions
-        sv = sv.replace("std_logic_vector(", "");
-        sv = sv.replace("unsigned(", "");
-        sv = sv.replace("signed(", "");
-
-        let mut paren_diff = sv.matches(')').count() as i32 - sv.matches('(').count() as i32;
-        while paren_diff > 0 {
-            if let Some(pos) = sv.rfind(')') {
-                sv.remove(pos);
-                paren_diff -= 1;
-            } else {
-                break;
-            }
+    /// ` // vhdl:<source>:<line>` when source refs are enabled and the line is known
+    fn source_ref(&self, line: Option<usize>) -> String {
+        match (&self.source_refs, line) {
+            (Some(source), Some(line)) => source_ref(source, line),
+            _ => String::new(),
         }
+    }
 
-        // Handle with...select statements
-        if sv.contains("with ") && sv.contains(" select") {
-            return Ok(format!("// TODO: Convert VHDL 'with...select' to SystemVerilog case:\n    // {}",
-                sv.replace("\n", "\n    // ")));
+    fn append_to_first_line(&self, code: String, line: Option<usize>) -> String {
+        let source_ref = self.source_ref(line);
+        if source_ref.is_empty() {
+            return code;
         }
-
-        // Handle conditional assignments
-        if sv.contains(" when ") && sv.contains(" else ") {
-            let parts: Vec<&str> = sv.split(" <= ").collect();
-            if parts.len() == 2 {
-                let target = parts[0].trim();
-                let rest = parts[1];
-
-                if let Some(when_pos) = rest.find(" when ") {
-                    if let Some(else_pos) = rest.find(" else ") {
-                        let value1 = rest[..when_pos].trim();
-                        let condition = rest[when_pos+6..else_pos].trim();
-                        let value2 = rest[else_pos+6..].trim();
-
-                        let mut cond_conv = condition.to_string();
-                        cond_conv = cond_conv.replace(" = ", " == ");
-                        
-                        // Convert binary literals in conditions
-                        if cond_conv.contains('"') {
-                            let mut result = String::new();
-                            let mut chars = cond_conv.chars().peekable();
-                            
-                            while let Some(ch) = chars.next() {
-                                if ch == '"' {
-                                    let mut binary = String::new();
-                                    while let Some(&next_ch) = chars.peek() {
-                                        if next_ch == '"' {
-                                            chars.next();
-                                            break;
-                                        }
-                                        if next_ch == '0' || next_ch == '1' {
-                                            binary.push(chars.next().unwrap());
-                                        } else {
-                                            chars.next();
-                                        }
-                                    }
-                                    
-                                    if !binary.is_empty() && binary.chars().all(|c| c == '0' || c == '1') {
-                                        result.push_str(&format!("{}'b{}", binary.len(), binary));
-                                    } else {
-                                        result.push('"');
-                                        result.push_str(&binary);
-                                        result.push('"');
-                                    }
-                                } else {
-                                    result.push(ch);
-                                }
-                            }
-                            cond_conv = result;
-                        }
-
-                        let val1_conv = value1.replace("'1'", "1'b1").replace("'0'", "1'b0");
-                        let val2_conv = value2.replace("'1'", "1'b1").replace("'0'", "1'b0");
-
-                        sv = format!("assign {} = {} ? {} : {};", target, cond_conv, val1_conv, val2_conv);
-                        return Ok(sv);
-                    }
-                }
-            }
+        match code.find('\n') {
+            Some(pos) => format!("{}{}{}", &code[..pos], source_ref, &code[pos..]),
+            None => code + &source_ref,
         }
-
-        sv = sv.replace(" <= ", " = ");
-        sv = sv.replace("'1'", "1'b1");
-        sv = sv.replace("'0'", "1'b0");
-
-        if sv.contains(" = ") && !sv.starts_with("assign ") {
-            sv = format!("assign {};", sv.trim_end_matches(';'));
-        }
-
-        Ok(sv)
     }
 }
 
@@ -479,6 +354,7 @@ impl VHDLType {
             VHDLType::Integer => "logic signed [31:0]".to_string(),
             VHDLType::Natural => "logic [31:0]".to_string(),
             VHDLType::Positive => "logic [31:0]".to_string(),
+            VHDLType::ConstrainedInteger(range) => format!("logic {}", range.to_verilog()),
             VHDLType::Boolean => "logic".to_string(),
             VHDLType::Bit => "logic".to_string(),
             VHDLType::BitVector(range) => format!("logic {}", range.to_systemverilog()),
@@ -494,11 +370,7 @@ use crate::ir::VectorRange;
 impl VectorRange {
     pub fn to_systemverilog(&self) -> String {
         // SystemVerilog uses [msb:lsb] format like Verilog
-        if self.downto {
-            format!("[{}:{}]", self.left, self.right)
-        } else {
-            format!("[{}:{}]", self.right, self.left)
-        }
+        self.to_verilog()
     }
 }
 
@@ -524,20 +396,20 @@ mod tests {
             "count".to_string(),
             PortDirection::Out,
             VHDLType::StdLogicVector(VectorRange {
-                left: 7,
-                right: 0,
+                left: 7.into(),
+                right: 0.into(),
                 downto: true,
             }),
         ));
 
         let generator = SystemVerilogGenerator::new();
-        let sv = generator.generate(&entity).unwrap();
+        let sv = generator.generate(&entity).unwrap().0;
 
         println!("Generated SystemVerilog:\n{}", sv);
 
         assert!(sv.contains("module counter"));
-        assert!(sv.contains("input logic clk"));
-        assert!(sv.contains("input logic reset"));
+        assert!(sv.contains("input  logic       clk"));
+        assert!(sv.contains("input  logic       reset"));
         assert!(sv.contains("output logic [7:0] count"));
         assert!(sv.contains("endmodule"));
     }
@@ -552,14 +424,540 @@ mod tests {
                 label: None,
                 sensitivity_list: vec!["a".to_string(), "b".to_string(), "sel".to_string()],
                 body: "if sel = '0' then\n    y <= a;\nelse\n    y <= b;\nend if;".to_string(),
+                source_line: None,
+                testbench_style: false,
             }],
             concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
         };
         entity.architecture = Some(arch);
 
         let generator = SystemVerilogGenerator::new();
-        let sv = generator.generate(&entity).unwrap();
+        let sv = generator.generate(&entity).unwrap().0;
 
         assert!(sv.contains("always_comb"));
     }
+
+    const NESTED_PROCESS_BODY: &str = r#"
+        if rising_edge(clk) then
+            if (rst = '1') then
+                state <= IDLE;
+                count <= x"00";
+            else
+                case state is
+                    when IDLE =>
+                        if (start = '1') then
+                            if (mode = '1') then
+                                state <= RUN;
+                            else
+                                state <= DONE;
+                            end if;
+                        end if;
+                    when RUN =>
+                        count <= count + x"01";
+                    when others =>
+                        state <= IDLE;
+                end case;
+            end if;
+        end if;
+    "#;
+
+    fn nested_process_entity(body: &str) -> Entity {
+        let mut entity = Entity::new("nested".to_string());
+        for name in ["clk", "rst", "start", "mode"] {
+            entity.add_port(Port::new(name.to_string(), PortDirection::In, VHDLType::StdLogic));
+        }
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![crate::ir::Process {
+                label: Some("fsm".to_string()),
+                sensitivity_list: vec!["clk".to_string()],
+                body: body.to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    #[test]
+    fn test_nested_if_case_matches_golden() {
+        let generator = SystemVerilogGenerator::new();
+        let output = generator.generate(&nested_process_entity(NESTED_PROCESS_BODY)).unwrap().0;
+
+        crate::ir::golden::assert_golden(&output, "nested_if_case.sv");
+    }
+
+    #[test]
+    fn test_unbalanced_process_body_is_an_error() {
+        let generator = SystemVerilogGenerator::new();
+
+        let extra_end = nested_process_entity("if a = '1' then\nb <= '0';\nend if;\nend if;");
+        assert!(generator.generate(&extra_end).is_err());
+
+        let unclosed = nested_process_entity("if a = '1' then\nb <= '0';");
+        let err = generator.generate(&unclosed).unwrap_err();
+        assert!(format!("{:#}", err).contains("still open"));
+    }
+
+    #[test]
+    fn test_stub_has_ports_only() {
+        let mut entity = nested_process_entity(NESTED_PROCESS_BODY);
+        if let Some(arch) = entity.architecture.as_mut() {
+            arch.signals.push(crate::ir::Signal {
+                name: "state".to_string(),
+                signal_type: VHDLType::Custom("state_t".to_string()),
+                source_line: None,
+            });
+        }
+
+        let stub = SystemVerilogGenerator::new().generate_stub(&entity).unwrap();
+        assert!(stub.starts_with("module nested (\n"));
+        assert!(stub.contains("input logic clk"));
+        assert!(!stub.contains("state"));
+        assert!(!stub.contains("always"));
+        assert!(stub.trim_end().ends_with(");\nendmodule"));
+
+        let black_box = SystemVerilogGenerator::new().with_black_box(true).generate_stub(&entity).unwrap();
+        assert!(black_box.starts_with("// pragma: black_box\n(* black_box *)\nmodule nested"));
+    }
+
+    #[test]
+    fn test_generate_adder_array_matches_golden() {
+        let bus = || VHDLType::StdLogicVector(VectorRange { left: 31.into(), right: 0.into(), downto: true });
+        let slice = |name: &str| crate::ir::Association::named(name, &format!("{}(8 * i + 7 downto 8 * i)", name));
+
+        let mut entity = Entity::new("adder_array".to_string());
+        entity.add_port(Port::new("a".to_string(), PortDirection::In, bus()));
+        entity.add_port(Port::new("b".to_string(), PortDirection::In, bus()));
+        entity.add_port(Port::new("sum".to_string(), PortDirection::Out, bus()));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![crate::ir::GenerateBlock {
+                label: "gen_add".to_string(),
+                scheme: crate::ir::GenerateScheme::For {
+                    var: "i".to_string(),
+                    left: "0".to_string(),
+                    right: "3".to_string(),
+                    downto: false,
+                },
+                concurrent_statements: vec![],
+                instantiations: vec![crate::ir::Instantiation {
+                    label: "u_add".to_string(),
+                    component: "adder".to_string(),
+                    generic_map: vec![],
+                    port_map: vec![slice("a"), slice("b"), slice("sum")],
+                    architecture: None,
+                    configuration: None,
+                }],
+                generate_blocks: vec![],
+            }],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+
+        let output = SystemVerilogGenerator::new().generate(&entity).unwrap().0;
+        crate::ir::golden::assert_golden(&output, "adder_array.sv");
+    }
+
+    #[test]
+    fn test_generic_width_emits_parametric_range() {
+        let mut entity = Entity::new("reg_n".to_string());
+        entity.add_generic(crate::ir::Generic {
+            name: "WIDTH".to_string(),
+            generic_type: "integer".to_string(),
+            default_value: Some("8".to_string()),
+        });
+        entity.add_port(Port::new(
+            "data".to_string(),
+            PortDirection::In,
+            VHDLType::StdLogicVector(VectorRange {
+                left: crate::ir::RangeExpr::from_vhdl("WIDTH-1").unwrap(),
+                right: 0.into(),
+                downto: true,
+            }),
+        ));
+
+        let sv = SystemVerilogGenerator::new().generate(&entity).unwrap().0;
+        assert_eq!(sv, "module reg_n #(\n    parameter WIDTH = 8\n) (\n    input logic [WIDTH-1:0] data\n);\nendmodule\n");
+    }
+
+    #[test]
+    fn test_ranged_integer_ports_are_sized() {
+        let mut entity = Entity::new("levels".to_string());
+        for (name, range) in [("level", "0 to 255"), ("offset", "-8 to 7"), ("index", "0 to DEPTH-1")] {
+            let port_type = VHDLType::ConstrainedInteger(crate::ir::IntegerRange::from_vhdl(range).unwrap());
+            entity.add_port(Port::new(name.to_string(), PortDirection::In, port_type));
+        }
+
+        let sv = SystemVerilogGenerator::new().generate(&entity).unwrap().0;
+        assert_eq!(
+            sv,
+            "module levels (\n    input logic [7:0]               level,\n    input logic signed [3:0]        offset,\n    input logic [$clog2(DEPTH)-1:0] index\n);\nendmodule\n"
+        );
+    }
+
+    fn commented_entity() -> Entity {
+        let mut entity = Entity::new("toggle".to_string());
+        entity.header_comments = vec![
+            "Copyright (c) 2024 Example Corp.".to_string(),
+            "Licensed under the Apache License, Version 2.0".to_string(),
+        ];
+        for (name, direction, comment) in [
+            ("clk", PortDirection::In, "system clock"),
+            ("rst", PortDirection::In, "active-high reset"),
+            ("q", PortDirection::Out, "toggles every cycle"),
+        ] {
+            entity.add_port(
+                Port::new(name.to_string(), direction, VHDLType::StdLogic).with_comment(Some(comment.to_string())),
+            );
+        }
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![crate::ir::Signal { name: "debug_q".to_string(), signal_type: VHDLType::StdLogic, source_line: None }],
+            processes: vec![crate::ir::Process {
+                label: None,
+                sensitivity_list: vec!["clk".to_string(), "rst".to_string()],
+                body: [
+                    "-- asynchronous reset",
+                    "if rst = '1' then",
+                    "    q <= '0'; -- clear",
+                    "elsif rising_edge(clk) then",
+                    "    -- synthesis translate_off",
+                    "    debug_q <= q;",
+                    "    -- synthesis translate_on",
+                    "    q <= not q;",
+                    "end if;",
+                ].join("\n"),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    #[test]
+    fn test_comments_match_golden() {
+        let output = SystemVerilogGenerator::new().generate(&commented_entity()).unwrap().0;
+        crate::ir::golden::assert_golden(&output, "commented.sv");
+
+        let stripped = SystemVerilogGenerator::new().with_strip_comments(true).generate(&commented_entity()).unwrap().0;
+        assert!(!stripped.contains("//"));
+        assert!(stripped.starts_with("module toggle (\n"));
+    }
+
+    #[test]
+    fn test_reserved_port_is_renamed() {
+        let mut entity = Entity::new("buf1".to_string());
+        entity.add_port(Port::new("input".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("output".to_string(), PortDirection::Out, VHDLType::StdLogic));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![],
+            concurrent_statements: vec!["Output <= INPUT".to_string()],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+
+        let (sv, report) = SystemVerilogGenerator::new().generate(&entity).unwrap();
+        assert!(sv.contains("input  logic input_r,"));
+        assert!(sv.contains("output logic output_r\n"));
+        assert!(sv.contains("assign output_r = input_r;"));
+
+        let summary: Vec<String> = report.renames.iter().map(|r| r.to_string()).collect();
+        assert_eq!(summary, vec![
+            "input -> input_r (reserved word)",
+            "output -> output_r (reserved word)",
+            "Output -> output_r (case normalized)",
+            "INPUT -> input_r (case normalized)",
+        ]);
+    }
+
+    #[test]
+    fn test_source_refs_and_map() {
+        let mut entity = Entity::new("buf".to_string());
+        entity.add_port(Port::new("a".to_string(), PortDirection::In, VHDLType::StdLogic).with_source_line(Some(3)));
+        entity.add_port(Port::new("y".to_string(), PortDirection::Out, VHDLType::StdLogic).with_source_line(Some(4)));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![crate::ir::Signal { name: "t".to_string(), signal_type: VHDLType::StdLogic, source_line: Some(9) }],
+            processes: vec![],
+            concurrent_statements: vec!["t <= not a;".to_string(), "y <= t;".to_string()],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![11, 12],
+            types: Default::default(),
+        });
+
+        let plain = SystemVerilogGenerator::new().generate(&entity).unwrap().0;
+        assert!(!plain.contains("// vhdl:"));
+
+        let sv = SystemVerilogGenerator::new()
+            .with_source_refs(Some("buf.vhd".to_string()))
+            .generate(&entity)
+            .unwrap().0;
+        assert!(sv.contains("input  logic a, // vhdl:buf.vhd:3\n"));
+        assert!(sv.contains("logic t; // vhdl:buf.vhd:9\n"));
+        assert!(sv.contains("assign y = t; // vhdl:buf.vhd:12\n"));
+
+        let map = crate::ir::source_map::source_ref_lines(&sv);
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), vec![3, 4, 9, 11, 12]);
+        let (generated, _) = map.iter().find(|(_, source)| **source == 12).unwrap();
+        assert_eq!(sv.lines().nth(generated - 1).unwrap().trim(), "assign y = t; // vhdl:buf.vhd:12");
+    }
+
+    fn counter_entity() -> Entity {
+        let mut entity = Entity::new("counter".to_string());
+        for name in ["clk", "reset", "enable"] {
+            entity.add_port(Port::new(name.to_string(), PortDirection::In, VHDLType::StdLogic));
+        }
+        let byte = VectorRange { left: 7.into(), right: 0.into(), downto: true };
+        entity.add_port(Port::new("count".to_string(), PortDirection::Out, VHDLType::StdLogicVector(byte.clone())));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![crate::ir::Signal { name: "count_reg".to_string(), signal_type: VHDLType::Unsigned(byte), source_line: None }],
+            processes: vec![crate::ir::Process {
+                label: None,
+                sensitivity_list: vec!["clk".to_string(), "reset".to_string()],
+                body: "if reset = '1' then\n    count_reg <= (others => '0');\nelsif rising_edge(clk) then\n    if enable = '1' then\n        count_reg <= count_reg + 1;\n    end if;\nend if;".to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec!["count <= std_logic_vector(count_reg);".to_string()],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    fn mux_entity() -> Entity {
+        let mut entity = Entity::new("mux2".to_string());
+        let byte = || VHDLType::StdLogicVector(VectorRange { left: 7.into(), right: 0.into(), downto: true });
+        entity.add_port(Port::new("a".to_string(), PortDirection::In, byte()));
+        entity.add_port(Port::new("b".to_string(), PortDirection::In, byte()));
+        entity.add_port(Port::new("sel".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("y".to_string(), PortDirection::Out, byte()));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![crate::ir::Process {
+                label: None,
+                sensitivity_list: vec!["a".to_string(), "b".to_string(), "sel".to_string()],
+                body: "if sel = '0' then\n    y <= a;\nelse\n    y <= b;\nend if;".to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    #[test]
+    fn test_formatted_counter_and_mux_match_golden() {
+        let generator = SystemVerilogGenerator::new();
+        let counter = generator.generate(&counter_entity()).unwrap().0;
+        crate::ir::golden::assert_golden(&counter, "counter.sv");
+        let mux = generator.generate(&mux_entity()).unwrap().0;
+        crate::ir::golden::assert_golden(&mux, "mux2.sv");
+
+        // The formatter is idempotent, and opting out keeps the unaligned ports
+        assert_eq!(HdlFormatter::new("    ").format(&counter), counter);
+        let raw = SystemVerilogGenerator::new().with_formatting(false).generate(&counter_entity()).unwrap().0;
+        assert!(raw.contains("    input logic clk,\n"));
+    }
+
+    fn stimulus_entity() -> Entity {
+        let mut entity = Entity::new("tb_counter".to_string());
+        entity.architecture = Some(Architecture {
+            name: "sim".to_string(),
+            signals: vec![
+                crate::ir::Signal { name: "rst".to_string(), signal_type: VHDLType::StdLogic, source_line: None },
+                crate::ir::Signal { name: "done".to_string(), signal_type: VHDLType::StdLogic, source_line: None },
+            ],
+            processes: vec![crate::ir::Process {
+                label: Some("stim".to_string()),
+                sensitivity_list: vec![],
+                body: [
+                    "rst <= '1';",
+                    "wait for 20 ns;",
+                    "rst <= '0'; -- release reset",
+                    "wait until rising_edge(clk);",
+                    "wait for 1 us;",
+                    "wait until done = '1';",
+                    "wait;",
+                ].join("\n"),
+                source_line: None,
+                testbench_style: true,
+            }],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    #[test]
+    fn test_wait_process_becomes_initial_block() {
+        let (sv, report) = SystemVerilogGenerator::new().generate(&stimulus_entity()).unwrap();
+
+        assert!(sv.contains(
+            "    initial begin\n        rst <= 1'b1;\n        #20;\n        rst <= 1'b0; // release reset\n        @(posedge clk);\n        #1000;\n        wait (done == 1'b1);\n    end\n"
+        ));
+        assert!(!sv.contains("always"));
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_synthesizable_mode_skips_wait_process() {
+        let (sv, report) = SystemVerilogGenerator::new()
+            .with_synthesizable(true)
+            .generate(&stimulus_entity())
+            .unwrap();
+
+        assert!(!sv.contains("initial"));
+        assert!(!sv.contains("wait"));
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].to_string(), "process stim: wait statements are not synthesizable; process skipped");
+    }
+    #[test]
+    fn test_package_types_are_resolved() {
+        let mut packages = crate::parser::PackageRegistry::new();
+        packages.add_source("package types_pkg is\n    subtype word_t is std_logic_vector(15 downto 0);\n    type state_t is (IDLE, LOAD, RUN);\nend package;\n");
+
+        let mut entity = Entity::new("regs".to_string());
+        entity.add_port(Port::new("d".to_string(), PortDirection::In, VHDLType::Custom("word_t".to_string())));
+        entity.add_port(Port::new("cfg".to_string(), PortDirection::In, VHDLType::Custom("cfg_t".to_string())));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![crate::ir::Signal { name: "state".to_string(), signal_type: VHDLType::Custom("state_t".to_string()), source_line: None }],
+            processes: vec![],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+
+        let (sv, report) = SystemVerilogGenerator::new()
+            .with_type_context(Some(packages.type_context()))
+            .generate(&entity)
+            .unwrap();
+        assert!(sv.contains("    input logic [15:0]      d,\n    input logic /* cfg_t */ cfg\n"));
+        assert!(sv.contains("    localparam logic [1:0] IDLE = 2'd0;\n    localparam logic [1:0] LOAD = 2'd1;\n    localparam logic [1:0] RUN = 2'd2;\n"));
+        assert!(sv.contains("    logic [1:0] state;\n"));
+        assert_eq!(report.warnings, vec![TranspilationWarning::unresolvable_type("cfg_t")]);
+
+        // Without a context every custom type is reported
+        let (_, report) = SystemVerilogGenerator::new().generate(&entity).unwrap();
+        assert_eq!(report.warnings.len(), 3);
+    }
+
+    #[test]
+    fn test_incomplete_if_infers_always_latch() {
+        let latch_entity = |body: &str| {
+            let mut entity = Entity::new("d_latch".to_string());
+            entity.add_port(Port::new("en".to_string(), PortDirection::In, VHDLType::StdLogic));
+            entity.add_port(Port::new("d".to_string(), PortDirection::In, VHDLType::StdLogic));
+            entity.add_port(Port::new("q".to_string(), PortDirection::Out, VHDLType::StdLogic));
+            entity.architecture = Some(Architecture {
+                name: "rtl".to_string(),
+                signals: vec![],
+                processes: vec![crate::ir::Process {
+                    label: Some("latch".to_string()),
+                    sensitivity_list: vec!["en".to_string(), "d".to_string()],
+                    body: body.to_string(),
+                    source_line: None,
+                    testbench_style: false,
+                }],
+                concurrent_statements: vec![],
+                instantiations: vec![],
+                generate_blocks: vec![],
+                concurrent_statement_lines: vec![],
+                types: Default::default(),
+            });
+            entity
+        };
+
+        let (sv, report) = SystemVerilogGenerator::new()
+            .generate(&latch_entity("if en = '1' then\n    q <= d;\nend if;"))
+            .unwrap();
+        assert!(sv.contains("    always_latch begin\n"));
+        assert!(!sv.contains("always_comb"));
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].to_string(), "process latch: inferred latch for q (not assigned on every path)");
+
+        let (sv, report) = SystemVerilogGenerator::new()
+            .generate(&latch_entity("if en = '1' then\n    q <= d;\nelse\n    q <= '0';\nend if;"))
+            .unwrap();
+        assert!(sv.contains("    always_comb begin\n"));
+        assert!(report.warnings.is_empty());
+
+        // With fix_latches the enable latch gets a default and becomes combinational
+        let (sv, report) = SystemVerilogGenerator::new()
+            .with_fix_latches(true)
+            .generate(&latch_entity("if en = '1' then\n    q <= d;\nend if;"))
+            .unwrap();
+        assert!(sv.contains("    always_comb begin\n        q = 1'b0;\n        if (en == 1'b1) begin\n"), "{}", sv);
+        assert!(!sv.contains("always_latch"));
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(
+            report.warnings[0].to_string(),
+            "process latch: inferred latch for q (not assigned on every path); default assignment inserted"
+        );
+    }
+
+    #[test]
+    fn test_counter_conversion_report() {
+        let (_, report) = SystemVerilogGenerator::new().generate(&counter_entity()).unwrap();
+        assert_eq!(report.entity_name, "counter");
+        assert_eq!(report.port_mappings.len(), 4);
+        assert_eq!(report.port_mappings[3], crate::ir::PortMapping {
+            vhdl_name: "count".to_string(),
+            hdl_name: "count".to_string(),
+            vhdl_direction: "out".to_string(),
+            hdl_direction: "output".to_string(),
+            vhdl_type: "std_logic_vector(7 downto 0)".to_string(),
+            hdl_type: "logic [7:0]".to_string(),
+        });
+        // std_logic, std_logic_vector and the unsigned signal
+        assert_eq!(report.type_mappings.len(), 3);
+        assert_eq!(report.process_classifications.len(), 1);
+        assert_eq!(report.process_classifications[0].kind, crate::ir::ProcessKind::Sequential);
+        assert!(report.unsupported_constructs.is_empty());
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("## Conversion report: counter\n"));
+        assert!(markdown.contains("| count | out | std_logic_vector(7 downto 0) | count | output | logic [7:0] |\n"));
+        assert!(markdown.contains("| unsigned(7 downto 0) | logic [7:0] |\n"));
+        assert!(markdown.contains("| process_0 | sequential | clock clk (rising), async reset reset (active high) |\n"));
+    }
 }
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::ir::hdl_body::{Dialect, SystemVerilogDialect};
+use crate::ir::sanitize::sanitize_entity;
+use crate::ir::{Entity, PortDirection};
+
+lazy_static! {
+    static ref EDGE_RE: Regex = Regex::new(r"(?i)\b(?:rising_edge|falling_edge)\s*\(\s*(\w+)\s*\)").unwrap();
+}
+
+/// Generate a SystemVerilog testbench skeleton that instantiates an entity's module
+pub struct TestbenchGenerator {
+    indent: String,
+    clock_half_period_ns: u32,
+}
+
+impl TestbenchGenerator {
+    pub fn new() -> Self {
+        Self {
+            indent: "    ".to_string(),
+            clock_half_period_ns: 5,
+        }
+    }
+
+    /// Input port used as a clock: an edge-detected signal in a process, or a clock-like
+    /// name in a sensitivity list
+    pub fn detect_clock(entity: &Entity) -> Option<String> {
+        let arch = entity.architecture.as_ref()?;
+        let is_input = |name: &str| entity.ports.iter()
+            .any(|p| p.direction == PortDirection::In && p.name.eq_ignore_ascii_case(name));
+
+        let edge_signal = arch.processes.iter()
+            .flat_map(|p| EDGE_RE.captures_iter(&p.body).map(|caps| caps[1].to_string()))
+            .find(|name| is_input(name));
+
+        edge_signal.or_else(|| {
+            arch.processes.iter()
+                .flat_map(|p| p.sensitivity_list.iter())
+                .find(|s| {
+                    let lower = s.to_lowercase();
+                    (lower.contains("clk") || lower.contains("clock")) && is_input(s)
+                })
+                .cloned()
+        })
+    }
+
+    /// Reset-like input port and whether it is active low
+    pub fn detect_reset(entity: &Entity) -> Option<(String, bool)> {
+        let port = entity.ports.iter()
+            .filter(|p| p.direction == PortDirection::In)
+            .find(|p| {
+                let name = p.name.to_lowercase();
+                name.contains("rst") || name.contains("reset")
+            })?;
+
+        let name = port.name.to_lowercase();
+        let compared_low = entity.architecture.iter()
+            .flat_map(|arch| arch.processes.iter())
+            .any(|p| p.body.contains(&format!("{} = '0'", port.name)));
+        let active_low = compared_low || name.ends_with("_n") || name.ends_with("_b") || name.ends_with('n');
+
+        Some((port.name.clone(), active_low))
+    }
+
+    pub fn generate(&self, entity: &Entity) -> Result<String> {
+        // Match the port names of the generated module
+        let (sanitized, _) = sanitize_entity(entity, |name| SystemVerilogDialect.is_reserved(name));
+        let entity = &sanitized;
+        let indent = &self.indent;
+        let clock = Self::detect_clock(entity);
+        let reset = Self::detect_reset(entity);
+
+        let mut output = String::new();
+        output.push_str("`timescale 1ns/1ps\n\n");
+        output.push_str(&format!("module {}_tb;\n\n", entity.name));
+
+        // Generics become local parameters so parametric port widths resolve
+        for generic in &entity.generics {
+            if let Some(value) = &generic.default_value {
+                output.push_str(&format!("{}localparam {} = {};\n", indent, generic.name, value));
+            }
+        }
+        if entity.generics.iter().any(|g| g.default_value.is_some()) {
+            output.push('\n');
+        }
+
+        for port in &entity.ports {
+            output.push_str(&format!("{}{} {};\n", indent, port.port_type.to_systemverilog(), port.name));
+        }
+
+        // DUT instantiation with every port connected by name
+        output.push('\n');
+        output.push_str(&format!("{}{}", indent, entity.name));
+        let parameters: Vec<String> = entity.generics.iter()
+            .filter(|g| g.default_value.is_some())
+            .map(|g| format!(".{}({})", g.name, g.name))
+            .collect();
+        if !parameters.is_empty() {
+            output.push_str(&format!(" #({})", parameters.join(", ")));
+        }
+        output.push_str(" dut (\n");
+        for (i, port) in entity.ports.iter().enumerate() {
+            output.push_str(&format!("{}{}.{}({})", indent, indent, port.name, port.name));
+            if i < entity.ports.len() - 1 {
+                output.push(',');
+            }
+            output.push('\n');
+        }
+        output.push_str(&format!("{});\n", indent));
+
+        if let Some(clk) = &clock {
+            output.push_str(&format!("\n{}// Clock generation\n", indent));
+            output.push_str(&format!("{}initial {} = 1'b0;\n", indent, clk));
+            output.push_str(&format!("{}always #{} {} = ~{};\n", indent, self.clock_half_period_ns, clk, clk));
+        }
+
+        if let Some((rst, active_low)) = &reset {
+            let (asserted, released) = if *active_low { ("1'b0", "1'b1") } else { ("1'b1", "1'b0") };
+            output.push_str(&format!("\n{}// Reset pulse\n", indent));
+            output.push_str(&format!("{}initial begin\n", indent));
+            output.push_str(&format!("{}{}{} = {};\n", indent, indent, rst, asserted));
+            output.push_str(&format!("{}{}#{};\n", indent, indent, self.clock_half_period_ns * 4));
+            output.push_str(&format!("{}{}{} = {};\n", indent, indent, rst, released));
+            output.push_str(&format!("{}end\n", indent));
+        }
+
+        output.push_str(&format!("\n{}// Stimulus\n", indent));
+        output.push_str(&format!("{}initial begin\n", indent));
+        output.push_str(&format!("{}{}// Drive inputs and check outputs here\n", indent, indent));
+        output.push_str(&format!("{}{}#{};\n", indent, indent, self.clock_half_period_ns * 20));
+        output.push_str(&format!("{}{}$finish;\n", indent, indent));
+        output.push_str(&format!("{}end\n\n", indent));
+
+        output.push_str("endmodule\n");
+
+        Ok(output)
+    }
+}
+
+impl Default for TestbenchGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Architecture, Port, Process, VHDLType, VectorRange};
+
+    fn counter() -> Entity {
+        let mut entity = Entity::new("counter".to_string());
+        entity.add_port(Port::new("clock_in".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("rst_n".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new(
+            "count".to_string(),
+            PortDirection::Out,
+            VHDLType::StdLogicVector(VectorRange { left: 7.into(), right: 0.into(), downto: true }),
+        ));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![Process {
+                label: None,
+                sensitivity_list: vec!["clock_in".to_string(), "rst_n".to_string()],
+                body: "if rst_n = '0' then\ncount <= (others => '0');\nelsif rising_edge(clock_in) then\ncount <= count + 1;\nend if;".to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    #[test]
+    fn test_testbench_connects_every_port_and_toggles_clock() {
+        let tb = TestbenchGenerator::new().generate(&counter()).unwrap();
+
+        assert!(tb.contains("module counter_tb;"));
+        assert!(tb.contains("logic [7:0] count;"));
+        assert!(tb.contains("counter dut (\n"));
+        for port in ["clock_in", "rst_n", "count"] {
+            assert!(tb.contains(&format!(".{}({})", port, port)), "missing {}", port);
+        }
+        assert!(tb.contains("always #5 clock_in = ~clock_in;"));
+        assert!(tb.contains("rst_n = 1'b0;\n        #20;\n        rst_n = 1'b1;"));
+        assert!(tb.contains("$finish;"));
+    }
+
+    #[test]
+    fn test_combinational_entity_has_no_clock() {
+        let mut entity = Entity::new("inv".to_string());
+        entity.add_port(Port::new("a".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("y".to_string(), PortDirection::Out, VHDLType::StdLogic));
+
+        assert_eq!(TestbenchGenerator::detect_clock(&entity), None);
+        let tb = TestbenchGenerator::new().generate(&entity).unwrap();
+        assert!(!tb.contains("always #"));
+        assert!(!tb.contains("Reset pulse"));
+        assert!(tb.contains("inv dut (\n        .a(a),\n        .y(y)\n    );"));
+    }
+}
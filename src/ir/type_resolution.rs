@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use crate::ir::{Entity, VHDLType, VectorRange};
+use crate::ir::report::TranspilationWarning;
+
+/// Subtype chains longer than this are treated as cycles
+const MAX_ALIAS_DEPTH: usize = 16;
+
+/// Concrete types for user-defined type names, typically collected from package
+/// declarations; names are case-insensitive
+#[derive(Debug, Clone, Default)]
+pub struct TypeResolutionContext {
+    types: HashMap<String, VHDLType>,
+    enums: HashMap<String, Vec<String>>,
+}
+
+impl TypeResolutionContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve `name` to `resolved`, which may itself name another custom type
+    pub fn with_type(mut self, name: &str, resolved: VHDLType) -> Self {
+        self.types.insert(name.to_lowercase(), resolved);
+        self
+    }
+
+    /// Resolve the enumeration `name` to the narrowest vector that encodes its literals,
+    /// which become `localparam`s numbered in declaration order
+    pub fn with_enum(mut self, name: &str, literals: Vec<String>) -> Self {
+        let width = enum_width(literals.len());
+        let range = VectorRange::downto(width as i32 - 1, 0);
+        self.types.insert(name.to_lowercase(), VHDLType::StdLogicVector(range));
+        self.enums.insert(name.to_lowercase(), literals);
+        self
+    }
+
+    /// Add the types of `other`, which take precedence over same-named ones, e.g. an
+    /// architecture's local types over package types
+    pub fn with_context(mut self, other: &TypeResolutionContext) -> Self {
+        self.types.extend(other.types.iter().map(|(name, ty)| (name.clone(), ty.clone())));
+        self.enums.extend(other.enums.iter().map(|(name, literals)| (name.clone(), literals.clone())));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Concrete type for `ty`, following subtype chains; built-in types resolve to themselves
+    pub fn resolve(&self, ty: &VHDLType) -> Option<VHDLType> {
+        self.resolve_name(ty).map(|(_, resolved)| resolved.clone())
+    }
+
+    /// Literals of the enumeration `ty` resolves to, if any
+    pub fn enum_literals(&self, ty: &VHDLType) -> Option<&[String]> {
+        let (name, _) = self.resolve_name(ty)?;
+        self.enums.get(&name?).map(Vec::as_slice)
+    }
+
+    /// Copy of `entity` with resolvable custom port and signal types substituted, and one
+    /// warning per custom type left unresolved
+    pub fn resolve_entity(&self, entity: &Entity) -> (Entity, Vec<TranspilationWarning>) {
+        let mut resolved = entity.clone();
+        let mut unresolved: Vec<String> = Vec::new();
+        let mut resolve = |ty: &mut VHDLType| {
+            match self.resolve(ty) {
+                Some(concrete) => *ty = concrete,
+                None => {
+                    if let VHDLType::Custom(name) = ty {
+                        if !unresolved.contains(name) {
+                            unresolved.push(name.clone());
+                        }
+                    }
+                }
+            }
+        };
+
+        for port in &mut resolved.ports {
+            resolve(&mut port.port_type);
+        }
+        if let Some(arch) = &mut resolved.architecture {
+            for signal in &mut arch.signals {
+                resolve(&mut signal.signal_type);
+            }
+        }
+
+        let warnings = unresolved.iter().map(|name| TranspilationWarning::unresolvable_type(name)).collect();
+        (resolved, warnings)
+    }
+
+    /// Width and literals of each enumeration used by the entity's ports and signals, in
+    /// order of first use
+    pub fn enumerations(&self, entity: &Entity) -> Vec<(u32, Vec<String>)> {
+        let signal_types = entity.architecture.iter().flat_map(|arch| arch.signals.iter().map(|s| &s.signal_type));
+        let mut enumerations: Vec<(u32, Vec<String>)> = Vec::new();
+        for ty in entity.ports.iter().map(|p| &p.port_type).chain(signal_types) {
+            if let Some(literals) = self.enum_literals(ty) {
+                if !enumerations.iter().any(|(_, known)| known.as_slice() == literals) {
+                    enumerations.push((enum_width(literals.len()), literals.to_vec()));
+                }
+            }
+        }
+        enumerations
+    }
+
+    /// Last custom name in the chain (if any) and the concrete type it ends at
+    fn resolve_name<'a>(&'a self, ty: &'a VHDLType) -> Option<(Option<String>, &'a VHDLType)> {
+        let mut current = ty;
+        let mut last_name = None;
+        for _ in 0..MAX_ALIAS_DEPTH {
+            let VHDLType::Custom(name) = current else {
+                return Some((last_name, current));
+            };
+            let key = name.to_lowercase();
+            current = self.types.get(&key)?;
+            last_name = Some(key);
+        }
+        None
+    }
+}
+
+/// Bits needed to number `count` literals, at least one
+fn enum_width(count: usize) -> u32 {
+    (usize::BITS - count.saturating_sub(1).leading_zeros()).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Port, PortDirection};
+
+    fn byte() -> VectorRange {
+        VectorRange { left: 7.into(), right: 0.into(), downto: true }
+    }
+
+    #[test]
+    fn test_resolves_aliases_and_enums() {
+        let types = TypeResolutionContext::new()
+            .with_type("Byte_T", VHDLType::StdLogicVector(byte()))
+            .with_type("data_t", VHDLType::Custom("byte_t".to_string()))
+            .with_type("loop_t", VHDLType::Custom("loop_t".to_string()))
+            .with_enum("state_t", vec!["IDLE".to_string(), "RUN".to_string(), "DONE".to_string()]);
+
+        assert_eq!(types.resolve(&VHDLType::Custom("data_t".to_string())), Some(VHDLType::StdLogicVector(byte())));
+        assert_eq!(types.resolve(&VHDLType::StdLogic), Some(VHDLType::StdLogic));
+        assert_eq!(types.resolve(&VHDLType::Custom("loop_t".to_string())), None);
+        assert_eq!(types.resolve(&VHDLType::Custom("missing_t".to_string())), None);
+
+        let state = VHDLType::Custom("state_t".to_string());
+        assert_eq!(types.resolve(&state).and_then(|ty| ty.bit_width()), Some(2));
+        assert_eq!(types.enum_literals(&state), Some(&["IDLE".to_string(), "RUN".to_string(), "DONE".to_string()][..]));
+        assert_eq!((enum_width(1), enum_width(2), enum_width(4), enum_width(5)), (1, 1, 2, 3));
+    }
+
+    #[test]
+    fn test_resolve_entity_warns_once_per_unresolved_type() {
+        let mut entity = Entity::new("regs".to_string());
+        entity.add_port(Port::new("d".to_string(), PortDirection::In, VHDLType::Custom("byte_t".to_string())));
+        entity.add_port(Port::new("a".to_string(), PortDirection::In, VHDLType::Custom("addr_t".to_string())));
+        entity.add_port(Port::new("b".to_string(), PortDirection::In, VHDLType::Custom("addr_t".to_string())));
+        let types = TypeResolutionContext::new().with_type("byte_t", VHDLType::StdLogicVector(byte()));
+
+        let (resolved, warnings) = types.resolve_entity(&entity);
+        assert_eq!(resolved.ports[0].port_type, VHDLType::StdLogicVector(byte()));
+        assert_eq!(resolved.ports[1].port_type, VHDLType::Custom("addr_t".to_string()));
+        assert_eq!(warnings, vec![TranspilationWarning::unresolvable_type("addr_t")]);
+    }
+}
@@ -1,24 +1,164 @@
-use crate::ir::{Entity, Architecture, Port, PortDirection, VHDLType};
+use crate::ir::{Entity, Architecture, Port, PortDirection, VHDLType, VectorRange};
+use crate::ir::hdl_body::{convert_comment, Dialect, HdlBodyConverter, VerilogDialect};
+use crate::ir::formatter::HdlFormatter;
+use crate::ir::report::ConversionReport;
+use crate::ir::sanitize::{sanitize_entity, Rename};
+use crate::ir::source_map::{source_ref, SourceMap, SourceMapOutput};
+use crate::ir::report::TranspilationWarning;
+use crate::ir::type_resolution::TypeResolutionContext;
 use anyhow::Result;
 
+/// How the ports of a Verilog module are declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerilogStyle {
+    /// `module counter (input wire clk, ...);`
+    #[default]
+    Verilog2001Ansi,
+    /// `module counter(clk, ...);` followed by `input wire clk;` declarations
+    Verilog2001NonAnsi,
+    /// `module counter(clk, ...);` followed by `input clk;` declarations, with `reg`
+    /// outputs and parameters declared separately in the body
+    Verilog95,
+}
+
+impl std::str::FromStr for VerilogStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "verilog2001_ansi" => Ok(VerilogStyle::Verilog2001Ansi),
+            "verilog2001_non_ansi" => Ok(VerilogStyle::Verilog2001NonAnsi),
+            "verilog95" => Ok(VerilogStyle::Verilog95),
+            other => Err(anyhow::anyhow!(
+                "Unknown Verilog style '{}': expected \"verilog2001_ansi\", \"verilog2001_non_ansi\" or \"verilog95\"",
+                other
+            )),
+        }
+    }
+}
+
 /// Generate Verilog module from Entity IR
+#[derive(Clone)]
 pub struct VerilogGenerator {
     indent: String,
+    black_box: bool,
+    strip_comments: bool,
+    format: bool,
+    synthesizable: bool,
+    fix_latches: bool,
+    blocking_assignments: bool,
+    source_refs: Option<String>,
+    types: Option<TypeResolutionContext>,
+    style: VerilogStyle,
 }
 
 impl VerilogGenerator {
     pub fn new() -> Self {
         Self {
             indent: "    ".to_string(),
+            black_box: false,
+            strip_comments: false,
+            format: true,
+            synthesizable: false,
+            fix_latches: false,
+            blocking_assignments: true,
+            source_refs: None,
+            types: None,
+            style: VerilogStyle::default(),
         }
     }
 
     pub fn with_indent(indent: String) -> Self {
-        Self { indent }
+        Self { indent, ..Self::new() }
+    }
+
+    /// Annotate stubs with `(* black_box *)` and `// pragma: black_box`
+    pub fn with_black_box(mut self, black_box: bool) -> Self {
+        self.black_box = black_box;
+        self
+    }
+
+    /// Drop comments carried over from the VHDL source
+    pub fn with_strip_comments(mut self, strip_comments: bool) -> Self {
+        self.strip_comments = strip_comments;
+        self
+    }
+
+    /// Append `// vhdl:<source_name>:<line>` to declarations, always blocks and assigns
+    /// whose VHDL line is known; `None` disables the comments
+    pub fn with_source_refs(mut self, source_name: Option<String>) -> Self {
+        self.source_refs = source_name;
+        self
+    }
+
+    /// Run the `HdlFormatter` layout pass over the output (default: true)
+    pub fn with_formatting(mut self, format: bool) -> Self {
+        self.format = format;
+        self
     }
 
-    /// Generate complete Verilog module from entity
-    pub fn generate(&self, entity: &Entity) -> Result<String> {
+    /// Skip testbench-style processes (those using `wait`) with a warning instead of
+    /// converting them to `initial`/`always` blocks
+    pub fn with_synthesizable(mut self, synthesizable: bool) -> Self {
+        self.synthesizable = synthesizable;
+        self
+    }
+
+    /// Give signals that would infer a latch a default assignment at the top of their
+    /// process, converting it to a combinational block; each fix is reported as a warning
+    pub fn with_fix_latches(mut self, fix_latches: bool) -> Self {
+        self.fix_latches = fix_latches;
+        self
+    }
+
+    /// Emit signal assignments in combinational blocks as blocking `=` (default: true);
+    /// false keeps VHDL's `<=` as written. Sequential blocks keep `<=` either way
+    pub fn with_blocking_assignments(mut self, blocking_assignments: bool) -> Self {
+        self.blocking_assignments = blocking_assignments;
+        self
+    }
+
+    /// Port declaration style of the module header (default: Verilog-2001 ANSI)
+    pub fn with_style(mut self, style: VerilogStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Substitute custom types with their definitions from `types`, e.g. package
+    /// subtypes and enumerations; unresolvable ones are reported as warnings
+    pub fn with_type_context(mut self, types: Option<TypeResolutionContext>) -> Self {
+        self.types = types;
+        self
+    }
+
+    /// Generate a module stub with the entity's ports and no body
+    pub fn generate_stub(&self, entity: &Entity) -> Result<String> {
+        let (resolved, _) = self.type_context(entity).resolve_entity(entity);
+        let (sanitized, _) = self.sanitize(&resolved);
+        let entity = &sanitized;
+        let mut output = String::new();
+
+        if self.black_box {
+            output.push_str("// pragma: black_box\n");
+            output.push_str("(* black_box *)\n");
+        }
+
+        output.push_str(&self.generate_module_header(entity, &std::collections::HashSet::new())?);
+        output.push_str("endmodule\n");
+
+        Ok(self.finish(output))
+    }
+
+    /// Generate complete Verilog module from entity, with the mapping decisions made,
+    /// renamed identifiers and warnings
+    pub fn generate(&self, entity: &Entity) -> Result<(String, ConversionReport)> {
+        let original = entity;
+        let types = self.type_context(entity);
+        let enumerations = types.enumerations(entity);
+        let (resolved, mut warnings) = types.resolve_entity(entity);
+        let mut unsupported: Vec<String> = warnings.iter().map(|w| w.construct.clone()).collect();
+        let (sanitized, renames) = self.sanitize(&resolved);
+        let entity = &sanitized;
         let mut output = String::new();
 
         // Collect all signals assigned in processes (need to be reg)
@@ -28,12 +168,75 @@ impl VerilogGenerator {
         output.push_str(&self.generate_module_header(entity, &procedural_signals)?);
 
         // Module body (empty for now, just entity conversion)
-        output.push_str(&self.generate_module_body(entity)?);
+        output.push_str(&self.generate_enum_parameters(&enumerations));
+        output.push_str(&self.generate_module_body(entity, &mut warnings)?);
 
         // Module footer
         output.push_str("endmodule\n");
 
-        Ok(output)
+        for warning in &warnings {
+            tracing::warn!("Verilog output for {}: {}", entity.name, warning);
+        }
+        if self.synthesizable {
+            let skipped = entity.architecture.iter()
+                .flat_map(|arch| &arch.processes)
+                .filter(|process| process.testbench_style)
+                .map(|process| format!("process {}", process.label.as_deref().unwrap_or("<unlabeled>")));
+            unsupported.extend(skipped);
+        }
+        let signal_type = |signal_type: &VHDLType| signal_type.to_verilog().replace("wire ", "reg ");
+        let report = ConversionReport::new(original, entity, |port| self.port_type(port, &procedural_signals), signal_type)
+            .with_renames(renames)
+            .with_unsupported_constructs(unsupported)
+            .with_warnings(warnings);
+        Ok((self.finish(output), report))
+    }
+
+    /// Generate the module with source refs to `source_name` (see `with_source_refs`)
+    /// and the line/column mapping they trace to
+    pub fn generate_with_source_map(&self, entity: &Entity, source_name: &str, source_map: &SourceMap) -> Result<(String, SourceMapOutput)> {
+        let generator = self.clone().with_source_refs(Some(source_name.to_string()));
+        let (output, _) = generator.generate(entity)?;
+        let mapping = SourceMapOutput::from_generated(&output, source_map);
+        Ok((output, mapping))
+    }
+
+    fn finish(&self, output: String) -> String {
+        if self.format {
+            HdlFormatter::new(&self.indent).format(&output)
+        } else {
+            output
+        }
+    }
+
+    /// The generator's types with the architecture's local types on top
+    fn type_context(&self, entity: &Entity) -> std::borrow::Cow<'_, TypeResolutionContext> {
+        let local = entity.architecture.as_ref().map(|arch| &arch.types).filter(|types| !types.is_empty());
+        match (&self.types, local) {
+            (Some(types), None) => std::borrow::Cow::Borrowed(types),
+            (None, None) => std::borrow::Cow::Owned(TypeResolutionContext::new()),
+            (types, Some(local)) => std::borrow::Cow::Owned(types.clone().unwrap_or_default().with_context(local)),
+        }
+    }
+
+    /// One `localparam` per enumeration literal, numbered in declaration order
+    fn generate_enum_parameters(&self, enumerations: &[(u32, Vec<String>)]) -> String {
+        let mut output = String::new();
+        for (width, literals) in enumerations {
+            output.push('\n');
+            for (value, literal) in literals.iter().enumerate() {
+                output.push_str(&format!("{}localparam {} {} = {}'d{};\n", self.indent, VectorRange::to_verilog_part_select(width - 1, 0), literal, width, value));
+            }
+        }
+        output
+    }
+
+    fn sanitize(&self, entity: &Entity) -> (Entity, Vec<Rename>) {
+        let (entity, renames) = sanitize_entity(entity, |name| VerilogDialect.is_reserved(name));
+        for rename in &renames {
+            tracing::warn!("Renamed identifier in {}: {}", entity.name, rename);
+        }
+        (entity, renames)
     }
 
     fn collect_procedural_signals(&self, entity: &Entity) -> std::collections::HashSet<String> {
@@ -45,7 +248,9 @@ impl VerilogGenerator {
                 for line in process.body.lines() {
                     let trimmed = line.trim();
                     if let Some(pos) = trimmed.find(" <=") {
-                        let signal_name = trimmed[..pos].trim();
+                        // `q(3 downto 0) <= ...` and `when ... => q(i) <= ...` assign `q`
+                        let target = trimmed[..pos].split('(').next().unwrap_or("");
+                        let signal_name = target.split_whitespace().last().unwrap_or("");
                         procedural_signals.insert(signal_name.to_string());
                     }
                 }
@@ -58,30 +263,39 @@ impl VerilogGenerator {
     fn generate_module_header(&self, entity: &Entity, procedural_signals: &std::collections::HashSet<String>) -> Result<String> {
         let mut output = String::new();
 
+        if !self.strip_comments {
+            for comment in &entity.header_comments {
+                output.push_str(&convert_comment(comment));
+                output.push('\n');
+            }
+        }
+
+        if self.style != VerilogStyle::Verilog2001Ansi {
+            output.push_str(&self.generate_non_ansi_header(entity, procedural_signals));
+            return Ok(output);
+        }
+
         // Start module declaration
-        output.push_str(&format!("module {} (\n", entity.name));
+        output.push_str(&entity.module_declaration(&self.indent));
 
         // Generate port list
         if !entity.ports.is_empty() {
             for (i, port) in entity.ports.iter().enumerate() {
                 output.push_str(&self.indent);
                 
-                // Check if this port is assigned in a process and needs to be reg
-                let is_procedural = procedural_signals.contains(&port.name);
                 let direction = port.direction.to_verilog();
-                let mut verilog_type = port.port_type.to_verilog();
-                
-                // If output port is assigned in process, change wire to reg
-                if is_procedural && matches!(port.direction, PortDirection::Out | PortDirection::Buffer) {
-                    verilog_type = verilog_type.replace("wire", "reg");
-                }
-                
+                let verilog_type = self.port_type(port, procedural_signals);
                 output.push_str(&format!("{} {} {}", direction, verilog_type, port.name));
 
                 // Add comma if not last port
                 if i < entity.ports.len() - 1 {
                     output.push(',');
                 }
+                if let Some(comment) = port.comment.as_deref().filter(|_| !self.strip_comments) {
+                    output.push(' ');
+                    output.push_str(&convert_comment(comment));
+                }
+                output.push_str(&self.source_ref(port.source_line));
                 output.push('\n');
             }
         }
@@ -91,18 +305,90 @@ impl VerilogGenerator {
         Ok(output)
     }
 
-    fn generate_module_body(&self, entity: &Entity) -> Result<String> {
+    /// Port names in the module declaration, directions and types in the body
+    fn generate_non_ansi_header(&self, entity: &Entity, procedural_signals: &std::collections::HashSet<String>) -> String {
+        let verilog95 = self.style == VerilogStyle::Verilog95;
+        // Verilog-1995 has no `#(...)` parameter port list
+        let declaration = if verilog95 || entity.generics.is_empty() {
+            format!("module {}", entity.name)
+        } else {
+            entity.module_declaration(&self.indent).trim_end_matches("(\n").to_string()
+        };
+        let names: Vec<&str> = entity.ports.iter().map(|p| p.name.as_str()).collect();
+        let mut output = if names.is_empty() {
+            format!("{};\n", declaration.trim_end())
+        } else {
+            format!("{}({});\n", declaration, names.join(", "))
+        };
+
+        if verilog95 {
+            for generic in &entity.generics {
+                output.push_str(&format!("{}{};\n", self.indent, generic.to_verilog()));
+            }
+        }
+
+        let mut registers = Vec::new();
+        for port in &entity.ports {
+            let verilog_type = self.port_type(port, procedural_signals);
+            let declared = if verilog95 {
+                // Only the range goes with the direction; `reg` is declared again below
+                let (net, range) = match verilog_type.split_once(' ') {
+                    Some((net, range)) => (net, format!(" {}", range)),
+                    None => (verilog_type.as_str(), String::new()),
+                };
+                if net == "reg" {
+                    registers.push(format!("{}reg{} {};\n", self.indent, range, port.name));
+                }
+                format!("{}{} {}", port.direction.to_verilog(), range, port.name)
+            } else {
+                format!("{} {} {}", port.direction.to_verilog(), verilog_type, port.name)
+            };
+            output.push_str(&format!("{}{};", self.indent, declared));
+            if let Some(comment) = port.comment.as_deref().filter(|_| !self.strip_comments) {
+                output.push(' ');
+                output.push_str(&convert_comment(comment));
+            }
+            output.push_str(&self.source_ref(port.source_line));
+            output.push('\n');
+        }
+        for register in registers {
+            output.push_str(&register);
+        }
+        output
+    }
+
+    /// Port declaration type; outputs assigned in a process are `reg`
+    fn port_type(&self, port: &Port, procedural_signals: &std::collections::HashSet<String>) -> String {
+        let verilog_type = port.port_type.to_verilog();
+        if procedural_signals.contains(&port.name) && matches!(port.direction, PortDirection::Out | PortDirection::Buffer) {
+            verilog_type.replace("wire", "reg")
+        } else {
+            verilog_type
+        }
+    }
+
+    fn generate_module_body(&self, entity: &Entity, warnings: &mut Vec<TranspilationWarning>) -> Result<String> {
         let mut output = String::new();
 
         // If there's an architecture, generate the implementation
         if let Some(arch) = &entity.architecture {
-            output.push_str(&self.generate_architecture_body(arch)?);
+            let converter = HdlBodyConverter::new(VerilogDialect, self.indent.clone())
+                .with_symbols(entity.symbol_table())
+                .with_comments(!self.strip_comments)
+                .with_fix_latches(self.fix_latches)
+                .with_blocking_assignments(self.blocking_assignments);
+            output.push_str(&self.generate_architecture_body(arch, &converter, warnings)?);
         }
 
         Ok(output)
     }
 
-    fn generate_architecture_body(&self, arch: &Architecture) -> Result<String> {
+    fn generate_architecture_body(
+        &self,
+        arch: &Architecture,
+        converter: &HdlBodyConverter<VerilogDialect>,
+        warnings: &mut Vec<TranspilationWarning>,
+    ) -> Result<String> {
         let mut output = String::new();
 
         // Generate signal declarations (internal signals are always reg when assigned in processes)
@@ -111,378 +397,75 @@ impl VerilogGenerator {
             for signal in &arch.signals {
                 output.push_str(&self.indent);
                 let verilog_type = signal.signal_type.to_verilog();
-                output.push_str(&format!("{} {};\n", verilog_type.replace("wire ", "reg "), signal.name));
+                output.push_str(&format!(
+                    "{} {};{}\n",
+                    verilog_type.replace("wire ", "reg "),
+                    signal.name,
+                    self.source_ref(signal.source_line)
+                ));
             }
         }
 
-        // Generate processes as always blocks
+        // Generate processes as always blocks, wait-based ones as initial/always
         for process in &arch.processes {
+            if process.testbench_style && self.synthesizable {
+                warnings.push(TranspilationWarning {
+                    construct: format!("process {}", process.label.as_deref().unwrap_or("<unlabeled>")),
+                    message: "wait statements are not synthesizable; process skipped".to_string(),
+                });
+                continue;
+            }
+            warnings.extend(converter.latch_warnings(process));
             output.push('\n');
-            output.push_str(&self.generate_process(process)?);
+            let block = if process.testbench_style {
+                converter.convert_timed_process(process)?
+            } else {
+                converter.convert_process(process)?
+            };
+            output.push_str(&self.append_to_first_line(block, process.source_line));
         }
 
         // Generate concurrent statements as assign statements
-        for stmt in &arch.concurrent_statements {
+        for (i, stmt) in arch.concurrent_statements.iter().enumerate() {
+            let assign = converter.convert_concurrent_statement(stmt)?;
             output.push('\n');
             output.push_str(&self.indent);
-            output.push_str(&self.convert_concurrent_statement(stmt)?);
+            output.push_str(&self.append_to_first_line(assign, arch.concurrent_statement_lines.get(i).copied()));
             output.push('\n');
         }
 
-        Ok(output)
-    }
-
-    fn generate_process(&self, process: &crate::ir::Process) -> Result<String> {
-        let mut output = String::new();
-
-        // Determine if it's sequential or combinational based on sensitivity list
-        let is_sequential = process.sensitivity_list.iter()
-            .any(|s| s.contains("clk") || s.contains("clock") || s.contains("rising_edge") || s.contains("falling_edge"));
-
-        output.push_str(&self.indent);
-
-        if is_sequential {
-            // Sequential logic - always @(posedge clk)
-            let mut edge_signals = Vec::new();
-            for sig in &process.sensitivity_list {
-                if sig.contains("clk") || sig.contains("clock") {
-                    edge_signals.push(format!("posedge {}", sig));
-                } else if sig.contains("reset") || sig.contains("rst") {
-                    // Check if active high or low reset
-                    if process.body.contains(&format!("{} = '1'", sig)) || process.body.contains(&format!("{} = \"1\"", sig)) {
-                        edge_signals.push(format!("posedge {}", sig));
-                    } else {
-                        edge_signals.push(format!("negedge {}", sig));
-                    }
-                }
-            }
-
-            if edge_signals.is_empty() {
-                edge_signals.push("posedge clk".to_string());
-            }
-
-            output.push_str(&format!("always @({}) begin\n", edge_signals.join(" or ")));
-        } else {
-            // Combinational logic - always @(*)
-            output.push_str("always @(*) begin\n");
+        // Generate component/entity instantiations as module instances
+        for inst in &arch.instantiations {
+            output.push('\n');
+            output.push_str(&converter.convert_instantiation(inst, 1));
         }
 
-        // Convert VHDL process body to Verilog
-        let verilog_body = self.convert_process_body(&process.body)?;
-        output.push_str(&verilog_body);
-
-        output.push_str(&self.indent);
-        output.push_str("end\n");
-
-        Ok(output)
-    }
-
-    fn convert_process_body(&self, vhdl_body: &str) -> Result<String> {
-        let mut output = String::new();
-        let double_indent = format!("{}{}", self.indent, self.indent);
-        let triple_indent = format!("{}{}{}", self.indent, self.indent, self.indent);
-        let mut in_case = false;
-        let mut case_branch_has_stmt = false;
-        let mut indent_level = 0; // Track nesting level for proper indentation
-
-        for line in vhdl_body.lines() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() || trimmed.starts_with("--") {
-                continue;
-            }
-
-            let mut verilog_line = trimmed.to_string();
-
-            // Skip lines with rising_edge/falling_edge as they're handled in sensitivity list
-            if verilog_line.starts_with("if") && (verilog_line.contains("rising_edge") || verilog_line.contains("falling_edge")) {
-                continue;
-            }
-
-            // Convert hex literals first (before other conversions)
-            // x"0" -> 4'h0, x"1" -> 4'h1, x"FF" -> 8'hFF
-            let hex_re = regex::Regex::new(r#"x"([0-9A-Fa-f]+)""#).unwrap();
-            verilog_line = hex_re.replace_all(&verilog_line, |caps: &regex::Captures| {
-                let hex_value = &caps[1];
-                let bit_width = hex_value.len() * 4; // Each hex digit is 4 bits
-                format!("{}'h{}", bit_width, hex_value)
-            }).to_string();
-
-            // Convert bit literals and comparison operators
-            // Handle '=' comparisons with bit literals (with or without spaces)
-            verilog_line = verilog_line.replace("='1'", " == 1'b1");
-            verilog_line = verilog_line.replace("='0'", " == 1'b0");
-            verilog_line = verilog_line.replace(" = '1'", " == 1'b1");
-            verilog_line = verilog_line.replace(" = '0'", " == 1'b0");
-
-            // Convert remaining bit literals
-            verilog_line = verilog_line.replace("'1'", "1'b1");
-            verilog_line = verilog_line.replace("'0'", "1'b0");
-
-            // Convert others => value
-            verilog_line = verilog_line.replace("(others => 1'b0)", "8'b0");
-            verilog_line = verilog_line.replace("(others => 1'b1)", "8'b1");
-
-            // Convert case statements
-            if verilog_line.starts_with("case ") && verilog_line.contains(" is") {
-                verilog_line = verilog_line.replace(" is", "");
-                verilog_line = verilog_line.replacen("case ", "case (", 1);
-                if !verilog_line.ends_with(")") {
-                    verilog_line.push(')');
-                }
-                in_case = true;
-                case_branch_has_stmt = false;
-            } else if verilog_line.starts_with("when ") {
-                // Close previous case branch if it had statements
-                if in_case && case_branch_has_stmt {
-                    output.push_str(&format!("{}end\n", &double_indent));
-                    case_branch_has_stmt = false;
-                }
-
-                // "when "00" =>" -> "2'b00: begin" or "when IDLE =>" -> "IDLE: begin"
-                if let Some(value_end) = verilog_line.find(" =>") {
-                    let value_part = &verilog_line[5..value_end]; // Skip "when "
-                    let value = value_part.trim();
-                    if value == "others" {
-                        verilog_line = "default: begin".to_string();
-                    } else if value.starts_with('"') && value.ends_with('"') {
-                        // Binary literal: "00" -> 2'b00: begin
-                        let binary = value.trim_matches('"');
-                        let width = binary.len();
-                        verilog_line = format!("{}'b{}: begin", width, binary);
-                    } else {
-                        // Enum or identifier: IDLE -> IDLE: begin
-                        verilog_line = format!("{}: begin", value);
-                    }
-                }
-            } else if verilog_line == "end case" || verilog_line == "end case;" {
-                // Close last case branch
-                if in_case && case_branch_has_stmt {
-                    output.push_str(&format!("{}end\n", &double_indent));
-                }
-                verilog_line = "endcase".to_string();
-                in_case = false;
-                case_branch_has_stmt = false;
-            }
-
-            // Handle if/elsif/else/then keywords
-            let is_if = verilog_line.starts_with("if ") || verilog_line.starts_with("if(");
-            let is_elsif = verilog_line.starts_with("elsif ") || verilog_line.starts_with("elsif(");
-            let is_else = verilog_line.trim() == "else";
-            let is_endif = verilog_line == "end if" || verilog_line == "end if;";
-
-            if is_if {
-                // "if(reset == 1'b1) then" -> "if (reset == 1'b1) begin"
-                // First, add space after 'if' if needed
-                if verilog_line.starts_with("if(") {
-                    verilog_line = verilog_line.replacen("if(", "if (", 1);
-                }
-                // Remove 'then' and add 'begin'
-                if verilog_line.contains(" then") {
-                    verilog_line = verilog_line.replace(" then", ") begin");
-                } else if verilog_line.contains(" begin") {
-                    // Already has begin, ensure proper parentheses
-                    if !verilog_line.contains(')') && verilog_line.contains('(') {
-                        verilog_line = verilog_line.replace(" begin", ") begin");
-                    }
-                } else {
-                    // No 'then' or 'begin', add them
-                    if verilog_line.contains('(') && !verilog_line.contains(')') {
-                        verilog_line.push_str(") begin");
-                    } else if !verilog_line.ends_with("begin") {
-                        verilog_line.push_str(" begin");
-                    }
-                }
-            } else if is_elsif {
-                // "elsif rising_edge(clk) then" -> "end else begin"
-                // (rising_edge is already handled in sensitivity list)
-                if verilog_line.contains("rising_edge") || verilog_line.contains("falling_edge") {
-                    verilog_line = "end else begin".to_string();
-                } else {
-                    verilog_line = verilog_line.replacen("elsif ", "end else if (", 1);
-                    verilog_line = verilog_line.replace(" then", ") begin");
-                    if !verilog_line.contains(") begin") {
-                        verilog_line.push_str(" begin");
-                    }
-                }
-            } else if is_else {
-                verilog_line = "end else begin".to_string();
-            } else if is_endif {
-                verilog_line = "end".to_string();
-            }
-
-            // Convert logical operators
-            verilog_line = verilog_line.replace(" and ", " & ");
-            verilog_line = verilog_line.replace(" or ", " | ");
-            verilog_line = verilog_line.replace(" xor ", " ^ ");
-            verilog_line = verilog_line.replace(" not ", " ~");
-
-            // Convert type conversions - remove VHDL type casts
-            // Handle nested type conversions
-            verilog_line = verilog_line.replace("std_logic_vector(unsigned(", "");
-            verilog_line = verilog_line.replace("std_logic_vector(signed(", "");
-            verilog_line = verilog_line.replace("std_logic_vector(", "");
-            verilog_line = verilog_line.replace("unsigned(", "");
-            verilog_line = verilog_line.replace("signed(", "");
-            verilog_line = verilog_line.replace("to_unsigned(", "");
-            verilog_line = verilog_line.replace("to_signed(", "");
-            verilog_line = verilog_line.replace("to_integer(", "");
-
-            // Remove extra closing parens from type conversions
-            let mut paren_diff = verilog_line.matches(')').count() as i32 - verilog_line.matches('(').count() as i32;
-            while paren_diff > 0 {
-                if let Some(pos) = verilog_line.rfind(')') {
-                    verilog_line.remove(pos);
-                    paren_diff -= 1;
-                } else {
-                    break;
-                }
-            }
-
-            // Don't add semicolons to control flow keywords
-            let is_control_flow = verilog_line.contains("begin") ||
-                                   (verilog_line.starts_with("end") && !verilog_line.starts_with("endcase")) ||
-                                   verilog_line == "else" ||
-                                   verilog_line.ends_with(":") || // case labels
-                                   verilog_line.starts_with("case") ||
-                                   verilog_line == "endcase";
-
-            // Adjust indent level based on control flow
-            if verilog_line.starts_with("end") {
-                if indent_level > 0 {
-                    indent_level -= 1;
-                }
-            }
-
-            // Choose appropriate indentation
-            let current_indent = match indent_level {
-                0 => double_indent.clone(),
-                1 => triple_indent.clone(),
-                _ => format!("{}{}", triple_indent, self.indent.repeat(indent_level - 1)),
-            };
-
-            output.push_str(&current_indent);
-            output.push_str(&verilog_line);
-
-            // Add semicolon to assignments only
-            if !is_control_flow && !verilog_line.ends_with(';') {
-                output.push(';');
-            }
-
+        // Generate for/if generate statements inside a generate region
+        if !arch.generate_blocks.is_empty() {
             output.push('\n');
-
-            // Increase indent level after begin
-            if verilog_line.contains("begin") {
-                indent_level += 1;
-            }
-
-            // Track if we've added a statement to a case branch
-            if in_case && !is_control_flow && verilog_line.contains(" <= ") {
-                case_branch_has_stmt = true;
-            }
+            output.push_str(&converter.convert_generate_blocks(&arch.generate_blocks)?);
         }
 
         Ok(output)
     }
 
-    fn convert_concurrent_statement(&self, stmt: &str) -> Result<String> {
-        // Convert VHDL concurrent statements to Verilog assign statements
-        let mut verilog = stmt.to_string();
-
-        // Remove type conversions
-        verilog = verilog.replace("std_logic_vector(", "");
-        verilog = verilog.replace("unsigned(", "");
-        verilog = verilog.replace("signed(", "");
-
-        // Remove extra closing parens from type conversions
-        let mut paren_diff = verilog.matches(')').count() as i32 - verilog.matches('(').count() as i32;
-        while paren_diff > 0 {
-            if let Some(pos) = verilog.rfind(')') {
-                verilog.remove(pos);
-                paren_diff -= 1;
-            } else {
-                break;
-            }
+    /// ` // vhdl:<source>:<line>` when source refs are enabled and the line is known
+    fn source_ref(&self, line: Option<usize>) -> String {
+        match (&self.source_refs, line) {
+            (Some(source), Some(line)) => source_ref(source, line),
+            _ => String::new(),
         }
+    }
 
-        // Handle with...select statements
-        if verilog.contains("with ") && verilog.contains(" select") {
-            return Ok(format!("// TODO: Convert VHDL 'with...select' statement:\n    // {}",
-                verilog.replace("\n", "\n    // ")));
+    fn append_to_first_line(&self, code: String, line: Option<usize>) -> String {
+        let source_ref = self.source_ref(line);
+        if source_ref.is_empty() {
+            return code;
         }
-
-        // Handle conditional assignments: "signal <= '1' when condition else '0'"
-        if verilog.contains(" when ") && verilog.contains(" else ") {
-            // Parse: "target <= value1 when condition else value2"
-            let parts: Vec<&str> = verilog.split(" <= ").collect();
-            if parts.len() == 2 {
-                let target = parts[0].trim();
-                let rest = parts[1];
-
-                if let Some(when_pos) = rest.find(" when ") {
-                    if let Some(else_pos) = rest.find(" else ") {
-                        let value1 = rest[..when_pos].trim();
-                        let condition = rest[when_pos+6..else_pos].trim();
-                        let value2 = rest[else_pos+6..].trim();
-
-                        // Convert to ternary: target = condition ? value1 : value2
-                        let mut cond_conv = condition.to_string();
-                        cond_conv = cond_conv.replace(" = ", " == ");
-                        // Convert string literals in conditions like "00000000" to 8'b00000000
-                        if cond_conv.contains('"') {
-                            // Simple string replacement for binary literals
-                            let mut result = String::new();
-                            let mut chars = cond_conv.chars().peekable();
-                            
-                            while let Some(ch) = chars.next() {
-                                if ch == '"' {
-                                    // Found start of string literal
-                                    let mut binary = String::new();
-                                    while let Some(&next_ch) = chars.peek() {
-                                        if next_ch == '"' {
-                                            chars.next(); // consume the closing quote
-                                            break;
-                                        }
-                                        if next_ch == '0' || next_ch == '1' {
-                                            binary.push(chars.next().unwrap());
-                                        } else {
-                                            chars.next();
-                                        }
-                                    }
-                                    
-                                    if !binary.is_empty() && binary.chars().all(|c| c == '0' || c == '1') {
-                                        result.push_str(&format!("{}'b{}", binary.len(), binary));
-                                    } else {
-                                        result.push('"');
-                                        result.push_str(&binary);
-                                        result.push('"');
-                                    }
-                                } else {
-                                    result.push(ch);
-                                }
-                            }
-                            cond_conv = result;
-                        }
-
-                        let val1_conv = value1.replace("'1'", "1'b1").replace("'0'", "1'b0");
-                        let val2_conv = value2.replace("'1'", "1'b1").replace("'0'", "1'b0");
-
-                        verilog = format!("assign {} = {} ? {} : {};", target, cond_conv, val1_conv, val2_conv);
-                        return Ok(verilog);
-                    }
-                }
-            }
+        match code.find('\n') {
+            Some(pos) => format!("{}{}{}", &code[..pos], source_ref, &code[pos..]),
+            None => code + &source_ref,
         }
-
-        verilog = verilog.replace(" <= ", " = ");  // Concurrent assignment
-        verilog = verilog.replace("'1'", "1'b1");
-        verilog = verilog.replace("'0'", "1'b0");
-
-        // If it doesn't look like an assignment, wrap it in assign
-        if verilog.contains(" = ") && !verilog.starts_with("assign ") {
-            verilog = format!("assign {};", verilog.trim_end_matches(';'));
-        }
-
-        Ok(verilog)
     }
 
     /// Generate port declarations in Verilog-2001 style (separate from module header)
@@ -508,7 +491,7 @@ impl Default for VerilogGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{PortDirection, VHDLType, VectorRange};
+    use crate::ir::{Generic, PortDirection, VHDLType, VectorRange};
 
     #[test]
     fn test_generate_simple_module() {
@@ -527,20 +510,20 @@ mod tests {
             "count".to_string(),
             PortDirection::Out,
             VHDLType::StdLogicVector(VectorRange {
-                left: 7,
-                right: 0,
+                left: 7.into(),
+                right: 0.into(),
                 downto: true,
             }),
         ));
 
         let generator = VerilogGenerator::new();
-        let verilog = generator.generate(&entity).unwrap();
+        let verilog = generator.generate(&entity).unwrap().0;
 
         println!("Generated Verilog:\n{}", verilog);
 
         assert!(verilog.contains("module counter"));
-        assert!(verilog.contains("input wire clk"));
-        assert!(verilog.contains("input wire reset"));
+        assert!(verilog.contains("input  wire       clk"));
+        assert!(verilog.contains("input  wire       reset"));
         assert!(verilog.contains("output wire [7:0] count"));
         assert!(verilog.contains("endmodule"));
     }
@@ -560,9 +543,270 @@ mod tests {
         ));
 
         let generator = VerilogGenerator::new();
-        let verilog = generator.generate(&entity).unwrap();
+        let verilog = generator.generate(&entity).unwrap().0;
 
-        assert!(verilog.contains("input wire signed [31:0] int_signal"));
-        assert!(verilog.contains("output wire bit_signal"));
+        assert!(verilog.contains("input  wire signed [31:0] int_signal"));
+        assert!(verilog.contains("output wire               bit_signal"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_ranged_integer_ports_are_sized() {
+        let mut entity = Entity::new("levels".to_string());
+        for (name, direction, range) in [
+            ("level", PortDirection::In, "0 to 255"),
+            ("offset", PortDirection::In, "-8 to 7"),
+            ("addr", PortDirection::Out, "0 to 2**AW-1"),
+        ] {
+            let port_type = VHDLType::ConstrainedInteger(crate::ir::IntegerRange::from_vhdl(range).unwrap());
+            entity.add_port(Port::new(name.to_string(), direction, port_type));
+        }
+        entity.add_port(Port::new("count".to_string(), PortDirection::Out, VHDLType::Integer));
+
+        let verilog = VerilogGenerator::new().generate(&entity).unwrap().0;
+        assert_eq!(
+            verilog,
+            [
+                "module levels (",
+                "    input  wire [7:0]         level,",
+                "    input  wire signed [3:0]  offset,",
+                "    output wire [AW-1:0]      addr,",
+                "    output wire signed [31:0] count",
+                ");",
+                "endmodule",
+                "",
+            ].join("\n")
+        );
+    }
+
+    const NESTED_PROCESS_BODY: &str = r#"
+        if rising_edge(clk) then
+            if (rst = '1') then
+                state <= IDLE;
+                count <= x"00";
+            else
+                case state is
+                    when IDLE =>
+                        if (start = '1') then
+                            if (mode = '1') then
+                                state <= RUN;
+                            else
+                                state <= DONE;
+                            end if;
+                        end if;
+                    when RUN =>
+                        count <= count + x"01";
+                    when others =>
+                        state <= IDLE;
+                end case;
+            end if;
+        end if;
+    "#;
+
+    fn nested_process_entity(body: &str) -> Entity {
+        let mut entity = Entity::new("nested".to_string());
+        for name in ["clk", "rst", "start", "mode"] {
+            entity.add_port(Port::new(name.to_string(), PortDirection::In, VHDLType::StdLogic));
+        }
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![crate::ir::Process {
+                label: Some("fsm".to_string()),
+                sensitivity_list: vec!["clk".to_string()],
+                body: body.to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    fn counter_entity() -> Entity {
+        let mut entity = nested_process_entity("if rising_edge(clk) then\ncount <= count + 1;\nend if;");
+        entity.name = "counter".to_string();
+        entity.ports = vec![
+            Port::new("clk".to_string(), PortDirection::In, VHDLType::StdLogic),
+            Port::new("reset".to_string(), PortDirection::In, VHDLType::StdLogic),
+            Port::new(
+                "count".to_string(),
+                PortDirection::Out,
+                VHDLType::StdLogicVector(VectorRange { left: 7.into(), right: 0.into(), downto: true }),
+            ),
+        ];
+        entity
+    }
+
+    #[test]
+    fn test_verilog2001_ansi_style() {
+        let output = VerilogGenerator::new().with_style(VerilogStyle::Verilog2001Ansi)
+            .generate(&counter_entity()).unwrap().0;
+        assert!(output.starts_with("module counter (\n    input  wire      clk,\n"));
+        assert!(output.contains("    output reg [7:0] count\n);\n"));
+    }
+
+    #[test]
+    fn test_sliced_and_indexed_outputs_are_reg() {
+        let mut entity = counter_entity();
+        entity.architecture.as_mut().unwrap().processes[0].body =
+            "if rising_edge(clk) then\ncount(3 downto 0) <= count(7 downto 4);\ncount(7) <= '0';\nend if;".to_string();
+        let output = VerilogGenerator::new().generate(&entity).unwrap().0;
+        assert!(output.contains("    output reg [7:0] count\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_architecture_local_enum() {
+        let mut entity = counter_entity();
+        let arch = entity.architecture.as_mut().unwrap();
+        arch.signals.push(crate::ir::Signal { name: "state".to_string(), signal_type: VHDLType::Custom("state_t".to_string()), source_line: None });
+        arch.types = TypeResolutionContext::new()
+            .with_enum("state_t", vec!["IDLE".to_string(), "REQUEST".to_string(), "FINISH".to_string()]);
+
+        // The local type shadows a same-named package type
+        let packages = TypeResolutionContext::new().with_enum("state_t", vec!["A".to_string(), "B".to_string()]);
+        let (output, report) = VerilogGenerator::new().with_type_context(Some(packages)).generate(&entity).unwrap();
+        assert!(output.contains("    localparam [1:0] IDLE = 2'd0;\n    localparam [1:0] REQUEST = 2'd1;\n    localparam [1:0] FINISH = 2'd2;\n"), "{}", output);
+        assert!(output.contains("    reg [1:0] state;\n"));
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_verilog2001_non_ansi_style() {
+        let mut entity = counter_entity();
+        entity.add_generic(Generic { name: "WIDTH".to_string(), generic_type: "integer".to_string(), default_value: Some("8".to_string()) });
+        let output = VerilogGenerator::new().with_style(VerilogStyle::Verilog2001NonAnsi)
+            .generate(&entity).unwrap().0;
+        assert!(output.starts_with(
+            "module counter #(\n    parameter WIDTH = 8\n) (clk, reset, count);\n    input wire clk;\n    input wire reset;\n    output reg [7:0] count;\n"
+        ));
+        assert_eq!("verilog2001_non_ansi".parse::<VerilogStyle>().unwrap(), VerilogStyle::Verilog2001NonAnsi);
+    }
+
+    #[test]
+    fn test_verilog95_style() {
+        let mut entity = counter_entity();
+        entity.add_generic(Generic { name: "WIDTH".to_string(), generic_type: "integer".to_string(), default_value: Some("8".to_string()) });
+        let generator = VerilogGenerator::new().with_style(VerilogStyle::Verilog95);
+        let output = generator.generate(&entity).unwrap().0;
+        assert!(output.starts_with(
+            "module counter(clk, reset, count);\n    parameter WIDTH = 8;\n    input clk;\n    input reset;\n    output [7:0] count;\n    reg [7:0] count;\n"
+        ));
+        assert!(output.contains("always @(posedge clk)"));
+
+        let stub = generator.generate_stub(&Entity::new("empty".to_string())).unwrap();
+        assert_eq!(stub, "module empty;\nendmodule\n");
+        assert!("verilog2005".parse::<VerilogStyle>().is_err());
+    }
+
+    #[test]
+    fn test_nested_if_case_matches_golden() {
+        let generator = VerilogGenerator::new();
+        let output = generator.generate(&nested_process_entity(NESTED_PROCESS_BODY)).unwrap().0;
+
+        crate::ir::golden::assert_golden(&output, "nested_if_case.v");
+    }
+
+    #[test]
+    fn test_unbalanced_process_body_is_an_error() {
+        let generator = VerilogGenerator::new();
+
+        let extra_end = nested_process_entity("if a = '1' then\nb <= '0';\nend if;\nend if;");
+        assert!(generator.generate(&extra_end).is_err());
+
+        let unclosed = nested_process_entity("if a = '1' then\nb <= '0';");
+        let err = generator.generate(&unclosed).unwrap_err();
+        assert!(format!("{:#}", err).contains("still open"));
+    }
+
+    #[test]
+    fn test_stub_has_ports_only() {
+        let mut entity = nested_process_entity(NESTED_PROCESS_BODY);
+        if let Some(arch) = entity.architecture.as_mut() {
+            arch.signals.push(crate::ir::Signal {
+                name: "state".to_string(),
+                signal_type: VHDLType::Custom("state_t".to_string()),
+                source_line: None,
+            });
+        }
+
+        let stub = VerilogGenerator::new().generate_stub(&entity).unwrap();
+        assert!(stub.starts_with("module nested (\n"));
+        assert!(stub.contains("input wire clk"));
+        assert!(!stub.contains("state"));
+        assert!(!stub.contains("always"));
+        assert!(stub.trim_end().ends_with(");\nendmodule"));
+
+        let black_box = VerilogGenerator::new().with_black_box(true).generate_stub(&entity).unwrap();
+        assert!(black_box.starts_with("// pragma: black_box\n(* black_box *)\nmodule nested"));
+    }
+
+    #[test]
+    fn test_formatted_counter_matches_golden() {
+        let mut entity = Entity::new("counter".to_string());
+        for name in ["clk", "reset", "enable"] {
+            entity.add_port(Port::new(name.to_string(), PortDirection::In, VHDLType::StdLogic));
+        }
+        let byte = VectorRange { left: 7.into(), right: 0.into(), downto: true };
+        entity.add_port(Port::new("count".to_string(), PortDirection::Out, VHDLType::StdLogicVector(byte.clone())));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![crate::ir::Signal { name: "count_reg".to_string(), signal_type: VHDLType::Unsigned(byte), source_line: None }],
+            processes: vec![crate::ir::Process {
+                label: None,
+                sensitivity_list: vec!["clk".to_string(), "reset".to_string()],
+                body: "if reset = '1' then\n    count_reg <= (others => '0');\nelsif rising_edge(clk) then\n    if enable = '1' then\n        count_reg <= count_reg + 1;\n    end if;\nend if;".to_string(),
+                source_line: None,
+                testbench_style: false,
+            }],
+            concurrent_statements: vec!["count <= std_logic_vector(count_reg);".to_string()],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+
+        let (verilog, report) = VerilogGenerator::new().generate(&entity).unwrap();
+        crate::ir::golden::assert_golden(&verilog, "counter.v");
+        assert_eq!(report.port_mappings.len(), 4);
+        assert_eq!(report.port_mappings[3].hdl_type, "wire [7:0]");
+    }
+    #[test]
+    fn test_source_map_traces_ports_to_vhdl_lines() {
+        let vhdl = "entity buf is\n  port (\n    a : in  std_logic;\n    y : out std_logic\n  );\nend entity;\n\narchitecture rtl of buf is\n  signal t : std_logic;\nbegin\n  t <= not a;\n  y <= t;\nend architecture;\n";
+        let mut entity = Entity::new("buf".to_string());
+        entity.add_port(Port::new("a".to_string(), PortDirection::In, VHDLType::StdLogic).with_source_line(Some(3)));
+        entity.add_port(Port::new("y".to_string(), PortDirection::Out, VHDLType::StdLogic).with_source_line(Some(4)));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![crate::ir::Signal { name: "t".to_string(), signal_type: VHDLType::StdLogic, source_line: Some(9) }],
+            processes: vec![],
+            concurrent_statements: vec!["t <= not a;".to_string(), "y <= t;".to_string()],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![11, 12],
+            types: Default::default(),
+        });
+
+        let generator = VerilogGenerator::new();
+        assert!(!generator.generate(&entity).unwrap().0.contains("// vhdl:"));
+
+        let (verilog, mapping) = generator.generate_with_source_map(&entity, "buf.vhd", &SourceMap::new(vhdl)).unwrap();
+        assert!(verilog.contains("    input  wire a, // vhdl:buf.vhd:3\n    output wire y // vhdl:buf.vhd:4\n"));
+        assert!(verilog.contains("wire t; // vhdl:buf.vhd:9\n"));
+
+        let line = |n: u32| verilog.lines().nth(n as usize - 1).unwrap().trim();
+        let ports: Vec<(u32, u32)> = mapping.entries.iter()
+            .filter(|entry| line(entry.verilog_line).starts_with("input") || line(entry.verilog_line).starts_with("output"))
+            .map(|entry| (entry.vhdl_line, entry.vhdl_col))
+            .collect();
+        assert_eq!(ports, vec![(3, 4), (4, 4)]);
+        assert_eq!(mapping.entries.iter().map(|entry| entry.vhdl_line).collect::<Vec<_>>(), vec![3, 4, 9, 11, 12]);
+        let assign = &mapping.entries[4];
+        assert_eq!(line(assign.verilog_line), "assign y = t; // vhdl:buf.vhd:12");
+        assert_eq!(assign.vhdl_col, 2);
+    }
+}
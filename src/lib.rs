@@ -7,6 +7,13 @@ pub mod parser;
 pub mod config;
 pub mod constants;
 pub mod utils;
+pub mod api;
+#[cfg(test)]
+mod test_support;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "ffi")]
+mod ffi;
 
 // Re-export commonly used types
 pub use agent::{Agent, AgentType, BaseAgent};
@@ -63,6 +63,54 @@ impl LLMMessage {
             LLMMessage::Tool { content, .. } => Some(content),
         }
     }
+
+    /// Rough token count: UTF-8 bytes / 4, plus 4 tokens per tool call
+    pub fn estimated_tokens(&self) -> usize {
+        let mut bytes = self.content().map(str::len).unwrap_or(0);
+        let mut overhead = 0;
+
+        if let LLMMessage::Assistant { tool_calls: Some(tool_calls), .. } = self {
+            for call in tool_calls {
+                bytes += call.name.len() + call.arguments.to_string().len();
+                overhead += 4;
+            }
+        }
+
+        bytes.div_ceil(4) + overhead
+    }
+}
+
+/// Estimated token count of a whole conversation
+pub fn conversation_tokens(messages: &[LLMMessage]) -> usize {
+    messages.iter().map(LLMMessage::estimated_tokens).sum()
+}
+
+/// Input prices in USD per million tokens; more specific names come first
+const INPUT_PRICES_PER_MILLION: &[(&str, f64)] = &[
+    ("gpt-4o-mini", 0.15),
+    ("gpt-4o", 5.0),
+    ("gpt-4-turbo", 10.0),
+    ("gpt-4", 30.0),
+    ("gpt-3.5-turbo", 0.5),
+    ("claude-3-5-sonnet", 3.0),
+    ("claude-3-5-haiku", 0.8),
+    ("claude-3-opus", 15.0),
+    ("claude-3-haiku", 0.25),
+];
+
+/// Price assumed for models missing from the table, so limits stay conservative
+const DEFAULT_INPUT_PRICE_PER_MILLION: f64 = 10.0;
+
+/// Estimated input cost of `tokens` for a model; names may carry a provider prefix (e.g. "openai/gpt-4o")
+pub fn estimate_cost_usd(tokens: usize, model: &str) -> f64 {
+    let model = model.to_lowercase();
+    let price = INPUT_PRICES_PER_MILLION
+        .iter()
+        .find(|(name, _)| model.contains(name))
+        .map(|(_, price)| *price)
+        .unwrap_or(DEFAULT_INPUT_PRICE_PER_MILLION);
+
+    tokens as f64 * price / 1_000_000.0
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -139,4 +187,31 @@ impl LLMResponse {
         self.usage = Some(usage);
         self
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimated_tokens() {
+        assert_eq!(LLMMessage::user("abcdefgh".to_string()).estimated_tokens(), 2);
+
+        let call = ToolCall::new("bash".to_string(), serde_json::json!({}));
+        let message = LLMMessage::assistant("abcd".to_string(), Some(vec![call.clone(), call]));
+        // (4 + 2 * ("bash" + "{}")) bytes / 4, plus 4 per call
+        assert_eq!(message.estimated_tokens(), 4 + 8);
+
+        assert_eq!(conversation_tokens(&[LLMMessage::user("abcd".to_string()), message]), 13);
+    }
+
+    #[test]
+    fn test_price_table() {
+        assert_eq!(estimate_cost_usd(1_000_000, "gpt-4o"), 5.0);
+        assert_eq!(estimate_cost_usd(1_000_000, "openai/gpt-4o-mini"), 0.15);
+        assert_eq!(estimate_cost_usd(2_000_000, "claude-3-5-sonnet-20241022"), 6.0);
+        assert_eq!(estimate_cost_usd(1_000_000, "GPT-4"), 30.0);
+        assert_eq!(estimate_cost_usd(1_000_000, "some-local-model"), DEFAULT_INPUT_PRICE_PER_MILLION);
+        assert_eq!(estimate_cost_usd(0, "gpt-4o"), 0.0);
+    }
+}
@@ -27,18 +27,14 @@ pub fn create_llm_client(config: &ModelConfig) -> Result<Arc<dyn LLMClient>> {
             let client = crate::llm::mock::MockLLMClient::new(config.clone())?;
             Ok(Arc::new(client))
         }
-        // "openai" => {
-        //     let client = crate::llm::openai::OpenAIClient::new(config.clone())?;
-        //     Ok(Arc::new(client))
-        // }
+        "openai" | "openrouter" | "azure_openai" => {
+            let client = crate::llm::openai::OpenAIClient::new(config.clone())?;
+            Ok(Arc::new(client))
+        }
         // "anthropic" => {
         //     let client = crate::llm::openai::OpenAIClient::new(config.clone())?; // Using OpenAI-compatible for now
         //     Ok(Arc::new(client))
         // }
-        // "openrouter" => {
-        //     let client = crate::llm::openai::OpenAIClient::new(config.clone())?;
-        //     Ok(Arc::new(client))
-        // }
         // "infineon" => {
         //     let client = crate::llm::infineon::InfineonClient::new(config.clone())?;
         //     Ok(Arc::new(client))
@@ -1,10 +1,12 @@
 // Mock LLM client for testing
 use anyhow::Result;
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 use crate::config::ModelConfig;
 use crate::llm::{LLMClient, LLMMessage, LLMResponse};
-use crate::tools::Tool;
+use crate::tools::{Tool, ToolCall};
 
 pub struct MockLLMClient {
     model_name: String,
@@ -37,4 +39,85 @@ impl LLMClient for MockLLMClient {
     fn get_model_name(&self) -> &str {
         &self.model_name
     }
-}
\ No newline at end of file
+}
+
+/// Client that replays pre-recorded responses in order, for deterministic tests
+pub struct ReplayLLMClient {
+    model_name: String,
+    responses: Mutex<VecDeque<LLMResponse>>,
+}
+
+impl ReplayLLMClient {
+    pub fn new(responses: Vec<LLMResponse>) -> Self {
+        Self {
+            model_name: "replay".to_string(),
+            responses: Mutex::new(responses.into()),
+        }
+    }
+
+    /// Load responses recorded by `OpenAIClient::with_record_mode`
+    pub fn from_fixture(path: &Path) -> Result<Self> {
+        Ok(Self::new(crate::llm::openai::read_fixture(path)?))
+    }
+
+    /// Queue a response that calls `tool_name` with `args`
+    pub fn with_tool_call_response(self, tool_name: &str, args: serde_json::Value) -> Self {
+        let response = LLMResponse::new(String::new())
+            .with_tool_calls(vec![ToolCall::new(tool_name.to_string(), args)]);
+        self.responses.lock().unwrap().push_back(response);
+        self
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.responses.lock().unwrap().len()
+    }
+
+    /// Panic if the test did not request every queued response
+    pub fn assert_all_responses_consumed(&self) {
+        let remaining = self.remaining();
+        assert!(remaining == 0, "ReplayLLMClient: {} recorded response(s) were never requested", remaining);
+    }
+}
+
+impl LLMClient for ReplayLLMClient {
+    fn complete(
+        &self,
+        _messages: &[LLMMessage],
+        _tools: Option<Vec<Arc<dyn Tool>>>,
+    ) -> Result<LLMResponse> {
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("ReplayLLMClient exhausted: more requests than recorded responses"))
+    }
+
+    fn get_model_name(&self) -> &str {
+        &self.model_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_in_order_then_exhausted() {
+        let client = ReplayLLMClient::new(vec![LLMResponse::new("first".to_string())])
+            .with_tool_call_response("task_done", serde_json::json!({"result": "ok"}));
+
+        assert_eq!(client.complete(&[], None).unwrap().content.as_deref(), Some("first"));
+        let tool_calls = client.complete(&[], None).unwrap().tool_calls.unwrap();
+        assert_eq!(tool_calls[0].name, "task_done");
+        assert_eq!(tool_calls[0].arguments["result"], "ok");
+        client.assert_all_responses_consumed();
+
+        assert!(client.complete(&[], None).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "never requested")]
+    fn test_unconsumed_responses_panic() {
+        ReplayLLMClient::new(vec![LLMResponse::new("unused".to_string())]).assert_all_responses_consumed();
+    }
+}
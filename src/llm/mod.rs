@@ -1,8 +1,11 @@
 pub mod basics;
 pub mod client;
-// pub mod openai;  // Commented out - has compilation errors
+pub mod openai;
 pub mod mock;
+pub mod summarizer;
 // pub mod infineon;  // Commented out for now
 
-pub use basics::{LLMMessage, LLMResponse, LLMUsage};
-pub use client::{LLMClient, create_llm_client};
\ No newline at end of file
+pub use basics::{conversation_tokens, estimate_cost_usd, LLMMessage, LLMResponse, LLMUsage};
+pub use client::{LLMClient, create_llm_client};
+pub use mock::ReplayLLMClient;
+pub use summarizer::{truncate_conversation, LLMConversationSummarizer, DEFAULT_PRESERVED_MESSAGES};
\ No newline at end of file
@@ -1,8 +1,8 @@
 use anyhow::{Context, Result};
-use crossterm::style::{Color, Stylize};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -18,11 +18,12 @@ struct OpenAIRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<serde_json::Value>>,
     temperature: f32,
-    top_p: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
-    max_tokens: Option<i32>,
+    top_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    max_completion_tokens: Option<i32>,
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stop: Option<Vec<String>>,
 }
@@ -74,14 +75,24 @@ struct OpenAIUsage {
     prompt_tokens: i32,
     #[serde(default)]
     completion_tokens: i32,
-    #[serde(default)]
-    total_tokens: i32,
+}
+
+const DEFAULT_AZURE_API_VERSION: &str = "2024-02-01";
+
+/// Azure OpenAI routes requests per deployment and versions the API via a query parameter
+#[derive(Debug, Clone)]
+struct AzureEndpoint {
+    api_version: String,
 }
 
 pub struct OpenAIClient {
     config: ModelConfig,
     client: reqwest::blocking::Client,
     base_url: String,
+    azure: Option<AzureEndpoint>,
+    /// Append every response to `fixture_path` for offline replay
+    record_mode: bool,
+    fixture_path: Option<PathBuf>,
 }
 
 impl OpenAIClient {
@@ -91,24 +102,47 @@ impl OpenAIClient {
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("Model provider not configured"))?;
 
-        let base_url = provider.base_url.clone().unwrap_or_else(|| {
-            match provider.provider.to_lowercase().as_str() {
+        let provider_name = provider.provider.to_lowercase();
+        let api_key = provider.api_key.clone().unwrap_or_default();
+
+        let azure = if provider_name == "azure_openai" {
+            Some(AzureEndpoint {
+                api_version: provider.azure_api_version.clone()
+                    .unwrap_or_else(|| DEFAULT_AZURE_API_VERSION.to_string()),
+            })
+        } else {
+            None
+        };
+
+        let base_url = match provider.base_url.clone() {
+            Some(base_url) => base_url,
+            None => match provider_name.as_str() {
                 "openai" => "https://api.openai.com/v1".to_string(),
                 "anthropic" => "https://api.anthropic.com/v1".to_string(),
                 "openrouter" => "https://openrouter.ai/api/v1".to_string(),
+                "azure_openai" => {
+                    let resource = provider.azure_resource_name.as_ref().ok_or_else(|| {
+                        anyhow::anyhow!("azure_resource_name is required for the azure_openai provider")
+                    })?;
+                    format!("https://{}.openai.azure.com", resource)
+                }
                 _ => "https://api.openai.com/v1".to_string(),
-            }
-        });
+            },
+        };
 
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
-        let auth_header = if provider.provider.to_lowercase() == "anthropic" {
-            format!("x-api-key {}", provider.api_key)
+        if azure.is_some() {
+            headers.insert("api-key", HeaderValue::from_str(&api_key)?);
         } else {
-            format!("Bearer {}", provider.api_key)
-        };
-        headers.insert(AUTHORIZATION, HeaderValue::from_str(&auth_header)?);
+            let auth_header = if provider_name == "anthropic" {
+                format!("x-api-key {}", api_key)
+            } else {
+                format!("Bearer {}", api_key)
+            };
+            headers.insert(AUTHORIZATION, HeaderValue::from_str(&auth_header)?);
+        }
 
         let client = reqwest::blocking::Client::builder()
             .default_headers(headers)
@@ -119,9 +153,30 @@ impl OpenAIClient {
             config,
             client,
             base_url,
+            azure,
+            record_mode: false,
+            fixture_path: None,
         })
     }
 
+    /// Record responses to a JSON fixture that `ReplayLLMClient::from_fixture` can load
+    pub fn with_record_mode(mut self, fixture_path: impl Into<PathBuf>) -> Self {
+        self.record_mode = true;
+        self.fixture_path = Some(fixture_path.into());
+        self
+    }
+
+    fn record_response(&self, response: &LLMResponse) -> Result<()> {
+        let Some(path) = self.fixture_path.as_deref().filter(|_| self.record_mode) else {
+            return Ok(());
+        };
+
+        let mut recorded = read_fixture(path)?;
+        recorded.push(response.clone());
+        std::fs::write(path, serde_json::to_string_pretty(&recorded)?)
+            .context(format!("Failed to write LLM fixture: {}", path.display()))
+    }
+
     fn convert_messages(&self, messages: &[LLMMessage]) -> Vec<OpenAIMessage> {
         messages
             .iter()
@@ -170,7 +225,15 @@ impl OpenAIClient {
     }
 
     fn make_request(&self, request: OpenAIRequest) -> Result<OpenAIResponse> {
-        let url = format!("{}/chat/completions", self.base_url);
+        let url = match &self.azure {
+            Some(azure) => format!(
+                "{}/openai/deployments/{}/chat/completions?api-version={}",
+                self.base_url.trim_end_matches('/'),
+                self.config.model,
+                azure.api_version
+            ),
+            None => format!("{}/chat/completions", self.base_url),
+        };
 
         // Log outgoing request
         tracing::info!("Sending request to OpenAI API: {}", url);
@@ -211,114 +274,6 @@ impl OpenAIClient {
 
         Ok(openai_response)
     }
-
-    /// Log formatted JSON (removed visual formatting as it's now logged to file)
-    fn print_json_box(&self, json_str: &str, tag: &str) {
-        // This function is kept for compatibility but doesn't print anything
-        // Logging is handled in make_request
-        tracing::trace!("{} JSON: {}", tag, json_str);
-    }
-
-    /// Flatten JSON into hierarchical table rows
-    fn flatten_json(&self, value: &serde_json::Value, path: &mut Vec<String>, rows: &mut Vec<Vec<String>>) {
-        match value {
-            serde_json::Value::Object(map) => {
-                for (key, val) in map {
-                    path.push(key.clone());
-                    self.flatten_json(val, path, rows);
-                    path.pop();
-                }
-            }
-            serde_json::Value::Array(arr) => {
-                for (i, item) in arr.iter().enumerate() {
-                    path.push(format!("[{}]", i));
-                    self.flatten_json(item, path, rows);
-                    path.pop();
-                }
-            }
-            _ => {
-                // This is a leaf value, create a row
-                let mut row = vec!["".to_string(); 4]; // 4 columns max
-                for (i, path_part) in path.iter().enumerate() {
-                    if i < 4 {
-                        row[i] = path_part.clone();
-                    }
-                }
-                
-                // Put the value in the last column (no truncation since we wrap)
-                let value_str = match value {
-                    serde_json::Value::String(s) => s.replace('\n', " ").replace('\r', " "),
-                    serde_json::Value::Number(n) => n.to_string(),
-                    serde_json::Value::Bool(b) => b.to_string(),
-                    serde_json::Value::Null => "null".to_string(),
-                    _ => value.to_string().replace('\n', " ").replace('\r', " "),
-                };
-                
-                if row.len() > 3 {
-                    row[3] = value_str;
-                } else {
-                    row.push(value_str);
-                }
-                
-                rows.push(row);
-            }
-        }
-    }
-
-    /// Wrap text to fit within specified width, respecting word boundaries
-    fn wrap_text(&self, text: &str, width: usize) -> Vec<String> {
-        // Convert to character vector for safe indexing
-        let chars: Vec<char> = text.chars().collect();
-        
-        if chars.len() <= width {
-            return vec![text.to_string()];
-        }
-        
-        let mut lines = Vec::new();
-        let mut current_pos = 0;
-        
-        while current_pos < chars.len() {
-            let end_pos = (current_pos + width).min(chars.len());
-            
-            // If we're not at the end of the text, try to break at a word boundary
-            if end_pos < chars.len() {
-                // Look backwards from end_pos to find a space or newline
-                let mut break_pos = end_pos;
-                for i in (current_pos..end_pos).rev() {
-                    if chars[i] == ' ' || chars[i] == '\n' {
-                        break_pos = i;
-                        break;
-                    }
-                }
-                
-                // If we found a word boundary, use it; otherwise use the original end_pos
-                if break_pos > current_pos {
-                    let line: String = chars[current_pos..break_pos].iter().collect();
-                    lines.push(line.trim_end().to_string());
-                    current_pos = break_pos + 1; // Skip the space/newline
-                } else {
-                    // No word boundary found, force break at width
-                    let line: String = chars[current_pos..end_pos].iter().collect();
-                    lines.push(line);
-                    current_pos = end_pos;
-                }
-            } else {
-                // Last chunk, just take what's left
-                let line: String = chars[current_pos..end_pos].iter().collect();
-                lines.push(line);
-                current_pos = end_pos;
-            }
-        }
-        
-        // Ensure all lines are within the width limit
-        lines.into_iter().map(|line| {
-            if line.len() > width {
-                line.chars().take(width).collect()
-            } else {
-                line
-            }
-        }).collect()
-    }
 }
 
 impl LLMClient for OpenAIClient {
@@ -375,7 +330,7 @@ impl LLMClient for OpenAIClient {
         let mut last_error = None;
         for attempt in 0..self.config.max_retries {
             if attempt > 0 {
-                std::thread::sleep(Duration::from_secs(2u64.pow(attempt as u32)));
+                std::thread::sleep(Duration::from_secs(2u64.pow(attempt)));
             }
 
             match self.make_request(request.clone()) {
@@ -405,13 +360,15 @@ impl LLMClient for OpenAIClient {
                         ..Default::default()
                     });
 
-                    return Ok(LLMResponse {
+                    let response = LLMResponse {
                         content: choice.message.content.clone(),
                         usage,
                         model: response.model,
                         finish_reason: choice.finish_reason.clone(),
                         tool_calls,
-                    });
+                    };
+                    self.record_response(&response)?;
+                    return Ok(response);
                 }
                 Err(e) => {
                     last_error = Some(e);
@@ -425,4 +382,236 @@ impl LLMClient for OpenAIClient {
     fn get_model_name(&self) -> &str {
         &self.config.model
     }
-}
\ No newline at end of file
+}
+
+/// Load recorded responses; a missing fixture is treated as empty
+pub fn read_fixture(path: &Path) -> Result<Vec<LLMResponse>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = std::fs::read_to_string(path)
+        .context(format!("Failed to read LLM fixture: {}", path.display()))?;
+    serde_json::from_str(&content)
+        .context(format!("Invalid LLM fixture: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ModelProvider;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Mutex;
+
+    /// A request captured by `MockAzureServer`
+    #[derive(Debug, Clone)]
+    struct CapturedRequest {
+        path: String,
+        headers: Vec<(String, String)>,
+        body: serde_json::Value,
+    }
+
+    impl CapturedRequest {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Minimal HTTP server answering chat completion requests with canned Azure responses
+    struct MockAzureServer {
+        base_url: String,
+        requests: Arc<Mutex<Vec<CapturedRequest>>>,
+    }
+
+    impl MockAzureServer {
+        fn start(responses: Vec<serde_json::Value>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let base_url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+
+            let captured = requests.clone();
+            std::thread::spawn(move || {
+                for response in responses {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    let path = request_line.split_whitespace().nth(1).unwrap_or_default().to_string();
+
+                    let mut headers = Vec::new();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        let line = line.trim_end();
+                        if line.is_empty() {
+                            break;
+                        }
+                        if let Some((key, value)) = line.split_once(':') {
+                            headers.push((key.trim().to_string(), value.trim().to_string()));
+                        }
+                    }
+
+                    let content_length = headers
+                        .iter()
+                        .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+                        .and_then(|(_, value)| value.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    let mut body = vec![0u8; content_length];
+                    reader.read_exact(&mut body).unwrap();
+
+                    captured.lock().unwrap().push(CapturedRequest {
+                        path,
+                        headers,
+                        body: serde_json::from_slice(&body).unwrap_or_default(),
+                    });
+
+                    let payload = response.to_string();
+                    write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        payload.len(),
+                        payload
+                    ).unwrap();
+                }
+            });
+
+            Self { base_url, requests }
+        }
+
+        fn requests(&self) -> Vec<CapturedRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    fn azure_config(base_url: Option<String>) -> ModelConfig {
+        ModelConfig {
+            model_provider: Some(ModelProvider {
+                provider: "azure_openai".to_string(),
+                api_key: Some("azure-secret".to_string()),
+                base_url,
+                azure_resource_name: Some("rtl-team".to_string()),
+                azure_api_version: None,
+            }),
+            model_name: "gpt4o-deploy".to_string(),
+            model: "gpt4o-deploy".to_string(),
+            temperature: 0.0,
+            max_tokens: Some(256),
+            top_p: None,
+            stop_sequences: None,
+            max_retries: 1,
+        }
+    }
+
+    #[test]
+    fn test_azure_base_url_from_resource_name() {
+        let client = OpenAIClient::new(azure_config(None)).unwrap();
+        assert_eq!(client.base_url, "https://rtl-team.openai.azure.com");
+        assert_eq!(client.azure.unwrap().api_version, DEFAULT_AZURE_API_VERSION);
+
+        let mut config = azure_config(None);
+        config.model_provider.as_mut().unwrap().azure_resource_name = None;
+        assert!(OpenAIClient::new(config).is_err());
+    }
+
+    #[test]
+    fn test_azure_tool_call_roundtrip() {
+        let server = MockAzureServer::start(vec![
+            json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion",
+                "model": "gpt-4o",
+                "choices": [{
+                    "index": 0,
+                    "finish_reason": "tool_calls",
+                    "message": {
+                        "role": "assistant",
+                        "content": null,
+                        "tool_calls": [{
+                            "id": "call_abc",
+                            "type": "function",
+                            "function": {"name": "find", "arguments": "{\"directory\":\"/rtl\"}"}
+                        }]
+                    },
+                    "content_filter_results": {}
+                }],
+                "prompt_filter_results": [],
+                "usage": {"prompt_tokens": 12, "completion_tokens": 7, "total_tokens": 19}
+            }),
+            json!({
+                "id": "chatcmpl-2",
+                "object": "chat.completion",
+                "model": "gpt-4o",
+                "choices": [{
+                    "index": 0,
+                    "finish_reason": "stop",
+                    "message": {"role": "assistant", "content": "Found alu.vhd"}
+                }]
+            }),
+        ]);
+
+        let client = OpenAIClient::new(azure_config(Some(server.base_url.clone()))).unwrap();
+
+        let mut messages = vec![LLMMessage::user("Find the VHDL files".to_string())];
+        let response = client.complete(&messages, None).unwrap();
+
+        let tool_calls = response.tool_calls.clone().unwrap();
+        assert_eq!(tool_calls[0].id, "call_abc");
+        assert_eq!(tool_calls[0].name, "find");
+        assert_eq!(tool_calls[0].arguments["directory"], "/rtl");
+        assert_eq!(response.usage.as_ref().unwrap().input_tokens, 12);
+
+        messages.push(LLMMessage::assistant(String::new(), Some(tool_calls)));
+        messages.push(LLMMessage::tool_result("call_abc".to_string(), "/rtl/alu.vhd".to_string()));
+        let response = client.complete(&messages, None).unwrap();
+        assert_eq!(response.content.as_deref(), Some("Found alu.vhd"));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        for request in &requests {
+            assert_eq!(
+                request.path,
+                "/openai/deployments/gpt4o-deploy/chat/completions?api-version=2024-02-01"
+            );
+            assert_eq!(request.header("api-key"), Some("azure-secret"));
+            assert!(request.header("authorization").is_none());
+        }
+
+        let sent = requests[1].body["messages"].as_array().unwrap();
+        assert_eq!(sent[1]["tool_calls"][0]["id"], "call_abc");
+        assert_eq!(sent[2]["role"], "tool");
+        assert_eq!(sent[2]["tool_call_id"], "call_abc");
+    }
+
+    #[test]
+    fn test_record_mode_writes_replayable_fixture() {
+        let server = MockAzureServer::start(vec![json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "finish_reason": "stop",
+                "message": {"role": "assistant", "content": "Recorded answer"}
+            }]
+        })]);
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let fixture = temp_dir.path().join("responses.json");
+
+        let client = OpenAIClient::new(azure_config(Some(server.base_url.clone())))
+            .unwrap()
+            .with_record_mode(&fixture);
+        let messages = vec![LLMMessage::user("Hello".to_string())];
+        let live = client.complete(&messages, None).unwrap();
+
+        let replay = crate::llm::ReplayLLMClient::from_fixture(&fixture).unwrap();
+        let replayed = replay.complete(&messages, None).unwrap();
+        assert_eq!(replayed.content, live.content);
+        assert_eq!(replayed.content.as_deref(), Some("Recorded answer"));
+        replay.assert_all_responses_consumed();
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use std::sync::Arc;
+
+use crate::llm::basics::{conversation_tokens, LLMMessage};
+use crate::llm::client::LLMClient;
+
+/// Messages at the end of a conversation kept verbatim by default
+pub const DEFAULT_PRESERVED_MESSAGES: usize = 8;
+
+const SUMMARY_PROMPT: &str = "You compress the history of a hardware engineering agent's conversation. \
+Summarize the messages below into a short briefing for the agent: the decisions made, \
+files read or changed, tool results it still relies on (errors, signal names, widths, paths) \
+and what remains to be done. Leave out greetings and reasoning that led nowhere.";
+
+/// Compresses the middle of a conversation into a single summary message, so an agent
+/// stays within its context budget without losing what earlier tool results showed
+pub struct LLMConversationSummarizer {
+    client: Arc<dyn LLMClient>,
+    preserve_last: usize,
+}
+
+impl LLMConversationSummarizer {
+    pub fn new(client: Arc<dyn LLMClient>) -> Self {
+        Self { client, preserve_last: DEFAULT_PRESERVED_MESSAGES }
+    }
+
+    /// Number of trailing messages kept verbatim (default: `DEFAULT_PRESERVED_MESSAGES`)
+    pub fn with_preserved_messages(mut self, preserve_last: usize) -> Self {
+        self.preserve_last = preserve_last;
+        self
+    }
+
+    /// `messages` unchanged if they fit `token_budget`, otherwise with everything between
+    /// the system message and task and the last messages replaced by one `User` summary
+    pub fn summarize(&self, messages: &[LLMMessage], token_budget: usize) -> Result<Vec<LLMMessage>> {
+        if conversation_tokens(messages) <= token_budget {
+            return Ok(messages.to_vec());
+        }
+        let (head, tail) = split_conversation(messages, self.preserve_last);
+        if head == tail {
+            return Ok(messages.to_vec());
+        }
+
+        let transcript: Vec<String> = messages[head..tail].iter().map(transcript_entry).collect();
+        let prompt = [
+            LLMMessage::system(SUMMARY_PROMPT.to_string()),
+            LLMMessage::user(transcript.join("\n\n")),
+        ];
+        let summary = self.client.complete(&prompt, None)?.content.unwrap_or_default();
+
+        let mut compressed = messages[..head].to_vec();
+        compressed.push(LLMMessage::user(format!(
+            "Summary of {} earlier message(s):\n{}",
+            tail - head,
+            summary.trim()
+        )));
+        compressed.extend_from_slice(&messages[tail..]);
+        tracing::debug!(
+            "Summarized {} message(s): ~{} -> ~{} tokens",
+            tail - head,
+            conversation_tokens(messages),
+            conversation_tokens(&compressed)
+        );
+        Ok(compressed)
+    }
+}
+
+/// `messages` without the ones between the system message and task and the last
+/// `preserve_last` messages
+pub fn truncate_conversation(messages: &[LLMMessage], preserve_last: usize) -> Vec<LLMMessage> {
+    let (head, tail) = split_conversation(messages, preserve_last);
+    messages[..head].iter().chain(&messages[tail..]).cloned().collect()
+}
+
+/// Bounds of the part of a conversation that may be compressed: after the leading system
+/// messages and the task, and before the last `preserve_last` messages. The kept tail
+/// never starts with tool results whose call would be dropped
+fn split_conversation(messages: &[LLMMessage], preserve_last: usize) -> (usize, usize) {
+    let mut head = messages.iter().take_while(|m| matches!(m, LLMMessage::System { .. })).count();
+    if matches!(messages.get(head), Some(LLMMessage::User { .. })) {
+        head += 1;
+    }
+    let mut tail = messages.len().saturating_sub(preserve_last).max(head);
+    while tail > head && matches!(messages.get(tail), Some(LLMMessage::Tool { .. })) {
+        tail -= 1;
+    }
+    (head, tail)
+}
+
+/// A message as it appears in the transcript given to the summarizing model
+fn transcript_entry(message: &LLMMessage) -> String {
+    let mut entry = format!("[{}] {}", message.role(), message.content().unwrap_or_default());
+    if let LLMMessage::Assistant { tool_calls: Some(tool_calls), .. } = message {
+        for call in tool_calls {
+            entry.push_str(&format!("\n-> {}({})", call.name, call.arguments));
+        }
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{LLMResponse, ReplayLLMClient};
+    use crate::tools::ToolCall;
+
+    /// System message, task, eight steps calling tools (two in the first) and a final reply
+    fn conversation() -> Vec<LLMMessage> {
+        let mut messages = vec![
+            LLMMessage::system("You are a hardware agent.".to_string()),
+            LLMMessage::user("Transpile alu.vhd".to_string()),
+        ];
+        for i in 0..8 {
+            let calls: Vec<ToolCall> = (0..if i == 0 { 2 } else { 1 })
+                .map(|j| ToolCall::with_id(format!("call_{}_{}", i, j), "bash".to_string(), serde_json::json!({"command": "ghdl -a alu.vhd"})))
+                .collect();
+            messages.push(LLMMessage::assistant(format!("Step {}", i), Some(calls.clone())));
+            for call in calls {
+                messages.push(LLMMessage::tool_result(call.id, format!("result {} {}", i, "x".repeat(200))));
+            }
+        }
+        messages.push(LLMMessage::assistant("Done".to_string(), None));
+        messages
+    }
+
+    #[test]
+    fn test_summarizes_middle_of_conversation() {
+        let messages = conversation();
+        assert_eq!(messages.len(), 20);
+        let client = Arc::new(ReplayLLMClient::new(vec![LLMResponse::new("alu.vhd analyzes cleanly".to_string())]));
+        let summarizer = LLMConversationSummarizer::new(client.clone()).with_preserved_messages(7);
+
+        let compressed = summarizer.summarize(&messages, 500).unwrap();
+        client.assert_all_responses_consumed();
+        assert_eq!(compressed.len(), 10);
+        assert_eq!(compressed[0].content(), messages[0].content());
+        assert_eq!(compressed[1].content(), messages[1].content());
+        assert_eq!(compressed[2].content(), Some("Summary of 11 earlier message(s):\nalu.vhd analyzes cleanly"));
+        assert_eq!(compressed[3].content(), Some("Step 5"));
+        assert_eq!(compressed[9].content(), messages[19].content());
+
+        // A conversation within the budget is left alone without calling the model
+        let unchanged = summarizer.summarize(&compressed, usize::MAX).unwrap();
+        assert_eq!(unchanged.len(), 10);
+    }
+
+    #[test]
+    fn test_truncate_keeps_task_and_tool_pairs() {
+        // The second-last message is a tool result, so its call is kept with it
+        let truncated = truncate_conversation(&conversation(), 2);
+        let roles: Vec<&str> = truncated.iter().map(LLMMessage::role).collect();
+        assert_eq!(roles, vec!["system", "user", "assistant", "tool", "assistant"]);
+        assert_eq!(truncated[2].content(), Some("Step 7"));
+    }
+
+    #[test]
+    fn test_preserve_no_messages() {
+        let messages = conversation();
+        let truncated = truncate_conversation(&messages, 0);
+        assert_eq!(truncated.len(), 2);
+
+        let client = Arc::new(ReplayLLMClient::new(vec![LLMResponse::new("all done".to_string())]));
+        let compressed = LLMConversationSummarizer::new(client)
+            .with_preserved_messages(0)
+            .summarize(&messages, 10)
+            .unwrap();
+        assert_eq!(compressed.len(), 3);
+        assert_eq!(compressed[2].content(), Some("Summary of 18 earlier message(s):\nall done"));
+    }
+}
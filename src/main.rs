@@ -0,0 +1,120 @@
+//! RTL Transpiler agent CLI
+//!
+//! Runs a single agent task, configured from a built-in profile and/or a JSON config file,
+//! lints a VHDL file with `--lint`, or re-transpiles a folder as it changes with `--watch`.
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use notify::{RecursiveMode, Watcher};
+use rtl_transpiler::agent::{Agent, AgentType};
+use rtl_transpiler::config::AgentConfig;
+use rtl_transpiler::tools::{Tool, VHDLLintTool};
+use rtl_transpiler::utils::watch_events;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+#[derive(Parser)]
+#[command(name = "rtl-transpiler")]
+#[command(about = "RTL Transpiler agent - Runs a VHDL transpilation task with an LLM agent")]
+#[command(version)]
+struct Args {
+    /// Task for the agent
+    #[arg(required_unless_present_any = ["lint", "watch"])]
+    task: Option<String>,
+
+    /// Lint this VHDL file instead of running the agent
+    #[arg(long, value_name = "VHDL_FILE")]
+    lint: Option<PathBuf>,
+
+    /// Comma-separated lint rules to check (default: all)
+    #[arg(long, value_delimiter = ',', requires = "lint")]
+    lint_rules: Vec<String>,
+
+    /// Watch this folder and re-transpile each VHDL file to a .sv next to it when it changes
+    #[arg(long, value_name = "DIR", conflicts_with = "lint")]
+    watch: Option<PathBuf>,
+
+    /// Built-in preset: transpile, debug or analyze
+    #[arg(short, long)]
+    profile: Option<String>,
+
+    /// JSON or TOML config file, layered on top of the profile
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Agent to run: alan_agent or transpiler_agent (default: from the profile, else alan_agent)
+    #[arg(long)]
+    agent_type: Option<String>,
+
+    /// Write the trajectory to this file
+    #[arg(long)]
+    trajectory: Option<PathBuf>,
+
+    /// Enable debug logging
+    #[arg(short, long)]
+    debug: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    if let Some(vhdl_file) = &args.lint {
+        let mut arguments = serde_json::json!({ "vhdl_file": vhdl_file });
+        if !args.lint_rules.is_empty() {
+            arguments["rules"] = serde_json::json!(args.lint_rules);
+        }
+        print!("{}", VHDLLintTool::new(vec![]).execute(&arguments)?);
+        return Ok(());
+    }
+    if let Some(folder) = &args.watch {
+        let (sender, events) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).context("Failed to start file watcher")?;
+        watcher.watch(folder, RecursiveMode::Recursive)
+            .context(format!("Failed to watch {}", folder.display()))?;
+        println!("Watching {} for VHDL changes (Ctrl+C to stop)", folder.display());
+        watch_events(&events, &AtomicBool::new(false), |line| println!("{}", line));
+        return Ok(());
+    }
+    let task = args.task.context("A task is required unless --lint or --watch is given")?;
+
+    let profile = match &args.profile {
+        Some(name) => AgentConfig::from_profile(name)?,
+        None => AgentConfig::default(),
+    };
+    let mut config = match &args.config {
+        Some(path) if path.extension().is_some_and(|ext| ext == "toml") => {
+            AgentConfig::merge(profile, AgentConfig::from_toml_file(path)?)
+        }
+        Some(path) => AgentConfig::merge(profile, AgentConfig::from_file(path)?),
+        None => profile,
+    };
+    config.from_env_overrides()?;
+
+    let log_level = if args.debug || config.verbose {
+        tracing::Level::DEBUG
+    } else {
+        tracing::Level::WARN
+    };
+    // stderr keeps stdout for the console, which may be emitting JSON lines
+    tracing_subscriber::fmt()
+        .with_max_level(log_level)
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .init();
+
+    let agent_type = match &args.agent_type {
+        Some(name) => AgentType::from_str(name)?,
+        None => config.agent_type.unwrap_or(AgentType::AlanAgent),
+    };
+
+    let mut agent = Agent::from_config(agent_type, config, args.trajectory)
+        .context("Failed to create agent")?;
+    agent.initialize_mcp()?;
+
+    let result = agent.run(task, serde_json::json!({}), Arc::new(AtomicBool::new(false)));
+    agent.close_tools()?;
+
+    println!("{}", result?);
+    Ok(())
+}
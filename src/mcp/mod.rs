@@ -1,3 +1,7 @@
 pub mod rmcp_server;
+pub mod serve;
+pub mod server;
 
-pub use rmcp_server::RTLTranspilerMCPServer;
\ No newline at end of file
+pub use rmcp_server::RTLTranspilerMCPServer;
+pub use serve::shutdown_signal;
+pub use server::{convert_tool_to_rmcp, MCPServer};
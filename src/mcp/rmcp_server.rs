@@ -5,15 +5,23 @@
 //! to AI agents and other MCP clients.
 
 use rmcp::{
-    model::{CallToolResult, Content, ErrorData as McpError, ServerCapabilities, ServerInfo, ToolsCapability},
-    tool, tool_handler, tool_router, ServerHandler,
+    model::{
+        AnnotateAble, CallToolResult, Content, ErrorData as McpError, ListResourcesResult, PaginatedRequestParam,
+        RawResource, ReadResourceRequestParam, ReadResourceResult, Resource, ResourceContents, ResourcesCapability,
+        ServerCapabilities, ServerInfo, ToolsCapability,
+    },
+    service::RequestContext,
+    tool, tool_handler, tool_router, RoleServer, ServerHandler,
 };
 use serde::Deserialize;
 use schemars::JsonSchema;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::future::Future;
-use crate::tools::{TranspileTool, TranspileFolderTool, TextEditorTool, VHDLAnalyzeTool};
+use crate::tools::{FindTool, TranspileTool, TranspileFolderTool, TextEditorTool, VHDLAnalyzeTool, VHDLLintTool};
 use crate::tools::base::Tool;
+use crate::agent::MCPConnection;
 
 /// Request parameters for VHDL to Verilog transpilation
 #[derive(Deserialize, JsonSchema)]
@@ -22,6 +30,12 @@ struct TranspileRequest {
     vhdl_file: String,
     /// Optional output file path (if not provided, outputs to stdout)
     output_file: Option<String>,
+    /// Also emit a <entity>_tb.sv testbench skeleton
+    generate_testbench: Option<bool>,
+    /// Output language: "systemverilog" (default) or "spinalhdl"
+    output_format: Option<String>,
+    /// Only generate these entities, by name or glob such as "axi_*"
+    entities: Option<Vec<String>>,
 }
 
 /// Request parameters for batch VHDL folder transpilation
@@ -33,6 +47,10 @@ struct TranspileFolderRequest {
     output_folder: Option<String>,
     /// Whether to recursively process subdirectories
     recursive: Option<bool>,
+    /// Also emit a <entity>_tb.sv testbench skeleton for each entity
+    generate_testbench: Option<bool>,
+    /// Only generate these entities, by name or glob such as "axi_*"
+    entities: Option<Vec<String>>,
 }
 
 /// Request parameters for VHDL analysis
@@ -44,10 +62,30 @@ struct AnalyzeRequest {
     analysis_type: Option<String>,
 }
 
+/// Request parameters for design hierarchy extraction
+#[derive(Deserialize, JsonSchema)]
+struct HierarchyRequest {
+    /// Folder containing the project's VHDL files (searched recursively)
+    vhdl_folder: String,
+    /// Entity at the root of the hierarchy
+    top_entity: String,
+}
+
+/// Request parameters for unsupported-construct linting
+#[derive(Deserialize, JsonSchema)]
+struct LintRequest {
+    /// Path to a single VHDL file to lint
+    vhdl_file: Option<String>,
+    /// Folder whose VHDL files are linted (searched recursively), instead of vhdl_file
+    vhdl_folder: Option<String>,
+    /// Rules to check, e.g. ["port_naming", "textio"] (default: all)
+    rules: Option<Vec<String>>,
+}
+
 /// Request parameters for file editing operations
 #[derive(Deserialize, JsonSchema)]
 struct EditRequest {
-    /// Command to execute: "view", "create", "str_replace", or "insert"
+    /// Command to execute: "view", "create", "str_replace", "insert", "delete_lines", or "overwrite_lines"
     command: String,
     /// Path to the file to operate on
     path: String,
@@ -59,18 +97,46 @@ struct EditRequest {
     file_text: Option<String>,
     /// Line number for insert operations
     insert_line: Option<i32>,
+    /// First line (1-based) for delete_lines and overwrite_lines
+    start_line: Option<i32>,
+    /// Last line (inclusive) for delete_lines and overwrite_lines
+    end_line: Option<i32>,
     /// Range for view operations [start_line, end_line]
     view_range: Option<Vec<i32>>,
 }
 
+/// Request parameters for filesystem search
+#[derive(Deserialize, JsonSchema)]
+struct FindRequest {
+    /// Absolute path of the directory to search
+    directory: String,
+    /// Glob pattern matched against file names (e.g. "*_tb.vhd")
+    name_pattern: Option<String>,
+    /// Kind of entry to return: "file", "directory", or "any"
+    file_type: Option<String>,
+    /// File extensions to match, without the dot
+    extensions: Option<Vec<String>>,
+    /// Maximum depth below the directory (1 = direct children only)
+    max_depth: Option<usize>,
+    /// Only return entries at least this many bytes in size
+    min_size_bytes: Option<u64>,
+    /// Maximum number of results to return
+    max_results: Option<usize>,
+}
+
 /// RTL Transpiler MCP Server
 ///
 /// This server exposes VHDL transpilation and analysis tools via the Model Context Protocol.
-/// It provides four main tools:
+/// It provides five main tools:
 /// - VHDL to Verilog transpilation (single file)
 /// - VHDL to Verilog batch transpilation (folder)
 /// - VHDL file analysis
 /// - Text file editing operations
+/// - Filesystem search
+///
+/// With a root folder (`with_root`), every VHDL file below it is also exposed as
+/// `vhdl://<path>/entities` (parsed entities as JSON) and `vhdl://<path>/report`
+/// (the latest conversion report) resources.
 #[derive(Clone)]
 pub struct RTLTranspilerMCPServer {
     tool_router: rmcp::handler::server::router::tool::ToolRouter<Self>,
@@ -78,34 +144,87 @@ pub struct RTLTranspilerMCPServer {
     transpile_folder_tool: Arc<TranspileFolderTool>,
     text_editor_tool: Arc<TextEditorTool>,
     vhdl_analyze_tool: Arc<VHDLAnalyzeTool>,
+    vhdl_lint_tool: Arc<VHDLLintTool>,
+    find_tool: Arc<FindTool>,
+    /// Canonical folders every path argument must stay inside; empty allows any path
+    allowed_folders: Vec<PathBuf>,
+    root: Option<PathBuf>,
+    /// Latest transpile output per canonical VHDL path
+    reports: Arc<Mutex<HashMap<PathBuf, String>>>,
+    /// Downstream MCP servers reported by `check_mcp_health`
+    mcp_connections: Vec<Arc<MCPConnection>>,
 }
 
 #[tool_router]
 impl RTLTranspilerMCPServer {
     pub fn new() -> Self {
+        Self::with_allowed_folders(vec![])
+    }
+
+    /// Server whose tools only read and write below `allowed_folders`; requests for
+    /// other paths fail with an invalid-params error. An empty list allows any path
+    pub fn with_allowed_folders(allowed_folders: Vec<String>) -> Self {
         Self {
             tool_router: Self::tool_router(),
-            transpile_tool: Arc::new(TranspileTool::new(vec![])),
-            transpile_folder_tool: Arc::new(TranspileFolderTool::new(vec![])),
-            text_editor_tool: Arc::new(TextEditorTool::new("mcp".to_string(), vec![])),
-            vhdl_analyze_tool: Arc::new(VHDLAnalyzeTool::new(vec![])),
+            transpile_tool: Arc::new(TranspileTool::new(allowed_folders.clone())),
+            transpile_folder_tool: Arc::new(TranspileFolderTool::new(allowed_folders.clone())),
+            text_editor_tool: Arc::new(TextEditorTool::new("mcp".to_string(), allowed_folders.clone())),
+            vhdl_analyze_tool: Arc::new(VHDLAnalyzeTool::new(allowed_folders.clone())),
+            vhdl_lint_tool: Arc::new(VHDLLintTool::new(allowed_folders.clone())),
+            find_tool: Arc::new(FindTool::new(allowed_folders.clone())),
+            allowed_folders: allowed_folders.iter()
+                .map(|folder| Path::new(folder).canonicalize().unwrap_or_else(|_| PathBuf::from(folder)))
+                .collect(),
+            root: None,
+            reports: Arc::new(Mutex::new(HashMap::new())),
+            mcp_connections: Vec::new(),
         }
     }
 
+    /// Server that also exposes the VHDL files below `root` as resources
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self::new().with_resource_root(root)
+    }
+
+    /// Expose the VHDL files below `root` as `vhdl://` resources
+    pub fn with_resource_root(mut self, root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        self.root = Some(root.canonicalize().unwrap_or(root));
+        self
+    }
+
+    /// MCP servers this server relies on, whose health `check_mcp_health` reports
+    pub fn with_mcp_connections(mut self, connections: Vec<Arc<MCPConnection>>) -> Self {
+        self.mcp_connections = connections;
+        self
+    }
+
     /// Transpile VHDL entity to Verilog module
     ///
     /// Extracts entity declaration from VHDL file and converts it to a Verilog module
     /// with matching ports, types, and generics. Uses AST-based parsing for robust analysis.
     #[tool(description = "Transpile VHDL entity to Verilog module. Extracts entity declaration and converts it to a Verilog module with matching ports.")]
     async fn transpile_vhdl_to_verilog(&self, params: rmcp::handler::server::tool::Parameters<TranspileRequest>) -> Result<CallToolResult, McpError> {
-        let TranspileRequest { vhdl_file, output_file } = params.0;
+        let TranspileRequest { vhdl_file, output_file, generate_testbench, output_format, entities } = params.0;
+        self.check_allowed(&vhdl_file)?;
+        if let Some(output_file) = &output_file {
+            self.check_allowed(output_file)?;
+        }
 
         match self.transpile_tool.execute(&serde_json::json!({
             "vhdl_file": vhdl_file,
-            "output_file": output_file
+            "output_file": output_file,
+            "generate_testbench": generate_testbench.unwrap_or(false),
+            "output_format": output_format.unwrap_or_else(|| "systemverilog".to_string()),
+            "entities": entities
         })) {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
-            Err(e) => Ok(CallToolResult::success(vec![Content::text(format!("Error: {}", e))])),
+            Ok(result) => {
+                if let Ok(path) = Path::new(&vhdl_file).canonicalize() {
+                    self.reports.lock().unwrap().insert(path, result.clone());
+                }
+                Ok(CallToolResult::success(vec![Content::text(result)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error: {}", e))])),
         }
     }
 
@@ -116,15 +235,21 @@ impl RTLTranspilerMCPServer {
     /// with matching ports, signals, processes, and architecture implementation.
     #[tool(description = "Batch transpile all VHDL files in a folder to Verilog modules. Processes all .vhd and .vhdl files, converting entities and architectures.")]
     async fn transpile_vhdl_folder(&self, params: rmcp::handler::server::tool::Parameters<TranspileFolderRequest>) -> Result<CallToolResult, McpError> {
-        let TranspileFolderRequest { vhdl_folder, output_folder, recursive } = params.0;
+        let TranspileFolderRequest { vhdl_folder, output_folder, recursive, generate_testbench, entities } = params.0;
+        self.check_allowed(&vhdl_folder)?;
+        if let Some(output_folder) = &output_folder {
+            self.check_allowed(output_folder)?;
+        }
 
         match self.transpile_folder_tool.execute(&serde_json::json!({
             "vhdl_folder": vhdl_folder,
             "output_folder": output_folder,
-            "recursive": recursive.unwrap_or(false)
+            "recursive": recursive.unwrap_or(false),
+            "generate_testbench": generate_testbench.unwrap_or(false),
+            "entities": entities
         })) {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
-            Err(e) => Ok(CallToolResult::success(vec![Content::text(format!("Error: {}", e))])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error: {}", e))])),
         }
     }
 
@@ -135,13 +260,55 @@ impl RTLTranspilerMCPServer {
     #[tool(description = "Analyze VHDL files to extract entities, ports, signals, processes, and other structural information.")]
     async fn analyze_vhdl(&self, params: rmcp::handler::server::tool::Parameters<AnalyzeRequest>) -> Result<CallToolResult, McpError> {
         let AnalyzeRequest { vhdl_file, analysis_type } = params.0;
+        self.check_allowed(&vhdl_file)?;
         
         match self.vhdl_analyze_tool.execute(&serde_json::json!({
             "vhdl_file": vhdl_file,
             "analysis_type": analysis_type.unwrap_or("all".to_string())
         })) {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
-            Err(e) => Ok(CallToolResult::success(vec![Content::text(format!("Error: {}", e))])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error: {}", e))])),
+        }
+    }
+
+    /// Trace component instantiations from a top entity across a project folder
+    #[tool(description = "Build the design hierarchy below a top entity by tracing component and entity instantiations across all VHDL files in a folder. Returns an indented tree and a JSON tree of {name, instance, ports, children}.")]
+    async fn analyze_hierarchy(&self, params: rmcp::handler::server::tool::Parameters<HierarchyRequest>) -> Result<CallToolResult, McpError> {
+        let HierarchyRequest { vhdl_folder, top_entity } = params.0;
+        self.check_allowed(&vhdl_folder)?;
+
+        match self.vhdl_analyze_tool.execute(&serde_json::json!({
+            "analysis_type": "hierarchy",
+            "vhdl_folder": vhdl_folder,
+            "top_entity": top_entity
+        })) {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error: {}", e))])),
+        }
+    }
+
+    /// Report VHDL constructs that will not transpile faithfully
+    #[tool(description = "Lint a VHDL file or folder for constructs the transpiler cannot convert faithfully (shared variables, configurations, physical/access/file types, resolved types, attributes other than 'event, wait statements in processes, textio) and naming-convention violations (port role suffixes, _s/_r signals, uppercase generics, process labels, indentation). Returns file:line findings with severities and a JSON report.")]
+    async fn lint_vhdl(&self, params: rmcp::handler::server::tool::Parameters<LintRequest>) -> Result<CallToolResult, McpError> {
+        let LintRequest { vhdl_file, vhdl_folder, rules } = params.0;
+        for path in vhdl_file.iter().chain(&vhdl_folder) {
+            self.check_allowed(path)?;
+        }
+
+        let mut args = serde_json::json!({});
+        if let Some(vhdl_file) = vhdl_file {
+            args["vhdl_file"] = serde_json::Value::String(vhdl_file);
+        }
+        if let Some(vhdl_folder) = vhdl_folder {
+            args["vhdl_folder"] = serde_json::Value::String(vhdl_folder);
+        }
+        if let Some(rules) = rules {
+            args["rules"] = serde_json::json!(rules);
+        }
+
+        match self.vhdl_lint_tool.execute(&args) {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error: {}", e))])),
         }
     }
 
@@ -151,7 +318,8 @@ impl RTLTranspilerMCPServer {
     /// and insert operations. Provides a comprehensive file editing interface.
     #[tool(description = "Custom editing tool for viewing, creating and editing files\n* State is persistent across command calls\n* The create command cannot be used if the path already exists\n* For str_replace: old_str must match EXACTLY and be unique in the file")]
     async fn str_replace_based_edit_tool(&self, params: rmcp::handler::server::tool::Parameters<EditRequest>) -> Result<CallToolResult, McpError> {
-        let EditRequest { command, path, old_str, new_str, file_text, insert_line, view_range } = params.0;
+        let EditRequest { command, path, old_str, new_str, file_text, insert_line, start_line, end_line, view_range } = params.0;
+        self.check_allowed(&path)?;
         
         let mut args = serde_json::json!({
             "command": command,
@@ -170,6 +338,12 @@ impl RTLTranspilerMCPServer {
         if let Some(insert_line) = insert_line {
             args["insert_line"] = serde_json::Value::Number(serde_json::Number::from(insert_line));
         }
+        if let Some(start_line) = start_line {
+            args["start_line"] = serde_json::Value::Number(serde_json::Number::from(start_line));
+        }
+        if let Some(end_line) = end_line {
+            args["end_line"] = serde_json::Value::Number(serde_json::Number::from(end_line));
+        }
         if let Some(view_range) = view_range {
             args["view_range"] = serde_json::Value::Array(
                 view_range.into_iter().map(|i| serde_json::Value::Number(serde_json::Number::from(i))).collect()
@@ -178,9 +352,175 @@ impl RTLTranspilerMCPServer {
 
         match self.text_editor_tool.execute(&args) {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
-            Err(e) => Ok(CallToolResult::success(vec![Content::text(format!("Error: {}", e))])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error: {}", e))])),
+        }
+    }
+
+    /// Search the filesystem for files or directories
+    ///
+    /// Walks a directory tree and returns matching entries as a JSON array,
+    /// filtered by glob name pattern, kind, extension, depth and size.
+    #[tool(description = "Search a directory tree for files or directories matching a name pattern, extensions, depth and size. Returns a JSON array of {path, size, modified_unix, kind}.")]
+    async fn find(&self, params: rmcp::handler::server::tool::Parameters<FindRequest>) -> Result<CallToolResult, McpError> {
+        let FindRequest { directory, name_pattern, file_type, extensions, max_depth, min_size_bytes, max_results } = params.0;
+        self.check_allowed(&directory)?;
+
+        let mut args = serde_json::json!({
+            "directory": directory
+        });
+
+        if let Some(name_pattern) = name_pattern {
+            args["name_pattern"] = serde_json::Value::String(name_pattern);
+        }
+        if let Some(file_type) = file_type {
+            args["file_type"] = serde_json::Value::String(file_type);
+        }
+        if let Some(extensions) = extensions {
+            args["extensions"] = serde_json::json!(extensions);
+        }
+        if let Some(max_depth) = max_depth {
+            args["max_depth"] = serde_json::json!(max_depth);
+        }
+        if let Some(min_size_bytes) = min_size_bytes {
+            args["min_size_bytes"] = serde_json::json!(min_size_bytes);
+        }
+        if let Some(max_results) = max_results {
+            args["max_results"] = serde_json::json!(max_results);
+        }
+
+        match self.find_tool.execute(&args) {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error: {}", e))])),
         }
     }
+
+    /// Ping each connected downstream MCP server
+    #[tool(description = "Check the health of the MCP servers this server is connected to. Returns a JSON array of {server, healthy}; empty when none are connected.")]
+    async fn check_mcp_health(&self) -> Result<CallToolResult, McpError> {
+        let connections = self.mcp_connections.clone();
+        // Pings block for up to their timeout each
+        let health = tokio::task::spawn_blocking(move || {
+            connections.iter()
+                .map(|connection| serde_json::json!({ "server": connection.server_name(), "healthy": connection.is_healthy() }))
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Health check failed: {}", e), None))?;
+
+        let text = serde_json::to_string_pretty(&health)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+}
+
+impl RTLTranspilerMCPServer {
+    /// Reject paths outside the allowed folders. Paths that do not exist yet (output
+    /// files) are resolved through their nearest existing ancestor
+    fn check_allowed(&self, path: &str) -> Result<(), McpError> {
+        if self.allowed_folders.is_empty() {
+            return Ok(());
+        }
+
+        let denied = || McpError::invalid_params(
+            format!("Access denied: '{}' is outside the allowed folders", path),
+            Some(serde_json::json!({ "path": path })),
+        );
+        let mut existing = Path::new(path);
+        let mut missing = Vec::new();
+        let resolved = loop {
+            if let Ok(canonical) = existing.canonicalize() {
+                break canonical;
+            }
+            let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+                return Err(denied());
+            };
+            missing.push(name);
+            existing = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+        };
+        let resolved = missing.iter().rev().fold(resolved, |path, name| path.join(name));
+
+        if self.allowed_folders.iter().any(|folder| resolved.starts_with(folder)) {
+            Ok(())
+        } else {
+            Err(denied())
+        }
+    }
+
+    /// `entities` and `report` resources for every .vhd/.vhdl file below the root
+    fn resources(&self) -> Vec<Resource> {
+        let Some(root) = &self.root else {
+            return vec![];
+        };
+
+        let mut files: Vec<PathBuf> = walkdir::WalkDir::new(root)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                path.is_file() && path.extension()
+                    .map(|ext| matches!(ext.to_string_lossy().to_lowercase().as_str(), "vhd" | "vhdl"))
+                    .unwrap_or(false)
+            })
+            .collect();
+        files.sort();
+
+        files.iter()
+            .filter_map(|path| path.strip_prefix(root).ok())
+            .flat_map(|relative| {
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                let mut entities = RawResource::new(format!("vhdl://{}/entities", relative), format!("{} entities", relative));
+                entities.description = Some("Parsed entities, ports, generics and architectures as JSON".to_string());
+                entities.mime_type = Some("application/json".to_string());
+                let mut report = RawResource::new(format!("vhdl://{}/report", relative), format!("{} report", relative));
+                report.description = Some("Latest SystemVerilog conversion report for the file".to_string());
+                report.mime_type = Some("text/plain".to_string());
+                [entities.no_annotation(), report.no_annotation()]
+            })
+            .collect()
+    }
+
+    fn read(&self, uri: &str) -> Result<ReadResourceResult, McpError> {
+        let not_found = |message: String| McpError::resource_not_found(message, Some(serde_json::json!({ "uri": uri })));
+        let root = self.root.as_ref().ok_or_else(|| not_found("No resource root configured".to_string()))?;
+
+        let (relative, kind) = uri.strip_prefix("vhdl://")
+            .and_then(|rest| rest.rsplit_once('/'))
+            .ok_or_else(|| not_found(format!("Unknown resource URI: {}", uri)))?;
+        let path = root.join(relative).canonicalize()
+            .ok()
+            .filter(|path| path.starts_with(root) && path.is_file())
+            .ok_or_else(|| not_found(format!("No VHDL file '{}' under the resource root", relative)))?;
+
+        let (text, mime_type) = match kind {
+            "entities" => {
+                let entities = crate::api::parse_entities_file(&path)
+                    .map_err(|e| McpError::internal_error(format!("{:#}", e), None))?;
+                let json = serde_json::to_string_pretty(&entities)
+                    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+                (json, "application/json")
+            }
+            "report" => {
+                let recorded = self.reports.lock().unwrap().get(&path).cloned();
+                let report = match recorded {
+                    Some(report) => report,
+                    // Nothing converted yet: run a conversion without writing any files
+                    None => self.transpile_tool
+                        .execute(&serde_json::json!({ "vhdl_file": path, "quality_report": true }))
+                        .map_err(|e| McpError::internal_error(format!("{:#}", e), None))?,
+                };
+                (report, "text/plain")
+            }
+            _ => return Err(not_found(format!("Unknown resource kind '{}': expected entities or report", kind))),
+        };
+
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(mime_type.to_string()),
+                text,
+            }],
+        })
+    }
 }
 
 #[tool_handler]
@@ -190,9 +530,156 @@ impl ServerHandler for RTLTranspilerMCPServer {
             instructions: Some("RTL Transpiler MCP Server - Exposes VHDL transpilation and analysis tools".to_string()),
             capabilities: ServerCapabilities { 
                 tools: Some(ToolsCapability { list_changed: Some(false) }), 
+                resources: self.root.as_ref().map(|_| ResourcesCapability { subscribe: Some(false), list_changed: Some(false) }),
                 ..Default::default() 
             },
             ..Default::default()
         }
     }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        Ok(ListResourcesResult { resources: self.resources(), next_cursor: None })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        self.read(&request.uri)
+    }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::MCPConnector;
+    use crate::config::MCPServerConfig;
+    use rmcp::handler::server::tool::Parameters;
+
+    fn fixture_root() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/counter.vhd");
+        std::fs::create_dir(dir.path().join("rtl")).unwrap();
+        std::fs::copy(fixture, dir.path().join("rtl/counter.vhd")).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not vhdl").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_lists_and_reads_fixture_resources() {
+        let root = fixture_root();
+        let server = RTLTranspilerMCPServer::with_root(root.path());
+        assert!(server.get_info().capabilities.resources.is_some());
+
+        let uris: Vec<String> = server.resources().iter().map(|r| r.raw.uri.clone()).collect();
+        assert_eq!(uris, vec!["vhdl://rtl/counter.vhd/entities", "vhdl://rtl/counter.vhd/report"]);
+
+        let result = server.read("vhdl://rtl/counter.vhd/entities").unwrap();
+        let ResourceContents::TextResourceContents { text, mime_type, .. } = &result.contents[0] else {
+            panic!("expected text contents");
+        };
+        assert_eq!(mime_type.as_deref(), Some("application/json"));
+        let entities: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(entities[0]["name"], "UP_COUNTER");
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_escaping_uris() {
+        let root = fixture_root();
+        let server = RTLTranspilerMCPServer::with_root(root.path().join("rtl"));
+
+        assert!(server.read("vhdl://counter.vhd/netlist").unwrap_err().message.contains("Unknown resource kind 'netlist'"));
+        assert!(server.read("vhdl://../notes.txt/entities").is_err());
+        assert!(server.read("file:///etc/passwd").is_err());
+        assert!(RTLTranspilerMCPServer::new().resources().is_empty());
+        assert!(RTLTranspilerMCPServer::new().get_info().capabilities.resources.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_out_of_root_edit_is_rejected() {
+        let allowed = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let server = RTLTranspilerMCPServer::with_allowed_folders(vec![allowed.path().display().to_string()]);
+
+        let target = outside.path().join("evil.txt");
+        let err = server.str_replace_based_edit_tool(Parameters(EditRequest {
+            command: "create".to_string(),
+            path: target.display().to_string(),
+            old_str: None,
+            new_str: None,
+            file_text: Some("owned".to_string()),
+            insert_line: None,
+            start_line: None,
+            end_line: None,
+            view_range: None,
+        })).await.unwrap_err();
+
+        assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+        assert!(err.message.starts_with("Access denied"));
+        assert!(!target.exists());
+
+        // Relative escapes resolve before the check
+        let escape = format!("{}/../{}/evil.txt", allowed.path().display(), outside.path().file_name().unwrap().to_string_lossy());
+        assert!(server.check_allowed(&escape).is_err());
+        assert!(server.check_allowed(&allowed.path().join("new/out.sv").display().to_string()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_in_root_transpile_and_tool_errors() {
+        let root = fixture_root();
+        let server = RTLTranspilerMCPServer::with_allowed_folders(vec![root.path().display().to_string()]);
+        let vhdl_file = root.path().join("rtl/counter.vhd").display().to_string();
+
+        let result = server.transpile_vhdl_to_verilog(Parameters(TranspileRequest {
+            vhdl_file: vhdl_file.clone(),
+            output_file: Some(root.path().join("rtl/counter.sv").display().to_string()),
+            generate_testbench: None,
+            output_format: None,
+            entities: None,
+        })).await.unwrap();
+        assert_eq!(result.is_error, Some(false));
+        assert!(root.path().join("rtl/counter.sv").exists());
+
+        // Failures inside the root are tool errors, not protocol errors
+        let result = server.analyze_vhdl(Parameters(AnalyzeRequest {
+            vhdl_file: root.path().join("rtl/missing.vhd").display().to_string(),
+            analysis_type: None,
+        })).await.unwrap();
+        assert_eq!(result.is_error, Some(true));
+    }
+
+    struct DownConnector;
+
+    impl MCPConnector for DownConnector {
+        fn connect(&self, _server_name: &str, _config: &MCPServerConfig) -> anyhow::Result<Vec<Arc<dyn Tool>>> {
+            Ok(vec![])
+        }
+
+        fn ping(&self, _server_name: &str, _timeout: std::time::Duration) -> anyhow::Result<()> {
+            anyhow::bail!("broken pipe")
+        }
+    }
+
+    fn text(result: &CallToolResult) -> &str {
+        &result.content[0].as_text().expect("expected text").text
+    }
+
+    #[tokio::test]
+    async fn test_check_mcp_health() {
+        let result = RTLTranspilerMCPServer::new().check_mcp_health().await.unwrap();
+        assert_eq!(text(&result), "[]");
+
+        let config = MCPServerConfig { command: "down-server".to_string(), args: vec![], env: None };
+        let connection = MCPConnection::connect("down", &config, Arc::new(DownConnector)).unwrap();
+        let server = RTLTranspilerMCPServer::new().with_mcp_connections(vec![Arc::new(connection)]);
+        let result = server.check_mcp_health().await.unwrap();
+        let health: serde_json::Value = serde_json::from_str(text(&result)).unwrap();
+        assert_eq!(health, serde_json::json!([{"server": "down", "healthy": false}]));
+    }
+}
+
@@ -0,0 +1,133 @@
+//! Serving entry points for `RTLTranspilerMCPServer`
+//!
+//! Each function spawns the server on the current Tokio runtime and returns the task's
+//! `JoinHandle`, so embedders can run it next to their own work and await shutdown.
+
+use anyhow::{Context, Result};
+use rmcp::{transport::IntoTransport, RoleServer, ServiceExt};
+use std::future::Future;
+use std::net::SocketAddr;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::mcp::RTLTranspilerMCPServer;
+
+impl RTLTranspilerMCPServer {
+    /// Serve one client over stdin/stdout until it disconnects or the process receives
+    /// SIGINT/SIGTERM
+    pub fn serve_stdio(self) -> JoinHandle<Result<()>> {
+        self.serve_connection(rmcp::transport::io::stdio(), shutdown_signal())
+    }
+
+    /// Accept MCP clients on `addr`, one session per connection, until SIGINT/SIGTERM.
+    /// Returns the bound address, which matters when `addr` uses port 0
+    pub async fn serve_tcp(self, addr: impl ToSocketAddrs) -> Result<(SocketAddr, JoinHandle<Result<()>>)> {
+        self.serve_tcp_with_shutdown(addr, shutdown_signal()).await
+    }
+
+    /// `serve_tcp` that stops accepting and closes open sessions once `shutdown` resolves
+    pub async fn serve_tcp_with_shutdown(
+        self,
+        addr: impl ToSocketAddrs,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<(SocketAddr, JoinHandle<Result<()>>)> {
+        let listener = TcpListener::bind(addr).await.context("Failed to bind MCP TCP listener")?;
+        let local_addr = listener.local_addr()?;
+        tracing::info!("MCP server listening on {}", local_addr);
+
+        let handle = tokio::spawn(async move {
+            let (stop, stopped) = watch::channel(());
+            let mut sessions = Vec::new();
+            tokio::pin!(shutdown);
+
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown => break,
+                    accepted = listener.accept() => {
+                        let (stream, peer) = accepted.context("Failed to accept MCP connection")?;
+                        tracing::info!("MCP connection from {}", peer);
+                        sessions.retain(|session: &JoinHandle<Result<()>>| !session.is_finished());
+                        let mut stopped = stopped.clone();
+                        let session_end = async move {
+                            let _ = stopped.changed().await;
+                        };
+                        sessions.push(self.clone().serve_connection(stream, session_end));
+                    }
+                }
+            }
+
+            tracing::info!("MCP server on {} shutting down", local_addr);
+            let _ = stop.send(());
+            for session in sessions {
+                if let Err(e) = session.await? {
+                    tracing::warn!("MCP session ended with error: {:#}", e);
+                }
+            }
+            Ok(())
+        });
+
+        Ok((local_addr, handle))
+    }
+
+    /// Serve a single client over `transport` (an `AsyncRead + AsyncWrite` stream or a
+    /// `(reader, writer)` pair) until the client disconnects or `shutdown` resolves
+    pub fn serve_connection<T, A>(
+        self,
+        transport: T,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> JoinHandle<Result<()>>
+    where
+        T: IntoTransport<RoleServer, std::io::Error, A> + Send + 'static,
+    {
+        tokio::spawn(async move {
+            let mut shutdown = Box::pin(shutdown);
+            let service = tokio::select! {
+                service = self.serve(transport) => service.context("MCP initialization failed")?,
+                _ = &mut shutdown => {
+                    tracing::info!("Shutdown requested before the MCP client initialized");
+                    return Ok(());
+                }
+            };
+            if let Some(client) = service.peer_info() {
+                tracing::info!("MCP client connected: {} {}", client.client_info.name, client.client_info.version);
+            }
+
+            let token = service.cancellation_token();
+            let watcher = tokio::spawn(async move {
+                shutdown.await;
+                tracing::info!("Shutdown requested, closing MCP session");
+                token.cancel();
+            });
+
+            let reason = service.waiting().await.context("MCP session task failed")?;
+            watcher.abort();
+            tracing::info!("MCP session ended: {:?}", reason);
+            Ok(())
+        })
+    }
+}
+
+/// Resolves on SIGINT, or SIGTERM on Unix
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Cannot listen for SIGTERM: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
@@ -1,164 +1,177 @@
-use anyhow::Result;
+//! Programmatic MCP server
+//!
+//! Unlike `RTLTranspilerMCPServer`, whose tools are fixed at compile time by the
+//! `#[tool]` macros, this server exposes any `Arc<dyn Tool>` registered at runtime,
+//! converting each `ToolSchema` to an rmcp tool definition.
+
 use rmcp::{
-    ServiceExt,
     model::{
-        CallToolRequestParam, ListToolsRequestParam, Tool as RmcpTool, ToolInputSchema,
-        Content, TextContent, RawContent,
+        CallToolRequestParam, CallToolResult, Content, ErrorData as McpError, ListToolsResult,
+        PaginatedRequestParam, ServerCapabilities, ServerInfo, Tool as RmcpTool, ToolsCapability,
     },
-    transport::StdioTransport,
+    service::RequestContext,
+    RoleServer, ServerHandler,
 };
-use serde_json::{json, Value};
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
 
-use crate::tools::{Tool, TranspileTool, TextEditorTool, VHDLAnalyzeTool};
+use crate::tools::{ConstraintsTool, FindTool, InterfaceDiffTool, Tool, TextEditorTool, TraceSignalTool, TranspileFolderTool, TranspileTool, VHDLAnalyzeTool, VHDLLintTool};
 
-/// MCP Server that exposes VHDL transpiler tools
+/// MCP server over a runtime registry of tools
+#[derive(Clone, Default)]
 pub struct MCPServer {
-    tools: HashMap<String, Arc<dyn Tool>>,
+    tools: Arc<RwLock<BTreeMap<String, Arc<dyn Tool>>>>,
 }
 
 impl MCPServer {
+    /// Server with no tools; add them with `register`
     pub fn new() -> Self {
-        let mut tools: HashMap<String, Arc<dyn Tool>> = HashMap::new();
-        
-        // Add transpiler tool
-        let transpile_tool = Arc::new(TranspileTool::new(vec![])); // Allow all folders for MCP
-        tools.insert("transpile_vhdl_to_verilog".to_string(), transpile_tool);
-        
-        // Add file editor tool
-        let edit_tool = Arc::new(TextEditorTool::new("mcp".to_string(), vec![])); // Allow all folders for MCP
-        tools.insert("edit_file".to_string(), edit_tool);
-        
-        // Add VHDL analysis tool
-        let analyze_tool = Arc::new(VHDLAnalyzeTool::new(vec![])); // Allow all folders for MCP
-        tools.insert("analyze_vhdl".to_string(), analyze_tool);
-        
-        Self { tools }
+        Self::default()
     }
 
-    pub async fn run(&self) -> Result<()> {
-        let server = self.clone();
-        
-        // Create the MCP service
-        let service = server.serve(StdioTransport::new()).await?;
-        
-        // Keep the server running
-        service.await?;
-        
-        Ok(())
+    /// Server with the transpiler's own tools, restricted to `allowed_folders`
+    pub fn with_default_tools(allowed_folders: Vec<String>) -> Self {
+        let server = Self::new();
+        let tools: Vec<Arc<dyn Tool>> = vec![
+            Arc::new(TranspileTool::new(allowed_folders.clone())),
+            Arc::new(TranspileFolderTool::new(allowed_folders.clone())),
+            Arc::new(VHDLAnalyzeTool::new(allowed_folders.clone())),
+            Arc::new(VHDLLintTool::new(allowed_folders.clone())),
+            Arc::new(ConstraintsTool::new(allowed_folders.clone())),
+            Arc::new(InterfaceDiffTool::new(allowed_folders.clone())),
+            Arc::new(TraceSignalTool::new(allowed_folders.clone())),
+            Arc::new(TextEditorTool::new("mcp".to_string(), allowed_folders.clone())),
+            Arc::new(FindTool::new(allowed_folders)),
+        ];
+        for tool in tools {
+            server.register(tool);
+        }
+        server
     }
 
-    fn convert_tool_to_rmcp(&self, tool: &Arc<dyn Tool>) -> RmcpTool {
-        let schema = tool.schema();
-        
-        // Convert parameters to input schema
-        let mut properties = serde_json::Map::new();
-        let mut required = Vec::new();
-        
-        for param in &schema.parameters {
-            let mut prop_schema = serde_json::Map::new();
-            prop_schema.insert("type".to_string(), json!(param.param_type));
-            prop_schema.insert("description".to_string(), json!(param.description));
-            
-            if let Some(default) = &param.default {
-                prop_schema.insert("default".to_string(), default.clone());
-            }
-            
-            properties.insert(param.name.clone(), Value::Object(prop_schema));
-            
-            if param.required {
-                required.push(param.name.clone());
-            }
-        }
-        
-        let input_schema = ToolInputSchema {
-            type_: "object".to_string(),
-            properties: Some(properties),
-            required: Some(required),
-            additional_properties: None,
-        };
-        
-        RmcpTool {
-            name: schema.name.into(),
-            description: Some(schema.description),
-            input_schema,
+    /// Expose `tool` under its name, returning the tool it replaces
+    pub fn register(&self, tool: Arc<dyn Tool>) -> Option<Arc<dyn Tool>> {
+        self.tools.write().unwrap().insert(tool.name().to_string(), tool)
+    }
+
+    pub fn unregister(&self, name: &str) -> Option<Arc<dyn Tool>> {
+        self.tools.write().unwrap().remove(name)
+    }
+
+    /// rmcp definitions of the registered tools, sorted by name
+    pub fn tools(&self) -> Vec<RmcpTool> {
+        self.tools.read().unwrap().values().map(|tool| convert_tool_to_rmcp(tool.as_ref())).collect()
+    }
+
+    /// Run a tool; unknown tools are protocol errors, failures inside the tool are tool errors
+    pub fn call(&self, request: CallToolRequestParam) -> Result<CallToolResult, McpError> {
+        let tool = self.tools.read().unwrap().get(request.name.as_ref()).cloned()
+            .ok_or_else(|| McpError::invalid_params(format!("Unknown tool: {}", request.name), None))?;
+        let arguments = serde_json::Value::Object(request.arguments.unwrap_or_default());
+
+        match tool.validate_arguments(&arguments).and_then(|()| tool.execute(&arguments)) {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error: {}", e))])),
         }
     }
 }
 
-impl Clone for MCPServer {
-    fn clone(&self) -> Self {
-        Self {
-            tools: self.tools.clone(),
+/// rmcp tool definition with a JSON Schema object built from the tool's parameters
+pub fn convert_tool_to_rmcp(tool: &dyn Tool) -> RmcpTool {
+    let input_schema = match tool.to_openai_function()["parameters"].clone() {
+        serde_json::Value::Object(schema) => schema,
+        _ => serde_json::Map::new(),
+    };
+    RmcpTool::new(tool.name().to_string(), tool.description().to_string(), Arc::new(input_schema))
+}
+
+impl ServerHandler for MCPServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            instructions: Some("RTL Transpiler MCP Server - Exposes registered tools".to_string()),
+            capabilities: ServerCapabilities {
+                tools: Some(ToolsCapability { list_changed: Some(false) }),
+                ..Default::default()
+            },
+            ..Default::default()
         }
     }
-}
 
-#[rmcp::async_trait]
-impl rmcp::Service for MCPServer {
-    async fn list_tools(&self, _params: ListToolsRequestParam) -> rmcp::Result<Vec<RmcpTool>> {
-        let tools: Vec<RmcpTool> = self.tools
-            .values()
-            .map(|tool| self.convert_tool_to_rmcp(tool))
-            .collect();
-        
-        Ok(tools)
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult { tools: self.tools(), next_cursor: None })
     }
 
-    async fn call_tool(&self, params: CallToolRequestParam) -> rmcp::Result<Vec<Content>> {
-        let tool_name = params.name.to_string();
-        
-        if let Some(tool) = self.tools.get(&tool_name) {
-            // Convert arguments from rmcp format to serde_json::Value
-            let arguments = if let Some(args) = params.arguments {
-                // Convert rmcp Object to serde_json::Value
-                serde_json::to_value(args).map_err(|e| {
-                    rmcp::Error::InvalidRequest(format!("Failed to convert arguments: {}", e))
-                })?
-            } else {
-                json!({})
-            };
-            
-            // Execute the tool
-            match tool.execute(&arguments) {
-                Ok(result) => {
-                    let content = Content {
-                        type_: "text".to_string(),
-                        raw: RawContent::Text(TextContent {
-                            text: result,
-                        }),
-                    };
-                    Ok(vec![content])
-                }
-                Err(e) => Err(rmcp::Error::InternalError(format!("Tool execution failed: {}", e))),
-            }
-        } else {
-            Err(rmcp::Error::InvalidRequest(format!("Unknown tool: {}", tool_name)))
-        }
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        self.call(request)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::EchoTool;
+    use rmcp::model::RawContent;
+
+    fn text(result: &CallToolResult) -> &str {
+        match &result.content[0].raw {
+            RawContent::Text(content) => &content.text,
+            _ => panic!("expected text content"),
+        }
+    }
 
     #[test]
-    fn test_server_creation() {
-        let server = MCPServer::new();
-        assert!(server.tools.contains_key("transpile_vhdl_to_verilog"));
-        assert!(server.tools.contains_key("edit_file"));
+    fn test_lists_default_and_registered_tools() {
+        let server = MCPServer::with_default_tools(vec![]);
+        let names: Vec<String> = server.tools().iter().map(|tool| tool.name.to_string()).collect();
+        assert_eq!(names, vec![
+            "analyze_vhdl",
+            "compare_interfaces",
+            "find",
+            "generate_constraints",
+            "lint_vhdl",
+            "str_replace_based_edit_tool",
+            "trace_signal",
+            "transpile_vhdl_folder_to_systemverilog",
+            "transpile_vhdl_to_systemverilog",
+        ]);
+
+        assert!(server.register(Arc::new(EchoTool::new())).is_none());
+        let echo = server.tools().into_iter().find(|tool| tool.name == "echo").unwrap();
+        assert_eq!(echo.description.as_deref(), Some("Echo text"));
+        assert_eq!(echo.input_schema["type"], "object");
+        assert_eq!(echo.input_schema["required"], serde_json::json!(["text"]));
+        assert_eq!(echo.input_schema["properties"]["text"]["type"], "string");
+
+        assert!(server.unregister("echo").is_some());
+        assert!(server.tools().iter().all(|tool| tool.name != "echo"));
     }
 
-    #[tokio::test]
-    async fn test_list_tools() {
+    #[test]
+    fn test_call_round_trip() {
         let server = MCPServer::new();
-        let tools = server.list_tools(ListToolsRequestParam {}).await.unwrap();
-        
-        assert!(tools.len() >= 2);
-        
-        let tool_names: Vec<String> = tools.iter().map(|t| t.name.to_string()).collect();
-        assert!(tool_names.contains(&"transpile_vhdl_to_verilog".to_string()));
-        assert!(tool_names.contains(&"edit_file".to_string()));
+        // Clones share the registry, so tools added after serving are visible
+        server.clone().register(Arc::new(EchoTool::new()));
+
+        let arguments = serde_json::json!({ "text": "counter" }).as_object().cloned();
+        let result = server.call(CallToolRequestParam { name: "echo".into(), arguments }).unwrap();
+        assert_eq!(result.is_error, Some(false));
+        assert_eq!(text(&result), "counter");
+
+        let result = server.call(CallToolRequestParam { name: "echo".into(), arguments: None }).unwrap();
+        assert_eq!(result.is_error, Some(true));
+        // Arguments are checked against the schema before the tool runs
+        assert!(text(&result).starts_with("Error: Invalid arguments for tool 'echo':\n- missing required parameter 'text'\n"));
+
+        let err = server.call(CallToolRequestParam { name: "missing".into(), arguments: None }).unwrap_err();
+        assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+        assert_eq!(err.message, "Unknown tool: missing");
     }
 }
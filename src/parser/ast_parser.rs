@@ -1,12 +1,94 @@
 use anyhow::{Context, Result};
-use tree_sitter::{Node, Tree};
-use crate::ir::{Entity, Port, PortDirection, VHDLType, VectorRange, Architecture, Signal, Process, Generic};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::ops::Range;
+use tree_sitter::{Node, Point, Tree};
+use crate::ir::{Entity, Port, PortDirection, VHDLType, VectorRange, RangeExpr, Architecture, Signal, Process, Generic};
+use crate::ir::{Association, GenerateBlock, GenerateScheme, Instantiation, IntegerRange, Rename, TypeResolutionContext, SENSITIVITY_ALL};
+use crate::ir::sanitize::normalize_case;
+use crate::ir::source_map::SourceMap;
+use crate::parser::binding::{self, Configuration};
+use crate::parser::package::{declared_types, package_body_name, Package, PackageRegistry};
+use crate::parser::parse_error::{error_nodes, strict_error, ParseError};
+use crate::parser::stimulus::{ArrayTypes, StimulusPattern, ARRAY_DEFINITION_KINDS};
 use crate::parser::tree_sitter_vhdl::{TreeSitterVHDLParser, VHDLASTHelper};
 
+lazy_static! {
+    static ref FOR_GENERATE_RE: Regex =
+        Regex::new(r"(?is)^\s*(\w+)\s*:\s*for\s+(\w+)\s+in\s+(.+?)\s+(to|downto)\s+(.+?)\s+generate\b").unwrap();
+    static ref IF_GENERATE_RE: Regex =
+        Regex::new(r"(?is)^\s*(\w+)\s*:\s*if\s+(.+?)\s+generate\b").unwrap();
+    static ref INSTANCE_RE: Regex =
+        Regex::new(r"(?is)^\s*(\w+)\s*:\s*(?:(entity|component|configuration)\s+)?(?:\w+\.)?(\w+)(?:\s*\(\s*(\w+)\s*\))?").unwrap();
+    static ref PROCESS_ALL_RE: Regex = Regex::new(r"(?i)^(?:\s*\w+\s*:)?\s*(?:postponed\s+)?process\s*\(\s*all\s*\)").unwrap();
+}
+
+const CONCURRENT_ASSIGNMENT_TYPES: &[&str] = &[
+    "concurrent_signal_assignment_statement",
+    "simple_concurrent_signal_assignment",
+    "conditional_signal_assignment",
+    "selected_signal_assignment",
+];
+
+const GENERATE_TYPES: &[&str] = &["for_generate_statement", "if_generate_statement"];
+
+/// Concurrent items found in an architecture or generate body
+#[derive(Default)]
+struct ConcurrentItems {
+    statements: Vec<String>,
+    statement_lines: Vec<usize>,
+    instantiations: Vec<Instantiation>,
+    generate_blocks: Vec<GenerateBlock>,
+}
+
+/// Text edit in byte offsets, converted to a `tree_sitter::InputEdit` by
+/// `ASTVHDLParser::update`: `old_source[start_byte..old_end_byte]` was replaced by
+/// `new_source[start_byte..new_end_byte]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEdit {
+    pub start_byte: usize,
+    pub old_end_byte: usize,
+    pub new_end_byte: usize,
+}
+
+impl InputEdit {
+    fn to_tree_sitter(self, old_source: &str, new_source: &str) -> Result<tree_sitter::InputEdit> {
+        let in_bounds = |source: &str, end: usize| {
+            self.start_byte <= end && source.is_char_boundary(self.start_byte) && source.is_char_boundary(end)
+        };
+        if !in_bounds(old_source, self.old_end_byte) || !in_bounds(new_source, self.new_end_byte) {
+            return Err(anyhow::anyhow!("Edit {:?} does not fit the old and new source", self));
+        }
+
+        Ok(tree_sitter::InputEdit {
+            start_byte: self.start_byte,
+            old_end_byte: self.old_end_byte,
+            new_end_byte: self.new_end_byte,
+            start_position: point_at(old_source, self.start_byte),
+            old_end_position: point_at(old_source, self.old_end_byte),
+            new_end_position: point_at(new_source, self.new_end_byte),
+        })
+    }
+}
+
 /// AST-based VHDL parser using tree-sitter
 pub struct ASTVHDLParser {
     parser: TreeSitterVHDLParser,
     content: String,
+    /// Spellings unified with their declaration by the last parse, per entity
+    case_normalizations: Vec<(String, Rename)>,
+    /// Instance bindings the last parse could not resolve, per entity
+    binding_diagnostics: Vec<(String, String)>,
+    /// Tree of the last parse, reused by `update`
+    tree: Option<Tree>,
+    /// Entities of the last successful parse
+    entities: Vec<Entity>,
+    /// Entities extracted from the syntax tree by the last parse rather than reused
+    reparsed: Vec<String>,
+    /// The last parse hit syntax errors, so `entities` may not match the tree
+    stale: bool,
+    /// Types of packages declared elsewhere, from `with_packages`
+    packages: PackageRegistry,
 }
 
 impl ASTVHDLParser {
@@ -14,7 +96,33 @@ impl ASTVHDLParser {
         let parser = TreeSitterVHDLParser::new()
             .context("Failed to create tree-sitter VHDL parser")?;
         
-        Ok(Self { parser, content })
+        Ok(Self {
+            parser,
+            content,
+            case_normalizations: Vec::new(),
+            binding_diagnostics: Vec::new(),
+            tree: None,
+            entities: Vec::new(),
+            reparsed: Vec::new(),
+            stale: false,
+            packages: PackageRegistry::new(),
+        })
+    }
+
+    /// Seed the type resolution context with packages parsed from other files
+    pub fn with_packages(mut self, packages: Vec<Package>) -> Self {
+        for package in &packages {
+            self.packages.add_package(package);
+        }
+        self
+    }
+
+    /// Types of the packages given to `with_packages` and of those declared in the source,
+    /// for the generators' `with_type_context`
+    pub fn type_context(&self) -> TypeResolutionContext {
+        let mut packages = self.packages.clone();
+        packages.add_source(&self.content);
+        packages.type_context()
     }
 
     pub fn from_file(path: &std::path::Path) -> Result<Self> {
@@ -23,24 +131,352 @@ impl ASTVHDLParser {
         Self::new(content)
     }
 
-    /// Parse and extract all entities from the VHDL content
+    /// Parse and extract all entities from the VHDL content, failing on any syntax error
     pub fn parse_entities(&mut self) -> Result<Vec<Entity>> {
+        let (entities, errors) = self.parse_entities_partial()?;
+        if !errors.is_empty() {
+            return Err(strict_error(&errors));
+        }
+        Ok(entities)
+    }
+
+    /// Parse the VHDL content, extracting the entities whose declaration and architectures
+    /// are free of syntax errors, and locate the errors that kept the others out
+    pub fn parse_entities_partial(&mut self) -> Result<(Vec<Entity>, Vec<ParseError>)> {
         let tree = self.parser.parse(&self.content)
             .context("Failed to parse VHDL content with tree-sitter")?;
+        self.extract_entities(tree, None)
+    }
 
-        let root = tree.root_node();
-        if root.has_error() {
-            return Err(anyhow::anyhow!("Parse tree contains errors"));
+    /// Apply `edit`, which turned the current source into `new_source`, and re-parse
+    /// incrementally: only entities whose declaration or architectures overlap the
+    /// changed text are extracted again, the rest come from the cache
+    pub fn update(&mut self, edit: InputEdit, new_source: String) -> Result<Vec<Entity>> {
+        let Some(mut old_tree) = self.tree.take() else {
+            self.content = new_source;
+            return self.parse_entities();
+        };
+        let ts_edit = match edit.to_tree_sitter(&self.content, &new_source) {
+            Ok(ts_edit) => ts_edit,
+            Err(e) => {
+                self.tree = Some(old_tree);
+                return Err(e);
+            }
+        };
+
+        old_tree.edit(&ts_edit);
+        let tree = self.parser.parse_with_old_tree(&new_source, Some(&old_tree))
+            .context("Failed to parse VHDL content with tree-sitter")?;
+        self.content = new_source;
+
+        // Token-only edits leave the tree's structure intact, so the edited text counts too
+        let mut changed: Vec<Range<usize>> = old_tree.changed_ranges(&tree)
+            .map(|range| range.start_byte..range.end_byte)
+            .collect();
+        changed.push(edit.start_byte..edit.new_end_byte);
+
+        let delta = ts_edit.new_end_position.row as isize - ts_edit.old_end_position.row as isize;
+        if delta != 0 {
+            for entity in &mut self.entities {
+                entity.shift_source_lines(ts_edit.start_position.row + 1, delta);
+            }
+        }
+
+        let (entities, errors) = self.extract_entities(tree, Some(&changed))?;
+        if !errors.is_empty() {
+            return Err(strict_error(&errors));
         }
+        Ok(entities)
+    }
+
+    /// Entities of the last successful `parse_entities` or `update`
+    pub fn get_cached_entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Names of the entities the last parse extracted from the tree instead of the cache
+    pub fn reparsed_entities(&self) -> &[String] {
+        &self.reparsed
+    }
+
+    /// Extract entities from `tree`, reusing cached ones that do not overlap `changed`
+    /// (all are extracted when `changed` is `None`), and keep the tree for `update`.
+    /// Entities with a syntax error in their declaration or an architecture are left out
+    fn extract_entities(&mut self, tree: Tree, changed: Option<&[Range<usize>]>) -> Result<(Vec<Entity>, Vec<ParseError>)> {
+        let errors = self.parse_errors(&tree.root_node());
+        let changed = if self.stale { None } else { changed };
 
         let mut entities = Vec::new();
+        let mut reparsed = Vec::new();
+        let mut case_normalizations = Vec::new();
+        let mut binding_diagnostics = Vec::new();
+        let configurations = binding::configurations(&self.content);
+        {
+            let root = tree.root_node();
+            let arch_nodes = VHDLASTHelper::find_all_nodes_by_type(&root, "architecture_body");
+
+            // Find all entity declarations in the AST
+            let entity_nodes = VHDLASTHelper::find_all_nodes_by_type(&root, "entity_declaration");
+
+            for entity_node in entity_nodes {
+                let broken = entity_node.has_error() || VHDLASTHelper::find_child_by_type(&entity_node, "identifier")
+                    .map(|name| VHDLASTHelper::node_text(&name, &self.content))
+                    .is_some_and(|name| errors.iter().any(|e| e.entity.as_deref().is_some_and(|entity| entity.eq_ignore_ascii_case(name))));
+                if broken {
+                    continue;
+                }
 
-        // Find all entity declarations in the AST
-        let entity_nodes = VHDLASTHelper::find_all_nodes_by_type(&root, "entity_declaration");
-        
-        for entity_node in entity_nodes {
-            let entity = self.parse_entity_from_node(&entity_node, &tree)?;
-            entities.push(entity);
+                let cached = changed.and_then(|changed| self.cached_entity(&entity_node, &arch_nodes, changed));
+                if let Some(entity) = cached {
+                    case_normalizations.extend(self.case_normalizations.iter()
+                        .filter(|(name, _)| *name == entity.name)
+                        .cloned());
+                    binding_diagnostics.extend(self.binding_diagnostics.iter()
+                        .filter(|(name, _)| *name == entity.name)
+                        .cloned());
+                    entities.push(entity.clone());
+                    continue;
+                }
+
+                let mut entity = self.parse_entity_from_node(&entity_node, &tree)?;
+                entity.resolve_standard_types();
+                for diagnostic in self.bind_instances(&mut entity, &arch_nodes, &configurations) {
+                    tracing::warn!("{}: {}", entity.name, diagnostic);
+                    binding_diagnostics.push((entity.name.clone(), diagnostic));
+                }
+
+                // VHDL is case-insensitive: references take the declaration's spelling
+                let (entity, renames) = normalize_case(&entity);
+                for rename in renames {
+                    tracing::info!("Unified identifier spelling in {}: {}", entity.name, rename);
+                    case_normalizations.push((entity.name.clone(), rename));
+                }
+                reparsed.push(entity.name.clone());
+                entities.push(entity);
+            }
+        }
+
+        for configuration in &configurations {
+            let target = entities.iter().find(|e| e.name.eq_ignore_ascii_case(&configuration.entity));
+            let reason = match target {
+                None => format!("entity {} is not declared in this file", configuration.entity),
+                Some(entity) if !entity.architecture.iter().chain(&entity.alternate_architectures).any(|a| a.name.eq_ignore_ascii_case(&configuration.architecture)) => {
+                    format!("{} has no architecture {}", entity.name, configuration.architecture)
+                }
+                Some(_) => continue,
+            };
+            binding_diagnostics.push((
+                configuration.entity.clone(),
+                format!("configuration {} ignored: {}", configuration.name, reason),
+            ));
+        }
+
+        self.tree = Some(tree);
+        // Entities left out of this parse are missing from the cache too
+        self.stale = !errors.is_empty();
+        self.entities = entities.clone();
+        self.reparsed = reparsed;
+        self.case_normalizations = case_normalizations;
+        self.binding_diagnostics = binding_diagnostics;
+        Ok((entities, errors))
+    }
+
+    /// Syntax errors in `root`, each with the entity whose declaration or architecture
+    /// contains it
+    fn parse_errors(&self, root: &Node) -> Vec<ParseError> {
+        error_nodes(root).iter()
+            .map(|node| {
+                let mut ancestor = node.parent();
+                let mut entity = None;
+                while let Some(current) = ancestor {
+                    entity = match current.kind() {
+                        "entity_declaration" => VHDLASTHelper::find_child_by_type(&current, "identifier")
+                            .map(|name| VHDLASTHelper::node_text(&name, &self.content).to_string()),
+                        "architecture_body" => self.architecture_entity_name(&current).ok(),
+                        _ => None,
+                    };
+                    if entity.is_some() {
+                        break;
+                    }
+                    ancestor = current.parent();
+                }
+                ParseError::from_node(node, &self.content, entity)
+            })
+            .collect()
+    }
+
+    /// Bind the component and configuration instances in each architecture of `entity`
+    /// through the architecture's configuration specifications and the file's
+    /// configuration declarations, returning what could not be resolved
+    fn bind_instances(&self, entity: &mut Entity, arch_nodes: &[Node], configurations: &[Configuration]) -> Vec<String> {
+        let mut diagnostics = Vec::new();
+        let entity_name = entity.name.clone();
+        let architectures = entity.architecture.iter_mut().chain(entity.alternate_architectures.iter_mut());
+        for arch in architectures {
+            let declarative_part = arch_nodes.iter()
+                .filter(|node| self.architecture_entity_name(node).is_ok_and(|name| name.eq_ignore_ascii_case(&entity_name)))
+                .filter(|node| VHDLASTHelper::find_child_by_type(node, "identifier")
+                    .is_some_and(|name| VHDLASTHelper::node_text(&name, &self.content).eq_ignore_ascii_case(&arch.name)))
+                .find_map(|node| VHDLASTHelper::find_child_by_type(node, "declarative_part"))
+                .map_or("", |part| VHDLASTHelper::node_text(&part, &self.content));
+
+            binding::bind_instances(
+                arch,
+                &entity_name,
+                &binding::component_declarations(declarative_part),
+                &binding::binding_specs(declarative_part),
+                configurations,
+                &mut diagnostics,
+            );
+        }
+        diagnostics
+    }
+
+    /// Cached entity for `entity_node` when neither its declaration (with the comments
+    /// above it) nor any of its architectures overlap `changed`
+    fn cached_entity(&self, entity_node: &Node, arch_nodes: &[Node], changed: &[Range<usize>]) -> Option<&Entity> {
+        let name_node = VHDLASTHelper::find_child_by_type(entity_node, "identifier")?;
+        let name = VHDLASTHelper::node_text(&name_node, &self.content);
+
+        let architectures: Vec<Range<usize>> = arch_nodes.iter()
+            .filter(|arch| self.architecture_entity_name(arch).is_ok_and(|entity| entity == name))
+            .map(|arch| arch.byte_range())
+            .collect();
+        let declaration = leading_comments_start(&self.content, entity_node.start_byte())..entity_node.end_byte();
+
+        let touched = std::iter::once(&declaration).chain(&architectures)
+            .any(|span| changed.iter().any(|range| range.start <= span.end && span.start <= range.end));
+        if touched {
+            return None;
+        }
+
+        // A deleted architecture leaves no span behind, so the count must match too
+        self.entities.iter().find(|entity| {
+            let count = entity.architecture.iter().count() + entity.alternate_architectures.len();
+            entity.name == name && count == architectures.len()
+        })
+    }
+
+    /// Identifier spellings the last parse unified with their declaration, with the entity name
+    pub fn case_normalizations(&self) -> &[(String, Rename)] {
+        &self.case_normalizations
+    }
+
+    /// Configuration specifications and declarations the last parse ignored, with the
+    /// entity name
+    pub fn binding_diagnostics(&self) -> &[(String, String)] {
+        &self.binding_diagnostics
+    }
+
+    /// Line/column index of the source built from the byte ranges of its syntax nodes
+    pub fn source_map(&mut self) -> Result<SourceMap> {
+        let tree = self.parser.parse(&self.content)
+            .context("Failed to parse VHDL content with tree-sitter")?;
+
+        let mut source_map = SourceMap::new(&self.content);
+        let mut queue = vec![tree.root_node()];
+        while let Some(node) = queue.pop() {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                if child.kind() != "comment" {
+                    source_map.add_node(child.start_byte());
+                }
+                queue.push(child);
+            }
+        }
+        Ok(source_map)
+    }
+
+    /// Architectures of entities not declared in this file, with the entity name, so a
+    /// split `foo_entity.vhd`/`foo_rtl.vhd` pair can be joined by the caller
+    pub fn parse_unmatched_architectures(&mut self) -> Result<Vec<(String, Architecture)>> {
+        let tree = self.parser.parse(&self.content)
+            .context("Failed to parse VHDL content with tree-sitter")?;
+        if tree.root_node().has_error() {
+            return Err(strict_error(&self.parse_errors(&tree.root_node())));
+        }
+        let root = tree.root_node();
+
+        let declared: Vec<String> = VHDLASTHelper::find_all_nodes_by_type(&root, "entity_declaration").iter()
+            .filter_map(|node| VHDLASTHelper::find_child_by_type(node, "identifier"))
+            .map(|name| VHDLASTHelper::node_text(&name, &self.content).to_string())
+            .collect();
+
+        let mut arch_nodes = VHDLASTHelper::find_all_nodes_by_type(&root, "architecture_body");
+        arch_nodes.sort_by_key(|node| node.start_byte());
+        let mut architectures = Vec::new();
+        for arch_node in arch_nodes {
+            let entity_name = self.architecture_entity_name(&arch_node)?;
+            if !declared.iter().any(|name| name.eq_ignore_ascii_case(&entity_name)) {
+                let mut arch = self.parse_architecture_from_node(&arch_node, &entity_name)?;
+                arch.resolve_standard_types();
+                architectures.push((entity_name, arch));
+            }
+        }
+        Ok(architectures)
+    }
+
+    /// Constants typed as one of the file's array types, such as testbench stimulus
+    /// vectors, in source order
+    pub fn stimulus_patterns(&mut self) -> Result<Vec<StimulusPattern>> {
+        let tree = self.parser.parse(&self.content)
+            .context("Failed to parse VHDL content with tree-sitter")?;
+        let root = tree.root_node();
+
+        let mut arrays = ArrayTypes::default();
+        for kind in ARRAY_DEFINITION_KINDS {
+            for definition in VHDLASTHelper::find_all_nodes_by_type(&root, kind) {
+                if let Some(declaration) = definition.parent() {
+                    arrays.add_declaration(VHDLASTHelper::node_text(&declaration, &self.content));
+                }
+            }
+        }
+
+        let mut constants = VHDLASTHelper::find_all_nodes_by_type(&root, "constant_declaration");
+        constants.sort_by_key(|node| node.start_byte());
+        Ok(constants.iter()
+            .filter_map(|node| StimulusPattern::from_declaration(VHDLASTHelper::node_text(node, &self.content), &arrays))
+            .collect())
+    }
+
+    /// Packages declared in the source with their types, constants and function
+    /// prototypes; deferred constants take their value from the package body
+    pub fn parse_package(&mut self) -> Result<Vec<Package>> {
+        let tree = self.parser.parse(&self.content)
+            .context("Failed to parse VHDL content with tree-sitter")?;
+        if tree.root_node().has_error() {
+            return Err(strict_error(&self.parse_errors(&tree.root_node())));
+        }
+        let root = tree.root_node();
+
+        let mut declarations = VHDLASTHelper::find_all_nodes_by_type(&root, "package_declaration");
+        declarations.sort_by_key(|node| node.start_byte());
+        let mut packages: Vec<Package> = declarations.iter()
+            .filter_map(|node| Package::from_declaration_text(VHDLASTHelper::node_text(node, &self.content)))
+            .collect();
+
+        for body in VHDLASTHelper::find_all_nodes_by_type(&root, "package_body") {
+            let text = VHDLASTHelper::node_text(&body, &self.content);
+            let package = package_body_name(text)
+                .and_then(|name| packages.iter_mut().find(|package| package.name.eq_ignore_ascii_case(&name)));
+            if let Some(package) = package {
+                package.add_body_text(text);
+            }
+        }
+        Ok(packages)
+    }
+
+    /// Parse all entities, using the named architecture wherever an entity defines it
+    pub fn parse_with_architecture(&mut self, arch_name: &str) -> Result<Vec<Entity>> {
+        let mut entities = self.parse_entities()?;
+
+        let mut found = false;
+        for entity in &mut entities {
+            found |= entity.select_architecture(arch_name);
+        }
+
+        if !found {
+            return Err(anyhow::anyhow!("No entity has an architecture named '{}'", arch_name));
         }
 
         Ok(entities)
@@ -53,6 +489,7 @@ impl ASTVHDLParser {
         
         let entity_name = VHDLASTHelper::node_text(&name_node, &self.content).to_string();
         let mut entity = Entity::new(entity_name.clone());
+        entity.header_comments = leading_comments(&self.content, entity_node.start_byte());
 
         // Parse generic clause if present
         if let Some(generic_node) = VHDLASTHelper::find_child_by_type(entity_node, "generic_clause") {
@@ -72,13 +509,12 @@ impl ASTVHDLParser {
             }
         }
 
-        // Try to find and parse architecture for this entity
+        // Collect every architecture of this entity; the first one is the default
         let root_node = tree.root_node();
         let arch_nodes = VHDLASTHelper::find_all_nodes_by_type(&root_node, "architecture_body");
         for arch_node in arch_nodes {
             if let Ok(arch) = self.parse_architecture_from_node(&arch_node, &entity_name) {
-                entity.architecture = Some(arch);
-                break;
+                entity.add_architecture(arch);
             }
         }
 
@@ -171,10 +607,16 @@ impl ASTVHDLParser {
         
         let port_type = self.parse_type_from_subtype(&subtype_indication)?;
 
+        let comment = trailing_comment(&self.content, decl_node.end_byte());
+
         // Create ports for all identifiers
         for identifier in identifiers {
             let name = VHDLASTHelper::node_text(&identifier, &self.content).to_string();
-            ports.push(Port::new(name, direction.clone(), port_type.clone()));
+            ports.push(
+                Port::new(name, direction.clone(), port_type.clone())
+                    .with_comment(comment.clone())
+                    .with_source_line(Some(source_line(&identifier)))
+            );
         }
 
         Ok(ports)
@@ -190,6 +632,14 @@ impl ASTVHDLParser {
         
         let type_name = VHDLASTHelper::node_text(&simple_name, &self.content).to_lowercase();
 
+        // Ranged integers (`integer range 0 to 255`) are sized from their range constraint
+        if matches!(type_name.as_str(), "integer" | "natural" | "positive") {
+            let constraint = VHDLASTHelper::find_all_nodes_by_type(subtype_node, "range_constraint").into_iter().next();
+            if let Some(range) = constraint.and_then(|node| IntegerRange::from_vhdl(VHDLASTHelper::node_text(&node, &self.content))) {
+                return Ok(VHDLType::ConstrainedInteger(range));
+            }
+        }
+
         // Handle basic types
         match type_name.as_str() {
             "std_logic" | "std_ulogic" => return Ok(VHDLType::StdLogic),
@@ -211,7 +661,7 @@ impl ASTVHDLParser {
                     "bit_vector" => VHDLType::BitVector(range),
                     "signed" => VHDLType::Signed(range),
                     "unsigned" => VHDLType::Unsigned(range),
-                    _ => VHDLType::Custom(format!("{}({})", type_name, range.left)),
+                    _ => VHDLType::Custom(format!("{}{}", type_name, range.to_vhdl())),
                 });
             }
         }
@@ -249,8 +699,8 @@ impl ASTVHDLParser {
             let left_expr = &simple_expressions[0];
             let right_expr = &simple_expressions[1];
             
-            let left = self.parse_integer_from_expression(left_expr)?;
-            let right = self.parse_integer_from_expression(right_expr)?;
+            let left = self.parse_range_bound(left_expr)?;
+            let right = self.parse_range_bound(right_expr)?;
             
             return Ok(VectorRange { left, right, downto: true });
         }
@@ -265,8 +715,8 @@ impl ASTVHDLParser {
             let left_expr = &simple_expressions[0];
             let right_expr = &simple_expressions[1];
             
-            let left = self.parse_integer_from_expression(left_expr)?;
-            let right = self.parse_integer_from_expression(right_expr)?;
+            let left = self.parse_range_bound(left_expr)?;
+            let right = self.parse_range_bound(right_expr)?;
             
             return Ok(VectorRange { left, right, downto: false });
         }
@@ -274,33 +724,26 @@ impl ASTVHDLParser {
         Err(anyhow::anyhow!("Could not parse ascending range"))
     }
 
-    fn parse_integer_from_expression(&self, expr: &Node) -> Result<i32> {
-        // Look for integer_decimal in the expression
-        if let Some(integer_node) = VHDLASTHelper::find_child_by_type(expr, "integer_decimal") {
-            let integer_text = VHDLASTHelper::node_text(&integer_node, &self.content);
-            return integer_text.parse()
-                .context(format!("Failed to parse integer: {}", integer_text));
-        }
-
-        // If not found directly, try to get the text of the whole expression
+    fn parse_range_bound(&self, expr: &Node) -> Result<RangeExpr> {
         let expr_text = VHDLASTHelper::node_text(expr, &self.content).trim();
-        
-        // Handle simple expressions like "WIDTH-1" by trying to parse as integer first
-        if let Ok(value) = expr_text.parse::<i32>() {
-            return Ok(value);
-        }
-        
-        // For expressions like "WIDTH-1", we'll need to handle them differently
-        // For now, return a default value and log a warning
-        if expr_text.contains('-') && expr_text.len() < 20 {
-            // Simple heuristic: if it looks like "WIDTH-1", assume it's a reasonable range
-            // This is a temporary solution - in a real implementation, we'd need proper expression evaluation
-            tracing::warn!("Could not parse expression '{}', using default value 7", expr_text);
-            return Ok(7); // Default to 8-bit range
+
+        RangeExpr::from_vhdl(expr_text)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported range expression: {}", expr_text))
+    }
+
+    /// Name of the entity an architecture body belongs to
+    fn architecture_entity_name(&self, arch_node: &Node) -> Result<String> {
+        // Look for the entity name reference after "of" keyword
+        let all_identifiers = VHDLASTHelper::find_children_by_type(arch_node, "identifier");
+
+        // The entity name should be the second identifier (after architecture name)
+        if all_identifiers.len() >= 2 {
+            Ok(VHDLASTHelper::node_text(&all_identifiers[1], &self.content).to_string())
+        } else {
+            // Try to find entity name in a different way - look for it after "of"
+            let arch_text = VHDLASTHelper::node_text(arch_node, &self.content);
+            referenced_entity_from_text(arch_text)
         }
-        
-        expr_text.parse()
-            .context(format!("Failed to parse expression as integer: {}", expr_text))
     }
 
     fn parse_architecture_from_node(&self, arch_node: &Node, entity_name: &str) -> Result<Architecture> {
@@ -311,54 +754,38 @@ impl ASTVHDLParser {
         let arch_name = VHDLASTHelper::node_text(&arch_name_node, &self.content).to_string();
 
         // Check if this architecture is for the correct entity
-        // Look for the entity name reference after "of" keyword
-        let all_identifiers = VHDLASTHelper::find_children_by_type(arch_node, "identifier");
-        
-        // The entity name should be the second identifier (after architecture name)
-        let referenced_entity = if all_identifiers.len() >= 2 {
-            VHDLASTHelper::node_text(&all_identifiers[1], &self.content).to_string()
-        } else {
-            // Try to find entity name in a different way - look for it after "of"
-            let arch_text = VHDLASTHelper::node_text(arch_node, &self.content);
-            
-            // Simple text parsing: "architecture NAME of ENTITY is"
-            if let Some(of_pos) = arch_text.find(" of ") {
-                if let Some(is_pos) = arch_text.find(" is") {
-                    let entity_part = arch_text[of_pos + 4..is_pos].trim().to_string();
-                    entity_part
-                } else {
-                    return Err(anyhow::anyhow!("Architecture missing 'is' keyword"));
-                }
-            } else {
-                return Err(anyhow::anyhow!("Architecture missing 'of' keyword"));
-            }
-        };
-        
+        let referenced_entity = self.architecture_entity_name(arch_node)?;
         if referenced_entity != entity_name {
             return Err(anyhow::anyhow!("Architecture is for different entity: {}", referenced_entity));
         }
 
-        // Parse architecture declarative part (signals)
+        // Parse architecture declarative part (signals and local types)
         let mut signals = Vec::new();
+        let mut local_types = TypeResolutionContext::new();
         if let Some(decl_part) = VHDLASTHelper::find_child_by_type(arch_node, "declarative_part") {
             signals = self.parse_signals_from_declarative_part(&decl_part)?;
+            local_types = declared_types(VHDLASTHelper::node_text(&decl_part, &self.content));
         }
 
         // Parse architecture statement part (processes and concurrent statements)
         let mut processes = Vec::new();
-        let mut concurrent_statements = Vec::new();
+        let mut items = ConcurrentItems::default();
         
         if let Some(stmt_part) = VHDLASTHelper::find_child_by_type(arch_node, "concurrent_statement_part") {
             let (procs, concurrent) = self.parse_statements_from_statement_part(&stmt_part)?;
             processes = procs;
-            concurrent_statements = concurrent;
+            items = concurrent;
         }
 
         Ok(Architecture {
             name: arch_name,
             signals,
             processes,
-            concurrent_statements,
+            concurrent_statements: items.statements,
+            instantiations: items.instantiations,
+            generate_blocks: items.generate_blocks,
+            concurrent_statement_lines: items.statement_lines,
+            types: local_types,
         })
     }
 
@@ -396,15 +823,15 @@ impl ASTVHDLParser {
             signals.push(Signal {
                 name,
                 signal_type: signal_type.clone(),
+                source_line: Some(source_line(&identifier)),
             });
         }
 
         Ok(signals)
     }
 
-    fn parse_statements_from_statement_part(&self, stmt_part: &Node) -> Result<(Vec<Process>, Vec<String>)> {
+    fn parse_statements_from_statement_part(&self, stmt_part: &Node) -> Result<(Vec<Process>, ConcurrentItems)> {
         let mut processes = Vec::new();
-        let mut concurrent_statements = Vec::new();
 
         // Find process statements
         let process_nodes = VHDLASTHelper::find_all_nodes_by_type(stmt_part, "process_statement");
@@ -414,26 +841,53 @@ impl ASTVHDLParser {
             }
         }
 
-        // Find concurrent signal assignments - try different node types
-        let concurrent_types = vec![
-            "concurrent_signal_assignment_statement",
-            "simple_concurrent_signal_assignment",
-            "conditional_signal_assignment",
-            "selected_signal_assignment",
-        ];
-
-        for node_type in concurrent_types {
-            let concurrent_nodes = VHDLASTHelper::find_all_nodes_by_type(stmt_part, node_type);
-            for concurrent_node in concurrent_nodes {
-                let stmt_text = VHDLASTHelper::node_text(&concurrent_node, &self.content);
-                let stmt_str = stmt_text.trim().to_string();
-                if !stmt_str.is_empty() && !concurrent_statements.contains(&stmt_str) {
-                    concurrent_statements.push(stmt_str);
+        // Find concurrent assignments, instantiations and generate statements
+        let mut items = ConcurrentItems::default();
+        self.collect_concurrent_items(stmt_part, &mut items)?;
+
+        Ok((processes, items))
+    }
+
+    /// Walk concurrent statements, stopping at generate statements so their contents stay nested
+    fn collect_concurrent_items(&self, node: &Node, items: &mut ConcurrentItems) -> Result<()> {
+        for child in VHDLASTHelper::get_named_children(node) {
+            let kind = child.kind();
+            let text = VHDLASTHelper::node_text(&child, &self.content).trim();
+
+            if GENERATE_TYPES.contains(&kind) {
+                items.generate_blocks.push(self.parse_generate_from_node(&child)?);
+            } else if kind == "component_instantiation_statement" {
+                let inst = parse_instantiation(text)
+                    .ok_or_else(|| anyhow::anyhow!("Malformed instantiation: {}", text))?;
+                items.instantiations.push(inst);
+            } else if CONCURRENT_ASSIGNMENT_TYPES.contains(&kind) {
+                if !text.is_empty() && !items.statements.iter().any(|s| s == text) {
+                    items.statements.push(text.to_string());
+                    items.statement_lines.push(source_line(&child));
                 }
+            } else if kind != "process_statement" {
+                self.collect_concurrent_items(&child, items)?;
             }
         }
 
-        Ok((processes, concurrent_statements))
+        Ok(())
+    }
+
+    fn parse_generate_from_node(&self, generate_node: &Node) -> Result<GenerateBlock> {
+        let text = VHDLASTHelper::node_text(generate_node, &self.content);
+        let (label, scheme) = parse_generate_header(text)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported generate statement: {}", text.lines().next().unwrap_or("")))?;
+
+        let mut items = ConcurrentItems::default();
+        self.collect_concurrent_items(generate_node, &mut items)?;
+
+        Ok(GenerateBlock {
+            label,
+            scheme,
+            concurrent_statements: items.statements,
+            instantiations: items.instantiations,
+            generate_blocks: items.generate_blocks,
+        })
     }
 
     fn parse_process_from_node(&self, process_node: &Node) -> Result<Process> {
@@ -444,8 +898,10 @@ impl ASTVHDLParser {
         // Get sensitivity list - check both in process_node itself and in sensitivity_list child
         let mut sensitivity_list = Vec::new();
 
-        // First, try to find sensitivity_list directly as a child
-        if let Some(sensitivity_list_node) = VHDLASTHelper::find_child_by_type(process_node, "sensitivity_list") {
+        // VHDL-2008 `process(all)`: the list holds the keyword rather than names
+        if PROCESS_ALL_RE.is_match(VHDLASTHelper::node_text(process_node, &self.content)) {
+            sensitivity_list.push(SENSITIVITY_ALL.to_string());
+        } else if let Some(sensitivity_list_node) = VHDLASTHelper::find_child_by_type(process_node, "sensitivity_list") {
             // Extract identifiers or simple_names from the sensitivity list
             let simple_names = VHDLASTHelper::find_children_by_type(&sensitivity_list_node, "simple_name");
             for name_node in simple_names {
@@ -470,14 +926,176 @@ impl ASTVHDLParser {
             String::new()
         };
 
+        // Testbench-style processes suspend on `wait` instead of a sensitivity list
+        let testbench_style = !VHDLASTHelper::find_all_nodes_by_type(process_node, "wait_statement").is_empty();
+
         Ok(Process {
             label,
             sensitivity_list,
             body,
+            source_line: Some(source_line(process_node)),
+            testbench_style,
         })
     }
 }
 
+/// Add an architecture parsed from another file to its entity in `entities`, matched
+/// case-insensitively, and unify identifier spellings again. Returns the entity's index,
+/// or `None` when no entity has that name
+pub fn attach_architecture(entities: &mut [Entity], entity_name: &str, arch: Architecture) -> Option<usize> {
+    let index = entities.iter().position(|entity| entity.name.eq_ignore_ascii_case(entity_name))?;
+    entities[index].add_architecture(arch);
+    let (entity, renames) = normalize_case(&entities[index]);
+    for rename in renames {
+        tracing::info!("Unified identifier spelling in {}: {}", entity.name, rename);
+    }
+    entities[index] = entity;
+    Some(index)
+}
+
+/// Simple text parsing: "architecture NAME of ENTITY is"
+fn referenced_entity_from_text(arch_text: &str) -> Result<String> {
+    let of_pos = arch_text.find(" of ")
+        .ok_or_else(|| anyhow::anyhow!("Architecture missing 'of' keyword"))?;
+
+    // Search for "is" after "of" only: the architecture name itself may start with "is"
+    let after_of = &arch_text[of_pos + 4..];
+    let is_pos = after_of.find(" is")
+        .ok_or_else(|| anyhow::anyhow!("Architecture missing 'is' keyword"))?;
+
+    Ok(after_of[..is_pos].trim().to_string())
+}
+
+/// Comment lines directly above `pos`, skipping blank lines and library/use clauses
+fn leading_comments(content: &str, pos: usize) -> Vec<String> {
+    let mut comments = Vec::new();
+
+    for line in content[..pos].lines().rev() {
+        let trimmed = line.trim();
+        let lower = trimmed.to_ascii_lowercase();
+        if let Some(comment) = trimmed.strip_prefix("--") {
+            comments.push(comment.trim().to_string());
+        } else if !(trimmed.is_empty() || lower.starts_with("library ") || lower.starts_with("use ")) {
+            break;
+        }
+    }
+
+    comments.reverse();
+    comments
+}
+
+/// Start of the line where the comment block read by `leading_comments` begins
+fn leading_comments_start(content: &str, pos: usize) -> usize {
+    let mut start = content[..pos].rfind('\n').map_or(0, |i| i + 1);
+    while start > 0 {
+        let line_start = content[..start - 1].rfind('\n').map_or(0, |i| i + 1);
+        let trimmed = content[line_start..start - 1].trim();
+        let lower = trimmed.to_ascii_lowercase();
+        let skipped = trimmed.is_empty() || trimmed.starts_with("--")
+            || lower.starts_with("library ") || lower.starts_with("use ");
+        if !skipped {
+            break;
+        }
+        start = line_start;
+    }
+    start
+}
+
+/// Row (0-based) and byte column of `byte` in `source`
+fn point_at(source: &str, byte: usize) -> Point {
+    let before = &source[..byte];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Point { row: before.matches('\n').count(), column: byte - line_start }
+}
+
+/// 1-based source line where `node` starts
+fn source_line(node: &Node) -> usize {
+    node.start_position().row + 1
+}
+
+/// `--` comment on the rest of the line after `pos`, past a separating `;`
+fn trailing_comment(content: &str, pos: usize) -> Option<String> {
+    let rest = content[pos..].lines().next()?;
+    let rest = rest.trim_start().trim_start_matches(';').trim_start();
+
+    rest.strip_prefix("--").map(|comment| comment.trim().to_string())
+}
+
+/// Parse the label and scheme of a `for ... generate` or `if ... generate` statement
+fn parse_generate_header(text: &str) -> Option<(String, GenerateScheme)> {
+    if let Some(caps) = FOR_GENERATE_RE.captures(text) {
+        let scheme = GenerateScheme::For {
+            var: caps[2].to_string(),
+            left: caps[3].trim().to_string(),
+            right: caps[5].trim().to_string(),
+            downto: caps[4].eq_ignore_ascii_case("downto"),
+        };
+        return Some((caps[1].to_string(), scheme));
+    }
+
+    IF_GENERATE_RE.captures(text).map(|caps| {
+        let scheme = GenerateScheme::If { condition: caps[2].trim().to_string() };
+        (caps[1].to_string(), scheme)
+    })
+}
+
+/// Parse `label : [entity lib.]name[(arch)] [generic map (...)] port map (...);`, also
+/// `component name` and `configuration lib.name` instantiations
+fn parse_instantiation(text: &str) -> Option<Instantiation> {
+    let caps = INSTANCE_RE.captures(text)?;
+    let keyword = caps.get(2).map(|m| m.as_str().to_lowercase());
+
+    Some(Instantiation {
+        label: caps[1].to_string(),
+        component: caps[3].to_string(),
+        generic_map: parse_association_list(text, "generic map"),
+        port_map: parse_association_list(text, "port map"),
+        architecture: caps.get(4).filter(|_| keyword.as_deref() == Some("entity")).map(|m| m.as_str().to_string()),
+        configuration: (keyword.as_deref() == Some("configuration")).then(|| caps[3].to_string()),
+    })
+}
+
+/// Split the parenthesised list after `keyword` into association elements
+fn parse_association_list(text: &str, keyword: &str) -> Vec<Association> {
+    let lower = text.to_ascii_lowercase();
+    let Some(keyword_pos) = lower.find(keyword) else {
+        return Vec::new();
+    };
+    let Some(open) = text[keyword_pos..].find('(').map(|i| keyword_pos + i) else {
+        return Vec::new();
+    };
+
+    let mut depth = 0;
+    let mut start = open + 1;
+    let mut elements = Vec::new();
+    for (i, c) in text[open..].char_indices().map(|(i, c)| (open + i, c)) {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    elements.push(&text[start..i]);
+                    break;
+                }
+            }
+            ',' if depth == 1 => {
+                elements.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    elements.into_iter()
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
+        .map(|element| match element.split_once("=>") {
+            Some((formal, actual)) if !element.starts_with('(') => Association::named(formal.trim(), actual),
+            _ => Association::positional(element),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -512,4 +1130,335 @@ mod tests {
             assert_eq!(entities[0].ports.len(), 3);
         }
     }
+
+    #[test]
+    fn test_parse_with_architecture() {
+        let vhdl = r#"
+        entity inv is
+            port(
+                a : in  std_logic;
+                y : out std_logic
+            );
+        end entity inv;
+
+        architecture rtl of inv is
+        begin
+            y <= not a;
+        end architecture rtl;
+
+        architecture passthrough of inv is
+        begin
+            y <= a;
+        end architecture passthrough;
+        "#;
+
+        let mut parser = ASTVHDLParser::new(vhdl.to_string()).unwrap();
+
+        // Like test_parse_simple_entity, this needs the tree-sitter grammar
+        if let Ok(entities) = parser.parse_entities() {
+            assert_eq!(entities[0].architecture.as_ref().unwrap().name, "rtl");
+            assert_eq!(entities[0].alternate_architectures.len(), 1);
+
+            let generator = crate::ir::SystemVerilogGenerator::new();
+            let rtl = generator.generate(&entities[0]).unwrap().0;
+            let passthrough = parser.parse_with_architecture("passthrough").unwrap();
+            assert_eq!(passthrough[0].architecture.as_ref().unwrap().name, "passthrough");
+            assert_ne!(generator.generate(&passthrough[0]).unwrap().0, rtl);
+
+            assert!(parser.parse_with_architecture("missing").is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_ranged_integer_ports() {
+        let vhdl = r#"
+        entity levels is
+            generic(AW : natural := 4);
+            port(
+                level  : in  integer range 0 to 255;
+                offset : in  integer range -8 to 7;
+                addr   : out natural range 0 to 2**AW-1;
+                count  : out integer
+            );
+        end entity levels;
+        "#;
+
+        let mut parser = ASTVHDLParser::new(vhdl.to_string()).unwrap();
+
+        // Like test_parse_simple_entity, this needs the tree-sitter grammar
+        if let Ok(entities) = parser.parse_entities() {
+            let widths: Vec<String> = entities[0].ports.iter().map(|port| port.port_type.to_verilog()).collect();
+            assert_eq!(widths, vec!["wire [7:0]", "wire signed [3:0]", "wire [AW-1:0]", "wire signed [31:0]"]);
+        }
+    }
+
+    #[test]
+    fn test_parse_process_all() {
+        assert!(PROCESS_ALL_RE.is_match("comb : process (ALL)\n    begin"));
+        assert!(!PROCESS_ALL_RE.is_match("process(all_valid)"));
+
+        let vhdl = r#"
+        entity mux is
+            port(a, b, sel : in std_logic; y : out std_logic);
+        end entity mux;
+
+        architecture rtl of mux is
+        begin
+            comb : process(all)
+            begin
+                y <= a when sel = '1' else b;
+            end process;
+        end architecture rtl;
+        "#;
+
+        let mut parser = ASTVHDLParser::new(vhdl.to_string()).unwrap();
+
+        // Like test_parse_simple_entity, this needs the tree-sitter grammar
+        if let Ok(entities) = parser.parse_entities() {
+            let process = &entities[0].architecture.as_ref().unwrap().processes[0];
+            assert!(process.senses_all());
+
+            let sv = crate::ir::SystemVerilogGenerator::new().generate(&entities[0]).unwrap().0;
+            assert!(sv.contains("always_comb begin\n        y <= (sel == 1'b1) ? a : b;\n"));
+        }
+    }
+
+    #[test]
+    fn test_parse_generate_header_and_instantiation() {
+        use crate::ir::Actual;
+
+        let (label, scheme) = parse_generate_header("gen_add : for i in 0 to WIDTH - 1 generate\n").unwrap();
+        assert_eq!(label, "gen_add");
+        assert_eq!(scheme, GenerateScheme::For {
+            var: "i".to_string(),
+            left: "0".to_string(),
+            right: "WIDTH - 1".to_string(),
+            downto: false,
+        });
+
+        let (label, scheme) = parse_generate_header("g_reg: IF USE_REG = 1 GENERATE").unwrap();
+        assert_eq!(label, "g_reg");
+        assert_eq!(scheme, GenerateScheme::If { condition: "USE_REG = 1".to_string() });
+
+        let inst = parse_instantiation(
+            "u_add : entity work.adder\n  generic map (WIDTH => 8)\n  port map (a => a(i), b => open, sum => s(f(i, 1)));",
+        ).unwrap();
+        assert_eq!(inst.label, "u_add");
+        assert_eq!(inst.component, "adder");
+        assert_eq!(inst.generic_map, vec![Association::named("WIDTH", "8")]);
+        assert_eq!(inst.port_map, vec![
+            Association::named("a", "a(i)"),
+            Association { formal: Some("b".to_string()), actual: Actual::Open },
+            Association::named("sum", "s(f(i, 1))"),
+        ]);
+
+        let positional = parse_instantiation("u0: inv port map (x, y);").unwrap();
+        assert_eq!(positional.port_map[1], Association::positional("y"));
+
+        let aggregate = parse_instantiation("u1: reg port map ((others => '0'), q);").unwrap();
+        assert_eq!(aggregate.port_map[0], Association::positional("(others => '0')"));
+    }
+
+    #[test]
+    fn test_generic_width_range() {
+        let vhdl = r#"
+        entity reg_n is
+            generic(
+                WIDTH : integer := 8
+            );
+            port(
+                data : in std_logic_vector(WIDTH-1 downto 0)
+            );
+        end entity reg_n;
+        "#;
+
+        let mut parser = ASTVHDLParser::new(vhdl.to_string()).unwrap();
+
+        // Like test_parse_simple_entity, this needs the tree-sitter grammar
+        if let Ok(entities) = parser.parse_entities() {
+            let expected = VHDLType::StdLogicVector(VectorRange {
+                left: RangeExpr::Parametric { base: "WIDTH".to_string(), offset: -1 },
+                right: 0.into(),
+                downto: true,
+            });
+            assert_eq!(entities[0].ports[0].port_type, expected);
+
+            let sv = crate::ir::SystemVerilogGenerator::new().generate(&entities[0]).unwrap().0;
+            assert!(sv.contains("parameter WIDTH = 8"));
+            assert!(sv.contains("input logic [WIDTH-1:0] data"));
+        }
+    }
+
+    #[test]
+    fn test_numeric_std_port_types() {
+        let vhdl = r#"
+        library ieee;
+        use ieee.std_logic_1164.all;
+        use ieee.numeric_std.all;
+
+        entity adder is
+            port(
+                a   : in  unsigned(7 downto 0);
+                b   : in  std_ulogic_vector(7 downto 0);
+                sum : out unsigned(7 downto 0)
+            );
+        end entity adder;
+        "#;
+
+        let mut parser = ASTVHDLParser::new(vhdl.to_string()).unwrap();
+
+        // Like test_parse_simple_entity, this needs the tree-sitter grammar
+        if let Ok(entities) = parser.parse_entities() {
+            let byte = VectorRange { left: 7.into(), right: 0.into(), downto: true };
+            let types: Vec<&VHDLType> = entities[0].ports.iter().map(|p| &p.port_type).collect();
+            assert_eq!(types, vec![
+                &VHDLType::Unsigned(byte.clone()),
+                &VHDLType::StdLogicVector(byte.clone()),
+                &VHDLType::Unsigned(byte),
+            ]);
+
+            let sv = crate::ir::SystemVerilogGenerator::new().generate(&entities[0]).unwrap().0;
+            assert!(sv.contains("logic [7:0] a"));
+        }
+    }
+
+    #[test]
+    fn test_comment_capture_helpers() {
+        let vhdl = "-- Copyright 2024\n--\n-- Counter block\nlibrary ieee;\nuse ieee.std_logic_1164.all;\n\nentity c is\n    port(\n        clk : in std_logic; -- system clock\n        q   : out std_logic -- registered output\n    );\nend entity c;\n";
+
+        let entity_pos = vhdl.find("entity c").unwrap();
+        assert_eq!(leading_comments(vhdl, entity_pos), vec!["Copyright 2024", "", "Counter block"]);
+
+        let clk_end = vhdl.find("std_logic; --").unwrap() + "std_logic".len();
+        assert_eq!(trailing_comment(vhdl, clk_end), Some("system clock".to_string()));
+
+        let q_end = vhdl.find("std_logic -- registered").unwrap() + "std_logic".len();
+        assert_eq!(trailing_comment(vhdl, q_end), Some("registered output".to_string()));
+
+        let end_pos = vhdl.find("end entity").unwrap();
+        assert_eq!(trailing_comment(vhdl, end_pos), None);
+    }
+
+    const THREE_ENTITIES: &str = r#"
+entity a is
+    port(x : in std_logic);
+end entity a;
+
+-- Second block
+entity b is
+    port(old_name : in std_logic);
+end entity b;
+
+architecture rtl of b is
+begin
+end architecture rtl;
+
+entity c is
+    port(y : out std_logic);
+end entity c;
+
+architecture rtl of c is
+    signal s : std_logic;
+begin
+    y <= s;
+end architecture rtl;
+"#;
+
+    #[test]
+    fn test_update_reparses_only_edited_entity() {
+        let mut parser = ASTVHDLParser::new(THREE_ENTITIES.to_string()).unwrap();
+
+        // Like test_parse_simple_entity, this needs the tree-sitter grammar
+        if parser.parse_entities().is_ok() {
+            assert_eq!(parser.reparsed_entities(), ["a", "b", "c"]);
+            let c_signal_line = parser.get_cached_entities()[2].architecture.as_ref().unwrap().signals[0].source_line;
+
+            // Rename b's port and add a line above c
+            let start = THREE_ENTITIES.find("old_name").unwrap();
+            let new_source = THREE_ENTITIES.replacen("old_name : in", "new_name :\n        in", 1);
+            let edit = InputEdit {
+                start_byte: start,
+                old_end_byte: start + "old_name : in".len(),
+                new_end_byte: start + "new_name :\n        in".len(),
+            };
+            let entities = parser.update(edit, new_source).unwrap();
+
+            assert_eq!(parser.reparsed_entities(), ["b"]);
+            assert_eq!(entities[1].ports[0].name, "new_name");
+            assert_eq!(parser.get_cached_entities()[0].ports[0].name, "x");
+            let shifted = parser.get_cached_entities()[2].architecture.as_ref().unwrap().signals[0].source_line;
+            assert_eq!(shifted, c_signal_line.map(|line| line + 1));
+        }
+    }
+
+    #[test]
+    fn test_partial_parse_skips_broken_entity() {
+        let source = include_str!("../../tests/fixtures/partial_parse.vhd");
+        let mut parser = ASTVHDLParser::new(source.to_string()).unwrap();
+
+        let (entities, errors) = parser.parse_entities_partial().unwrap();
+        if let Some(clean) = entities.first() {
+            assert_eq!(entities.len(), 1);
+            assert_eq!(clean.name, "clean_buf");
+            assert!(clean.architecture.is_some());
+            assert!(!errors.is_empty());
+            assert_eq!(errors[0].line, 29);
+            assert_eq!(errors[0].snippet, "q <= ;");
+            assert_eq!(errors[0].entity.as_deref(), Some("broken_reg"));
+
+            let err = parser.parse_entities().unwrap_err().to_string();
+            assert!(err.starts_with("Parse tree contains errors: line 29:"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_input_edit_positions() {
+        let old = "entity a is\nend;\n";
+        let new = "entity a is\n  -- x\nend;\n";
+        let edit = InputEdit { start_byte: 12, old_end_byte: 12, new_end_byte: 19 };
+        let ts_edit = edit.to_tree_sitter(old, new).unwrap();
+        assert_eq!(ts_edit.start_position, Point { row: 1, column: 0 });
+        assert_eq!(ts_edit.new_end_position, Point { row: 2, column: 0 });
+
+        let bad = InputEdit { start_byte: 12, old_end_byte: 40, new_end_byte: 19 };
+        assert!(bad.to_tree_sitter(old, new).is_err());
+
+        let mut parser = ASTVHDLParser::new(old.to_string()).unwrap();
+        assert!(parser.get_cached_entities().is_empty());
+        assert_eq!(leading_comments_start(THREE_ENTITIES, THREE_ENTITIES.find("entity b").unwrap()), THREE_ENTITIES.find("end entity a").unwrap() + "end entity a;\n".len());
+        // Without a previous parse the new source is parsed in full
+        let _ = parser.update(edit, new.to_string());
+        assert_eq!(parser.content, new);
+    }
+
+    #[test]
+    fn test_referenced_entity_from_text() {
+        // Regression: an architecture name starting with "is" put " is" before " of "
+        // and sliced the text backwards
+        assert_eq!(referenced_entity_from_text("architecture is_first of e is\nbegin").unwrap(), "e");
+        assert_eq!(referenced_entity_from_text("architecture rtl of counter is").unwrap(), "counter");
+        assert!(referenced_entity_from_text("architecture rtl is").is_err());
+        assert!(referenced_entity_from_text("architecture rtl of counter").is_err());
+    }
+
+    #[test]
+    fn test_parse_package_seeds_type_context() {
+        let source = include_str!("../../tests/fixtures/bus_pkg.vhd");
+        let mut parser = ASTVHDLParser::new(source.to_string()).unwrap();
+
+        // Like test_parse_simple_entity, this needs the tree-sitter grammar
+        if let Ok(packages) = parser.parse_package() {
+            assert_eq!(packages.len(), 1);
+            assert_eq!(packages[0].constants[1].value.as_deref(), Some("x\"DEADBEEF\""));
+
+            // An entity in another file sees the package types through with_packages
+            let entity_source = &source[source.rfind("library ieee;").unwrap()..];
+            let parser = ASTVHDLParser::new(entity_source.to_string()).unwrap().with_packages(packages);
+            let types = parser.type_context();
+            let bus = types.resolve(&VHDLType::Custom("bus_t".to_string())).unwrap();
+            assert_eq!(bus.bit_width(), Some(32));
+            assert!(types.enum_literals(&VHDLType::Custom("bus_state_t".to_string())).is_some());
+        }
+    }
 }
+
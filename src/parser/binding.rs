@@ -0,0 +1,287 @@
+//! Binding of component instances to entities: configuration specifications in an
+//! architecture (`for u0 : adder use entity work.fast_adder(rtl);`) and configuration
+//! declarations (`configuration cfg of top is for rtl ... end for; end configuration;`)
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::ir::{Architecture, GenerateBlock, Instantiation};
+
+lazy_static! {
+    static ref COMMENT_RE: Regex = Regex::new(r"--[^\n]*").unwrap();
+    static ref COMPONENT_RE: Regex = Regex::new(r"(?i)(?:^|;)\s*component\s+(\w+)").unwrap();
+    static ref BINDING_SPEC_RE: Regex = Regex::new(
+        r"(?is)\bfor\s+(all|others|\w+(?:\s*,\s*\w+)*)\s*:\s*(\w+)\s+use\s+(entity|configuration)\s+(?:\w+\.)?(\w+)(?:\s*\(\s*(\w+)\s*\))?"
+    ).unwrap();
+    static ref CONFIGURATION_RE: Regex =
+        Regex::new(r"(?i)\bconfiguration\s+(\w+)\s+of\s+(\w+)\s+is\s+for\s+(\w+)").unwrap();
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Labels {
+    All,
+    Others,
+    Named(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BindingTarget {
+    Entity { name: String, architecture: Option<String> },
+    Configuration(String),
+}
+
+/// `for <labels> : <component> use entity|configuration ...`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BindingSpec {
+    labels: Labels,
+    component: String,
+    target: BindingTarget,
+}
+
+/// A configuration declaration binding the instances of one architecture
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Configuration {
+    pub(crate) name: String,
+    pub(crate) entity: String,
+    pub(crate) architecture: String,
+    specs: Vec<BindingSpec>,
+}
+
+/// Names of the components declared in an architecture's declarative part
+pub(crate) fn component_declarations(declarative_part: &str) -> Vec<String> {
+    let text = COMMENT_RE.replace_all(declarative_part, "");
+    let mut components: Vec<String> = Vec::new();
+    for caps in COMPONENT_RE.captures_iter(&text) {
+        if !components.iter().any(|c| c.eq_ignore_ascii_case(&caps[1])) {
+            components.push(caps[1].to_string());
+        }
+    }
+    components
+}
+
+/// Configuration specifications in `text`, in source order
+pub(crate) fn binding_specs(text: &str) -> Vec<BindingSpec> {
+    let text = COMMENT_RE.replace_all(text, "");
+    BINDING_SPEC_RE.captures_iter(&text)
+        .map(|caps| {
+            let labels = match caps[1].to_lowercase().as_str() {
+                "all" => Labels::All,
+                "others" => Labels::Others,
+                _ => Labels::Named(caps[1].split(',').map(|label| label.trim().to_string()).collect()),
+            };
+            let target = if caps[3].eq_ignore_ascii_case("entity") {
+                BindingTarget::Entity { name: caps[4].to_string(), architecture: caps.get(5).map(|m| m.as_str().to_string()) }
+            } else {
+                BindingTarget::Configuration(caps[4].to_string())
+            };
+            BindingSpec { labels, component: caps[2].to_string(), target }
+        })
+        .collect()
+}
+
+/// Configuration declarations in a whole source file
+pub(crate) fn configurations(source: &str) -> Vec<Configuration> {
+    let source = COMMENT_RE.replace_all(source, "");
+    CONFIGURATION_RE.captures_iter(&source)
+        .map(|caps| {
+            let name = caps[1].to_string();
+            let start = caps.get(0).map_or(0, |m| m.end());
+            let end_re = Regex::new(&format!(r"(?i)\bend\b\s*(?:configuration\b\s*)?(?:{}\b\s*)?;", regex::escape(&name)))
+                .expect("escaped configuration name is a valid pattern");
+            let end = end_re.find(&source[start..]).map_or(source.len(), |m| start + m.start());
+            Configuration {
+                name,
+                entity: caps[2].to_string(),
+                architecture: caps[3].to_string(),
+                specs: binding_specs(&source[start..end]),
+            }
+        })
+        .collect()
+}
+
+/// Resolve the instances of `arch` (of `entity`) to the entities they are bound to:
+/// `configuration` instantiations through `configurations`, component instances through
+/// the architecture's `specs`, then through a configuration declaration of `entity`.
+/// Unbound component instances keep the default binding to the same-named entity;
+/// bindings that cannot be resolved are described in `diagnostics` and ignored
+pub(crate) fn bind_instances(
+    arch: &mut Architecture,
+    entity: &str,
+    components: &[String],
+    specs: &[BindingSpec],
+    configurations: &[Configuration],
+    diagnostics: &mut Vec<String>,
+) {
+    let arch_name = arch.name.clone();
+    let declared = configurations.iter()
+        .filter(|c| c.entity.eq_ignore_ascii_case(entity) && c.architecture.eq_ignore_ascii_case(&arch_name))
+        .flat_map(|c| c.specs.iter().map(move |spec| (Some(c.name.as_str()), spec)));
+    let specs: Vec<(Option<&str>, &BindingSpec)> = specs.iter().map(|spec| (None, spec)).chain(declared).collect();
+
+    let mut instances: Vec<&mut Instantiation> = arch.instantiations.iter_mut().collect();
+    collect_generate_instances(&mut arch.generate_blocks, &mut instances);
+
+    let mut bound: Vec<String> = Vec::new();
+    for inst in instances.iter_mut() {
+        let Some(configuration) = inst.configuration.clone() else { continue };
+        match configurations.iter().find(|c| c.name.eq_ignore_ascii_case(&configuration)) {
+            Some(c) => bind(inst, &c.entity, Some(&c.architecture)),
+            None => diagnostics.push(format!(
+                "configuration {} of instance {} is not declared in this file; instance left unbound",
+                configuration, inst.label
+            )),
+        }
+        bound.push(inst.label.to_lowercase());
+    }
+
+    for (configuration, spec) in specs {
+        let source = configuration.map_or("configuration specification".to_string(), |c| format!("configuration {}", c));
+        if configuration.is_none() && !components.iter().any(|c| c.eq_ignore_ascii_case(&spec.component)) {
+            diagnostics.push(format!("{}: component {} is not declared; binding ignored", source, spec.component));
+            continue;
+        }
+
+        let mut matched = false;
+        for inst in instances.iter_mut() {
+            let label = inst.label.to_lowercase();
+            let label_matches = match &spec.labels {
+                Labels::All | Labels::Others => true,
+                Labels::Named(labels) => labels.iter().any(|l| l.eq_ignore_ascii_case(&label)),
+            };
+            if !label_matches || bound.contains(&label) || !inst.component.eq_ignore_ascii_case(&spec.component) {
+                continue;
+            }
+            matched = true;
+            bound.push(label);
+
+            match &spec.target {
+                BindingTarget::Entity { name, architecture } => bind(inst, name, architecture.as_deref()),
+                BindingTarget::Configuration(name) => match configurations.iter().find(|c| c.name.eq_ignore_ascii_case(name)) {
+                    Some(c) => {
+                        inst.configuration = Some(c.name.clone());
+                        bind(inst, &c.entity, Some(&c.architecture));
+                    }
+                    None => diagnostics.push(format!(
+                        "{}: configuration {} for instance {} is not declared in this file; instance left unbound",
+                        source, name, inst.label
+                    )),
+                },
+            }
+            if let (Some(configuration), None) = (configuration, &inst.configuration) {
+                inst.configuration = Some(configuration.to_string());
+            }
+        }
+        if !matched {
+            diagnostics.push(format!(
+                "{}: no instance of component {} in architecture {} of {}; binding ignored",
+                source, spec.component, arch_name, entity
+            ));
+        }
+    }
+}
+
+fn bind(inst: &mut Instantiation, entity: &str, architecture: Option<&str>) {
+    inst.component = entity.to_string();
+    inst.architecture = architecture.map(str::to_string);
+}
+
+fn collect_generate_instances<'a>(blocks: &'a mut [GenerateBlock], instances: &mut Vec<&'a mut Instantiation>) {
+    for block in blocks {
+        instances.extend(block.instantiations.iter_mut());
+        collect_generate_instances(&mut block.generate_blocks, instances);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn architecture(instances: &[(&str, &str)]) -> Architecture {
+        Architecture {
+            name: "rtl".to_string(),
+            signals: vec![],
+            processes: vec![],
+            concurrent_statements: vec![],
+            instantiations: instances.iter()
+                .map(|(label, component)| Instantiation {
+                    label: label.to_string(),
+                    component: component.to_string(),
+                    generic_map: vec![],
+                    port_map: vec![],
+                    architecture: None,
+                    configuration: None,
+                })
+                .collect(),
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_configuration_specification_binds_component() {
+        let declarative_part = "
+            signal s : std_logic;
+            component inv is port(a : in std_logic; y : out std_logic); end component;
+            for u0 : inv use entity work.fast_inv(rtl);
+            for all : buf use configuration work.buf_cfg; -- buf is not declared
+        ";
+        let mut arch = architecture(&[("u0", "inv"), ("u1", "inv")]);
+        let mut diagnostics = Vec::new();
+        bind_instances(
+            &mut arch,
+            "top",
+            &component_declarations(declarative_part),
+            &binding_specs(declarative_part),
+            &[],
+            &mut diagnostics,
+        );
+
+        assert_eq!(arch.instantiations[0].component, "fast_inv");
+        assert_eq!(arch.instantiations[0].architecture.as_deref(), Some("rtl"));
+        // Default binding to the same-named entity
+        assert_eq!(arch.instantiations[1].component, "inv");
+        assert_eq!(arch.instantiations[1].architecture, None);
+        assert_eq!(diagnostics, vec!["configuration specification: component buf is not declared; binding ignored"]);
+    }
+
+    #[test]
+    fn test_configuration_declaration() {
+        let source = "
+            architecture rtl of top is begin end architecture;
+            configuration top_cfg of top is
+                for rtl
+                    for u0, u1 : inv
+                        use entity work.fast_inv(rtl);
+                    end for;
+                    for others : reg use configuration work.reg_cfg;
+                    end for;
+                end for;
+            end configuration top_cfg;
+            configuration reg_cfg of reg is for behav end for; end reg_cfg;
+        ";
+        let configurations = configurations(source);
+        assert_eq!(configurations.len(), 2);
+        assert_eq!((configurations[0].entity.as_str(), configurations[0].architecture.as_str()), ("top", "rtl"));
+        assert_eq!(configurations[0].specs.len(), 2);
+        assert!(configurations[1].specs.is_empty());
+
+        let mut arch = architecture(&[("u0", "inv"), ("u1", "inv"), ("r0", "reg"), ("u9", "other_cfg")]);
+        arch.instantiations[3].configuration = Some("other_cfg".to_string());
+        let mut diagnostics = Vec::new();
+        bind_instances(&mut arch, "top", &[], &[], &configurations, &mut diagnostics);
+
+        let bound: Vec<(&str, Option<&str>, Option<&str>)> = arch.instantiations.iter()
+            .map(|i| (i.component.as_str(), i.architecture.as_deref(), i.configuration.as_deref()))
+            .collect();
+        assert_eq!(bound, vec![
+            ("fast_inv", Some("rtl"), Some("top_cfg")),
+            ("fast_inv", Some("rtl"), Some("top_cfg")),
+            ("reg", Some("behav"), Some("reg_cfg")),
+            ("other_cfg", None, Some("other_cfg")),
+        ]);
+        assert_eq!(diagnostics, vec![
+            "configuration other_cfg of instance u9 is not declared in this file; instance left unbound",
+        ]);
+    }
+}
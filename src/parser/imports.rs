@@ -0,0 +1,191 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+
+lazy_static! {
+    static ref COMMENT_RE: Regex = Regex::new(r"--[^\n]*").unwrap();
+    static ref STRING_RE: Regex = Regex::new(r#""[^"\n]*""#).unwrap();
+    static ref LIBRARY_RE: Regex = Regex::new(r"(?i)\blibrary\s+([\w\s,]+?)\s*;").unwrap();
+    static ref USE_RE: Regex = Regex::new(r"(?i)\buse\s+([\w\s.,]+?)\s*;").unwrap();
+    static ref IDENTIFIER_RE: Regex = Regex::new(r"\b[A-Za-z]\w*\b").unwrap();
+}
+
+/// Packages superseded by `ieee.numeric_std`
+const OBSOLETE_PACKAGES: &[&str] = &["std_logic_arith", "std_logic_unsigned", "std_logic_signed"];
+
+/// IEEE packages and the identifiers that need them
+const IEEE_PACKAGES: &[(&str, &[&str])] = &[
+    ("std_logic_1164", &["std_logic", "std_ulogic", "std_logic_vector", "std_ulogic_vector", "rising_edge", "falling_edge", "to_x01"]),
+    ("numeric_std", &["unsigned", "signed", "to_unsigned", "to_signed", "to_integer", "resize", "shift_left", "shift_right", "rotate_left", "rotate_right", "std_match"]),
+];
+
+/// A `use library.package.items;` clause
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UseClause {
+    pub library: String,
+    pub package: String,
+    /// `all` or a single declaration; `None` for `use library.package;`
+    pub items: Option<String>,
+}
+
+/// Library and use clauses of a file and the problems found in them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportAnalysis {
+    /// Libraries declared or referenced by a use clause, in order of first appearance
+    pub libraries: Vec<String>,
+    pub uses: Vec<UseClause>,
+    /// IEEE packages whose types or functions the file uses
+    pub required_packages: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ImportAnalysis {
+    fn uses_package(&self, library: &str, package: &str) -> bool {
+        self.uses.iter().any(|clause| clause.library == library && clause.package == package)
+    }
+}
+
+/// Extract the library and use clauses of `source`, flag obsolete packages, and
+/// IEEE types or functions used without the `use` clause that provides them
+pub fn analyze_imports(source: &str) -> ImportAnalysis {
+    let source = COMMENT_RE.replace_all(source, "");
+    let source = STRING_RE.replace_all(&source, "\"\"");
+    let mut analysis = ImportAnalysis::default();
+    let mut declared = Vec::new();
+
+    for caps in LIBRARY_RE.captures_iter(&source) {
+        for library in caps[1].split(',').map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty()) {
+            if !declared.contains(&library) {
+                declared.push(library.clone());
+            }
+            if !analysis.libraries.contains(&library) {
+                analysis.libraries.push(library);
+            }
+        }
+    }
+
+    for caps in USE_RE.captures_iter(&source) {
+        for name in caps[1].split(',') {
+            let parts: Vec<String> = name.split('.').map(|p| p.trim().to_lowercase()).collect();
+            if parts.len() < 2 || parts.iter().any(|p| p.is_empty()) {
+                continue;
+            }
+            let clause = UseClause {
+                library: parts[0].clone(),
+                package: parts[1].clone(),
+                items: parts.get(2).cloned(),
+            };
+            // `std` and `work` are visible without a library clause
+            if !declared.contains(&clause.library) && !["std", "work"].contains(&clause.library.as_str()) {
+                analysis.warnings.push(format!(
+                    "use {}.{} has no 'library {}' clause",
+                    clause.library, clause.package, clause.library
+                ));
+            }
+            if !analysis.libraries.contains(&clause.library) {
+                analysis.libraries.push(clause.library.clone());
+            }
+            if !analysis.uses.contains(&clause) {
+                analysis.uses.push(clause);
+            }
+        }
+    }
+
+    for clause in &analysis.uses {
+        if clause.library == "ieee" && OBSOLETE_PACKAGES.contains(&clause.package.as_str()) {
+            analysis.warnings.push(format!(
+                "{} is obsolete, use ieee.numeric_std instead",
+                clause.package
+            ));
+        }
+    }
+
+    let body = USE_RE.replace_all(&LIBRARY_RE.replace_all(&source, ""), "").to_lowercase();
+    let identifiers: Vec<&str> = IDENTIFIER_RE.find_iter(&body).map(|m| m.as_str()).collect();
+    // std_logic_arith also declares the unsigned and signed types
+    let arith = analysis.uses_package("ieee", "std_logic_arith");
+    for (package, names) in IEEE_PACKAGES {
+        let mut used: Vec<&str> = names.iter()
+            .copied()
+            .filter(|name| identifiers.contains(name))
+            .filter(|name| !(arith && matches!(*name, "unsigned" | "signed")))
+            .collect();
+        if used.is_empty() {
+            continue;
+        }
+        analysis.required_packages.push(package.to_string());
+        if !analysis.uses_package("ieee", package) {
+            used.sort_by_key(|name| identifiers.iter().position(|id| id == name));
+            analysis.warnings.push(format!(
+                "{} used without 'use ieee.{}.all'",
+                used.join(", "),
+                package
+            ));
+        }
+    }
+
+    analysis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correct_imports() {
+        let analysis = analyze_imports(r#"
+            library ieee;
+            use ieee.std_logic_1164.all;
+            use ieee.numeric_std.all;
+            use work.bus_pkg.bus_t;
+
+            entity counter is
+                port(clk : in std_logic; q : out std_logic_vector(7 downto 0));
+            end entity counter;
+            architecture rtl of counter is
+                signal count : unsigned(7 downto 0);
+            begin
+                process(clk)
+                begin
+                    if rising_edge(clk) then
+                        count <= count + 1;
+                    end if;
+                end process;
+                q <= std_logic_vector(count);
+            end architecture rtl;
+        "#);
+
+        assert_eq!(analysis.libraries, vec!["ieee", "work"]);
+        assert_eq!(analysis.uses[0], UseClause {
+            library: "ieee".to_string(),
+            package: "std_logic_1164".to_string(),
+            items: Some("all".to_string()),
+        });
+        assert_eq!(analysis.uses[2].items.as_deref(), Some("bus_t"));
+        assert_eq!(analysis.required_packages, vec!["std_logic_1164", "numeric_std"]);
+        assert!(analysis.warnings.is_empty(), "{:?}", analysis.warnings);
+    }
+
+    #[test]
+    fn test_incorrect_imports() {
+        let analysis = analyze_imports(r#"
+            use ieee.std_logic_1164.all;
+            use IEEE.STD_LOGIC_ARITH.ALL; -- to_unsigned(x, 8) in a comment is ignored
+
+            architecture rtl of adder is
+                signal sum : unsigned(8 downto 0);
+            begin
+                sum <= resize(a, 9) + to_unsigned(b, 9);
+            end architecture rtl;
+        "#);
+
+        assert_eq!(analysis.libraries, vec!["ieee"]);
+        assert_eq!(analysis.uses[1].package, "std_logic_arith");
+        assert_eq!(analysis.warnings, vec![
+            "use ieee.std_logic_1164 has no 'library ieee' clause",
+            "use ieee.std_logic_arith has no 'library ieee' clause",
+            "std_logic_arith is obsolete, use ieee.numeric_std instead",
+            "resize, to_unsigned used without 'use ieee.numeric_std.all'",
+        ]);
+    }
+}
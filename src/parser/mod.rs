@@ -1,4 +1,15 @@
 pub mod tree_sitter_vhdl;
 pub mod ast_parser;
+pub mod binding;
+pub mod imports;
+pub mod package;
+pub mod parse_error;
+pub mod stimulus;
+pub mod verilog_parser;
 
-pub use ast_parser::ASTVHDLParser;
\ No newline at end of file
+pub use ast_parser::{attach_architecture, ASTVHDLParser, InputEdit};
+pub use imports::{analyze_imports, ImportAnalysis, UseClause};
+pub use package::{Constant, FunctionDecl, FunctionParameter, Package, PackageRegistry, PackageType, TypeDeclaration};
+pub use parse_error::ParseError;
+pub use stimulus::{StimulusPattern, StimulusValue};
+pub use verilog_parser::VerilogModuleParser;
\ No newline at end of file
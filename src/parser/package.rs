@@ -0,0 +1,372 @@
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::ir::{TypeResolutionContext, VHDLType, VectorRange};
+
+lazy_static! {
+    static ref COMMENT_RE: Regex = Regex::new(r"--[^\n]*").unwrap();
+    static ref PACKAGE_RE: Regex = Regex::new(r"(?i)\bpackage\s+(\w+)\s+is\b").unwrap();
+    static ref TYPE_RE: Regex = Regex::new(r"(?is)\b(?:sub)?type\s+(\w+)\s+is\s+(.*?);").unwrap();
+    static ref ARRAY_RE: Regex =
+        Regex::new(r"(?is)^array\s*\((.+)\)\s*of\s+(std_logic|std_ulogic|bit)$").unwrap();
+    static ref SUBTYPE_RE: Regex =
+        Regex::new(r"(?is)^(\w+)\s*(?:\((.+)\))?(?:\s+range\s+.+)?$").unwrap();
+    static ref PACKAGE_BODY_RE: Regex = Regex::new(r"(?i)\bpackage\s+body\s+(\w+)\s+is\b").unwrap();
+    static ref CONSTANT_RE: Regex =
+        Regex::new(r"(?is)\bconstant\s+(\w+(?:\s*,\s*\w+)*)\s*:\s*(.+?)(?:\s*:=\s*(.+?))?\s*;").unwrap();
+    static ref FUNCTION_RE: Regex =
+        Regex::new(r"(?is)\b(?:(?:pure|impure)\s+)?function\s+(\w+)\s*(?:\((.*?)\))?\s*return\s+([\w.]+)").unwrap();
+}
+
+/// Type definition declared in a package
+#[derive(Debug, Clone, PartialEq)]
+pub enum PackageType {
+    /// Subtype or type mapping onto a built-in type, or naming another package type
+    Alias(VHDLType),
+    Enumeration(Vec<String>),
+}
+
+/// `type` or `subtype` declaration of a package
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeDeclaration {
+    pub name: String,
+    /// `None` for records, memories and other composite types
+    pub definition: Option<PackageType>,
+}
+
+/// Package constant; a deferred constant takes its value from the package body
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constant {
+    pub name: String,
+    pub constant_type: String,
+    pub value: Option<String>,
+}
+
+/// Formal parameter of a function prototype, without its class and mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionParameter {
+    pub name: String,
+    pub param_type: String,
+}
+
+/// Function prototype declared in a package
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionDecl {
+    pub name: String,
+    pub parameters: Vec<FunctionParameter>,
+    pub return_type: String,
+}
+
+/// Declarations of a VHDL package visible to the designs that `use` it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Package {
+    pub name: String,
+    pub types: Vec<TypeDeclaration>,
+    pub constants: Vec<Constant>,
+    pub functions: Vec<FunctionDecl>,
+}
+
+impl Package {
+    /// Package `name` from the text between `package name is` and its `end`, without comments
+    fn from_declarations(name: &str, declarations: &str) -> Self {
+        let types = TYPE_RE.captures_iter(declarations)
+            .map(|decl| TypeDeclaration {
+                name: decl[1].to_string(),
+                definition: parse_type_definition(&decl[2]),
+            })
+            .collect();
+
+        let constants = CONSTANT_RE.captures_iter(declarations)
+            .flat_map(|decl| {
+                let constant_type = decl[2].trim().to_string();
+                let value = decl.get(3).map(|value| value.as_str().trim().to_string());
+                decl[1].split(',')
+                    .map(|name| Constant { name: name.trim().to_string(), constant_type: constant_type.clone(), value: value.clone() })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let functions = FUNCTION_RE.captures_iter(declarations)
+            .map(|decl| FunctionDecl {
+                name: decl[1].to_string(),
+                parameters: decl.get(2).map_or_else(Vec::new, |list| parse_parameters(list.as_str())),
+                return_type: decl[3].to_string(),
+            })
+            .collect();
+
+        Self { name: name.to_string(), types, constants, functions }
+    }
+
+    /// Package declared in `text`, a `package ... end package;` declaration
+    pub(crate) fn from_declaration_text(text: &str) -> Option<Self> {
+        let text = COMMENT_RE.replace_all(text, "");
+        let caps = PACKAGE_RE.captures(&text)?;
+        let start = caps.get(0).map_or(0, |m| m.end());
+        let end = package_end(&text, &caps[1], start);
+        Some(Self::from_declarations(&caps[1], &text[start..end]))
+    }
+
+    /// Fill in the values of deferred constants from `text`, this package's body
+    pub(crate) fn add_body_text(&mut self, text: &str) {
+        let text = COMMENT_RE.replace_all(text, "");
+        let body = Self::from_declarations(&self.name, &text);
+        for constant in self.constants.iter_mut().filter(|constant| constant.value.is_none()) {
+            constant.value = body.constants.iter()
+                .find(|defined| defined.name.eq_ignore_ascii_case(&constant.name))
+                .and_then(|defined| defined.value.clone());
+        }
+    }
+}
+
+/// Name of the package whose body is `text`
+pub(crate) fn package_body_name(text: &str) -> Option<String> {
+    PACKAGE_BODY_RE.captures(text).map(|caps| caps[1].to_string())
+}
+
+/// Start of the `end [package] [name];` closing the package declared before `start`
+fn package_end(source: &str, package: &str, start: usize) -> usize {
+    let end_re = Regex::new(&format!(r"(?i)\bend\b\s*(?:package\b\s*)?(?:{}\b\s*)?;", regex::escape(package)))
+        .expect("escaped package name is a valid pattern");
+    end_re.find(&source[start..]).map_or(source.len(), |m| start + m.start())
+}
+
+/// `a, b : in word_t; c : natural` -> a, b and c with their types
+fn parse_parameters(list: &str) -> Vec<FunctionParameter> {
+    let mut parameters = Vec::new();
+    for declaration in list.split(';') {
+        let Some((names, param_type)) = declaration.split_once(':') else {
+            continue;
+        };
+        let names = strip_leading_word(names.trim(), &["constant", "signal", "variable", "file"]);
+        let param_type = param_type.split_once(":=").map_or(param_type, |(ty, _)| ty);
+        let param_type = strip_leading_word(param_type.trim(), &["in", "out", "inout", "buffer"]).trim_end();
+        for name in names.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            parameters.push(FunctionParameter { name: name.to_string(), param_type: param_type.to_string() });
+        }
+    }
+    parameters
+}
+
+/// `text` without its first word when that is one of `words`
+fn strip_leading_word<'a>(text: &'a str, words: &[&str]) -> &'a str {
+    text.split_once(char::is_whitespace)
+        .filter(|(first, _)| words.iter().any(|word| first.eq_ignore_ascii_case(word)))
+        .map_or(text, |(_, rest)| rest.trim_start())
+}
+
+/// Types declared in the VHDL packages seen so far, for resolving `VHDLType::Custom`
+/// names in entities that `use` those packages
+#[derive(Debug, Clone, Default)]
+pub struct PackageRegistry {
+    /// Lower-cased type name -> (package, definition)
+    types: BTreeMap<String, (String, PackageType)>,
+}
+
+impl PackageRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the types of every package declared in `source`; returns how many were
+    /// added. Records, memories and other composite types are skipped
+    pub fn add_source(&mut self, source: &str) -> usize {
+        let source = COMMENT_RE.replace_all(source, "");
+        let mut added = 0;
+
+        for caps in PACKAGE_RE.captures_iter(&source) {
+            let start = caps.get(0).map_or(0, |m| m.end());
+            let end = package_end(&source, &caps[1], start);
+            added += self.add_package(&Package::from_declarations(&caps[1], &source[start..end]));
+        }
+        added
+    }
+
+    /// Register the types of `package` that map onto a supported type; returns how many
+    pub fn add_package(&mut self, package: &Package) -> usize {
+        let mut added = 0;
+        for declaration in &package.types {
+            if let Some(definition) = &declaration.definition {
+                self.types.insert(declaration.name.to_lowercase(), (package.name.clone(), definition.clone()));
+                added += 1;
+            }
+        }
+        added
+    }
+
+    pub fn add_file(&mut self, path: &Path) -> Result<usize> {
+        let source = std::fs::read_to_string(path)
+            .context(format!("Failed to read VHDL file: {:?}", path))?;
+        Ok(self.add_source(&source))
+    }
+
+    /// Registry of the packages declared in the file at `path`
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let mut registry = Self::new();
+        registry.add_file(path)?;
+        Ok(registry)
+    }
+
+    /// Definition of `name` and the package declaring it
+    pub fn get(&self, name: &str) -> Option<(&str, &PackageType)> {
+        self.types.get(&name.to_lowercase()).map(|(package, definition)| (package.as_str(), definition))
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Resolution context mapping every registered type to its definition
+    pub fn type_context(&self) -> TypeResolutionContext {
+        self.types.iter().fold(TypeResolutionContext::new(), |types, (name, (_, definition))| match definition {
+            PackageType::Alias(resolved) => types.with_type(name, resolved.clone()),
+            PackageType::Enumeration(literals) => types.with_enum(name, literals.clone()),
+        })
+    }
+}
+
+/// Resolution context for the `type` and `subtype` declarations in `declarations`, such
+/// as an architecture's declarative part
+pub(crate) fn declared_types(declarations: &str) -> TypeResolutionContext {
+    let declarations = COMMENT_RE.replace_all(declarations, "");
+    let mut registry = PackageRegistry::new();
+    registry.add_package(&Package::from_declarations("", &declarations));
+    registry.type_context()
+}
+
+/// Definition after `type name is` / `subtype name is`, or `None` when unsupported
+fn parse_type_definition(definition: &str) -> Option<PackageType> {
+    let definition = definition.trim();
+
+    if let Some(inner) = definition.strip_prefix('(').and_then(|d| d.strip_suffix(')')) {
+        let literals: Vec<String> = inner.split(',').map(|literal| literal.trim().to_string()).collect();
+        let identifiers = literals.iter()
+            .all(|l| l.starts_with(|c: char| c.is_ascii_alphabetic()) && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+        return identifiers.then_some(PackageType::Enumeration(literals));
+    }
+
+    if let Some(caps) = ARRAY_RE.captures(definition) {
+        let range = VectorRange::from_vhdl(&caps[1])?;
+        return Some(PackageType::Alias(if caps[2].eq_ignore_ascii_case("bit") {
+            VHDLType::BitVector(range)
+        } else {
+            VHDLType::StdLogicVector(range)
+        }));
+    }
+
+    if definition.to_lowercase().starts_with("range ") {
+        return Some(PackageType::Alias(VHDLType::Integer));
+    }
+
+    let caps = SUBTYPE_RE.captures(definition)?;
+    let name = caps[1].to_lowercase();
+    let resolved = match (name.as_str(), caps.get(2)) {
+        ("std_logic" | "std_ulogic", None) => VHDLType::StdLogic,
+        ("bit", None) => VHDLType::Bit,
+        ("boolean", None) => VHDLType::Boolean,
+        ("integer", None) => VHDLType::Integer,
+        ("natural", None) => VHDLType::Natural,
+        ("positive", None) => VHDLType::Positive,
+        ("std_logic_vector" | "std_ulogic_vector", Some(range)) => VHDLType::StdLogicVector(VectorRange::from_vhdl(range.as_str())?),
+        ("bit_vector", Some(range)) => VHDLType::BitVector(VectorRange::from_vhdl(range.as_str())?),
+        ("signed", Some(range)) => VHDLType::Signed(VectorRange::from_vhdl(range.as_str())?),
+        ("unsigned", Some(range)) => VHDLType::Unsigned(VectorRange::from_vhdl(range.as_str())?),
+        (_, None) => VHDLType::Custom(name),
+        _ => return None,
+    };
+    Some(PackageType::Alias(resolved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TYPES_PKG: &str = r#"
+library ieee;
+use ieee.std_logic_1164.all;
+
+package types_pkg is
+    subtype word_t is std_logic_vector(15 downto 0); -- bus word
+    subtype data_t is word_t;
+    type state_t is (IDLE, LOAD, RUN);
+    type nibble_t is array (3 downto 0) of std_logic;
+    type count_t is range 0 to 255;
+    type regs_t is record
+        a : std_logic;
+    end record;
+    type mem_t is array (0 to 15) of word_t;
+end package types_pkg;
+
+package body types_pkg is
+    type hidden_t is (A, B);
+end package body;
+"#;
+
+    #[test]
+    fn test_collects_package_types() {
+        let mut registry = PackageRegistry::new();
+        assert_eq!(registry.add_source(TYPES_PKG), 5);
+
+        let word = VectorRange { left: 15.into(), right: 0.into(), downto: true };
+        assert_eq!(registry.get("WORD_T"), Some(("types_pkg", &PackageType::Alias(VHDLType::StdLogicVector(word.clone())))));
+        assert_eq!(registry.get("data_t").unwrap().1, &PackageType::Alias(VHDLType::Custom("word_t".to_string())));
+        assert_eq!(registry.get("state_t").unwrap().1, &PackageType::Enumeration(vec!["IDLE".to_string(), "LOAD".to_string(), "RUN".to_string()]));
+        assert_eq!(registry.get("count_t").unwrap().1, &PackageType::Alias(VHDLType::Integer));
+        assert!(registry.get("regs_t").is_none());
+        assert!(registry.get("mem_t").is_none());
+        assert!(registry.get("hidden_t").is_none());
+
+        let types = registry.type_context();
+        assert_eq!(types.resolve(&VHDLType::Custom("data_t".to_string())), Some(VHDLType::StdLogicVector(word)));
+        let nibble = types.resolve(&VHDLType::Custom("nibble_t".to_string())).unwrap();
+        assert_eq!(nibble.bit_width(), Some(4));
+    }
+
+    #[test]
+    fn test_declared_types() {
+        let types = declared_types("type state_t is (IDLE, BUSY, DONE); -- FSM\nsubtype byte_t is std_logic_vector(7 downto 0);\nsignal state : state_t;");
+        assert_eq!(types.resolve(&VHDLType::Custom("STATE_T".to_string())).and_then(|ty| ty.bit_width()), Some(2));
+        assert_eq!(types.resolve(&VHDLType::Custom("byte_t".to_string())).and_then(|ty| ty.bit_width()), Some(8));
+        assert!(declared_types("signal a : std_logic;").is_empty());
+    }
+
+    #[test]
+    fn test_package_declarations_and_body() {
+        let source = include_str!("../../tests/fixtures/bus_pkg.vhd");
+        let body_start = source.find("package body").unwrap();
+        let mut package = Package::from_declaration_text(&source[..body_start]).unwrap();
+        assert_eq!(package.name, "bus_pkg");
+
+        let bus = VectorRange { left: 31.into(), right: 0.into(), downto: true };
+        assert_eq!(package.types[0], TypeDeclaration {
+            name: "bus_t".to_string(),
+            definition: Some(PackageType::Alias(VHDLType::StdLogicVector(bus))),
+        });
+        assert_eq!(package.types[1].name, "bus_state_t");
+
+        assert_eq!(package.constants[0], Constant {
+            name: "BUS_WIDTH".to_string(),
+            constant_type: "integer".to_string(),
+            value: Some("32".to_string()),
+        });
+        assert_eq!(package.constants[1].value, None);
+        package.add_body_text(&source[body_start..]);
+        assert_eq!(package.constants[1].value.as_deref(), Some("x\"DEADBEEF\""));
+
+        let pack = &package.functions[1];
+        assert_eq!((package.functions.len(), pack.name.as_str(), pack.return_type.as_str()), (2, "pack", "bus_t"));
+        let parameters: Vec<(&str, &str)> = pack.parameters.iter().map(|p| (p.name.as_str(), p.param_type.as_str())).collect();
+        assert_eq!(parameters, vec![("hi", "std_logic_vector(15 downto 0)"), ("lo", "std_logic_vector(15 downto 0)")]);
+        assert_eq!(package.functions[0].parameters[0], FunctionParameter { name: "data".to_string(), param_type: "bus_t".to_string() });
+
+        let registry = PackageRegistry::from_file(Path::new("tests/fixtures/bus_pkg.vhd")).unwrap();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.get("BUS_T").unwrap().0, "bus_pkg");
+    }
+}
@@ -0,0 +1,104 @@
+//! Syntax errors tree-sitter recovered from, located in the source
+
+use serde::Serialize;
+use std::ops::Range;
+use tree_sitter::Node;
+
+/// An `ERROR` or `MISSING` node of the syntax tree
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParseError {
+    /// `ERROR` for text the grammar could not place, `MISSING <kind>` for a token
+    /// tree-sitter inserted to recover
+    pub kind: String,
+    pub byte_range: Range<usize>,
+    /// 1-based line and column of the start of the error
+    pub line: usize,
+    pub column: usize,
+    /// The source line the error starts on, trimmed
+    pub snippet: String,
+    /// Entity whose declaration or architecture contains the error
+    pub entity: Option<String>,
+}
+
+impl ParseError {
+    pub(crate) fn from_node(node: &Node, source: &str, entity: Option<String>) -> Self {
+        let kind = if node.is_missing() {
+            format!("MISSING {}", node.kind())
+        } else {
+            "ERROR".to_string()
+        };
+        let start = node.start_position();
+        Self {
+            kind,
+            byte_range: node.byte_range(),
+            line: start.row + 1,
+            column: start.column + 1,
+            snippet: source.lines().nth(start.row).unwrap_or("").trim().to_string(),
+            entity,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}:{}: syntax error ({})", self.line, self.column, self.kind)?;
+        if let Some(entity) = &self.entity {
+            write!(f, " in {}", entity)?;
+        }
+        write!(f, ": `{}`", self.snippet)
+    }
+}
+
+/// Outermost `ERROR` nodes and all `MISSING` nodes below `root`, in source order
+pub(crate) fn error_nodes<'a>(root: &Node<'a>) -> Vec<Node<'a>> {
+    let mut errors = Vec::new();
+    let mut queue = vec![*root];
+    while let Some(node) = queue.pop() {
+        if node.is_error() || node.is_missing() {
+            errors.push(node);
+            continue;
+        }
+        if node.has_error() {
+            let mut cursor = node.walk();
+            queue.extend(node.children(&mut cursor));
+        }
+    }
+    errors.sort_by_key(|node| node.start_byte());
+    errors
+}
+
+/// `Parse tree contains errors: <first error> (and N more)`, for callers that need a clean file
+pub(crate) fn strict_error(errors: &[ParseError]) -> anyhow::Error {
+    let more = match errors.len() {
+        0 | 1 => String::new(),
+        n => format!(" (and {} more)", n - 1),
+    };
+    match errors.first() {
+        Some(first) => anyhow::anyhow!("Parse tree contains errors: {}{}", first, more),
+        None => anyhow::anyhow!("Parse tree contains errors"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_display() {
+        let error = ParseError {
+            kind: "ERROR".to_string(),
+            byte_range: 120..131,
+            line: 9,
+            column: 17,
+            snippet: "count <= ;".to_string(),
+            entity: Some("broken".to_string()),
+        };
+        assert_eq!(error.to_string(), "line 9:17: syntax error (ERROR) in broken: `count <= ;`");
+
+        let second = ParseError { entity: None, line: 12, ..error.clone() };
+        assert_eq!(
+            strict_error(&[error, second]).to_string(),
+            "Parse tree contains errors: line 9:17: syntax error (ERROR) in broken: `count <= ;` (and 1 more)"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref ARRAY_TYPE_RE: Regex =
+        Regex::new(r"(?is)^\s*type\s+(\w+)\s+is\s+array\s*\((.+?)\)\s*of\s+(.+?)\s*;?\s*$").unwrap();
+    static ref CONSTANT_RE: Regex =
+        Regex::new(r"(?is)^\s*constant\s+(\w+)\s*:\s*(\w+)\s*:=\s*\((.*)\)\s*;?\s*$").unwrap();
+    static ref VECTOR_TYPE_RE: Regex = Regex::new(
+        r"(?i)^(?:std_logic_vector|std_ulogic_vector|bit_vector|signed|unsigned)\s*\(\s*(\d+)\s+(?:downto|to)\s+(\d+)\s*\)$"
+    ).unwrap();
+    static ref HEX_RE: Regex = Regex::new(r#"(?i)^x"([0-9a-f_]+)"$"#).unwrap();
+    static ref BINARY_RE: Regex = Regex::new(r#"(?i)^b?"([01_]+)"$"#).unwrap();
+}
+
+/// Syntax-tree node kinds of array type definitions across grammar versions
+pub(crate) const ARRAY_DEFINITION_KINDS: &[&str] =
+    &["array_type_definition", "constrained_array_definition", "unbounded_array_definition"];
+
+/// Array types declared in a file: lower-cased name -> element width in bits, if known
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ArrayTypes(HashMap<String, Option<u32>>);
+
+impl ArrayTypes {
+    /// Record `type name is array (...) of element;`, ignoring other declarations
+    pub(crate) fn add_declaration(&mut self, declaration: &str) {
+        if let Some(caps) = ARRAY_TYPE_RE.captures(declaration) {
+            self.0.insert(caps[1].to_lowercase(), element_width(&caps[3]));
+        }
+    }
+}
+
+/// Bits of a scalar or constrained vector element type
+fn element_width(element: &str) -> Option<u32> {
+    let element = element.trim();
+    if ["std_logic", "std_ulogic", "bit"].iter().any(|t| element.eq_ignore_ascii_case(t)) {
+        return Some(1);
+    }
+    let caps = VECTOR_TYPE_RE.captures(element)?;
+    let (left, right): (u32, u32) = (caps[1].parse().ok()?, caps[2].parse().ok()?);
+    Some(left.abs_diff(right) + 1)
+}
+
+/// One element of a stimulus constant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StimulusValue {
+    /// Literal as written, e.g. `X"A5"`
+    pub literal: String,
+    /// Numeric value, `None` for literals such as `(others => '0')` or named constants
+    pub value: Option<u128>,
+}
+
+/// A constant array of stimulus vectors, one element per time step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StimulusPattern {
+    pub name: String,
+    pub type_name: String,
+    /// Element width from the array type, else from the widest bit-string literal
+    pub width: Option<u32>,
+    pub values: Vec<StimulusValue>,
+}
+
+impl StimulusPattern {
+    /// Pattern from a `constant NAME : array_type := (...);` declaration whose type is one
+    /// of `arrays`
+    pub(crate) fn from_declaration(declaration: &str, arrays: &ArrayTypes) -> Option<Self> {
+        let caps = CONSTANT_RE.captures(declaration)?;
+        let declared_width = *arrays.0.get(&caps[2].to_lowercase())?;
+
+        let mut literal_width = None;
+        let values: Vec<StimulusValue> = split_aggregate(&caps[3]).into_iter()
+            .map(|element| {
+                // Named associations keep their value: `0 => X"00"`
+                let literal = element.split_once("=>")
+                    .filter(|(choice, _)| !choice.contains('('))
+                    .map_or(element, |(_, value)| value.trim());
+                let (value, width) = parse_literal(literal).unzip();
+                literal_width = literal_width.max(width.flatten());
+                StimulusValue { literal: literal.to_string(), value }
+            })
+            .collect();
+
+        Some(Self {
+            name: caps[1].to_string(),
+            type_name: caps[2].to_string(),
+            width: declared_width.or(literal_width),
+            values,
+        })
+    }
+
+    /// Step-by-step table of the values in hex and binary
+    pub fn waveform_table(&self) -> String {
+        let width = self.width.unwrap_or(0) as usize;
+        let (hex_digits, binary_digits) = (width.div_ceil(4).max(1), width.max(1));
+        let hex_column = (hex_digits + 2).max(3);
+        let binary_column = binary_digits.max(6);
+
+        let mut output = format!(
+            "{} : {} ({} steps{})\n",
+            self.name,
+            self.type_name,
+            self.values.len(),
+            self.width.map(|w| format!(" x {} bits", w)).unwrap_or_default()
+        );
+        output.push_str(&format!("  step | {:<hex_column$} | binary\n", "hex"));
+        output.push_str(&format!("  -----+-{}-+-{}\n", "-".repeat(hex_column), "-".repeat(binary_column)));
+        for (step, value) in self.values.iter().enumerate() {
+            let (hex, binary) = match value.value {
+                Some(v) => (format!("0x{:0hex_digits$X}", v), format!("{:0binary_digits$b}", v)),
+                None => ("?".to_string(), value.literal.clone()),
+            };
+            output.push_str(&format!("  {:>4} | {:<hex_column$} | {}\n", step, hex, binary));
+        }
+        output
+    }
+}
+
+/// Value and bit width (unknown for integers) of a literal
+fn parse_literal(literal: &str) -> Option<(u128, Option<u32>)> {
+    if let Some(caps) = HEX_RE.captures(literal) {
+        let digits = caps[1].replace('_', "");
+        return Some((u128::from_str_radix(&digits, 16).ok()?, Some(digits.len() as u32 * 4)));
+    }
+    if let Some(caps) = BINARY_RE.captures(literal) {
+        let digits = caps[1].replace('_', "");
+        return Some((u128::from_str_radix(&digits, 2).ok()?, Some(digits.len() as u32)));
+    }
+    match literal {
+        "'0'" => Some((0, Some(1))),
+        "'1'" => Some((1, Some(1))),
+        _ => literal.parse().ok().map(|value| (value, None)),
+    }
+}
+
+/// Top-level elements of an aggregate body, splitting on commas outside parentheses
+fn split_aggregate(body: &str) -> Vec<&str> {
+    let mut elements = Vec::new();
+    let (mut depth, mut start, mut in_string) = (0, 0, false);
+    for (i, c) in body.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                elements.push(body[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    elements.push(body[start..].trim());
+    elements.retain(|element| !element.is_empty());
+    elements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_four_entry_stimulus_table() {
+        let mut arrays = ArrayTypes::default();
+        arrays.add_declaration("type stim_array is array(0 to 3) of std_logic_vector(7 downto 0);");
+        arrays.add_declaration("type state_t is (IDLE, RUN);");
+
+        let pattern = StimulusPattern::from_declaration(
+            "constant STIM : stim_array := (X\"00\", X\"FF\", \"10100101\", 3 => x\"0F\");",
+            &arrays,
+        ).unwrap();
+        assert_eq!(pattern.width, Some(8));
+        assert_eq!(pattern.values.iter().map(|v| v.value).collect::<Vec<_>>(), vec![Some(0), Some(255), Some(0xA5), Some(0x0F)]);
+        assert_eq!(pattern.waveform_table(), "\
+STIM : stim_array (4 steps x 8 bits)
+  step | hex  | binary
+  -----+------+---------
+     0 | 0x00 | 00000000
+     1 | 0xFF | 11111111
+     2 | 0xA5 | 10100101
+     3 | 0x0F | 00001111
+");
+
+        // Not an array-typed constant
+        assert!(StimulusPattern::from_declaration("constant WIDTH : integer := (8);", &arrays).is_none());
+    }
+}
@@ -0,0 +1,283 @@
+//! Port lists of existing Verilog and SystemVerilog modules, read back into the IR to
+//! compare a reference design with the transpiled output
+
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+use crate::ir::{Entity, Generic, Port, PortDirection, RangeExpr, VHDLType, VectorRange};
+
+lazy_static! {
+    static ref LINE_COMMENT_RE: Regex = Regex::new(r"//[^\n]*").unwrap();
+    static ref BLOCK_COMMENT_RE: Regex = Regex::new(r"(?s)/\*.*?\*/").unwrap();
+    static ref MODULE_RE: Regex = Regex::new(r"\bmodule\s+(?:automatic\s+|static\s+)?(\w+)\s*").unwrap();
+    static ref ENDMODULE_RE: Regex = Regex::new(r"\bendmodule\b").unwrap();
+    static ref PARAMETER_RE: Regex = Regex::new(r"^(?:parameter\s+)?(?:(\w+)\s+)?(?:signed\s+)?(?:\[[^\]]*\]\s*)?(\w+)\s*=\s*(.+)$").unwrap();
+    static ref BODY_PARAMETER_RE: Regex = Regex::new(r"\bparameter\b([^;]*);").unwrap();
+    static ref BODY_PORT_RE: Regex = Regex::new(r"\b(?:input|output|inout)\b[^;]*;").unwrap();
+    static ref RANGE_RE: Regex = Regex::new(r"\[([^:\]]+):([^\]]+)\]").unwrap();
+}
+
+/// Net and variable keywords that leave a port a plain bit or vector
+const NET_KEYWORDS: &[&str] = &["wire", "reg", "logic", "bit", "var", "tri", "wand", "wor", "unsigned"];
+
+/// Regex-based reader of module headers: names, parameters and ANSI or non-ANSI ports
+pub struct VerilogModuleParser {
+    content: String,
+}
+
+impl VerilogModuleParser {
+    pub fn new(content: String) -> Self {
+        Self { content }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read Verilog file: {}", path.display()))?;
+        Ok(Self::new(content))
+    }
+
+    /// One entity per module, with ports and parameters but no architecture. Port types
+    /// map back to VHDL: `[7:0]` to `std_logic_vector(7 downto 0)`, `signed [7:0]` to
+    /// `signed`, `int`/`integer` to `integer`
+    pub fn parse_modules(&self) -> Result<Vec<Entity>> {
+        let source = BLOCK_COMMENT_RE.replace_all(&self.content, "");
+        let source = LINE_COMMENT_RE.replace_all(&source, "");
+
+        let mut entities = Vec::new();
+        let mut search_from = 0;
+        while let Some(caps) = MODULE_RE.captures_at(&source, search_from) {
+            let name = caps[1].to_string();
+            let header_start = caps.get(0).map_or(0, |m| m.end());
+            let module_end = ENDMODULE_RE.find_at(&source, header_start)
+                .map_or(source.len(), |m| m.start());
+            search_from = module_end.max(header_start);
+            entities.push(parse_module(&name, &source[header_start..module_end])?);
+        }
+        Ok(entities)
+    }
+}
+
+fn parse_module(name: &str, text: &str) -> Result<Entity> {
+    let mut entity = Entity::new(name.to_string());
+    let mut rest = text.trim_start();
+
+    if let Some(after_hash) = rest.strip_prefix('#') {
+        let (list, after) = parenthesized(after_hash.trim_start())
+            .context(format!("Unterminated parameter list in module {}", name))?;
+        for parameter in split_top_level(list) {
+            entity.add_generic(parse_parameter(parameter)
+                .context(format!("Unsupported parameter in module {}: '{}'", name, parameter))?);
+        }
+        rest = after.trim_start();
+    }
+
+    let (list, body) = match parenthesized(rest) {
+        Some((list, body)) => (list, body),
+        None => ("", rest),
+    };
+    let items = split_top_level(list);
+    let ansi = items.first().is_some_and(|first| first.contains(char::is_whitespace) || first.contains('['));
+
+    if ansi {
+        let mut previous: Option<Port> = None;
+        for declaration in items {
+            let port = parse_port(declaration, previous.as_ref())
+                .context(format!("Unsupported port declaration in module {}: '{}'", name, declaration))?;
+            previous = Some(port.clone());
+            entity.add_port(port);
+        }
+        return Ok(entity);
+    }
+
+    // Non-ANSI: the header names the ports, declarations in the body give their types
+    let mut declared: Vec<Port> = Vec::new();
+    for declaration in BODY_PORT_RE.find_iter(body) {
+        let mut previous: Option<Port> = None;
+        for item in split_top_level(declaration.as_str().trim_end_matches(';')) {
+            let port = parse_port(item, previous.as_ref())
+                .context(format!("Unsupported port declaration in module {}: '{}'", name, item))?;
+            previous = Some(port.clone());
+            declared.push(port);
+        }
+    }
+    for caps in BODY_PARAMETER_RE.captures_iter(body) {
+        for parameter in split_top_level(&caps[1]) {
+            if let Some(generic) = parse_parameter(parameter) {
+                entity.add_generic(generic);
+            }
+        }
+    }
+    for port_name in items {
+        let port = declared.iter().find(|port| port.name == port_name)
+            .context(format!("Port '{}' of module {} has no direction declaration", port_name, name))?;
+        entity.add_port(port.clone());
+    }
+    Ok(entity)
+}
+
+/// `parameter int W = 8`, `W = 8` or `parameter [7:0] INIT = 8'h00`
+fn parse_parameter(parameter: &str) -> Option<Generic> {
+    let parameter = parameter.split_whitespace().collect::<Vec<_>>().join(" ");
+    let caps = PARAMETER_RE.captures(&parameter)?;
+    let generic_type = match caps.get(1).map(|m| m.as_str()) {
+        Some("string") => "string",
+        Some("real") => "real",
+        Some("bit" | "logic") if !parameter.contains('[') => "std_logic",
+        Some("bit" | "logic") => "std_logic_vector",
+        _ => "integer",
+    };
+    Some(Generic {
+        name: caps[2].to_string(),
+        generic_type: generic_type.to_string(),
+        default_value: Some(caps[3].trim().to_string()),
+    })
+}
+
+/// `[direction] [net/var type] [signed] [range] name`; a bare name continues the previous
+/// declaration's direction and type
+fn parse_port(declaration: &str, previous: Option<&Port>) -> Option<Port> {
+    let declaration = declaration.split('=').next().unwrap_or(declaration).trim();
+    let name_start = declaration.rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .map_or(0, |i| i + 1);
+    let name = &declaration[name_start..];
+    if name.is_empty() {
+        return None;
+    }
+
+    let head = declaration[..name_start].trim();
+    if head.is_empty() {
+        let previous = previous?;
+        return Some(Port::new(name.to_string(), previous.direction.clone(), previous.port_type.clone()));
+    }
+    let direction = match head.split_whitespace().next() {
+        Some("input") => Some(PortDirection::In),
+        Some("output") => Some(PortDirection::Out),
+        Some("inout") => Some(PortDirection::InOut),
+        _ => None,
+    };
+    let direction = direction.or_else(|| previous.map(|port| port.direction.clone()))?;
+    let type_words: Vec<&str> = head.split_whitespace()
+        .filter(|word| !matches!(*word, "input" | "output" | "inout"))
+        .take_while(|word| !word.starts_with('['))
+        .collect();
+
+    Some(Port::new(name.to_string(), direction, port_type(head, &type_words)?))
+}
+
+fn port_type(head: &str, type_words: &[&str]) -> Option<VHDLType> {
+    let range = match RANGE_RE.captures(head) {
+        Some(caps) => {
+            let (left, right) = (RangeExpr::from_vhdl(&caps[1])?, RangeExpr::from_vhdl(&caps[2])?);
+            let downto = !matches!((left.as_literal(), right.as_literal()), (Some(l), Some(r)) if l < r);
+            Some(VectorRange { left, right, downto })
+        }
+        None => None,
+    };
+    let signed = type_words.contains(&"signed");
+
+    if let Some(custom) = type_words.iter().find(|word| !NET_KEYWORDS.contains(word) && **word != "signed") {
+        return Some(match *custom {
+            "int" | "integer" => VHDLType::Integer,
+            custom => VHDLType::Custom(custom.to_string()),
+        });
+    }
+    Some(match (range, signed) {
+        (Some(range), true) => VHDLType::Signed(range),
+        (Some(range), false) => VHDLType::StdLogicVector(range),
+        (None, true) => VHDLType::Signed(VectorRange { left: 0.into(), right: 0.into(), downto: true }),
+        (None, false) => VHDLType::StdLogic,
+    })
+}
+
+/// Contents of the parenthesized group `text` starts with, and the text after it
+pub(crate) fn parenthesized(text: &str) -> Option<(&str, &str)> {
+    if !text.starts_with('(') {
+        return None;
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((&text[1..i], &text[i + 1..]));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split on commas outside parentheses, brackets and braces
+pub(crate) fn split_top_level(list: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(list[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port<'a>(entity: &'a Entity, name: &str) -> &'a Port {
+        entity.ports.iter().find(|port| port.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_parse_ansi_modules() {
+        let source = "// reference design\nmodule counter #(\n    parameter int WIDTH = 8\n) (\n    input  wire clk, rst_n,\n    input  logic signed [WIDTH-1:0] step,\n    output reg [7:0] count, /* low byte */\n    output state_t state\n);\nendmodule\n\nmodule top (input [0:3] sel, inout wire [31:0] bus);\nendmodule\n";
+        let entities = VerilogModuleParser::new(source.to_string()).parse_modules().unwrap();
+        assert_eq!(entities.len(), 2);
+
+        let counter = &entities[0];
+        assert_eq!(counter.name, "counter");
+        assert_eq!(counter.generics[0].name, "WIDTH");
+        assert_eq!(counter.generics[0].default_value.as_deref(), Some("8"));
+        let names: Vec<&str> = counter.ports.iter().map(|port| port.name.as_str()).collect();
+        assert_eq!(names, vec!["clk", "rst_n", "step", "count", "state"]);
+        assert_eq!(port(counter, "rst_n").port_type, VHDLType::StdLogic);
+        assert_eq!(port(counter, "rst_n").direction, PortDirection::In);
+        assert_eq!(port(counter, "step").port_type.to_vhdl(), "signed(WIDTH-1 downto 0)");
+        assert_eq!(port(counter, "count").direction, PortDirection::Out);
+        assert_eq!(port(counter, "count").port_type.bit_width(), Some(8));
+        assert_eq!(port(counter, "state").port_type, VHDLType::Custom("state_t".to_string()));
+
+        let top = &entities[1];
+        assert_eq!(port(top, "sel").port_type.to_vhdl(), "std_logic_vector(0 to 3)");
+        assert_eq!(port(top, "bus").direction, PortDirection::InOut);
+    }
+
+    #[test]
+    fn test_parse_non_ansi_module() {
+        let source = "module counter(clk, reset, count);\n    parameter W = 8;\n    input clk;\n    input reset;\n    output [W-1:0] count;\n    reg [W-1:0] count;\nendmodule\n";
+        let entities = VerilogModuleParser::new(source.to_string()).parse_modules().unwrap();
+        let counter = &entities[0];
+        assert_eq!(counter.generics[0].name, "W");
+        let ports: Vec<(&str, String)> = counter.ports.iter().map(|p| (p.name.as_str(), p.port_type.to_vhdl())).collect();
+        assert_eq!(ports, vec![
+            ("clk", "std_logic".to_string()),
+            ("reset", "std_logic".to_string()),
+            ("count", "std_logic_vector(W-1 downto 0)".to_string()),
+        ]);
+
+        let undeclared = VerilogModuleParser::new("module m(a, b); input a; endmodule".to_string()).parse_modules();
+        assert!(undeclared.unwrap_err().to_string().contains("Port 'b' of module m"));
+    }
+}
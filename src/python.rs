@@ -0,0 +1,155 @@
+//! Python bindings, enabled with the `python` feature and packaged with maturin:
+//!
+//! ```python
+//! import rtl_transpiler
+//! sv = rtl_transpiler.transpile_file("fifo.vhd", target="sv")
+//! ```
+
+// #[pyfunction] expands to an `Into<PyErr>` conversion clippy flags on every function
+#![allow(clippy::useless_conversion)]
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde_json::Value;
+
+use crate::api::{self, Target};
+use crate::ir::Entity;
+
+create_exception!(
+    rtl_transpiler,
+    TranspileError,
+    PyException,
+    "VHDL could not be parsed or transpiled; args are (message, diagnostics)"
+);
+
+/// `TranspileError(message, diagnostics)`, with one diagnostic per error in the cause chain
+fn to_py_err(err: anyhow::Error) -> PyErr {
+    let diagnostics: Vec<String> = err.chain().map(|cause| cause.to_string()).collect();
+    TranspileError::new_err((format!("{:#}", err), diagnostics))
+}
+
+/// Convert a serde JSON value into the equivalent Python object
+fn json_to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.into_py(py),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.into_py(py),
+            (None, Some(u)) => u.into_py(py),
+            _ => n.as_f64().unwrap_or_default().into_py(py),
+        },
+        Value::String(s) => s.into_py(py),
+        Value::Array(items) => {
+            let list = PyList::empty_bound(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            list.into_py(py)
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new_bound(py);
+            for (key, item) in map {
+                dict.set_item(key, json_to_py(py, item)?)?;
+            }
+            dict.into_py(py)
+        }
+    })
+}
+
+/// Entity as a dict mirroring its serde serialization
+fn entity_to_py(py: Python<'_>, entity: &Entity) -> PyResult<PyObject> {
+    let value = serde_json::to_value(entity).map_err(|e| to_py_err(e.into()))?;
+    json_to_py(py, &value)
+}
+
+fn parse_target(target: &str) -> PyResult<Target> {
+    target.parse().map_err(to_py_err)
+}
+
+/// Transpile VHDL source text; `target` is "verilog", "sv" or "spinalhdl"
+#[pyfunction]
+#[pyo3(signature = (vhdl, target = "sv"))]
+fn transpile_str(py: Python<'_>, vhdl: &str, target: &str) -> PyResult<String> {
+    let target = parse_target(target)?;
+    py.allow_threads(|| api::transpile_vhdl_str(vhdl, target))
+        .map(|output| output.code)
+        .map_err(to_py_err)
+}
+
+/// Transpile a VHDL file; `target` is "verilog", "sv" or "spinalhdl"
+#[pyfunction]
+#[pyo3(signature = (path, target = "sv"))]
+fn transpile_file(py: Python<'_>, path: &str, target: &str) -> PyResult<String> {
+    let target = parse_target(target)?;
+    py.allow_threads(|| api::transpile_vhdl_file(path, target))
+        .map(|output| output.code)
+        .map_err(to_py_err)
+}
+
+/// Parse VHDL source text into a list of entity dicts
+#[pyfunction]
+fn parse_entities(py: Python<'_>, vhdl: &str) -> PyResult<Vec<PyObject>> {
+    let entities = py.allow_threads(|| api::parse_entities(vhdl)).map_err(to_py_err)?;
+    entities.iter().map(|entity| entity_to_py(py, entity)).collect()
+}
+
+#[pymodule]
+#[pyo3(name = "rtl_transpiler")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(transpile_str, m)?)?;
+    m.add_function(wrap_pyfunction!(transpile_file, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_entities, m)?)?;
+    m.add("TranspileError", m.py().get_type_bound::<TranspileError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Port, PortDirection, VHDLType, VectorRange};
+
+    #[test]
+    fn test_entity_converts_to_dict() {
+        pyo3::prepare_freethreaded_python();
+        let mut entity = Entity::new("counter".to_string());
+        entity.add_port(Port::new(
+            "count".to_string(),
+            PortDirection::Out,
+            VHDLType::StdLogicVector(VectorRange { left: 7.into(), right: 0.into(), downto: true }),
+        ));
+
+        Python::with_gil(|py| {
+            let dict = entity_to_py(py, &entity).unwrap();
+            let dict = dict.downcast_bound::<PyDict>(py).unwrap();
+
+            let name: String = dict.get_item("name").unwrap().unwrap().extract().unwrap();
+            assert_eq!(name, "counter");
+            assert!(dict.get_item("architecture").unwrap().unwrap().is_none());
+
+            let ports = dict.get_item("ports").unwrap().unwrap();
+            let port = ports.get_item(0).unwrap();
+            let direction: String = port.get_item("direction").unwrap().extract().unwrap();
+            assert_eq!(direction, "Out");
+            let left: i64 = port.get_item("port_type").unwrap()
+                .get_item("StdLogicVector").unwrap()
+                .get_item("left").unwrap()
+                .extract().unwrap();
+            assert_eq!(left, 7);
+        });
+    }
+
+    #[test]
+    fn test_errors_carry_cause_chain() {
+        pyo3::prepare_freethreaded_python();
+        let err = to_py_err(anyhow::anyhow!("Parse tree contains errors").context("Failed to extract entities from VHDL"));
+
+        Python::with_gil(|py| {
+            assert!(err.is_instance_of::<TranspileError>(py));
+            let (message, diagnostics): (String, Vec<String>) = err.value_bound(py).getattr("args").unwrap().extract().unwrap();
+            assert_eq!(message, "Failed to extract entities from VHDL: Parse tree contains errors");
+            assert_eq!(diagnostics, vec!["Failed to extract entities from VHDL", "Parse tree contains errors"]);
+        });
+    }
+}
@@ -0,0 +1,136 @@
+//! Test doubles shared by the unit tests; LLM responses are scripted with `ReplayLLMClient`
+
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::agent::observer::{AgentObserver, Approval};
+use crate::llm::LLMUsage;
+use crate::tools::{BaseToolImpl, Tool, ToolCall, ToolParameter, ToolResult, ToolSchema};
+
+/// Event captured by `RecordingObserver`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObserverEvent {
+    StepStart(u32),
+    ToolCall(String),
+    ToolResult(String, bool),
+    LLMUsage(i32, i32),
+}
+
+/// Observer that records every event in order and denies a configurable set of tools
+pub struct RecordingObserver {
+    events: Mutex<Vec<ObserverEvent>>,
+    denied_tools: Vec<String>,
+}
+
+impl RecordingObserver {
+    pub fn new() -> Self {
+        Self {
+            events: Mutex::new(Vec::new()),
+            denied_tools: Vec::new(),
+        }
+    }
+
+    pub fn with_denied_tools(denied_tools: Vec<String>) -> Self {
+        Self {
+            events: Mutex::new(Vec::new()),
+            denied_tools,
+        }
+    }
+
+    pub fn events(&self) -> Vec<ObserverEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    fn record(&self, event: ObserverEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl Default for RecordingObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AgentObserver for RecordingObserver {
+    fn on_step_start(&self, step: u32, _max_steps: u32) {
+        self.record(ObserverEvent::StepStart(step));
+    }
+
+    fn on_tool_call(&self, tool_call: &ToolCall) -> Approval {
+        self.record(ObserverEvent::ToolCall(tool_call.name.clone()));
+        if self.denied_tools.contains(&tool_call.name) {
+            Approval::Deny(format!("'{}' is not allowed", tool_call.name))
+        } else {
+            Approval::Approve
+        }
+    }
+
+    fn on_tool_result(&self, tool_call: &ToolCall, result: &ToolResult) {
+        self.record(ObserverEvent::ToolResult(tool_call.name.clone(), result.success));
+    }
+
+    fn on_llm_usage(&self, usage: &LLMUsage) {
+        self.record(ObserverEvent::LLMUsage(usage.input_tokens, usage.output_tokens));
+    }
+}
+
+/// Tool echoing its required `text` argument and counting its calls
+pub struct EchoTool {
+    base: BaseToolImpl,
+    pub calls: AtomicUsize,
+    online: Arc<AtomicBool>,
+}
+
+impl EchoTool {
+    pub fn new() -> Self {
+        let parameters = vec![ToolParameter {
+            name: "text".to_string(),
+            param_type: "string".to_string(),
+            description: "Text to echo".to_string(),
+            required: true,
+            default: None,
+            ..Default::default()
+        }];
+        Self {
+            base: BaseToolImpl::new("echo".to_string(), "Echo text".to_string(), parameters),
+            calls: AtomicUsize::new(0),
+            online: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    /// Fail with "broken pipe", like a dropped MCP connection, while `online` is false
+    pub fn with_connection(mut self, online: Arc<AtomicBool>) -> Self {
+        self.online = online;
+        self
+    }
+}
+
+impl Default for EchoTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for EchoTool {
+    fn name(&self) -> &str {
+        &self.base.name
+    }
+
+    fn description(&self) -> &str {
+        &self.base.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.base.schema.clone()
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        if !self.online.load(Ordering::SeqCst) {
+            anyhow::bail!("broken pipe");
+        }
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(arguments["text"].as_str().unwrap_or_default().to_string())
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,15 +51,194 @@ impl ToolResult {
             error: Some(error),
         }
     }
+
+    /// Cut `content` to at most `max_bytes` (on a char boundary) and note how much was dropped
+    pub fn truncate(&mut self, max_bytes: usize) {
+        if self.content.len() <= max_bytes {
+            return;
+        }
+        let mut cut = max_bytes;
+        while !self.content.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let omitted = self.content.len() - cut;
+        self.content.truncate(cut);
+        self.content.push_str(&format!(
+            "\n[...truncated: {} bytes omitted. Use view_range to see specific lines...]",
+            omitted
+        ));
+    }
+
+    /// Split `content` into results of at most `page_size` lines each
+    pub fn split_into_pages(&self, page_size: usize) -> Vec<ToolResult> {
+        let lines: Vec<&str> = self.content.lines().collect();
+        if lines.is_empty() {
+            return vec![self.clone()];
+        }
+        lines.chunks(page_size.max(1))
+            .map(|page| ToolResult { content: page.join("\n"), ..self.clone() })
+            .collect()
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ToolParameter {
     pub name: String,
     pub param_type: String,
     pub description: String,
     pub required: bool,
     pub default: Option<serde_json::Value>,
+    /// Allowed values, emitted as the JSON Schema `enum`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enum_values: Vec<serde_json::Value>,
+    /// Element type of an `array` parameter; "string" when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_items: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_items: Option<usize>,
+    /// Members of an `object` parameter
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<ToolParameter>,
+}
+
+impl ToolParameter {
+    pub fn with_enum_values<V: Into<serde_json::Value>>(mut self, values: impl IntoIterator<Item = V>) -> Self {
+        self.enum_values = values.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Array elements of `items_type`, optionally bounded in number
+    pub fn with_items(mut self, items_type: &str, min_items: Option<usize>, max_items: Option<usize>) -> Self {
+        self.items_type = Some(items_type.to_string());
+        self.min_items = min_items;
+        self.max_items = max_items;
+        self
+    }
+
+    pub fn with_properties(mut self, properties: Vec<ToolParameter>) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Append a message to `violations` for each way `value` (at `path`) breaks this parameter
+    fn validate(&self, value: Option<&serde_json::Value>, path: &str, violations: &mut Vec<String>) {
+        let value = match value {
+            Some(value) if !value.is_null() => value,
+            _ => {
+                if self.required {
+                    violations.push(format!("missing required parameter '{}'", path));
+                }
+                return;
+            }
+        };
+
+        if !type_matches(&self.param_type, value) {
+            violations.push(format!("'{}' must be {}, got {}", path, self.param_type, json_type(value)));
+            return;
+        }
+
+        if !self.enum_values.is_empty() && !self.enum_values.contains(value) {
+            let allowed: Vec<String> = self.enum_values.iter().map(|v| v.to_string()).collect();
+            violations.push(format!("'{}' must be one of {}, got {}", path, allowed.join(", "), value));
+        }
+
+        if let Some(items) = value.as_array() {
+            if let Some(min_items) = self.min_items.filter(|min| items.len() < *min) {
+                violations.push(format!("'{}' needs at least {} item(s), got {}", path, min_items, items.len()));
+            }
+            if let Some(max_items) = self.max_items.filter(|max| items.len() > *max) {
+                violations.push(format!("'{}' allows at most {} item(s), got {}", path, max_items, items.len()));
+            }
+            let items_type = self.items_type.as_deref().unwrap_or("string");
+            for (i, item) in items.iter().enumerate() {
+                if !type_matches(items_type, item) {
+                    violations.push(format!("'{}[{}]' must be {}, got {}", path, i, items_type, json_type(item)));
+                }
+            }
+        }
+
+        if let Some(object) = value.as_object() {
+            for property in &self.properties {
+                property.validate(object.get(&property.name), &format!("{}.{}", path, property.name), violations);
+            }
+        }
+    }
+
+    /// JSON Schema of this parameter's value
+    pub fn json_schema(&self) -> serde_json::Value {
+        let mut prop = serde_json::Map::new();
+        prop.insert("type".to_string(), serde_json::json!(self.param_type));
+        prop.insert("description".to_string(), serde_json::json!(self.description));
+
+        if !self.enum_values.is_empty() {
+            prop.insert("enum".to_string(), serde_json::json!(self.enum_values));
+        }
+
+        if self.param_type == "array" {
+            let items_type = self.items_type.as_deref().unwrap_or("string");
+            prop.insert("items".to_string(), serde_json::json!({ "type": items_type }));
+            if let Some(min_items) = self.min_items {
+                prop.insert("minItems".to_string(), serde_json::json!(min_items));
+            }
+            if let Some(max_items) = self.max_items {
+                prop.insert("maxItems".to_string(), serde_json::json!(max_items));
+            }
+        }
+
+        if self.param_type == "object" && !self.properties.is_empty() {
+            prop.extend(object_schema(&self.properties));
+        }
+
+        if let Some(default) = &self.default {
+            prop.insert("default".to_string(), default.clone());
+        }
+
+        serde_json::Value::Object(prop)
+    }
+}
+
+/// Whether `value` has the JSON Schema `param_type`; unknown types accept anything
+fn type_matches(param_type: &str, value: &serde_json::Value) -> bool {
+    match param_type {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn json_type(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(n) if n.is_f64() => "number",
+        serde_json::Value::Number(_) => "integer",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+/// `type`, `properties` and `required` of an object with `parameters` as members
+fn object_schema(parameters: &[ToolParameter]) -> serde_json::Map<String, serde_json::Value> {
+    let properties: serde_json::Map<String, serde_json::Value> = parameters.iter()
+        .map(|param| (param.name.clone(), param.json_schema()))
+        .collect();
+    let required: Vec<&str> = parameters.iter()
+        .filter(|param| param.required)
+        .map(|param| param.name.as_str())
+        .collect();
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), serde_json::json!("object"));
+    schema.insert("properties".to_string(), serde_json::Value::Object(properties));
+    schema.insert("required".to_string(), serde_json::json!(required));
+    schema
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +248,67 @@ pub struct ToolSchema {
     pub parameters: Vec<ToolParameter>,
 }
 
+/// Argument that does not satisfy its `ToolParameter`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgumentViolation {
+    /// Top-level parameter the violation belongs to
+    pub parameter: String,
+    pub message: String,
+}
+
+impl ToolSchema {
+    /// Every way `arguments` breaks the schema: missing required parameters, wrong JSON
+    /// types, values outside `enum_values` and array lengths out of bounds. Arguments the
+    /// schema does not declare are allowed; `null` counts as absent
+    pub fn validate(&self, arguments: &serde_json::Value) -> Vec<ArgumentViolation> {
+        let Some(arguments) = arguments.as_object() else {
+            return vec![ArgumentViolation {
+                parameter: String::new(),
+                message: format!("arguments must be a JSON object, got {}", json_type(arguments)),
+            }];
+        };
+
+        let mut violations = Vec::new();
+        for param in &self.parameters {
+            let mut messages = Vec::new();
+            param.validate(arguments.get(&param.name), &param.name, &mut messages);
+            violations.extend(messages.into_iter().map(|message| ArgumentViolation { parameter: param.name.clone(), message }));
+        }
+        violations
+    }
+
+    /// Arguments the schema does not declare, for callers that reject them
+    pub fn unknown_arguments(&self, arguments: &serde_json::Value) -> Vec<ArgumentViolation> {
+        let Some(arguments) = arguments.as_object() else {
+            return Vec::new();
+        };
+        arguments.keys()
+            .filter(|name| !self.parameters.iter().any(|param| &param.name == *name))
+            .map(|name| ArgumentViolation { parameter: name.clone(), message: format!("unknown parameter '{}'", name) })
+            .collect()
+    }
+
+    /// Error listing `violations` with the schema of the parameters involved
+    pub fn validation_error(&self, violations: &[ArgumentViolation]) -> String {
+        let mut message = format!("Invalid arguments for tool '{}':\n", self.name);
+        for violation in violations {
+            message.push_str(&format!("- {}\n", violation.message));
+        }
+
+        let involved: Vec<ToolParameter> = self.parameters.iter()
+            .filter(|param| violations.iter().any(|v| v.parameter == param.name))
+            .cloned()
+            .collect();
+        let expected = if involved.is_empty() { &self.parameters } else { &involved };
+        let snippet = serde_json::Value::Object(object_schema(expected));
+        message.push_str(&format!(
+            "Expected:\n{}",
+            serde_json::to_string_pretty(&snippet).unwrap_or_else(|_| snippet.to_string())
+        ));
+        message
+    }
+}
+
 pub trait Tool: Send + Sync {
     fn name(&self) -> &str;
     fn description(&self) -> &str;
@@ -77,6 +318,17 @@ pub trait Tool: Send + Sync {
         Ok(())
     }
 
+    /// Check `arguments` before `execute`; by default against `schema()`, see `ToolSchema::validate`
+    fn validate_arguments(&self, arguments: &serde_json::Value) -> Result<()> {
+        let schema = self.schema();
+        let violations = schema.validate(arguments);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(schema.validation_error(&violations)))
+        }
+    }
+
     fn execute(&self, arguments: &serde_json::Value) -> Result<String>;
 
     fn cleanup(&self) -> Result<()> {
@@ -85,86 +337,31 @@ pub trait Tool: Send + Sync {
 
     fn to_openai_function(&self) -> serde_json::Value {
         let schema = self.schema();
-        let mut properties = serde_json::Map::new();
-        let mut required = Vec::new();
-
-        for param in &schema.parameters {
-            let mut prop = serde_json::Map::new();
-            prop.insert("type".to_string(), serde_json::json!(param.param_type));
-            prop.insert("description".to_string(), serde_json::json!(param.description));
-
-            // Handle array types by adding items specification
-            if param.param_type == "array" {
-                // For view_range, it should be an array of integers
-                if param.name == "view_range" {
-                    prop.insert("items".to_string(), serde_json::json!({
-                        "type": "integer"
-                    }));
-                } else {
-                    // Default to array of strings for other array parameters
-                    prop.insert("items".to_string(), serde_json::json!({
-                        "type": "string"
-                    }));
-                }
-            }
-
-            if let Some(default) = &param.default {
-                prop.insert("default".to_string(), default.clone());
-            }
-
-            properties.insert(param.name.clone(), serde_json::Value::Object(prop));
-
-            if param.required {
-                required.push(param.name.clone());
-            }
-        }
-
         serde_json::json!({
             "name": schema.name,
             "description": schema.description,
-            "parameters": {
-                "type": "object",
-                "properties": properties,
-                "required": required,
-            }
+            "parameters": object_schema(&schema.parameters),
         })
     }
 
     fn to_anthropic_tool(&self) -> serde_json::Value {
         let schema = self.schema();
-        let mut properties = serde_json::Map::new();
-        let mut required = Vec::new();
-
-        for param in &schema.parameters {
-            let mut prop = serde_json::Map::new();
-            prop.insert("type".to_string(), serde_json::json!(param.param_type));
-            prop.insert("description".to_string(), serde_json::json!(param.description));
-
-            if let Some(default) = &param.default {
-                prop.insert("default".to_string(), default.clone());
-            }
-
-            properties.insert(param.name.clone(), serde_json::Value::Object(prop));
-
-            if param.required {
-                required.push(param.name.clone());
-            }
-        }
-
         serde_json::json!({
             "name": schema.name,
             "description": schema.description,
-            "input_schema": {
-                "type": "object",
-                "properties": properties,
-                "required": required,
-            }
+            "input_schema": object_schema(&schema.parameters),
         })
     }
 }
 
+/// Default cap on the size of a single tool result passed back to the LLM
+pub const DEFAULT_MAX_RESULT_BYTES: usize = 16 * 1024;
+
 pub struct ToolExecutor {
     tools: HashMap<String, Arc<dyn Tool>>,
+    max_result_bytes: usize,
+    /// Reject arguments a tool's schema does not declare
+    strict_arguments: bool,
 }
 
 impl ToolExecutor {
@@ -173,22 +370,124 @@ impl ToolExecutor {
         for tool in tools {
             tool_map.insert(tool.name().to_string(), tool);
         }
-        Self { tools: tool_map }
+        Self { tools: tool_map, max_result_bytes: DEFAULT_MAX_RESULT_BYTES, strict_arguments: false }
+    }
+
+    /// Truncate tool results longer than `max_result_bytes`
+    pub fn with_max_result_bytes(mut self, max_result_bytes: usize) -> Self {
+        self.max_result_bytes = max_result_bytes;
+        self
+    }
+
+    /// Fail calls passing arguments the tool's schema does not declare, instead of ignoring them
+    pub fn with_strict_arguments(mut self, strict_arguments: bool) -> Self {
+        self.strict_arguments = strict_arguments;
+        self
+    }
+
+    /// Wrap every tool so calls are described instead of executed
+    pub fn with_dry_run(self, dry_run: bool) -> Self {
+        if !dry_run {
+            return self;
+        }
+
+        let tools = self.tools
+            .into_iter()
+            .map(|(name, tool)| {
+                let tool: Arc<dyn Tool> = Arc::new(crate::tools::DryRunTool::new(tool));
+                (name, tool)
+            })
+            .collect();
+        Self { tools, ..self }
     }
 
     pub fn execute(&self, tool_call: &ToolCall) -> Result<ToolResult> {
-        match self.tools.get(&tool_call.name) {
-            Some(tool) => {
-                match tool.execute(&tool_call.arguments) {
-                    Ok(result) => Ok(ToolResult::success(tool_call.id.clone(), result)),
-                    Err(e) => Ok(ToolResult::error(tool_call.id.clone(), e.to_string())),
-                }
-            }
-            None => Ok(ToolResult::error(
+        let mut result = match self.tools.get(&tool_call.name) {
+            // Tools can rely on their schema; bad arguments never reach them
+            Some(tool) => match self.validate(tool.as_ref(), &tool_call.arguments)
+                .and_then(|()| tool.execute(&tool_call.arguments))
+            {
+                Ok(result) => ToolResult::success(tool_call.id.clone(), result),
+                Err(e) => ToolResult::error(tool_call.id.clone(), e.to_string()),
+            },
+            None => ToolResult::error(
                 tool_call.id.clone(),
                 format!("Tool '{}' not found", tool_call.name),
-            )),
+            ),
+        };
+        result.truncate(self.max_result_bytes);
+        Ok(result)
+    }
+
+    fn validate(&self, tool: &dyn Tool, arguments: &serde_json::Value) -> Result<()> {
+        tool.validate_arguments(arguments)?;
+        if self.strict_arguments {
+            let schema = tool.schema();
+            let unknown = schema.unknown_arguments(arguments);
+            if !unknown.is_empty() {
+                return Err(anyhow::anyhow!(schema.validation_error(&unknown)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Execute calls concurrently on at most `rayon::current_num_threads()` threads.
+    /// Calls sharing a `path` argument run one after another in input order;
+    /// results are returned in the order of `tool_calls`
+    pub fn execute_parallel(&self, tool_calls: &[ToolCall]) -> Vec<Result<ToolResult>> {
+        // Group call indices so that calls touching the same path stay in one group
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut group_by_path: HashMap<&str, usize> = HashMap::new();
+        for (i, tool_call) in tool_calls.iter().enumerate() {
+            match tool_call.arguments.get("path").and_then(|p| p.as_str()) {
+                Some(path) => {
+                    let group = *group_by_path.entry(path).or_insert_with(|| {
+                        groups.push(Vec::new());
+                        groups.len() - 1
+                    });
+                    groups[group].push(i);
+                }
+                None => groups.push(vec![i]),
+            }
         }
+
+        let next_group = AtomicUsize::new(0);
+        let workers = rayon::current_num_threads().clamp(1, groups.len().max(1));
+        let mut results: Vec<Option<Result<ToolResult>>> = tool_calls.iter().map(|_| None).collect();
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let group = next_group.fetch_add(1, Ordering::Relaxed);
+                        let Some(indices) = groups.get(group) else { break };
+                        for &i in indices {
+                            done.push((i, self.execute(&tool_calls[i])));
+                        }
+                    }
+                    done
+                }))
+                .collect();
+
+            for handle in handles {
+                match handle.join() {
+                    Ok(done) => {
+                        for (i, result) in done {
+                            results[i] = Some(result);
+                        }
+                    }
+                    Err(_) => tracing::error!("Tool execution thread panicked"),
+                }
+            }
+        });
+
+        results.into_iter()
+            .zip(tool_calls)
+            .map(|(result, tool_call)| result.unwrap_or_else(|| {
+                Err(anyhow::anyhow!("Tool '{}' did not complete", tool_call.name))
+            }))
+            .collect()
     }
 
     pub fn get_tool(&self, name: &str) -> Option<Arc<dyn Tool>> {
@@ -219,4 +518,275 @@ impl BaseToolImpl {
             },
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Sleeps, then returns its `path`/`tag` arguments; tracks how many calls overlap per path
+    struct SleepTool {
+        base: BaseToolImpl,
+        active: std::sync::Mutex<HashMap<String, usize>>,
+        max_same_path: AtomicUsize,
+    }
+
+    impl SleepTool {
+        fn new() -> Self {
+            Self {
+                base: BaseToolImpl::new("sleep".to_string(), "Sleep briefly".to_string(), vec![]),
+                active: std::sync::Mutex::new(HashMap::new()),
+                max_same_path: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl Tool for SleepTool {
+        fn name(&self) -> &str { &self.base.name }
+        fn description(&self) -> &str { &self.base.description }
+        fn schema(&self) -> ToolSchema { self.base.schema.clone() }
+
+        fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+            let path = arguments["path"].as_str().unwrap_or("").to_string();
+            {
+                let mut active = self.active.lock().unwrap();
+                let count = active.entry(path.clone()).or_insert(0);
+                *count += 1;
+                self.max_same_path.fetch_max(*count, Ordering::SeqCst);
+            }
+            std::thread::sleep(Duration::from_millis(arguments["ms"].as_u64().unwrap_or(20)));
+            *self.active.lock().unwrap().get_mut(&path).unwrap() -= 1;
+            Ok(format!("{}:{}", path, arguments["tag"]))
+        }
+    }
+
+    fn sleep_call(path: Option<&str>, tag: usize, ms: u64) -> ToolCall {
+        let mut arguments = serde_json::json!({"tag": tag, "ms": ms});
+        if let Some(path) = path {
+            arguments["path"] = serde_json::json!(path);
+        }
+        ToolCall::with_id(format!("call_{}", tag), "sleep".to_string(), arguments)
+    }
+
+    #[test]
+    fn test_object_parameter_schema() {
+        let field = |name: &str, param_type: &str, required: bool| ToolParameter {
+            name: name.to_string(),
+            param_type: param_type.to_string(),
+            description: format!("{} field", name),
+            required,
+            ..Default::default()
+        };
+        let options = field("options", "object", true).with_properties(vec![
+            field("mode", "string", true).with_enum_values(["fast", "exact"]),
+            field("lines", "array", false).with_items("integer", Some(1), None),
+        ]);
+
+        assert_eq!(options.json_schema(), serde_json::json!({
+            "type": "object",
+            "description": "options field",
+            "properties": {
+                "mode": { "type": "string", "description": "mode field", "enum": ["fast", "exact"] },
+                "lines": { "type": "array", "description": "lines field", "items": { "type": "integer" }, "minItems": 1 }
+            },
+            "required": ["mode"]
+        }));
+        // Arrays without an items type stay arrays of strings
+        assert_eq!(field("files", "array", false).json_schema()["items"], serde_json::json!({ "type": "string" }));
+    }
+
+    fn validation_executor() -> ToolExecutor {
+        ToolExecutor::new(vec![
+            Arc::new(crate::tools::TextEditorTool::new("test".to_string(), vec![])),
+            Arc::new(crate::tools::VHDLAnalyzeTool::new(vec![])),
+        ])
+    }
+
+    fn call(name: &str, arguments: serde_json::Value) -> ToolResult {
+        validation_executor().execute(&ToolCall::with_id("call_0".to_string(), name.to_string(), arguments)).unwrap()
+    }
+
+    #[test]
+    fn test_validation_reports_missing_required() {
+        let result = call("str_replace_based_edit_tool", serde_json::json!({ "command": "view" }));
+        assert!(!result.success);
+        let error = result.error.unwrap();
+        assert!(error.starts_with("Invalid arguments for tool 'str_replace_based_edit_tool':\n- missing required parameter 'path'\nExpected:\n"));
+        // Only the offending parameter's schema is echoed
+        assert!(error.contains("\"path\": {"));
+        assert!(!error.contains("\"command\": {"));
+
+        let result = call("str_replace_based_edit_tool", serde_json::json!([]));
+        assert!(result.error.unwrap().contains("- arguments must be a JSON object, got array"));
+    }
+
+    #[test]
+    fn test_validation_reports_wrong_types() {
+        let result = call("str_replace_based_edit_tool", serde_json::json!({
+            "command": "view",
+            "path": 7,
+            "view_range": [1, "10", 20],
+            "insert_line": null
+        }));
+        let error = result.error.unwrap();
+        assert!(error.contains("- 'path' must be string, got integer\n"));
+        assert!(error.contains("- 'view_range' allows at most 2 item(s), got 3\n"));
+        assert!(error.contains("- 'view_range[1]' must be integer, got string\n"));
+        assert!(!error.contains("insert_line"));
+
+        let result = call("analyze_vhdl", serde_json::json!({ "vhdl_file": ["a.vhd"] }));
+        assert!(result.error.unwrap().contains("- 'vhdl_file' must be string, got array\n"));
+    }
+
+    #[test]
+    fn test_validation_reports_unknown_enum_values() {
+        let result = call("str_replace_based_edit_tool", serde_json::json!({ "command": "delete", "path": "/tmp/x" }));
+        assert!(result.error.unwrap().contains(
+            "- 'command' must be one of \"view\", \"create\", \"str_replace\", \"insert\", \"delete_lines\", \"overwrite_lines\", got \"delete\"\n"
+        ));
+
+        let result = call("analyze_vhdl", serde_json::json!({ "vhdl_file": "a.vhd", "analysis_type": "everything" }));
+        let error = result.error.unwrap();
+        assert!(error.contains("- 'analysis_type' must be one of \"entities\""));
+        assert!(error.contains("\"enum\": ["));
+    }
+
+    #[test]
+    fn test_validate_arguments_on_tools() {
+        let constraints = crate::tools::ConstraintsTool::new(vec![]);
+        assert!(constraints.validate_arguments(&serde_json::json!({ "vhdl_file": "a.vhd", "format": "xdc" })).is_ok());
+        let error = constraints.validate_arguments(&serde_json::json!({ "format": "xdc", "clock_periods": [8.0] }))
+            .unwrap_err().to_string();
+        assert!(error.contains("- missing required parameter 'vhdl_file'\n"));
+        assert!(error.contains("- 'clock_periods' must be object, got array\n"));
+
+        let transpile = crate::tools::TranspileTool::new(vec![]);
+        let error = transpile.validate_arguments(&serde_json::json!({ "vhdl_file": "a.vhd", "strict": "no" }))
+            .unwrap_err().to_string();
+        assert!(error.contains("- 'strict' must be boolean, got string\n"));
+    }
+
+    #[test]
+    fn test_strict_arguments_reject_unknown_parameters() {
+        let arguments = serde_json::json!({ "vhdl_file": "missing.vhd", "signal": "busy" });
+        let executor = ToolExecutor::new(vec![Arc::new(crate::tools::TraceSignalTool::new(vec![]))]);
+        let call = ToolCall::with_id("call_0".to_string(), "trace_signal".to_string(), arguments);
+
+        // Lenient: the misspelled 'signal_name' only shows up as missing
+        let error = executor.execute(&call).unwrap().error.unwrap();
+        assert!(error.contains("- missing required parameter 'signal_name'\n"));
+        assert!(!error.contains("unknown parameter"));
+
+        let mut call = call;
+        call.arguments["signal_name"] = serde_json::json!("busy");
+        assert!(!executor.execute(&call).unwrap().error.unwrap().contains("Invalid arguments"));
+        let error = executor.with_strict_arguments(true).execute(&call).unwrap().error.unwrap();
+        assert!(error.starts_with("Invalid arguments for tool 'trace_signal':\n- unknown parameter 'signal'\nExpected:\n"));
+        // Nothing declared is involved, so the whole schema is echoed
+        assert!(error.contains("\"signal_name\": {"));
+    }
+
+    #[test]
+    fn test_validation_checks_object_members() {
+        let schema = ToolSchema {
+            name: "configure".to_string(),
+            description: String::new(),
+            parameters: vec![ToolParameter {
+                name: "options".to_string(),
+                param_type: "object".to_string(),
+                required: true,
+                ..Default::default()
+            }
+            .with_properties(vec![ToolParameter {
+                name: "mode".to_string(),
+                param_type: "string".to_string(),
+                required: true,
+                ..Default::default()
+            }])],
+        };
+
+        let violations = schema.validate(&serde_json::json!({ "options": {}, "extra": 1 }));
+        assert_eq!(violations, vec![ArgumentViolation {
+            parameter: "options".to_string(),
+            message: "missing required parameter 'options.mode'".to_string(),
+        }]);
+        assert!(schema.validate(&serde_json::json!({ "options": { "mode": "fast" } })).is_empty());
+    }
+
+    #[test]
+    fn test_truncation_at_exact_limit() {
+        let executor = ToolExecutor::new(vec![Arc::new(SleepTool::new())]).with_max_result_bytes(8);
+        // SleepTool returns "<path>:<tag>"
+        let exact = executor.execute(&sleep_call(Some("abcd"), 123, 0)).unwrap();
+        assert_eq!(exact.content, "abcd:123");
+
+        let over = executor.execute(&sleep_call(Some("abcde"), 123, 0)).unwrap();
+        assert_eq!(
+            over.content,
+            "abcde:12\n[...truncated: 1 bytes omitted. Use view_range to see specific lines...]"
+        );
+
+        // Never cuts inside a multi-byte character
+        let mut result = ToolResult::success("id".to_string(), "ab\u{e9}".to_string());
+        result.truncate(3);
+        assert!(result.content.starts_with("ab\n[...truncated: 2 bytes omitted"));
+    }
+
+    #[test]
+    fn test_split_into_pages() {
+        let content: Vec<String> = (1..=1000).map(|i| i.to_string()).collect();
+        let result = ToolResult::success("id".to_string(), content.join("\n"));
+
+        let pages = result.split_into_pages(300);
+        assert_eq!(pages.len(), 4);
+        assert!(pages.iter().all(|page| page.tool_call_id == "id" && page.success));
+        assert_eq!(pages[0].content.lines().next(), Some("1"));
+        assert_eq!(pages[3].content.lines().count(), 100);
+        assert_eq!(pages[3].content.lines().last(), Some("1000"));
+    }
+
+    #[test]
+    fn test_execute_parallel_keeps_order_and_serializes_same_path() {
+        let tool = Arc::new(SleepTool::new());
+        let executor = ToolExecutor::new(vec![tool.clone()]);
+        let calls = vec![
+            sleep_call(Some("a.vhd"), 0, 30),
+            sleep_call(Some("b.vhd"), 1, 5),
+            sleep_call(Some("a.vhd"), 2, 5),
+            sleep_call(None, 3, 5),
+            ToolCall::with_id("call_4".to_string(), "missing".to_string(), serde_json::json!({})),
+        ];
+
+        let results: Vec<ToolResult> = executor.execute_parallel(&calls).into_iter().map(|r| r.unwrap()).collect();
+        let ids: Vec<&str> = results.iter().map(|r| r.tool_call_id.as_str()).collect();
+        assert_eq!(ids, vec!["call_0", "call_1", "call_2", "call_3", "call_4"]);
+        assert_eq!(results[0].content, "a.vhd:0");
+        assert_eq!(results[2].content, "a.vhd:2");
+        assert_eq!(results[3].content, ":3");
+        assert!(!results[4].success);
+        assert_eq!(tool.max_same_path.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_execute_parallel_benchmark() {
+        let executor = ToolExecutor::new(vec![Arc::new(SleepTool::new())]);
+        let calls: Vec<ToolCall> = (0..8).map(|i| sleep_call(Some(&format!("f{}.vhd", i)), i, 25)).collect();
+
+        let start = Instant::now();
+        for call in &calls {
+            executor.execute(call).unwrap();
+        }
+        let sequential = start.elapsed();
+
+        let start = Instant::now();
+        let results = executor.execute_parallel(&calls);
+        let parallel = start.elapsed();
+
+        let threads = rayon::current_num_threads();
+        println!("8 calls x 25ms: sequential {:?}, parallel {:?} on {} thread(s)", sequential, parallel, threads);
+        assert!(results.iter().all(|r| r.as_ref().unwrap().success));
+        if threads > 1 {
+            assert!(parallel < sequential);
+        }
+    }
+}
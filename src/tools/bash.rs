@@ -1,10 +1,30 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::BashPolicy;
 use crate::tools::{BaseToolImpl, Tool, ToolParameter, ToolSchema};
 
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+/// Time left to drain output once the command's processes are gone
+const READ_GRACE: Duration = Duration::from_secs(1);
+
+lazy_static! {
+    /// Separators between the simple commands of a list or pipeline
+    static ref COMMAND_SEPARATOR_RE: Regex = Regex::new(r"&&|\|\||[;|&\n]").unwrap();
+}
+
 pub struct BashTool {
     base: BaseToolImpl,
     _provider: String,
     _allowed_folders: Vec<String>,
+    policy: BashPolicy,
 }
 
 impl BashTool {
@@ -16,12 +36,29 @@ impl BashTool {
                 description: "The bash command to execute".to_string(),
                 required: true,
                 default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "timeout_secs".to_string(),
+                param_type: "integer".to_string(),
+                description: "Kill the command if it runs longer than this many seconds".to_string(),
+                required: false,
+                default: Some(serde_json::json!(DEFAULT_TIMEOUT_SECS)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "max_output_bytes".to_string(),
+                param_type: "integer".to_string(),
+                description: "Maximum bytes kept from each of stdout and stderr".to_string(),
+                required: false,
+                default: Some(serde_json::json!(DEFAULT_MAX_OUTPUT_BYTES)),
+                ..Default::default()
             },
         ];
 
         let base = BaseToolImpl::new(
             "bash".to_string(),
-            "Execute a bash command".to_string(),
+            "Execute a bash command. Returns the exit code together with stdout and stderr.".to_string(),
             parameters,
         );
 
@@ -29,7 +66,93 @@ impl BashTool {
             base,
             _provider: provider,
             _allowed_folders: allowed_folders,
+            policy: BashPolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: BashPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Why `policy` forbids `command`, if it does
+    fn policy_violation(&self, command: &str) -> Option<String> {
+        let normalized = command.split_whitespace().collect::<Vec<_>>().join(" ");
+        if let Some(pattern) = self.policy.denied_patterns.iter().find(|p| normalized.contains(p.as_str())) {
+            return Some(format!("the command contains the denied pattern '{}'", pattern));
+        }
+
+        if self.policy.allowed_commands.is_empty() {
+            return None;
+        }
+        // Substitutions could run anything, so only plain command lists are checked
+        if ["$(", "`", "<(", ">("].iter().any(|s| command.contains(s)) {
+            return Some("command substitution is not allowed when allowed_commands is set".to_string());
+        }
+        let allowed = self.policy.allowed_commands.join(", ");
+        // `2>&1` and `&>` redirect rather than separate commands
+        let redirects_merged = command.replace(">&", ">").replace("<&", "<").replace("&>", ">");
+        COMMAND_SEPARATOR_RE.split(&redirects_merged)
+            .map(|segment| segment.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|segment| !segment.is_empty())
+            .find(|segment| {
+                !self.policy.allowed_commands.iter()
+                    .any(|prefix| segment == prefix || segment.starts_with(&format!("{} ", prefix)))
+            })
+            .map(|segment| {
+                let program = segment.split(' ').next().unwrap_or(&segment);
+                format!("'{}' is not an allowed command (allowed: {})", program, allowed)
+            })
+    }
+
+    /// Read a stream to the end on a separate thread, keeping at most `max_bytes`
+    fn spawn_reader<R: Read + Send + 'static>(
+        mut stream: R,
+        max_bytes: usize,
+    ) -> mpsc::Receiver<(Vec<u8>, bool)> {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut kept = Vec::new();
+            let mut truncated = false;
+            let mut buf = [0u8; 8192];
+
+            // Keep draining after the limit so the child never blocks on a full pipe
+            while let Ok(n) = stream.read(&mut buf) {
+                if n == 0 {
+                    break;
+                }
+                let room = max_bytes.saturating_sub(kept.len());
+                if n > room {
+                    truncated = true;
+                }
+                kept.extend_from_slice(&buf[..n.min(room)]);
+            }
+
+            sender.send((kept, truncated)).ok();
+        });
+        receiver
+    }
+
+    /// Kill the command's process group, including jobs it left running in the background
+    fn kill_process_group(child: &mut Child) {
+        #[cfg(unix)]
+        {
+            Command::new("kill")
+                .args(["-KILL", "--", &format!("-{}", child.id())])
+                .stderr(Stdio::null())
+                .status()
+                .ok();
+        }
+        child.kill().ok();
+    }
+
+    fn format_stream(output: (Vec<u8>, bool), max_bytes: usize) -> String {
+        let (bytes, truncated) = output;
+        let mut text = String::from_utf8_lossy(&bytes).into_owned();
+        if truncated {
+            text.push_str(&format!("\n[output truncated to {} bytes]", max_bytes));
         }
+        text
     }
 }
 
@@ -46,8 +169,202 @@ impl Tool for BashTool {
         self.base.schema.clone()
     }
 
-    fn execute(&self, _arguments: &serde_json::Value) -> Result<String> {
-        // Stub implementation
-        Ok("Bash tool not implemented yet".to_string())
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        let command = arguments
+            .get("command")
+            .and_then(|v| v.as_str())
+            .context("Missing 'command' argument")?;
+
+        let timeout_secs = arguments
+            .get("timeout_secs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_TIMEOUT_SECS);
+
+        let max_output_bytes = arguments
+            .get("max_output_bytes")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_MAX_OUTPUT_BYTES);
+
+        if let Some(violation) = self.policy_violation(command) {
+            return Err(anyhow::anyhow!("Command blocked by bash policy: {}", violation));
+        }
+
+        let mut bash = Command::new("bash");
+        if self.policy.clear_env {
+            bash.env_clear();
+            for name in &self.policy.env_allowlist {
+                if let Some(value) = std::env::var_os(name) {
+                    bash.env(name, value);
+                }
+            }
+        }
+
+        // Its own process group, so background jobs can be killed with the shell
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut bash, 0);
+
+        let mut child = bash
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to spawn bash")?;
+
+        let stdout_reader = Self::spawn_reader(child.stdout.take().unwrap(), max_output_bytes);
+        let stderr_reader = Self::spawn_reader(child.stderr.take().unwrap(), max_output_bytes);
+
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                Self::kill_process_group(&mut child);
+                child.wait().ok();
+                return Err(anyhow::anyhow!(
+                    "Command timed out after {} seconds and was killed: {}",
+                    timeout_secs,
+                    command
+                ));
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        // Background jobs would otherwise hold the output pipes open past the timeout
+        Self::kill_process_group(&mut child);
+        let read = |reader: mpsc::Receiver<(Vec<u8>, bool)>, stream: &str| {
+            reader.recv_timeout(deadline.saturating_duration_since(Instant::now()).max(READ_GRACE))
+                .map_err(|_| anyhow::anyhow!("Timed out reading {} of: {}", stream, command))
+        };
+        let stdout = read(stdout_reader, "stdout")?;
+        let stderr = read(stderr_reader, "stderr")?;
+
+        let exit_code = status
+            .code()
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "terminated by signal".to_string());
+
+        Ok(format!(
+            "Exit code: {}\n=== STDOUT ===\n{}\n=== STDERR ===\n{}",
+            exit_code,
+            Self::format_stream(stdout, max_output_bytes),
+            Self::format_stream(stderr, max_output_bytes)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_captures_stderr_and_exit_code() {
+        let tool = BashTool::new("test".to_string(), vec![]);
+
+        let result = tool.execute(&serde_json::json!({
+            "command": "echo compiled; echo 'alu.v:3: syntax error' >&2; exit 2"
+        })).unwrap();
+
+        assert!(result.starts_with("Exit code: 2\n"));
+        assert!(result.contains("=== STDOUT ===\ncompiled\n"));
+        assert!(result.contains("=== STDERR ===\nalu.v:3: syntax error\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_background_job_does_not_outlive_timeout() {
+        let tool = BashTool::new("test".to_string(), vec![]);
+        let start = Instant::now();
+        let result = tool.execute(&serde_json::json!({
+            "command": "sleep 100 & echo started",
+            "timeout_secs": 5
+        })).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(result.starts_with("Exit code: 0\n"));
+        assert!(result.contains("=== STDOUT ===\nstarted\n"));
+    }
+
+    fn policy_tool() -> BashTool {
+        BashTool::new("test".to_string(), vec![]).with_policy(BashPolicy {
+            allowed_commands: vec!["echo".to_string(), "ls".to_string(), "git status".to_string()],
+            denied_patterns: vec!["rm -rf".to_string(), "sudo".to_string()],
+            clear_env: true,
+            env_allowlist: vec!["PATH".to_string()],
+        })
+    }
+
+    #[test]
+    fn test_policy_allows_listed_commands() {
+        let tool = policy_tool();
+        let result = tool.execute(&serde_json::json!({ "command": "echo ok && ls / | echo piped" })).unwrap();
+        assert!(result.starts_with("Exit code: 0\n"));
+        assert!(tool.policy_violation("git status --short 2>&1").is_none());
+        assert!(tool.policy_violation("echo a\ncat b").is_some());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_policy_denies_unlisted_prefix() {
+        let tool = policy_tool();
+        let err = tool.execute(&serde_json::json!({ "command": "echo ok; cat /etc/passwd" })).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Command blocked by bash policy: 'cat' is not an allowed command (allowed: echo, ls, git status)"
+        );
+        // A prefix matches whole words only
+        assert!(tool.policy_violation("lsblk").is_some());
+        assert!(tool.policy_violation("git push").is_some());
+        assert!(tool.policy_violation("echo $(whoami)").unwrap().contains("command substitution"));
+    }
+
+    #[test]
+    fn test_policy_denies_pattern() {
+        let tool = policy_tool();
+        let err = tool.execute(&serde_json::json!({ "command": "echo x; rm   -rf /tmp/nothing" })).unwrap_err();
+        assert_eq!(err.to_string(), "Command blocked by bash policy: the command contains the denied pattern 'rm -rf'");
+
+        // Patterns apply even without an allowlist
+        let tool = BashTool::new("test".to_string(), vec![]).with_policy(BashPolicy {
+            denied_patterns: vec!["curl".to_string()],
+            ..Default::default()
+        });
+        assert!(tool.execute(&serde_json::json!({ "command": "curl example.com" })).is_err());
+    }
+
+    #[test]
+    fn test_policy_clears_environment() {
+        std::env::set_var("RTL_TRANSPILER_BASH_SECRET", "hunter2");
+        let result = policy_tool().execute(&serde_json::json!({ "command": "echo \"[$RTL_TRANSPILER_BASH_SECRET]\"" })).unwrap();
+        std::env::remove_var("RTL_TRANSPILER_BASH_SECRET");
+        assert!(result.contains("=== STDOUT ===\n[]\n"));
+    }
+
+    #[test]
+    fn test_bash_timeout_kills_command() {
+        let tool = BashTool::new("test".to_string(), vec![]);
+        let start = Instant::now();
+
+        let result = tool.execute(&serde_json::json!({
+            "command": "sleep 10",
+            "timeout_secs": 1
+        }));
+
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_bash_truncates_large_output() {
+        let tool = BashTool::new("test".to_string(), vec![]);
+
+        let result = tool.execute(&serde_json::json!({
+            "command": "head -c 100000 /dev/zero | tr '\\0' 'x'",
+            "max_output_bytes": 100
+        })).unwrap();
+
+        assert!(result.starts_with("Exit code: 0\n"));
+        assert!(result.contains(&format!("{}\n[output truncated to 100 bytes]", "x".repeat(100))));
+    }
+}
@@ -0,0 +1,197 @@
+//! Machine-readable results of a folder transpilation, for CI systems
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    Transpiled,
+    /// Only held parts of entities emitted with another file's module
+    Merged,
+    Failed,
+    /// Not reached, e.g. after an error in the whole batch
+    Skipped,
+}
+
+/// Outcome of one input file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileReport {
+    pub file: String,
+    pub status: FileStatus,
+    /// Generated SystemVerilog file
+    pub output: Option<String>,
+    /// Time spent parsing and generating this file
+    pub duration_ms: f64,
+    /// Errors, in the order they were reported
+    pub diagnostics: Vec<String>,
+}
+
+impl FileReport {
+    pub fn new(file: &Path) -> Self {
+        Self {
+            file: file.display().to_string(),
+            status: FileStatus::Skipped,
+            output: None,
+            duration_ms: 0.0,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn add_duration(&mut self, duration: Duration) {
+        self.duration_ms += duration.as_secs_f64() * 1000.0;
+    }
+
+    /// Record an error; the file stays failed even if it is transpiled afterwards
+    pub fn fail(&mut self, message: impl Into<String>) {
+        self.status = FileStatus::Failed;
+        self.diagnostics.push(message.into());
+    }
+
+    pub fn transpiled(&mut self, output: impl Into<String>) {
+        self.output = Some(output.into());
+        if self.status != FileStatus::Failed {
+            self.status = FileStatus::Transpiled;
+        }
+    }
+
+    pub fn merged(&mut self) {
+        if self.status != FileStatus::Failed {
+            self.status = FileStatus::Merged;
+        }
+    }
+}
+
+/// Per-file results of one batch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchReport {
+    pub vhdl_folder: String,
+    pub output_folder: String,
+    pub duration_ms: f64,
+    pub files: Vec<FileReport>,
+}
+
+impl BatchReport {
+    pub fn failure_count(&self) -> usize {
+        self.files.iter().filter(|f| f.status == FileStatus::Failed).count()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// JUnit XML with one testcase per input file, as rendered by Jenkins and GitLab
+    pub fn to_junit(&self) -> String {
+        let skipped = self.files.iter().filter(|f| f.status == FileStatus::Skipped).count();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"rtl_transpiler\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            self.files.len(), self.failure_count(), self.duration_ms / 1000.0
+        ));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{:.3}\">\n",
+            escape_xml(&self.vhdl_folder), self.files.len(), self.failure_count(), skipped, self.duration_ms / 1000.0
+        ));
+        for file in &self.files {
+            xml.push_str(&format!(
+                "    <testcase classname=\"transpile\" name=\"{}\" time=\"{:.3}\"",
+                escape_xml(&file.file), file.duration_ms / 1000.0
+            ));
+            match file.status {
+                FileStatus::Failed => {
+                    let message = file.diagnostics.first().map(String::as_str).unwrap_or("transpilation failed");
+                    xml.push_str(&format!(
+                        ">\n      <failure message=\"{}\" type=\"transpilation\">{}</failure>\n    </testcase>\n",
+                        escape_xml(message), escape_xml(&file.diagnostics.join("\n"))
+                    ));
+                }
+                FileStatus::Skipped => xml.push_str(">\n      <skipped/>\n    </testcase>\n"),
+                FileStatus::Merged => xml.push_str(
+                    ">\n      <system-out>merged into the module of its entity's declaration</system-out>\n    </testcase>\n"
+                ),
+                FileStatus::Transpiled => match &file.output {
+                    Some(output) => xml.push_str(&format!(
+                        ">\n      <system-out>{}</system-out>\n    </testcase>\n",
+                        escape_xml(output)
+                    )),
+                    None => xml.push_str("/>\n"),
+                },
+            }
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch() -> BatchReport {
+        let mut counter = FileReport::new(Path::new("rtl/counter.vhd"));
+        counter.add_duration(Duration::from_millis(12));
+        counter.transpiled("out/counter.sv");
+        let mut alu = FileReport::new(Path::new("rtl/alu.vhd"));
+        alu.fail("GHDL pre-check failed: rtl/alu.vhd:3:1: no declaration for \"a<b\"");
+        alu.transpiled("out/alu.sv");
+        let mut alu_arch = FileReport::new(Path::new("rtl/alu_rtl.vhd"));
+        alu_arch.merged();
+        BatchReport {
+            vhdl_folder: "rtl".to_string(),
+            output_folder: "out".to_string(),
+            duration_ms: 20.0,
+            files: vec![counter, alu, alu_arch, FileReport::new(Path::new("rtl/top.vhd"))],
+        }
+    }
+
+    #[test]
+    fn test_json_report() {
+        let json: serde_json::Value = serde_json::from_str(&batch().to_json().unwrap()).unwrap();
+        assert_eq!(json["vhdl_folder"], "rtl");
+        let files = json["files"].as_array().unwrap();
+        assert_eq!(files.len(), 4);
+        assert_eq!(files[0]["status"], "transpiled");
+        assert_eq!(files[0]["output"], "out/counter.sv");
+        assert_eq!(files[0]["duration_ms"].as_f64(), Some(12.0));
+        assert_eq!(files[1]["status"], "failed");
+        assert_eq!(files[1]["diagnostics"].as_array().unwrap().len(), 1);
+        assert_eq!(files[2]["status"], "merged");
+        assert_eq!(files[3]["status"], "skipped");
+        assert!(files[3]["output"].is_null());
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let xml = batch().to_junit();
+        let mut reader = quick_xml::Reader::from_str(&xml);
+        let (mut testcases, mut failures, mut skipped) = (0, Vec::new(), 0);
+        loop {
+            match reader.read_event().unwrap() {
+                quick_xml::events::Event::Start(e) | quick_xml::events::Event::Empty(e) => match e.name().as_ref() {
+                    b"testcase" => testcases += 1,
+                    b"failure" => failures.push(
+                        e.try_get_attribute("message").unwrap().unwrap().unescape_value().unwrap().into_owned()
+                    ),
+                    b"skipped" => skipped += 1,
+                    _ => {}
+                },
+                quick_xml::events::Event::Eof => break,
+                _ => {}
+            }
+        }
+        assert_eq!(testcases, 4);
+        assert_eq!(failures, vec!["GHDL pre-check failed: rtl/alu.vhd:3:1: no declaration for \"a<b\""]);
+        assert_eq!(skipped, 1);
+        assert!(xml.contains("<testsuites name=\"rtl_transpiler\" tests=\"4\" failures=\"1\" time=\"0.020\">"));
+    }
+}
@@ -0,0 +1,371 @@
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+use crate::constants;
+use crate::tools::{path_within, BaseToolImpl, Tool, ToolParameter, ToolSchema};
+
+const DEFAULT_CLOCK_PERIOD_NS: u64 = 10;
+
+lazy_static! {
+    static ref MODULE_RE: Regex = Regex::new(r"(?m)^\s*module\s+(\w+)").unwrap();
+    static ref PORT_RE: Regex = Regex::new(
+        r"(?m)^\s*(input|output|inout)\b\s*(?:logic|wire|reg|bit)?\s*(?:signed\s*)?(\[[^\]]*\])?\s*(\w+)"
+    ).unwrap();
+}
+
+/// Port of a SystemVerilog module header
+#[derive(Debug, Clone, PartialEq)]
+struct SvPort {
+    name: String,
+    direction: String,
+    /// Packed dimension such as `[7:0]`, absent for single bits
+    range: Option<String>,
+}
+
+/// Module name and ANSI-style port list of a generated SystemVerilog file
+#[derive(Debug, Clone, PartialEq)]
+struct SvModule {
+    name: String,
+    ports: Vec<SvPort>,
+}
+
+impl SvModule {
+    /// Light regex pass over the first module header in `sv`
+    fn parse(sv: &str) -> Result<Self> {
+        let caps = MODULE_RE.captures(sv).context("No module declaration found")?;
+        let name = caps[1].to_string();
+        let start = caps.get(0).map_or(0, |m| m.end());
+        let header_end = sv[start..].find(");").map_or(sv.len(), |pos| start + pos);
+
+        let ports = PORT_RE.captures_iter(&sv[start..header_end])
+            .map(|caps| SvPort {
+                name: caps[3].to_string(),
+                direction: caps[1].to_string(),
+                range: caps.get(2).map(|m| m.as_str().to_string()),
+            })
+            .collect();
+
+        Ok(Self { name, ports })
+    }
+
+    fn inputs(&self) -> impl Iterator<Item = &SvPort> {
+        self.ports.iter().filter(|p| p.direction == "input")
+    }
+
+    fn clock(&self) -> Option<&SvPort> {
+        self.inputs().find(|p| {
+            let name = p.name.to_lowercase();
+            (name == "clk" || name == "clock" || name.ends_with("_clk") || name.starts_with("clk_") && !is_enable(&name))
+                && p.range.is_none()
+        })
+    }
+
+    /// Reset input and whether it is active low
+    fn reset(&self) -> Option<(&SvPort, bool)> {
+        let port = self.inputs().find(|p| {
+            let name = p.name.to_lowercase();
+            name.contains("rst") || name.contains("reset")
+        })?;
+        let name = port.name.to_lowercase();
+        Some((port, name.ends_with("_n") || name.ends_with("_b") || name.ends_with('n')))
+    }
+
+    fn clock_enable(&self) -> Option<&SvPort> {
+        self.inputs().find(|p| is_enable(&p.name.to_lowercase()))
+    }
+}
+
+fn is_enable(name: &str) -> bool {
+    matches!(name, "en" | "ce" | "enable" | "clk_en" | "clken" | "clock_enable") || name.ends_with("_ce")
+}
+
+/// Makefile for the cocotb simulator flow
+fn makefile(module: &SvModule, sv_file_name: &str) -> String {
+    format!(
+        "# cocotb Makefile for {name}; run with `make` or `make SIM=verilator`\n\
+         SIM ?= icarus\n\
+         TOPLEVEL_LANG ?= verilog\n\
+         \n\
+         VERILOG_SOURCES += $(PWD)/{sv}\n\
+         \n\
+         # Top-level HDL module and Python test module\n\
+         TOPLEVEL = {name}\n\
+         MODULE = test_{name}\n\
+         \n\
+         include $(shell cocotb-config --makefiles)/Makefile.sim\n",
+        name = module.name,
+        sv = sv_file_name,
+    )
+}
+
+/// `test_<module>.py` with a cocotb coroutine and a cocotb-test pytest runner
+fn test_module(module: &SvModule, sv_file_name: &str, test_name: &str, clock_period_ns: u64) -> String {
+    let clock = module.clock();
+    let reset = module.reset();
+    let enable = module.clock_enable();
+
+    let mut py = String::new();
+    py.push_str("import os\n\n");
+    py.push_str("import cocotb\n");
+    if clock.is_some() {
+        py.push_str("from cocotb.clock import Clock\n");
+        py.push_str("from cocotb.triggers import ClockCycles, RisingEdge\n");
+    } else {
+        py.push_str("from cocotb.triggers import Timer\n");
+    }
+    py.push_str("\n\n@cocotb.test()\n");
+    py.push_str(&format!("async def {}(dut):\n", test_name));
+    py.push_str(&format!("    \"\"\"Stimulus and checks for {}\"\"\"\n", module.name));
+
+    if let Some(clk) = clock {
+        py.push_str(&format!(
+            "    cocotb.start_soon(Clock(dut.{}, {}, units=\"ns\").start())\n\n",
+            clk.name, clock_period_ns
+        ));
+    }
+
+    // Known values on every data input before reset is released
+    let data_inputs: Vec<&SvPort> = module.inputs()
+        .filter(|p| Some(*p) != clock && Some(*p) != reset.map(|(r, _)| r))
+        .collect();
+    if !data_inputs.is_empty() {
+        py.push_str("    # Idle inputs\n");
+        for port in &data_inputs {
+            py.push_str(&format!("    dut.{}.value = 0\n", port.name));
+        }
+        py.push('\n');
+    }
+
+    if let Some((rst, active_low)) = reset {
+        let (asserted, released) = if active_low { (0, 1) } else { (1, 0) };
+        py.push_str(&format!("    # Reset (active {})\n", if active_low { "low" } else { "high" }));
+        py.push_str(&format!("    dut.{}.value = {}\n", rst.name, asserted));
+        match clock {
+            Some(clk) => py.push_str(&format!("    await ClockCycles(dut.{}, 5)\n", clk.name)),
+            None => py.push_str(&format!("    await Timer({}, units=\"ns\")\n", clock_period_ns * 5)),
+        }
+        py.push_str(&format!("    dut.{}.value = {}\n", rst.name, released));
+        if let Some(clk) = clock {
+            py.push_str(&format!("    await RisingEdge(dut.{})\n", clk.name));
+        }
+        py.push('\n');
+    }
+
+    if let Some(en) = enable {
+        py.push_str(&format!("    # NOTE: {} looks like a clock enable; the design only advances while it is high\n", en.name));
+        py.push_str(&format!("    dut.{}.value = 1\n\n", en.name));
+    }
+
+    py.push_str("    # TODO: drive stimulus\n");
+    match clock {
+        Some(clk) => py.push_str(&format!("    await ClockCycles(dut.{}, 10)\n\n", clk.name)),
+        None => py.push_str(&format!("    await Timer({}, units=\"ns\")\n\n", clock_period_ns)),
+    }
+    py.push_str("    # TODO: check outputs\n");
+    for port in module.ports.iter().filter(|p| p.direction == "output") {
+        py.push_str(&format!("    # assert dut.{}.value == expected\n", port.name));
+    }
+
+    py.push_str(&format!("\n\ndef test_{}_runner():\n", module.name));
+    py.push_str("    \"\"\"pytest entry point using cocotb-test\"\"\"\n");
+    py.push_str("    from cocotb_test.simulator import run\n\n");
+    py.push_str("    run(\n");
+    py.push_str(&format!(
+        "        verilog_sources=[os.path.join(os.path.dirname(__file__), \"{}\")],\n",
+        sv_file_name
+    ));
+    py.push_str(&format!("        toplevel=\"{}\",\n", module.name));
+    py.push_str(&format!("        module=\"test_{}\",\n", module.name));
+    py.push_str("    )\n");
+
+    py
+}
+
+/// Tool generating a cocotb testbench (Makefile and Python test) for a SystemVerilog module
+pub struct CocotbGenerator {
+    base: BaseToolImpl,
+    allowed_folders: Vec<String>,
+}
+
+impl CocotbGenerator {
+    pub fn new(allowed_folders: Vec<String>) -> Self {
+        let parameters = vec![
+            ToolParameter {
+                name: "sv_file".to_string(),
+                param_type: "string".to_string(),
+                description: "Path to the SystemVerilog file whose module is tested".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "test_name".to_string(),
+                param_type: "string".to_string(),
+                description: "Name of the cocotb test coroutine (default: test_<module>)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "clock_period_ns".to_string(),
+                param_type: "integer".to_string(),
+                description: "Clock period driven on the detected clock input, in ns".to_string(),
+                required: false,
+                default: Some(serde_json::json!(DEFAULT_CLOCK_PERIOD_NS)),
+                ..Default::default()
+            },
+        ];
+
+        let base = BaseToolImpl::new(
+            constants::TOOL_COCOTB.to_string(),
+            "Generate a cocotb testbench for a SystemVerilog module: a Makefile and test_<module>.py with clock, reset and stimulus placeholders, written next to the SV file.".to_string(),
+            parameters,
+        );
+
+        Self {
+            base,
+            allowed_folders,
+        }
+    }
+}
+
+impl Tool for CocotbGenerator {
+    fn name(&self) -> &str {
+        &self.base.name
+    }
+
+    fn description(&self) -> &str {
+        &self.base.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.base.schema.clone()
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        let sv_file = arguments
+            .get("sv_file")
+            .and_then(|v| v.as_str())
+            .context("Missing 'sv_file' argument")?;
+
+        let test_name = arguments
+            .get("test_name")
+            .and_then(|v| v.as_str());
+
+        let clock_period_ns = arguments
+            .get("clock_period_ns")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_CLOCK_PERIOD_NS);
+
+        let sv_path = Path::new(sv_file);
+
+        // Check if path is allowed
+        if !path_within(&self.allowed_folders, sv_path) {
+            return Err(anyhow::anyhow!(
+                "Access denied: '{}' is not in allowed folders",
+                sv_file
+            ));
+        }
+
+        let sv = std::fs::read_to_string(sv_path)
+            .context(format!("Failed to read SystemVerilog file: {}", sv_file))?;
+        let module = SvModule::parse(&sv)
+            .context(format!("Failed to find a module in: {}", sv_file))?;
+
+        let sv_file_name = sv_path.file_name().and_then(|n| n.to_str()).unwrap_or(sv_file);
+        let test_name = test_name.map_or_else(|| format!("test_{}", module.name), str::to_string);
+
+        let makefile = makefile(&module, sv_file_name);
+        let test_py = test_module(&module, sv_file_name, &test_name, clock_period_ns);
+
+        let out_dir = sv_path.parent().unwrap_or(Path::new("."));
+        let makefile_path = out_dir.join("Makefile");
+        let test_path = out_dir.join(format!("test_{}.py", module.name));
+        std::fs::write(&makefile_path, &makefile)
+            .context(format!("Failed to write Makefile to: {}", makefile_path.display()))?;
+        std::fs::write(&test_path, &test_py)
+            .context(format!("Failed to write cocotb test to: {}", test_path.display()))?;
+
+        let mut notes = String::new();
+        if module.clock().is_none() {
+            notes.push_str("\nNo clock input detected; the test advances with timers.");
+        }
+        if let Some(enable) = module.clock_enable() {
+            notes.push_str(&format!(
+                "\nNote: '{}' looks like a clock enable; the test holds it high before stimulus.",
+                enable.name
+            ));
+        }
+
+        Ok(format!(
+            "Generated cocotb testbench for module '{}'{}\n\n// {}\n{}\n// {}\n{}",
+            module.name,
+            notes,
+            makefile_path.display(),
+            makefile,
+            test_path.display(),
+            test_py
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const COUNTER_SV: &str = "module counter #(\n    parameter WIDTH = 8\n) (\n    input logic clk,\n    input logic rst_n,\n    input logic en,\n    output logic [WIDTH-1:0] count\n);\n\n    always_ff @(posedge clk or negedge rst_n) begin\n    end\nendmodule\n";
+
+    #[test]
+    fn test_parse_module_ports() {
+        let module = SvModule::parse(COUNTER_SV).unwrap();
+
+        assert_eq!(module.name, "counter");
+        let names: Vec<&str> = module.ports.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["clk", "rst_n", "en", "count"]);
+        assert_eq!(module.ports[3].range.as_deref(), Some("[WIDTH-1:0]"));
+        assert_eq!(module.clock().unwrap().name, "clk");
+        assert_eq!(module.reset().map(|(p, low)| (p.name.as_str(), low)), Some(("rst_n", true)));
+        assert_eq!(module.clock_enable().unwrap().name, "en");
+    }
+
+    #[test]
+    fn test_generates_makefile_and_test_module() {
+        let dir = TempDir::new().unwrap();
+        let sv_path = dir.path().join("counter.sv");
+        std::fs::write(&sv_path, COUNTER_SV).unwrap();
+
+        let tool = CocotbGenerator::new(vec![]);
+        let result = tool.execute(&serde_json::json!({
+            "sv_file": sv_path.to_str().unwrap(),
+            "clock_period_ns": 8
+        })).unwrap();
+        assert!(result.contains("'en' looks like a clock enable"));
+
+        let makefile = std::fs::read_to_string(dir.path().join("Makefile")).unwrap();
+        assert!(makefile.contains("\nTOPLEVEL = counter\n"));
+        assert!(makefile.contains("\nMODULE = test_counter\n"));
+        assert!(makefile.contains("VERILOG_SOURCES += $(PWD)/counter.sv"));
+
+        let test_py = std::fs::read_to_string(dir.path().join("test_counter.py")).unwrap();
+        assert!(test_py.contains("@cocotb.test()\nasync def test_counter(dut):"));
+        assert!(test_py.contains("Clock(dut.clk, 8, units=\"ns\")"));
+        assert!(test_py.contains("dut.rst_n.value = 0\n    await ClockCycles(dut.clk, 5)\n    dut.rst_n.value = 1"));
+        assert!(test_py.contains("# assert dut.count.value == expected"));
+        assert!(test_py.contains("toplevel=\"counter\""));
+    }
+
+    #[test]
+    fn test_combinational_module_uses_timers() {
+        let module = SvModule::parse("module inv (\n    input logic a,\n    output logic y\n);\nendmodule\n").unwrap();
+        let test_py = test_module(&module, "inv.sv", "check_inv", 10);
+
+        assert!(test_py.contains("async def check_inv(dut):"));
+        assert!(!test_py.contains("Clock("));
+        assert!(test_py.contains("dut.a.value = 0"));
+        assert!(test_py.contains("await Timer(10, units=\"ns\")"));
+        assert_eq!(makefile(&module, "inv.sv").matches("MODULE = test_inv").count(), 1);
+    }
+}
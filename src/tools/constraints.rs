@@ -0,0 +1,170 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::constants;
+use crate::ir::{ClockConstraint, SystemVerilogGenerator};
+use crate::parser::ASTVHDLParser;
+use crate::tools::{path_within, BaseToolImpl, Tool, ToolParameter, ToolSchema};
+
+/// Tool generating SDC or XDC timing constraints for the modules transpiled from a VHDL file
+pub struct ConstraintsTool {
+    base: BaseToolImpl,
+    allowed_folders: Vec<String>,
+}
+
+impl ConstraintsTool {
+    pub fn new(allowed_folders: Vec<String>) -> Self {
+        let parameters = vec![
+            ToolParameter {
+                name: "vhdl_file".to_string(),
+                param_type: "string".to_string(),
+                description: "Path to the VHDL file whose entities are constrained".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "format".to_string(),
+                param_type: "string".to_string(),
+                description: "Constraint format: \"sdc\" (Synopsys, Quartus) or \"xdc\" (Vivado, with pin stubs)".to_string(),
+                required: false,
+                default: Some(serde_json::json!("sdc")),
+                ..Default::default()
+            }
+            .with_enum_values(["sdc", "xdc"]),
+            ToolParameter {
+                name: "clock_periods".to_string(),
+                param_type: "object".to_string(),
+                description: "Clock period in ns per clock port, e.g. {\"clk\": 8.0}; other clocks get 10 ns".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "output_file".to_string(),
+                param_type: "string".to_string(),
+                description: "Path to write the constraints to (optional)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+        ];
+
+        let base = BaseToolImpl::new(
+            constants::TOOL_CONSTRAINTS.to_string(),
+            "Generate synthesis timing constraints for the modules transpiled from a VHDL file: create_clock for detected clock ports, set_false_path for resets and set_input_delay/set_output_delay placeholders for data ports, as SDC or Xilinx XDC.".to_string(),
+            parameters,
+        );
+
+        Self {
+            base,
+            allowed_folders,
+        }
+    }
+}
+
+impl Tool for ConstraintsTool {
+    fn name(&self) -> &str {
+        &self.base.name
+    }
+
+    fn description(&self) -> &str {
+        &self.base.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.base.schema.clone()
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        let vhdl_file = arguments
+            .get("vhdl_file")
+            .and_then(|v| v.as_str())
+            .context("Missing 'vhdl_file' argument")?;
+
+        let format = arguments
+            .get("format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("sdc");
+        if format != "sdc" && format != "xdc" {
+            return Err(anyhow::anyhow!("Unsupported format '{}': expected \"sdc\" or \"xdc\"", format));
+        }
+
+        let mut clocks = HashMap::new();
+        if let Some(periods) = arguments.get("clock_periods").and_then(|v| v.as_object()) {
+            for (clock, period) in periods {
+                let period = period.as_f64()
+                    .filter(|period| *period > 0.0)
+                    .context(format!("Clock period for '{}' must be a positive number of ns", clock))?;
+                clocks.insert(clock.clone(), ClockConstraint::new(period));
+            }
+        }
+
+        let output_file = arguments.get("output_file").and_then(|v| v.as_str());
+        for path in std::iter::once(vhdl_file).chain(output_file) {
+            // Output files may not exist yet, so check their folder
+            let file = Path::new(path);
+            let existing = if file.exists() { file } else { file.parent().unwrap_or(file) };
+            if !path_within(&self.allowed_folders, existing) {
+                return Err(anyhow::anyhow!("Access denied: '{}' is not in allowed folders", path));
+            }
+        }
+
+        let mut parser = ASTVHDLParser::from_file(Path::new(vhdl_file))?;
+        let entities = parser.parse_entities()
+            .context(format!("Failed to parse VHDL file: {}", vhdl_file))?;
+        if entities.is_empty() {
+            return Err(anyhow::anyhow!("No entities found in: {}", vhdl_file));
+        }
+
+        let generator = SystemVerilogGenerator::new();
+        let mut constraints = Vec::new();
+        for entity in &entities {
+            constraints.push(match format {
+                "xdc" => generator.generate_xdc(entity, &clocks)?,
+                _ => generator.generate_sdc(entity, &clocks)?,
+            });
+        }
+        let constraints = constraints.join("\n");
+
+        match output_file {
+            Some(output_file) => {
+                std::fs::write(output_file, &constraints)
+                    .context(format!("Failed to write constraints to: {}", output_file))?;
+                Ok(format!("Wrote {} constraints for {} entity(ies) to {}\n\n{}", format.to_uppercase(), entities.len(), output_file, constraints))
+            }
+            None => Ok(constraints),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_bad_arguments() {
+        let tool = ConstraintsTool::new(vec![]);
+
+        let err = tool.execute(&serde_json::json!({ "vhdl_file": "counter.vhd", "format": "ucf" })).unwrap_err();
+        assert!(err.to_string().contains("Unsupported format 'ucf'"));
+
+        let args = serde_json::json!({ "vhdl_file": "counter.vhd", "clock_periods": { "clk": -1 } });
+        let err = tool.execute(&args).unwrap_err();
+        assert_eq!(err.to_string(), "Clock period for 'clk' must be a positive number of ns");
+    }
+
+    #[test]
+    fn test_counter_fixture_sdc() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/counter.vhd");
+        let args = serde_json::json!({
+            "vhdl_file": fixture.display().to_string(),
+            "clock_periods": { "clk": 5 }
+        });
+
+        let sdc = ConstraintsTool::new(vec![]).execute(&args).unwrap();
+        assert!(sdc.contains("create_clock -name clk -period 5.0 [get_ports clk]"));
+        assert!(sdc.contains("set_false_path -from [get_ports reset]"));
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use std::sync::Arc;
+
+use crate::tools::{Tool, ToolSchema};
+
+/// Wrapper that reports what a tool would do instead of executing it
+pub struct DryRunTool {
+    inner: Arc<dyn Tool>,
+}
+
+impl DryRunTool {
+    pub fn new(inner: Arc<dyn Tool>) -> Self {
+        Self { inner }
+    }
+
+    pub fn describe_call(tool_name: &str, arguments: &serde_json::Value) -> String {
+        format!("[DRY-RUN] Would call tool '{}' with args: {}", tool_name, arguments)
+    }
+}
+
+impl Tool for DryRunTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.inner.schema()
+    }
+
+    fn initialize(&self) -> Result<()> {
+        self.inner.initialize()
+    }
+
+    fn validate_arguments(&self, arguments: &serde_json::Value) -> Result<()> {
+        self.inner.validate_arguments(arguments)
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        Ok(Self::describe_call(self.inner.name(), arguments))
+    }
+
+    fn cleanup(&self) -> Result<()> {
+        self.inner.cleanup()
+    }
+}
@@ -6,7 +6,10 @@ use std::path::{Path, PathBuf};
 use obfstr::obfstr;
 use lazy_static::lazy_static;
 
-use crate::tools::base::{BaseToolImpl, Tool, ToolParameter, ToolSchema};
+use crate::tools::base::{BaseToolImpl, Tool, ToolParameter, ToolResult, ToolSchema};
+
+/// Lines per page for `view` with `view_page`
+const VIEW_PAGE_LINES: usize = 200;
 
 lazy_static! {
     static ref EDIT_TOOL_DESCRIPTION: String = obfstr!(r#"Custom editing tool for viewing, creating and editing files
@@ -28,7 +31,13 @@ struct EditArguments {
     #[serde(default)]
     insert_line: Option<usize>,
     #[serde(default)]
+    start_line: Option<usize>,
+    #[serde(default)]
+    end_line: Option<usize>,
+    #[serde(default)]
     view_range: Option<Vec<i32>>,
+    #[serde(default)]
+    view_page: Option<usize>,
 }
 
 pub struct TextEditorTool {
@@ -43,16 +52,19 @@ impl TextEditorTool {
             ToolParameter {
                 name: "command".to_string(),
                 param_type: "string".to_string(),
-                description: obfstr!("The command to run. Allowed: view, create, str_replace, insert").to_string(),
+                description: obfstr!("The command to run. Allowed: view, create, str_replace, insert, delete_lines, overwrite_lines").to_string(),
                 required: true,
                 default: None,
-            },
+                ..Default::default()
+            }
+            .with_enum_values(["view", "create", "str_replace", "insert", "delete_lines", "overwrite_lines"]),
             ToolParameter {
                 name: "path".to_string(),
                 param_type: "string".to_string(),
                 description: obfstr!("Absolute path to file or directory").to_string(),
                 required: true,
                 default: None,
+                ..Default::default()
             },
             ToolParameter {
                 name: "file_text".to_string(),
@@ -60,6 +72,7 @@ impl TextEditorTool {
                 description: obfstr!("Content for create command").to_string(),
                 required: false,
                 default: None,
+                ..Default::default()
             },
             ToolParameter {
                 name: "old_str".to_string(),
@@ -67,13 +80,15 @@ impl TextEditorTool {
                 description: obfstr!("String to replace (for str_replace)").to_string(),
                 required: false,
                 default: None,
+                ..Default::default()
             },
             ToolParameter {
                 name: "new_str".to_string(),
                 param_type: "string".to_string(),
-                description: obfstr!("Replacement string (for str_replace/insert)").to_string(),
+                description: obfstr!("Replacement string (for str_replace/insert/overwrite_lines)").to_string(),
                 required: false,
                 default: None,
+                ..Default::default()
             },
             ToolParameter {
                 name: "insert_line".to_string(),
@@ -81,6 +96,23 @@ impl TextEditorTool {
                 description: obfstr!("Line number for insert command").to_string(),
                 required: false,
                 default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "start_line".to_string(),
+                param_type: "integer".to_string(),
+                description: obfstr!("First line for delete_lines/overwrite_lines, starting at 1").to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "end_line".to_string(),
+                param_type: "integer".to_string(),
+                description: obfstr!("Last line (inclusive) for delete_lines/overwrite_lines").to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
             },
             ToolParameter {
                 name: "view_range".to_string(),
@@ -88,6 +120,16 @@ impl TextEditorTool {
                 description: obfstr!("Line range for view command [start, end]").to_string(),
                 required: false,
                 default: None,
+                ..Default::default()
+            }
+            .with_items("integer", Some(2), Some(2)),
+            ToolParameter {
+                name: "view_page".to_string(),
+                param_type: "integer".to_string(),
+                description: obfstr!("Page of a large file for view command, 200 lines per page starting at 1").to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
             },
         ];
 
@@ -214,6 +256,16 @@ impl TextEditorTool {
         Ok(output)
     }
 
+    fn view_page(&self, path: &Path, page: usize) -> Result<String> {
+        let numbered = ToolResult::success(String::new(), self.view_file(path, None)?);
+        let pages = numbered.split_into_pages(VIEW_PAGE_LINES);
+        let content = page.checked_sub(1)
+            .and_then(|index| pages.get(index))
+            .map(|result| result.content.clone())
+            .ok_or_else(|| anyhow::anyhow!("view_page {} is out of range (1-{})", page, pages.len()))?;
+        Ok(format!("{}\n[page {} of {}]", content, page, pages.len()))
+    }
+
     fn create_file(&self, path: &Path, content: &str) -> Result<String> {
         if path.exists() {
             return Err(anyhow::anyhow!(
@@ -277,6 +329,31 @@ impl TextEditorTool {
 
         Ok(format!("Successfully inserted content at line {} in {}", insert_line + 1, path.display()))
     }
+
+    /// Replace lines `start_line..=end_line` (1-based) with the lines of `new_str`, or
+    /// remove them when it is `None`. A trailing newline is kept, never added
+    fn replace_lines(&self, path: &Path, start_line: usize, end_line: usize, new_str: Option<&str>) -> Result<()> {
+        let content = fs::read_to_string(path)?;
+        let mut lines: Vec<&str> = content.lines().collect();
+
+        if start_line < 1 {
+            return Err(anyhow::anyhow!("start_line must be at least 1"));
+        }
+        if end_line < start_line {
+            return Err(anyhow::anyhow!("end_line {} is before start_line {}", end_line, start_line));
+        }
+        if end_line > lines.len() {
+            return Err(anyhow::anyhow!("end_line {} is beyond the file length {}", end_line, lines.len()));
+        }
+
+        lines.splice(start_line - 1..end_line, new_str.map(|text| text.lines().collect::<Vec<_>>()).unwrap_or_default());
+        let mut new_content = lines.join("\n");
+        if content.ends_with('\n') && !new_content.is_empty() {
+            new_content.push('\n');
+        }
+        fs::write(path, new_content)?;
+        Ok(())
+    }
 }
 
 
@@ -301,7 +378,13 @@ impl Tool for TextEditorTool {
         self.validate_path(path)?;
 
         match args.command.as_str() {
-            "view" => self.view_file(path, args.view_range),
+            "view" => match args.view_page {
+                Some(_) if args.view_range.is_some() => {
+                    Err(anyhow::anyhow!("view_range and view_page cannot be combined"))
+                }
+                Some(page) => self.view_page(path, page),
+                None => self.view_file(path, args.view_range),
+            },
 
             "create" => {
                 let content = args.file_text
@@ -323,8 +406,24 @@ impl Tool for TextEditorTool {
                 self.insert_at_line(path, insert_line, &new_str)
             }
 
+            "delete_lines" | "overwrite_lines" => {
+                let command = args.command.as_str();
+                let start_line = args.start_line
+                    .ok_or_else(|| anyhow::anyhow!("start_line is required for {} command", command))?;
+                let end_line = args.end_line
+                    .ok_or_else(|| anyhow::anyhow!("end_line is required for {} command", command))?;
+                if command == "delete_lines" {
+                    self.replace_lines(path, start_line, end_line, None)?;
+                    return Ok(format!("Deleted lines {}–{} from {}", start_line, end_line, path.display()));
+                }
+                let new_str = args.new_str
+                    .ok_or_else(|| anyhow::anyhow!("new_str is required for overwrite_lines command"))?;
+                self.replace_lines(path, start_line, end_line, Some(&new_str))?;
+                Ok(format!("Overwrote lines {}–{} in {}", start_line, end_line, path.display()))
+            }
+
             _ => Err(anyhow::anyhow!(
-                "Unknown command: {}. Allowed: view, create, str_replace, insert",
+                "Unknown command: {}. Allowed: view, create, str_replace, insert, delete_lines, overwrite_lines",
                 args.command
             )),
         }
@@ -342,6 +441,43 @@ mod tests {
         TextEditorTool::new("test".to_string(), allowed_folders)
     }
 
+    #[test]
+    fn test_function_schema_snapshot() {
+        let function = create_tool_with_allowed_folders(vec![]).to_openai_function();
+        assert_eq!(function["name"], "str_replace_based_edit_tool");
+        assert_eq!(function["parameters"], serde_json::json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "description": "The command to run. Allowed: view, create, str_replace, insert, delete_lines, overwrite_lines",
+                    "enum": ["view", "create", "str_replace", "insert", "delete_lines", "overwrite_lines"]
+                },
+                "path": { "type": "string", "description": "Absolute path to file or directory" },
+                "file_text": { "type": "string", "description": "Content for create command" },
+                "old_str": { "type": "string", "description": "String to replace (for str_replace)" },
+                "new_str": { "type": "string", "description": "Replacement string (for str_replace/insert/overwrite_lines)" },
+                "insert_line": { "type": "integer", "description": "Line number for insert command" },
+                "start_line": { "type": "integer", "description": "First line for delete_lines/overwrite_lines, starting at 1" },
+                "end_line": { "type": "integer", "description": "Last line (inclusive) for delete_lines/overwrite_lines" },
+                "view_range": {
+                    "type": "array",
+                    "description": "Line range for view command [start, end]",
+                    "items": { "type": "integer" },
+                    "minItems": 2,
+                    "maxItems": 2
+                },
+                "view_page": {
+                    "type": "integer",
+                    "description": "Page of a large file for view command, 200 lines per page starting at 1"
+                }
+            },
+            "required": ["command", "path"]
+        }));
+        // Anthropic tools carry the same schema
+        assert_eq!(create_tool_with_allowed_folders(vec![]).to_anthropic_tool()["input_schema"], function["parameters"]);
+    }
+
     #[test]
     fn test_validate_path_rejects_relative_paths() {
         let tool = create_tool_with_allowed_folders(vec!["/tmp".to_string()]);
@@ -657,4 +793,93 @@ mod tests {
             );
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_view_page_of_large_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("big.vhd");
+        let content: Vec<String> = (1..=1000).map(|i| format!("-- line {}", i)).collect();
+        fs::write(&file, content.join("\n")).unwrap();
+
+        let tool = create_tool_with_allowed_folders(vec![]);
+        let view = |page: usize| tool.execute(&serde_json::json!({
+            "command": "view",
+            "path": file.to_str().unwrap(),
+            "view_page": page
+        }));
+
+        let first = view(1).unwrap();
+        assert!(first.starts_with("     1→-- line 1\n"));
+        assert!(first.ends_with("   200→-- line 200\n[page 1 of 5]"));
+
+        let last = view(5).unwrap();
+        assert!(last.starts_with("   801→-- line 801\n"));
+        assert!(last.ends_with("  1000→-- line 1000\n[page 5 of 5]"));
+        assert_eq!(last.lines().count(), 201);
+
+        assert_eq!(view(6).unwrap_err().to_string(), "view_page 6 is out of range (1-5)");
+        assert!(view(0).is_err());
+    }
+
+    #[test]
+    fn test_delete_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("lines.vhd");
+        let tool = create_tool_with_allowed_folders(vec![]);
+        let delete = |start: usize, end: usize| tool.execute(&serde_json::json!({
+            "command": "delete_lines",
+            "path": file.to_str().unwrap(),
+            "start_line": start,
+            "end_line": end
+        }));
+
+        // (start, end, content left)
+        for (start, end, expected) in [
+            (1, 1, "b\nc\nd\ne\n"),
+            (5, 5, "a\nb\nc\nd\n"),
+            (2, 4, "a\ne\n"),
+            (1, 5, ""),
+        ] {
+            fs::write(&file, "a\nb\nc\nd\ne\n").unwrap();
+            let result = delete(start, end).unwrap();
+            assert_eq!(result, format!("Deleted lines {}–{} from {}", start, end, file.display()));
+            assert_eq!(fs::read_to_string(&file).unwrap(), expected);
+        }
+
+        // No trailing newline is added to a file without one
+        fs::write(&file, "a\nb\nc").unwrap();
+        delete(3, 3).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a\nb");
+
+        assert_eq!(delete(0, 1).unwrap_err().to_string(), "start_line must be at least 1");
+        assert_eq!(delete(2, 1).unwrap_err().to_string(), "end_line 1 is before start_line 2");
+        assert_eq!(delete(1, 3).unwrap_err().to_string(), "end_line 3 is beyond the file length 2");
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a\nb");
+    }
+
+    #[test]
+    fn test_overwrite_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("lines.vhd");
+        fs::write(&file, "a\nb\nc\nd\n").unwrap();
+        let tool = create_tool_with_allowed_folders(vec![]);
+
+        let result = tool.execute(&serde_json::json!({
+            "command": "overwrite_lines",
+            "path": file.to_str().unwrap(),
+            "start_line": 2,
+            "end_line": 3,
+            "new_str": "x\ny\nz"
+        })).unwrap();
+        assert_eq!(result, format!("Overwrote lines 2–3 in {}", file.display()));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a\nx\ny\nz\nd\n");
+
+        let missing = tool.execute(&serde_json::json!({
+            "command": "overwrite_lines",
+            "path": file.to_str().unwrap(),
+            "start_line": 1,
+            "end_line": 1
+        }));
+        assert_eq!(missing.unwrap_err().to_string(), "new_str is required for overwrite_lines command");
+    }
+}
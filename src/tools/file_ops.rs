@@ -0,0 +1,577 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::constants;
+use crate::tools::{BaseToolImpl, Tool, ToolParameter, ToolSchema};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileOpsArguments {
+    command: String,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    destination: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    create_parents: bool,
+}
+
+/// Tool for moving, copying, deleting files and creating directories
+pub struct FileOpsTool {
+    base: BaseToolImpl,
+    allowed_folders: Vec<String>,
+}
+
+impl FileOpsTool {
+    pub fn new(allowed_folders: Vec<String>) -> Self {
+        let parameters = vec![
+            ToolParameter {
+                name: "command".to_string(),
+                param_type: "string".to_string(),
+                description: "The operation to run. Allowed: move, copy, delete, mkdir".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "source".to_string(),
+                param_type: "string".to_string(),
+                description: "Absolute source path (for move/copy)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "destination".to_string(),
+                param_type: "string".to_string(),
+                description: "Absolute destination path (for move/copy). Must not exist.".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "path".to_string(),
+                param_type: "string".to_string(),
+                description: "Absolute path to delete or create (for delete/mkdir)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "dry_run".to_string(),
+                param_type: "boolean".to_string(),
+                description: "For delete: only report what would be deleted".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "create_parents".to_string(),
+                param_type: "boolean".to_string(),
+                description: "For mkdir: also create missing parent directories".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+        ];
+
+        let base = BaseToolImpl::new(
+            constants::TOOL_FILE_OPS.to_string(),
+            "Move, copy or delete files and directories, or create directories. All paths must be absolute and within the allowed folders.".to_string(),
+            parameters,
+        );
+
+        Self {
+            base,
+            allowed_folders,
+        }
+    }
+
+    fn validate_path(&self, path: &Path) -> Result<()> {
+        if !path.is_absolute() {
+            return Err(anyhow::anyhow!(
+                "Path must be absolute, starting with '/'. Got: {}",
+                path.display()
+            ));
+        }
+
+        if self.allowed_folders.is_empty() {
+            return Ok(());
+        }
+
+        // Canonicalize the path, or its parent if the path does not exist yet
+        let path_to_check = if let Ok(canonical) = path.canonicalize() {
+            canonical
+        } else if let Some(parent_canonical) = path.parent().and_then(|p| p.canonicalize().ok()) {
+            parent_canonical.join(path.file_name().unwrap_or_default())
+        } else {
+            path.to_path_buf()
+        };
+
+        let path_str = path.to_string_lossy();
+        if (path_str.contains("/../") || path_str.ends_with("/..")) && path_to_check == path {
+            return Err(anyhow::anyhow!(
+                "Path {} is not within allowed folders",
+                path.display()
+            ));
+        }
+
+        for allowed_folder in &self.allowed_folders {
+            let allowed_canonical = Path::new(allowed_folder)
+                .canonicalize()
+                .unwrap_or_else(|_| PathBuf::from(allowed_folder));
+
+            if path_to_check.starts_with(&allowed_canonical) {
+                return Ok(());
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "Path {} is not within allowed folders",
+            path.display()
+        ))
+    }
+
+    fn is_allowed_root(&self, path: &Path) -> bool {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        canonical == Path::new("/")
+            || self.allowed_folders.iter().any(|allowed| {
+                Path::new(allowed)
+                    .canonicalize()
+                    .map(|a| a == canonical)
+                    .unwrap_or(false)
+            })
+    }
+
+    /// Copy a file or directory tree; symlinks are copied as links rather than followed,
+    /// so a link inside an allowed folder cannot pull in data from outside it
+    fn copy_path(source: &Path, destination: &Path) -> Result<u64> {
+        let metadata = fs::symlink_metadata(source)
+            .context(format!("Failed to read {}", source.display()))?;
+        if metadata.file_type().is_symlink() {
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            Self::copy_symlink(source, destination)?;
+            Ok(1)
+        } else if metadata.is_dir() {
+            fs::create_dir_all(destination)
+                .context(format!("Failed to create directory: {}", destination.display()))?;
+
+            let mut copied = 0;
+            for entry in fs::read_dir(source)? {
+                let entry = entry?;
+                copied += Self::copy_path(&entry.path(), &destination.join(entry.file_name()))?;
+            }
+            Ok(copied)
+        } else {
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(source, destination)
+                .context(format!("Failed to copy {} to {}", source.display(), destination.display()))?;
+            Ok(1)
+        }
+    }
+
+    #[cfg(unix)]
+    fn copy_symlink(source: &Path, destination: &Path) -> Result<()> {
+        let target = fs::read_link(source)?;
+        std::os::unix::fs::symlink(&target, destination)
+            .context(format!("Failed to copy link {} to {}", source.display(), destination.display()))
+    }
+
+    #[cfg(not(unix))]
+    fn copy_symlink(source: &Path, _destination: &Path) -> Result<()> {
+        Err(anyhow::anyhow!("Refusing to copy symlink: {}", source.display()))
+    }
+
+    fn remove_path(path: &Path) -> Result<()> {
+        let is_dir = fs::symlink_metadata(path).map(|m| m.is_dir()).unwrap_or(false);
+        if is_dir {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        }
+        .context(format!("Failed to delete: {}", path.display()))
+    }
+
+    /// Move via rename, falling back to copy + delete only when the destination is on
+    /// another filesystem; a failed copy is removed again
+    fn move_with_rename<F>(source: &Path, destination: &Path, rename: F) -> Result<String>
+    where
+        F: Fn(&Path, &Path) -> io::Result<()>,
+    {
+        match rename(source, destination) {
+            Ok(()) => Ok(format!(
+                "Moved {} to {}",
+                source.display(),
+                destination.display()
+            )),
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+                tracing::debug!("Rename failed ({}), falling back to copy and delete", e);
+                let copied = match Self::copy_path(source, destination) {
+                    Ok(copied) => copied,
+                    Err(e) => {
+                        if destination.symlink_metadata().is_ok() {
+                            Self::remove_path(destination).ok();
+                        }
+                        return Err(e);
+                    }
+                };
+                Self::remove_path(source)?;
+                Ok(format!(
+                    "Moved {} to {} (copied {} file(s), then deleted source)",
+                    source.display(),
+                    destination.display(),
+                    copied
+                ))
+            }
+            Err(e) => Err(e).context(format!(
+                "Failed to move {} to {}",
+                source.display(),
+                destination.display()
+            )),
+        }
+    }
+
+    fn check_transfer(source: &Path, destination: &Path) -> Result<()> {
+        if !source.exists() {
+            return Err(anyhow::anyhow!("Source does not exist: {}", source.display()));
+        }
+        if destination.exists() {
+            return Err(anyhow::anyhow!(
+                "Destination already exists: {}. Delete it first if you want to replace it.",
+                destination.display()
+            ));
+        }
+        if source.is_dir() && destination.starts_with(source) {
+            return Err(anyhow::anyhow!("Cannot move or copy a directory into itself"));
+        }
+        Ok(())
+    }
+
+    fn move_path(&self, source: &Path, destination: &Path) -> Result<String> {
+        Self::check_transfer(source, destination)?;
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Self::move_with_rename(source, destination, |a, b| fs::rename(a, b))
+    }
+
+    fn copy(&self, source: &Path, destination: &Path) -> Result<String> {
+        Self::check_transfer(source, destination)?;
+        let copied = Self::copy_path(source, destination)?;
+        Ok(format!(
+            "Copied {} to {} ({} file(s))",
+            source.display(),
+            destination.display(),
+            copied
+        ))
+    }
+
+    /// Paths a delete would remove; symlinks are leaves, so link cycles and
+    /// targets outside the tree are never walked
+    fn collect_paths(path: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+        let metadata = fs::symlink_metadata(path)
+            .context(format!("Failed to read {}", path.display()))?;
+        if metadata.is_dir() {
+            for entry in fs::read_dir(path)? {
+                Self::collect_paths(&entry?.path(), paths)?;
+            }
+        }
+        paths.push(path.to_path_buf());
+        Ok(())
+    }
+
+    fn delete(&self, path: &Path, dry_run: bool) -> Result<String> {
+        if !path.exists() {
+            return Err(anyhow::anyhow!("Path does not exist: {}", path.display()));
+        }
+
+        if self.is_allowed_root(path) {
+            return Err(anyhow::anyhow!(
+                "Refusing to delete {}: it is an allowed folder root",
+                path.display()
+            ));
+        }
+
+        let mut paths = Vec::new();
+        Self::collect_paths(path, &mut paths)?;
+
+        if dry_run {
+            let mut output = format!("Dry run: would delete {} path(s):\n", paths.len());
+            for p in &paths {
+                output.push_str(&format!("  {}\n", p.display()));
+            }
+            return Ok(output);
+        }
+
+        Self::remove_path(path)?;
+        Ok(format!("Deleted {} ({} path(s))", path.display(), paths.len()))
+    }
+
+    fn mkdir(&self, path: &Path, create_parents: bool) -> Result<String> {
+        if path.exists() {
+            return Err(anyhow::anyhow!("Path already exists: {}", path.display()));
+        }
+
+        if create_parents {
+            fs::create_dir_all(path)
+        } else {
+            fs::create_dir(path)
+        }
+        .context(format!("Failed to create directory: {}", path.display()))?;
+
+        Ok(format!("Created directory {}", path.display()))
+    }
+}
+
+impl Tool for FileOpsTool {
+    fn name(&self) -> &str {
+        &self.base.name
+    }
+
+    fn description(&self) -> &str {
+        &self.base.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.base.schema.clone()
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        let args: FileOpsArguments = serde_json::from_value(arguments.clone())
+            .context("Invalid arguments for file_ops tool")?;
+
+        match args.command.as_str() {
+            "move" | "copy" => {
+                let source = args.source
+                    .ok_or_else(|| anyhow::anyhow!("source is required for {} command", args.command))?;
+                let destination = args.destination
+                    .ok_or_else(|| anyhow::anyhow!("destination is required for {} command", args.command))?;
+
+                let source = Path::new(&source);
+                let destination = Path::new(&destination);
+                self.validate_path(source)?;
+                self.validate_path(destination)?;
+
+                if args.command == "move" {
+                    self.move_path(source, destination)
+                } else {
+                    self.copy(source, destination)
+                }
+            }
+
+            "delete" => {
+                let path = args.path
+                    .ok_or_else(|| anyhow::anyhow!("path is required for delete command"))?;
+                let path = Path::new(&path);
+                self.validate_path(path)?;
+                self.delete(path, args.dry_run)
+            }
+
+            "mkdir" => {
+                let path = args.path
+                    .ok_or_else(|| anyhow::anyhow!("path is required for mkdir command"))?;
+                let path = Path::new(&path);
+                self.validate_path(path)?;
+                self.mkdir(path, args.create_parents)
+            }
+
+            _ => Err(anyhow::anyhow!(
+                "Unknown command: {}. Allowed: move, copy, delete, mkdir",
+                args.command
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn tool_for(dirs: &[&TempDir]) -> FileOpsTool {
+        FileOpsTool::new(dirs.iter().map(|d| d.path().to_str().unwrap().to_string()).collect())
+    }
+
+    #[test]
+    fn test_move_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("alu.vhd");
+        let destination = temp_dir.path().join("rtl/alu.vhd");
+        fs::write(&source, "entity alu is end entity;").unwrap();
+
+        let tool = tool_for(&[&temp_dir]);
+        tool.execute(&serde_json::json!({
+            "command": "move",
+            "source": source.to_str().unwrap(),
+            "destination": destination.to_str().unwrap()
+        })).unwrap();
+
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "entity alu is end entity;");
+    }
+
+    #[test]
+    fn test_copy_directory_between_folders() {
+        let src_dir = TempDir::new().unwrap();
+        let dst_dir = TempDir::new().unwrap();
+        fs::create_dir_all(src_dir.path().join("rtl/core")).unwrap();
+        fs::write(src_dir.path().join("rtl/top.vhd"), "top").unwrap();
+        fs::write(src_dir.path().join("rtl/core/alu.vhd"), "alu").unwrap();
+
+        let tool = tool_for(&[&src_dir, &dst_dir]);
+        let result = tool.execute(&serde_json::json!({
+            "command": "copy",
+            "source": src_dir.path().join("rtl").to_str().unwrap(),
+            "destination": dst_dir.path().join("rtl_copy").to_str().unwrap()
+        })).unwrap();
+
+        assert!(result.contains("2 file(s)"));
+        assert!(src_dir.path().join("rtl/core/alu.vhd").exists());
+        assert_eq!(fs::read_to_string(dst_dir.path().join("rtl_copy/core/alu.vhd")).unwrap(), "alu");
+    }
+
+    #[test]
+    fn test_move_across_filesystems_falls_back_to_copy() {
+        let src_dir = TempDir::new().unwrap();
+        let dst_dir = TempDir::new().unwrap();
+        let source = src_dir.path().join("fifo.vhd");
+        let destination = dst_dir.path().join("fifo.vhd");
+        fs::write(&source, "fifo").unwrap();
+
+        // Simulate the EXDEV error returned by rename across filesystems
+        let result = FileOpsTool::move_with_rename(&source, &destination, |_, _| {
+            Err(io::Error::from(io::ErrorKind::CrossesDevices))
+        }).unwrap();
+
+        assert!(result.contains("copied 1 file(s)"));
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "fifo");
+
+        // Any other rename error is reported without copying
+        fs::write(&source, "fifo").unwrap();
+        let other = dst_dir.path().join("other.vhd");
+        let err = FileOpsTool::move_with_rename(&source, &other, |_, _| {
+            Err(io::Error::from(io::ErrorKind::PermissionDenied))
+        }).unwrap_err();
+
+        assert!(err.to_string().starts_with("Failed to move"));
+        assert!(source.exists());
+        assert!(!other.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_keeps_symlinks_as_links() {
+        let outside = TempDir::new().unwrap();
+        let src_dir = TempDir::new().unwrap();
+        fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        fs::create_dir_all(src_dir.path().join("rtl")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), src_dir.path().join("rtl/outside")).unwrap();
+
+        let copied = FileOpsTool::copy_path(&src_dir.path().join("rtl"), &src_dir.path().join("rtl_copy")).unwrap();
+
+        let link = src_dir.path().join("rtl_copy/outside");
+        assert_eq!(copied, 1);
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), outside.path());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_delete_treats_symlinks_as_leaves() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("build");
+        fs::create_dir_all(target.join("obj")).unwrap();
+        fs::write(target.join("obj/alu.o"), "").unwrap();
+        std::os::unix::fs::symlink(&target, target.join("obj/loop")).unwrap();
+
+        let tool = tool_for(&[&temp_dir]);
+        let result = tool.execute(&serde_json::json!({
+            "command": "delete",
+            "path": target.to_str().unwrap(),
+            "dry_run": true
+        })).unwrap();
+        assert!(result.contains("would delete 4 path(s)"));
+
+        tool.execute(&serde_json::json!({
+            "command": "delete",
+            "path": target.to_str().unwrap()
+        })).unwrap();
+        assert!(!target.exists());
+        assert!(temp_dir.path().exists());
+    }
+
+    #[test]
+    fn test_delete_dry_run_keeps_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("build");
+        fs::create_dir_all(target.join("obj")).unwrap();
+        fs::write(target.join("obj/alu.o"), "").unwrap();
+
+        let tool = tool_for(&[&temp_dir]);
+        let result = tool.execute(&serde_json::json!({
+            "command": "delete",
+            "path": target.to_str().unwrap(),
+            "dry_run": true
+        })).unwrap();
+
+        assert!(result.contains("would delete 3 path(s)"));
+        assert!(result.contains("alu.o"));
+        assert!(target.join("obj/alu.o").exists());
+
+        tool.execute(&serde_json::json!({
+            "command": "delete",
+            "path": target.to_str().unwrap()
+        })).unwrap();
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn test_delete_refuses_allowed_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = tool_for(&[&temp_dir]);
+
+        let result = tool.execute(&serde_json::json!({
+            "command": "delete",
+            "path": temp_dir.path().to_str().unwrap()
+        }));
+
+        assert!(result.is_err());
+        assert!(temp_dir.path().exists());
+    }
+
+    #[test]
+    fn test_mkdir_and_allowed_folders() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = tool_for(&[&temp_dir]);
+        let nested = temp_dir.path().join("a/b/c");
+
+        assert!(tool.execute(&serde_json::json!({
+            "command": "mkdir",
+            "path": nested.to_str().unwrap()
+        })).is_err());
+
+        tool.execute(&serde_json::json!({
+            "command": "mkdir",
+            "path": nested.to_str().unwrap(),
+            "create_parents": true
+        })).unwrap();
+        assert!(nested.is_dir());
+
+        let outside = tool.execute(&serde_json::json!({
+            "command": "mkdir",
+            "path": "/definitely_not_allowed_dir"
+        }));
+        assert!(outside.is_err());
+    }
+}
@@ -0,0 +1,349 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+use crate::constants;
+use crate::tools::{path_within, BaseToolImpl, Tool, ToolParameter, ToolSchema};
+
+const DEFAULT_MAX_RESULTS: usize = 1000;
+
+/// A single filesystem match returned by `FindTool`
+#[derive(Debug, Clone, Serialize)]
+struct FoundEntry {
+    path: String,
+    size: u64,
+    modified_unix: u64,
+    kind: String,
+}
+
+/// Tool for searching the filesystem by name pattern, kind, extension, depth and size
+pub struct FindTool {
+    base: BaseToolImpl,
+    allowed_folders: Vec<String>,
+}
+
+impl FindTool {
+    pub fn new(allowed_folders: Vec<String>) -> Self {
+        let parameters = vec![
+            ToolParameter {
+                name: "directory".to_string(),
+                param_type: "string".to_string(),
+                description: "Absolute path of the directory to search".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "name_pattern".to_string(),
+                param_type: "string".to_string(),
+                description: "Glob pattern matched against the file name (e.g. '*_tb.vhd')".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "file_type".to_string(),
+                param_type: "string".to_string(),
+                description: "Kind of entry to return: 'file', 'directory', or 'any'".to_string(),
+                required: false,
+                default: Some(serde_json::json!("any")),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "extensions".to_string(),
+                param_type: "array".to_string(),
+                description: "File extensions to match, without the dot (e.g. [\"vhd\", \"vhdl\"])".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "max_depth".to_string(),
+                param_type: "integer".to_string(),
+                description: "Maximum depth below the directory (1 = direct children only)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "min_size_bytes".to_string(),
+                param_type: "integer".to_string(),
+                description: "Only return entries at least this many bytes in size".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "max_results".to_string(),
+                param_type: "integer".to_string(),
+                description: "Maximum number of results to return".to_string(),
+                required: false,
+                default: Some(serde_json::json!(DEFAULT_MAX_RESULTS)),
+                ..Default::default()
+            },
+        ];
+
+        let base = BaseToolImpl::new(
+            constants::TOOL_FIND.to_string(),
+            "Search a directory tree for files or directories matching a name pattern, extensions, depth and size. Returns a JSON array of {path, size, modified_unix, kind}.".to_string(),
+            parameters,
+        );
+
+        Self {
+            base,
+            allowed_folders,
+        }
+    }
+}
+
+impl Tool for FindTool {
+    fn name(&self) -> &str {
+        &self.base.name
+    }
+
+    fn description(&self) -> &str {
+        &self.base.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.base.schema.clone()
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        let directory = arguments
+            .get("directory")
+            .and_then(|v| v.as_str())
+            .context("Missing 'directory' argument")?;
+
+        let name_pattern = arguments
+            .get("name_pattern")
+            .and_then(|v| v.as_str())
+            .map(glob::Pattern::new)
+            .transpose()
+            .context("Invalid 'name_pattern' glob")?;
+
+        let file_type = arguments
+            .get("file_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("any");
+
+        if !matches!(file_type, "file" | "directory" | "any") {
+            return Err(anyhow::anyhow!(
+                "Invalid file_type '{}'. Allowed: file, directory, any",
+                file_type
+            ));
+        }
+
+        let extensions: Vec<String> = arguments
+            .get("extensions")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|e| e.trim_start_matches('.').to_lowercase())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let max_depth = arguments
+            .get("max_depth")
+            .and_then(|v| v.as_u64())
+            .map(|d| d as usize)
+            .unwrap_or(usize::MAX);
+
+        let min_size_bytes = arguments
+            .get("min_size_bytes")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+
+        let max_results = arguments
+            .get("max_results")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_MAX_RESULTS);
+
+        let dir_path = Path::new(directory);
+
+        // Check if path is allowed
+        if !path_within(&self.allowed_folders, dir_path) {
+            return Err(anyhow::anyhow!(
+                "Access denied: '{}' is not in allowed folders",
+                directory
+            ));
+        }
+
+        if !dir_path.is_dir() {
+            return Err(anyhow::anyhow!("'{}' is not a directory", directory));
+        }
+
+        let mut results = Vec::new();
+
+        let walker = WalkDir::new(dir_path)
+            .min_depth(1)
+            .max_depth(max_depth)
+            .sort_by_file_name();
+
+        for entry in walker {
+            if results.len() >= max_results {
+                break;
+            }
+
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable entry: {}", e);
+                    continue;
+                }
+            };
+
+            let is_dir = entry.file_type().is_dir();
+            match file_type {
+                "file" if is_dir => continue,
+                "directory" if !is_dir => continue,
+                _ => {}
+            }
+
+            let file_name = entry.file_name().to_string_lossy();
+            if let Some(pattern) = &name_pattern {
+                if !pattern.matches(&file_name) {
+                    continue;
+                }
+            }
+
+            if !extensions.is_empty() {
+                let ext = entry
+                    .path()
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                if !extensions.contains(&ext) {
+                    continue;
+                }
+            }
+
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    tracing::warn!("Failed to read metadata for {}: {}", entry.path().display(), e);
+                    continue;
+                }
+            };
+
+            if metadata.len() < min_size_bytes {
+                continue;
+            }
+
+            let modified_unix = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+
+            results.push(FoundEntry {
+                path: entry.path().display().to_string(),
+                size: metadata.len(),
+                modified_unix,
+                kind: if is_dir { "directory" } else { "file" }.to_string(),
+            });
+        }
+
+        Ok(serde_json::to_string_pretty(&results)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_project() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        fs::create_dir_all(root.join("rtl/core")).unwrap();
+        fs::write(root.join("top.vhd"), "entity top is end entity;").unwrap();
+        fs::write(root.join("rtl/alu.vhd"), "entity alu is end entity;").unwrap();
+        fs::write(root.join("rtl/notes.txt"), "notes").unwrap();
+        fs::write(root.join("rtl/core/regfile.VHD"), "entity regfile is end entity;").unwrap();
+
+        temp_dir
+    }
+
+    fn run_find(tool: &FindTool, args: serde_json::Value) -> Vec<serde_json::Value> {
+        let output = tool.execute(&args).unwrap();
+        serde_json::from_str(&output).unwrap()
+    }
+
+    #[test]
+    fn test_find_vhdl_files_recursively() {
+        let project = create_project();
+        let tool = FindTool::new(vec![]);
+
+        let results = run_find(&tool, serde_json::json!({
+            "directory": project.path().to_str().unwrap(),
+            "extensions": ["vhd"],
+            "file_type": "file"
+        }));
+
+        assert_eq!(results.len(), 3);
+        for result in &results {
+            assert_eq!(result["kind"], "file");
+            assert!(result["size"].as_u64().unwrap() > 0);
+        }
+    }
+
+    #[test]
+    fn test_find_respects_max_depth() {
+        let project = create_project();
+        let tool = FindTool::new(vec![]);
+
+        let results = run_find(&tool, serde_json::json!({
+            "directory": project.path().to_str().unwrap(),
+            "extensions": ["vhd"],
+            "max_depth": 2
+        }));
+
+        let paths: Vec<&str> = results.iter().map(|r| r["path"].as_str().unwrap()).collect();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.iter().any(|p| p.ends_with("top.vhd")));
+        assert!(paths.iter().any(|p| p.ends_with("alu.vhd")));
+        assert!(!paths.iter().any(|p| p.ends_with("regfile.VHD")));
+    }
+
+    #[test]
+    fn test_find_with_name_pattern_and_directories() {
+        let project = create_project();
+        let tool = FindTool::new(vec![]);
+
+        let results = run_find(&tool, serde_json::json!({
+            "directory": project.path().to_str().unwrap(),
+            "name_pattern": "a*"
+        }));
+        assert_eq!(results.len(), 1);
+        assert!(results[0]["path"].as_str().unwrap().ends_with("alu.vhd"));
+
+        let results = run_find(&tool, serde_json::json!({
+            "directory": project.path().to_str().unwrap(),
+            "file_type": "directory"
+        }));
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r["kind"] == "directory"));
+    }
+
+    #[test]
+    fn test_find_honours_allowed_folders() {
+        let project = create_project();
+        let other = TempDir::new().unwrap();
+        let tool = FindTool::new(vec![other.path().to_str().unwrap().to_string()]);
+
+        let result = tool.execute(&serde_json::json!({
+            "directory": project.path().to_str().unwrap()
+        }));
+
+        assert!(result.is_err());
+    }
+}
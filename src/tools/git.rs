@@ -0,0 +1,421 @@
+//! Version control for the agent: status, diffs, staging, commits and file restores
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use crate::constants;
+use crate::tools::{BaseToolImpl, Tool, ToolParameter, ToolSchema};
+
+/// Number of commits `log` shows when `n` is not given
+const DEFAULT_LOG_COUNT: usize = 10;
+
+#[derive(Debug, Clone, Deserialize)]
+struct GitArguments {
+    command: String,
+    #[serde(default)]
+    repository: Option<String>,
+    #[serde(default)]
+    paths: Vec<String>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    n: Option<usize>,
+    #[serde(default)]
+    path: Option<String>,
+}
+
+/// One `@@` hunk of a diff, with its header line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GitHunk {
+    pub file: String,
+    pub hunk: String,
+}
+
+/// `git diff` output, summarized and split into hunks
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GitDiff {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub hunks: Vec<GitHunk>,
+}
+
+impl GitDiff {
+    /// Parse the unified diff printed by `git diff`
+    pub fn parse(output: &str) -> Self {
+        let mut diff = GitDiff::default();
+        let mut file = String::new();
+        let mut hunk: Option<String> = None;
+
+        for line in output.lines() {
+            if let Some(header) = line.strip_prefix("diff --git ") {
+                diff.hunks.extend(hunk.take().map(|hunk| GitHunk { file: file.clone(), hunk }));
+                diff.files_changed += 1;
+                // `a/<path> b/<path>`; refined by the `+++`/`---` lines when present
+                file = header.rsplit_once(" b/").map_or(header, |(_, path)| path).to_string();
+            } else if hunk.is_none() && line.starts_with("+++ ") {
+                if let Some(path) = line.strip_prefix("+++ b/") {
+                    file = path.to_string();
+                }
+            } else if line.starts_with("@@") {
+                diff.hunks.extend(hunk.take().map(|hunk| GitHunk { file: file.clone(), hunk }));
+                hunk = Some(format!("{}\n", line));
+            } else if let Some(text) = hunk.as_mut() {
+                match line.as_bytes().first() {
+                    Some(b'+') => diff.insertions += 1,
+                    Some(b'-') => diff.deletions += 1,
+                    _ => {}
+                }
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        diff.hunks.extend(hunk.map(|hunk| GitHunk { file, hunk }));
+        diff
+    }
+}
+
+/// Tool running a fixed set of git commands in a repository within the allowed folders
+pub struct GitTool {
+    base: BaseToolImpl,
+    allowed_folders: Vec<String>,
+}
+
+impl GitTool {
+    pub fn new(allowed_folders: Vec<String>) -> Self {
+        let parameters = vec![
+            ToolParameter {
+                name: "command".to_string(),
+                param_type: "string".to_string(),
+                description: "The git operation to run. Allowed: status, diff, add, commit, log, checkout, branch".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            }
+            .with_enum_values(["status", "diff", "add", "commit", "log", "checkout", "branch"]),
+            ToolParameter {
+                name: "repository".to_string(),
+                param_type: "string".to_string(),
+                description: "Absolute path inside the repository (optional, defaults to the first allowed folder)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "paths".to_string(),
+                param_type: "array".to_string(),
+                description: "Files to stage (for add) or to limit the diff to (for diff), relative to the repository root or absolute".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            }
+            .with_items("string", None, None),
+            ToolParameter {
+                name: "message".to_string(),
+                param_type: "string".to_string(),
+                description: "Commit message (for commit)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "n".to_string(),
+                param_type: "integer".to_string(),
+                description: format!("Number of commits to show (for log, default {})", DEFAULT_LOG_COUNT),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "path".to_string(),
+                param_type: "string".to_string(),
+                description: "File to restore to its committed content (for checkout)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+        ];
+
+        let base = BaseToolImpl::new(
+            constants::TOOL_GIT.to_string(),
+            "Run git in a repository within the allowed folders: status, diff (returned as JSON with per-file hunks), add, commit, log, checkout of a single file to discard its changes, and branch (list only).".to_string(),
+            parameters,
+        );
+
+        Self {
+            base,
+            allowed_folders,
+        }
+    }
+
+    /// Run git in `dir`, failing with its stderr when it exits with an error
+    fn git(dir: &Path, args: &[&str]) -> Result<String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .context("Failed to run git")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let reason = if stderr.trim().is_empty() { stdout } else { stderr };
+            return Err(anyhow::anyhow!("git {} failed: {}", args.first().unwrap_or(&""), reason.trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Root of the repository containing `repository`, which must lie within the allowed folders
+    fn repository_root(&self, repository: Option<&str>) -> Result<PathBuf> {
+        let dir = match repository {
+            Some(repository) => PathBuf::from(repository),
+            None => match self.allowed_folders.first() {
+                Some(folder) => PathBuf::from(folder),
+                None => std::env::current_dir().context("Failed to get the current directory")?,
+            },
+        };
+        if !dir.is_absolute() {
+            return Err(anyhow::anyhow!("Repository path must be absolute. Got: {}", dir.display()));
+        }
+
+        let root = Self::git(&dir, &["rev-parse", "--show-toplevel"])
+            .context(format!("{} is not inside a git repository", dir.display()))?;
+        let root = PathBuf::from(root.trim());
+        let root = root.canonicalize().unwrap_or(root);
+
+        let allowed = self.allowed_folders.is_empty()
+            || self.allowed_folders.iter()
+                .filter_map(|allowed| Path::new(allowed).canonicalize().ok())
+                .any(|allowed| root.starts_with(allowed));
+        if !allowed {
+            return Err(anyhow::anyhow!(
+                "Repository root {} is not within allowed folders",
+                root.display()
+            ));
+        }
+        Ok(root)
+    }
+
+    /// `path` relative to `root`, rejecting paths that leave the repository
+    fn repository_path(root: &Path, path: &str) -> Result<String> {
+        let path = Path::new(path);
+        let relative = if path.is_absolute() {
+            let canonical = path.canonicalize()
+                .or_else(|_| path.parent().map_or(Ok(path.to_path_buf()), |parent| {
+                    parent.canonicalize().map(|parent| parent.join(path.file_name().unwrap_or_default()))
+                }))
+                .unwrap_or_else(|_| path.to_path_buf());
+            canonical.strip_prefix(root)
+                .map_err(|_| anyhow::anyhow!("Path {} is not within the repository {}", path.display(), root.display()))?
+                .to_path_buf()
+        } else {
+            path.to_path_buf()
+        };
+
+        if relative.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+            return Err(anyhow::anyhow!("Path {} is not within the repository {}", path.display(), root.display()));
+        }
+        Ok(relative.to_string_lossy().into_owned())
+    }
+
+    fn repository_paths(root: &Path, paths: &[String]) -> Result<Vec<String>> {
+        paths.iter().map(|path| Self::repository_path(root, path)).collect()
+    }
+
+    fn diff(root: &Path, paths: &[String]) -> Result<String> {
+        let mut args = vec!["diff", "--no-color", "--no-ext-diff"];
+        if !paths.is_empty() {
+            args.push("--");
+            args.extend(paths.iter().map(String::as_str));
+        }
+        let diff = GitDiff::parse(&Self::git(root, &args)?);
+        Ok(serde_json::to_string_pretty(&diff)?)
+    }
+}
+
+impl Tool for GitTool {
+    fn name(&self) -> &str {
+        &self.base.name
+    }
+
+    fn description(&self) -> &str {
+        &self.base.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.base.schema.clone()
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        let args: GitArguments = serde_json::from_value(arguments.clone())
+            .context("Invalid arguments for git tool")?;
+
+        let root = self.repository_root(args.repository.as_deref())?;
+
+        match args.command.as_str() {
+            "status" => Self::git(&root, &["status", "--short", "--branch"]),
+
+            "diff" => Self::diff(&root, &Self::repository_paths(&root, &args.paths)?),
+
+            "add" => {
+                if args.paths.is_empty() {
+                    return Err(anyhow::anyhow!("paths is required for add command"));
+                }
+                let paths = Self::repository_paths(&root, &args.paths)?;
+                let mut git_args = vec!["add", "--"];
+                git_args.extend(paths.iter().map(String::as_str));
+                Self::git(&root, &git_args)?;
+                Ok(format!("Staged {} path(s): {}", paths.len(), paths.join(", ")))
+            }
+
+            "commit" => {
+                let message = args.message
+                    .filter(|message| !message.trim().is_empty())
+                    .ok_or_else(|| anyhow::anyhow!("message is required for commit command"))?;
+                Self::git(&root, &["commit", "-m", &message])
+            }
+
+            "log" => {
+                let count = args.n.unwrap_or(DEFAULT_LOG_COUNT).to_string();
+                Self::git(&root, &["log", "--no-color", "--format=%h %ad %an%n    %s", "--date=short", "-n", &count])
+            }
+
+            "checkout" => {
+                let path = args.path
+                    .ok_or_else(|| anyhow::anyhow!("path is required for checkout command"))?;
+                let path = Self::repository_path(&root, &path)?;
+                Self::git(&root, &["checkout", "--", &path])?;
+                Ok(format!("Restored {}", path))
+            }
+
+            "branch" => Self::git(&root, &["branch", "--list", "--no-color"]),
+
+            _ => Err(anyhow::anyhow!(
+                "Unknown command: {}. Allowed: status, diff, add, commit, log, checkout, branch",
+                args.command
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Repository with one committed file, `rtl/counter.vhd`
+    fn repository() -> TempDir {
+        let repo = TempDir::new().unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["config", "user.name", "Test"],
+            &["config", "user.email", "test@example.com"],
+            &["config", "commit.gpgsign", "false"],
+        ] {
+            GitTool::git(repo.path(), args).unwrap();
+        }
+        fs::create_dir(repo.path().join("rtl")).unwrap();
+        fs::write(repo.path().join("rtl/counter.vhd"), "entity counter is\nend entity;\n").unwrap();
+        GitTool::git(repo.path(), &["add", "."]).unwrap();
+        GitTool::git(repo.path(), &["commit", "-q", "-m", "Add counter"]).unwrap();
+        repo
+    }
+
+    fn run(tool: &GitTool, repo: &TempDir, arguments: serde_json::Value) -> Result<String> {
+        let mut arguments = arguments;
+        arguments["repository"] = repo.path().to_str().unwrap().into();
+        tool.execute(&arguments)
+    }
+
+    #[test]
+    fn test_parse_diff() {
+        let output = "\
+diff --git a/rtl/alu.vhd b/rtl/alu.vhd
+index 1111111..2222222 100644
+--- a/rtl/alu.vhd
++++ b/rtl/alu.vhd
+@@ -1,3 +1,3 @@
+ entity alu is
+--- 8-bit
++-- 16-bit
+ end entity;
+@@ -10,2 +10,3 @@ architecture rtl of alu is
+ begin
++    y <= a;
+ end architecture;
+diff --git a/old.vhd b/old.vhd
+deleted file mode 100644
+--- a/old.vhd
++++ /dev/null
+@@ -1 +0,0 @@
+-entity old is end entity;
+";
+        let diff = GitDiff::parse(output);
+        assert_eq!((diff.files_changed, diff.insertions, diff.deletions), (2, 2, 2));
+        let files: Vec<&str> = diff.hunks.iter().map(|h| h.file.as_str()).collect();
+        assert_eq!(files, vec!["rtl/alu.vhd", "rtl/alu.vhd", "old.vhd"]);
+        assert_eq!(diff.hunks[0].hunk, "@@ -1,3 +1,3 @@\n entity alu is\n--- 8-bit\n+-- 16-bit\n end entity;\n");
+    }
+
+    #[test]
+    fn test_edit_diff_add_commit_log() {
+        let repo = repository();
+        let tool = GitTool::new(vec![repo.path().to_str().unwrap().to_string()]);
+        fs::write(repo.path().join("rtl/counter.vhd"), "entity counter is\n    port(clk : in std_logic);\nend entity;\n").unwrap();
+
+        let diff: serde_json::Value = serde_json::from_str(&run(&tool, &repo, serde_json::json!({"command": "diff"})).unwrap()).unwrap();
+        assert_eq!(diff["files_changed"], 1);
+        assert_eq!(diff["insertions"], 1);
+        assert_eq!(diff["deletions"], 0);
+        assert_eq!(diff["hunks"][0]["file"], "rtl/counter.vhd");
+
+        let status = run(&tool, &repo, serde_json::json!({"command": "status"})).unwrap();
+        assert!(status.contains(" M rtl/counter.vhd"));
+
+        let absolute = repo.path().join("rtl/counter.vhd");
+        run(&tool, &repo, serde_json::json!({"command": "add", "paths": [absolute.to_str().unwrap()]})).unwrap();
+        run(&tool, &repo, serde_json::json!({"command": "commit", "message": "Add clock port"})).unwrap();
+
+        let log = run(&tool, &repo, serde_json::json!({"command": "log", "n": 1})).unwrap();
+        assert!(log.contains("Add clock port"));
+        assert!(!log.contains("Add counter"));
+
+        let branches = run(&tool, &repo, serde_json::json!({"command": "branch"})).unwrap();
+        assert!(branches.starts_with("* "));
+    }
+
+    #[test]
+    fn test_checkout_restores_file() {
+        let repo = repository();
+        let tool = GitTool::new(vec![]);
+        let file = repo.path().join("rtl/counter.vhd");
+        fs::write(&file, "broken").unwrap();
+
+        run(&tool, &repo, serde_json::json!({"command": "checkout", "path": "rtl/counter.vhd"})).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "entity counter is\nend entity;\n");
+
+        let escape = run(&tool, &repo, serde_json::json!({"command": "checkout", "path": "../counter.vhd"}));
+        assert!(escape.unwrap_err().to_string().contains("not within the repository"));
+    }
+
+    #[test]
+    fn test_repository_must_be_within_allowed_folders() {
+        let repo = repository();
+        let other = TempDir::new().unwrap();
+        let tool = GitTool::new(vec![other.path().to_str().unwrap().to_string()]);
+
+        let error = run(&tool, &repo, serde_json::json!({"command": "status"})).unwrap_err();
+        assert!(error.to_string().contains("not within allowed folders"));
+
+        // Without a repository argument the first allowed folder is used
+        let error = tool.execute(&serde_json::json!({"command": "status"})).unwrap_err();
+        assert!(error.to_string().contains("not inside a git repository"));
+
+        let error = run(&GitTool::new(vec![]), &repo, serde_json::json!({"command": "commit"})).unwrap_err();
+        assert!(error.to_string().contains("message is required"));
+    }
+}
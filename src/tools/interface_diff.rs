@@ -0,0 +1,497 @@
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::constants;
+use crate::ir::{Entity, Port, VHDLType};
+use crate::parser::verilog_parser::{parenthesized, split_top_level};
+use crate::parser::ASTVHDLParser;
+use crate::tools::{path_within, BaseToolImpl, Tool, ToolParameter, ToolSchema};
+
+lazy_static! {
+    static ref LINE_COMMENT_RE: Regex = Regex::new(r"//[^\n]*").unwrap();
+    static ref BLOCK_COMMENT_RE: Regex = Regex::new(r"(?s)/\*.*?\*/").unwrap();
+    static ref MODULE_RE: Regex = Regex::new(r"\bmodule\s+(?:automatic\s+|static\s+)?(\w+)\s*").unwrap();
+    static ref RANGE_RE: Regex = Regex::new(r"^\[\s*(-?\d+)\s*:\s*(-?\d+)\s*\]$").unwrap();
+    static ref PARAMETER_RE: Regex = Regex::new(r"(\w+)\s*(?:\[[^\]]*\]\s*)?=").unwrap();
+}
+
+/// Width of a port: a bit count, or the range text when it depends on parameters
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum PortWidth {
+    Bits(u32),
+    Range(String),
+}
+
+impl std::fmt::Display for PortWidth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortWidth::Bits(bits) => write!(f, "{} bit{}", bits, if *bits == 1 { "" } else { "s" }),
+            PortWidth::Range(range) => write!(f, "{}", range),
+        }
+    }
+}
+
+/// A port of an ANSI-style SystemVerilog module header
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SvPort {
+    pub name: String,
+    pub direction: String,
+    pub width: PortWidth,
+}
+
+/// Name, parameters and ports of a SystemVerilog module
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SvModuleHeader {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub ports: Vec<SvPort>,
+}
+
+impl SvModuleHeader {
+    /// Parse the first ANSI-style module header in `source`: `module m #(parameter W = 8)
+    /// (input logic [W-1:0] a, b, output logic y);`. Ports without a direction take the
+    /// previous one, as in SystemVerilog
+    pub fn parse(source: &str) -> Result<Self> {
+        let source = BLOCK_COMMENT_RE.replace_all(source, "");
+        let source = LINE_COMMENT_RE.replace_all(&source, "");
+        let caps = MODULE_RE.captures(&source).context("No module declaration found")?;
+        let name = caps[1].to_string();
+        let mut rest = &source[caps.get(0).map_or(0, |m| m.end())..];
+
+        let mut parameters = Vec::new();
+        if let Some(after_hash) = rest.strip_prefix('#') {
+            let (list, after) = parenthesized(after_hash.trim_start())
+                .context(format!("Unterminated parameter list in module {}", name))?;
+            parameters = split_top_level(list).iter()
+                .filter_map(|parameter| PARAMETER_RE.captures(parameter))
+                .map(|caps| caps[1].to_string())
+                .collect();
+            rest = after.trim_start();
+        }
+
+        let mut ports = Vec::new();
+        if rest.starts_with('(') {
+            let (list, _) = parenthesized(rest).context(format!("Unterminated port list in module {}", name))?;
+            let mut direction: Option<String> = None;
+            let mut width = PortWidth::Bits(1);
+            for declaration in split_top_level(list) {
+                let port = parse_port(declaration, direction.as_deref(), &width)
+                    .context(format!("Unsupported port declaration in module {}: '{}'", name, declaration))?;
+                direction = Some(port.direction.clone());
+                width = port.width.clone();
+                ports.push(port);
+            }
+        }
+
+        Ok(Self { name, parameters, ports })
+    }
+}
+
+/// `[direction] [net/var type] [signed] [range] name [unpacked dims]`; a bare name
+/// continues the previous declaration's direction and width
+fn parse_port(declaration: &str, previous_direction: Option<&str>, previous_width: &PortWidth) -> Option<SvPort> {
+    let mut declaration = declaration.split('=').next().unwrap_or(declaration).trim();
+    // Unpacked dimensions follow the name: `mem [0:3]`
+    while declaration.ends_with(']') {
+        declaration = declaration[..declaration.rfind('[')?].trim_end();
+    }
+    let name_start = declaration.rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .map_or(0, |i| i + 1);
+    let name = &declaration[name_start..];
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut head = declaration[..name_start].trim();
+    let direction = ["input", "output", "inout"].into_iter()
+        .find(|direction| head.split_whitespace().next() == Some(*direction));
+    if head.is_empty() {
+        return Some(SvPort {
+            name: name.to_string(),
+            direction: previous_direction?.to_string(),
+            width: previous_width.clone(),
+        });
+    }
+    if let Some(direction) = direction {
+        head = head[direction.len()..].trim();
+    }
+
+    let width = match head.find('[') {
+        Some(open) => {
+            let range: String = head[open..].chars().filter(|c| !c.is_whitespace()).collect();
+            match RANGE_RE.captures(&range) {
+                Some(caps) => {
+                    let (left, right): (i64, i64) = (caps[1].parse().ok()?, caps[2].parse().ok()?);
+                    PortWidth::Bits(left.abs_diff(right) as u32 + 1)
+                }
+                None => PortWidth::Range(range),
+            }
+        }
+        None if head.split_whitespace().any(|word| word == "int" || word == "integer") => PortWidth::Bits(32),
+        None => PortWidth::Bits(1),
+    };
+
+    Some(SvPort {
+        name: name.to_string(),
+        direction: direction.or(previous_direction)?.to_string(),
+        width,
+    })
+}
+
+/// Width of a VHDL port in the form the SystemVerilog header would use
+fn vhdl_width(port_type: &VHDLType) -> PortWidth {
+    if let Some(bits) = port_type.bit_width() {
+        return PortWidth::Bits(bits);
+    }
+    match port_type {
+        VHDLType::StdLogicVector(range)
+        | VHDLType::BitVector(range)
+        | VHDLType::Signed(range)
+        | VHDLType::Unsigned(range) => PortWidth::Range(range.to_systemverilog().replace(' ', "")),
+        other => PortWidth::Range(other.to_vhdl()),
+    }
+}
+
+/// A VHDL port and a SystemVerilog port that disagree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PortMismatch {
+    pub port: String,
+    pub vhdl: String,
+    pub sv: String,
+}
+
+/// A VHDL port missing from the module whose direction and width match a module port
+/// the entity lacks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PortRename {
+    pub vhdl: String,
+    pub sv: String,
+}
+
+/// Differences between a VHDL entity and a SystemVerilog module header; "added" and
+/// "removed" are from the SystemVerilog side
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InterfaceDiff {
+    pub entity: String,
+    pub module: String,
+    pub added_ports: Vec<String>,
+    pub removed_ports: Vec<String>,
+    pub renamed_ports: Vec<PortRename>,
+    pub direction_mismatches: Vec<PortMismatch>,
+    pub width_mismatches: Vec<PortMismatch>,
+    pub added_parameters: Vec<String>,
+    pub removed_parameters: Vec<String>,
+}
+
+impl InterfaceDiff {
+    /// Compare ports and generics by case-insensitive name
+    pub fn compare(entity: &Entity, module: &SvModuleHeader) -> Self {
+        let mut diff = Self {
+            entity: entity.name.clone(),
+            module: module.name.clone(),
+            added_ports: Vec::new(),
+            removed_ports: Vec::new(),
+            renamed_ports: Vec::new(),
+            direction_mismatches: Vec::new(),
+            width_mismatches: Vec::new(),
+            added_parameters: Vec::new(),
+            removed_parameters: Vec::new(),
+        };
+
+        let find = |name: &str| module.ports.iter().find(|p| p.name.eq_ignore_ascii_case(name));
+        let mut unmatched: Vec<&Port> = Vec::new();
+        for port in &entity.ports {
+            let Some(sv) = find(&port.name) else {
+                unmatched.push(port);
+                continue;
+            };
+            let direction = port.direction.to_systemverilog();
+            if sv.direction != direction {
+                diff.direction_mismatches.push(PortMismatch {
+                    port: port.name.clone(),
+                    vhdl: direction.to_string(),
+                    sv: sv.direction.clone(),
+                });
+            }
+            let width = vhdl_width(&port.port_type);
+            if sv.width != width {
+                diff.width_mismatches.push(PortMismatch {
+                    port: port.name.clone(),
+                    vhdl: width.to_string(),
+                    sv: sv.width.to_string(),
+                });
+            }
+        }
+
+        let mut extra: Vec<&SvPort> = module.ports.iter()
+            .filter(|sv| !entity.ports.iter().any(|p| p.name.eq_ignore_ascii_case(&sv.name)))
+            .collect();
+        for port in unmatched {
+            let width = vhdl_width(&port.port_type);
+            let renamed = extra.iter()
+                .position(|sv| sv.direction == port.direction.to_systemverilog() && sv.width == width);
+            match renamed {
+                Some(i) => {
+                    let sv = extra.remove(i);
+                    diff.renamed_ports.push(PortRename { vhdl: port.name.clone(), sv: sv.name.clone() });
+                }
+                None => diff.removed_ports.push(port.name.clone()),
+            }
+        }
+        diff.added_ports = extra.into_iter().map(|sv| sv.name.clone()).collect();
+
+        diff.removed_parameters = entity.generics.iter()
+            .filter(|g| !module.parameters.iter().any(|p| p.eq_ignore_ascii_case(&g.name)))
+            .map(|g| g.name.clone())
+            .collect();
+        diff.added_parameters = module.parameters.iter()
+            .filter(|p| !entity.generics.iter().any(|g| g.name.eq_ignore_ascii_case(p)))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    pub fn is_match(&self) -> bool {
+        self.added_ports.is_empty()
+            && self.removed_ports.is_empty()
+            && self.renamed_ports.is_empty()
+            && self.direction_mismatches.is_empty()
+            && self.width_mismatches.is_empty()
+            && self.added_parameters.is_empty()
+            && self.removed_parameters.is_empty()
+    }
+
+    /// One line per difference, or "interfaces match"
+    pub fn summary(&self) -> String {
+        let mut output = format!("VHDL entity {} vs SystemVerilog module {}: ", self.entity, self.module);
+        if self.is_match() {
+            output.push_str("interfaces match\n");
+            return output;
+        }
+        output.push_str("interfaces differ\n");
+        for port in &self.added_ports {
+            output.push_str(&format!("  + port {}: only in the SystemVerilog module\n", port));
+        }
+        for port in &self.removed_ports {
+            output.push_str(&format!("  - port {}: missing from the SystemVerilog module\n", port));
+        }
+        for rename in &self.renamed_ports {
+            output.push_str(&format!("  ~ port {} renamed to {}\n", rename.vhdl, rename.sv));
+        }
+        for mismatch in &self.direction_mismatches {
+            output.push_str(&format!("  ! port {}: direction {} in VHDL, {} in SystemVerilog\n", mismatch.port, mismatch.vhdl, mismatch.sv));
+        }
+        for mismatch in &self.width_mismatches {
+            output.push_str(&format!("  ! port {}: width {} in VHDL, {} in SystemVerilog\n", mismatch.port, mismatch.vhdl, mismatch.sv));
+        }
+        for parameter in &self.added_parameters {
+            output.push_str(&format!("  + parameter {}: only in the SystemVerilog module\n", parameter));
+        }
+        for parameter in &self.removed_parameters {
+            output.push_str(&format!("  - parameter {}: missing from the SystemVerilog module\n", parameter));
+        }
+        output
+    }
+}
+
+/// Tool comparing a VHDL entity's ports and generics with a hand-written SystemVerilog module
+pub struct InterfaceDiffTool {
+    base: BaseToolImpl,
+    allowed_folders: Vec<String>,
+}
+
+impl InterfaceDiffTool {
+    pub fn new(allowed_folders: Vec<String>) -> Self {
+        let parameters = vec![
+            ToolParameter {
+                name: "vhdl_file".to_string(),
+                param_type: "string".to_string(),
+                description: "Path to the VHDL file declaring the entity".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "sv_file".to_string(),
+                param_type: "string".to_string(),
+                description: "Path to the SystemVerilog file with the ANSI-style module header".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "format".to_string(),
+                param_type: "string".to_string(),
+                description: "Report format: \"text\" or \"json\" (default: \"text\")".to_string(),
+                required: false,
+                default: Some(serde_json::json!("text")),
+                ..Default::default()
+            }
+            .with_enum_values(["text", "json"]),
+        ];
+
+        let base = BaseToolImpl::new(
+            constants::TOOL_COMPARE_INTERFACES.to_string(),
+            "Compare a VHDL entity with an existing SystemVerilog module header (ANSI style) and report added, removed and renamed ports, direction and width mismatches and parameter differences, or \"interfaces match\". The entity named like the module is used.".to_string(),
+            parameters,
+        );
+
+        Self {
+            base,
+            allowed_folders,
+        }
+    }
+}
+
+impl Tool for InterfaceDiffTool {
+    fn name(&self) -> &str {
+        &self.base.name
+    }
+
+    fn description(&self) -> &str {
+        &self.base.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.base.schema.clone()
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        let vhdl_file = arguments
+            .get("vhdl_file")
+            .and_then(|v| v.as_str())
+            .context("Missing 'vhdl_file' argument")?;
+        let sv_file = arguments
+            .get("sv_file")
+            .and_then(|v| v.as_str())
+            .context("Missing 'sv_file' argument")?;
+
+        let format = arguments
+            .get("format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("text");
+        if format != "text" && format != "json" {
+            return Err(anyhow::anyhow!("Unsupported format '{}': expected \"text\" or \"json\"", format));
+        }
+
+        for path in [vhdl_file, sv_file] {
+            if !path_within(&self.allowed_folders, Path::new(path)) {
+                return Err(anyhow::anyhow!("Access denied: '{}' is not in allowed folders", path));
+            }
+        }
+
+        let sv_source = std::fs::read_to_string(sv_file)
+            .context(format!("Failed to read SystemVerilog file: {}", sv_file))?;
+        let module = SvModuleHeader::parse(&sv_source)
+            .context(format!("Failed to parse module header in: {}", sv_file))?;
+
+        let mut parser = ASTVHDLParser::from_file(Path::new(vhdl_file))?;
+        let entities = parser.parse_entities()
+            .context(format!("Failed to parse VHDL file: {}", vhdl_file))?;
+        let entity = match entities.iter().find(|e| e.name.eq_ignore_ascii_case(&module.name)) {
+            Some(entity) => entity,
+            None if entities.len() == 1 => &entities[0],
+            None => {
+                let names: Vec<&str> = entities.iter().map(|e| e.name.as_str()).collect();
+                return Err(anyhow::anyhow!(
+                    "No entity named {} in {} (found: {})",
+                    module.name,
+                    vhdl_file,
+                    names.join(", ")
+                ));
+            }
+        };
+
+        let diff = InterfaceDiff::compare(entity, &module);
+        match format {
+            "json" => Ok(serde_json::to_string_pretty(&diff)?),
+            _ => Ok(diff.summary()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Generic, PortDirection, VectorRange};
+
+    fn fifo_entity() -> Entity {
+        let mut entity = Entity::new("fifo".to_string());
+        entity.add_generic(Generic { name: "DEPTH".to_string(), generic_type: "integer".to_string(), default_value: Some("16".to_string()) });
+        entity.add_port(Port::new("clk".to_string(), PortDirection::In, VHDLType::StdLogic));
+        let byte = VectorRange { left: 7.into(), right: 0.into(), downto: true };
+        entity.add_port(Port::new("din".to_string(), PortDirection::In, VHDLType::StdLogicVector(byte.clone())));
+        entity.add_port(Port::new("dout".to_string(), PortDirection::Out, VHDLType::StdLogicVector(byte)));
+        entity.add_port(Port::new("full".to_string(), PortDirection::Out, VHDLType::StdLogic));
+        entity
+    }
+
+    const FIFO_SV: &str = "
+        // Hand-written port of fifo.vhd
+        module fifo #(
+            parameter int DEPTH = 16
+        ) (
+            input  logic       clk,
+            input  logic [7:0] din,  /* write data */
+            output logic [7:0] dout,
+            output logic       full
+        );
+            assign full = 1'b0;
+        endmodule
+    ";
+
+    #[test]
+    fn test_matching_interfaces() {
+        let module = SvModuleHeader::parse(FIFO_SV).unwrap();
+        assert_eq!(module.parameters, vec!["DEPTH"]);
+        assert_eq!(module.ports[1], SvPort { name: "din".to_string(), direction: "input".to_string(), width: PortWidth::Bits(8) });
+
+        let diff = InterfaceDiff::compare(&fifo_entity(), &module);
+        assert!(diff.is_match());
+        assert_eq!(diff.summary(), "VHDL entity fifo vs SystemVerilog module fifo: interfaces match\n");
+    }
+
+    #[test]
+    fn test_width_mismatch() {
+        let module = SvModuleHeader::parse(&FIFO_SV.replace("output logic [7:0] dout", "output logic [15:0] dout")).unwrap();
+        let diff = InterfaceDiff::compare(&fifo_entity(), &module);
+        assert_eq!(diff.width_mismatches, vec![PortMismatch {
+            port: "dout".to_string(),
+            vhdl: "8 bits".to_string(),
+            sv: "16 bits".to_string(),
+        }]);
+        assert!(diff.summary().contains("  ! port dout: width 8 bits in VHDL, 16 bits in SystemVerilog\n"));
+
+        let json: serde_json::Value = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["width_mismatches"][0]["port"], "dout");
+    }
+
+    #[test]
+    fn test_missing_and_renamed_ports() {
+        let sv = "module fifo #(parameter DEPTH = 16, WIDTH = 8) (input clk, input [7:0] wr_data, output reg [7:0] dout);";
+        let module = SvModuleHeader::parse(sv).unwrap();
+        let diff = InterfaceDiff::compare(&fifo_entity(), &module);
+
+        assert_eq!(diff.removed_ports, vec!["full"]);
+        assert_eq!(diff.renamed_ports, vec![PortRename { vhdl: "din".to_string(), sv: "wr_data".to_string() }]);
+        assert!(diff.added_ports.is_empty());
+        assert_eq!(diff.added_parameters, vec!["WIDTH"]);
+        let summary = diff.summary();
+        assert!(summary.contains("interfaces differ"));
+        assert!(summary.contains("  - port full: missing from the SystemVerilog module\n"));
+    }
+
+    #[test]
+    fn test_unpacked_dimensions_follow_the_name() {
+        let sv = "module regs (input logic [7:0] mem [0:3], input logic [1:0][3:0] taps [4][2], output valid);";
+        let module = SvModuleHeader::parse(sv).unwrap();
+
+        assert_eq!(module.ports[0], SvPort { name: "mem".to_string(), direction: "input".to_string(), width: PortWidth::Bits(8) });
+        assert_eq!(module.ports[1].name, "taps");
+        assert_eq!(module.ports[2], SvPort { name: "valid".to_string(), direction: "output".to_string(), width: PortWidth::Bits(1) });
+    }
+}
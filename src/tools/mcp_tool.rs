@@ -61,6 +61,17 @@ impl MCPTool {
                             .to_string(),
                         required: required.contains(name),
                         default: prop_obj.get("default").cloned(),
+                        enum_values: prop_obj.get("enum")
+                            .and_then(|e| e.as_array())
+                            .cloned()
+                            .unwrap_or_default(),
+                        items_type: prop_obj.get("items")
+                            .and_then(|i| i.get("type"))
+                            .and_then(|t| t.as_str())
+                            .map(String::from),
+                        min_items: prop_obj.get("minItems").and_then(|n| n.as_u64()).map(|n| n as usize),
+                        max_items: prop_obj.get("maxItems").and_then(|n| n.as_u64()).map(|n| n as usize),
+                        ..Default::default()
                     };
                     parameters.push(param);
                 }
@@ -1,31 +1,73 @@
 pub mod base;
 pub mod bash;
+pub mod batch_report;
+pub mod cocotb_gen;
+pub mod constraints;
+pub mod dry_run;
 pub mod edit;
+pub mod file_ops;
+pub mod find;
+pub mod git;
+pub mod interface_diff;
+pub mod progress;
 pub mod sequential_thinking;
 pub mod task_done;
+pub mod trace_signal;
 pub mod transpile;
 pub mod transpile_folder;
 pub mod vhdl_analyze;
+pub mod vhdl_lint;
 
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use anyhow::Result;
 
-use crate::config::ModelProvider;
+use crate::config::{BashPolicy, ModelProvider};
 use crate::constants;
 
-pub use base::{Tool, ToolCall, ToolExecutor, ToolResult, ToolParameter, ToolSchema, BaseToolImpl};
+pub use base::{ArgumentViolation, Tool, ToolCall, ToolExecutor, ToolResult, ToolParameter, ToolSchema, BaseToolImpl, DEFAULT_MAX_RESULT_BYTES};
 pub use bash::BashTool;
+pub use batch_report::{BatchReport, FileReport, FileStatus};
+pub use cocotb_gen::CocotbGenerator;
+pub use constraints::ConstraintsTool;
+pub use dry_run::DryRunTool;
 pub use edit::TextEditorTool;
-pub use sequential_thinking::SequentialThinkingTool;
+pub use file_ops::FileOpsTool;
+pub use find::FindTool;
+pub use git::{GitDiff, GitHunk, GitTool};
+pub use interface_diff::{InterfaceDiff, InterfaceDiffTool, SvModuleHeader};
+pub use progress::{FileTranspileResult, ProgressCallback};
+pub use sequential_thinking::{SequentialThinkingTool, Thought, ThoughtStore};
 pub use task_done::TaskDoneTool;
+pub use trace_signal::{SignalDriver, SignalReader, SignalTrace, TraceSignalTool};
 pub use transpile::TranspileTool;
 pub use transpile_folder::TranspileFolderTool;
 pub use vhdl_analyze::VHDLAnalyzeTool;
+pub use vhdl_lint::{lint_vhdl, lint_vhdl_with_rules, LintSeverity, LintViolation, VHDLLintTool, CONSTRUCT_RULES, NAMING_RULES};
+
+/// Whether `path` lies inside one of the `allowed` folders, after resolving symlinks
+/// and `..` components. An empty list allows every path; paths that do not exist are refused.
+pub(crate) fn path_within(allowed: &[String], path: &Path) -> bool {
+    if allowed.is_empty() {
+        return true;
+    }
+
+    let canonical_path = match path.canonicalize() {
+        Ok(p) => p,
+        Err(_) => return false,
+    };
+
+    allowed.iter()
+        .filter_map(|allowed| Path::new(allowed).canonicalize().ok())
+        .any(|allowed_path| canonical_path.starts_with(allowed_path))
+}
 
 pub fn create_tool(
     tool_name: &str,
     allowed_folders: Vec<String>,
     model_provider: Option<&ModelProvider>,
+    thought_store: Arc<Mutex<ThoughtStore>>,
+    bash_policy: &BashPolicy,
 ) -> Result<Arc<dyn Tool>> {
     let provider_name = model_provider
         .map(|p| p.provider.as_str())
@@ -33,17 +75,44 @@ pub fn create_tool(
 
     match tool_name {
         constants::TOOL_BASH => {
-            Ok(Arc::new(BashTool::new(provider_name.to_string(), allowed_folders)))
+            Ok(Arc::new(BashTool::new(provider_name.to_string(), allowed_folders).with_policy(bash_policy.clone())))
         }
         constants::TOOL_STR_REPLACE_EDIT => {
             Ok(Arc::new(TextEditorTool::new(provider_name.to_string(), allowed_folders)))
         }
         constants::TOOL_SEQUENTIAL_THINKING => {
-            Ok(Arc::new(SequentialThinkingTool::new(provider_name.to_string())))
+            Ok(Arc::new(SequentialThinkingTool::new(thought_store)))
         }
         constants::TOOL_TASK_DONE => {
             Ok(Arc::new(TaskDoneTool::new()))
         }
+        constants::TOOL_FIND => {
+            Ok(Arc::new(FindTool::new(allowed_folders)))
+        }
+        constants::TOOL_FILE_OPS => {
+            Ok(Arc::new(FileOpsTool::new(allowed_folders)))
+        }
+        constants::TOOL_ANALYZE => {
+            Ok(Arc::new(VHDLAnalyzeTool::new(allowed_folders)))
+        }
+        constants::TOOL_COCOTB => {
+            Ok(Arc::new(CocotbGenerator::new(allowed_folders)))
+        }
+        constants::TOOL_LINT => {
+            Ok(Arc::new(VHDLLintTool::new(allowed_folders)))
+        }
+        constants::TOOL_CONSTRAINTS => {
+            Ok(Arc::new(ConstraintsTool::new(allowed_folders)))
+        }
+        constants::TOOL_COMPARE_INTERFACES => {
+            Ok(Arc::new(InterfaceDiffTool::new(allowed_folders)))
+        }
+        constants::TOOL_TRACE_SIGNAL => {
+            Ok(Arc::new(TraceSignalTool::new(allowed_folders)))
+        }
+        constants::TOOL_GIT => {
+            Ok(Arc::new(GitTool::new(allowed_folders)))
+        }
         "transpile_vhdl_to_verilog" => {
             Ok(Arc::new(TranspileTool::new(allowed_folders)))
         }
@@ -52,4 +121,24 @@ pub fn create_tool(
         }
         _ => Err(anyhow::anyhow!("Unknown tool: {}", tool_name)),
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_path_within() {
+        let root = TempDir::new().unwrap();
+        let inside = root.path().join("rtl");
+        std::fs::create_dir(&inside).unwrap();
+        let outside = TempDir::new().unwrap();
+        let allowed = vec![inside.to_string_lossy().to_string()];
+
+        assert!(path_within(&allowed, &inside));
+        assert!(path_within(&[], outside.path()));
+        assert!(!path_within(&allowed, outside.path()));
+        assert!(!path_within(&allowed, &inside.join("..")));
+        assert!(!path_within(&allowed, &inside.join("missing.vhd")));
+    }
+}
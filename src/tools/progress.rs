@@ -0,0 +1,20 @@
+//! Per-file progress of batch tools, for UIs that show it while the batch runs
+
+use std::sync::Arc;
+
+use crate::tools::FileReport;
+
+/// Outcome of one file as passed to a `ProgressCallback`
+pub type FileTranspileResult = FileReport;
+
+/// Receives an event each time a batch finishes a file
+pub trait ProgressCallback: Send + Sync {
+    /// `current` files of `total` are done, the last one being `current_file`
+    fn on_progress(&self, current: usize, total: usize, current_file: &str, result: &FileTranspileResult);
+}
+
+impl<T: ProgressCallback + ?Sized> ProgressCallback for Arc<T> {
+    fn on_progress(&self, current: usize, total: usize, current_file: &str, result: &FileTranspileResult) {
+        (**self).on_progress(current, total, current_file, result)
+    }
+}
@@ -1,13 +1,63 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
 use crate::tools::{BaseToolImpl, Tool, ToolParameter, ToolSchema};
 
+/// A single recorded reasoning step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Thought {
+    pub thought: String,
+    pub thought_number: u32,
+    pub total_thoughts: u32,
+    pub next_thought_needed: bool,
+    pub is_revision: bool,
+    pub revises_thought: Option<u32>,
+}
+
+/// Thought chain shared between tool instances so it survives across agent steps
+#[derive(Debug, Default)]
+pub struct ThoughtStore {
+    thoughts: Vec<Thought>,
+}
+
+impl ThoughtStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a thought to the current chain. A non-revision thought numbered 1 starts a new chain.
+    pub fn add(&mut self, thought: Thought) -> Result<()> {
+        if thought.is_revision {
+            let revised = thought.revises_thought
+                .ok_or_else(|| anyhow::anyhow!("revises_thought is required when is_revision is true"))?;
+            if !self.thoughts.iter().any(|t| t.thought_number == revised) {
+                return Err(anyhow::anyhow!("Cannot revise thought {}: it is not in the current chain", revised));
+            }
+        } else if thought.thought_number == 1 {
+            self.thoughts.clear();
+        }
+
+        self.thoughts.push(thought);
+        Ok(())
+    }
+
+    pub fn thoughts(&self) -> &[Thought] {
+        &self.thoughts
+    }
+
+    pub fn clear(&mut self) {
+        self.thoughts.clear();
+    }
+}
+
 pub struct SequentialThinkingTool {
     base: BaseToolImpl,
-    _provider: String,
+    store: Arc<Mutex<ThoughtStore>>,
 }
 
 impl SequentialThinkingTool {
-    pub fn new(provider: String) -> Self {
+    pub fn new(store: Arc<Mutex<ThoughtStore>>) -> Self {
         let parameters = vec![
             ToolParameter {
                 name: "thought".to_string(),
@@ -15,19 +65,79 @@ impl SequentialThinkingTool {
                 description: "A thought or reasoning step".to_string(),
                 required: true,
                 default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "thought_number".to_string(),
+                param_type: "integer".to_string(),
+                description: "Number of this thought in the chain (1 starts a new chain)".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "total_thoughts".to_string(),
+                param_type: "integer".to_string(),
+                description: "Estimated total number of thoughts needed".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "next_thought_needed".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Whether another thought step is needed".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "is_revision".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Whether this thought revises a previous one".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "revises_thought".to_string(),
+                param_type: "integer".to_string(),
+                description: "Number of the thought being revised (required if is_revision)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
             },
         ];
 
         let base = BaseToolImpl::new(
             "sequential_thinking".to_string(),
-            "Record sequential thinking steps".to_string(),
+            "Record sequential thinking steps. Returns the full thought chain so far.".to_string(),
             parameters,
         );
 
-        Self {
-            base,
-            _provider: provider,
+        Self { base, store }
+    }
+
+    fn format_chain(thoughts: &[Thought]) -> String {
+        let mut output = String::new();
+
+        for thought in thoughts {
+            output.push_str(&format!("Thought {}/{}", thought.thought_number, thought.total_thoughts));
+            if let Some(revised) = thought.revises_thought.filter(|_| thought.is_revision) {
+                output.push_str(&format!(" (revises thought {})", revised));
+            }
+            output.push_str(&format!(": {}\n", thought.thought));
+        }
+
+        if let Some(last) = thoughts.last() {
+            if last.next_thought_needed {
+                output.push_str("\nNext thought needed.");
+            } else {
+                output.push_str("\nThought chain complete.");
+            }
         }
+
+        output
     }
 }
 
@@ -44,8 +154,105 @@ impl Tool for SequentialThinkingTool {
         self.base.schema.clone()
     }
 
-    fn execute(&self, _arguments: &serde_json::Value) -> Result<String> {
-        // Stub implementation
-        Ok("Sequential thinking tool not implemented yet".to_string())
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        let thought = arguments
+            .get("thought")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'thought' argument"))?;
+
+        let thought_number = arguments
+            .get("thought_number")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'thought_number' argument"))? as u32;
+
+        let total_thoughts = arguments
+            .get("total_thoughts")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(thought_number as u64) as u32;
+
+        let next_thought_needed = arguments
+            .get("next_thought_needed")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let is_revision = arguments
+            .get("is_revision")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let revises_thought = arguments
+            .get("revises_thought")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as u32);
+
+        let mut store = self.store.lock().unwrap();
+        store.add(Thought {
+            thought: thought.to_string(),
+            thought_number,
+            total_thoughts,
+            next_thought_needed,
+            is_revision,
+            revises_thought,
+        })?;
+
+        Ok(Self::format_chain(store.thoughts()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thoughts_persist_across_tool_instances() {
+        let store = Arc::new(Mutex::new(ThoughtStore::new()));
+
+        let first = SequentialThinkingTool::new(store.clone());
+        first.execute(&serde_json::json!({
+            "thought": "Parse the entity",
+            "thought_number": 1,
+            "total_thoughts": 3,
+            "next_thought_needed": true
+        })).unwrap();
+
+        // A fresh instance sharing the same store sees the earlier thought
+        let second = SequentialThinkingTool::new(store.clone());
+        second.execute(&serde_json::json!({
+            "thought": "Map ports to logic",
+            "thought_number": 2,
+            "total_thoughts": 3,
+            "next_thought_needed": true
+        })).unwrap();
+
+        let result = second.execute(&serde_json::json!({
+            "thought": "Map ports to logic, keeping buffer ports as outputs",
+            "thought_number": 3,
+            "total_thoughts": 3,
+            "next_thought_needed": false,
+            "is_revision": true,
+            "revises_thought": 2
+        })).unwrap();
+
+        assert!(result.contains("Thought 1/3: Parse the entity"));
+        assert!(result.contains("Thought 2/3: Map ports to logic"));
+        assert!(result.contains("Thought 3/3 (revises thought 2)"));
+        assert!(result.contains("Thought chain complete."));
+        assert_eq!(store.lock().unwrap().thoughts().len(), 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_revision_of_unknown_thought_fails() {
+        let tool = SequentialThinkingTool::new(Arc::new(Mutex::new(ThoughtStore::new())));
+
+        let result = tool.execute(&serde_json::json!({
+            "thought": "Revise something that never happened",
+            "thought_number": 1,
+            "total_thoughts": 1,
+            "next_thought_needed": false,
+            "is_revision": true,
+            "revises_thought": 2
+        }));
+
+        assert!(result.is_err());
+    }
+}
@@ -14,6 +14,7 @@ impl TaskDoneTool {
                 description: "The final result or summary of the task".to_string(),
                 required: false,
                 default: None,
+                ..Default::default()
             },
         ];
 
@@ -0,0 +1,597 @@
+//! Drivers and readers of one signal, for debugging multiple or missing drivers
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::constants;
+use crate::ir::hdl_body::split_comment;
+use crate::ir::{Architecture, Entity, GenerateBlock, Process};
+use crate::parser::ASTVHDLParser;
+use crate::tools::{path_within, BaseToolImpl, Tool, ToolParameter, ToolSchema};
+
+/// Statement assigning the traced signal
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignalDriver {
+    /// `process tick in counter(rtl)` or `concurrent in counter(rtl)`
+    #[serde(rename = "where")]
+    pub location: String,
+    /// Sensitivity list of the process, or the signals a concurrent statement reads
+    pub sensitivity: Vec<String>,
+    pub statement: String,
+    /// 1-based source line of the statement, when it could be located
+    pub line: Option<usize>,
+    pub conditional: bool,
+    /// Enclosing `if`/`case` branches, or the `when`/`select` of a concurrent statement
+    pub condition: Option<String>,
+}
+
+/// Statement reading the traced signal
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignalReader {
+    #[serde(rename = "where")]
+    pub location: String,
+    pub statement: String,
+    pub line: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignalTrace {
+    pub signal: String,
+    /// More than one process or concurrent statement of one architecture drives the signal
+    pub multiple_drivers: bool,
+    pub drivers: Vec<SignalDriver>,
+    pub readers: Vec<SignalReader>,
+    /// Processes and concurrent statements among the drivers
+    #[serde(skip)]
+    sources: usize,
+}
+
+impl SignalTrace {
+    /// Trace `signal` through every architecture of `entities`; `source` locates process statements
+    pub fn trace(entities: &[Entity], signal: &str, source: &str) -> Self {
+        let mut trace = Self {
+            signal: signal.to_string(),
+            multiple_drivers: false,
+            drivers: Vec::new(),
+            readers: Vec::new(),
+            sources: 0,
+        };
+        let source_lines: Vec<&str> = source.lines().map(|line| split_comment(line).0.trim()).collect();
+
+        for entity in entities {
+            let mut declared: Vec<&str> = entity.ports.iter().map(|port| port.name.as_str()).collect();
+            for architecture in entity.architecture.iter().chain(&entity.alternate_architectures) {
+                declared.extend(architecture.signals.iter().map(|s| s.name.as_str()));
+                let scope = format!("{}({})", entity.name, architecture.name);
+                // Other architectures and entities are separate designs, so drivers only
+                // conflict within one scope
+                let sources = trace.sources;
+                trace.trace_architecture(architecture, &scope, &declared, &source_lines);
+                trace.multiple_drivers |= trace.sources - sources > 1;
+                declared.truncate(entity.ports.len());
+            }
+        }
+        trace
+    }
+
+    fn trace_architecture(&mut self, architecture: &Architecture, scope: &str, declared: &[&str], source_lines: &[&str]) {
+        for (i, process) in architecture.processes.iter().enumerate() {
+            let location = match &process.label {
+                Some(label) => format!("process {} in {}", label, scope),
+                None => format!("process_{} in {}", i, scope),
+            };
+            self.trace_process(process, &location, source_lines);
+        }
+
+        for (i, statement) in architecture.concurrent_statements.iter().enumerate() {
+            let line = architecture.concurrent_statement_lines.get(i).copied();
+            self.trace_concurrent(statement, line, &format!("concurrent in {}", scope), declared);
+        }
+        for block in &architecture.generate_blocks {
+            self.trace_generate(block, scope, declared);
+        }
+    }
+
+    fn trace_generate(&mut self, block: &GenerateBlock, scope: &str, declared: &[&str]) {
+        let location = format!("concurrent in generate {} of {}", block.label, scope);
+        for statement in &block.concurrent_statements {
+            self.trace_concurrent(statement, None, &location, declared);
+        }
+        for nested in &block.generate_blocks {
+            self.trace_generate(nested, scope, declared);
+        }
+    }
+
+    fn trace_process(&mut self, process: &Process, location: &str, source_lines: &[&str]) {
+        // Body lines are searched for from the process statement on, in order
+        let mut cursor = process.source_line.unwrap_or(1).saturating_sub(1);
+        let mut locate = |statement: &str| {
+            let found = source_lines.iter().skip(cursor).position(|line| *line == statement)?;
+            cursor += found + 1;
+            Some(cursor)
+        };
+
+        let (mut branches, mut drives) = (BranchStack::default(), false);
+        for raw in process.body.lines() {
+            let line = split_comment(raw).0.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (tested, statement) = branches.enter(line);
+            let assignment = statement.and_then(split_assignment);
+            let reads_signal = tested.is_some_and(|tested| mentions(tested, &self.signal))
+                || match assignment {
+                    Some((target, value)) => mentions(value, &self.signal) || index_mentions(target, &self.signal),
+                    None => statement.is_some_and(|statement| mentions(statement, &self.signal)),
+                };
+            let drives_signal = assignment.is_some_and(|(target, _)| base_name(target).eq_ignore_ascii_case(&self.signal));
+
+            if reads_signal || drives_signal {
+                let line_number = locate(line);
+                if reads_signal {
+                    self.readers.push(SignalReader {
+                        location: location.to_string(),
+                        statement: line.to_string(),
+                        line: line_number,
+                    });
+                }
+                if drives_signal {
+                    let condition = branches.condition();
+                    self.drivers.push(SignalDriver {
+                        location: location.to_string(),
+                        sensitivity: process.sensitivity_list.clone(),
+                        statement: line.to_string(),
+                        line: line_number,
+                        conditional: condition.is_some(),
+                        condition,
+                    });
+                    drives = true;
+                }
+            }
+            branches.leave(line);
+        }
+        self.sources += usize::from(drives);
+    }
+
+    fn trace_concurrent(&mut self, statement: &str, line: Option<usize>, location: &str, declared: &[&str]) {
+        let text = statement.lines().map(|line| split_comment(line).0.trim()).collect::<Vec<_>>().join(" ");
+        let Some((target, value)) = split_assignment(&text) else {
+            return;
+        };
+        // `[label :] with sel select target <= ...`
+        let target = target.rsplit_once(':').map_or(target, |(_, target)| target).trim();
+        let (selector, target) = match strip_keyword(target, "with") {
+            Some(rest) => match rest.split_once(" select ") {
+                Some((selector, target)) => (Some(selector.trim()), target.trim()),
+                None => (None, target),
+            },
+            None => (None, target),
+        };
+
+        let reads_signal = mentions(value, &self.signal)
+            || index_mentions(target, &self.signal)
+            || selector.is_some_and(|selector| mentions(selector, &self.signal));
+        if reads_signal {
+            self.readers.push(SignalReader {
+                location: location.to_string(),
+                statement: text.clone(),
+                line,
+            });
+        }
+        if !base_name(target).eq_ignore_ascii_case(&self.signal) {
+            return;
+        }
+
+        let condition = match selector {
+            Some(selector) => Some(format!("with {} select", selector)),
+            None => {
+                let conditions: Vec<String> = value.split(" else ")
+                    .filter_map(|branch| branch.split_once(" when "))
+                    .map(|(_, condition)| format!("when {}", condition.trim().trim_end_matches(';').trim()))
+                    .collect();
+                (!conditions.is_empty()).then(|| conditions.join(" else "))
+            }
+        };
+        let mut sensitivity: Vec<String> = Vec::new();
+        for name in identifiers(value).chain(selector.into_iter().flat_map(identifiers)) {
+            if let Some(declared) = declared.iter().find(|declared| declared.eq_ignore_ascii_case(name)) {
+                if !sensitivity.iter().any(|s| s == declared) {
+                    sensitivity.push(declared.to_string());
+                }
+            }
+        }
+        self.sources += 1;
+        self.drivers.push(SignalDriver {
+            location: location.to_string(),
+            sensitivity,
+            statement: text,
+            line,
+            conditional: condition.is_some(),
+            condition,
+        });
+    }
+}
+
+/// One open `if` or `case` of a process body
+struct Branch {
+    /// `case` selector; `None` for an `if`
+    selector: Option<String>,
+    /// Conditions of the earlier branches of an `if`, which failed
+    failed: Vec<String>,
+    current: Option<String>,
+}
+
+/// The `if`/`case` branches enclosing the current line of a process body
+#[derive(Default)]
+struct BranchStack(Vec<Branch>);
+
+impl BranchStack {
+    /// Open or switch branches for `line`. Returns the expression the line tests, if any,
+    /// and the statement it carries, if any
+    fn enter<'a>(&mut self, line: &'a str) -> (Option<&'a str>, Option<&'a str>) {
+        let lower = line.to_ascii_lowercase();
+        if let Some(rest) = strip_keyword(line, "if") {
+            if let Some((condition, statement)) = split_then(rest) {
+                self.0.push(Branch { selector: None, failed: Vec::new(), current: Some(condition.to_string()) });
+                return (Some(condition), statement);
+            }
+        }
+        if let Some(rest) = strip_keyword(line, "elsif") {
+            if let (Some((condition, statement)), Some(branch)) = (split_then(rest), self.0.last_mut()) {
+                branch.failed.extend(branch.current.take());
+                branch.current = Some(condition.to_string());
+                return (Some(condition), statement);
+            }
+        }
+        if lower == "else" || lower.starts_with("else ") {
+            if let Some(branch) = self.0.last_mut() {
+                branch.failed.extend(branch.current.take());
+            }
+            let statement = line[4..].trim();
+            return (None, (!statement.is_empty()).then_some(statement));
+        }
+        if let Some(rest) = strip_keyword(line, "case") {
+            let rest = rest.trim();
+            let selector = match rest.to_ascii_lowercase().ends_with(" is") {
+                true => rest[..rest.len() - 3].trim(),
+                false => rest,
+            };
+            self.0.push(Branch { selector: Some(selector.to_string()), failed: Vec::new(), current: None });
+            return (Some(selector), None);
+        }
+        if let Some(rest) = strip_keyword(line, "when") {
+            if let (Some((choice, statement)), Some(branch)) = (rest.split_once("=>"), self.0.last_mut()) {
+                if let Some(selector) = &branch.selector {
+                    let choice = choice.trim();
+                    branch.current = Some(if choice.eq_ignore_ascii_case("others") {
+                        format!("{} = others", selector)
+                    } else {
+                        format!("{} = {}", selector, choice)
+                    });
+                    let statement = statement.trim();
+                    return (None, (!statement.is_empty()).then_some(statement));
+                }
+            }
+        }
+        if lower.starts_with("end if") || lower.starts_with("end case") {
+            return (None, None);
+        }
+        (None, Some(line))
+    }
+
+    /// Close the branch `line` ends, including a one-line `if ... then ...; end if;`
+    fn leave(&mut self, line: &str) {
+        let lower = line.to_ascii_lowercase();
+        if lower.ends_with("end if;") || lower.ends_with("end case;") {
+            self.0.pop();
+        }
+    }
+
+    /// Every enclosing branch condition, joined with `and`
+    fn condition(&self) -> Option<String> {
+        let parts: Vec<String> = self.0.iter()
+            .flat_map(|branch| {
+                branch.failed.iter().map(|failed| format!("not ({})", failed)).chain(branch.current.clone())
+            })
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" and "))
+    }
+}
+
+/// Rest of `line` after a leading keyword, matched case-insensitively
+fn strip_keyword<'a>(line: &'a str, keyword: &str) -> Option<&'a str> {
+    let head = line.get(..keyword.len())?;
+    let rest = &line[keyword.len()..];
+    (head.eq_ignore_ascii_case(keyword) && rest.starts_with([' ', '('])).then_some(rest)
+}
+
+/// `cond then [statement]` split at its `then`
+fn split_then(rest: &str) -> Option<(&str, Option<&str>)> {
+    let lower = rest.to_ascii_lowercase();
+    let at = lower.find(" then").filter(|at| {
+        lower[at + 5..].is_empty() || lower[at + 5..].starts_with(' ')
+    })?;
+    let statement = rest[at + 5..].trim();
+    Some((rest[..at].trim(), (!statement.is_empty()).then_some(statement)))
+}
+
+/// Target and value of a `<=` or `:=` assignment, outside parentheses and literals
+fn split_assignment(statement: &str) -> Option<(&str, &str)> {
+    let bytes = statement.as_bytes();
+    let (mut depth, mut in_string, mut i) = (0usize, false, 0);
+    while i + 1 < bytes.len() {
+        match bytes[i] {
+            b'"' => in_string = !in_string,
+            b'\'' if !in_string && bytes.get(i + 2) == Some(&b'\'') => i += 2,
+            b'(' if !in_string => depth += 1,
+            b')' if !in_string => depth = depth.saturating_sub(1),
+            b'<' | b':' if !in_string && depth == 0 && bytes[i + 1] == b'=' => {
+                return Some((statement[..i].trim(), statement[i + 2..].trim()));
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// `count` of `count(3)` or `regs(i).valid`
+fn base_name(target: &str) -> &str {
+    target.split(['(', '.']).next().unwrap_or(target).trim()
+}
+
+/// The signal appears in an index of the target, e.g. `mem(addr) <= d`
+fn index_mentions(target: &str, signal: &str) -> bool {
+    target.find('(').is_some_and(|at| mentions(&target[at..], signal))
+}
+
+fn mentions(text: &str, signal: &str) -> bool {
+    identifiers(text).any(|name| name.eq_ignore_ascii_case(signal))
+}
+
+/// Identifiers of a VHDL expression, skipping string, bit string and character literals
+fn identifiers(text: &str) -> impl Iterator<Item = &str> {
+    let bytes = text.as_bytes();
+    let mut names = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += 1;
+                }
+            }
+            b'\'' if bytes.get(i + 2) == Some(&b'\'') => i += 2,
+            // Attribute names such as 'event are not identifiers of their own
+            b'\'' => {
+                while i + 1 < bytes.len() && (bytes[i + 1].is_ascii_alphanumeric() || bytes[i + 1] == b'_') {
+                    i += 1;
+                }
+            }
+            c if c.is_ascii_alphabetic() => {
+                let start = i;
+                while i + 1 < bytes.len() && (bytes[i + 1].is_ascii_alphanumeric() || bytes[i + 1] == b'_') {
+                    i += 1;
+                }
+                // x"FF" and b"0101" are bit string literals
+                if bytes.get(i + 1) != Some(&b'"') {
+                    names.push(&text[start..=i]);
+                }
+            }
+            c if c.is_ascii_digit() => {
+                while i + 1 < bytes.len() && (bytes[i + 1].is_ascii_alphanumeric() || bytes[i + 1] == b'_' || bytes[i + 1] == b'.') {
+                    i += 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    names.into_iter()
+}
+
+/// Tool listing the processes and concurrent statements that drive and read a signal
+pub struct TraceSignalTool {
+    base: BaseToolImpl,
+    allowed_folders: Vec<String>,
+}
+
+impl TraceSignalTool {
+    pub fn new(allowed_folders: Vec<String>) -> Self {
+        let parameters = vec![
+            ToolParameter {
+                name: "vhdl_file".to_string(),
+                param_type: "string".to_string(),
+                description: "Path to the VHDL file to search".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "signal_name".to_string(),
+                param_type: "string".to_string(),
+                description: "Signal, port or variable to trace (case-insensitive)".to_string(),
+                required: true,
+                default: None,
+                ..Default::default()
+            },
+        ];
+
+        let base = BaseToolImpl::new(
+            constants::TOOL_TRACE_SIGNAL.to_string(),
+            "Trace a signal through a VHDL file: every process or concurrent statement assigning it (with the sensitivity list, the assignment line and the enclosing conditions) and every statement reading it, as JSON. Use it to find multiple or missing drivers.".to_string(),
+            parameters,
+        );
+
+        Self {
+            base,
+            allowed_folders,
+        }
+    }
+}
+
+impl Tool for TraceSignalTool {
+    fn name(&self) -> &str {
+        &self.base.name
+    }
+
+    fn description(&self) -> &str {
+        &self.base.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.base.schema.clone()
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        let vhdl_file = arguments
+            .get("vhdl_file")
+            .and_then(|v| v.as_str())
+            .context("Missing 'vhdl_file' argument")?;
+        let signal_name = arguments
+            .get("signal_name")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .context("Missing 'signal_name' argument")?;
+
+        if !path_within(&self.allowed_folders, Path::new(vhdl_file)) {
+            return Err(anyhow::anyhow!("Access denied: '{}' is not in allowed folders", vhdl_file));
+        }
+
+        let source = std::fs::read_to_string(vhdl_file)
+            .context(format!("Failed to read VHDL file: {}", vhdl_file))?;
+        let mut parser = ASTVHDLParser::new(source.clone())?;
+        let entities = parser.parse_entities()
+            .context(format!("Failed to parse VHDL file: {}", vhdl_file))?;
+
+        let trace = SignalTrace::trace(&entities, signal_name, &source);
+        let declared = entities.iter().any(|entity| {
+            entity.ports.iter().any(|port| port.name.eq_ignore_ascii_case(signal_name))
+                || entity.architecture.iter().chain(&entity.alternate_architectures)
+                    .any(|arch| arch.signals.iter().any(|s| s.name.eq_ignore_ascii_case(signal_name)))
+        });
+        if !declared && trace.drivers.is_empty() && trace.readers.is_empty() {
+            return Err(anyhow::anyhow!("Signal '{}' is not declared or used in {}", signal_name, vhdl_file));
+        }
+
+        Ok(serde_json::to_string_pretty(&trace)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Port, PortDirection, Signal, VHDLType};
+
+    fn arbiter() -> Entity {
+        let mut entity = Entity::new("arbiter".to_string());
+        entity.add_port(Port::new("clk".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("req".to_string(), PortDirection::In, VHDLType::StdLogic));
+        entity.add_port(Port::new("grant".to_string(), PortDirection::Out, VHDLType::StdLogic));
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: vec![Signal { name: "busy".to_string(), signal_type: VHDLType::StdLogic, source_line: Some(5) }],
+            processes: vec![Process {
+                label: Some("seq".to_string()),
+                sensitivity_list: vec!["clk".to_string()],
+                body: "if rising_edge(clk) then\nif req = '1' then -- new request\nbusy <= '1';\nelsif busy = '1' then\nbusy <= '0';\nend if;\nend if;".to_string(),
+                source_line: Some(7),
+                testbench_style: false,
+            }],
+            concurrent_statements: vec!["busy <= '0' when req = '0' else 'Z';".to_string(), "grant <= busy and req;".to_string()],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![17, 18],
+            types: Default::default(),
+        });
+        entity
+    }
+
+    const ARBITER_SOURCE: &str = "entity arbiter is\n  port(clk, req : in std_logic; grant : out std_logic);\nend entity;\narchitecture rtl of arbiter is\n  signal busy : std_logic;\nbegin\n  seq: process(clk)\n  begin\n    if rising_edge(clk) then\n      if req = '1' then -- new request\n        busy <= '1';\n      elsif busy = '1' then\n        busy <= '0';\n      end if;\n    end if;\n  end process;\n  busy <= '0' when req = '0' else 'Z';\n  grant <= busy and req;\nend architecture;\n";
+
+    #[test]
+    fn test_trace_process_and_concurrent_drivers() {
+        let trace = SignalTrace::trace(&[arbiter()], "BUSY", ARBITER_SOURCE);
+        assert!(trace.multiple_drivers);
+        assert_eq!(trace.drivers.len(), 3);
+
+        assert_eq!(trace.drivers[0].location, "process seq in arbiter(rtl)");
+        assert_eq!(trace.drivers[0].sensitivity, vec!["clk"]);
+        assert_eq!(trace.drivers[0].line, Some(11));
+        assert_eq!(trace.drivers[0].condition.as_deref(), Some("rising_edge(clk) and req = '1'"));
+        assert_eq!(trace.drivers[1].statement, "busy <= '0';");
+        assert_eq!(trace.drivers[1].line, Some(13));
+        assert_eq!(trace.drivers[1].condition.as_deref(), Some("rising_edge(clk) and not (req = '1') and busy = '1'"));
+
+        assert_eq!(trace.drivers[2].location, "concurrent in arbiter(rtl)");
+        assert_eq!(trace.drivers[2].sensitivity, vec!["req"]);
+        assert_eq!(trace.drivers[2].line, Some(17));
+        assert!(trace.drivers[2].conditional);
+        assert_eq!(trace.drivers[2].condition.as_deref(), Some("when req = '0'"));
+
+        let readers: Vec<(&str, Option<usize>)> = trace.readers.iter().map(|r| (r.statement.as_str(), r.line)).collect();
+        assert_eq!(readers, vec![("elsif busy = '1' then", Some(12)), ("grant <= busy and req;", Some(18))]);
+
+        let json: serde_json::Value = serde_json::to_value(&trace).unwrap();
+        assert_eq!(json["signal"], "BUSY");
+        assert_eq!(json["drivers"][0]["where"], "process seq in arbiter(rtl)");
+        assert_eq!(json["readers"][1]["where"], "concurrent in arbiter(rtl)");
+    }
+
+    #[test]
+    fn test_case_and_selected_assignment_conditions() {
+        let mut entity = arbiter();
+        let architecture = entity.architecture.as_mut().unwrap();
+        architecture.processes[0].body = "case state is\nwhen IDLE => grant <= '0';\nwhen others =>\ngrant <= req;\nend case;".to_string();
+        architecture.concurrent_statements = vec!["with busy select grant <= req when '1', '0' when others;".to_string()];
+
+        let trace = SignalTrace::trace(&[entity], "grant", "");
+        let conditions: Vec<Option<&str>> = trace.drivers.iter().map(|d| d.condition.as_deref()).collect();
+        assert_eq!(conditions, vec![Some("state = IDLE"), Some("state = others"), Some("with busy select")]);
+        assert_eq!(trace.drivers[2].sensitivity, vec!["req", "busy"]);
+        assert!(trace.readers.is_empty());
+    }
+
+    #[test]
+    fn test_drivers_in_separate_architectures_do_not_conflict() {
+        let mut entity = arbiter();
+        let architecture = entity.architecture.as_mut().unwrap();
+        architecture.concurrent_statements = vec!["grant <= busy and req;".to_string()];
+        let mut alternate = architecture.clone();
+        alternate.name = "fast".to_string();
+        alternate.concurrent_statements = vec!["grant <= req;".to_string()];
+        entity.alternate_architectures.push(alternate);
+        let mut other = arbiter();
+        other.name = "other_arbiter".to_string();
+        other.architecture.as_mut().unwrap().concurrent_statements = vec!["grant <= req;".to_string()];
+
+        let trace = SignalTrace::trace(&[entity.clone(), other], "grant", "");
+        let locations: Vec<&str> = trace.drivers.iter().map(|d| d.location.as_str()).collect();
+        assert_eq!(locations, vec!["concurrent in arbiter(rtl)", "concurrent in arbiter(fast)", "concurrent in other_arbiter(rtl)"]);
+        assert!(!trace.multiple_drivers);
+
+        entity.alternate_architectures[0].concurrent_statements.push("grant <= '0';".to_string());
+        assert!(SignalTrace::trace(&[entity], "grant", "").multiple_drivers);
+    }
+
+    #[test]
+    fn test_trace_signal_tool_on_file() {
+        let folder = tempfile::TempDir::new().unwrap();
+        let file = folder.path().join("arbiter.vhd");
+        std::fs::write(&file, format!("library ieee;\nuse ieee.std_logic_1164.all;\n{}", ARBITER_SOURCE)).unwrap();
+
+        let tool = TraceSignalTool::new(vec![]);
+        let missing = tool.execute(&serde_json::json!({"vhdl_file": file.to_str().unwrap()}));
+        assert!(missing.unwrap_err().to_string().contains("signal_name"));
+
+        if let Ok(result) = tool.execute(&serde_json::json!({"vhdl_file": file.to_str().unwrap(), "signal_name": "busy"})) {
+            let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+            assert_eq!(json["drivers"].as_array().unwrap().len(), 3);
+            assert_eq!(json["drivers"][0]["line"], 13);
+            assert_eq!(json["multiple_drivers"], true);
+        }
+    }
+}
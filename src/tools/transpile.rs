@@ -1,9 +1,68 @@
 use anyhow::{Context, Result};
 use std::path::Path;
 
-use crate::ir::SystemVerilogGenerator;
-use crate::parser::ASTVHDLParser;
-use crate::tools::{BaseToolImpl, Tool, ToolParameter, ToolSchema};
+use crate::ir::source_map::source_ref_lines;
+use crate::ir::{Entity, SourceMapOutput, SpinalHDLGenerator, SystemVerilogGenerator, TestbenchGenerator, TranspilationWarning, TranspileQualityChecker, VerilogGenerator, VerilogStyle};
+use crate::parser::parse_error::strict_error;
+use crate::parser::{attach_architecture, ASTVHDLParser, PackageRegistry};
+use crate::tools::{path_within, BaseToolImpl, Tool, ToolParameter, ToolSchema};
+use crate::utils::{GhdlCheck, GhdlRunner};
+
+/// `entities` argument of the transpile tools: entity names or globs such as `axi_*`,
+/// matched case-insensitively
+#[derive(Debug, Clone)]
+pub(crate) struct EntityFilter {
+    patterns: Vec<glob::Pattern>,
+}
+
+impl EntityFilter {
+    /// Filter from a list of patterns, or a single one; `None` when absent or empty
+    pub(crate) fn from_arguments(arguments: &serde_json::Value) -> Result<Option<Self>> {
+        let patterns: Vec<&str> = match arguments.get("entities") {
+            None | Some(serde_json::Value::Null) => return Ok(None),
+            Some(serde_json::Value::String(pattern)) => vec![pattern.as_str()],
+            Some(serde_json::Value::Array(patterns)) => patterns.iter()
+                .map(|p| p.as_str().context("'entities' must list entity names or glob patterns"))
+                .collect::<Result<_>>()?,
+            Some(_) => return Err(anyhow::anyhow!("'entities' must list entity names or glob patterns")),
+        };
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+        let patterns = patterns.into_iter()
+            .map(|pattern| glob::Pattern::new(pattern).context(format!("Invalid entity pattern '{}'", pattern)))
+            .collect::<Result<_>>()?;
+        Ok(Some(Self { patterns }))
+    }
+
+    pub(crate) fn matches(&self, name: &str) -> bool {
+        let options = glob::MatchOptions { case_sensitive: false, ..Default::default() };
+        self.patterns.iter().any(|pattern| pattern.matches_with(name, options))
+    }
+
+    /// Matching entities, and the names of the others
+    pub(crate) fn select(&self, entities: Vec<Entity>) -> (Vec<Entity>, Vec<String>) {
+        let (selected, skipped): (Vec<Entity>, Vec<Entity>) = entities.into_iter().partition(|e| self.matches(&e.name));
+        (selected, skipped.into_iter().map(|e| e.name).collect())
+    }
+
+    /// Error for a filter matching none of `names`, the entities found in `source`
+    pub(crate) fn no_match_error(&self, names: &[&str], source: &str) -> anyhow::Error {
+        anyhow::anyhow!(
+            "Entity filter '{}' matches none of the entities in '{}' (found: {})",
+            self,
+            source,
+            if names.is_empty() { "none".to_string() } else { names.join(", ") }
+        )
+    }
+}
+
+impl std::fmt::Display for EntityFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let patterns: Vec<&str> = self.patterns.iter().map(glob::Pattern::as_str).collect();
+        write!(f, "{}", patterns.join(", "))
+    }
+}
 
 /// Tool for transpiling VHDL entities to SystemVerilog 2012 modules
 pub struct TranspileTool {
@@ -20,14 +79,134 @@ impl TranspileTool {
                 description: "Path to the VHDL file to transpile".to_string(),
                 required: true,
                 default: None,
+                ..Default::default()
             },
             ToolParameter {
                 name: "output_file".to_string(),
                 param_type: "string".to_string(),
-                description: "Path to the output SystemVerilog file (optional)".to_string(),
+                description: "Path to the output file (optional)".to_string(),
                 required: false,
                 default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "architecture_name".to_string(),
+                param_type: "string".to_string(),
+                description: "Architecture to transpile when an entity has several (optional, defaults to the first)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "package_files".to_string(),
+                param_type: "array".to_string(),
+                description: "VHDL files declaring packages whose types the design uses; packages in vhdl_file itself are always read (optional)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            }
+            .with_items("string", None, None),
+            ToolParameter {
+                name: "context_files".to_string(),
+                param_type: "array".to_string(),
+                description: "VHDL files holding the entity of an architecture in vhdl_file, or architectures of its entities, when a design is split across files (optional)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            }
+            .with_items("string", None, None),
+            ToolParameter {
+                name: "stub_only".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Emit black-box module stubs with ports only, skipping architectures (default: false)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "strict".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Fail on any syntax error; when false, entities free of syntax errors are still transpiled and the errors are listed with their lines (default: true)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(true)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "generate_testbench".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Also emit a <entity>_tb.sv testbench skeleton next to the output file (default: false)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "output_format".to_string(),
+                param_type: "string".to_string(),
+                description: "Output language: \"systemverilog\", \"verilog\" (Verilog-2001) or \"spinalhdl\" (Scala) (default: systemverilog)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::String("systemverilog".to_string())),
+                ..Default::default()
+            }
+            .with_enum_values(["systemverilog", "verilog", "spinalhdl"]),
+            ToolParameter {
+                name: "verilog_style".to_string(),
+                param_type: "string".to_string(),
+                description: "Port declarations of Verilog output: \"verilog2001_ansi\" (in the module header), \"verilog2001_non_ansi\" (names in the header, `input wire clk;` in the body) or \"verilog95\" (`input clk;`, separate `reg` and parameter declarations) (default: verilog2001_ansi)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::String("verilog2001_ansi".to_string())),
+                ..Default::default()
+            }
+            .with_enum_values(["verilog2001_ansi", "verilog2001_non_ansi", "verilog95"]),
+            ToolParameter {
+                name: "emit_source_refs".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Verilog and SystemVerilog output: append // vhdl:<file>:<line> comments linking generated code to the VHDL source, and write a <output_file>.map.json line map (default: false)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "emit_source_map".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Verilog output only: append // vhdl:<file>:<line> comments and write a Source Map v3 <output_file>.map (default: false)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "quality_report".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Append a quality report scoring each generated module against its VHDL entity (default: false)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "verbose".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Append a conversion report per module: port and type mappings, process classifications, unsupported constructs and warnings (default: false)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
             },
+            ToolParameter {
+                name: "precheck".to_string(),
+                param_type: "string".to_string(),
+                description: "Semantic check before transpiling: \"ghdl\" runs ghdl -s and stops on its errors, skipped with a warning if ghdl is missing (default: \"none\")".to_string(),
+                required: false,
+                default: Some(serde_json::json!("none")),
+                ..Default::default()
+            }
+            .with_enum_values(["ghdl", "none"]),
+            ToolParameter {
+                name: "entities".to_string(),
+                param_type: "array".to_string(),
+                description: "Only generate these entities, by name or glob such as \"axi_*\"; the others are listed as skipped (optional, defaults to all)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            }
+            .with_items("string", None, None),
         ];
 
         let base = BaseToolImpl::new(
@@ -41,30 +220,6 @@ impl TranspileTool {
             allowed_folders,
         }
     }
-
-    fn is_path_allowed(&self, path: &Path) -> bool {
-        if self.allowed_folders.is_empty() {
-            return true;
-        }
-
-        let canonical_path = match path.canonicalize() {
-            Ok(p) => p,
-            Err(_) => return false,
-        };
-
-        for allowed in &self.allowed_folders {
-            let allowed_path = match Path::new(allowed).canonicalize() {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
-
-            if canonical_path.starts_with(&allowed_path) {
-                return true;
-            }
-        }
-
-        false
-    }
 }
 
 impl Tool for TranspileTool {
@@ -90,39 +245,392 @@ impl Tool for TranspileTool {
             .get("output_file")
             .and_then(|v| v.as_str());
 
+        let architecture_name = arguments
+            .get("architecture_name")
+            .and_then(|v| v.as_str());
+
+        let package_files: Vec<&str> = arguments
+            .get("package_files")
+            .and_then(|v| v.as_array())
+            .map(|files| files.iter().filter_map(|f| f.as_str()).collect())
+            .unwrap_or_default();
+
+        let context_files: Vec<&str> = arguments
+            .get("context_files")
+            .and_then(|v| v.as_array())
+            .map(|files| files.iter().filter_map(|f| f.as_str()).collect())
+            .unwrap_or_default();
+
+        let stub_only = arguments
+            .get("stub_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let generate_testbench = arguments
+            .get("generate_testbench")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let quality_report = arguments
+            .get("quality_report")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let verbose = arguments
+            .get("verbose")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let strict = arguments
+            .get("strict")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let emit_source_refs = arguments
+            .get("emit_source_refs")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let emit_source_map = arguments
+            .get("emit_source_map")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let precheck = arguments
+            .get("precheck")
+            .and_then(|v| v.as_str())
+            .unwrap_or("none");
+        if precheck != "ghdl" && precheck != "none" {
+            return Err(anyhow::anyhow!("Unsupported precheck '{}': expected \"ghdl\" or \"none\"", precheck));
+        }
+
+        let entity_filter = EntityFilter::from_arguments(arguments)?;
+
+        let output_format = arguments
+            .get("output_format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("systemverilog");
+
+        let language = match output_format {
+            "systemverilog" => "SystemVerilog",
+            "verilog" => "Verilog",
+            "spinalhdl" => "SpinalHDL",
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unsupported output_format '{}': expected \"systemverilog\", \"verilog\" or \"spinalhdl\"",
+                    other
+                ));
+            }
+        };
+        let spinalhdl = language == "SpinalHDL";
+        let verilog = language == "Verilog";
+        if language != "SystemVerilog" && (generate_testbench || quality_report) {
+            return Err(anyhow::anyhow!(
+                "generate_testbench and quality_report are only available for SystemVerilog output"
+            ));
+        }
+        if spinalhdl && emit_source_refs {
+            return Err(anyhow::anyhow!("emit_source_refs is only available for Verilog and SystemVerilog output"));
+        }
+        if verbose && (spinalhdl || stub_only) {
+            return Err(anyhow::anyhow!(
+                "verbose needs full Verilog or SystemVerilog modules (not spinalhdl or stub_only)"
+            ));
+        }
+        let verilog_style = match arguments.get("verilog_style").and_then(|v| v.as_str()) {
+            Some(_) if !verilog => {
+                return Err(anyhow::anyhow!("verilog_style needs output_format \"verilog\""));
+            }
+            Some(style) => style.parse::<VerilogStyle>()?,
+            None => VerilogStyle::default(),
+        };
+        if emit_source_map && (!verilog || stub_only || output_file.is_none()) {
+            return Err(anyhow::anyhow!(
+                "emit_source_map needs output_format \"verilog\", an output_file and full modules (not stub_only)"
+            ));
+        }
+
         let vhdl_path = Path::new(vhdl_file);
 
         // Check if path is allowed
-        if !self.is_path_allowed(vhdl_path) {
+        if !path_within(&self.allowed_folders, vhdl_path) {
             return Err(anyhow::anyhow!(
                 "Access denied: '{}' is not in allowed folders",
                 vhdl_file
             ));
         }
 
+        for package_file in &package_files {
+            if !path_within(&self.allowed_folders, Path::new(package_file)) {
+                return Err(anyhow::anyhow!(
+                    "Access denied: package file '{}' is not in allowed folders",
+                    package_file
+                ));
+            }
+        }
+        for context_file in &context_files {
+            if !path_within(&self.allowed_folders, Path::new(context_file)) {
+                return Err(anyhow::anyhow!(
+                    "Access denied: context file '{}' is not in allowed folders",
+                    context_file
+                ));
+            }
+        }
+
+        let mut precheck_output = String::new();
+        if precheck == "ghdl" {
+            let files: Vec<&Path> = package_files.iter().map(Path::new).chain(std::iter::once(vhdl_path)).collect();
+            match GhdlRunner::new().check(&files)? {
+                GhdlCheck::Unavailable(reason) => {
+                    tracing::warn!("{}", reason);
+                    precheck_output.push_str(&format!("\n  {}", reason));
+                }
+                check => {
+                    let errors: Vec<String> = files.iter()
+                        .flat_map(|file| check.errors_for(file))
+                        .map(|diagnostic| format!("\n  {}", diagnostic))
+                        .collect();
+                    if !errors.is_empty() {
+                        return Err(anyhow::anyhow!("GHDL pre-check failed for '{}':{}", vhdl_file, errors.concat()));
+                    }
+                }
+            }
+        }
+
         // Parse VHDL using AST parser
         tracing::info!("Parsing VHDL file: {}", vhdl_file);
         let mut parser = ASTVHDLParser::from_file(vhdl_path)
             .context(format!("Failed to parse VHDL file: {}", vhdl_file))?;
 
-        let entities = parser.parse_entities()
+        let (mut entities, parse_errors) = parser.parse_entities_partial()
             .context("Failed to extract entities from VHDL")?;
+        if !parse_errors.is_empty() && (strict || entities.is_empty()) {
+            return Err(strict_error(&parse_errors)).context("Failed to extract entities from VHDL");
+        }
+
+        // Join entities and architectures split between vhdl_file and the context files
+        let mut contributor_output = String::new();
+        let mut warning_output = String::new();
+        if !context_files.is_empty() {
+            let mut local_architectures = parser.parse_unmatched_architectures()
+                .context("Failed to extract architectures from VHDL")?;
+            for context_file in &context_files {
+                let mut context_parser = ASTVHDLParser::from_file(Path::new(context_file))
+                    .context(format!("Failed to parse context file: {}", context_file))?;
+                let mut context_entities = context_parser.parse_entities()
+                    .context(format!("Failed to extract entities from context file: {}", context_file))?;
+
+                let mut adopted = Vec::new();
+                for (entity_name, arch) in std::mem::take(&mut local_architectures) {
+                    match attach_architecture(&mut context_entities, &entity_name, arch.clone()) {
+                        Some(index) => {
+                            contributor_output.push_str(&format!("\n  {}: {}, {}", context_entities[index].name, context_file, vhdl_file));
+                            if !adopted.contains(&index) {
+                                adopted.push(index);
+                            }
+                        }
+                        None => local_architectures.push((entity_name, arch)),
+                    }
+                }
+                entities.extend(adopted.into_iter().map(|index| context_entities[index].clone()));
+
+                let context_architectures = context_parser.parse_unmatched_architectures()
+                    .context(format!("Failed to extract architectures from context file: {}", context_file))?;
+                for (entity_name, arch) in context_architectures {
+                    if let Some(index) = attach_architecture(&mut entities, &entity_name, arch) {
+                        contributor_output.push_str(&format!("\n  {}: {}, {}", entities[index].name, vhdl_file, context_file));
+                    }
+                }
+            }
+            for (entity_name, arch) in &local_architectures {
+                warning_output.push_str(&format!(
+                    "\n  architecture {} of {}: entity not found in the context files",
+                    arch.name, entity_name
+                ));
+            }
+        }
+
+        if let Some(arch_name) = architecture_name {
+            let mut found = false;
+            for entity in &mut entities {
+                found |= entity.select_architecture(arch_name);
+            }
+            if !found {
+                return Err(anyhow::anyhow!("No entity has an architecture named '{}'", arch_name))
+                    .context("Failed to extract entities from VHDL");
+            }
+        }
 
         if entities.is_empty() {
             return Err(anyhow::anyhow!("No entities found in VHDL file"));
         }
 
-        // Generate SystemVerilog for all entities
-        let generator = SystemVerilogGenerator::new();
-        let mut systemverilog_output = String::new();
+        let mut skipped_entities = Vec::new();
+        if let Some(filter) = &entity_filter {
+            let names: Vec<String> = entities.iter().map(|e| e.name.clone()).collect();
+            (entities, skipped_entities) = filter.select(entities);
+            if entities.is_empty() {
+                let names: Vec<&str> = names.iter().map(String::as_str).collect();
+                return Err(filter.no_match_error(&names, vhdl_file));
+            }
+        }
+        let selected = |name: &str| !skipped_entities.iter().any(|skipped| skipped == name);
+
+        // Collect package types so custom port and signal types get concrete widths
+        let mut packages = PackageRegistry::new();
+        packages.add_file(vhdl_path)?;
+        for file in package_files.iter().chain(&context_files) {
+            packages.add_file(Path::new(file))?;
+        }
+        let types = packages.type_context();
+
+        // Generate the target language for all entities
+        let file_name = vhdl_path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let source_name = Some(file_name.clone()).filter(|_| emit_source_refs);
+        let generator = SystemVerilogGenerator::new()
+            .with_black_box(stub_only)
+            .with_source_refs(source_name.clone())
+            .with_type_context(Some(types.clone()));
+        let spinalhdl_generator = SpinalHDLGenerator::new();
+        let verilog_generator = VerilogGenerator::new()
+            .with_style(verilog_style)
+            .with_black_box(stub_only)
+            .with_source_refs(source_name)
+            .with_type_context(Some(types));
+        let positions = if emit_source_map {
+            Some(parser.source_map().context("Failed to index VHDL source positions")?)
+        } else {
+            None
+        };
+        let mut mapping = SourceMapOutput::default();
+        let mut generated_output = String::new();
+        let mut report_output = String::new();
+        let mut conversion_output = String::new();
+        let mut rename_output = String::new();
+        let mut testbenches = Vec::new();
+
+        for (entity_name, rename) in parser.case_normalizations().iter().filter(|(name, _)| selected(name)) {
+            rename_output.push_str(&format!("\n  {}: {}", entity_name, rename));
+        }
+        for (entity_name, diagnostic) in parser.binding_diagnostics().iter().filter(|(name, _)| selected(name)) {
+            warning_output.push_str(&format!("\n  {}: {}", entity_name, diagnostic));
+        }
 
         for entity in &entities {
-            tracing::info!("Generating SystemVerilog for entity: {}", entity.name);
-            let systemverilog = generator.generate(entity)
-                .context(format!("Failed to generate SystemVerilog for entity: {}", entity.name))?;
+            tracing::info!("Generating {} for entity: {}", language, entity.name);
+            if let Some(arch) = entity.architecture.as_ref().filter(|arch| !stub_only && arch.is_behavioral_only()) {
+                warning_output.push_str(&format!("\n  {}: {}", entity.name, TranspilationWarning::behavioral_only(&arch.name)));
+            }
+            let (generated, renames) = if spinalhdl {
+                if stub_only {
+                    spinalhdl_generator.generate_stub(entity).map(|scala| (scala, Vec::new()))
+                        .context(format!("Failed to generate SpinalHDL for entity: {}", entity.name))?
+                } else {
+                    let (scala, report) = spinalhdl_generator.generate(entity)
+                        .context(format!("Failed to generate SpinalHDL for entity: {}", entity.name))?;
+                    for warning in &report.warnings {
+                        warning_output.push_str(&format!("\n  {}: {}", entity.name, warning));
+                    }
+                    if verbose {
+                        conversion_output.push_str(&format!("\n{}", report.to_markdown()));
+                    }
+                    (scala, report.renames)
+                }
+            } else if verilog {
+                let context = || format!("Failed to generate Verilog for entity: {}", entity.name);
+                if let Some(positions) = &positions {
+                    let (module, entity_mapping) = verilog_generator.generate_with_source_map(entity, &file_name, positions)
+                        .with_context(context)?;
+                    let offset = generated_output.lines().count() as u32;
+                    mapping.entries.extend(entity_mapping.entries.into_iter().map(|mut entry| {
+                        entry.verilog_line += offset;
+                        entry
+                    }));
+                    if verbose {
+                        let (_, report) = verilog_generator.generate(entity).with_context(context)?;
+                        conversion_output.push_str(&format!("\n{}", report.to_markdown()));
+                    }
+                    (module, Vec::new())
+                } else if stub_only {
+                    verilog_generator.generate_stub(entity).map(|v| (v, Vec::new())).with_context(context)?
+                } else {
+                    let (module, report) = verilog_generator.generate(entity)
+                        .with_context(context)?;
+                    for warning in &report.warnings {
+                        warning_output.push_str(&format!("\n  {}: {}", entity.name, warning));
+                    }
+                    if verbose {
+                        conversion_output.push_str(&format!("\n{}", report.to_markdown()));
+                    }
+                    (module, report.renames)
+                }
+            } else if stub_only {
+                generator.generate_stub(entity).map(|sv| (sv, Vec::new()))
+                    .context(format!("Failed to generate SystemVerilog for entity: {}", entity.name))?
+            } else {
+                let (sv, report) = generator.generate(entity)
+                    .context(format!("Failed to generate SystemVerilog for entity: {}", entity.name))?;
+                for warning in &report.warnings {
+                    warning_output.push_str(&format!("\n  {}: {}", entity.name, warning));
+                }
+                if verbose {
+                    conversion_output.push_str(&format!("\n{}", report.to_markdown()));
+                }
+                (sv, report.renames)
+            };
 
-            systemverilog_output.push_str(&systemverilog);
-            systemverilog_output.push('\n');
+            for rename in renames {
+                rename_output.push_str(&format!("\n  {}: {}", entity.name, rename));
+            }
+
+            if generate_testbench {
+                let testbench = TestbenchGenerator::new().generate(entity)
+                    .context(format!("Failed to generate testbench for entity: {}", entity.name))?;
+                testbenches.push((format!("{}_tb.sv", entity.name), testbench));
+            }
+
+            if quality_report && !stub_only {
+                let report = TranspileQualityChecker::check(entity, &generated);
+                report_output.push_str(&format!("\n{}: {}", entity.name, report));
+            }
+
+            generated_output.push_str(&generated);
+            generated_output.push('\n');
+        }
+
+        let mut report_section = String::new();
+        if !precheck_output.is_empty() {
+            report_section.push_str(&format!("\n\nPre-check warnings:{}", precheck_output));
+        }
+        if !parse_errors.is_empty() {
+            report_section.push_str("\n\nParse errors (entities containing them were not transpiled):");
+            for error in &parse_errors {
+                report_section.push_str(&format!("\n  {}", error));
+            }
+        }
+        if !skipped_entities.is_empty() {
+            report_section.push_str(&format!(
+                "\n\nSkipped entities (not matching '{}'): {}",
+                entity_filter.as_ref().map(ToString::to_string).unwrap_or_default(),
+                skipped_entities.join(", ")
+            ));
+        }
+        if !contributor_output.is_empty() {
+            report_section.push_str(&format!("\n\nContributing files:{}", contributor_output));
+        }
+        if !rename_output.is_empty() {
+            report_section.push_str(&format!("\n\nRenamed identifiers:{}", rename_output));
+        }
+        if !warning_output.is_empty() {
+            report_section.push_str(&format!("\n\nTranspilation warnings:{}", warning_output));
+        }
+        if !report_output.is_empty() {
+            report_section.push_str(&format!("\n\nQuality report:{}", report_output));
+        }
+        if !conversion_output.is_empty() {
+            report_section.push_str(&format!("\n\nConversion reports:{}", conversion_output));
         }
 
         // Write to file if output path provided
@@ -130,31 +638,70 @@ impl Tool for TranspileTool {
             let out_path = Path::new(output_path);
 
             // Check output path is allowed
-            if !self.is_path_allowed(out_path.parent().unwrap_or(Path::new("."))) {
+            if !path_within(&self.allowed_folders, out_path.parent().unwrap_or(Path::new("."))) {
                 return Err(anyhow::anyhow!(
                     "Access denied: output path '{}' is not in allowed folders",
                     output_path
                 ));
             }
 
-            std::fs::write(out_path, &systemverilog_output)
-                .context(format!("Failed to write SystemVerilog to: {}", output_path))?;
+            std::fs::write(out_path, &generated_output)
+                .context(format!("Failed to write {} to: {}", language, output_path))?;
+
+            tracing::info!("{} written to: {}", language, output_path);
+
+            let mut source_map_section = String::new();
+            if emit_source_refs {
+                let map_path = format!("{}.map.json", output_path);
+                let map = serde_json::json!({
+                    "source": vhdl_file,
+                    "generated": output_path,
+                    "lines": source_ref_lines(&generated_output),
+                });
+                std::fs::write(&map_path, serde_json::to_string_pretty(&map)?)
+                    .context(format!("Failed to write source map to: {}", map_path))?;
+                source_map_section = format!("\nSource map written to: {}", map_path);
+            }
+            if emit_source_map {
+                let map_path = format!("{}.map", output_path);
+                let map = mapping.to_v3(output_path, vhdl_file);
+                std::fs::write(&map_path, serde_json::to_string_pretty(&map)?)
+                    .context(format!("Failed to write source map to: {}", map_path))?;
+                source_map_section.push_str(&format!("\nSource map written to: {}", map_path));
+            }
 
-            tracing::info!("SystemVerilog written to: {}", output_path);
+            let mut testbench_section = String::new();
+            for (filename, testbench) in &testbenches {
+                let tb_path = out_path.parent().unwrap_or(Path::new(".")).join(filename);
+                std::fs::write(&tb_path, testbench)
+                    .context(format!("Failed to write testbench to: {}", tb_path.display()))?;
+                testbench_section.push_str(&format!("\nTestbench written to: {}", tb_path.display()));
+            }
 
             Ok(format!(
-                "Successfully transpiled {} entity(ies) from '{}' to '{}'\n\nGenerated SystemVerilog:\n{}",
+                "Successfully transpiled {} entity(ies) from '{}' to '{}'\n\nGenerated {}:\n{}{}{}{}",
                 entities.len(),
                 vhdl_file,
                 output_path,
-                systemverilog_output
+                language,
+                generated_output,
+                testbench_section,
+                source_map_section,
+                report_section
             ))
         } else {
+            let testbench_section: String = testbenches.iter()
+                .map(|(filename, testbench)| format!("\n// {}\n{}", filename, testbench))
+                .collect();
+
             Ok(format!(
-                "Successfully transpiled {} entity(ies) from '{}'\n\nGenerated SystemVerilog:\n{}",
+                "Successfully transpiled {} entity(ies) from '{}'\n\nGenerated {}:\n{}{}{}",
                 entities.len(),
                 vhdl_file,
-                systemverilog_output
+                language,
+                generated_output,
+                testbench_section,
+                report_section
             ))
         }
     }
@@ -197,7 +744,156 @@ mod tests {
 
         assert!(result.contains("Successfully transpiled"));
         assert!(result.contains("module counter"));
-        assert!(result.contains("input logic clk"));
+        assert!(result.contains("input  logic       clk"));
         assert!(result.contains("output logic [7:0] count"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_unknown_output_format_is_rejected() {
+        let tool = TranspileTool::new(vec![]);
+        let args = serde_json::json!({
+            "vhdl_file": "counter.vhd",
+            "output_format": "chisel"
+        });
+
+        let err = tool.execute(&args).unwrap_err();
+        assert!(err.to_string().contains("Unsupported output_format 'chisel'"));
+
+        // The schema lists the accepted formats so callers need not guess
+        let properties = &tool.to_openai_function()["parameters"]["properties"];
+        assert_eq!(properties["output_format"]["enum"], serde_json::json!(["systemverilog", "verilog", "spinalhdl"]));
+        assert_eq!(properties["package_files"]["items"], serde_json::json!({ "type": "string" }));
+    }
+    #[test]
+    fn test_verilog_style_needs_verilog_output() {
+        let tool = TranspileTool::new(vec![]);
+        let err = tool.execute(&serde_json::json!({ "vhdl_file": "counter.vhd", "verilog_style": "verilog95" })).unwrap_err();
+        assert!(err.to_string().contains("verilog_style needs output_format \"verilog\""));
+
+        let args = serde_json::json!({ "vhdl_file": "counter.vhd", "output_format": "verilog", "verilog_style": "verilog2005" });
+        assert!(tool.execute(&args).unwrap_err().to_string().contains("Unknown Verilog style 'verilog2005'"));
+    }
+
+    #[test]
+    fn test_partial_output_with_parse_errors() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/partial_parse.vhd");
+        let tool = TranspileTool::new(vec![]);
+
+        let err = tool.execute(&serde_json::json!({ "vhdl_file": fixture.to_str().unwrap() })).unwrap_err();
+        assert!(format!("{:#}", err).contains("Parse tree contains errors: line 29:"));
+
+        let args = serde_json::json!({ "vhdl_file": fixture.to_str().unwrap(), "strict": false });
+        let result = tool.execute(&args).unwrap();
+        assert!(result.contains("Successfully transpiled 1 entity(ies)"));
+        assert!(result.contains("module clean_buf"));
+        assert!(!result.contains("module broken_reg"));
+        assert!(result.contains("Parse errors (entities containing them were not transpiled):\n  line 29:"));
+        assert!(result.contains("in broken_reg: `q <= ;`"));
+    }
+
+    #[test]
+    fn test_context_file_supplies_entity() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/split");
+        let rtl = fixtures.join("blinker_rtl.vhd");
+        let entity = fixtures.join("blinker_entity.vhd");
+
+        let result = TranspileTool::new(vec![]).execute(&serde_json::json!({
+            "vhdl_file": rtl.to_str().unwrap(),
+            "context_files": [entity.to_str().unwrap()]
+        })).unwrap();
+        assert!(result.contains("Successfully transpiled 1 entity(ies)"));
+        assert_eq!(result.matches("module blinker").count(), 1);
+        assert!(result.contains("always_ff @(posedge clk or posedge reset)"));
+        assert!(result.contains(&format!("Contributing files:\n  blinker: {}, {}", entity.display(), rtl.display())));
+    }
+
+    #[test]
+    fn test_verilog_source_map() {
+        let vhdl_content = "entity buf is\n    port(\n        a : in  std_logic;\n        y : out std_logic\n    );\nend entity buf;\n";
+        let dir = tempfile::tempdir().unwrap();
+        let vhdl_path = dir.path().join("buf.vhd");
+        std::fs::write(&vhdl_path, vhdl_content).unwrap();
+        let output_path = dir.path().join("buf.v");
+
+        let tool = TranspileTool::new(vec![]);
+        let args = serde_json::json!({
+            "vhdl_file": vhdl_path.to_str().unwrap(),
+            "output_file": output_path.to_str().unwrap(),
+            "output_format": "verilog",
+            "emit_source_map": true
+        });
+        let result = tool.execute(&args).unwrap();
+        assert!(result.contains("Generated Verilog:"));
+
+        let verilog = std::fs::read_to_string(&output_path).unwrap();
+        assert!(verilog.contains("input  wire a, // vhdl:buf.vhd:3"));
+        assert!(verilog.contains("output wire y // vhdl:buf.vhd:4"));
+
+        let map: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join("buf.v.map")).unwrap()).unwrap();
+        assert_eq!(map["version"], 3);
+        // Line 2 maps to VHDL line 3 column 8, line 3 one line further down
+        assert_eq!(map["mappings"], ";AAEQ;AACA");
+
+        let args = serde_json::json!({ "vhdl_file": vhdl_path.to_str().unwrap(), "emit_source_map": true });
+        let err = tool.execute(&args).unwrap_err();
+        assert!(err.to_string().contains("emit_source_map needs output_format \"verilog\""));
+    }
+
+    #[test]
+    fn test_verbose_conversion_report() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/counter.vhd");
+        let tool = TranspileTool::new(vec![]);
+
+        let args = serde_json::json!({ "vhdl_file": fixture.to_str().unwrap(), "verbose": true });
+        let result = tool.execute(&args).unwrap();
+        assert!(result.contains("Conversion reports:\n## Conversion report: counter\n"));
+        assert!(result.contains("| clk | in | std_logic | clk | input | logic |"));
+
+        let args = serde_json::json!({ "vhdl_file": fixture.to_str().unwrap(), "verbose": true, "output_format": "spinalhdl" });
+        let err = tool.execute(&args).unwrap_err();
+        assert!(err.to_string().contains("verbose needs full Verilog or SystemVerilog modules"));
+    }
+
+    #[test]
+    fn test_entity_filter_patterns() {
+        let filter = EntityFilter::from_arguments(&serde_json::json!({ "entities": ["axi_*", "Top"] })).unwrap().unwrap();
+        assert!(filter.matches("AXI_Lite_Slave"));
+        assert!(filter.matches("top"));
+        assert!(!filter.matches("top_tb"));
+        assert_eq!(filter.to_string(), "axi_*, Top");
+
+        assert!(EntityFilter::from_arguments(&serde_json::json!({ "entities": [] })).unwrap().is_none());
+        assert!(EntityFilter::from_arguments(&serde_json::json!({ "entities": "uart" })).unwrap().unwrap().matches("UART"));
+        let err = EntityFilter::from_arguments(&serde_json::json!({ "entities": ["axi_[*"] })).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid entity pattern 'axi_[*'");
+    }
+
+    #[test]
+    fn test_entity_filter() {
+        let vhdl_content = r#"
+        entity axi_fifo is
+            port(clk : in std_logic; full : out std_logic);
+        end entity axi_fifo;
+
+        entity fifo_tb is
+        end entity fifo_tb;
+        "#;
+        let mut vhdl_file = NamedTempFile::new().unwrap();
+        vhdl_file.write_all(vhdl_content.as_bytes()).unwrap();
+        let vhdl_path = vhdl_file.path().to_str().unwrap();
+        let tool = TranspileTool::new(vec![]);
+
+        let result = tool.execute(&serde_json::json!({ "vhdl_file": vhdl_path, "entities": ["AXI_*"] })).unwrap();
+        assert!(result.contains("Successfully transpiled 1 entity(ies)"));
+        assert!(result.contains("module axi_fifo"));
+        assert!(!result.contains("module fifo_tb"));
+        assert!(result.contains("Skipped entities (not matching 'AXI_*'): fifo_tb"));
+
+        let err = tool.execute(&serde_json::json!({ "vhdl_file": vhdl_path, "entities": ["uart", "spi_*"] })).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Entity filter 'uart, spi_*' matches none of the entities in '{}' (found: axi_fifo, fifo_tb)", vhdl_path)
+        );
+    }
+}
@@ -1,15 +1,22 @@
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::time::Instant;
 
-use crate::ir::SystemVerilogGenerator;
-use crate::parser::ASTVHDLParser;
-use crate::tools::{BaseToolImpl, Tool, ToolParameter, ToolSchema};
+use crate::ir::{build_hierarchy, Architecture, Entity, SystemVerilogGenerator, TestbenchGenerator, TypeResolutionContext};
+use crate::parser::{attach_architecture, ASTVHDLParser, PackageRegistry};
+use crate::tools::transpile::EntityFilter;
+use crate::tools::{path_within, BaseToolImpl, BatchReport, FileReport, ProgressCallback, Tool, ToolParameter, ToolSchema};
+use crate::utils::{GhdlCheck, GhdlRunner};
+
+/// Entities of a file, and the architectures it holds for entities declared elsewhere
+type ParsedFile = (Vec<Entity>, Vec<(String, Architecture)>);
 
 /// Tool for batch transpiling VHDL files in a folder to SystemVerilog 2012 modules
 pub struct TranspileFolderTool {
     base: BaseToolImpl,
     allowed_folders: Vec<String>,
+    progress: Option<Box<dyn ProgressCallback>>,
 }
 
 impl TranspileFolderTool {
@@ -21,6 +28,7 @@ impl TranspileFolderTool {
                 description: "Path to the folder containing VHDL files to transpile".to_string(),
                 required: true,
                 default: None,
+                ..Default::default()
             },
             ToolParameter {
                 name: "output_folder".to_string(),
@@ -28,6 +36,7 @@ impl TranspileFolderTool {
                 description: "Path to the output folder for SystemVerilog files (optional, defaults to same folder)".to_string(),
                 required: false,
                 default: None,
+                ..Default::default()
             },
             ToolParameter {
                 name: "recursive".to_string(),
@@ -35,6 +44,66 @@ impl TranspileFolderTool {
                 description: "Whether to recursively process subdirectories (default: false)".to_string(),
                 required: false,
                 default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "stub_only".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Emit black-box module stubs with ports only, skipping architectures (default: false)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "generate_testbench".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Also emit a <entity>_tb.sv testbench skeleton for each entity (default: false)".to_string(),
+                required: false,
+                default: Some(serde_json::Value::Bool(false)),
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "precheck".to_string(),
+                param_type: "string".to_string(),
+                description: "Semantic check before transpiling: \"ghdl\" runs ghdl -s on all files and skips those with errors, skipped with a warning if ghdl is missing (default: \"none\")".to_string(),
+                required: false,
+                default: Some(serde_json::json!("none")),
+                ..Default::default()
+            }
+            .with_enum_values(["ghdl", "none"]),
+            ToolParameter {
+                name: "top_entity".to_string(),
+                param_type: "string".to_string(),
+                description: "Only transpile this entity and the entities it instantiates, directly or not, leaves first (optional, defaults to every file)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "entities".to_string(),
+                param_type: "array".to_string(),
+                description: "Only generate these entities, by name or glob such as \"axi_*\"; files without a match are skipped (optional, defaults to all)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            }
+            .with_items("string", None, None),
+            ToolParameter {
+                name: "report_format".to_string(),
+                param_type: "string".to_string(),
+                description: "Format of the per-file report written to report_file: \"text\" (the summary), \"json\" or \"junit\" XML (default: \"text\")".to_string(),
+                required: false,
+                default: Some(serde_json::json!("text")),
+                ..Default::default()
+            }
+            .with_enum_values(["text", "json", "junit"]),
+            ToolParameter {
+                name: "report_file".to_string(),
+                param_type: "string".to_string(),
+                description: "Path to write the report to (required for json and junit)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
             },
         ];
 
@@ -47,31 +116,22 @@ impl TranspileFolderTool {
         Self {
             base,
             allowed_folders,
+            progress: None,
         }
     }
 
-    fn is_path_allowed(&self, path: &Path) -> bool {
-        if self.allowed_folders.is_empty() {
-            return true;
-        }
-
-        let canonical_path = match path.canonicalize() {
-            Ok(p) => p,
-            Err(_) => return false,
-        };
-
-        for allowed in &self.allowed_folders {
-            let allowed_path = match Path::new(allowed).canonicalize() {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
+    /// Report each file to `callback` as soon as it is done
+    pub fn with_progress_callback(mut self, callback: Box<dyn ProgressCallback>) -> Self {
+        self.progress = Some(callback);
+        self
+    }
 
-            if canonical_path.starts_with(&allowed_path) {
-                return true;
-            }
+    /// Count `file` as done and pass its outcome to the progress callback, if any
+    fn report_progress(&self, done: &mut usize, total: usize, file: &Path, report: &FileReport) {
+        *done += 1;
+        if let Some(progress) = &self.progress {
+            progress.on_progress(*done, total, &file.display().to_string(), report);
         }
-
-        false
     }
 
     fn find_vhdl_files(&self, folder: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
@@ -104,28 +164,54 @@ impl TranspileFolderTool {
         Ok(vhdl_files)
     }
 
-    fn transpile_file(&self, vhdl_path: &Path, output_folder: &Path) -> Result<(String, String)> {
-        // Parse VHDL using AST parser
+    fn parse_file(vhdl_path: &Path) -> Result<ParsedFile> {
         let mut parser = ASTVHDLParser::from_file(vhdl_path)
             .context(format!("Failed to parse VHDL file: {}", vhdl_path.display()))?;
 
         let entities = parser.parse_entities()
             .context("Failed to extract entities from VHDL")?;
+        let architectures = parser.parse_unmatched_architectures()
+            .context("Failed to extract architectures from VHDL")?;
+        Ok((entities, architectures))
+    }
 
+    fn transpile_file(
+        &self,
+        vhdl_path: &Path,
+        entities: &[Entity],
+        output_folder: &Path,
+        stub_only: bool,
+        generate_testbench: bool,
+        types: &TypeResolutionContext,
+    ) -> Result<(String, String)> {
         if entities.is_empty() {
             return Err(anyhow::anyhow!("No entities found in VHDL file"));
         }
 
         // Generate SystemVerilog for all entities
-        let generator = SystemVerilogGenerator::new();
+        let generator = SystemVerilogGenerator::new()
+            .with_black_box(stub_only)
+            .with_type_context(Some(types.clone()));
         let mut systemverilog_output = String::new();
 
-        for entity in &entities {
-            let systemverilog = generator.generate(entity)
+        for entity in entities {
+            let systemverilog = if stub_only {
+                generator.generate_stub(entity)
+            } else {
+                generator.generate(entity).map(|(sv, _)| sv)
+            }
                 .context(format!("Failed to generate SystemVerilog for entity: {}", entity.name))?;
 
             systemverilog_output.push_str(&systemverilog);
             systemverilog_output.push('\n');
+
+            if generate_testbench {
+                let tb_path = output_folder.join(format!("{}_tb.sv", entity.name));
+                let testbench = TestbenchGenerator::new().generate(entity)
+                    .context(format!("Failed to generate testbench for entity: {}", entity.name))?;
+                std::fs::write(&tb_path, testbench)
+                    .context(format!("Failed to write testbench to: {}", tb_path.display()))?;
+            }
         }
 
         // Determine output file path
@@ -173,24 +259,76 @@ impl Tool for TranspileFolderTool {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let stub_only = arguments
+            .get("stub_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let generate_testbench = arguments
+            .get("generate_testbench")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let precheck = arguments
+            .get("precheck")
+            .and_then(|v| v.as_str())
+            .unwrap_or("none");
+        if precheck != "ghdl" && precheck != "none" {
+            return Err(anyhow::anyhow!("Unsupported precheck '{}': expected \"ghdl\" or \"none\"", precheck));
+        }
+
+        let top_entity = arguments
+            .get("top_entity")
+            .and_then(|v| v.as_str());
+
+        let entity_filter = EntityFilter::from_arguments(arguments)?;
+
+        let report_format = arguments
+            .get("report_format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("text");
+        if !["text", "json", "junit"].contains(&report_format) {
+            return Err(anyhow::anyhow!(
+                "Unsupported report_format '{}': expected \"text\", \"json\" or \"junit\"",
+                report_format
+            ));
+        }
+
+        let report_file = arguments
+            .get("report_file")
+            .and_then(|v| v.as_str());
+        if report_file.is_none() && report_format != "text" {
+            return Err(anyhow::anyhow!("report_format '{}' requires a report_file", report_format));
+        }
+
         let vhdl_path = Path::new(vhdl_folder);
         let output_path = Path::new(output_folder);
 
         // Check if paths are allowed
-        if !self.is_path_allowed(vhdl_path) {
+        if !path_within(&self.allowed_folders, vhdl_path) {
             return Err(anyhow::anyhow!(
                 "Access denied: '{}' is not in allowed folders",
                 vhdl_folder
             ));
         }
 
-        if !self.is_path_allowed(output_path) {
+        if !path_within(&self.allowed_folders, output_path) {
             return Err(anyhow::anyhow!(
                 "Access denied: output path '{}' is not in allowed folders",
                 output_folder
             ));
         }
 
+        if let Some(report_file) = report_file {
+            let parent = Path::new(report_file).parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            if !path_within(&self.allowed_folders, parent) {
+                return Err(anyhow::anyhow!(
+                    "Access denied: report file '{}' is not in allowed folders",
+                    report_file
+                ));
+            }
+        }
+
         // Create output folder if it doesn't exist
         if !output_path.exists() {
             fs::create_dir_all(output_path)
@@ -199,33 +337,214 @@ impl Tool for TranspileFolderTool {
 
         // Find all VHDL files
         tracing::info!("Searching for VHDL files in: {}", vhdl_folder);
-        let vhdl_files = self.find_vhdl_files(vhdl_path, recursive)?;
+        let mut vhdl_files = self.find_vhdl_files(vhdl_path, recursive)?;
+        vhdl_files.sort();
 
         if vhdl_files.is_empty() {
             return Ok(format!("No VHDL files found in '{}'", vhdl_folder));
         }
 
         tracing::info!("Found {} VHDL file(s)", vhdl_files.len());
+        let started = Instant::now();
+        let mut file_reports: Vec<FileReport> = vhdl_files.iter().map(|f| FileReport::new(f)).collect();
+        let index_of = |file: &Path| vhdl_files.iter().position(|f| f == file).expect("file is from vhdl_files");
+        let mut done = 0;
+
+        // Package types from every file, so entities can use types declared elsewhere
+        let mut packages = PackageRegistry::new();
+        for vhdl_file in &vhdl_files {
+            if let Err(e) = packages.add_file(vhdl_file) {
+                tracing::warn!("Skipping package scan of {}: {}", vhdl_file.display(), e);
+            }
+        }
+        let types = packages.type_context();
+
+        let mut precheck_warning = None;
+        let check = if precheck == "ghdl" {
+            let files: Vec<&Path> = vhdl_files.iter().map(PathBuf::as_path).collect();
+            let check = GhdlRunner::new().check(&files)?;
+            if let GhdlCheck::Unavailable(reason) = &check {
+                tracing::warn!("{}", reason);
+                precheck_warning = Some(reason.clone());
+            }
+            Some(check)
+        } else {
+            None
+        };
 
         // Transpile each file
         let mut results = Vec::new();
         let mut errors = Vec::new();
         let mut success_count = 0;
 
+        // Parse every file first, so architectures can join entities declared in other files
+        let mut parsed = Vec::new();
+        let mut foreign_architectures = Vec::new();
         for vhdl_file in &vhdl_files {
+            let precheck_errors = check.as_ref().map(|check| check.errors_for(vhdl_file)).unwrap_or_default();
+            if !precheck_errors.is_empty() {
+                let diagnostics: String = precheck_errors.iter().map(|d| format!("\n    {}", d)).collect();
+                errors.push(format!("✗ {}: GHDL pre-check failed:{}", vhdl_file.display(), diagnostics));
+                for diagnostic in precheck_errors {
+                    file_reports[index_of(vhdl_file)].fail(format!("GHDL pre-check failed: {}", diagnostic));
+                }
+                self.report_progress(&mut done, vhdl_files.len(), vhdl_file, &file_reports[index_of(vhdl_file)]);
+                continue;
+            }
+
+            let parse_started = Instant::now();
+            let parse_result = Self::parse_file(vhdl_file);
+            file_reports[index_of(vhdl_file)].add_duration(parse_started.elapsed());
+            match parse_result {
+                Ok((entities, architectures)) => {
+                    foreign_architectures.extend(architectures.into_iter().map(|(entity, arch)| (vhdl_file, entity, arch)));
+                    parsed.push((vhdl_file, entities, Vec::new()));
+                }
+                Err(e) => {
+                    let error_msg = format!("✗ {}: {}", vhdl_file.display(), e);
+                    errors.push(error_msg.clone());
+                    file_reports[index_of(vhdl_file)].fail(e.to_string());
+                    tracing::error!("{}", error_msg);
+                    self.report_progress(&mut done, vhdl_files.len(), vhdl_file, &file_reports[index_of(vhdl_file)]);
+                }
+            }
+        }
+
+        // Contributing files of each entity that got ports or an architecture from another file
+        let mut merged_files = Vec::new();
+
+        // An entity declared again in a later file is another partial parse of it
+        for j in 1..parsed.len() {
+            let (earlier, rest) = parsed.split_at_mut(j);
+            let (file, entities, _) = &mut rest[0];
+            let mut k = 0;
+            while k < entities.len() {
+                let found = earlier.iter_mut().find_map(|(base_file, base_entities, contributors)| {
+                    base_entities.iter_mut()
+                        .find(|e| e.name.eq_ignore_ascii_case(&entities[k].name))
+                        .map(|base| (*base_file, base, contributors))
+                });
+                let Some((base_file, base, contributors)) = found else {
+                    k += 1;
+                    continue;
+                };
+                let duplicate = entities.remove(k);
+                match Entity::merge(base, &duplicate) {
+                    Ok(merged) => {
+                        *base = merged;
+                        contributors.push(format!("{}: {}, {}", base.name, base_file.display(), file.display()));
+                        merged_files.push(*file);
+                    }
+                    Err(e) => {
+                        errors.push(format!(
+                            "✗ {}: entity {} conflicts with {}: {}",
+                            file.display(), duplicate.name, base_file.display(), e
+                        ));
+                        file_reports[index_of(file)].fail(format!(
+                            "entity {} conflicts with {}: {}",
+                            duplicate.name, base_file.display(), e
+                        ));
+                    }
+                }
+            }
+        }
+
+        for (arch_file, entity_name, arch) in foreign_architectures {
+            let arch_name = arch.name.clone();
+            let target = parsed.iter_mut().find_map(|(file, entities, contributors)| {
+                attach_architecture(entities, &entity_name, arch.clone()).map(|i| (*file, &entities[i].name, contributors))
+            });
+            match target {
+                Some((entity_file, name, contributors)) => {
+                    contributors.push(format!("{}: {}, {}", name, entity_file.display(), arch_file.display()));
+                    merged_files.push(arch_file);
+                }
+                None => {
+                    let message = format!("architecture {} of {}: no entity {} in the folder", arch_name, entity_name, entity_name);
+                    errors.push(format!("✗ {}: {}", arch_file.display(), message));
+                    file_reports[index_of(arch_file)].fail(message);
+                }
+            }
+        }
+
+        if let Some(filter) = &entity_filter {
+            let names: Vec<&str> = parsed.iter()
+                .flat_map(|(_, entities, _)| entities.iter().map(|e| e.name.as_str()))
+                .collect();
+            if !names.iter().any(|name| filter.matches(name)) {
+                return Err(filter.no_match_error(&names, vhdl_folder));
+            }
+        }
+
+        // Entities reachable from the top entity, leaves first, and the files holding them in that order
+        let mut dependency_tree = None;
+        let mut cone: Option<Vec<String>> = None;
+        if let Some(top) = top_entity {
+            let all_entities: Vec<Entity> = parsed.iter().flat_map(|(_, entities, _)| entities.iter().cloned()).collect();
+            let tree = build_hierarchy(&all_entities, top)
+                .context(format!("Failed to trace the dependencies of top entity '{}'", top))?;
+            let order = tree.dependency_order();
+            let position = |name: &str| order.iter().position(|n| n.eq_ignore_ascii_case(name));
+            parsed.sort_by_key(|(_, entities, _)| entities.iter().filter_map(|e| position(&e.name)).max().unwrap_or(usize::MAX));
+            cone = Some(order);
+            dependency_tree = Some(tree);
+        }
+        let mut outside_cone = 0;
+
+        for (vhdl_file, entities, contributors) in &parsed {
+            if entities.is_empty() && merged_files.contains(vhdl_file) {
+                results.push(format!("↳ {}: merged into their entities' modules", vhdl_file.display()));
+                file_reports[index_of(vhdl_file)].merged();
+                self.report_progress(&mut done, vhdl_files.len(), vhdl_file, &file_reports[index_of(vhdl_file)]);
+                continue;
+            }
+
+            let entities: Vec<Entity> = match &cone {
+                Some(cone) => entities.iter().filter(|e| cone.iter().any(|name| name.eq_ignore_ascii_case(&e.name))).cloned().collect(),
+                None => entities.clone(),
+            };
+            if entities.is_empty() && cone.is_some() {
+                outside_cone += 1;
+                self.report_progress(&mut done, vhdl_files.len(), vhdl_file, &file_reports[index_of(vhdl_file)]);
+                continue;
+            }
+
+            let (entities, skipped) = match &entity_filter {
+                Some(filter) => filter.select(entities),
+                None => (entities, Vec::new()),
+            };
+            if entities.is_empty() && !skipped.is_empty() {
+                results.push(format!("- {}: skipped, no entity matches the filter ({})", vhdl_file.display(), skipped.join(", ")));
+                self.report_progress(&mut done, vhdl_files.len(), vhdl_file, &file_reports[index_of(vhdl_file)]);
+                continue;
+            }
+
             tracing::info!("Transpiling: {}", vhdl_file.display());
 
-            match self.transpile_file(vhdl_file, output_path) {
+            let transpile_started = Instant::now();
+            let transpile_result = self.transpile_file(vhdl_file, &entities, output_path, stub_only, generate_testbench, &types);
+            let file_report = &mut file_reports[index_of(vhdl_file)];
+            file_report.add_duration(transpile_started.elapsed());
+            match transpile_result {
                 Ok((input, output)) => {
                     results.push(format!("✓ {} -> {}", input, output));
+                    file_report.transpiled(output);
+                    for contributor in contributors {
+                        results.push(format!("    {}", contributor));
+                    }
+                    if !skipped.is_empty() {
+                        results.push(format!("    skipped: {}", skipped.join(", ")));
+                    }
                     success_count += 1;
                 }
                 Err(e) => {
                     let error_msg = format!("✗ {}: {}", vhdl_file.display(), e);
                     errors.push(error_msg.clone());
+                    file_report.fail(e.to_string());
                     tracing::error!("{}", error_msg);
                 }
             }
+            self.report_progress(&mut done, vhdl_files.len(), vhdl_file, &file_reports[index_of(vhdl_file)]);
         }
 
         // Build summary report
@@ -235,9 +554,33 @@ impl Tool for TranspileFolderTool {
         report.push_str(&format!("Output folder: {}\n", output_folder));
         report.push_str(&format!("Recursive:     {}\n\n", recursive));
         report.push_str(&format!("Total files found:      {}\n", vhdl_files.len()));
+        report.push_str(&format!("Package types:          {}\n", packages.len()));
         report.push_str(&format!("Successfully transpiled: {}\n", success_count));
         report.push_str(&format!("Failed:                 {}\n\n", errors.len()));
 
+        if let (Some(top), Some(tree), Some(cone)) = (top_entity, &dependency_tree, &cone) {
+            report.push_str(&format!(
+                "Top entity: {} ({} entities, {} files outside its dependency cone not transpiled)\n\n",
+                top, cone.len(), outside_cone
+            ));
+            report.push_str("=== Dependency Tree ===\n");
+            report.push_str(&tree.summary());
+            report.push('\n');
+
+            let unresolved = tree.unresolved_instances();
+            if !unresolved.is_empty() {
+                report.push_str("=== Warnings ===\n");
+                for instance in unresolved {
+                    report.push_str(&format!("⚠ unresolved instance {} has no entity in the folder\n", instance));
+                }
+                report.push('\n');
+            }
+        }
+
+        if let Some(warning) = precheck_warning {
+            report.push_str(&format!("Pre-check warning: {}\n\n", warning));
+        }
+
         if !results.is_empty() {
             report.push_str("=== Successful Transpilations ===\n");
             for result in results {
@@ -254,6 +597,22 @@ impl Tool for TranspileFolderTool {
             report.push('\n');
         }
 
+        if let Some(report_file) = report_file {
+            let batch = BatchReport {
+                vhdl_folder: vhdl_folder.to_string(),
+                output_folder: output_folder.to_string(),
+                duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+                files: file_reports,
+            };
+            let contents = match report_format {
+                "json" => batch.to_json()?,
+                "junit" => batch.to_junit(),
+                _ => format!("{}=== Transpilation Complete ===\n", report),
+            };
+            fs::write(report_file, contents).context(format!("Failed to write report to: {}", report_file))?;
+            report.push_str(&format!("Report ({}) written to: {}\n\n", report_format, report_file));
+        }
+
         report.push_str(&format!("=== Transpilation Complete ===\n"));
 
         Ok(report)
@@ -266,6 +625,66 @@ mod tests {
     use tempfile::TempDir;
     use std::io::Write;
 
+    #[test]
+    fn test_function_schema_snapshot() {
+        let function = TranspileFolderTool::new(vec![]).to_openai_function();
+        assert_eq!(function["name"], "transpile_vhdl_folder_to_systemverilog");
+        assert_eq!(function["parameters"], serde_json::json!({
+            "type": "object",
+            "properties": {
+                "vhdl_folder": {
+                    "type": "string",
+                    "description": "Path to the folder containing VHDL files to transpile"
+                },
+                "output_folder": {
+                    "type": "string",
+                    "description": "Path to the output folder for SystemVerilog files (optional, defaults to same folder)"
+                },
+                "recursive": {
+                    "type": "boolean",
+                    "description": "Whether to recursively process subdirectories (default: false)",
+                    "default": false
+                },
+                "stub_only": {
+                    "type": "boolean",
+                    "description": "Emit black-box module stubs with ports only, skipping architectures (default: false)",
+                    "default": false
+                },
+                "generate_testbench": {
+                    "type": "boolean",
+                    "description": "Also emit a <entity>_tb.sv testbench skeleton for each entity (default: false)",
+                    "default": false
+                },
+                "precheck": {
+                    "type": "string",
+                    "description": "Semantic check before transpiling: \"ghdl\" runs ghdl -s on all files and skips those with errors, skipped with a warning if ghdl is missing (default: \"none\")",
+                    "default": "none",
+                    "enum": ["ghdl", "none"]
+                },
+                "top_entity": {
+                    "type": "string",
+                    "description": "Only transpile this entity and the entities it instantiates, directly or not, leaves first (optional, defaults to every file)"
+                },
+                "entities": {
+                    "type": "array",
+                    "description": "Only generate these entities, by name or glob such as \"axi_*\"; files without a match are skipped (optional, defaults to all)",
+                    "items": { "type": "string" }
+                },
+                "report_format": {
+                    "type": "string",
+                    "description": "Format of the per-file report written to report_file: \"text\" (the summary), \"json\" or \"junit\" XML (default: \"text\")",
+                    "default": "text",
+                    "enum": ["text", "json", "junit"]
+                },
+                "report_file": {
+                    "type": "string",
+                    "description": "Path to write the report to (required for json and junit)"
+                }
+            },
+            "required": ["vhdl_folder"]
+        }));
+    }
+
     #[test]
     fn test_transpile_folder_tool() {
         // Create temp directory with VHDL files
@@ -316,4 +735,216 @@ mod tests {
         assert!(vhdl_folder.join("counter.sv").exists());
         assert!(vhdl_folder.join("buffer.sv").exists());
     }
+    /// Progress events as (current, total, file name, status)
+    #[derive(Default)]
+    struct ProgressRecorder(std::sync::Mutex<Vec<(usize, usize, String, crate::tools::FileStatus)>>);
+
+    impl ProgressCallback for ProgressRecorder {
+        fn on_progress(&self, current: usize, total: usize, current_file: &str, result: &crate::tools::FileTranspileResult) {
+            let name = Path::new(current_file).file_name().unwrap().to_string_lossy().into_owned();
+            self.0.lock().unwrap().push((current, total, name, result.status));
+        }
+    }
+
+    #[test]
+    fn test_progress_callback_reports_each_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let names = ["alu", "counter", "fifo", "mux", "uart"];
+        for name in names {
+            let vhdl = format!("entity {} is\n    port(a : in std_logic; y : out std_logic);\nend entity {};\n", name, name);
+            fs::write(temp_dir.path().join(format!("{}.vhd", name)), vhdl).unwrap();
+        }
+
+        let recorder = std::sync::Arc::new(ProgressRecorder::default());
+        let tool = TranspileFolderTool::new(vec![]).with_progress_callback(Box::new(recorder.clone()));
+        tool.execute(&serde_json::json!({ "vhdl_folder": temp_dir.path().to_str().unwrap() })).unwrap();
+
+        let events = recorder.0.lock().unwrap();
+        let expected: Vec<(usize, usize, String)> = names.iter().enumerate()
+            .map(|(i, name)| (i + 1, 5, format!("{}.vhd", name)))
+            .collect();
+        let reported: Vec<(usize, usize, String)> = events.iter().map(|(c, t, f, _)| (*c, *t, f.clone())).collect();
+        assert_eq!(reported, expected);
+        assert!(events.iter().all(|(_, _, _, status)| *status == crate::tools::FileStatus::Transpiled));
+    }
+
+    #[test]
+    fn test_entity_uses_type_from_package_file() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("types_pkg.vhd"), "\
+package types_pkg is
+    subtype word_t is std_logic_vector(15 downto 0);
+end package types_pkg;
+").unwrap();
+        fs::write(temp_dir.path().join("reg.vhd"), "\
+use work.types_pkg.all;
+
+entity word_reg is
+    port(
+        d : in  word_t;
+        q : out word_t
+    );
+end entity word_reg;
+").unwrap();
+
+        let tool = TranspileFolderTool::new(vec![]);
+        let result = tool.execute(&serde_json::json!({ "vhdl_folder": temp_dir.path().to_str().unwrap() })).unwrap();
+        assert!(result.contains("Package types:          1"));
+
+        let sv = fs::read_to_string(temp_dir.path().join("reg.sv")).unwrap();
+        assert!(sv.contains("input  logic [15:0] d"));
+        assert!(!sv.contains("word_t"));
+    }
+
+    #[test]
+    fn test_architecture_in_separate_file() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/split");
+        let output = TempDir::new().unwrap();
+
+        let tool = TranspileFolderTool::new(vec![]);
+        let result = tool.execute(&serde_json::json!({
+            "vhdl_folder": fixtures.to_str().unwrap(),
+            "output_folder": output.path().to_str().unwrap()
+        })).unwrap();
+        assert!(result.contains("Successfully transpiled: 1"));
+        assert!(result.contains("Failed:                 0"));
+        assert!(result.contains(&format!(
+            "    blinker: {}, {}",
+            fixtures.join("blinker_entity.vhd").display(),
+            fixtures.join("blinker_rtl.vhd").display()
+        )));
+
+        let sv = fs::read_to_string(output.path().join("blinker_entity.sv")).unwrap();
+        assert_eq!(sv.matches("module blinker").count(), 1);
+        assert!(sv.contains("always_ff @(posedge clk or posedge reset)"));
+        assert!(!output.path().join("blinker_rtl.sv").exists());
+    }
+
+    #[test]
+    fn test_machine_readable_reports() {
+        let folder = TempDir::new().unwrap();
+        fs::write(
+            folder.path().join("inverter.vhd"),
+            "entity inverter is port(a : in std_logic; y : out std_logic); end entity inverter;",
+        ).unwrap();
+        fs::write(folder.path().join("broken.vhd"), "-- no design units here\n").unwrap();
+        let tool = TranspileFolderTool::new(vec![]);
+        let run = |format: &str| {
+            let report_file = folder.path().join(format!("report.{}", format));
+            let result = tool.execute(&serde_json::json!({
+                "vhdl_folder": folder.path().to_str().unwrap(),
+                "report_format": format,
+                "report_file": report_file.to_str().unwrap()
+            })).unwrap();
+            assert!(result.contains(&format!("Report ({}) written to: {}", format, report_file.display())));
+            fs::read_to_string(report_file).unwrap()
+        };
+
+        let json: serde_json::Value = serde_json::from_str(&run("json")).unwrap();
+        let mut files: Vec<&serde_json::Value> = json["files"].as_array().unwrap().iter().collect();
+        files.sort_by_key(|f| f["file"].as_str().unwrap().to_string());
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0]["status"], "failed");
+        assert_eq!(files[0]["diagnostics"].as_array().unwrap().len(), 1);
+        assert!(files[0]["output"].is_null());
+        assert_eq!(files[1]["status"], "transpiled");
+        assert!(files[1]["output"].as_str().unwrap().ends_with("inverter.sv"));
+        assert!(files[1]["duration_ms"].as_f64().unwrap() >= 0.0);
+
+        let junit = run("junit");
+        let mut reader = quick_xml::Reader::from_str(&junit);
+        let (mut testcases, mut failures) = (0, 0);
+        loop {
+            match reader.read_event().unwrap() {
+                quick_xml::events::Event::Start(e) | quick_xml::events::Event::Empty(e) => match e.name().as_ref() {
+                    b"testcase" => testcases += 1,
+                    b"failure" => failures += 1,
+                    _ => {}
+                },
+                quick_xml::events::Event::Eof => break,
+                _ => {}
+            }
+        }
+        assert_eq!((testcases, failures), (2, 1));
+
+        let error = tool.execute(&serde_json::json!({
+            "vhdl_folder": folder.path().to_str().unwrap(),
+            "report_format": "junit"
+        })).unwrap_err();
+        assert_eq!(error.to_string(), "report_format 'junit' requires a report_file");
+    }
+
+    #[test]
+    fn test_entity_glob_filter_across_folder() {
+        let folder = TempDir::new().unwrap();
+        fs::write(
+            folder.path().join("axi_regs.vhd"),
+            "entity axi_regs is port(clk : in std_logic); end entity axi_regs;\nentity regs_model is end entity regs_model;",
+        ).unwrap();
+        fs::write(folder.path().join("axi_dma.vhd"), "entity AXI_DMA is port(clk : in std_logic); end entity AXI_DMA;").unwrap();
+        fs::write(folder.path().join("uart_tb.vhd"), "entity uart_tb is end entity uart_tb;").unwrap();
+        let tool = TranspileFolderTool::new(vec![]);
+
+        let result = tool.execute(&serde_json::json!({
+            "vhdl_folder": folder.path().to_str().unwrap(),
+            "entities": ["axi_*"]
+        })).unwrap();
+        assert!(result.contains("Successfully transpiled: 2"));
+        assert!(result.contains("Failed:                 0"));
+        assert!(result.contains("    skipped: regs_model"));
+        assert!(result.contains(&format!("- {}: skipped, no entity matches the filter (uart_tb)", folder.path().join("uart_tb.vhd").display())));
+        let regs = fs::read_to_string(folder.path().join("axi_regs.sv")).unwrap();
+        assert!(regs.contains("module axi_regs") && !regs.contains("module regs_model"));
+        assert!(!folder.path().join("uart_tb.sv").exists());
+
+        let err = tool.execute(&serde_json::json!({
+            "vhdl_folder": folder.path().to_str().unwrap(),
+            "entities": ["spi_*"]
+        })).unwrap_err();
+        assert!(err.to_string().starts_with("Entity filter 'spi_*' matches none of the entities in"));
+    }
+
+    #[test]
+    fn test_top_entity_dependency_cone() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/top_down");
+        let output = TempDir::new().unwrap();
+        let tool = TranspileFolderTool::new(vec![]);
+
+        let result = tool.execute(&serde_json::json!({
+            "vhdl_folder": fixtures.to_str().unwrap(),
+            "output_folder": output.path().to_str().unwrap(),
+            "top_entity": "top_ctrl"
+        })).unwrap();
+        assert!(result.contains("Successfully transpiled: 3"));
+        assert!(result.contains("Top entity: top_ctrl (3 entities, 1 files outside its dependency cone not transpiled)"));
+        assert!(result.contains(
+            "=== Dependency Tree ===\ntop_ctrl (3 ports)\n  u_regs: reg_bank (3 ports)\n  u_uart: uart_tx (3 ports)\n  u_debug: debug_core (not found)\n"
+        ));
+        assert!(result.contains("⚠ unresolved instance top_ctrl/u_debug: debug_core has no entity in the folder"));
+
+        // Leaves first
+        let transpiled: Vec<&str> = result.lines().filter(|line| line.starts_with("✓ ")).collect();
+        assert!(transpiled[2].contains("top_ctrl.vhd"));
+        assert!(output.path().join("uart_tx.sv").exists());
+        assert!(!output.path().join("legacy_dma.sv").exists());
+    }
+
+    #[test]
+    fn test_top_entity_instantiation_cycle() {
+        let folder = TempDir::new().unwrap();
+        fs::write(
+            folder.path().join("ping.vhd"),
+            "entity ping is end entity ping;\narchitecture rtl of ping is\nbegin\n    u_pong : entity work.pong;\nend architecture rtl;\n",
+        ).unwrap();
+        fs::write(
+            folder.path().join("pong.vhd"),
+            "entity pong is end entity pong;\narchitecture rtl of pong is\nbegin\n    u_ping : entity work.ping;\nend architecture rtl;\n",
+        ).unwrap();
+
+        let err = TranspileFolderTool::new(vec![]).execute(&serde_json::json!({
+            "vhdl_folder": folder.path().to_str().unwrap(),
+            "top_entity": "ping"
+        })).unwrap_err();
+        assert_eq!(format!("{:#}", err), "Failed to trace the dependencies of top entity 'ping': Instantiation cycle: ping -> pong -> ping");
+    }
 }
@@ -1,8 +1,9 @@
 use anyhow::{Context, Result};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::parser::ASTVHDLParser;
-use crate::tools::{BaseToolImpl, Tool, ToolParameter, ToolSchema};
+use crate::ir::{analyze_clocking, build_hierarchy};
+use crate::parser::{analyze_imports, ASTVHDLParser};
+use crate::tools::{path_within, BaseToolImpl, Tool, ToolParameter, ToolSchema};
 
 /// Tool for analyzing VHDL files and extracting information
 pub struct VHDLAnalyzeTool {
@@ -16,22 +17,41 @@ impl VHDLAnalyzeTool {
             ToolParameter {
                 name: "vhdl_file".to_string(),
                 param_type: "string".to_string(),
-                description: "Path to the VHDL file to analyze".to_string(),
-                required: true,
+                description: "Path to the VHDL file to analyze (all analysis types except 'hierarchy')".to_string(),
+                required: false,
                 default: None,
+                ..Default::default()
             },
             ToolParameter {
                 name: "analysis_type".to_string(),
                 param_type: "string".to_string(),
-                description: "Type of analysis: 'entities', 'ports', 'signals', 'processes', or 'all'".to_string(),
+                description: "Type of analysis: 'entities', 'ports', 'signals', 'processes', 'clocking', 'stimulus_patterns', 'imports', 'hierarchy', or 'all'".to_string(),
                 required: false,
                 default: Some(serde_json::json!("all")),
+                ..Default::default()
+            }
+            .with_enum_values(["entities", "ports", "signals", "processes", "clocking", "stimulus_patterns", "imports", "hierarchy", "all"]),
+            ToolParameter {
+                name: "vhdl_folder".to_string(),
+                param_type: "string".to_string(),
+                description: "Folder whose .vhd/.vhdl files (recursively) are searched for instantiated entities ('hierarchy' only)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "top_entity".to_string(),
+                param_type: "string".to_string(),
+                description: "Entity at the root of the hierarchy ('hierarchy' only)".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
             },
         ];
 
         let base = BaseToolImpl::new(
             "analyze_vhdl".to_string(),
-            "Analyze VHDL files to extract entities, ports, signals, processes, and other structural information.".to_string(),
+            "Analyze VHDL files to extract entities, ports, signals, processes, and other structural information, or trace the instantiation hierarchy of a project folder.".to_string(),
             parameters,
         );
 
@@ -41,28 +61,70 @@ impl VHDLAnalyzeTool {
         }
     }
 
-    fn is_path_allowed(&self, path: &Path) -> bool {
-        if self.allowed_folders.is_empty() {
-            return true;
-        }
+    /// Instantiation tree below `top_entity`, as an indented summary followed by JSON
+    fn analyze_hierarchy(&self, arguments: &serde_json::Value) -> Result<String> {
+        let vhdl_folder = arguments
+            .get("vhdl_folder")
+            .and_then(|v| v.as_str())
+            .context("Missing 'vhdl_folder' argument")?;
+
+        let top_entity = arguments
+            .get("top_entity")
+            .and_then(|v| v.as_str())
+            .context("Missing 'top_entity' argument")?;
 
-        let canonical_path = match path.canonicalize() {
-            Ok(p) => p,
-            Err(_) => return false,
-        };
+        let folder = Path::new(vhdl_folder);
+        if !path_within(&self.allowed_folders, folder) {
+            return Err(anyhow::anyhow!(
+                "Access denied: '{}' is not in allowed folders",
+                vhdl_folder
+            ));
+        }
+        if !folder.is_dir() {
+            return Err(anyhow::anyhow!("'{}' is not a directory", vhdl_folder));
+        }
 
-        for allowed in &self.allowed_folders {
-            let allowed_path = match Path::new(allowed).canonicalize() {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
+        let mut vhdl_files: Vec<PathBuf> = walkdir::WalkDir::new(folder)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                path.is_file() && path.extension()
+                    .map(|ext| matches!(ext.to_string_lossy().to_lowercase().as_str(), "vhd" | "vhdl"))
+                    .unwrap_or(false)
+            })
+            .collect();
+        vhdl_files.sort();
 
-            if canonical_path.starts_with(&allowed_path) {
-                return true;
+        let mut entities = Vec::new();
+        let mut skipped = String::new();
+        for path in &vhdl_files {
+            let parsed = ASTVHDLParser::from_file(path).and_then(|mut parser| parser.parse_entities());
+            match parsed {
+                Ok(parsed) => entities.extend(parsed),
+                Err(e) => {
+                    tracing::warn!("Skipping {}: {:#}", path.display(), e);
+                    skipped.push_str(&format!("\n  {}: {:#}", path.display(), e));
+                }
             }
         }
 
-        false
+        let tree = build_hierarchy(&entities, top_entity)
+            .context(format!("Failed to build hierarchy from: {}", vhdl_folder))?;
+
+        let mut result = format!(
+            "Design hierarchy for '{}' ({} entities in {} files):\n\n{}",
+            top_entity,
+            entities.len(),
+            vhdl_files.len(),
+            tree.summary()
+        );
+        if !skipped.is_empty() {
+            result.push_str(&format!("\nSkipped files:{}\n", skipped));
+        }
+        result.push_str(&format!("\nJSON:\n{}\n", serde_json::to_string_pretty(&tree)?));
+
+        Ok(result)
     }
 }
 
@@ -80,31 +142,55 @@ impl Tool for VHDLAnalyzeTool {
     }
 
     fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
-        let vhdl_file = arguments
-            .get("vhdl_file")
-            .and_then(|v| v.as_str())
-            .context("Missing 'vhdl_file' argument")?;
-
         let analysis_type = arguments
             .get("analysis_type")
             .and_then(|v| v.as_str())
             .unwrap_or("all");
 
+        if analysis_type == "hierarchy" {
+            return self.analyze_hierarchy(arguments);
+        }
+
+        let vhdl_file = arguments
+            .get("vhdl_file")
+            .and_then(|v| v.as_str())
+            .context("Missing 'vhdl_file' argument")?;
+
         let vhdl_path = Path::new(vhdl_file);
 
         // Check if path is allowed
-        if !self.is_path_allowed(vhdl_path) {
+        if !path_within(&self.allowed_folders, vhdl_path) {
             return Err(anyhow::anyhow!(
                 "Access denied: '{}' is not in allowed folders",
                 vhdl_file
             ));
         }
 
+        if analysis_type == "imports" {
+            let source = std::fs::read_to_string(vhdl_path)
+                .context(format!("Failed to read VHDL file: {}", vhdl_file))?;
+            return Ok(serde_json::to_string_pretty(&analyze_imports(&source))?);
+        }
+
         // Parse VHDL using AST parser
         tracing::info!("Analyzing VHDL file: {}", vhdl_file);
         let mut parser = ASTVHDLParser::from_file(vhdl_path)
             .context(format!("Failed to parse VHDL file: {}", vhdl_file))?;
 
+        if analysis_type == "stimulus_patterns" {
+            let patterns = parser.stimulus_patterns()
+                .context("Failed to extract stimulus patterns from VHDL")?;
+            if patterns.is_empty() {
+                return Ok("No constant array stimulus patterns found".to_string());
+            }
+            let mut result = format!("Stimulus Patterns ({}):\n\n", patterns.len());
+            for pattern in &patterns {
+                result.push_str(&pattern.waveform_table());
+                result.push('\n');
+            }
+            return Ok(result);
+        }
+
         let entities = parser.parse_entities()
             .context("Failed to extract entities from VHDL")?;
 
@@ -187,6 +273,24 @@ impl Tool for VHDLAnalyzeTool {
                     result.push('\n');
                 }
             }
+            "clocking" => {
+                result.push_str("Clocking Analysis:\n\n");
+                let clocking: Vec<_> = entities.iter().map(analyze_clocking).collect();
+                for entity in &clocking {
+                    result.push_str(&format!("Entity: {}\n", entity.entity));
+                    if entity.processes.is_empty() {
+                        result.push_str("  No processes\n");
+                    }
+                    for process in &entity.processes {
+                        result.push_str(&format!("  Process: {}: {}\n", process.process, process.summary()));
+                        for warning in &process.warnings {
+                            result.push_str(&format!("    Warning: {}\n", warning));
+                        }
+                    }
+                    result.push('\n');
+                }
+                result.push_str(&format!("JSON:\n{}\n", serde_json::to_string_pretty(&clocking)?));
+            }
             "all" | _ => {
                 result.push_str(&format!("Complete VHDL Analysis for: {}\n", vhdl_file));
                 result.push_str(&format!("Found {} entities\n\n", entities.len()));
@@ -245,6 +349,39 @@ mod tests {
     use tempfile::NamedTempFile;
     use std::io::Write;
 
+    #[test]
+    fn test_function_schema_snapshot() {
+        let function = VHDLAnalyzeTool::new(vec![]).to_openai_function();
+        assert_eq!(function, serde_json::json!({
+            "name": "analyze_vhdl",
+            "description": "Analyze VHDL files to extract entities, ports, signals, processes, and other structural information, or trace the instantiation hierarchy of a project folder.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "vhdl_file": {
+                        "type": "string",
+                        "description": "Path to the VHDL file to analyze (all analysis types except 'hierarchy')"
+                    },
+                    "analysis_type": {
+                        "type": "string",
+                        "description": "Type of analysis: 'entities', 'ports', 'signals', 'processes', 'clocking', 'stimulus_patterns', 'imports', 'hierarchy', or 'all'",
+                        "enum": ["entities", "ports", "signals", "processes", "clocking", "stimulus_patterns", "imports", "hierarchy", "all"],
+                        "default": "all"
+                    },
+                    "vhdl_folder": {
+                        "type": "string",
+                        "description": "Folder whose .vhd/.vhdl files (recursively) are searched for instantiated entities ('hierarchy' only)"
+                    },
+                    "top_entity": {
+                        "type": "string",
+                        "description": "Entity at the root of the hierarchy ('hierarchy' only)"
+                    }
+                },
+                "required": []
+            }
+        }));
+    }
+
     #[test]
     fn test_vhdl_analyze_tool() {
         let vhdl_content = r#"
@@ -278,4 +415,155 @@ mod tests {
         assert!(result.contains("Entity: counter"));
         assert!(result.contains("Ports: 3"));
     }
+
+    #[test]
+    fn test_stimulus_patterns_analysis() {
+        let mut vhdl_file = NamedTempFile::new().unwrap();
+        vhdl_file.write_all(br#"
+        entity tb_alu is
+        end entity tb_alu;
+        architecture sim of tb_alu is
+            type stim_array is array (0 to 3) of std_logic_vector(3 downto 0);
+            constant STIM : stim_array := (X"0", X"F", "1010", "0101");
+            constant WIDTH : integer := 4;
+        begin
+        end architecture sim;
+        "#).unwrap();
+
+        let result = VHDLAnalyzeTool::new(vec![]).execute(&serde_json::json!({
+            "vhdl_file": vhdl_file.path().to_str().unwrap(),
+            "analysis_type": "stimulus_patterns"
+        })).unwrap();
+        assert!(result.starts_with("Stimulus Patterns (1):\n\nSTIM : stim_array (4 steps x 4 bits)\n"));
+        assert!(result.contains("     2 | 0xA | 1010\n"));
+    }
+
+    #[test]
+    fn test_clocking_analysis() {
+        let mut vhdl_file = NamedTempFile::new().unwrap();
+        vhdl_file.write_all(br#"
+        entity regs is
+            port(clk, rst_n, srst, d, a, b, sel : in std_logic; q, r, y : out std_logic);
+        end entity regs;
+        architecture rtl of regs is
+        begin
+            p_async : process(clk, rst_n)
+            begin
+                if rst_n = '0' then
+                    q <= '0';
+                elsif rising_edge(clk) then
+                    q <= d;
+                end if;
+            end process;
+
+            p_sync : process(clk)
+            begin
+                if rising_edge(clk) then
+                    if srst = '1' then
+                        r <= '0';
+                    else
+                        r <= d;
+                    end if;
+                end if;
+            end process;
+
+            p_comb : process(a, b, sel)
+            begin
+                if sel = '1' then
+                    y <= a;
+                else
+                    y <= b;
+                end if;
+            end process;
+        end architecture rtl;
+        "#).unwrap();
+
+        let tool = VHDLAnalyzeTool::new(vec![]);
+        let result = tool.execute(&serde_json::json!({
+            "vhdl_file": vhdl_file.path().to_str().unwrap(),
+            "analysis_type": "clocking"
+        })).unwrap();
+
+        assert!(result.contains("Process: p_async: clock clk (rising), async reset rst_n (active low)\n"));
+        assert!(result.contains("Process: p_sync: clock clk (rising), sync reset srst (active high)\n"));
+        assert!(result.contains("Process: p_comb: combinational\n"));
+        assert!(result.contains("\"style\": \"async\""));
+    }
+
+    #[test]
+    fn test_imports_analysis() {
+        let mut vhdl_file = NamedTempFile::new().unwrap();
+        vhdl_file.write_all(br#"
+        library ieee;
+        use ieee.std_logic_1164.all;
+        use ieee.std_logic_unsigned.all;
+        entity inc is
+            port(a : in std_logic_vector(3 downto 0); y : out std_logic_vector(3 downto 0));
+        end entity inc;
+        architecture rtl of inc is
+        begin
+            y <= std_logic_vector(to_unsigned(to_integer(unsigned(a)) + 1, 4));
+        end architecture rtl;
+        "#).unwrap();
+
+        let result = VHDLAnalyzeTool::new(vec![]).execute(&serde_json::json!({
+            "vhdl_file": vhdl_file.path().to_str().unwrap(),
+            "analysis_type": "imports"
+        })).unwrap();
+        let imports: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(imports["libraries"], serde_json::json!(["ieee"]));
+        assert_eq!(imports["uses"][1], serde_json::json!({
+            "library": "ieee", "package": "std_logic_unsigned", "items": "all"
+        }));
+        assert_eq!(imports["warnings"], serde_json::json!([
+            "std_logic_unsigned is obsolete, use ieee.numeric_std instead",
+            "to_unsigned, to_integer, unsigned used without 'use ieee.numeric_std.all'"
+        ]));
+    }
+
+    #[test]
+    fn test_hierarchy_across_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("half_adder.vhd"), r#"
+        entity half_adder is
+            port(a, b : in std_logic; s, c : out std_logic);
+        end entity half_adder;
+        architecture rtl of half_adder is
+        begin
+            s <= a xor b;
+            c <= a and b;
+        end architecture rtl;
+        "#).unwrap();
+        std::fs::write(dir.path().join("full_adder.vhd"), r#"
+        entity full_adder is
+            port(a, b, cin : in std_logic; s, cout : out std_logic);
+        end entity full_adder;
+        architecture rtl of full_adder is
+            signal s1, c1, c2 : std_logic;
+        begin
+            ha0 : entity work.half_adder port map (a => a, b => b, s => s1, c => c1);
+            ha1 : entity work.half_adder port map (a => s1, b => cin, s => s, c => c2);
+            cout <= c1 or c2;
+        end architecture rtl;
+        "#).unwrap();
+        std::fs::write(dir.path().join("top.vhd"), r#"
+        entity top is
+            port(x, y, z : in std_logic);
+        end entity top;
+        architecture rtl of top is
+        begin
+            u_add : entity work.full_adder port map (a => x, b => y, cin => z, s => open, cout => open);
+        end architecture rtl;
+        "#).unwrap();
+
+        let tool = VHDLAnalyzeTool::new(vec![]);
+        let result = tool.execute(&serde_json::json!({
+            "analysis_type": "hierarchy",
+            "vhdl_folder": dir.path().to_str().unwrap(),
+            "top_entity": "top"
+        })).unwrap();
+
+        assert!(result.contains("top (3 ports)\n  u_add: full_adder (5 ports)\n    ha0: half_adder (4 ports)\n    ha1: half_adder (4 ports)\n"));
+        assert!(result.contains("\"name\": \"half_adder\""));
+    }
 }
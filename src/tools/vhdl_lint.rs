@@ -0,0 +1,519 @@
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tree_sitter::Node;
+
+use crate::parser::tree_sitter_vhdl::{TreeSitterVHDLParser, VHDLASTHelper};
+use crate::parser::ASTVHDLParser;
+use crate::tools::{path_within, BaseToolImpl, Tool, ToolParameter, ToolSchema};
+
+lazy_static! {
+    static ref ATTRIBUTE_RE: Regex = Regex::new(r"'\s*([A-Za-z]\w*)").unwrap();
+    static ref RESOLVED_SUBTYPE_RE: Regex = Regex::new(r"(?i)^\s*subtype\s+\w+\s+is\s+\w+\s+\w+\s*(;|\()").unwrap();
+    static ref SHARED_RE: Regex = Regex::new(r"(?i)^\s*shared\b").unwrap();
+    static ref TEXTIO_RE: Regex = Regex::new(r"(?i)\btextio\b").unwrap();
+}
+
+/// Rules flagging constructs the transpiler cannot convert faithfully
+pub const CONSTRUCT_RULES: &[&str] = &[
+    "syntax_error",
+    "shared_variable",
+    "configuration",
+    "physical_type",
+    "access_type",
+    "file_type",
+    "resolved_type",
+    "attribute",
+    "wait_in_process",
+    "multiple_wait_processes",
+    "textio",
+];
+
+/// Naming and layout rules that keep the generated Verilog readable
+pub const NAMING_RULES: &[&str] = &[
+    "port_naming",
+    "signal_naming",
+    "generic_naming",
+    "no_signal_in_port_name",
+    "process_labels_required",
+    "consistent_indentation",
+];
+
+/// Name segments with a conventional position at the end of a port name
+const ROLE_WORDS: &[&str] = &["clk", "clock", "rst", "reset", "data"];
+
+/// Polarity and direction segments allowed after the role word, e.g. `rst_n`, `rx_data_i`
+const TRAILING_SEGMENTS: &[&str] = &["n", "i", "o", "in", "out"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// The transpiler cannot convert the construct
+    Error,
+    /// The construct converts, but its meaning may change, or a naming rule is broken
+    Warning,
+}
+
+/// Rule violation at a line of a VHDL source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintViolation {
+    /// Rule that matched, e.g. "shared_variable" or "port_naming"
+    pub rule: String,
+    pub line: usize,
+    pub message: String,
+    pub severity: LintSeverity,
+}
+
+impl LintViolation {
+    fn new(line: usize, severity: LintSeverity, rule: &str, message: String) -> Self {
+        Self { rule: rule.to_string(), line, message, severity }
+    }
+
+    fn at(node: &Node, severity: LintSeverity, rule: &str, message: String) -> Self {
+        Self::new(node.start_position().row + 1, severity, rule, message)
+    }
+}
+
+/// Violations of every rule, sorted by line
+pub fn lint_vhdl(source: &str) -> Result<Vec<LintViolation>> {
+    let rules: Vec<&str> = CONSTRUCT_RULES.iter().chain(NAMING_RULES).copied().collect();
+    lint_vhdl_with_rules(source, &rules)
+}
+
+/// Violations of the given rules, sorted by line; construct rules scan tree-sitter node
+/// kinds, naming rules check the parsed entities
+pub fn lint_vhdl_with_rules(source: &str, rules: &[&str]) -> Result<Vec<LintViolation>> {
+    if let Some(unknown) = rules.iter().find(|rule| !CONSTRUCT_RULES.contains(rule) && !NAMING_RULES.contains(rule)) {
+        return Err(anyhow::anyhow!(
+            "Unknown lint rule '{}'. Available: {}",
+            unknown,
+            CONSTRUCT_RULES.iter().chain(NAMING_RULES).copied().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    let mut violations = construct_violations(source)?;
+    if rules.iter().any(|rule| NAMING_RULES.contains(rule)) {
+        violations.extend(naming_violations(source));
+    }
+    violations.retain(|v| rules.contains(&v.rule.as_str()));
+
+    violations.sort_by(|a, b| (a.line, &a.rule).cmp(&(b.line, &b.rule)));
+    violations.dedup_by(|a, b| a.line == b.line && a.rule == b.rule);
+    // Report only the first syntax error; later ones are usually follow-on noise
+    let mut syntax_errors = 0;
+    violations.retain(|v| v.rule != "syntax_error" || { syntax_errors += 1; syntax_errors == 1 });
+    Ok(violations)
+}
+
+fn construct_violations(source: &str) -> Result<Vec<LintViolation>> {
+    let mut parser = TreeSitterVHDLParser::new()?;
+    let tree = parser.parse(source)?;
+    let root = tree.root_node();
+
+    let mut violations = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if let Some(violation) = lint_node(&node, source) {
+            violations.push(violation);
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+
+    for arch in VHDLASTHelper::find_all_nodes_by_type(&root, "architecture_body") {
+        let wait_processes: Vec<Node> = VHDLASTHelper::find_all_nodes_by_type(&arch, "process_statement")
+            .into_iter()
+            .filter(|process| !VHDLASTHelper::find_all_nodes_by_type(process, "wait_statement").is_empty())
+            .collect();
+        for process in wait_processes.iter().skip(1) {
+            violations.push(LintViolation::at(
+                process,
+                LintSeverity::Warning,
+                "multiple_wait_processes",
+                format!("{} processes in this architecture use wait statements; their relative timing is not preserved", wait_processes.len()),
+            ));
+        }
+    }
+    Ok(violations)
+}
+
+/// Naming rules need parsed entities; a source that does not parse only gets the
+/// indentation check (its syntax error is reported by the construct rules)
+fn naming_violations(source: &str) -> Vec<LintViolation> {
+    let mut violations: Vec<LintViolation> = indentation_violation(source).into_iter().collect();
+    let Ok(entities) = ASTVHDLParser::new(source.to_string()).and_then(|mut parser| parser.parse_entities()) else {
+        return violations;
+    };
+    let warning = |line: Option<usize>, rule: &str, message: String| {
+        LintViolation::new(line.unwrap_or(0), LintSeverity::Warning, rule, message)
+    };
+
+    for entity in &entities {
+        for generic in &entity.generics {
+            if generic.name != generic.name.to_uppercase() {
+                violations.push(warning(
+                    declaration_line(source, &generic.name),
+                    "generic_naming",
+                    format!("generic {} should be uppercase ({})", generic.name, generic.name.to_uppercase()),
+                ));
+            }
+        }
+
+        for port in &entity.ports {
+            let segments: Vec<String> = port.name.to_lowercase().split('_').map(str::to_string).collect();
+            if segments.iter().any(|segment| segment == "signal" || segment == "sig") {
+                violations.push(warning(
+                    port.source_line,
+                    "no_signal_in_port_name",
+                    format!("port {} should not be named as a signal", port.name),
+                ));
+            }
+
+            let end = segments.iter().rposition(|segment| !TRAILING_SEGMENTS.contains(&segment.as_str())).unwrap_or(0);
+            if let Some(role) = segments[..end].iter().find(|segment| ROLE_WORDS.contains(&segment.as_str())) {
+                let mut suggestion: Vec<&str> = segments[..=end].iter()
+                    .map(String::as_str)
+                    .filter(|segment| segment != role)
+                    .collect();
+                suggestion.push(role);
+                suggestion.extend(segments[end + 1..].iter().map(String::as_str));
+                violations.push(warning(
+                    port.source_line,
+                    "port_naming",
+                    format!("port {} should end in {} (e.g. {})", port.name, role, suggestion.join("_")),
+                ));
+            }
+        }
+
+        let Some(arch) = &entity.architecture else {
+            continue;
+        };
+        for signal in &arch.signals {
+            let lower = signal.name.to_lowercase();
+            if !(lower.ends_with("_s") || lower.ends_with("_r")) {
+                violations.push(warning(
+                    signal.source_line,
+                    "signal_naming",
+                    format!("signal {} should end in _s (combinational) or _r (registered)", signal.name),
+                ));
+            }
+        }
+        for process in arch.processes.iter().filter(|process| process.label.is_none()) {
+            violations.push(warning(
+                process.source_line,
+                "process_labels_required",
+                "process has no label; generated always blocks are hard to trace back".to_string(),
+            ));
+        }
+    }
+    violations
+}
+
+/// First line indented with the other of tabs and spaces than the file's first indented line
+fn indentation_violation(source: &str) -> Option<LintViolation> {
+    let indents: Vec<(usize, &str)> = source.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, &line[..line.len() - line.trim_start().len()]))
+        .filter(|(_, indent)| !indent.is_empty())
+        .collect();
+    let uses_tabs = indents.first()?.1.starts_with('\t');
+
+    let inconsistent: Vec<usize> = indents.iter()
+        .filter(|(_, indent)| if uses_tabs { indent.contains(' ') } else { indent.contains('\t') })
+        .map(|(line, _)| *line)
+        .collect();
+    let (first, style) = (*inconsistent.first()?, if uses_tabs { "tabs" } else { "spaces" });
+    Some(LintViolation::new(
+        first,
+        LintSeverity::Warning,
+        "consistent_indentation",
+        format!("{} line(s) mix tabs and spaces; the file indents with {}", inconsistent.len(), style),
+    ))
+}
+
+/// Line of `name :` in a declaration, for IR items without a recorded line
+fn declaration_line(source: &str, name: &str) -> Option<usize> {
+    let pattern = Regex::new(&format!(r"(?i)\b{}\s*:", regex::escape(name))).ok()?;
+    source.lines().position(|line| pattern.is_match(line)).map(|i| i + 1)
+}
+
+fn lint_node(node: &Node, source: &str) -> Option<LintViolation> {
+    let text = VHDLASTHelper::node_text(node, source);
+    let finding = |severity, rule: &str, message: &str| {
+        Some(LintViolation::at(node, severity, rule, message.to_string()))
+    };
+
+    match node.kind() {
+        "ERROR" => finding(LintSeverity::Error, "syntax_error", "source does not parse; nothing past this point is checked reliably"),
+        "shared_variable_declaration" => {
+            finding(LintSeverity::Error, "shared_variable", "shared variables have no synthesizable Verilog equivalent")
+        }
+        "variable_declaration" if SHARED_RE.is_match(text) => {
+            finding(LintSeverity::Error, "shared_variable", "shared variables have no synthesizable Verilog equivalent")
+        }
+        "configuration_declaration" | "configuration_specification" => {
+            finding(LintSeverity::Error, "configuration", "configurations are ignored; the default binding is used")
+        }
+        "physical_type_definition" => {
+            finding(LintSeverity::Warning, "physical_type", "physical types are converted to plain integers and lose their units")
+        }
+        "access_type_definition" => {
+            finding(LintSeverity::Error, "access_type", "access types cannot be converted")
+        }
+        "file_type_definition" | "file_declaration" => {
+            finding(LintSeverity::Error, "file_type", "file types and file objects cannot be converted")
+        }
+        "resolution_function" | "resolution_indication" => {
+            finding(LintSeverity::Warning, "resolved_type", "custom resolution functions are dropped")
+        }
+        "subtype_declaration" if RESOLVED_SUBTYPE_RE.is_match(text) => {
+            finding(LintSeverity::Warning, "resolved_type", "custom resolution functions are dropped")
+        }
+        "attribute_declaration" | "attribute_specification" => {
+            finding(LintSeverity::Warning, "attribute", "user-defined attributes are dropped")
+        }
+        "attribute_name" => {
+            let attribute = ATTRIBUTE_RE.captures_iter(text).last()?[1].to_lowercase();
+            (attribute != "event").then(|| LintViolation::at(
+                node,
+                LintSeverity::Warning,
+                "attribute",
+                format!("attribute '{} may not be converted; only 'event is supported", attribute),
+            ))
+        }
+        "wait_statement" if has_ancestor(node, "process_statement") => {
+            finding(LintSeverity::Warning, "wait_in_process", "wait-based processes become initial blocks with delays in a 1ns timescale and are not synthesizable")
+        }
+        "use_clause" if TEXTIO_RE.is_match(text) => {
+            finding(LintSeverity::Error, "textio", "textio file I/O cannot be converted")
+        }
+        _ => None,
+    }
+}
+
+fn has_ancestor(node: &Node, kind: &str) -> bool {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        if parent.kind() == kind {
+            return true;
+        }
+        current = parent.parent();
+    }
+    false
+}
+
+/// Tool that reports VHDL constructs the transpiler cannot convert faithfully and
+/// naming-convention violations
+pub struct VHDLLintTool {
+    base: BaseToolImpl,
+    allowed_folders: Vec<String>,
+}
+
+impl VHDLLintTool {
+    pub fn new(allowed_folders: Vec<String>) -> Self {
+        let parameters = vec![
+            ToolParameter {
+                name: "vhdl_file".to_string(),
+                param_type: "string".to_string(),
+                description: "Path to the VHDL file to lint".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "vhdl_folder".to_string(),
+                param_type: "string".to_string(),
+                description: "Folder whose .vhd/.vhdl files (recursively) are linted, instead of vhdl_file".to_string(),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+            ToolParameter {
+                name: "rules".to_string(),
+                param_type: "array".to_string(),
+                description: format!(
+                    "Rules to check (default: all). Constructs: {}. Naming: {}",
+                    CONSTRUCT_RULES.join(", "),
+                    NAMING_RULES.join(", ")
+                ),
+                required: false,
+                default: None,
+                ..Default::default()
+            },
+        ];
+
+        let base = BaseToolImpl::new(
+            "lint_vhdl".to_string(),
+            "Report VHDL constructs the transpiler cannot convert faithfully (shared variables, configurations, physical/access/file types, resolved types, attributes other than 'event, wait statements in processes, textio) and naming-convention violations (port role suffixes, _s/_r signals, uppercase generics, process labels, indentation), with line numbers and severities.".to_string(),
+            parameters,
+        );
+
+        Self {
+            base,
+            allowed_folders,
+        }
+    }
+
+    fn vhdl_files(&self, arguments: &serde_json::Value) -> Result<Vec<PathBuf>> {
+        let file = arguments.get("vhdl_file").and_then(|v| v.as_str());
+        let folder = arguments.get("vhdl_folder").and_then(|v| v.as_str());
+
+        let (path, files) = match (file, folder) {
+            (Some(file), None) => (file, vec![PathBuf::from(file)]),
+            (None, Some(folder)) => {
+                if !Path::new(folder).is_dir() {
+                    return Err(anyhow::anyhow!("'{}' is not a directory", folder));
+                }
+                let mut files: Vec<PathBuf> = walkdir::WalkDir::new(folder)
+                    .into_iter()
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.into_path())
+                    .filter(|path| {
+                        path.is_file() && path.extension()
+                            .map(|ext| matches!(ext.to_string_lossy().to_lowercase().as_str(), "vhd" | "vhdl"))
+                            .unwrap_or(false)
+                    })
+                    .collect();
+                files.sort();
+                (folder, files)
+            }
+            _ => return Err(anyhow::anyhow!("Provide exactly one of 'vhdl_file' or 'vhdl_folder'")),
+        };
+
+        if !path_within(&self.allowed_folders, Path::new(path)) {
+            return Err(anyhow::anyhow!("Access denied: '{}' is not in allowed folders", path));
+        }
+        Ok(files)
+    }
+}
+
+impl Tool for VHDLLintTool {
+    fn name(&self) -> &str {
+        &self.base.name
+    }
+
+    fn description(&self) -> &str {
+        &self.base.description
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.base.schema.clone()
+    }
+
+    fn execute(&self, arguments: &serde_json::Value) -> Result<String> {
+        let files = self.vhdl_files(arguments)?;
+        let rules: Vec<&str> = match arguments.get("rules").and_then(|v| v.as_array()) {
+            Some(rules) => rules.iter().filter_map(|v| v.as_str()).collect(),
+            None => CONSTRUCT_RULES.iter().chain(NAMING_RULES).copied().collect(),
+        };
+
+        let mut result = String::new();
+        let mut report = serde_json::Map::new();
+        let (mut errors, mut warnings) = (0, 0);
+        for path in &files {
+            let source = std::fs::read_to_string(path)
+                .context(format!("Failed to read VHDL file: {}", path.display()))?;
+            let violations = lint_vhdl_with_rules(&source, &rules)
+                .context(format!("Failed to lint VHDL file: {}", path.display()))?;
+
+            for violation in &violations {
+                match violation.severity {
+                    LintSeverity::Error => errors += 1,
+                    LintSeverity::Warning => warnings += 1,
+                }
+                result.push_str(&format!(
+                    "{}:{}: {}: {} ({})\n",
+                    path.display(),
+                    violation.line,
+                    match violation.severity {
+                        LintSeverity::Error => "error",
+                        LintSeverity::Warning => "warning",
+                    },
+                    violation.message,
+                    violation.rule
+                ));
+            }
+            report.insert(path.display().to_string(), serde_json::to_value(&violations)?);
+        }
+
+        result.push_str(&format!(
+            "\nLinted {} file(s): {} error(s), {} warning(s)\n",
+            files.len(),
+            errors,
+            warnings
+        ));
+        result.push_str(&format!("\nJSON:\n{}\n", serde_json::to_string_pretty(&report)?));
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        std::fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)).unwrap()
+    }
+
+    #[test]
+    fn test_lint_fixture_findings() {
+        let findings = lint_vhdl_with_rules(&fixture("unsupported_constructs.vhd"), CONSTRUCT_RULES).unwrap();
+
+        let summary: Vec<(usize, LintSeverity, &str)> = findings.iter()
+            .map(|f| (f.line, f.severity, f.rule.as_str()))
+            .collect();
+        assert_eq!(summary, vec![
+            (4, LintSeverity::Error, "textio"),
+            (14, LintSeverity::Warning, "physical_type"),
+            (19, LintSeverity::Error, "access_type"),
+            (20, LintSeverity::Error, "file_type"),
+            (21, LintSeverity::Error, "shared_variable"),
+            (27, LintSeverity::Warning, "attribute"),
+            (33, LintSeverity::Warning, "wait_in_process"),
+            (36, LintSeverity::Warning, "multiple_wait_processes"),
+            (38, LintSeverity::Warning, "wait_in_process"),
+        ]);
+        assert_eq!(findings[5].message, "attribute 'high may not be converted; only 'event is supported");
+    }
+
+    #[test]
+    fn test_naming_rules_report_one_violation_each() {
+        let violations = lint_vhdl_with_rules(&fixture("naming_violations.vhd"), NAMING_RULES).unwrap();
+
+        let summary: Vec<(usize, &str)> = violations.iter().map(|v| (v.line, v.rule.as_str())).collect();
+        assert_eq!(summary, vec![
+            (6, "generic_naming"),
+            (9, "port_naming"),
+            (11, "no_signal_in_port_name"),
+            (17, "signal_naming"),
+            (19, "process_labels_required"),
+            (21, "consistent_indentation"),
+        ]);
+        assert_eq!(violations[1].message, "port clk_sys should end in clk (e.g. sys_clk)");
+    }
+
+    #[test]
+    fn test_indentation_and_rule_selection() {
+        let source = "entity e is\n    port (a : in bit);\n\tend entity;\n";
+        let violations = lint_vhdl_with_rules(source, &["consistent_indentation"]).unwrap();
+        assert_eq!(violations, vec![LintViolation {
+            rule: "consistent_indentation".to_string(),
+            line: 3,
+            message: "1 line(s) mix tabs and spaces; the file indents with spaces".to_string(),
+            severity: LintSeverity::Warning,
+        }]);
+
+        let err = lint_vhdl_with_rules(source, &["camel_case"]).unwrap_err();
+        assert!(err.to_string().starts_with("Unknown lint rule 'camel_case'. Available: syntax_error, "));
+    }
+
+    #[test]
+    fn test_requires_one_path_argument() {
+        let tool = VHDLLintTool::new(vec![]);
+        let err = tool.execute(&serde_json::json!({})).unwrap_err();
+        assert_eq!(err.to_string(), "Provide exactly one of 'vhdl_file' or 'vhdl_folder'");
+    }
+}
@@ -1,8 +1,19 @@
 use anyhow::Result;
+use std::io::Write;
 use std::path::PathBuf;
 
-/// CLI console trait for output
-pub trait CLIConsole: Send + Sync {
+use crate::tools::{FileStatus, FileTranspileResult, ProgressCallback};
+
+pub mod console;
+pub mod ghdl;
+pub mod watch;
+
+pub use console::{create_console, JsonlConsole, QuietConsole};
+pub use ghdl::{GhdlCheck, GhdlDiagnostic, GhdlRunner};
+pub use watch::{watch_events, Debouncer, WatchUpdate};
+
+/// CLI console trait for output; batch progress goes through its `ProgressCallback`
+pub trait CLIConsole: ProgressCallback + Send + Sync {
     fn print_step(&self, step: u32, max_steps: u32);
     fn print_thinking(&self, step: u32);
     fn print_agent_message(&self, message: &str);
@@ -50,18 +61,101 @@ impl CLIConsole for SimpleConsole {
     }
 }
 
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Symbol of a file's outcome, as in the batch summary
+pub(crate) fn status_symbol(status: FileStatus) -> &'static str {
+    match status {
+        FileStatus::Transpiled => "✓",
+        FileStatus::Merged => "↳",
+        FileStatus::Failed => "✗",
+        FileStatus::Skipped => "-",
+    }
+}
+
+/// `⠙ [ 40%] 2/5 ✓ rtl/counter.vhd`, with the spinner advancing once per file
+pub(crate) fn progress_line(current: usize, total: usize, current_file: &str, status: FileStatus) -> String {
+    let percent = (current * 100).checked_div(total).unwrap_or(100);
+    let spinner = if current >= total { '✔' } else { SPINNER[current % SPINNER.len()] };
+    format!("{} [{:>3}%] {}/{} {} {}", spinner, percent, current, total, status_symbol(status), current_file)
+}
+
+impl ProgressCallback for SimpleConsole {
+    fn on_progress(&self, current: usize, total: usize, current_file: &str, result: &FileTranspileResult) {
+        // Redraw one line until the last file, which keeps its line
+        let mut stdout = std::io::stdout().lock();
+        let _ = write!(stdout, "\r\x1b[2K{}", progress_line(current, total, current_file, result.status));
+        if current >= total {
+            let _ = writeln!(stdout);
+        }
+        let _ = stdout.flush();
+    }
+}
+
+/// A tool call recorded in the trajectory
+#[derive(Debug, Clone)]
+pub struct RecordedAction {
+    pub tool: String,
+    pub arguments: serde_json::Value,
+    pub dry_run: bool,
+}
+
 /// Trajectory recorder for agent actions
 pub struct TrajectoryRecorder {
     _output_path: Option<PathBuf>,
+    step_profiles: Vec<(u32, String)>,
+    actions: Vec<RecordedAction>,
+    dry_run: bool,
 }
 
 impl TrajectoryRecorder {
     pub fn new(output_path: Option<PathBuf>) -> Result<Self> {
         Ok(Self {
             _output_path: output_path,
+            step_profiles: Vec::new(),
+            actions: Vec::new(),
+            dry_run: false,
         })
     }
 
+    /// Tag subsequently recorded actions as dry-run
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    pub fn actions(&self) -> &[RecordedAction] {
+        &self.actions
+    }
+
+    /// Summary of the tool calls skipped in dry-run mode, if any were recorded
+    pub fn dry_run_summary(&self) -> Option<String> {
+        let calls: Vec<String> = self.actions
+            .iter()
+            .filter(|a| a.dry_run)
+            .map(|a| format!("{}({})", a.tool, a.arguments))
+            .collect();
+
+        if calls.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "[DRY-RUN] Would have executed {} tool calls: {}",
+            calls.len(),
+            calls.join(", ")
+        ))
+    }
+
+    /// Record which model profile served a step
+    pub fn record_profile(&mut self, step: u32, profile: &str) -> Result<()> {
+        self.step_profiles.push((step, profile.to_string()));
+        Ok(())
+    }
+
+    pub fn step_profiles(&self) -> &[(u32, String)] {
+        &self.step_profiles
+    }
+
     pub fn record_task(&mut self, _task: &str) -> Result<()> {
         Ok(())
     }
@@ -70,7 +164,12 @@ impl TrajectoryRecorder {
         Ok(())
     }
 
-    pub fn record_action(&mut self, _action: &str, _args: &serde_json::Value) -> Result<()> {
+    pub fn record_action(&mut self, action: &str, args: &serde_json::Value) -> Result<()> {
+        self.actions.push(RecordedAction {
+            tool: action.to_string(),
+            arguments: args.clone(),
+            dry_run: self.dry_run,
+        });
         Ok(())
     }
 
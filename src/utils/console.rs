@@ -0,0 +1,185 @@
+use std::io::Write;
+use std::sync::Mutex;
+
+use crate::config::ConsoleMode;
+use crate::tools::{FileTranspileResult, ProgressCallback};
+use crate::utils::{CLIConsole, SimpleConsole};
+
+/// Create the console implementation selected in the agent configuration
+pub fn create_console(mode: ConsoleMode) -> Box<dyn CLIConsole> {
+    match mode {
+        ConsoleMode::Interactive => Box::new(SimpleConsole),
+        ConsoleMode::Quiet => Box::new(QuietConsole),
+        ConsoleMode::Jsonl => Box::new(JsonlConsole::new(std::io::stdout())),
+    }
+}
+
+/// Console that emits `tracing` events instead of printing, for CI and MCP runs
+pub struct QuietConsole;
+
+impl CLIConsole for QuietConsole {
+    fn print_step(&self, step: u32, max_steps: u32) {
+        tracing::debug!(step, max_steps, "Agent step");
+    }
+
+    fn print_thinking(&self, step: u32) {
+        tracing::trace!(step, "Thinking");
+    }
+
+    fn print_agent_message(&self, message: &str) {
+        tracing::info!("Agent: {}", message);
+    }
+
+    fn print_tool_use(&self, tool_name: &str, args: &str) {
+        tracing::info!(tool = tool_name, args, "Tool use");
+    }
+
+    fn print_tool_result(&self, result: &str) {
+        tracing::debug!("Tool result: {}", result);
+    }
+
+    fn print_success(&self, message: &str) {
+        tracing::info!("{}", message);
+    }
+
+    fn print_error(&self, message: &str) {
+        tracing::error!("{}", message);
+    }
+
+    fn print_info(&self, message: &str) {
+        tracing::info!("{}", message);
+    }
+}
+
+impl ProgressCallback for QuietConsole {
+    fn on_progress(&self, current: usize, total: usize, current_file: &str, result: &FileTranspileResult) {
+        tracing::debug!(current, total, file = current_file, status = ?result.status, "Batch progress");
+    }
+}
+
+/// Console that writes one JSON object per event, suitable for piping into other tools
+pub struct JsonlConsole<W: Write + Send> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonlConsole<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap()
+    }
+
+    fn emit(&self, event: serde_json::Value) {
+        let mut writer = self.writer.lock().unwrap();
+        if let Err(e) = writeln!(writer, "{}", event).and_then(|_| writer.flush()) {
+            tracing::warn!("Failed to write console event: {}", e);
+        }
+    }
+}
+
+impl<W: Write + Send> CLIConsole for JsonlConsole<W> {
+    fn print_step(&self, step: u32, max_steps: u32) {
+        self.emit(serde_json::json!({"event": "step", "step": step, "max_steps": max_steps}));
+    }
+
+    fn print_thinking(&self, step: u32) {
+        self.emit(serde_json::json!({"event": "thinking", "step": step}));
+    }
+
+    fn print_agent_message(&self, message: &str) {
+        self.emit(serde_json::json!({"event": "agent_message", "message": message}));
+    }
+
+    fn print_tool_use(&self, tool_name: &str, args: &str) {
+        // Arguments arrive serialized; keep them structured when they are valid JSON
+        let args = serde_json::from_str::<serde_json::Value>(args)
+            .unwrap_or_else(|_| serde_json::Value::String(args.to_string()));
+        self.emit(serde_json::json!({"event": "tool_use", "name": tool_name, "args": args}));
+    }
+
+    fn print_tool_result(&self, result: &str) {
+        self.emit(serde_json::json!({"event": "tool_result", "result": result}));
+    }
+
+    fn print_success(&self, message: &str) {
+        self.emit(serde_json::json!({"event": "success", "message": message}));
+    }
+
+    fn print_error(&self, message: &str) {
+        self.emit(serde_json::json!({"event": "error", "message": message}));
+    }
+
+    fn print_info(&self, message: &str) {
+        self.emit(serde_json::json!({"event": "info", "message": message}));
+    }
+}
+
+impl<W: Write + Send> ProgressCallback for JsonlConsole<W> {
+    fn on_progress(&self, current: usize, total: usize, current_file: &str, result: &FileTranspileResult) {
+        self.emit(serde_json::json!({
+            "event": "progress",
+            "current": current,
+            "total": total,
+            "file": current_file,
+            "status": result.status,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsonl_console_writes_one_object_per_event() {
+        let console = JsonlConsole::new(Vec::new());
+
+        console.print_step(1, 10);
+        console.print_thinking(1);
+        console.print_agent_message("Reading the entity\nthen the architecture");
+        console.print_tool_use("find", r#"{"directory":"/rtl"}"#);
+        console.print_tool_use("bash", "not json");
+        console.print_tool_result("\"quoted\" result");
+        console.print_success("Task completed!");
+        console.print_error("Something failed");
+        console.print_info("FYI");
+        let mut report = crate::tools::FileReport::new(std::path::Path::new("rtl/alu.vhd"));
+        report.transpiled("out/alu.sv");
+        console.on_progress(1, 4, "rtl/alu.vhd", &report);
+
+        let output = String::from_utf8(console.into_inner()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        let kinds: Vec<&str> = events.iter().map(|e| e["event"].as_str().unwrap()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "step", "thinking", "agent_message", "tool_use", "tool_use",
+                "tool_result", "success", "error", "info", "progress",
+            ]
+        );
+
+        assert_eq!(events[0]["max_steps"], 10);
+        assert_eq!(events[2]["message"], "Reading the entity\nthen the architecture");
+        assert_eq!(events[3]["name"], "find");
+        assert_eq!(events[3]["args"]["directory"], "/rtl");
+        assert_eq!(events[4]["args"], "not json");
+        assert_eq!(events[5]["result"], "\"quoted\" result");
+        assert_eq!(events[9]["file"], "rtl/alu.vhd");
+        assert_eq!(events[9]["status"], "transpiled");
+    }
+
+    #[test]
+    fn test_console_mode_deserialization() {
+        let mode: ConsoleMode = serde_json::from_str("\"jsonl\"").unwrap();
+        assert_eq!(mode, ConsoleMode::Jsonl);
+        assert!(serde_json::from_str::<ConsoleMode>("\"fancy\"").is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! Semantic pre-check of VHDL sources with `ghdl -s`
+
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+lazy_static! {
+    /// "file.vhd:12:5: message", "file.vhd:12:5:error: message" or "file.vhd:3:1:warning: message"
+    static ref DIAGNOSTIC_RE: Regex =
+        Regex::new(r"^(.+?):(\d+):(\d+):\s*(?:(error|warning|note):\s*)?(.*)$").unwrap();
+}
+
+/// One message reported by ghdl
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhdlDiagnostic {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// Warnings and notes do not stop transpilation
+    pub is_error: bool,
+}
+
+impl std::fmt::Display for GhdlDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}: {}", self.file, self.line, self.column, self.message)
+    }
+}
+
+/// Result of running the pre-check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GhdlCheck {
+    /// ghdl could not be started; the reason is meant to be reported as a warning
+    Unavailable(String),
+    Checked(Vec<GhdlDiagnostic>),
+}
+
+impl GhdlCheck {
+    /// Errors reported for `file`, matched against the path ghdl was given
+    pub fn errors_for(&self, file: &Path) -> Vec<&GhdlDiagnostic> {
+        match self {
+            GhdlCheck::Unavailable(_) => Vec::new(),
+            GhdlCheck::Checked(diagnostics) => diagnostics.iter()
+                .filter(|d| d.is_error && Path::new(&d.file) == file)
+                .collect(),
+        }
+    }
+}
+
+/// Runs `ghdl -s` (syntax and semantic analysis only, nothing is written to a library)
+pub struct GhdlRunner {
+    program: PathBuf,
+    args: Vec<String>,
+}
+
+impl GhdlRunner {
+    pub fn new() -> Self {
+        Self { program: PathBuf::from("ghdl"), args: Vec::new() }
+    }
+
+    /// Use another ghdl executable than the one on PATH
+    pub fn with_program(mut self, program: impl Into<PathBuf>) -> Self {
+        self.program = program.into();
+        self
+    }
+
+    /// Extra options such as `--std=08` or `-fsynopsys`
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    pub fn is_available(&self) -> bool {
+        Command::new(&self.program)
+            .arg("--version")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Analyze `files` in one run, so later files see the packages of earlier ones
+    pub fn check(&self, files: &[&Path]) -> Result<GhdlCheck> {
+        let output = match Command::new(&self.program).arg("-s").args(&self.args).args(files).output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(GhdlCheck::Unavailable(format!(
+                    "{} not found; semantic pre-check skipped",
+                    self.program.display()
+                )));
+            }
+            Err(e) => {
+                return Err(e).context(format!("Failed to run {}", self.program.display()));
+            }
+        };
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let diagnostics = Self::parse_output(&stderr);
+        if !output.status.success() && !diagnostics.iter().any(|d| d.is_error) {
+            return Err(anyhow::anyhow!(
+                "{} -s failed without reporting a source location: {}",
+                self.program.display(),
+                stderr.trim()
+            ));
+        }
+        Ok(GhdlCheck::Checked(diagnostics))
+    }
+
+    /// Diagnostics in ghdl's `file:line:column: message` output; other lines such as
+    /// "ghdl: compilation error" are skipped
+    pub fn parse_output(output: &str) -> Vec<GhdlDiagnostic> {
+        output.lines()
+            .filter_map(|line| DIAGNOSTIC_RE.captures(line.trim_end()))
+            .map(|caps| GhdlDiagnostic {
+                file: caps[1].to_string(),
+                line: caps[2].parse().unwrap_or(0),
+                column: caps[3].parse().unwrap_or(0),
+                message: caps[5].trim().to_string(),
+                is_error: caps.get(4).is_none_or(|severity| severity.as_str() == "error"),
+            })
+            .collect()
+    }
+}
+
+impl Default for GhdlRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ghdl_output() {
+        let output = "\
+rtl/counter.vhd:14:9: no declaration for \"cnt_next\"
+rtl/counter.vhd:21:17:error: can't match character literal '1' with type integer
+rtl/alu.vhd:3:1:warning: declaration of \"alu\" hides entity \"alu\" [-Whide]
+ghdl: compilation error
+";
+        let diagnostics = GhdlRunner::parse_output(output);
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0], GhdlDiagnostic {
+            file: "rtl/counter.vhd".to_string(),
+            line: 14,
+            column: 9,
+            message: "no declaration for \"cnt_next\"".to_string(),
+            is_error: true,
+        });
+        assert_eq!(diagnostics[1].to_string(), "rtl/counter.vhd:21:17: can't match character literal '1' with type integer");
+        assert!(!diagnostics[2].is_error);
+
+        let check = GhdlCheck::Checked(diagnostics);
+        assert_eq!(check.errors_for(Path::new("rtl/counter.vhd")).len(), 2);
+        assert!(check.errors_for(Path::new("rtl/alu.vhd")).is_empty());
+    }
+
+    #[test]
+    fn test_missing_ghdl_is_unavailable() {
+        let runner = GhdlRunner::new().with_program("/nonexistent/ghdl");
+        assert!(!runner.is_available());
+        let check = runner.check(&[Path::new("counter.vhd")]).unwrap();
+        assert!(matches!(check, GhdlCheck::Unavailable(reason) if reason.contains("semantic pre-check skipped")));
+    }
+}
@@ -0,0 +1,298 @@
+//! Watch mode: re-transpile VHDL files to SystemVerilog as they are saved
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use crate::ir::SystemVerilogGenerator;
+use crate::parser::ASTVHDLParser;
+use crate::tools::lint_vhdl_with_rules;
+
+/// Quiet period after the last event for a file before it is re-transpiled
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How often the stop flag is checked while no events arrive
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+fn is_vhdl(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| ext == "vhd" || ext == "vhdl")
+}
+
+/// Changed VHDL files, each released once no event arrived for it during the delay
+#[derive(Debug)]
+pub struct Debouncer {
+    delay: Duration,
+    pending: Vec<(PathBuf, Instant)>,
+}
+
+impl Debouncer {
+    pub fn new(delay: Duration) -> Self {
+        Self { delay, pending: Vec::new() }
+    }
+
+    /// Record the VHDL files created or modified by `event`; other events are ignored
+    pub fn add_event(&mut self, event: &Event, now: Instant) {
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            return;
+        }
+        for path in event.paths.iter().filter(|path| is_vhdl(path)) {
+            match self.pending.iter_mut().find(|(pending, _)| pending == path) {
+                Some((_, last_event)) => *last_event = now,
+                None => self.pending.push((path.clone(), now)),
+            }
+        }
+    }
+
+    /// Files whose last event is at least the delay before `now`, in the order they changed
+    pub fn ready(&mut self, now: Instant) -> Vec<PathBuf> {
+        let delay = self.delay;
+        let (ready, pending) = std::mem::take(&mut self.pending).into_iter()
+            .partition(|(_, last_event)| now.duration_since(*last_event) >= delay);
+        self.pending = pending;
+        ready.into_iter().map(|(path, _)| path).collect()
+    }
+
+    /// Every pending file, regardless of the delay
+    pub fn drain(&mut self) -> Vec<PathBuf> {
+        self.pending.drain(..).map(|(path, _)| path).collect()
+    }
+
+    /// When the next pending file becomes ready
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.iter().map(|(_, last_event)| *last_event + self.delay).min()
+    }
+}
+
+/// Regenerated output of one changed file
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchUpdate {
+    pub vhdl_file: PathBuf,
+    pub output_file: PathBuf,
+    /// `- ` and `+ ` prefixed lines between the previous and the new output, hunks separated
+    /// by `  ...`
+    pub diff: Vec<String>,
+}
+
+impl WatchUpdate {
+    /// Lines changed, counting a replaced line once
+    pub fn changed_lines(&self) -> usize {
+        let (mut changed, mut removed, mut added) = (0, 0, 0);
+        for line in self.diff.iter().chain(std::iter::once(&String::new())) {
+            match line.get(..2) {
+                Some("- ") => removed += 1,
+                Some("+ ") => added += 1,
+                _ => {
+                    changed += removed.max(added);
+                    (removed, added) = (0, 0);
+                }
+            }
+        }
+        changed
+    }
+
+    /// `[CHANGED] counter.vhd → counter.sv (3 lines changed)`
+    pub fn status_line(&self) -> String {
+        let file_name = |path: &Path| path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+        let changed = self.changed_lines();
+        if changed == 0 {
+            return format!("[UNCHANGED] {} → {}", file_name(&self.vhdl_file), file_name(&self.output_file));
+        }
+        format!(
+            "[CHANGED] {} → {} ({} line{} changed)",
+            file_name(&self.vhdl_file),
+            file_name(&self.output_file),
+            changed,
+            if changed == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Parse `vhdl_file` again and rewrite its `.sv` next to it. Syntax errors are reported
+/// with the line of the first one
+pub fn retranspile(vhdl_file: &Path) -> Result<WatchUpdate> {
+    let source = std::fs::read_to_string(vhdl_file)
+        .context(format!("Failed to read VHDL file: {}", vhdl_file.display()))?;
+
+    let mut parser = ASTVHDLParser::new(source.clone())?;
+    let entities = match parser.parse_entities() {
+        Ok(entities) => entities,
+        Err(e) => {
+            let syntax_error = lint_vhdl_with_rules(&source, &["syntax_error"]).ok()
+                .and_then(|violations| violations.into_iter().next());
+            return Err(match syntax_error {
+                Some(violation) => anyhow::anyhow!("{}:{}: syntax error", vhdl_file.display(), violation.line),
+                None => e.context(format!("Failed to parse {}", vhdl_file.display())),
+            });
+        }
+    };
+    if entities.is_empty() {
+        return Err(anyhow::anyhow!("No entities found in {}", vhdl_file.display()));
+    }
+
+    let generator = SystemVerilogGenerator::new();
+    let mut output = String::new();
+    for entity in &entities {
+        let (systemverilog, _) = generator.generate(entity)
+            .context(format!("Failed to generate SystemVerilog for entity: {}", entity.name))?;
+        output.push_str(&systemverilog);
+        output.push('\n');
+    }
+
+    let output_file = vhdl_file.with_extension("sv");
+    let previous = std::fs::read_to_string(&output_file).unwrap_or_default();
+    if previous != output {
+        std::fs::write(&output_file, &output)
+            .context(format!("Failed to write SystemVerilog to: {}", output_file.display()))?;
+    }
+
+    Ok(WatchUpdate {
+        vhdl_file: vhdl_file.to_path_buf(),
+        output_file,
+        diff: line_diff(&previous, &output),
+    })
+}
+
+/// Removed and added lines between `old` and `new`, from their longest common subsequence
+pub fn line_diff(old: &str, new: &str) -> Vec<String> {
+    let (old, new): (Vec<&str>, Vec<&str>) = (old.lines().collect(), new.lines().collect());
+    // common[i][j]: length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    // Unchanged lines are collapsed into one `  ...` line between hunks
+    let (mut diff, mut i, mut j): (Vec<String>, _, _) = (Vec::new(), 0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            if diff.last().is_some_and(|line| line != "  ...") {
+                diff.push("  ...".to_string());
+            }
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            diff.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    if diff.last().is_some_and(|line| line == "  ...") {
+        diff.pop();
+    }
+    diff
+}
+
+/// Re-transpile files as `events` report them changed, passing each status line (and the
+/// diff, or the error) to `report`. Runs until `stop` is set or the event sender is dropped
+pub fn watch_events(events: &Receiver<notify::Result<Event>>, stop: &AtomicBool, mut report: impl FnMut(String)) {
+    let mut debouncer = Debouncer::new(DEBOUNCE);
+    while !stop.load(Ordering::Relaxed) {
+        let timeout = debouncer.next_deadline()
+            .map_or(POLL_INTERVAL, |deadline| deadline.saturating_duration_since(Instant::now()).min(POLL_INTERVAL));
+        match events.recv_timeout(timeout) {
+            Ok(Ok(event)) => debouncer.add_event(&event, Instant::now()),
+            Ok(Err(e)) => report(format!("[ERROR] watch: {}", e)),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                retranspile_all(debouncer.drain(), &mut report);
+                return;
+            }
+        }
+        retranspile_all(debouncer.ready(Instant::now()), &mut report);
+    }
+}
+
+fn retranspile_all(files: Vec<PathBuf>, report: &mut impl FnMut(String)) {
+    for file in files {
+        match retranspile(&file) {
+            Ok(update) => {
+                report(update.status_line());
+                for line in &update.diff {
+                    report(format!("    {}", line));
+                }
+            }
+            Err(e) => report(format!("[ERROR] {:#}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, ModifyKind};
+    use std::sync::mpsc;
+
+    fn modified(path: &Path) -> Event {
+        Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content))).add_path(path.to_path_buf())
+    }
+
+    #[test]
+    fn test_debouncer_coalesces_events() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(DEBOUNCE);
+        debouncer.add_event(&modified(Path::new("rtl/counter.vhd")), start);
+        debouncer.add_event(&Event::new(EventKind::Create(CreateKind::File)).add_path("rtl/notes.txt".into()), start);
+        debouncer.add_event(&modified(Path::new("rtl/counter.vhd")), start + Duration::from_millis(200));
+        debouncer.add_event(&modified(Path::new("rtl/alu.VHDL")), start + Duration::from_millis(250));
+
+        assert!(debouncer.ready(start + Duration::from_millis(400)).is_empty());
+        assert_eq!(debouncer.next_deadline(), Some(start + Duration::from_millis(500)));
+        assert_eq!(debouncer.ready(start + Duration::from_millis(500)), vec![PathBuf::from("rtl/counter.vhd")]);
+        assert_eq!(debouncer.ready(start + Duration::from_millis(550)), vec![PathBuf::from("rtl/alu.VHDL")]);
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_update_status_line() {
+        let diff = line_diff(
+            "module counter (\n  input clk,\n  output q\n);\nassign q = clk;\n",
+            "module counter (\n  input clk,\n  input en,\n  output [7:0] q\n);\nassign q = en;\n",
+        );
+        assert_eq!(diff, vec!["-   output q", "+   input en,", "+   output [7:0] q", "  ...", "- assign q = clk;", "+ assign q = en;"]);
+        let update = WatchUpdate {
+            vhdl_file: PathBuf::from("rtl/counter.vhd"),
+            output_file: PathBuf::from("rtl/counter.sv"),
+            diff,
+        };
+        assert_eq!(update.status_line(), "[CHANGED] counter.vhd → counter.sv (3 lines changed)");
+    }
+
+    #[test]
+    fn test_watch_retranspiles_injected_events() {
+        let folder = tempfile::TempDir::new().unwrap();
+        let counter = folder.path().join("counter.vhd");
+        let broken = folder.path().join("broken.vhd");
+        std::fs::write(&counter, "entity counter is port(clk : in std_logic; q : out std_logic); end entity counter;").unwrap();
+        std::fs::write(&broken, "entity broken is port(clk : in std_logic;\nend entity;").unwrap();
+
+        // Events as the watcher would deliver them while the files are saved twice
+        let (sender, events) = mpsc::channel();
+        for path in [&counter, &broken, &counter] {
+            sender.send(Ok(modified(path))).unwrap();
+        }
+        drop(sender);
+
+        let mut lines = Vec::new();
+        watch_events(&events, &AtomicBool::new(false), |line| lines.push(line));
+
+        assert_eq!(lines.iter().filter(|line| line.starts_with('[')).count(), 2);
+        assert!(lines[0].starts_with("[CHANGED] counter.vhd → counter.sv ("));
+        assert!(lines.contains(&"    + module counter (".to_string()));
+        assert!(lines.last().unwrap().starts_with("[ERROR] "));
+        assert!(lines.last().unwrap().contains("broken.vhd:"));
+        assert!(folder.path().join("counter.sv").exists());
+    }
+}
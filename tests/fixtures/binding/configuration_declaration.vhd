@@ -0,0 +1,45 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity fast_inv is
+    port(
+        a : in  std_logic;
+        y : out std_logic
+    );
+end entity fast_inv;
+
+architecture rtl of fast_inv is
+begin
+    y <= not a;
+end architecture rtl;
+
+entity top is
+    port(
+        x : in  std_logic;
+        z : out std_logic
+    );
+end entity top;
+
+architecture rtl of top is
+    component inv is
+        port(
+            a : in  std_logic;
+            y : out std_logic
+        );
+    end component;
+begin
+    u0 : inv port map (a => x, y => z);
+end architecture rtl;
+
+configuration top_cfg of top is
+    for rtl
+        for u0 : inv
+            use entity work.fast_inv(rtl);
+        end for;
+    end for;
+end configuration top_cfg;
+
+configuration legacy_cfg of old_top is
+    for rtl
+    end for;
+end configuration legacy_cfg;
@@ -0,0 +1,31 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity inv is
+    port(
+        a : in  std_logic;
+        y : out std_logic
+    );
+end entity inv;
+
+architecture rtl of inv is
+begin
+    y <= not a;
+end architecture rtl;
+
+architecture gated of inv is
+begin
+    y <= a nand '1';
+end architecture gated;
+
+entity top is
+    port(
+        x : in  std_logic;
+        z : out std_logic
+    );
+end entity top;
+
+architecture rtl of top is
+begin
+    u0 : entity work.inv(gated) port map (a => x, y => z);
+end architecture rtl;
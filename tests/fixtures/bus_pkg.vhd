@@ -0,0 +1,56 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+package bus_pkg is
+    constant BUS_WIDTH : integer := 32;
+    constant RESET_VALUE : std_logic_vector(31 downto 0); -- deferred to the body
+
+    subtype bus_t is std_logic_vector(31 downto 0);
+    type bus_state_t is (BUS_IDLE, BUS_READ, BUS_WRITE);
+
+    function parity(data : in bus_t) return std_logic;
+    function pack(signal hi, lo : std_logic_vector(15 downto 0)) return bus_t;
+end package bus_pkg;
+
+package body bus_pkg is
+    constant RESET_VALUE : std_logic_vector(31 downto 0) := x"DEADBEEF";
+
+    function parity(data : in bus_t) return std_logic is
+        variable result : std_logic := '0';
+    begin
+        for i in data'range loop
+            result := result xor data(i);
+        end loop;
+        return result;
+    end function parity;
+
+    function pack(signal hi, lo : std_logic_vector(15 downto 0)) return bus_t is
+    begin
+        return hi & lo;
+    end function pack;
+end package body bus_pkg;
+
+library ieee;
+use ieee.std_logic_1164.all;
+use work.bus_pkg.all;
+
+entity bus_reg is
+    port(
+        clk   : in  std_logic;
+        d     : in  bus_t;
+        q     : out bus_t;
+        state : out bus_state_t
+    );
+end entity bus_reg;
+
+architecture rtl of bus_reg is
+begin
+    process(clk)
+    begin
+        if rising_edge(clk) then
+            q <= d;
+        end if;
+    end process;
+
+    state <= BUS_IDLE;
+end architecture rtl;
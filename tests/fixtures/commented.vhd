@@ -0,0 +1,29 @@
+-- Copyright (c) 2024 Example Corp.
+-- Licensed under the Apache License, Version 2.0
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity toggle is
+    port(
+        clk : in  std_logic; -- system clock
+        rst : in  std_logic; -- active-high reset
+        q   : out std_logic  -- toggles every cycle
+    );
+end entity toggle;
+
+architecture rtl of toggle is
+    signal debug_q : std_logic;
+begin
+    process(clk, rst)
+    begin
+        -- asynchronous reset
+        if rst = '1' then
+            q <= '0'; -- clear
+        elsif rising_edge(clk) then
+            -- synthesis translate_off
+            debug_q <= q;
+            -- synthesis translate_on
+            q <= not q;
+        end if;
+    end process;
+end architecture rtl;
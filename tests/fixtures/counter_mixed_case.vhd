@@ -0,0 +1,26 @@
+library IEEE;
+use IEEE.STD_LOGIC_1164.ALL;
+use IEEE.NUMERIC_STD.ALL;
+
+entity mixed_case_counter is
+    port(
+        Clk   : in  std_logic;
+        Reset : in  std_logic;
+        Count : out std_logic_vector(7 downto 0)
+    );
+end entity mixed_case_counter;
+
+architecture rtl of mixed_case_counter is
+    signal Count_Reg : unsigned(7 downto 0);
+begin
+    process(clk, RESET)
+    begin
+        if reset = '1' then
+            count_reg <= (others => '0');
+        elsif rising_edge(CLK) then
+            COUNT_REG <= Count_reg + 1;
+        end if;
+    end process;
+
+    count <= std_logic_vector(count_reg);
+end architecture rtl;
@@ -0,0 +1,17 @@
+module adder_array (
+    input  logic [31:0] a,
+    input  logic [31:0] b,
+    output logic [31:0] sum
+);
+
+    genvar i;
+    generate
+        for (i = 0; i <= 3; i = i + 1) begin : gen_add
+            adder u_add (
+                .a(a[8 * i + 7:8 * i]),
+                .b(b[8 * i + 7:8 * i]),
+                .sum(sum[8 * i + 7:8 * i])
+            );
+        end
+    endgenerate
+endmodule
@@ -0,0 +1,32 @@
+module alu4 (
+    input  logic [7:0] a,
+    input  logic [7:0] b,
+    input  logic [1:0] op,
+    output logic [7:0] result,
+    output logic       zero
+);
+
+    logic [7:0] res;
+
+    always_comb begin
+        unique case (op)
+            2'b00: begin
+                res = a + b;
+            end
+            2'b01: begin
+                res = a - b;
+            end
+            2'b10: begin
+                res = a & b;
+            end
+            default: begin
+                res = a | b;
+            end
+        endcase
+    end
+
+    assign result = res;
+
+    assign zero = (res == 8'b00000000) ? 1'b1 : 1'b0;
+endmodule
+
@@ -0,0 +1,32 @@
+module alu4 (
+    input  wire [7:0] a,
+    input  wire [7:0] b,
+    input  wire [1:0] op,
+    output wire [7:0] result,
+    output wire       zero
+);
+
+    reg [7:0] res;
+
+    always @(*) begin
+        case (op)
+            2'b00: begin
+                res = a + b;
+            end
+            2'b01: begin
+                res = a - b;
+            end
+            2'b10: begin
+                res = a & b;
+            end
+            default: begin
+                res = a | b;
+            end
+        endcase
+    end
+
+    assign result = res;
+
+    assign zero = (res == 8'b00000000) ? 1'b1 : 1'b0;
+endmodule
+
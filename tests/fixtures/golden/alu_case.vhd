@@ -0,0 +1,34 @@
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+entity alu4 is
+    port(
+        a      : in  std_logic_vector(7 downto 0);
+        b      : in  std_logic_vector(7 downto 0);
+        op     : in  std_logic_vector(1 downto 0);
+        result : out std_logic_vector(7 downto 0);
+        zero   : out std_logic
+    );
+end entity alu4;
+
+architecture rtl of alu4 is
+    signal res : std_logic_vector(7 downto 0);
+begin
+    process(a, b, op)
+    begin
+        case op is
+            when "00" =>
+                res <= std_logic_vector(unsigned(a) + unsigned(b));
+            when "01" =>
+                res <= std_logic_vector(unsigned(a) - unsigned(b));
+            when "10" =>
+                res <= a and b;
+            when others =>
+                res <= a or b;
+        end case;
+    end process;
+
+    result <= res;
+    zero <= '1' when res = "00000000" else '0';
+end architecture rtl;
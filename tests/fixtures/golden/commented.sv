@@ -0,0 +1,22 @@
+// Copyright (c) 2024 Example Corp.
+// Licensed under the Apache License, Version 2.0
+module toggle (
+    input  logic clk, // system clock
+    input  logic rst, // active-high reset
+    output logic q // toggles every cycle
+);
+
+    logic debug_q;
+
+    always_ff @(posedge clk or posedge rst) begin
+        // asynchronous reset
+        if (rst == 1'b1) begin
+            q <= 1'b0; // clear
+        end else begin
+            // synthesis translate_off
+            debug_q <= q;
+            // synthesis translate_on
+            q <= ~q;
+        end
+    end
+endmodule
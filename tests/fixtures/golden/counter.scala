@@ -0,0 +1,20 @@
+import spinal.core._
+
+case class Counter() extends Component {
+  val io = new Bundle {
+    val clk = in Bool()
+    val rst_n = in Bool()
+    val enable = in Bool()
+    val count = out UInt(8 bits)
+  }
+
+  val clockDomain = ClockDomain(io.clk, io.rst_n, config = ClockDomainConfig(clockEdge = RISING, resetKind = ASYNC, resetActiveLevel = LOW))
+  val core = new ClockingArea(clockDomain) {
+    io.count.setAsReg() init(U(8 bits, default -> False))
+
+    // tick
+    when(io.enable === True) { // count up
+      io.count := io.count + 1
+    }
+  }
+}
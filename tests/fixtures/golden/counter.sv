@@ -0,0 +1,21 @@
+module counter (
+    input  logic       clk,
+    input  logic       reset,
+    input  logic       enable,
+    output logic [7:0] count
+);
+
+    logic [7:0] count_reg;
+
+    always_ff @(posedge clk or posedge reset) begin
+        if (reset == 1'b1) begin
+            count_reg <= '0;
+        end else begin
+            if (enable == 1'b1) begin
+                count_reg <= count_reg + 1;
+            end
+        end
+    end
+
+    assign count = count_reg;
+endmodule
@@ -0,0 +1,22 @@
+module counter_async (
+    input  logic       clk,
+    input  logic       rst_n,
+    input  logic       enable,
+    output logic [7:0] count
+);
+
+    logic [7:0] count_reg;
+
+    always_ff @(posedge clk or negedge rst_n) begin
+        if (rst_n == 1'b0) begin
+            count_reg <= '0;
+        end else begin
+            if (enable == 1'b1) begin
+                count_reg <= count_reg + 1;
+            end
+        end
+    end
+
+    assign count = count_reg;
+endmodule
+
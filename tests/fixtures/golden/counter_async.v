@@ -0,0 +1,22 @@
+module counter_async (
+    input  wire       clk,
+    input  wire       rst_n,
+    input  wire       enable,
+    output wire [7:0] count
+);
+
+    reg [7:0] count_reg;
+
+    always @(posedge clk or negedge rst_n) begin
+        if (rst_n == 1'b0) begin
+            count_reg <= {8{1'b0}};
+        end else begin
+            if (enable == 1'b1) begin
+                count_reg <= count_reg + 1;
+            end
+        end
+    end
+
+    assign count = count_reg;
+endmodule
+
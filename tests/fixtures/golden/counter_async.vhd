@@ -0,0 +1,29 @@
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+entity counter_async is
+    port(
+        clk    : in  std_logic;
+        rst_n  : in  std_logic;
+        enable : in  std_logic;
+        count  : out std_logic_vector(7 downto 0)
+    );
+end entity counter_async;
+
+architecture rtl of counter_async is
+    signal count_reg : unsigned(7 downto 0);
+begin
+    process(clk, rst_n)
+    begin
+        if rst_n = '0' then
+            count_reg <= (others => '0');
+        elsif rising_edge(clk) then
+            if enable = '1' then
+                count_reg <= count_reg + 1;
+            end if;
+        end if;
+    end process;
+
+    count <= std_logic_vector(count_reg);
+end architecture rtl;
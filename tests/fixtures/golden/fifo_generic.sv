@@ -0,0 +1,46 @@
+module sync_fifo #(
+    parameter WIDTH = 8,
+    parameter ADDR_WIDTH = 4
+) (
+    input  logic             clk,
+    input  logic             rst,
+    input  logic             wr_en,
+    input  logic             rd_en,
+    input  logic [WIDTH-1:0] din,
+    output logic [WIDTH-1:0] dout,
+    output logic             empty,
+    output logic             full
+);
+
+    logic /* mem_t */ mem;
+    logic [ADDR_WIDTH-1:0] wr_ptr;
+    logic [ADDR_WIDTH-1:0] rd_ptr;
+    logic [ADDR_WIDTH:0] count;
+
+    always_ff @(posedge clk) begin
+        if (rst == 1'b1) begin
+            wr_ptr <= '0;
+            rd_ptr <= '0;
+            count <= '0;
+        end else begin
+            if (wr_en == 1'b1 & count != 2**ADDR_WIDTH) begin
+                mem[int'(wr_ptr)] <= din;
+                wr_ptr <= wr_ptr + 1;
+            end
+            if (rd_en == 1'b1 & count != 0) begin
+                dout <= mem[int'(rd_ptr)];
+                rd_ptr <= rd_ptr + 1;
+            end
+            if (wr_en == 1'b1 & rd_en == 1'b0 & count != 2**ADDR_WIDTH) begin
+                count <= count + 1;
+            end else if (rd_en == 1'b1 & wr_en == 1'b0 & count != 0) begin
+                count <= count - 1;
+            end
+        end
+    end
+
+    assign empty = (count == 0) ? 1'b1 : 1'b0;
+
+    assign full = (count == 2**ADDR_WIDTH) ? 1'b1 : 1'b0;
+endmodule
+
@@ -0,0 +1,46 @@
+module sync_fifo #(
+    parameter WIDTH = 8,
+    parameter ADDR_WIDTH = 4
+) (
+    input  wire             clk,
+    input  wire             rst,
+    input  wire             wr_en,
+    input  wire             rd_en,
+    input  wire [WIDTH-1:0] din,
+    output reg [WIDTH-1:0]  dout,
+    output wire             empty,
+    output wire             full
+);
+
+    reg /* mem_t */ mem;
+    reg [ADDR_WIDTH-1:0] wr_ptr;
+    reg [ADDR_WIDTH-1:0] rd_ptr;
+    reg [ADDR_WIDTH:0] count;
+
+    always @(posedge clk) begin
+        if (rst == 1'b1) begin
+            wr_ptr <= 0;
+            rd_ptr <= 0;
+            count <= 0;
+        end else begin
+            if (wr_en == 1'b1 & count != 2**ADDR_WIDTH) begin
+                mem[wr_ptr] <= din;
+                wr_ptr <= wr_ptr + 1;
+            end
+            if (rd_en == 1'b1 & count != 0) begin
+                dout <= mem[rd_ptr];
+                rd_ptr <= rd_ptr + 1;
+            end
+            if (wr_en == 1'b1 & rd_en == 1'b0 & count != 2**ADDR_WIDTH) begin
+                count <= count + 1;
+            end else if (rd_en == 1'b1 & wr_en == 1'b0 & count != 0) begin
+                count <= count - 1;
+            end
+        end
+    end
+
+    assign empty = (count == 0) ? 1'b1 : 1'b0;
+
+    assign full = (count == 2**ADDR_WIDTH) ? 1'b1 : 1'b0;
+endmodule
+
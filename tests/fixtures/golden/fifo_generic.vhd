@@ -0,0 +1,56 @@
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+entity sync_fifo is
+    generic(
+        WIDTH      : integer := 8;
+        ADDR_WIDTH : integer := 4
+    );
+    port(
+        clk   : in  std_logic;
+        rst   : in  std_logic;
+        wr_en : in  std_logic;
+        rd_en : in  std_logic;
+        din   : in  std_logic_vector(WIDTH-1 downto 0);
+        dout  : out std_logic_vector(WIDTH-1 downto 0);
+        empty : out std_logic;
+        full  : out std_logic
+    );
+end entity sync_fifo;
+
+architecture rtl of sync_fifo is
+    type mem_t is array (0 to 2**ADDR_WIDTH-1) of std_logic_vector(WIDTH-1 downto 0);
+    signal mem    : mem_t;
+    signal wr_ptr : unsigned(ADDR_WIDTH-1 downto 0);
+    signal rd_ptr : unsigned(ADDR_WIDTH-1 downto 0);
+    signal count  : unsigned(ADDR_WIDTH downto 0);
+begin
+    process(clk)
+    begin
+        if rising_edge(clk) then
+            if rst = '1' then
+                wr_ptr <= (others => '0');
+                rd_ptr <= (others => '0');
+                count <= (others => '0');
+            else
+                if wr_en = '1' and count /= 2**ADDR_WIDTH then
+                    mem(to_integer(wr_ptr)) <= din;
+                    wr_ptr <= wr_ptr + 1;
+                end if;
+                if rd_en = '1' and count /= 0 then
+                    dout <= mem(to_integer(rd_ptr));
+                    rd_ptr <= rd_ptr + 1;
+                end if;
+                if wr_en = '1' and rd_en = '0' and count /= 2**ADDR_WIDTH then
+                    count <= count + 1;
+                elsif rd_en = '1' and wr_en = '0' and count /= 0 then
+                    count <= count - 1;
+                end if;
+            end if;
+        end if;
+    end process;
+
+    empty <= '1' when count = 0 else '0';
+    full <= '1' when count = 2**ADDR_WIDTH else '0';
+end architecture rtl;
@@ -0,0 +1,42 @@
+module handshake_fsm (
+    input  wire clk,
+    input  wire rst,
+    input  wire start,
+    input  wire ack,
+    output wire req,
+    output wire done
+);
+
+    localparam [1:0] IDLE = 2'd0;
+    localparam [1:0] REQUEST = 2'd1;
+    localparam [1:0] FINISH = 2'd2;
+
+    reg [1:0] state;
+
+    always @(posedge clk) begin
+        if (rst == 1'b1) begin
+            state <= IDLE;
+        end else begin
+            case (state)
+                IDLE: begin
+                    if (start == 1'b1) begin
+                        state <= REQUEST;
+                    end
+                end
+                REQUEST: begin
+                    if (ack == 1'b1) begin
+                        state <= FINISH;
+                    end
+                end
+                default: begin
+                    state <= IDLE;
+                end
+            endcase
+        end
+    end
+
+    assign req = (state == REQUEST) ? 1'b1 : 1'b0;
+
+    assign done = (state == FINISH) ? 1'b1 : 1'b0;
+endmodule
+
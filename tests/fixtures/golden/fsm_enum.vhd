@@ -0,0 +1,43 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity handshake_fsm is
+    port(
+        clk   : in  std_logic;
+        rst   : in  std_logic;
+        start : in  std_logic;
+        ack   : in  std_logic;
+        req   : out std_logic;
+        done  : out std_logic
+    );
+end entity handshake_fsm;
+
+architecture rtl of handshake_fsm is
+    type state_t is (IDLE, REQUEST, FINISH);
+    signal state : state_t;
+begin
+    process(clk)
+    begin
+        if rising_edge(clk) then
+            if rst = '1' then
+                state <= IDLE;
+            else
+                case state is
+                    when IDLE =>
+                        if start = '1' then
+                            state <= REQUEST;
+                        end if;
+                    when REQUEST =>
+                        if ack = '1' then
+                            state <= FINISH;
+                        end if;
+                    when others =>
+                        state <= IDLE;
+                end case;
+            end if;
+        end if;
+    end process;
+
+    req <= '1' when state = REQUEST else '0';
+    done <= '1' when state = FINISH else '0';
+end architecture rtl;
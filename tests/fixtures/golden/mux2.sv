@@ -0,0 +1,15 @@
+module mux2 (
+    input  logic [7:0] a,
+    input  logic [7:0] b,
+    input  logic       sel,
+    output logic [7:0] y
+);
+
+    always_comb begin
+        if (sel == 1'b0) begin
+            y = a;
+        end else begin
+            y = b;
+        end
+    end
+endmodule
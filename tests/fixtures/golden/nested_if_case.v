@@ -0,0 +1,32 @@
+module nested (
+    input wire clk,
+    input wire rst,
+    input wire start,
+    input wire mode
+);
+
+    always @(posedge clk) begin
+        if (rst == 1'b1) begin
+            state <= IDLE;
+            count <= 8'h00;
+        end else begin
+            case (state)
+                IDLE: begin
+                    if (start == 1'b1) begin
+                        if (mode == 1'b1) begin
+                            state <= RUN;
+                        end else begin
+                            state <= DONE;
+                        end
+                    end
+                end
+                RUN: begin
+                    count <= count + 8'h01;
+                end
+                default: begin
+                    state <= IDLE;
+                end
+            endcase
+        end
+    end
+endmodule
@@ -0,0 +1,32 @@
+module reg_stage (
+    input  logic       clk,
+    input  logic [7:0] d,
+    output logic [7:0] q
+);
+
+    always_ff @(posedge clk) begin
+        q <= d;
+    end
+endmodule
+
+module pipeline_top (
+    input  logic       clk,
+    input  logic [7:0] din,
+    output logic [7:0] dout
+);
+
+    logic [7:0] stage1;
+
+    reg_stage u_stage0 (
+        .clk(clk),
+        .d(din),
+        .q(stage1)
+    );
+
+    reg_stage u_stage1 (
+        .clk(clk),
+        .d(stage1),
+        .q(dout)
+    );
+endmodule
+
@@ -0,0 +1,32 @@
+module reg_stage (
+    input  wire       clk,
+    input  wire [7:0] d,
+    output reg [7:0]  q
+);
+
+    always @(posedge clk) begin
+        q <= d;
+    end
+endmodule
+
+module pipeline_top (
+    input  wire       clk,
+    input  wire [7:0] din,
+    output wire [7:0] dout
+);
+
+    reg [7:0] stage1;
+
+    reg_stage u_stage0 (
+        .clk(clk),
+        .d(din),
+        .q(stage1)
+    );
+
+    reg_stage u_stage1 (
+        .clk(clk),
+        .d(stage1),
+        .q(dout)
+    );
+endmodule
+
@@ -0,0 +1,46 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity reg_stage is
+    port(
+        clk : in  std_logic;
+        d   : in  std_logic_vector(7 downto 0);
+        q   : out std_logic_vector(7 downto 0)
+    );
+end entity reg_stage;
+
+architecture rtl of reg_stage is
+begin
+    process(clk)
+    begin
+        if rising_edge(clk) then
+            q <= d;
+        end if;
+    end process;
+end architecture rtl;
+
+entity pipeline_top is
+    port(
+        clk  : in  std_logic;
+        din  : in  std_logic_vector(7 downto 0);
+        dout : out std_logic_vector(7 downto 0)
+    );
+end entity pipeline_top;
+
+architecture structural of pipeline_top is
+    signal stage1 : std_logic_vector(7 downto 0);
+begin
+    u_stage0 : entity work.reg_stage
+        port map(
+            clk => clk,
+            d   => din,
+            q   => stage1
+        );
+
+    u_stage1 : entity work.reg_stage
+        port map(
+            clk => clk,
+            d   => stage1,
+            q   => dout
+        );
+end architecture structural;
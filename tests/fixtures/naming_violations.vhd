@@ -0,0 +1,28 @@
+-- Breaks each naming rule of lint_vhdl exactly once
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity naming_demo is
+    generic(width : integer := 8);
+    port(
+        rst_n       : in  std_logic;
+        clk_sys     : in  std_logic;
+        rx_data     : in  std_logic_vector(7 downto 0);
+        dout_signal : out std_logic_vector(7 downto 0)
+    );
+end entity naming_demo;
+
+architecture rtl of naming_demo is
+    signal hold_r : std_logic_vector(7 downto 0);
+    signal latched : std_logic_vector(7 downto 0);
+begin
+    process(clk_sys)
+    begin
+	if rising_edge(clk_sys) then
+            hold_r <= rx_data;
+            latched <= hold_r;
+        end if;
+    end process;
+
+    dout_signal <= latched;
+end architecture rtl;
@@ -0,0 +1,32 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity clean_buf is
+    port(
+        a : in  std_logic;
+        y : out std_logic
+    );
+end entity clean_buf;
+
+architecture rtl of clean_buf is
+begin
+    y <= a;
+end architecture rtl;
+
+entity broken_reg is
+    port(
+        clk : in  std_logic;
+        d   : in  std_logic;
+        q   : out std_logic
+    );
+end entity broken_reg;
+
+architecture rtl of broken_reg is
+begin
+    process(clk)
+    begin
+        if rising_edge(clk) then
+            q <= ;
+        end if;
+    end process;
+end architecture rtl;
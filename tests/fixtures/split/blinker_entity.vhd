@@ -0,0 +1,10 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity blinker is
+    port(
+        clk   : in  std_logic;
+        reset : in  std_logic;
+        led   : out std_logic
+    );
+end entity blinker;
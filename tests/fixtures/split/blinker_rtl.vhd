@@ -0,0 +1,17 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+architecture rtl of blinker is
+    signal state : std_logic;
+begin
+    toggle : process(clk, reset)
+    begin
+        if reset = '1' then
+            state <= '0';
+        elsif rising_edge(clk) then
+            state <= not state;
+        end if;
+    end process;
+
+    led <= state;
+end architecture rtl;
@@ -0,0 +1,24 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity reg_bank is
+    port(
+        clk   : in  std_logic;
+        reset : in  std_logic;
+        ready : out std_logic
+    );
+end entity reg_bank;
+
+architecture rtl of reg_bank is
+begin
+    process(clk)
+    begin
+        if rising_edge(clk) then
+            if reset = '1' then
+                ready <= '0';
+            else
+                ready <= '1';
+            end if;
+        end if;
+    end process;
+end architecture rtl;
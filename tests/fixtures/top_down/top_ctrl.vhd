@@ -0,0 +1,47 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top_ctrl is
+    port(
+        clk   : in  std_logic;
+        reset : in  std_logic;
+        tx    : out std_logic
+    );
+end entity top_ctrl;
+
+architecture rtl of top_ctrl is
+    component reg_bank is
+        port(
+            clk   : in  std_logic;
+            reset : in  std_logic;
+            ready : out std_logic
+        );
+    end component;
+
+    component debug_core is
+        port(
+            clk : in std_logic
+        );
+    end component;
+
+    signal ready : std_logic;
+begin
+    u_regs : reg_bank
+        port map(
+            clk   => clk,
+            reset => reset,
+            ready => ready
+        );
+
+    u_uart : entity work.uart_tx
+        port map(
+            clk   => clk,
+            start => ready,
+            tx    => tx
+        );
+
+    u_debug : debug_core
+        port map(
+            clk => clk
+        );
+end architecture rtl;
@@ -0,0 +1,20 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity uart_tx is
+    port(
+        clk   : in  std_logic;
+        start : in  std_logic;
+        tx    : out std_logic
+    );
+end entity uart_tx;
+
+architecture rtl of uart_tx is
+begin
+    process(clk)
+    begin
+        if rising_edge(clk) then
+            tx <= not start;
+        end if;
+    end process;
+end architecture rtl;
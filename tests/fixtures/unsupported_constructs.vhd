@@ -0,0 +1,42 @@
+-- Constructs the transpiler cannot convert faithfully
+library ieee;
+use ieee.std_logic_1164.all;
+use std.textio.all;
+
+entity unsupported is
+    port(
+        clk : in  std_logic;
+        q   : out std_logic_vector(7 downto 0)
+    );
+end entity unsupported;
+
+architecture sim of unsupported is
+    type delay_t is range 0 to 1000 units
+        ps;
+        ns = 1000 ps;
+    end units;
+
+    type int_ptr is access integer;
+    type int_file is file of integer;
+    shared variable hits : integer := 0;
+    signal count : std_logic_vector(7 downto 0);
+begin
+    p_count : process(clk)
+    begin
+        if clk'event and clk = '1' then
+            count(count'high) <= '1';
+        end if;
+    end process;
+
+    p_stim : process
+    begin
+        wait for 10 ns;
+    end process;
+
+    p_check : process
+    begin
+        wait until rising_edge(clk);
+    end process;
+
+    q <= count;
+end architecture sim;
@@ -0,0 +1,67 @@
+use rtl_transpiler::tools::{Tool, TranspileFolderTool};
+use rtl_transpiler::utils::{GhdlCheck, GhdlRunner};
+use serde_json::json;
+
+const VALID: &str = r#"
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity buf is
+    port(
+        a : in  std_logic;
+        y : out std_logic
+    );
+end entity buf;
+
+architecture rtl of buf is
+begin
+    y <= a;
+end architecture rtl;
+"#;
+
+/// Parses fine but assigns an undeclared signal
+const UNDECLARED: &str = r#"
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity broken is
+    port(
+        a : in  std_logic;
+        y : out std_logic
+    );
+end entity broken;
+
+architecture rtl of broken is
+begin
+    y <= a and missing_sig;
+end architecture rtl;
+"#;
+
+#[test]
+fn test_ghdl_precheck_skips_files_with_errors() {
+    let runner = GhdlRunner::new();
+    if !runner.is_available() {
+        eprintln!("ghdl not on PATH, skipping");
+        return;
+    }
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let valid = dir.path().join("buf.vhd");
+    let broken = dir.path().join("broken.vhd");
+    std::fs::write(&valid, VALID).unwrap();
+    std::fs::write(&broken, UNDECLARED).unwrap();
+
+    let check = runner.check(&[valid.as_path(), broken.as_path()]).unwrap();
+    assert!(matches!(check, GhdlCheck::Checked(_)));
+    assert!(check.errors_for(&valid).is_empty());
+    let errors = check.errors_for(&broken);
+    assert!(!errors.is_empty());
+    assert!(errors.iter().any(|e| e.line == 15 && e.message.contains("missing_sig")));
+
+    let report = TranspileFolderTool::new(vec![])
+        .execute(&json!({ "vhdl_folder": dir.path().display().to_string(), "precheck": "ghdl" }))
+        .unwrap();
+    assert!(report.contains(&format!("✗ {}: GHDL pre-check failed:", broken.display())));
+    assert!(report.contains("Successfully transpiled: 1"));
+    assert!(!dir.path().join("broken.sv").exists());
+}
@@ -0,0 +1,131 @@
+//! Golden tests: every design in `tests/fixtures/golden` is transpiled to Verilog and SystemVerilog
+//! and compared with the `.v` and `.sv` files checked in next to it.
+//!
+//! Run with `UPDATE_GOLDEN=1` to rewrite the expected files from the current output.
+
+use rtl_transpiler::api::{transpile_vhdl_file, Target};
+use std::path::{Path, PathBuf};
+
+/// Lines of context around each changed region of a diff
+const CONTEXT: usize = 2;
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden")
+}
+
+fn designs() -> Vec<PathBuf> {
+    let mut designs: Vec<PathBuf> = std::fs::read_dir(golden_dir()).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "vhd"))
+        .collect();
+    designs.sort();
+    designs
+}
+
+/// Unified diff of `expected` against `actual`, from their longest common subsequence
+fn unified_diff(expected: &str, actual: &str, expected_name: &str) -> String {
+    let (old, new): (Vec<&str>, Vec<&str>) = (expected.lines().collect(), actual.lines().collect());
+    // common[i][j]: length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    // (prefix, old line index, new line index, text) for every line of both sides
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((' ', i, j, old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            ops.push(('-', i, j, old[i]));
+            i += 1;
+        } else {
+            ops.push(('+', i, j, new[j]));
+            j += 1;
+        }
+    }
+
+    let mut diff = format!("--- {}\n+++ actual\n", expected_name);
+    let mut index = 0;
+    while let Some(offset) = ops[index..].iter().position(|op| op.0 != ' ') {
+        let start = (index + offset).saturating_sub(CONTEXT);
+        // Extend the hunk while the next change is within two contexts of the last one
+        let mut end = index + offset;
+        while let Some(next) = ops[end + 1..].iter().position(|op| op.0 != ' ').map(|n| end + 1 + n) {
+            if next - end > 2 * CONTEXT + 1 {
+                break;
+            }
+            end = next;
+        }
+        let end = (end + CONTEXT + 1).min(ops.len());
+
+        let hunk = &ops[start..end];
+        let old_count = hunk.iter().filter(|op| op.0 != '+').count();
+        let new_count = hunk.iter().filter(|op| op.0 != '-').count();
+        diff.push_str(&format!("@@ -{},{} +{},{} @@\n", hunk[0].1 + 1, old_count, hunk[0].2 + 1, new_count));
+        for (prefix, _, _, text) in hunk {
+            diff.push_str(&format!("{}{}\n", prefix, text));
+        }
+        index = end;
+    }
+    diff
+}
+
+fn check_golden(design: &Path, target: Target, extension: &str, update: bool) -> Option<String> {
+    let golden = design.with_extension(extension);
+    let golden_name = golden.file_name().unwrap().to_string_lossy().into_owned();
+    let actual = match transpile_vhdl_file(design, target) {
+        Ok(output) => output.code,
+        Err(e) => return Some(format!("{}: transpilation failed: {:#}", golden_name, e)),
+    };
+
+    if update {
+        std::fs::write(&golden, &actual).unwrap();
+        return None;
+    }
+    let expected = match std::fs::read_to_string(&golden) {
+        Ok(expected) => expected,
+        Err(e) => return Some(format!("{}: cannot read golden file ({}), run with UPDATE_GOLDEN=1", golden_name, e)),
+    };
+    (expected != actual).then(|| unified_diff(&expected, &actual, &golden_name))
+}
+
+#[test]
+fn test_golden_outputs() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let designs = designs();
+    assert!(!designs.is_empty(), "no designs in {}", golden_dir().display());
+
+    let mut mismatches = Vec::new();
+    for design in &designs {
+        for (target, extension) in [(Target::Verilog, "v"), (Target::SystemVerilog, "sv")] {
+            mismatches.extend(check_golden(design, target, extension, update));
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "{} golden file(s) differ from the transpiler output (UPDATE_GOLDEN=1 regenerates them):\n\n{}",
+        mismatches.len(),
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn test_unified_diff_hunks() {
+    let expected = "module m (\n  input a,\n  output y\n);\nassign y = a;\nendmodule\n";
+    let actual = "module m (\n  input a,\n  input b,\n  output y\n);\nassign y = a & b;\nendmodule\n";
+    assert_eq!(
+        unified_diff(expected, actual, "m.v"),
+        "--- m.v\n+++ actual\n@@ -1,6 +1,7 @@\n module m (\n   input a,\n+  input b,\n   output y\n );\n-assign y = a;\n+assign y = a & b;\n endmodule\n"
+    );
+}
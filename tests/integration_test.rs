@@ -15,22 +15,22 @@ fn test_counter_transpilation() {
     assert_eq!(entities[0].ports.len(), 4);
 
     let generator = VerilogGenerator::new();
-    let verilog = generator.generate(&entities[0]).unwrap();
+    let verilog = generator.generate(&entities[0]).unwrap().0;
 
     println!("Generated Verilog:\n{}", verilog);
 
     // Verify key parts of the generated Verilog
     assert!(verilog.contains("module counter"));
-    assert!(verilog.contains("input wire clk"));
-    assert!(verilog.contains("input wire reset"));
-    assert!(verilog.contains("input wire enable"));
+    assert!(verilog.contains("input  wire       clk"));
+    assert!(verilog.contains("input  wire       reset"));
+    assert!(verilog.contains("input  wire       enable"));
     assert!(verilog.contains("output wire [7:0] count"));
     assert!(verilog.contains("endmodule"));
 
     // Verify port ordering (should match VHDL)
-    let clk_pos = verilog.find("input wire clk").unwrap();
-    let reset_pos = verilog.find("input wire reset").unwrap();
-    let enable_pos = verilog.find("input wire enable").unwrap();
+    let clk_pos = verilog.find("input  wire       clk").unwrap();
+    let reset_pos = verilog.find("input  wire       reset").unwrap();
+    let enable_pos = verilog.find("input  wire       enable").unwrap();
     let count_pos = verilog.find("output wire [7:0] count").unwrap();
 
     assert!(clk_pos < reset_pos);
@@ -57,18 +57,18 @@ fn test_alu_transpilation() {
     assert_eq!(entities[0].ports.len(), 6);
 
     let generator = VerilogGenerator::new();
-    let verilog = generator.generate(&entities[0]).unwrap();
+    let verilog = generator.generate(&entities[0]).unwrap().0;
 
     println!("Generated Verilog:\n{}", verilog);
 
     // Verify key parts
     assert!(verilog.contains("module alu"));
-    assert!(verilog.contains("input wire [15:0] a"));
-    assert!(verilog.contains("input wire [15:0] b"));
-    assert!(verilog.contains("input wire [2:0] opcode"));
+    assert!(verilog.contains("input  wire [15:0] a"));
+    assert!(verilog.contains("input  wire [15:0] b"));
+    assert!(verilog.contains("input  wire [2:0]  opcode"));
     assert!(verilog.contains("output wire [15:0] result"));
-    assert!(verilog.contains("output wire zero"));
-    assert!(verilog.contains("output wire carry"));
+    assert!(verilog.contains("output wire        zero"));
+    assert!(verilog.contains("output wire        carry"));
     assert!(verilog.contains("endmodule"));
 }
 
@@ -94,16 +94,180 @@ fn test_type_conversions() {
     assert_eq!(entities.len(), 1);
 
     let generator = VerilogGenerator::new();
-    let verilog = generator.generate(&entities[0]).unwrap();
+    let verilog = generator.generate(&entities[0]).unwrap().0;
 
     println!("Generated Verilog:\n{}", verilog);
 
     // Verify type mappings
-    assert!(verilog.contains("input wire bit_sig"));
-    assert!(verilog.contains("input wire logic_sig"));
+    assert!(verilog.contains("input wire               bit_sig"));
+    assert!(verilog.contains("input wire               logic_sig"));
     assert!(verilog.contains("input wire signed [31:0] int_sig"));
-    assert!(verilog.contains("input wire [31:0] natural_sig"));
-    assert!(verilog.contains("input wire [31:0] vec_sig"));
+    assert!(verilog.contains("input wire [31:0]        natural_sig"));
+    assert!(verilog.contains("input wire [31:0]        vec_sig"));
     assert!(verilog.contains("input wire signed [15:0] signed_sig"));
-    assert!(verilog.contains("input wire [7:0] unsigned_sig"));
-}
\ No newline at end of file
+    assert!(verilog.contains("input wire [7:0]         unsigned_sig"));
+}
+
+#[test]
+fn test_generate_adder_array_round_trip() {
+    use rtl_transpiler::ir::SystemVerilogGenerator;
+
+    let vhdl_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/adder_array.vhd");
+
+    let mut parser = ASTVHDLParser::from_file(&vhdl_path).unwrap();
+    let entities = parser.parse_entities().unwrap();
+
+    assert_eq!(entities.len(), 2);
+    let array = entities.iter().find(|e| e.name == "adder_array").unwrap();
+    let arch = array.architecture.as_ref().unwrap();
+    assert_eq!(arch.generate_blocks.len(), 1);
+    assert_eq!(arch.generate_blocks[0].instantiations.len(), 1);
+    assert!(arch.instantiations.is_empty());
+
+    let sv = SystemVerilogGenerator::new().generate(array).unwrap().0;
+    assert_eq!(sv, include_str!("fixtures/golden/adder_array.sv"));
+}
+#[test]
+fn test_comments_round_trip() {
+    use rtl_transpiler::ir::SystemVerilogGenerator;
+
+    let vhdl_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/commented.vhd");
+
+    let mut parser = ASTVHDLParser::from_file(&vhdl_path).unwrap();
+    let entities = parser.parse_entities().unwrap();
+
+    let sv = SystemVerilogGenerator::new().generate(&entities[0]).unwrap().0;
+    assert_eq!(sv, include_str!("fixtures/golden/commented.sv"));
+}
+
+#[test]
+fn test_source_refs_match_fixture_lines() {
+    use rtl_transpiler::ir::SystemVerilogGenerator;
+    use rtl_transpiler::ir::source_map::source_ref_lines;
+
+    let vhdl_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/counter_with_arch.vhd");
+
+    let mut parser = ASTVHDLParser::from_file(&vhdl_path).unwrap();
+    let entities = parser.parse_entities().unwrap();
+
+    let sv = SystemVerilogGenerator::new()
+        .with_source_refs(Some("counter_with_arch.vhd".to_string()))
+        .generate(&entities[0])
+        .unwrap().0;
+    println!("Generated SystemVerilog:\n{}", sv);
+
+    assert!(sv.contains("input  logic       clk, // vhdl:counter_with_arch.vhd:8\n"));
+    assert!(sv.contains("output logic [7:0] count // vhdl:counter_with_arch.vhd:11\n"));
+    assert!(sv.contains("logic [7:0] count_reg; // vhdl:counter_with_arch.vhd:16\n"));
+    assert!(sv.contains(") begin // vhdl:counter_with_arch.vhd:20\n"));
+    assert!(sv.contains("; // vhdl:counter_with_arch.vhd:32\n"));
+
+    let map = source_ref_lines(&sv);
+    let source_lines: Vec<usize> = map.values().copied().collect();
+    assert_eq!(source_lines, vec![8, 9, 10, 11, 16, 20, 32]);
+    for (generated, source) in &map {
+        let line = sv.lines().nth(generated - 1).unwrap();
+        assert!(line.ends_with(&format!(":{}", source)));
+    }
+}
+
+#[test]
+fn test_inconsistent_casing_is_unified() {
+    use rtl_transpiler::ir::SystemVerilogGenerator;
+
+    let vhdl_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/counter_mixed_case.vhd");
+
+    let mut parser = ASTVHDLParser::from_file(&vhdl_path).unwrap();
+    let entities = parser.parse_entities().unwrap();
+    assert!(!parser.case_normalizations().is_empty());
+
+    let sv = SystemVerilogGenerator::new().generate(&entities[0]).unwrap().0;
+    println!("Generated SystemVerilog:\n{}", sv);
+
+    for variant in ["count_reg", "COUNT_REG", "Count_reg", "clk", "CLK", "reset", "RESET"] {
+        let pattern = regex::Regex::new(&format!(r"\b{}\b", variant)).unwrap();
+        assert!(!pattern.is_match(&sv), "unnormalized spelling {} in output", variant);
+    }
+    assert!(sv.contains("always_ff @(posedge Clk or posedge Reset)"));
+    assert!(sv.contains("Count_Reg <= Count_Reg + 1;"));
+    assert!(sv.contains("assign Count = Count_Reg;"));
+}
+
+/// SystemVerilog of the `top` entity in a tests/fixtures/binding file, with the parser
+fn transpile_bound_top(fixture: &str) -> (String, ASTVHDLParser) {
+    use rtl_transpiler::ir::SystemVerilogGenerator;
+
+    let vhdl_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/binding")
+        .join(fixture);
+    let mut parser = ASTVHDLParser::from_file(&vhdl_path).unwrap();
+    let entities = parser.parse_entities().unwrap();
+    let top = entities.iter().find(|e| e.name == "top").unwrap();
+    let sv = SystemVerilogGenerator::new().generate(top).unwrap().0;
+    println!("Generated SystemVerilog:\n{}", sv);
+    (sv, parser)
+}
+
+#[test]
+fn test_configuration_specification_binding() {
+    let (sv, parser) = transpile_bound_top("component_config_spec.vhd");
+    assert!(sv.contains("// fast_inv(rtl)\n"));
+    assert!(sv.contains("fast_inv u0 ("));
+    assert!(parser.binding_diagnostics().is_empty());
+}
+
+#[test]
+fn test_direct_entity_architecture_binding() {
+    let (sv, _) = transpile_bound_top("direct_entity_arch.vhd");
+    assert!(sv.contains("// inv(gated)\n"));
+    assert!(sv.contains("inv u0 ("));
+}
+
+#[test]
+fn test_configuration_declaration_binding() {
+    let (sv, parser) = transpile_bound_top("configuration_declaration.vhd");
+    assert!(sv.contains("// fast_inv(rtl) via configuration top_cfg\n"));
+    assert!(sv.contains("fast_inv u0 ("));
+    assert_eq!(parser.binding_diagnostics(), [(
+        "old_top".to_string(),
+        "configuration legacy_cfg ignored: entity old_top is not declared in this file".to_string(),
+    )]);
+}
+
+#[test]
+fn test_agent_run_with_replayed_llm() {
+    use rtl_transpiler::agent::{BaseAgent, BaseAgentImpl};
+    use rtl_transpiler::config::AgentConfig;
+    use rtl_transpiler::llm::ReplayLLMClient;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let rtl_dir = temp_dir.path().join("rtl");
+
+    let config = AgentConfig {
+        tools: vec!["file_ops".to_string(), "task_done".to_string()],
+        allowed_folders: vec![temp_dir.path().to_str().unwrap().to_string()],
+        ..Default::default()
+    };
+    let client = Arc::new(
+        ReplayLLMClient::new(vec![])
+            .with_tool_call_response("file_ops", serde_json::json!({
+                "command": "mkdir",
+                "path": rtl_dir.to_str().unwrap()
+            }))
+            .with_tool_call_response("task_done", serde_json::json!({})),
+    );
+
+    let agent = BaseAgentImpl::new("replay".to_string(), config, client.clone(), None, None).unwrap();
+    agent
+        .run("Create the rtl folder".to_string(), serde_json::json!({}), Arc::new(AtomicBool::new(false)))
+        .unwrap();
+
+    assert!(rtl_dir.is_dir());
+    client.assert_all_responses_consumed();
+}
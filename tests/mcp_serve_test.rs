@@ -0,0 +1,174 @@
+use rtl_transpiler::mcp::RTLTranspilerMCPServer;
+use serde_json::{json, Value};
+use std::io::{BufRead, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// Newline-delimited JSON-RPC client over any byte stream
+struct RawClient<S> {
+    stream: BufReader<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> RawClient<S> {
+    fn new(stream: S) -> Self {
+        Self { stream: BufReader::new(stream) }
+    }
+
+    async fn send(&mut self, message: Value) {
+        let mut line = message.to_string();
+        line.push('\n');
+        self.stream.get_mut().write_all(line.as_bytes()).await.unwrap();
+    }
+
+    async fn request(&mut self, id: u64, method: &str, params: Value) -> Value {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })).await;
+        loop {
+            let mut line = String::new();
+            assert!(self.stream.read_line(&mut line).await.unwrap() > 0, "server closed the stream");
+            let message: Value = serde_json::from_str(&line).unwrap();
+            if message["id"] == id {
+                return message;
+            }
+        }
+    }
+
+    async fn initialize(&mut self) -> Value {
+        let response = self.request(1, "initialize", json!({
+            "protocolVersion": "2025-03-26",
+            "capabilities": {},
+            "clientInfo": { "name": "mcp-serve-test", "version": "0.1.0" }
+        })).await;
+        self.send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).await;
+        response
+    }
+}
+
+fn tool_names(response: &Value) -> Vec<String> {
+    response["result"]["tools"].as_array().unwrap().iter()
+        .map(|tool| tool["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_stdio_style_session_over_duplex() {
+    let (client_end, server_end) = tokio::io::duplex(64 * 1024);
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let handle = RTLTranspilerMCPServer::new().serve_connection(server_end, async move {
+        let _ = stopped.await;
+    });
+
+    let mut client = RawClient::new(client_end);
+    let init = client.initialize().await;
+    assert!(init["result"]["capabilities"]["tools"].is_object());
+
+    let tools = client.request(2, "tools/list", json!({})).await;
+    let names = tool_names(&tools);
+    assert!(names.contains(&"analyze_vhdl".to_string()));
+    assert!(names.contains(&"transpile_vhdl_to_verilog".to_string()));
+
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/counter.vhd");
+    let call = client.request(3, "tools/call", json!({
+        "name": "analyze_vhdl",
+        "arguments": { "vhdl_file": fixture.display().to_string() }
+    })).await;
+    assert_eq!(call["result"]["isError"], false);
+    assert!(call["result"]["content"][0]["text"].as_str().unwrap().contains("UP_COUNTER"));
+
+    stop.send(()).unwrap();
+    handle.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_tcp_sessions_close_on_shutdown() {
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let (addr, handle) = RTLTranspilerMCPServer::new()
+        .serve_tcp_with_shutdown("127.0.0.1:0", async move {
+            let _ = stopped.await;
+        })
+        .await
+        .unwrap();
+
+    let mut client = RawClient::new(tokio::net::TcpStream::connect(addr).await.unwrap());
+    client.initialize().await;
+    let tools = client.request(2, "tools/list", json!({})).await;
+    assert!(tool_names(&tools).contains(&"lint_vhdl".to_string()));
+
+    stop.send(()).unwrap();
+    handle.await.unwrap().unwrap();
+
+    // The session was cancelled, so the server side of the socket is closed
+    let mut line = String::new();
+    assert_eq!(client.stream.read_line(&mut line).await.unwrap(), 0);
+}
+
+/// Parse a line the server wrote to stdout, which must be nothing but JSON-RPC
+fn protocol_message(line: &str) -> Value {
+    let message: Value = serde_json::from_str(line)
+        .unwrap_or_else(|_| panic!("non-protocol output on stdout: {:?}", line));
+    assert_eq!(message["jsonrpc"], "2.0", "non-protocol output on stdout: {:?}", line);
+    message
+}
+
+#[test]
+fn test_stdio_binary_writes_only_protocol_messages() {
+    let mut server = Command::new(env!("CARGO_BIN_EXE_rtl-transpiler-mcp"))
+        .arg("--debug")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = server.stdin.take().unwrap();
+    let mut stdout = std::io::BufReader::new(server.stdout.take().unwrap());
+    // Drained on a thread so debug logging cannot fill the pipe and block the server
+    let mut stderr = server.stderr.take().unwrap();
+    let logs = std::thread::spawn(move || {
+        let mut logs = String::new();
+        let _ = stderr.read_to_string(&mut logs);
+        logs
+    });
+
+    let mut request = |stdin: &mut std::process::ChildStdin, id: u64, method: &str, params: Value| {
+        writeln!(stdin, "{}", json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })).unwrap();
+        loop {
+            let mut line = String::new();
+            assert!(stdout.read_line(&mut line).unwrap() > 0, "server closed stdout");
+            if protocol_message(&line)["id"] == id {
+                return;
+            }
+        }
+    };
+    request(&mut stdin, 1, "initialize", json!({
+        "protocolVersion": "2025-03-26",
+        "capabilities": {},
+        "clientInfo": { "name": "mcp-serve-test", "version": "0.1.0" }
+    }));
+    writeln!(stdin, "{}", json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).unwrap();
+
+    // A parse and generation, which log at debug level, and a failing call
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/counter.vhd");
+    request(&mut stdin, 2, "tools/call", json!({
+        "name": "transpile_vhdl_to_verilog",
+        "arguments": { "vhdl_file": fixture.display().to_string() }
+    }));
+    request(&mut stdin, 3, "tools/call", json!({
+        "name": "analyze_vhdl",
+        "arguments": { "vhdl_file": "does/not/exist.vhd" }
+    }));
+
+    drop(stdin);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while server.try_wait().unwrap().is_none() {
+        if Instant::now() > deadline {
+            server.kill().unwrap();
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    for line in stdout.lines() {
+        protocol_message(&line.unwrap());
+    }
+    assert!(logs.join().unwrap().contains("Starting RTL Transpiler MCP Server"));
+}
@@ -0,0 +1,354 @@
+use proptest::prelude::*;
+use proptest::test_runner::{Config, RngAlgorithm, TestRng, TestRunner};
+use rtl_transpiler::ir::{
+    Architecture, Entity, Generic, Port, PortDirection, Process, RangeExpr, Signal, SystemVerilogGenerator,
+    VHDLType, VectorRange, VerilogGenerator, VerilogStyle,
+};
+use rtl_transpiler::parser::ASTVHDLParser;
+
+/// Cases per property; CI sets PROPTEST_CASES=10000
+fn cases() -> u32 {
+    std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(256)
+}
+
+/// Fixed seed so failures reproduce; override with PROPTEST_SEED
+fn runner() -> TestRunner {
+    let seed: u64 = std::env::var("PROPTEST_SEED")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0x5eed_2074);
+    let mut seed_bytes = [0u8; 32];
+    seed_bytes[..8].copy_from_slice(&seed.to_le_bytes());
+
+    TestRunner::new_with_rng(
+        Config { cases: cases(), ..Config::default() },
+        TestRng::from_seed(RngAlgorithm::ChaCha, &seed_bytes),
+    )
+}
+
+const RESERVED: &[&str] = &[
+    "in", "out", "inout", "buffer", "is", "of", "to", "downto", "port", "entity", "end",
+    "begin", "signal", "generic", "architecture", "process", "if", "then", "else", "and",
+    "or", "not", "xor", "bit", "integer", "natural", "positive", "boolean", "signed", "unsigned",
+];
+
+fn identifier() -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9_]{0,10}[a-z0-9]"
+        .prop_filter("VHDL identifiers cannot contain '__' or be reserved", |name| {
+            !name.contains("__") && !RESERVED.contains(&name.as_str())
+        })
+}
+
+fn port_type() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("std_logic".to_string()),
+        Just("bit".to_string()),
+        Just("integer".to_string()),
+        Just("natural".to_string()),
+        Just("boolean".to_string()),
+        (0..64u32).prop_map(|msb| format!("std_logic_vector({} downto 0)", msb)),
+        (0..32u32).prop_map(|msb| format!("unsigned({} downto 0)", msb)),
+        (0..32u32).prop_map(|msb| format!("signed({} downto 0)", msb)),
+    ]
+}
+
+fn direction() -> impl Strategy<Value = &'static str> {
+    prop_oneof![Just("in"), Just("out"), Just("inout"), Just("buffer")]
+}
+
+#[derive(Debug, Clone)]
+struct EntitySkeleton {
+    name: String,
+    ports: Vec<(String, &'static str, String)>,
+}
+
+impl EntitySkeleton {
+    fn to_vhdl(&self) -> String {
+        let mut vhdl = format!("entity {} is\n", self.name);
+        if !self.ports.is_empty() {
+            let ports: Vec<String> = self.ports.iter()
+                .map(|(name, dir, ty)| format!("        {} : {} {}", name, dir, ty))
+                .collect();
+            vhdl.push_str(&format!("    port(\n{}\n    );\n", ports.join(";\n")));
+        }
+        vhdl.push_str(&format!("end entity {};\n\n", self.name));
+        vhdl
+    }
+}
+
+fn entity() -> impl Strategy<Value = EntitySkeleton> {
+    let ports = prop::collection::vec((identifier(), direction(), port_type()), 0..=10)
+        .prop_map(|mut ports| {
+            // Port names must be unique within an entity
+            let mut seen = std::collections::HashSet::new();
+            ports.retain(|(name, _, _)| seen.insert(name.clone()));
+            ports
+        });
+
+    (identifier(), ports).prop_map(|(name, ports)| EntitySkeleton { name, ports })
+}
+
+fn design() -> impl Strategy<Value = Vec<EntitySkeleton>> {
+    prop::collection::vec(entity(), 1..=3).prop_map(|mut entities| {
+        let mut seen = std::collections::HashSet::new();
+        entities.retain(|e| seen.insert(e.name.clone()));
+        entities
+    })
+}
+
+#[test]
+fn prop_entities_and_ports_round_trip() {
+    runner()
+        .run(&design(), |entities| {
+            let vhdl: String = entities.iter().map(EntitySkeleton::to_vhdl).collect();
+            let mut parser = ASTVHDLParser::new(vhdl.clone()).unwrap();
+            let parsed = parser.parse_entities()
+                .map_err(|e| TestCaseError::fail(format!("{:#}\n{}", e, vhdl)))?;
+
+            prop_assert_eq!(parsed.len(), entities.len());
+            for (expected, actual) in entities.iter().zip(&parsed) {
+                prop_assert_eq!(&actual.name, &expected.name);
+                prop_assert_eq!(actual.ports.len(), expected.ports.len());
+                for ((name, _, _), port) in expected.ports.iter().zip(&actual.ports) {
+                    prop_assert_eq!(&port.name, name);
+                }
+            }
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn prop_corrupted_input_never_panics() {
+    let corrupted = (design(), prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8));
+
+    runner()
+        .run(&corrupted, |(entities, flips)| {
+            let mut bytes: Vec<u8> = entities.iter().map(EntitySkeleton::to_vhdl).collect::<String>().into_bytes();
+            for (index, value) in flips {
+                let i = index.index(bytes.len());
+                bytes[i] = value;
+            }
+            let vhdl = String::from_utf8_lossy(&bytes).into_owned();
+
+            // Either outcome is fine; a panic fails the property
+            if let Ok(mut parser) = ASTVHDLParser::new(vhdl) {
+                let _ = parser.parse_entities();
+            }
+            Ok(())
+        })
+        .unwrap();
+}
+
+/// Identifiers the generators must cope with: VHDL and Verilog keywords, non-ASCII
+/// letters and case variants of each other
+fn unusual_identifier() -> impl Strategy<Value = String> {
+    prop_oneof![
+        3 => identifier(),
+        1 => prop::sample::select(vec![
+            "module", "endmodule", "begin", "end", "reg", "wire", "logic", "input", "output",
+            "always", "assign", "case", "default", "in", "out", "signal", "process", "Clk", "CLK",
+        ]).prop_map(str::to_string),
+        1 => "[\\p{L}][\\p{L}\\p{N}_]{0,8}",
+    ]
+}
+
+/// Bounds from -64 to 64 in either direction, literal or relative to a generic
+fn vector_range() -> impl Strategy<Value = VectorRange> {
+    let bound = prop_oneof![
+        3 => (-64..64i32).prop_map(RangeExpr::Literal),
+        1 => (-4..4i32).prop_map(|offset| RangeExpr::Parametric { base: "WIDTH".to_string(), offset }),
+    ];
+    (bound.clone(), bound, any::<bool>()).prop_map(|(left, right, downto)| VectorRange { left, right, downto })
+}
+
+fn vhdl_type() -> impl Strategy<Value = VHDLType> {
+    prop_oneof![
+        Just(VHDLType::StdLogic),
+        Just(VHDLType::Integer),
+        Just(VHDLType::Natural),
+        Just(VHDLType::Boolean),
+        Just(VHDLType::Bit),
+        vector_range().prop_map(VHDLType::StdLogicVector),
+        vector_range().prop_map(VHDLType::Signed),
+        vector_range().prop_map(VHDLType::Unsigned),
+        unusual_identifier().prop_map(VHDLType::Custom),
+    ]
+}
+
+fn port_direction() -> impl Strategy<Value = PortDirection> {
+    prop_oneof![Just(PortDirection::In), Just(PortDirection::Out), Just(PortDirection::InOut), Just(PortDirection::Buffer)]
+}
+
+/// Process body over signals `a` (read) and `b` (assigned)
+fn process_body(a: &str, b: &str, template: usize) -> String {
+    match template {
+        0 => format!("if rising_edge(clk) then\n    {b} <= {a};\nend if;"),
+        1 => format!("if {a} = '1' then\n    {b} <= '1';\nelsif {a} = '0' then\n    {b} <= '0';\nelse\n    {b} <= {a};\nend if;"),
+        2 => format!("case {a} is\n    when '0' => {b} <= '1';\n    when others =>\n        if {a} = '1' then {b} <= '0'; end if;\nend case;"),
+        3 => format!("{b} <= {a}(3 downto 0) & \"00\";"),
+        _ => format!("{b}({a}'high) <= not {a}({a}'low);"),
+    }
+}
+
+/// Statements of VHDL-ish tokens, mostly malformed, to shake out slicing at keyword positions
+fn token_soup() -> impl Strategy<Value = String> {
+    let token = prop::sample::select(vec![
+        "a", "b", "y", "<=", "=>", "when", "else", "with", "select", "if", "then", "elsif", "end", "case",
+        "is", "others", "(", ")", "'1'", "\"01\"", "x\"F\"", "&", "and", "not", "=", "/=", ",", ";", "downto",
+        "to", "--", "rising_edge(clk)", "(others => '0')", "",
+    ]);
+    prop::collection::vec(token, 1..12).prop_map(|tokens| tokens.join(" "))
+}
+
+fn generated_entity() -> impl Strategy<Value = Entity> {
+    let ports = prop::collection::vec((unusual_identifier(), port_direction(), vhdl_type()), 0..=8);
+    let signals = prop::collection::vec((unusual_identifier(), vhdl_type()), 0..=4);
+    let processes = prop::collection::vec((0..5usize, any::<prop::sample::Index>(), any::<prop::sample::Index>()), 0..=3);
+    (unusual_identifier(), any::<bool>(), ports, signals, processes).prop_map(|(name, generic, ports, signals, processes)| {
+        let mut entity = Entity::new(name);
+        if generic {
+            entity.add_generic(Generic { name: "WIDTH".to_string(), generic_type: "integer".to_string(), default_value: Some("8".to_string()) });
+        }
+        for (name, direction, port_type) in ports {
+            entity.add_port(Port::new(name, direction, port_type));
+        }
+        let names: Vec<String> = entity.ports.iter().map(|p| p.name.clone())
+            .chain(signals.iter().map(|(name, _)| name.clone()))
+            .chain(std::iter::once("clk".to_string()))
+            .collect();
+        entity.architecture = Some(Architecture {
+            name: "rtl".to_string(),
+            signals: signals.into_iter()
+                .map(|(name, signal_type)| Signal { name, signal_type, source_line: None })
+                .collect(),
+            processes: processes.into_iter()
+                .map(|(template, a, b)| Process {
+                    label: None,
+                    sensitivity_list: vec!["clk".to_string()],
+                    body: process_body(a.get::<String>(&names), b.get::<String>(&names), template),
+                    source_line: None,
+                    testbench_style: false,
+                })
+                .collect(),
+            concurrent_statements: vec![],
+            instantiations: vec![],
+            generate_blocks: vec![],
+            concurrent_statement_lines: vec![],
+            types: Default::default(),
+        });
+        entity
+    })
+}
+
+/// Identifier-like tokens of `code` outside comments and string literals
+fn code_tokens(code: &str) -> Vec<String> {
+    let mut text = String::new();
+    let mut chars = code.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            ('"', _) => while chars.next().is_some_and(|c| c != '"') {},
+            _ => text.push(c),
+        }
+    }
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn check_structure(hdl: &str) -> Result<(), TestCaseError> {
+    let tokens = code_tokens(hdl);
+    let count = |word: &str| tokens.iter().filter(|t| *t == word).count();
+    prop_assert_eq!(count("module"), 1, "{}", hdl);
+    prop_assert_eq!(count("endmodule"), 1, "{}", hdl);
+    prop_assert_eq!(count("begin"), count("end"), "{}", hdl);
+    prop_assert_eq!(count("case"), count("endcase"), "{}", hdl);
+    Ok(())
+}
+
+#[test]
+fn prop_generators_emit_balanced_modules() {
+    runner()
+        .run(&generated_entity(), |entity| {
+            // Errors are fine for bodies the converters reject; a panic fails the property
+            if let Ok((systemverilog, _)) = SystemVerilogGenerator::new().generate(&entity) {
+                check_structure(&systemverilog)?;
+            }
+            for style in [VerilogStyle::Verilog2001Ansi, VerilogStyle::Verilog2001NonAnsi, VerilogStyle::Verilog95] {
+                if let Ok((verilog, _)) = VerilogGenerator::new().with_style(style).generate(&entity) {
+                    check_structure(&verilog)?;
+                }
+            }
+            if let Ok(stub) = SystemVerilogGenerator::new().generate_stub(&entity) {
+                check_structure(&stub)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn prop_parsed_unusual_entities_transpile() {
+    let ports = prop::collection::vec((unusual_identifier(), direction(), vector_range()), 0..=6);
+    runner()
+        .run(&(unusual_identifier(), ports), |(name, ports)| {
+            let ports: Vec<String> = ports.iter()
+                .map(|(port, dir, range)| format!("{} : {} std_logic_vector{}", port, dir, range.to_vhdl()))
+                .collect();
+            let vhdl = format!("entity {} is\n    port(\n        {}\n    );\nend entity;\n", name, ports.join(";\n        "));
+
+            // Keywords and clashing names are parse errors; whatever parses must generate
+            let Ok(mut parser) = ASTVHDLParser::new(vhdl) else { return Ok(()) };
+            let Ok(entities) = parser.parse_entities() else { return Ok(()) };
+            for entity in &entities {
+                if let Ok((systemverilog, _)) = SystemVerilogGenerator::new().generate(entity) {
+                    check_structure(&systemverilog)?;
+                }
+                if let Ok((verilog, _)) = VerilogGenerator::new().generate(entity) {
+                    check_structure(&verilog)?;
+                }
+            }
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn prop_malformed_statements_never_panic() {
+    let soup = (generated_entity(), prop::collection::vec(token_soup(), 0..=3), prop::collection::vec(token_soup(), 1..6));
+    runner()
+        .run(&soup, |(mut entity, statements, body)| {
+            if let Some(arch) = entity.architecture.as_mut() {
+                arch.concurrent_statements = statements;
+                arch.processes.push(Process {
+                    label: Some("soup".to_string()),
+                    sensitivity_list: vec![],
+                    body: body.join("\n"),
+                    source_line: None,
+                    testbench_style: false,
+                });
+            }
+            // Garbage may come out or be rejected; a panic fails the property
+            let _ = SystemVerilogGenerator::new().generate(&entity);
+            let _ = VerilogGenerator::new().generate(&entity);
+            Ok(())
+        })
+        .unwrap();
+}